@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 
 use baml_types::{BamlValue, JinjaExpression};
-use minijinja::value::Value;
+use chrono::format::{Item, StrftimeItems};
+use minijinja::value::{ArgType, Kwargs, Rest, Value, ValueKind};
+use minijinja::{Error, ErrorKind};
 use regex::Regex;
 
 pub fn get_env<'a>() -> minijinja::Environment<'a> {
@@ -11,6 +13,14 @@ pub fn get_env<'a>() -> minijinja::Environment<'a> {
     env.set_lstrip_blocks(true);
     env.add_filter("regex_match", regex_match);
     env.add_filter("sum", sum_filter);
+    env.add_filter("format", format_filter);
+    env.add_filter("to_json", to_json);
+    env.add_filter("to_yaml", to_yaml);
+    env.add_filter("dedent", dedent);
+    env.add_filter("truncate_words", truncate_words);
+    env.add_filter("zip", zip);
+    env.add_filter("enumerate", enumerate);
+    env.add_filter("similarity", similarity);
     env
 }
 
@@ -43,6 +53,312 @@ fn sum_filter(value: Vec<Value>) -> Value {
     int_sum.map_or(float_sum.map_or(Value::from(0), Value::from), Value::from)
 }
 
+/// Formats a number with a Python-style spec (`",.2f"`, `".1%"`, `"d"`, `"e"`),
+/// or a date string (RFC 3339, `YYYY-MM-DDTHH:MM:SS` or `YYYY-MM-DD`) with a
+/// strftime spec (`"%B %d, %Y"`).
+fn format_filter(value: Value, spec: String) -> Result<String, Error> {
+    match value.kind() {
+        ValueKind::Number => format_number(&value, &spec),
+        ValueKind::String => format_date(value.as_str().unwrap_or_default(), &spec),
+        _ => Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!(
+                "format expects a number or a date string, got {}",
+                value.kind()
+            ),
+        )),
+    }
+}
+
+fn format_number(value: &Value, spec: &str) -> Result<String, Error> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidOperation,
+            format!("Invalid number format `{spec}`"),
+        )
+    };
+
+    let (grouped, rest) = match spec.strip_prefix(',') {
+        Some(rest) => (true, rest),
+        None => (false, spec),
+    };
+    let (precision, kind) = match rest.strip_prefix('.') {
+        Some(rest) => {
+            let (digits, kind) = rest.split_at(
+                rest.find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len()),
+            );
+            let precision = digits.parse::<usize>().map_err(|_| invalid())?;
+            (Some(precision), kind)
+        }
+        None => (None, rest),
+    };
+
+    let number = f64::try_from(value.clone())?;
+    let formatted = match kind {
+        "" if precision.is_none() => value.to_string(),
+        "" | "f" => format!("{:.*}", precision.unwrap_or(6), number),
+        "%" => format!("{:.*}%", precision.unwrap_or(6), number * 100.0),
+        "d" if precision.is_none() => format!("{:.0}", number.round()),
+        "e" => format!("{:.*e}", precision.unwrap_or(6), number),
+        _ => return Err(invalid()),
+    };
+
+    Ok(if grouped {
+        group_thousands(&formatted)
+    } else {
+        formatted
+    })
+}
+
+/// Inserts commas between the thousands of the integer part of a number.
+fn group_thousands(number: &str) -> String {
+    let (sign, unsigned) = match number.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", number),
+    };
+    let int_end = unsigned
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(unsigned.len());
+    let (int, rest) = unsigned.split_at(int_end);
+
+    let mut grouped = String::with_capacity(number.len() + int.len() / 3);
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("{sign}{grouped}{rest}")
+}
+
+fn format_date(value: &str, spec: &str) -> Result<String, Error> {
+    let value = value.trim();
+    // Dates without an offset are taken to be UTC.
+    let date = chrono::DateTime::parse_from_rfc3339(value)
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+                .or_else(|_| {
+                    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+                })
+                .map(|date| date.and_utc().fixed_offset())
+        })
+        .map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidOperation,
+                format!("format expects a number or a date string, got `{value}`"),
+            )
+        })?;
+
+    // Formatting an invalid spec panics, so check it first.
+    let items = StrftimeItems::new(spec).collect::<Vec<_>>();
+    if items.iter().any(|item| matches!(item, Item::Error)) {
+        return Err(Error::new(
+            ErrorKind::InvalidOperation,
+            format!("Invalid date format `{spec}`"),
+        ));
+    }
+    Ok(date.format_with_items(items.into_iter()).to_string())
+}
+
+/// An optional filter argument, given either positionally or as a keyword
+/// argument.
+fn optional_arg<T>(positional: Option<T>, kwargs: &Kwargs, name: &str) -> Result<Option<T>, Error>
+where
+    T: for<'a> ArgType<'a, Output = T>,
+{
+    let value = match positional {
+        Some(value) => Some(value),
+        None => kwargs.get::<Option<T>>(name)?,
+    };
+    kwargs.assert_all_used()?;
+    Ok(value)
+}
+
+fn to_serde_json(value: &Value) -> Result<serde_json::Value, Error> {
+    serde_json::to_value(value).map_err(|e| {
+        Error::new(ErrorKind::InvalidOperation, "Value cannot be serialized").with_source(e)
+    })
+}
+
+/// Serializes a value as JSON, on one line unless an `indent` is given.
+fn to_json(value: Value, indent: Option<usize>, kwargs: Kwargs) -> Result<String, Error> {
+    let value = to_serde_json(&value)?;
+    let json = match optional_arg(indent, &kwargs, "indent")? {
+        None => serde_json::to_string(&value),
+        Some(indent) => {
+            let indent = " ".repeat(indent);
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+            let mut buf = Vec::new();
+            let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+            serde::Serialize::serialize(&value, &mut serializer)
+                .map(|_| String::from_utf8_lossy(&buf).into_owned())
+        }
+    };
+    json.map_err(|e| {
+        Error::new(ErrorKind::InvalidOperation, "Value cannot be serialized").with_source(e)
+    })
+}
+
+/// Serializes a value as block-style YAML, indenting nested values by
+/// `indent` spaces (2 by default).
+fn to_yaml(value: Value, indent: Option<usize>, kwargs: Kwargs) -> Result<String, Error> {
+    let value = to_serde_json(&value)?;
+    let indent = optional_arg(indent, &kwargs, "indent")?.unwrap_or(2).max(1);
+    Ok(yaml_lines(&value, indent).join("\n"))
+}
+
+fn yaml_lines(value: &serde_json::Value, indent: usize) -> Vec<String> {
+    let indent_lines = |lines: Vec<String>, by: usize| -> Vec<String> {
+        lines
+            .into_iter()
+            .map(|line| {
+                if line.is_empty() {
+                    line
+                } else {
+                    format!("{}{line}", " ".repeat(by))
+                }
+            })
+            .collect()
+    };
+    let is_block = |value: &serde_json::Value| match value {
+        serde_json::Value::Array(items) => !items.is_empty(),
+        serde_json::Value::Object(fields) => !fields.is_empty(),
+        _ => false,
+    };
+
+    match value {
+        serde_json::Value::Null => vec!["null".to_string()],
+        serde_json::Value::Bool(b) => vec![b.to_string()],
+        serde_json::Value::Number(n) => vec![n.to_string()],
+        serde_json::Value::String(s) => yaml_string(s),
+        serde_json::Value::Array(items) if items.is_empty() => vec!["[]".to_string()],
+        serde_json::Value::Object(fields) if fields.is_empty() => vec!["{}".to_string()],
+        serde_json::Value::Array(items) => items
+            .iter()
+            .flat_map(|item| {
+                let mut lines = yaml_lines(item, indent).into_iter();
+                let first = format!("- {}", lines.next().unwrap_or_default());
+                std::iter::once(first).chain(indent_lines(lines.collect(), 2))
+            })
+            .collect(),
+        serde_json::Value::Object(fields) => fields
+            .iter()
+            .flat_map(|(key, value)| {
+                let key = match yaml_string(key).as_slice() {
+                    [plain] => plain.clone(),
+                    _ => serde_json::Value::String(key.clone()).to_string(),
+                };
+                let mut lines = yaml_lines(value, indent);
+                if is_block(value) {
+                    std::iter::once(format!("{key}:"))
+                        .chain(indent_lines(lines, indent))
+                        .collect::<Vec<_>>()
+                } else {
+                    let rest = lines.split_off(1);
+                    std::iter::once(format!("{key}: {}", lines[0]))
+                        .chain(indent_lines(rest, indent))
+                        .collect::<Vec<_>>()
+                }
+            })
+            .collect(),
+    }
+}
+
+/// A YAML scalar for a string: plain if that reads back as the same string,
+/// a literal block (`|-`) for multiline text, and double-quoted otherwise.
+fn yaml_string(s: &str) -> Vec<String> {
+    let quoted = || vec![serde_json::Value::String(s.to_string()).to_string()];
+
+    if s.contains('\n') {
+        let is_simple_block = !s.ends_with('\n')
+            && !s.starts_with([' ', '\t', '\n'])
+            && !s.chars().any(|c| c.is_control() && c != '\n');
+        if !is_simple_block {
+            return quoted();
+        }
+        return std::iter::once("|-".to_string())
+            .chain(s.lines().map(str::to_string))
+            .collect();
+    }
+
+    let is_keyword = matches!(
+        s.to_lowercase().as_str(),
+        "null" | "~" | "true" | "false" | "yes" | "no" | "on" | "off"
+    );
+    let needs_quotes = s.is_empty()
+        || is_keyword
+        || s.trim() != s
+        || s.parse::<f64>().is_ok()
+        || s.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c))
+        || s.contains(": ")
+        || s.contains(" #")
+        || s.ends_with(':')
+        || s.chars().any(char::is_control);
+    if needs_quotes {
+        quoted()
+    } else {
+        vec![s.to_string()]
+    }
+}
+
+/// Removes the indentation common to all lines of a string.
+fn dedent(value: String) -> String {
+    textwrap::dedent(&value)
+}
+
+/// Keeps the first `count` words of a string, followed by `end` ("..." by
+/// default) if any were removed.
+fn truncate_words(
+    value: String,
+    count: usize,
+    end: Option<String>,
+    kwargs: Kwargs,
+) -> Result<String, Error> {
+    let end = optional_arg(end, &kwargs, "end")?;
+    let words = value.split_whitespace().collect::<Vec<_>>();
+    if words.len() <= count {
+        return Ok(value);
+    }
+    Ok(format!(
+        "{}{}",
+        words[..count].join(" "),
+        end.as_deref().unwrap_or("...")
+    ))
+}
+
+/// Pairs up the items of lists, stopping at the end of the shortest one.
+fn zip(first: Vec<Value>, others: Rest<Vec<Value>>) -> Vec<Value> {
+    (0..first.len())
+        .map_while(|i| {
+            std::iter::once(&first)
+                .chain(others.iter())
+                .map(|list| list.get(i).cloned())
+                .collect::<Option<Vec<_>>>()
+                .map(Value::from)
+        })
+        .collect()
+}
+
+/// Pairs the items of a list with their index, counting from `start` (0 by
+/// default).
+fn enumerate(values: Vec<Value>, start: Option<i64>, kwargs: Kwargs) -> Result<Vec<Value>, Error> {
+    let start = optional_arg(start, &kwargs, "start")?.unwrap_or(0);
+    Ok(values
+        .into_iter()
+        .zip(start..)
+        .map(|(value, i)| Value::from(vec![Value::from(i), value]))
+        .collect())
+}
+
+/// How similar two strings are, from 0.0 (nothing in common) to 1.0
+/// (identical), by their normalized Levenshtein distance.
+fn similarity(value: String, other: String) -> f64 {
+    strsim::normalized_levenshtein(&value, &other)
+}
+
 /// Render a bare minijinaja expression with the given context.
 /// E.g. `"a|length > 2"` with context `{"a": [1, 2, 3]}` will return `"true"`.
 pub fn render_expression(
@@ -163,4 +479,109 @@ mod tests {
             "3.5"
         );
     }
+
+    fn render(expression: &str) -> String {
+        let ctx = vec![
+            (
+                "resume".to_string(),
+                BamlValue::Map(
+                    vec![
+                        ("name".to_string(), BamlValue::String("Grace".to_string())),
+                        (
+                            "skills".to_string(),
+                            BamlValue::List(vec![
+                                BamlValue::String("COBOL".to_string()),
+                                BamlValue::String("compilers".to_string()),
+                            ]),
+                        ),
+                        (
+                            "bio".to_string(),
+                            BamlValue::String("Rear admiral.\nProgrammer.".to_string()),
+                        ),
+                    ]
+                    .into_iter()
+                    .collect(),
+                )
+                .into(),
+            ),
+            (
+                "date".to_string(),
+                BamlValue::String("1906-12-09".to_string()).into(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        render_expression(&JinjaExpression(expression.to_string()), &ctx).unwrap()
+    }
+
+    #[test]
+    fn test_format_filter() {
+        assert_eq!(render(r#"1234567.891|format(",.2f")"#), "1,234,567.89");
+        assert_eq!(render(r#"(-1234)|format(",")"#), "-1,234");
+        assert_eq!(render(r#"0.256|format(".1%")"#), "25.6%");
+        assert_eq!(render(r#"2.5|format("d")"#), "3");
+        assert_eq!(render(r#"date|format("%B %d, %Y")"#), "December 09, 1906");
+        assert_eq!(
+            render(r#""2024-01-02T03:04:05+01:00"|format("%H:%M %z")"#),
+            "03:04 +0100"
+        );
+
+        let ctx = HashMap::new();
+        for expression in [
+            r#"1|format("x")"#,
+            r#""soon"|format("%Y")"#,
+            r#""2024-01-02"|format("%Q")"#,
+        ] {
+            assert!(render_expression(&JinjaExpression(expression.to_string()), &ctx).is_err());
+        }
+    }
+
+    #[test]
+    fn test_serialization_filters() {
+        assert_eq!(
+            render("resume|to_json"),
+            r#"{"name":"Grace","skills":["COBOL","compilers"],"bio":"Rear admiral.\nProgrammer."}"#
+        );
+        assert_eq!(
+            render("resume.skills|to_json(indent=4)"),
+            "[\n    \"COBOL\",\n    \"compilers\"\n]"
+        );
+        assert_eq!(
+            render("resume|to_yaml"),
+            "name: Grace\nskills:\n  - COBOL\n  - compilers\nbio: |-\n  Rear admiral.\n  Programmer."
+        );
+        assert_eq!(
+            render(r#"[{"a": 1, "b": "true"}, [], "x: y"]|to_yaml(4)"#),
+            "- a: 1\n  b: \"true\"\n- []\n- \"x: y\""
+        );
+    }
+
+    #[test]
+    fn test_string_filters() {
+        assert_eq!(render(r#""  a\n    b\n  c"|dedent"#), "a\n  b\nc");
+        assert_eq!(
+            render(r#""the quick brown fox"|truncate_words(2)"#),
+            "the quick..."
+        );
+        assert_eq!(
+            render(r#""the quick brown fox"|truncate_words(3, end=" [more]")"#),
+            "the quick brown [more]"
+        );
+        assert_eq!(render(r#""the fox"|truncate_words(5)"#), "the fox");
+        assert_eq!(render(r#""kitten"|similarity("sitting") > 0.5"#), "true");
+        assert_eq!(render(r#""abc"|similarity("abc")"#), "1.0");
+    }
+
+    #[test]
+    fn test_list_filters() {
+        assert_eq!(render("[3, 1, 3, 2, 1]|unique|list"), "[3, 1, 2]");
+        assert_eq!(
+            render(r#"[1, 2, 3]|zip(["a", "b"])|list"#),
+            r#"[[1, "a"], [2, "b"]]"#
+        );
+        assert_eq!(
+            render(r#"["a", "b"]|enumerate(start=1)|list"#),
+            r#"[[1, "a"], [2, "b"]]"#
+        );
+    }
 }
@@ -1,7 +1,9 @@
 use std::collections::HashSet;
 
 use anyhow::{anyhow, Result};
-use baml_types::{Constraint, ConstraintLevel, FieldType, StringOr, UnresolvedValue};
+use baml_types::{
+    Constraint, ConstraintLevel, FieldType, StreamingBehavior, StringOr, UnresolvedValue,
+};
 use either::Either;
 use indexmap::{IndexMap, IndexSet};
 use internal_baml_parser_database::{
@@ -9,7 +11,7 @@ use internal_baml_parser_database::{
         ClassWalker, ClientWalker, ConfigurationWalker, EnumValueWalker, EnumWalker, FieldWalker,
         FunctionWalker, TemplateStringWalker, Walker as AstWalker,
     },
-    Attributes, ParserDatabase, PromptAst, RetryPolicyStrategy,
+    Attributes, Candidates, ParserDatabase, PromptAst, RetryPolicyStrategy,
 };
 use internal_baml_schema_ast::ast::{SubType, ValExpId};
 
@@ -34,10 +36,55 @@ pub struct IntermediateRepr {
     clients: Vec<Node<Client>>,
     retry_policies: Vec<Node<RetryPolicy>>,
     template_strings: Vec<Node<TemplateString>>,
+    /// The namespaces of the source, with the classes and enums in them.
+    type_namespaces: Vec<TypeNamespace>,
 
     configuration: Configuration,
 }
 
+/// A namespace of the source, with the classes and enums declared directly
+/// in it. Everything in the IR has its canonical name (`Shared_Resume`);
+/// generators that mirror namespaces alias those names (`shared.Resume`).
+#[derive(Debug, Default)]
+pub struct TypeNamespace {
+    pub name: String,
+    pub types: Vec<NamespacedType>,
+    pub namespaces: Vec<TypeNamespace>,
+}
+
+#[derive(Debug)]
+pub struct NamespacedType {
+    /// The name within the namespace.
+    pub name: String,
+    /// The name of the class or enum in the IR.
+    pub canonical: String,
+    pub is_enum: bool,
+}
+
+impl TypeNamespace {
+    fn insert(namespaces: &mut Vec<TypeNamespace>, path: &[String], ty: NamespacedType) {
+        let Some((first, rest)) = path.split_first() else {
+            return;
+        };
+        let idx = match namespaces.iter().position(|n| &n.name == first) {
+            Some(idx) => idx,
+            None => {
+                namespaces.push(TypeNamespace {
+                    name: first.clone(),
+                    ..Default::default()
+                });
+                namespaces.len() - 1
+            }
+        };
+        let namespace = &mut namespaces[idx];
+        if rest.is_empty() {
+            namespace.types.push(ty);
+        } else {
+            TypeNamespace::insert(&mut namespace.namespaces, rest, ty);
+        }
+    }
+}
+
 /// A generic walker. Only walkers instantiated with a concrete ID type (`I`) are useful.
 #[derive(Clone, Copy)]
 pub struct Walker<'db, I> {
@@ -57,6 +104,7 @@ impl IntermediateRepr {
             clients: vec![],
             retry_policies: vec![],
             template_strings: vec![],
+            type_namespaces: vec![],
             configuration: Configuration::new(),
         }
     }
@@ -98,6 +146,12 @@ impl IntermediateRepr {
         &self.finite_recursive_cycles
     }
 
+    /// The namespaces with classes or enums in them, for generators that
+    /// mirror them. Empty if the project doesn't use namespaces.
+    pub fn type_namespaces(&self) -> &[TypeNamespace] {
+        &self.type_namespaces
+    }
+
     pub fn walk_enums(&self) -> impl ExactSizeIterator<Item = Walker<'_, &Node<Enum>>> {
         self.enums.iter().map(|e| Walker { db: self, item: e })
     }
@@ -184,6 +238,7 @@ impl IntermediateRepr {
                 .walk_templates()
                 .map(|e| e.node(db))
                 .collect::<Result<Vec<_>>>()?,
+            type_namespaces: vec![],
             configuration,
         };
 
@@ -196,6 +251,25 @@ impl IntermediateRepr {
         repr.retry_policies
             .sort_by(|a, b| a.elem.name.0.cmp(&b.elem.name.0));
 
+        let types = repr
+            .enums
+            .iter()
+            .map(|e| (&e.elem.name, true))
+            .chain(repr.classes.iter().map(|c| (&c.elem.name, false)));
+        for (canonical, is_enum) in types {
+            if let Some(qualified) = db.qualified_name(canonical) {
+                TypeNamespace::insert(
+                    &mut repr.type_namespaces,
+                    &qualified.namespace,
+                    NamespacedType {
+                        name: qualified.name.clone(),
+                        canonical: canonical.clone(),
+                        is_enum,
+                    },
+                );
+            }
+        }
+
         Ok(repr)
     }
 }
@@ -223,7 +297,10 @@ pub struct NodeAttributes {
     /// Some follow special conventions:
     ///
     ///   - @skip becomes ("skip", bool)
+    ///   - @sensitive becomes ("sensitive", bool)
     ///   - @alias(...) becomes ("alias", ...)
+    ///   - @stream.done becomes ("stream.done", bool), likewise for the
+    ///     other @stream.* attributes
     meta: IndexMap<String, UnresolvedValue<()>>,
 
     pub constraints: Vec<Constraint>,
@@ -236,6 +313,16 @@ impl NodeAttributes {
     pub fn get(&self, key: &str) -> Option<&UnresolvedValue<()>> {
         self.meta.get(key)
     }
+
+    /// The `@stream.*` / `@@stream.*` attributes of the node.
+    pub fn streaming_behavior(&self) -> StreamingBehavior {
+        let is_set = |key: &str| self.get(key).and_then(|v| v.as_bool()).unwrap_or(false);
+        StreamingBehavior {
+            done: is_set("stream.done"),
+            not_null: is_set("stream.not_null"),
+            state: is_set("stream.with_state"),
+        }
+    }
 }
 
 impl Default for NodeAttributes {
@@ -259,7 +346,11 @@ fn to_ir_attributes(
             alias,
             dynamic_type,
             skip,
+            sensitive,
             constraints,
+            stream_done,
+            stream_not_null,
+            stream_with_state,
         } = attributes;
 
         let description = description
@@ -285,9 +376,29 @@ fn to_ir_attributes(
             }
         });
 
-        let meta = vec![description, alias, dynamic_type, skip]
+        let sensitive = sensitive.as_ref().and_then(|v| {
+            if *v {
+                Some(("sensitive".to_string(), UnresolvedValue::Bool(true, ())))
+            } else {
+                None
+            }
+        });
+
+        let streaming = [
+            ("stream.done", stream_done),
+            ("stream.not_null", stream_not_null),
+            ("stream.with_state", stream_with_state),
+        ]
+        .into_iter()
+        .filter_map(|(key, v)| match v {
+            Some(true) => Some((key.to_string(), UnresolvedValue::Bool(true, ()))),
+            _ => None,
+        });
+
+        let meta = vec![description, alias, dynamic_type, skip, sensitive]
             .into_iter()
             .flatten()
+            .chain(streaming)
             .collect();
         (meta, constraints.clone())
     })
@@ -425,6 +536,9 @@ impl WithRepr<FieldType> for ast::FieldType {
                 },
                 arity,
             ),
+            ast::FieldType::Generic(..) => {
+                return Err(anyhow!("Generic type was not replaced by a concrete class"))
+            }
             ast::FieldType::List(arity, ft, dims, ..) => {
                 // NB: potential bug: this hands back a 1D list when dims == 0
                 let mut repr = FieldType::List(Box::new(ft.repr(db)?));
@@ -649,9 +763,12 @@ pub struct Class {
     /// User defined class name.
     pub name: ClassId,
 
-    /// Fields of the class.
+    /// Fields of the class, including the ones inherited from its parents.
     pub static_fields: Vec<Node<Field>>,
 
+    /// Names of the classes this class directly extends.
+    pub inherits: Vec<ClassId>,
+
     /// Parameters to the class definition.
     pub inputs: Vec<(String, FieldType)>,
 
@@ -676,9 +793,10 @@ impl WithRepr<Class> for ClassWalker<'_> {
         Ok(Class {
             name: self.name().to_string(),
             static_fields: self
-                .static_fields()
+                .walk_all_fields()
                 .map(|e| e.node(db))
                 .collect::<Result<Vec<_>>>()?,
+            inherits: self.parents().map(|p| p.name().to_string()).collect(),
             inputs: match self.ast_type_block().input() {
                 Some(input) => input
                     .args
@@ -791,6 +909,7 @@ pub struct FunctionConfig {
     pub prompt_template: String,
     pub prompt_span: ast::Span,
     pub client: ClientSpec,
+    pub candidates: Option<Candidates>,
 }
 
 // impl std::fmt::Display for ClientSpec {
@@ -883,6 +1002,7 @@ impl WithRepr<Function> for FunctionWalker<'_> {
                     Ok(spec) => spec,
                     Err(e) => anyhow::bail!("{}", e.message()),
                 },
+                candidates: self.metadata().candidates,
             }],
             default_config: "default_config".to_string(),
             tests: self
@@ -978,9 +1098,23 @@ pub struct TestCase {
     pub name: String,
     pub functions: Vec<Node<TestCaseFunction>>,
     pub args: IndexMap<String, UnresolvedValue<()>>,
+    pub dataset: Option<TestDataset>,
     pub constraints: Vec<Constraint>,
 }
 
+/// A JSONL or CSV file whose rows are run as separate cases of a test.
+#[derive(Debug, Clone)]
+pub struct TestDataset {
+    /// Resolved against the directory of the file declaring the test, like
+    /// media files.
+    pub path: std::path::PathBuf,
+    /// Argument name to the key or column it is read from. Arguments that
+    /// aren't listed are read from the key of the same name.
+    pub args: IndexMap<String, String>,
+    /// The key or column holding the expected output.
+    pub expected: Option<String>,
+}
+
 impl WithRepr<TestCaseFunction> for (&ConfigurationWalker<'_>, usize) {
     fn attributes(&self, _db: &ParserDatabase) -> NodeAttributes {
         let span = self.0.test_case().functions[self.1].1.clone();
@@ -1034,6 +1168,22 @@ impl WithRepr<TestCase> for ConfigurationWalker<'_> {
                 .iter()
                 .map(|(k, (_, v))| Ok((k.clone(), v.without_meta())))
                 .collect::<Result<IndexMap<_, _>>>()?,
+            dataset: self.test_case().dataset.as_ref().map(|dataset| {
+                let (file, span) = &dataset.file;
+                TestDataset {
+                    path: span
+                        .file
+                        .path_buf()
+                        .parent()
+                        .map_or_else(|| file.into(), |dir| dir.join(file)),
+                    args: dataset
+                        .args
+                        .iter()
+                        .map(|(arg, (column, _))| (arg.clone(), column.clone()))
+                        .collect(),
+                    expected: dataset.expected.as_ref().map(|(column, _)| column.clone()),
+                }
+            }),
             functions,
             constraints: <AstWalker<'_, (ValExpId, &str)> as WithRepr<TestCase>>::attributes(
                 self, db,
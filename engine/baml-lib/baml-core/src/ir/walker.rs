@@ -1,5 +1,5 @@
 use anyhow::Result;
-use baml_types::{BamlValue, EvaluationContext, UnresolvedValue};
+use baml_types::{BamlValue, EvaluationContext, StreamingBehavior, UnresolvedValue};
 use indexmap::IndexMap;
 
 use internal_baml_diagnostics::Span;
@@ -236,6 +236,11 @@ impl<'a> Walker<'a, (&'a FunctionNode, &'a TestCase)> {
         &self.item.1.elem
     }
 
+    /// The dataset whose rows are the cases of this test, if any.
+    pub fn dataset(&self) -> Option<&repr::TestDataset> {
+        self.item.1.elem.dataset.as_ref()
+    }
+
     pub fn span(&self) -> Option<&crate::Span> {
         self.item.1.attributes.span.as_ref()
     }
@@ -271,6 +276,53 @@ impl<'a> Walker<'a, &'a Class> {
             .transpose()
     }
 
+    /// The `@@stream.*` attributes of the class.
+    pub fn streaming_behavior(&self) -> StreamingBehavior {
+        self.item.attributes.streaming_behavior()
+    }
+
+    /// The classes this class directly extends.
+    pub fn parents(&self) -> impl Iterator<Item = Walker<'a, &'a Class>> + 'a {
+        let db = self.db;
+        self.elem()
+            .inherits
+            .iter()
+            .filter_map(move |name| db.find_class(name).ok())
+    }
+
+    /// Whether `field` is inherited as is from one of the parents, meaning
+    /// that a generated subclass doesn't need to declare it again.
+    pub fn inherits_field(&self, field: &Field) -> bool {
+        self.parents().any(|parent| {
+            parent
+                .elem()
+                .static_fields
+                .iter()
+                .any(|inherited| same_field(inherited, field))
+        })
+    }
+
+    /// Whether the class redefines a field of one of its parents with a
+    /// different type, or its parents disagree on the type of a field.
+    ///
+    /// Languages with structural subtyping (TypeScript interfaces) can't
+    /// express these classes with inheritance.
+    pub fn overrides_inherited_fields(&self) -> bool {
+        self.parents().any(|parent| {
+            parent.elem().static_fields.iter().any(|inherited| {
+                self.elem()
+                    .static_fields
+                    .iter()
+                    .find(|f| f.elem.name == inherited.elem.name)
+                    .map_or(false, |f| {
+                        f.elem.r#type.elem != inherited.elem.r#type.elem
+                            || f.attributes.streaming_behavior()
+                                != inherited.attributes.streaming_behavior()
+                    })
+            })
+        })
+    }
+
     pub fn walk_fields(&'a self) -> impl Iterator<Item = Walker<'a, &'a Field>> {
         self.item.elem.static_fields.iter().map(|f| Walker {
             db: self.db,
@@ -303,6 +355,14 @@ impl<'a> Walker<'a, &'a Class> {
     }
 }
 
+/// Two fields are the same if a subclass can inherit one in place of the other.
+fn same_field(a: &Field, b: &Field) -> bool {
+    a.elem.name == b.elem.name
+        && a.elem.r#type.elem == b.elem.r#type.elem
+        && a.elem.docstring.as_ref().map(|d| &d.0) == b.elem.docstring.as_ref().map(|d| &d.0)
+        && a.attributes.streaming_behavior() == b.attributes.streaming_behavior()
+}
+
 impl<'a> Walker<'a, &'a Client> {
     pub fn elem(&'a self) -> &'a repr::Client {
         &self.item.elem
@@ -402,6 +462,20 @@ impl<'a> Walker<'a, &'a Field> {
             .transpose()
     }
 
+    /// The `@stream.*` attributes of the field.
+    pub fn streaming_behavior(&self) -> StreamingBehavior {
+        self.item.attributes.streaming_behavior()
+    }
+
+    /// Whether the field is marked `@sensitive`, i.e. redacted from traces.
+    pub fn sensitive(&self) -> bool {
+        self.item
+            .attributes
+            .get("sensitive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
     pub fn span(&self) -> Option<&crate::Span> {
         self.item.attributes.span.as_ref()
     }
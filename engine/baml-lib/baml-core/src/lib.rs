@@ -18,6 +18,7 @@ use internal_baml_diagnostics::{DatamodelError, Diagnostics, SourceFile, Span};
 mod common;
 pub mod configuration;
 pub mod ir;
+pub mod lint;
 // mod lockfile;
 mod validate;
 
@@ -0,0 +1,495 @@
+//! Lint rules for BAML projects.
+//!
+//! Unlike validation errors, lint findings never stop a project from
+//! building: they point at code that is valid but probably not what the
+//! author meant (an unused class, a prompt that never renders the output
+//! format, an API key checked into source control, ...).
+//!
+//! Every rule has a stable ID and a default severity. Projects can change
+//! severities in their lint config, and silence a single finding with a
+//! comment on the line before it:
+//!
+//! ```baml
+//! // baml-lint-disable-next-line unused-class
+//! class Scratch {
+//!   ...
+//! }
+//! ```
+//!
+//! or every finding of a rule in a file with `// baml-lint-disable-file <rule>`.
+//! Leaving out the rule IDs disables every rule.
+
+mod rules;
+
+use indexmap::IndexMap;
+use internal_baml_diagnostics::{DatamodelError, DatamodelWarning, Diagnostics, Span};
+use internal_baml_parser_database::ParserDatabase;
+use serde::{Deserialize, Serialize};
+
+/// How a rule's findings are reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Off => "off",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+pub struct Rule {
+    /// The stable ID used in configs and suppression comments.
+    pub id: &'static str,
+    pub description: &'static str,
+    pub default_severity: Severity,
+}
+
+pub const UNUSED_CLASS: &str = "unused-class";
+pub const UNUSED_ENUM: &str = "unused-enum";
+pub const UNUSED_TEMPLATE_STRING: &str = "unused-template-string";
+pub const MISSING_OUTPUT_FORMAT: &str = "missing-output-format";
+pub const UNUSED_FUNCTION_INPUT: &str = "unused-function-input";
+pub const TEST_WITHOUT_ASSERTS: &str = "test-without-asserts";
+pub const HARDCODED_API_KEY: &str = "hardcoded-api-key";
+pub const UNDESCRIBED_ENUM_VALUE: &str = "undescribed-enum-value";
+
+/// Every rule, in the order they're documented.
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: UNUSED_CLASS,
+        description: "A class that no function, class or template string refers to.",
+        default_severity: Severity::Warning,
+    },
+    Rule {
+        id: UNUSED_ENUM,
+        description: "An enum that no function, class or template string refers to.",
+        default_severity: Severity::Warning,
+    },
+    Rule {
+        id: UNUSED_TEMPLATE_STRING,
+        description: "A template string that no prompt or template string calls.",
+        default_severity: Severity::Warning,
+    },
+    Rule {
+        id: MISSING_OUTPUT_FORMAT,
+        description: "A function that returns structured data, but whose prompt never renders `ctx.output_format`.",
+        default_severity: Severity::Warning,
+    },
+    Rule {
+        id: UNUSED_FUNCTION_INPUT,
+        description: "A function parameter that the prompt never mentions.",
+        default_severity: Severity::Warning,
+    },
+    Rule {
+        id: TEST_WITHOUT_ASSERTS,
+        description: "A test with no `@@assert` or `@@check`, so it can only fail if the call does.",
+        default_severity: Severity::Off,
+    },
+    Rule {
+        id: HARDCODED_API_KEY,
+        description: "A client whose API key or credentials are written in the source instead of read from `env`.",
+        default_severity: Severity::Error,
+    },
+    Rule {
+        id: UNDESCRIBED_ENUM_VALUE,
+        description: "An enum value without a `@description`, so the model only has its name to go on.",
+        default_severity: Severity::Off,
+    },
+];
+
+pub fn find_rule(id: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|r| r.id == id)
+}
+
+/// Per-project severity overrides.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub rules: IndexMap<String, Severity>,
+}
+
+impl LintConfig {
+    /// The file, in `baml_src`, that the config is read from.
+    pub const FILE_NAME: &'static str = "baml-lint.yaml";
+
+    pub fn severity(&self, rule: &Rule) -> Severity {
+        self.rules
+            .get(rule.id)
+            .copied()
+            .unwrap_or(rule.default_severity)
+    }
+
+    /// The rule IDs in the config that don't name a rule.
+    pub fn unknown_rules(&self) -> Vec<&str> {
+        self.rules
+            .keys()
+            .filter(|id| find_rule(id).is_none())
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Finding {
+    /// Adds the finding to `diagnostics`, as a warning or an error depending
+    /// on its severity.
+    pub fn push_to(&self, diagnostics: &mut Diagnostics) {
+        let message = format!("{} [{}]", self.message, self.rule);
+        match self.severity {
+            Severity::Off => {}
+            Severity::Warning => {
+                diagnostics.push_warning(DatamodelWarning::new(message, self.span.clone()))
+            }
+            Severity::Error => diagnostics.push_error(DatamodelError::new_validation_error(
+                &message,
+                self.span.clone(),
+            )),
+        }
+    }
+}
+
+/// Runs every enabled rule over a project that passed validation.
+///
+/// Findings come back sorted by file and position, without the ones that are
+/// turned off or suppressed by a comment.
+pub fn lint(db: &ParserDatabase, config: &LintConfig) -> Vec<Finding> {
+    let mut findings = vec![];
+    for rule in RULES {
+        let severity = config.severity(rule);
+        if severity == Severity::Off {
+            continue;
+        }
+        for (span, message) in rules::check(rule.id, db) {
+            if is_suppressed(&span, rule.id) {
+                continue;
+            }
+            findings.push(Finding {
+                rule: rule.id,
+                severity,
+                message,
+                span,
+            });
+        }
+    }
+
+    findings.sort_by(|a, b| {
+        (a.span.file.path_buf(), a.span.start).cmp(&(b.span.file.path_buf(), b.span.start))
+    });
+    findings
+}
+
+const DISABLE_NEXT_LINE: &str = "baml-lint-disable-next-line";
+const DISABLE_FILE: &str = "baml-lint-disable-file";
+
+fn is_suppressed(span: &Span, rule: &str) -> bool {
+    let text = span.file.as_str();
+    let start = span.start.min(text.len());
+
+    let file_wide = text
+        .lines()
+        .any(|line| suppresses(line, DISABLE_FILE, rule));
+    if file_wide {
+        return true;
+    }
+
+    let line_start = text[..start].rfind('\n').map_or(0, |i| i + 1);
+    if line_start == 0 {
+        return false;
+    }
+    let previous_line = text[..line_start - 1].rsplit('\n').next().unwrap_or("");
+    suppresses(previous_line, DISABLE_NEXT_LINE, rule)
+}
+
+/// Whether `line` is a `// <directive> [rule, ...]` comment that covers
+/// `rule`.
+fn suppresses(line: &str, directive: &str, rule: &str) -> bool {
+    let Some(comment) = line.trim().strip_prefix("//") else {
+        return false;
+    };
+    let Some(rules) = comment
+        .trim_start_matches('/')
+        .trim()
+        .strip_prefix(directive)
+    else {
+        return false;
+    };
+    if !rules.is_empty() && !rules.starts_with(char::is_whitespace) {
+        return false;
+    }
+    let mut rules = rules
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|r| !r.is_empty())
+        .peekable();
+    rules.peek().is_none() || rules.any(|r| r == rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use internal_baml_diagnostics::SourceFile;
+
+    use super::*;
+
+    fn lint_source(source: &str, config: &LintConfig) -> Vec<(&'static str, String)> {
+        let path = PathBuf::from("main.baml");
+        let schema = crate::validate(&path, vec![SourceFile::from((path.clone(), source))]);
+        assert!(
+            !schema.diagnostics.has_errors(),
+            "{}",
+            schema.diagnostics.to_pretty_string()
+        );
+        lint(&schema.db, config)
+            .into_iter()
+            .map(|f| (f.rule, f.message))
+            .collect()
+    }
+
+    fn rules(findings: &[(&'static str, String)]) -> Vec<&'static str> {
+        findings.iter().map(|(rule, _)| *rule).collect()
+    }
+
+    const FIXTURES_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/lint_files");
+
+    /// Each rule has a fixture, `tests/lint_files/<rule>.baml`, ending in a
+    /// `// <rule>: <line>: <message>` comment for each finding of the rule.
+    #[test]
+    fn rule_fixtures() {
+        let config = LintConfig {
+            rules: RULES
+                .iter()
+                .map(|rule| (rule.id.to_string(), Severity::Warning))
+                .collect(),
+        };
+
+        for rule in RULES {
+            let path = PathBuf::from(format!("{FIXTURES_ROOT}/{}.baml", rule.id));
+            let source = std::fs::read_to_string(&path)
+                .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()));
+            let prefix = format!("// {}: ", rule.id);
+            let expected = source
+                .lines()
+                .filter(|line| line.starts_with(&prefix))
+                .map(str::to_string)
+                .collect::<Vec<_>>();
+
+            let schema = crate::validate(&path, vec![SourceFile::from((path.clone(), source))]);
+            assert!(
+                !schema.diagnostics.has_errors(),
+                "{}",
+                schema.diagnostics.to_pretty_string()
+            );
+            let actual = lint(&schema.db, &config)
+                .into_iter()
+                .filter(|f| f.rule == rule.id)
+                .map(|f| {
+                    let ((line, _), _) = f.span.line_and_column();
+                    format!("{prefix}{}: {}", line + 1, f.message)
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{}", path.display());
+        }
+    }
+
+    const CLIENT: &str = r#"
+client<llm> GPT4 {
+  provider openai
+  options {
+    model gpt-4o
+    api_key env.OPENAI_API_KEY
+  }
+}
+"#;
+
+    #[test]
+    fn clean_project_has_no_findings() {
+        let source = format!(
+            r##"{CLIENT}
+class Resume {{
+  name string
+  skills Skill[]
+}}
+
+enum Skill {{
+  Rust
+  Python
+}}
+
+template_string Instructions(resume: string) #"
+  Read this: {{{{ resume }}}}
+"#
+
+function ExtractResume(resume: string) -> Resume {{
+  client GPT4
+  prompt #"
+    {{{{ Instructions(resume) }}}}
+    {{{{ ctx.output_format }}}}
+  "#
+}}
+"##
+        );
+        assert_eq!(lint_source(&source, &LintConfig::default()), vec![]);
+    }
+
+    #[test]
+    fn reports_unused_declarations_and_prompt_mistakes() {
+        let source = format!(
+            r##"{CLIENT}
+class Resume {{
+  name string
+}}
+
+class Unused {{
+  again Unused?
+}}
+
+enum Color {{
+  Red
+}}
+
+template_string Helper #"
+  Be nice.
+"#
+
+function ExtractResume(resume: string, notes: string) -> Resume {{
+  client GPT4
+  prompt #"
+    Extract: {{{{ resume }}}}
+  "#
+}}
+"##
+        );
+        let findings = lint_source(&source, &LintConfig::default());
+        assert_eq!(
+            rules(&findings),
+            vec![
+                UNUSED_CLASS,
+                UNUSED_ENUM,
+                UNUSED_TEMPLATE_STRING,
+                MISSING_OUTPUT_FORMAT,
+                UNUSED_FUNCTION_INPUT,
+            ]
+        );
+        assert!(findings[4].1.contains("`notes`"), "{:?}", findings[4]);
+    }
+
+    #[test]
+    fn reports_hardcoded_keys_and_opt_in_rules() {
+        let source = r##"
+client<llm> GPT4 {
+  provider openai
+  options {
+    model gpt-4o
+    api_key "sk-1234"
+  }
+}
+
+enum Color {
+  Red @description("The color of blood")
+  Blue
+}
+
+function Pick(text: string) -> Color {
+  client GPT4
+  prompt #"
+    {{ text }} {{ ctx.output_format }}
+  "#
+}
+
+test PickRed {
+  functions [Pick]
+  args {
+    text "blood"
+  }
+}
+"##;
+        assert_eq!(
+            rules(&lint_source(source, &LintConfig::default())),
+            vec![HARDCODED_API_KEY]
+        );
+
+        let config = LintConfig {
+            rules: [
+                (HARDCODED_API_KEY.to_string(), Severity::Off),
+                (TEST_WITHOUT_ASSERTS.to_string(), Severity::Warning),
+                (UNDESCRIBED_ENUM_VALUE.to_string(), Severity::Error),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        assert_eq!(
+            rules(&lint_source(source, &config)),
+            vec![UNDESCRIBED_ENUM_VALUE, TEST_WITHOUT_ASSERTS]
+        );
+    }
+
+    #[test]
+    fn respects_suppression_comments() {
+        let source = r#"
+// baml-lint-disable-next-line unused-class
+class Scratch {
+  a string
+}
+
+class Other {
+  b string
+}
+
+// baml-lint-disable-next-line unused-enum, unused-class
+enum Color {
+  Red
+}
+"#;
+        let findings = lint_source(source, &LintConfig::default());
+        assert_eq!(rules(&findings), vec![UNUSED_CLASS]);
+        assert!(findings[0].1.contains("`Other`"));
+
+        let source = format!("// baml-lint-disable-file\n{}", source);
+        assert_eq!(lint_source(&source, &LintConfig::default()), vec![]);
+    }
+
+    #[test]
+    fn parses_suppression_comments() {
+        assert!(suppresses(
+            "  // baml-lint-disable-next-line",
+            DISABLE_NEXT_LINE,
+            "x"
+        ));
+        assert!(suppresses(
+            "// baml-lint-disable-next-line a, x",
+            DISABLE_NEXT_LINE,
+            "x"
+        ));
+        assert!(!suppresses(
+            "// baml-lint-disable-next-line a",
+            DISABLE_NEXT_LINE,
+            "x"
+        ));
+        assert!(!suppresses(
+            "// baml-lint-disable-next-lines",
+            DISABLE_NEXT_LINE,
+            "x"
+        ));
+        assert!(!suppresses(
+            "baml-lint-disable-next-line",
+            DISABLE_NEXT_LINE,
+            "x"
+        ));
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+
+use baml_types::TypeValue;
+use internal_baml_diagnostics::Span;
+use internal_baml_parser_database::ParserDatabase;
+use internal_baml_schema_ast::ast::{
+    Expression, FieldType, Identifier, WithIdentifier, WithName, WithSpan,
+};
+
+use super::*;
+
+/// Client options that hold credentials.
+const SECRET_OPTIONS: &[&str] = &[
+    "api_key",
+    "aws_access_key_id",
+    "aws_secret_access_key",
+    "aws_session_token",
+];
+
+/// Headers that hold credentials, compared case-insensitively.
+const SECRET_HEADERS: &[&str] = &["authorization", "api-key", "x-api-key"];
+
+/// The findings of one rule, as the span to report and a message.
+pub(super) fn check(rule: &str, db: &ParserDatabase) -> Vec<(Span, String)> {
+    match rule {
+        UNUSED_CLASS => unused_classes(db),
+        UNUSED_ENUM => unused_enums(db),
+        UNUSED_TEMPLATE_STRING => unused_template_strings(db),
+        MISSING_OUTPUT_FORMAT => missing_output_format(db),
+        UNUSED_FUNCTION_INPUT => unused_function_inputs(db),
+        TEST_WITHOUT_ASSERTS => tests_without_asserts(db),
+        HARDCODED_API_KEY => hardcoded_api_keys(db),
+        UNDESCRIBED_ENUM_VALUE => undescribed_enum_values(db),
+        _ => vec![],
+    }
+}
+
+/// The names of the types that something refers to. A class referring to
+/// itself doesn't count, so recursive classes can still be unused.
+fn referenced_types(db: &ParserDatabase) -> HashSet<&str> {
+    let mut types: Vec<&FieldType> = vec![];
+    for function in db.walk_functions() {
+        types.extend(
+            function
+                .walk_input_args()
+                .chain(function.walk_output_args())
+                .map(|arg| arg.field_type()),
+        );
+    }
+    for template in db.walk_templates() {
+        types.extend(template.walk_input_args().map(|arg| arg.field_type()));
+    }
+
+    let mut names: HashSet<&str> = types
+        .into_iter()
+        .flat_map(|t| t.flat_idns())
+        .map(Identifier::name)
+        .collect();
+    for class in db.walk_classes() {
+        let referenced = class
+            .static_fields()
+            .filter_map(|f| f.r#type().as_ref())
+            .flat_map(|t| t.flat_idns())
+            .map(Identifier::name)
+            .chain(class.parents().map(|p| p.ast_type_block().name()));
+        names.extend(referenced.filter(|name| *name != class.name()));
+    }
+    names
+}
+
+fn unused_classes(db: &ParserDatabase) -> Vec<(Span, String)> {
+    let referenced = referenced_types(db);
+    db.walk_classes()
+        .filter(|class| !referenced.contains(class.name()))
+        .map(|class| {
+            (
+                class.identifier().span().clone(),
+                format!("Class `{}` is never used", class.name()),
+            )
+        })
+        .collect()
+}
+
+fn unused_enums(db: &ParserDatabase) -> Vec<(Span, String)> {
+    let referenced = referenced_types(db);
+    db.walk_enums()
+        .filter(|e| !referenced.contains(e.ast_type_block().name()))
+        .map(|e| {
+            let identifier = e.ast_type_block().identifier();
+            (
+                identifier.span().clone(),
+                format!("Enum `{}` is never used", identifier.name()),
+            )
+        })
+        .collect()
+}
+
+fn unused_template_strings(db: &ParserDatabase) -> Vec<(Span, String)> {
+    let prompts = db
+        .walk_functions()
+        .filter(|f| !f.is_old_function())
+        .map(|f| f.jinja_prompt())
+        .collect::<Vec<_>>();
+
+    db.walk_templates()
+        .filter(|template| {
+            let name = template.name();
+            let in_prompt = prompts.iter().any(|p| mentions(p, name));
+            let in_template = db
+                .walk_templates()
+                .filter(|other| other.name() != name)
+                .any(|other| mentions(other.template_string(), name));
+            !in_prompt && !in_template
+        })
+        .map(|template| {
+            (
+                template.identifier().span().clone(),
+                format!("Template string `{}` is never used", template.name()),
+            )
+        })
+        .collect()
+}
+
+fn missing_output_format(db: &ParserDatabase) -> Vec<(Span, String)> {
+    db.walk_functions()
+        .filter(|f| !f.is_old_function())
+        .filter(|f| {
+            // Strings need no instructions, the model's reply is the result.
+            let returns_string = f.walk_output_args().all(|arg| {
+                matches!(
+                    arg.field_type(),
+                    FieldType::Primitive(_, TypeValue::String, ..)
+                )
+            });
+            !returns_string && !f.jinja_prompt().contains("ctx.output_format")
+        })
+        .map(|f| {
+            (
+                f.identifier().span().clone(),
+                format!(
+                    "The prompt of `{}` never renders `{{{{ ctx.output_format }}}}`, so the model isn't told what shape to answer in",
+                    f.name()
+                ),
+            )
+        })
+        .collect()
+}
+
+fn unused_function_inputs(db: &ParserDatabase) -> Vec<(Span, String)> {
+    let mut findings = vec![];
+    for function in db.walk_functions().filter(|f| !f.is_old_function()) {
+        let prompt = function.jinja_prompt();
+        for arg in function.walk_input_args() {
+            let Some(identifier) = arg.ast_arg().0 else {
+                continue;
+            };
+            if !mentions(prompt, identifier.name()) {
+                findings.push((
+                    identifier.span().clone(),
+                    format!(
+                        "`{}` is never used in the prompt of `{}`",
+                        identifier.name(),
+                        function.name()
+                    ),
+                ));
+            }
+        }
+    }
+    findings
+}
+
+fn tests_without_asserts(db: &ParserDatabase) -> Vec<(Span, String)> {
+    db.walk_test_cases()
+        .filter(|test| test.test_case().constraints.is_empty())
+        .map(|test| {
+            (
+                test.identifier().span().clone(),
+                format!(
+                    "Test `{}` has no `@@assert` or `@@check`, so it only fails if the call does",
+                    test.name()
+                ),
+            )
+        })
+        .collect()
+}
+
+fn hardcoded_api_keys(db: &ParserDatabase) -> Vec<(Span, String)> {
+    let mut findings = vec![];
+    for client in db.walk_clients() {
+        let Some(options) = client
+            .ast_client()
+            .fields()
+            .iter()
+            .find(|f| f.name() == "options")
+            .and_then(|f| f.expr.as_ref())
+            .and_then(Expression::as_map)
+        else {
+            continue;
+        };
+
+        for (key, value) in options.0 {
+            let Some((key, _)) = key.as_string_value() else {
+                continue;
+            };
+            if SECRET_OPTIONS.contains(&key) && is_literal_secret(value) {
+                findings.push((
+                    value.span().clone(),
+                    format!(
+                        "`{}` of client `{}` is hard-coded, read it from the environment with `env.` instead",
+                        key,
+                        client.name()
+                    ),
+                ));
+            }
+            if key != "headers" {
+                continue;
+            }
+            let Some((headers, _)) = value.as_map() else {
+                continue;
+            };
+            for (header, value) in headers {
+                let Some((header, _)) = header.as_string_value() else {
+                    continue;
+                };
+                if SECRET_HEADERS.contains(&header.to_lowercase().as_str())
+                    && is_literal_secret(value)
+                {
+                    findings.push((
+                        value.span().clone(),
+                        format!(
+                            "The `{}` header of client `{}` is hard-coded, read it from the environment with `env.` instead",
+                            header,
+                            client.name()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    findings
+}
+
+/// Whether `value` is written out in the source, rather than read from
+/// `env`. Empty strings are how some providers are told to send no key.
+fn is_literal_secret(value: &Expression) -> bool {
+    match value {
+        Expression::Identifier(Identifier::ENV(..)) => false,
+        _ => value.as_string_value().is_some_and(|(s, _)| !s.is_empty()),
+    }
+}
+
+fn undescribed_enum_values(db: &ParserDatabase) -> Vec<(Span, String)> {
+    let mut findings = vec![];
+    for e in db.walk_enums() {
+        for value in e.values() {
+            let described = value
+                .get_default_attributes()
+                .is_some_and(|attributes| attributes.description.is_some());
+            if !described {
+                findings.push((
+                    value.span().clone(),
+                    format!(
+                        "`{}.{}` has no `@description`",
+                        e.ast_type_block().name(),
+                        value.name()
+                    ),
+                ));
+            }
+        }
+    }
+    findings
+}
+
+/// Whether `name` appears in `text` as a whole word.
+fn mentions(text: &str, name: &str) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(name).any(|(i, _)| {
+        let before = text[..i].chars().next_back();
+        let after = text[i + name.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
@@ -16,8 +16,6 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
     // graph because technically an optional field doesn't "depend" on anything,
     // it can just be null.
     let dependency_graph = HashMap::from_iter(ctx.db.walk_classes().map(|class| {
-        let expr_block = &ctx.db.ast()[class.id];
-
         // TODO: There's already a hash set that returns "dependencies" in
         // the DB, it shoudn't be necessary to traverse all the fields here
         // again and build yet another graph, we need to refactor
@@ -29,8 +27,10 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
         // fn visit_class()
         let mut dependencies = HashSet::new();
 
-        for field in &expr_block.fields {
-            if let Some(field_type) = &field.expr {
+        // Inherited fields count as well, a class can't extend a class that
+        // requires an instance of the child.
+        for field in class.walk_all_fields() {
+            if let Some(field_type) = &field.ast_field().expr {
                 insert_required_deps(class.id, field_type, ctx, &mut dependencies);
             }
         }
@@ -253,7 +253,7 @@ impl<'c> NestedChecks<'c> {
                         return false;
                     }
 
-                    let mut fields = class_walker.static_fields();
+                    let mut fields = class_walker.walk_all_fields();
                     fields.any(|field| {
                         field
                             .ast_field()
@@ -265,6 +265,7 @@ impl<'c> NestedChecks<'c> {
                 _ => false,
             },
 
+            FieldType::Generic(_, _, args, ..) => args.iter().any(|ft| self.has_checks_nested(ft)),
             FieldType::Primitive(..) => false,
             FieldType::Union(_, children, ..) => {
                 children.iter().any(|ft| self.has_checks_nested(ft))
@@ -1,6 +1,7 @@
 use baml_types::{Constraint, ConstraintLevel};
 use internal_baml_diagnostics::{DatamodelError, DatamodelWarning, Span};
 use internal_baml_jinja_types::{validate_expression, JinjaContext, PredefinedTypes, Type};
+use internal_baml_schema_ast::ast::WithName;
 
 use crate::validate::validation_pipeline::context::Context;
 
@@ -8,7 +9,26 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
     let tests = ctx.db.walk_test_cases().collect::<Vec<_>>();
     tests.iter().for_each(|walker| {
         let constraints = &walker.test_case().constraints;
-        let args = &walker.test_case().args;
+        let dataset = walker.test_case().dataset.as_ref();
+        // Arguments read from a dataset default to the keys named after the
+        // parameters of the functions under test.
+        let arg_names = walker
+            .test_case()
+            .args
+            .keys()
+            .cloned()
+            .chain(dataset.into_iter().flat_map(|dataset| {
+                dataset.args.keys().cloned().chain(
+                    walker
+                        .test_case()
+                        .functions
+                        .iter()
+                        .filter_map(|(name, _)| ctx.db.find_function_by_name(name))
+                        .flat_map(|function| function.walk_input_args())
+                        .filter_map(|arg| arg.ast_arg().0.map(|id| id.name().to_string())),
+                )
+            }))
+            .collect::<Vec<_>>();
         let mut check_names: Vec<String> = Vec::new();
         for (
             Constraint {
@@ -37,10 +57,16 @@ pub(super) fn validate(ctx: &mut Context<'_>) {
                     ("latency_ms".to_string(), Type::Number),
                 ]
                 .into_iter()
+                .chain(
+                    dataset
+                        .and_then(|dataset| dataset.expected.as_ref())
+                        .map(|_| ("expected".to_string(), Type::Unknown)),
+                )
                 .collect(),
             );
             defined_types.add_variable("_", Type::ClassRef("_".to_string()));
-            args.keys()
+            arg_names
+                .iter()
                 .for_each(|arg_name| defined_types.add_variable(arg_name, Type::Unknown));
             if let (ConstraintLevel::Check, Some(check_name)) = (level, label) {
                 check_names.push(check_name.to_string());
@@ -115,7 +115,7 @@ fn validate_type_allowed(ctx: &mut Context<'_>, field_type: &FieldType) {
 
         FieldType::Primitive(..) => {}
         FieldType::Literal(..) => {}
-        FieldType::Symbol(..) => {}
+        FieldType::Symbol(..) | FieldType::Generic(..) => {}
 
         FieldType::List(arity, field_type, ..) => {
             if arity.is_optional() {
@@ -2,6 +2,7 @@ mod constraint;
 mod map;
 mod media;
 mod minijinja;
+mod streaming;
 
 mod baml_value;
 mod field_type;
@@ -15,4 +16,7 @@ pub use generator::{GeneratorDefaultClientMode, GeneratorOutputType};
 pub use map::Map as BamlMap;
 pub use media::{BamlMedia, BamlMediaContent, BamlMediaType, MediaBase64, MediaUrl};
 pub use minijinja::JinjaExpression;
-pub use value_expr::{EvaluationContext, GetEnvVar, ResolvedValue, StringOr, UnresolvedValue};
+pub use streaming::{CompletionState, StreamingBehavior};
+pub use value_expr::{
+    EvaluationContext, GetEnvVar, ResolveSecret, ResolvedValue, StringOr, UnresolvedValue,
+};
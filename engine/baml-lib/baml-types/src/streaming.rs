@@ -0,0 +1,41 @@
+/// How a value should be surfaced while the LLM response is still streaming.
+///
+/// Populated from the `@stream.*` attributes on class fields and the
+/// `@@stream.*` attributes on classes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct StreamingBehavior {
+    /// `@stream.done`: only emit the value once it has been fully parsed.
+    pub done: bool,
+    /// `@stream.not_null`: hold back the parent class until this value is present.
+    pub not_null: bool,
+    /// `@stream.with_state`: wrap the value together with its `CompletionState`.
+    pub state: bool,
+}
+
+impl StreamingBehavior {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Combine the behavior of a field with the behavior of its type.
+    pub fn combine(&self, other: &StreamingBehavior) -> StreamingBehavior {
+        StreamingBehavior {
+            done: self.done || other.done,
+            not_null: self.not_null || other.not_null,
+            state: self.state || other.state,
+        }
+    }
+}
+
+/// The completion state reported for `@stream.with_state` values.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, strum::Display, strum::EnumString,
+)]
+pub enum CompletionState {
+    /// No tokens for the value have been received yet.
+    Pending,
+    /// Some tokens have been received, but the value may still change.
+    Incomplete,
+    /// The value is final.
+    Complete,
+}
@@ -208,8 +208,17 @@ pub trait GetEnvVar {
     fn set_allow_missing_env_var(&self, allow: bool) -> Self;
 }
 
+/// Resolves environment variables whose value refers to a secret stored
+/// elsewhere, e.g. `OPENAI_API_KEY=file:/run/secrets/openai`.
+pub trait ResolveSecret {
+    /// Returns the secret `value` refers to, or `None` if `value` is not a
+    /// reference this resolver understands and should be used as is.
+    fn resolve_secret(&self, value: &str) -> Option<Result<String>>;
+}
+
 pub struct EvaluationContext<'a> {
     env_vars: Option<&'a HashMap<String, String>>,
+    secrets: Option<&'a dyn ResolveSecret>,
     fill_missing_env_vars: bool,
 }
 
@@ -220,7 +229,14 @@ impl<'a> GetEnvVar for EvaluationContext<'a> {
             .as_ref()
             .and_then(|env_vars| env_vars.get(key))
         {
-            Some(v) => Ok(v.to_string()),
+            Some(v) => match self.secrets.and_then(|secrets| secrets.resolve_secret(v)) {
+                Some(secret) => secret.map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to resolve the secret in environment variable {key}: {e}"
+                    )
+                }),
+                None => Ok(v.to_string()),
+            },
             None => {
                 if self.fill_missing_env_vars {
                     Ok(format!("${key}"))
@@ -234,6 +250,7 @@ impl<'a> GetEnvVar for EvaluationContext<'a> {
     fn set_allow_missing_env_var(&self, allow: bool) -> Self {
         Self {
             env_vars: self.env_vars,
+            secrets: self.secrets,
             fill_missing_env_vars: allow,
         }
     }
@@ -243,15 +260,26 @@ impl<'a> EvaluationContext<'a> {
     pub fn new(env_vars: &'a HashMap<String, String>, fill_missing_env_vars: bool) -> Self {
         Self {
             env_vars: Some(env_vars),
+            secrets: None,
             fill_missing_env_vars,
         }
     }
+
+    /// Resolves the secret references in environment variables with
+    /// `secrets`. Without it, their values are used as is.
+    pub fn with_secrets(self, secrets: &'a dyn ResolveSecret) -> Self {
+        Self {
+            secrets: Some(secrets),
+            ..self
+        }
+    }
 }
 
 impl<'db> Default for EvaluationContext<'db> {
     fn default() -> Self {
         Self {
             env_vars: None,
+            secrets: None,
             fill_missing_env_vars: true,
         }
     }
@@ -195,6 +195,11 @@ impl minijinja::value::StructObject for MinijinjaBamlClass {
     fn static_fields(&self) -> Option<&'static [&'static str]> {
         None
     }
+
+    // Lets filters like `to_json` and `items` see the fields of a class.
+    fn fields(&self) -> Vec<std::sync::Arc<str>> {
+        self.class.keys().map(|k| k.as_str().into()).collect()
+    }
 }
 
 struct MinijinjaBamlClass {
@@ -5,25 +5,32 @@ mod chat_message_part;
 mod output_format;
 use internal_baml_core::ir::jinja_helpers::get_env;
 use internal_baml_core::ir::repr::IntermediateRepr;
-pub use output_format::types;
+pub use output_format::{types, OutputStyle};
 mod baml_value_to_jinja_value;
+mod tokenizer;
 
 use minijinja::{self, value::Kwargs};
 use minijinja::{context, ErrorKind};
 use output_format::types::OutputFormatContent;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
 
 use crate::baml_value_to_jinja_value::IntoMiniJinjaValue;
 pub use crate::chat_message_part::ChatMessagePart;
 use crate::output_format::OutputFormat;
+pub use crate::tokenizer::{TokenEstimate, Tokenizer};
 
 #[allow(non_camel_case_types)]
 #[derive(Clone, Debug, Serialize)]
 pub struct RenderContext_Client {
     pub name: String,
     pub provider: String,
+    /// The `model` option of the client, if it has one.
+    pub model: Option<String>,
     pub default_role: String,
     pub allowed_roles: Vec<String>,
 }
@@ -44,6 +51,20 @@ pub struct TemplateStringMacro {
 const MAGIC_CHAT_ROLE_DELIMITER: &str = "BAML_CHAT_ROLE_MAGIC_STRING_DELIMITER";
 const MAGIC_MEDIA_DELIMITER: &str = "BAML_MEDIA_MAGIC_STRING_DELIMITER";
 
+/// The marker a `_.role()` call leaves in the rendered template. The rendered
+/// output is split on these markers into chat messages.
+fn chat_role_marker(
+    role: &str,
+    allow_duplicate_role: bool,
+    mut props: HashMap<&str, serde_json::Value>,
+) -> String {
+    props.insert("role", role.into());
+    props.insert("__baml_allow_dupe_role__", allow_duplicate_role.into());
+
+    let props = json!(props).to_string();
+    format!("{MAGIC_CHAT_ROLE_DELIMITER}:baml-start-baml:{props}:baml-end-baml:{MAGIC_CHAT_ROLE_DELIMITER}")
+}
+
 fn render_minijinja(
     template: &str,
     args: &minijinja::Value,
@@ -51,6 +72,7 @@ fn render_minijinja(
     template_string_macros: &[TemplateStringMacro],
     default_role: String,
     allowed_roles: Vec<String>,
+    rendered_style: Arc<Mutex<Option<OutputStyle>>>,
 ) -> Result<RenderedPrompt, minijinja::Error> {
     let mut env = get_env();
 
@@ -94,8 +116,17 @@ fn render_minijinja(
 
     env.add_template("prompt", &template)?;
     let client = ctx.client.clone();
+
+    let tokenizer = Tokenizer::for_client(&client.provider, client.model.as_deref());
+    env.add_filter("token_count", move |text: String| {
+        tokenizer.count_tokens(&text)
+    });
+    env.add_filter("truncate_tokens", move |text: String, max_tokens: usize| {
+        tokenizer.truncate(&text, max_tokens).to_string()
+    });
+
     let tags = std::mem::take(&mut ctx.tags);
-    let formatter = OutputFormat::new(ctx);
+    let formatter = OutputFormat::new(ctx, rendered_style);
     env.add_global(
         "ctx",
         context! {
@@ -134,27 +165,55 @@ fn render_minijinja(
                 },
             };
 
-            let additional_properties = {
-                let mut props = kwargs
-                    .args()
-                    .filter(|&k| k != "role")
-                    .map(|k| {
-                        Ok((
-                            k,
-                            serde_json::Value::deserialize(kwargs.get::<minijinja::Value>(k)?)?,
-                        ))
-                    })
-                    .collect::<Result<HashMap<&str, serde_json::Value>, minijinja::Error>>()?;
-
-                props.insert("role", role.clone().into());
-                props.insert("__baml_allow_dupe_role__", allow_duplicate_role.into());
-
-                props
-            };
+            let additional_properties = kwargs
+                .args()
+                .filter(|&k| k != "role" && k != "__baml_allow_dupe_role__")
+                .map(|k| {
+                    Ok((
+                        k,
+                        serde_json::Value::deserialize(kwargs.get::<minijinja::Value>(k)?)?,
+                    ))
+                })
+                .collect::<Result<HashMap<&str, serde_json::Value>, minijinja::Error>>()?;
+
+            Ok(chat_role_marker(
+                &role,
+                allow_duplicate_role,
+                additional_properties,
+            ))
+        },
+    );
 
-            let additional_properties = json!(additional_properties).to_string();
+    // `_.chat_history(messages)` expands a list of `ChatMessage`s into one
+    // `_.role()` block per message, with media parts rendered in place.
+    let chat_history_fn = minijinja::Value::from_function(
+        |messages: minijinja::Value| -> Result<String, minijinja::Error> {
+            let mut rendered = String::new();
+            for message in messages.try_iter()? {
+                let Some(role) = message.get_attr("role")?.as_str().map(str::to_string) else {
+                    return Err(minijinja::Error::new(
+                        ErrorKind::InvalidOperation,
+                        format!(
+                            "chat_history() expects messages with a string role, got: {message}"
+                        ),
+                    ));
+                };
+                rendered.push_str(&chat_role_marker(&role, false, HashMap::new()));
 
-            Ok(format!("{MAGIC_CHAT_ROLE_DELIMITER}:baml-start-baml:{additional_properties}:baml-end-baml:{MAGIC_CHAT_ROLE_DELIMITER}"))
+                let content = message.get_attr("content")?;
+                if content.is_undefined() || content.is_none() {
+                    continue;
+                }
+                if content.kind() == minijinja::value::ValueKind::Seq {
+                    for part in content.try_iter()? {
+                        rendered.push_str(&part.to_string());
+                        rendered.push('\n');
+                    }
+                } else {
+                    rendered.push_str(&content.to_string());
+                }
+            }
+            Ok(rendered)
         },
     );
 
@@ -162,7 +221,8 @@ fn render_minijinja(
         "_",
         context! {
             chat => role_fn,
-            role => role_fn
+            role => role_fn,
+            chat_history => chat_history_fn,
         },
     );
 
@@ -410,6 +470,20 @@ pub fn render_prompt(
     ir: &IntermediateRepr,
     env_vars: &HashMap<String, String>,
 ) -> anyhow::Result<RenderedPrompt> {
+    render_prompt_with_output_style(template, args, ctx, template_string_macros, ir, env_vars)
+        .map(|(prompt, _)| prompt)
+}
+
+/// Renders a prompt, and returns the style `ctx.output_format` was rendered
+/// in, if the prompt used it. Responses should be parsed in that style.
+pub fn render_prompt_with_output_style(
+    template: &str,
+    args: &BamlValue,
+    ctx: RenderContext,
+    template_string_macros: &[TemplateStringMacro],
+    ir: &IntermediateRepr,
+    env_vars: &HashMap<String, String>,
+) -> anyhow::Result<(RenderedPrompt, Option<OutputStyle>)> {
     if !matches!(args, BamlValue::Map(_)) {
         anyhow::bail!("args must be a map");
     }
@@ -417,6 +491,7 @@ pub fn render_prompt(
     let minijinja_args: minijinja::Value = args.clone().to_minijinja_value(ir, &eval_ctx);
     let default_role = ctx.client.default_role.clone();
     let allowed_roles = ctx.client.allowed_roles.clone();
+    let rendered_style = Arc::new(Mutex::new(None));
     let rendered = render_minijinja(
         template,
         &minijinja_args,
@@ -424,10 +499,14 @@ pub fn render_prompt(
         template_string_macros,
         default_role,
         allowed_roles,
+        rendered_style.clone(),
     );
 
     match rendered {
-        Ok(r) => Ok(r),
+        Ok(r) => {
+            let style = rendered_style.lock().ok().and_then(|style| *style);
+            Ok((r, style))
+        }
         Err(err) => {
             let mut minijinja_err = "".to_string();
             minijinja_err += &format!("{err:#}");
@@ -512,6 +591,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -573,6 +653,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -632,6 +713,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -700,6 +782,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string(), "john doe".to_string()],
                 },
@@ -745,6 +828,248 @@ mod render_tests {
         Ok(())
     }
 
+    #[test]
+    fn render_chat_history() -> anyhow::Result<()> {
+        setup_logging();
+
+        let message = |role: &str, content: BamlValue| {
+            BamlValue::Class(
+                "ChatMessage".to_string(),
+                BamlMap::from([
+                    ("role".to_string(), BamlValue::String(role.to_string())),
+                    ("content".to_string(), content),
+                ]),
+            )
+        };
+        let image = BamlMedia::url(
+            BamlMediaType::Image,
+            "https://example.com/cat.jpg".to_string(),
+            None,
+        );
+
+        let args = BamlValue::Map(BamlMap::from([(
+            "history".to_string(),
+            BamlValue::List(vec![
+                message(
+                    "user",
+                    BamlValue::List(vec![
+                        BamlValue::String("What's in this picture?".to_string()),
+                        BamlValue::Media(image.clone()),
+                    ]),
+                ),
+                message("assistant", BamlValue::String("A cat.".to_string())),
+                message("narrator", BamlValue::String("It purrs.".to_string())),
+            ]),
+        )]));
+
+        let ir = make_test_ir(
+            "
+            class C {
+
+            }
+            ",
+        )?;
+
+        let rendered = render_prompt(
+            "
+                {{ _.role('system') }}
+                You are a helpful assistant.
+                {{ _.chat_history(history) }}
+                {{ _.role('user') }}
+                Is it cute?
+            ",
+            &args,
+            RenderContext {
+                client: RenderContext_Client {
+                    name: "gpt4".to_string(),
+                    provider: "openai".to_string(),
+                    model: None,
+                    default_role: "user".to_string(),
+                    allowed_roles: vec![
+                        "system".to_string(),
+                        "user".to_string(),
+                        "assistant".to_string(),
+                    ],
+                },
+                output_format: OutputFormatContent::new_string(),
+                tags: HashMap::new(),
+            },
+            &[],
+            &ir,
+            &HashMap::new(),
+        )?;
+
+        let text = |role: &str, text: &str| RenderedChatMessage {
+            role: role.to_string(),
+            allow_duplicate_role: false,
+            parts: vec![ChatMessagePart::Text(text.to_string())],
+        };
+
+        assert_eq!(
+            rendered,
+            RenderedPrompt::Chat(vec![
+                text("system", "You are a helpful assistant."),
+                RenderedChatMessage {
+                    role: "user".to_string(),
+                    allow_duplicate_role: false,
+                    parts: vec![
+                        ChatMessagePart::Text("What's in this picture?".to_string()),
+                        ChatMessagePart::Media(image),
+                    ]
+                },
+                text("assistant", "A cat."),
+                // Roles the client doesn't allow fall back to its default role.
+                text("user", "It purrs."),
+                text("user", "Is it cute?"),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_token_filters() -> anyhow::Result<()> {
+        setup_logging();
+
+        let args = BamlValue::Map(BamlMap::from([(
+            "text".to_string(),
+            BamlValue::String("abcdefghij".to_string()),
+        )]));
+
+        let ir = make_test_ir(
+            "
+            class C {
+
+            }
+            ",
+        )?;
+
+        let rendered = render_prompt(
+            "{{ text | token_count }} {{ text | truncate_tokens(2) }}",
+            &args,
+            RenderContext {
+                client: RenderContext_Client {
+                    name: "claude".to_string(),
+                    provider: "anthropic".to_string(),
+                    model: Some("claude-3-5-sonnet-latest".to_string()),
+                    default_role: "user".to_string(),
+                    allowed_roles: vec!["user".to_string()],
+                },
+                output_format: OutputFormatContent::new_string(),
+                tags: HashMap::new(),
+            },
+            &[],
+            &ir,
+            &HashMap::new(),
+        )?;
+
+        // Claude's tokens are estimated at 3.5 characters each.
+        assert_eq!(
+            rendered,
+            RenderedPrompt::Completion("3 abcdefg".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_helper_filters() -> anyhow::Result<()> {
+        setup_logging();
+
+        let args = BamlValue::Map(BamlMap::from([(
+            "bio".to_string(),
+            BamlValue::String("Rear admiral and computer scientist".to_string()),
+        )]));
+
+        let ir = make_test_ir(
+            "
+            class C {
+
+            }
+            ",
+        )?;
+
+        let rendered = render_prompt(
+            "{{ bio | truncate_words(2) }}|{{ bio | truncate_words(3, end=' [more]') }}|{{ bio | truncate_words(10) }}",
+            &args,
+            RenderContext {
+                client: RenderContext_Client {
+                    name: "gpt4".to_string(),
+                    provider: "openai".to_string(),
+                    model: None,
+                    default_role: "system".to_string(),
+                    allowed_roles: vec!["system".to_string()],
+                },
+                output_format: OutputFormatContent::new_string(),
+                tags: HashMap::new(),
+            },
+            &[],
+            &ir,
+            &HashMap::new(),
+        )?;
+
+        assert_eq!(
+            rendered,
+            RenderedPrompt::Completion(
+                "Rear admiral...|Rear admiral and [more]|Rear admiral and computer scientist"
+                    .to_string()
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn render_reports_the_output_style() -> anyhow::Result<()> {
+        setup_logging();
+
+        let ir = make_test_ir(
+            "
+            class C {
+
+            }
+            ",
+        )?;
+
+        let style = |template: &str| -> anyhow::Result<Option<OutputStyle>> {
+            let (_, style) = render_prompt_with_output_style(
+                template,
+                &BamlValue::Map(BamlMap::new()),
+                RenderContext {
+                    client: RenderContext_Client {
+                        name: "gpt4".to_string(),
+                        provider: "openai".to_string(),
+                        model: None,
+                        default_role: "system".to_string(),
+                        allowed_roles: vec!["system".to_string()],
+                    },
+                    output_format: OutputFormatContent::new_string(),
+                    tags: HashMap::new(),
+                },
+                &[],
+                &ir,
+                &HashMap::new(),
+            )?;
+            Ok(style)
+        };
+
+        // Styles passed through variables, with parentheses in the prefix.
+        assert_eq!(
+            style(
+                "{% set s = 'yaml' %}{{ ctx.output_format(prefix='Answer (in YAML):', style=s) }}"
+            )?,
+            Some(OutputStyle::Yaml)
+        );
+        assert_eq!(
+            style("{{ ctx.output_format(style='xml') }}")?,
+            Some(OutputStyle::Xml)
+        );
+        assert_eq!(style("{{ ctx.output_format }}")?, Some(OutputStyle::Baml));
+        assert_eq!(style("No output format")?, None);
+
+        Ok(())
+    }
+
     #[test]
     fn render_completion() -> anyhow::Result<()> {
         setup_logging();
@@ -778,6 +1103,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -829,6 +1155,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -869,6 +1196,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -909,6 +1237,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -949,6 +1278,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1011,6 +1341,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1071,6 +1402,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string(), "john doe".to_string()],
                 },
@@ -1118,7 +1450,6 @@ mod render_tests {
         Ok(())
     }
 
-
     #[test]
     fn render_with_kwargs_default_role() -> anyhow::Result<()> {
         setup_logging();
@@ -1156,6 +1487,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string(), "user".to_string()],
                 },
@@ -1234,6 +1566,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1289,6 +1622,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1340,6 +1674,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1387,6 +1722,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1407,6 +1743,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1450,6 +1787,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1470,6 +1808,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1513,6 +1852,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1587,6 +1927,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1681,6 +2022,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1787,6 +2129,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1864,6 +2207,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1924,6 +2268,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -2029,6 +2374,7 @@ mod render_tests {
                 client: RenderContext_Client {
                     name: "gpt4".to_string(),
                     provider: "openai".to_string(),
+                    model: None,
                     default_role: "system".to_string(),
                     allowed_roles: vec!["system".to_string()],
                 },
@@ -1,32 +1,47 @@
+mod style;
 pub mod types;
 
-use std::str::FromStr;
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
 
 use minijinja::{value::Kwargs, ErrorKind, Value};
 use strum::VariantNames;
 
 use crate::{types::RenderOptions, RenderContext};
 
+pub use self::style::OutputStyle;
 use self::types::OutputFormatContent;
 
 // TODO: Rename the field to `content`.
 #[derive(Debug)]
 pub struct OutputFormat {
     text: OutputFormatContent,
+    /// The style the output format was last rendered in, so the response
+    /// can be parsed the way the prompt asked for it.
+    rendered_style: Arc<Mutex<Option<OutputStyle>>>,
 }
 
 impl OutputFormat {
-    pub fn new(ctx: RenderContext) -> Self {
+    pub fn new(ctx: RenderContext, rendered_style: Arc<Mutex<Option<OutputStyle>>>) -> Self {
         Self {
             text: ctx.output_format,
+            rendered_style,
         }
     }
+
+    fn render(&self, options: RenderOptions) -> Result<Option<String>, minijinja::Error> {
+        if let Ok(mut rendered_style) = self.rendered_style.lock() {
+            *rendered_style = Some(options.style);
+        }
+        self.text.render(options)
+    }
 }
 
 impl std::fmt::Display for OutputFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         let content = self
-            .text
             .render(RenderOptions::default())
             .map_err(|_e| std::fmt::Error {})?;
 
@@ -132,51 +147,62 @@ impl minijinja::value::Object for OutputFormat {
             None
         };
 
-        let map_style = if kwargs.has("map_style") {
-            match kwargs
-                .get::<String>("map_style")
-                .map(|s| types::MapStyle::from_str(s.as_str()))
-            {
-                Ok(Ok(map_style)) => Some(map_style),
-                Ok(Err(e)) => {
-                    return Err(Error::new(
-                        ErrorKind::SyntaxError,
-                        format!(
-                            "Invalid value for map_style (expected one of {}): {}",
-                            types::MapStyle::VARIANTS.join(", "),
-                            e
-                        ),
-                    ))
-                }
-                Err(e) => {
-                    return Err(Error::new(
-                        ErrorKind::SyntaxError,
-                        format!(
-                            "Invalid value for map_style (expected one of {}): {}",
-                            types::MapStyle::VARIANTS.join(", "),
-                            e
-                        ),
-                    ))
-                }
+        // `none` means the default, like leaving the argument out.
+        let map_style = match kwargs
+            .get::<Option<String>>("map_style")
+            .map_err(|e| e.to_string())
+            .and_then(|s| {
+                s.map(|s| types::MapStyle::from_str(&s).map_err(|e| e.to_string()))
+                    .transpose()
+            }) {
+            Ok(map_style) => map_style,
+            Err(e) => {
+                return Err(Error::new(
+                    ErrorKind::SyntaxError,
+                    format!(
+                        "Invalid value for map_style (expected one of {}): {}",
+                        types::MapStyle::VARIANTS.join(", "),
+                        e
+                    ),
+                ))
+            }
+        };
+
+        let style = match kwargs
+            .get::<Option<String>>("style")
+            .map_err(|e| e.to_string())
+            .and_then(|s| {
+                s.map(|s| style::OutputStyle::from_str(&s).map_err(|e| e.to_string()))
+                    .transpose()
+            }) {
+            Ok(style) => style,
+            Err(e) => {
+                return Err(Error::new(
+                    ErrorKind::SyntaxError,
+                    format!(
+                        "Invalid value for style (expected one of {}): {}",
+                        style::OutputStyle::VARIANTS.join(", "),
+                        e
+                    ),
+                ))
             }
-        } else {
-            None
         };
 
         let Ok(_) = kwargs.assert_all_used() else {
             return Err(Error::new(
                 ErrorKind::TooManyArguments,
-                "output_format() got an unexpected keyword argument (only 'prefix', 'always_host_enums', 'enum_value_prefix', and 'or_splitter' are allowed)",
+                "output_format() got an unexpected keyword argument (only 'prefix', 'always_hoist_enums', 'enum_value_prefix', 'or_splitter', 'map_style', 'hoisted_class_prefix' and 'style' are allowed)",
             ));
         };
 
-        let content = self.text.render(RenderOptions::new(
+        let content = self.render(RenderOptions::new(
             prefix,
             or_splitter,
             enum_value_prefix,
             always_hoist_enums,
             map_style,
             hoisted_class_prefix,
+            style,
         ))?;
 
         match content {
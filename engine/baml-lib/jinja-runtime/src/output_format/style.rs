@@ -0,0 +1,928 @@
+use baml_types::{FieldType, LiteralValue, TypeValue};
+use indexmap::{IndexMap, IndexSet};
+use serde_json::json;
+
+use super::types::{Class, Enum, OutputFormatContent, RenderOptions, RenderSetting};
+
+/// How `ctx.output_format(style=...)` describes the output type.
+///
+/// Styles only change how structured types (classes, lists, maps, unions)
+/// are rendered: primitive and enum outputs render the same in every style.
+#[derive(Debug, Default, Clone, Copy, PartialEq, strum::EnumString, strum::VariantNames)]
+pub enum OutputStyle {
+    /// BAML's own type definitions, e.g. `{ name: string, age: int }`.
+    #[default]
+    #[strum(serialize = "baml")]
+    Baml,
+
+    #[strum(serialize = "json_schema")]
+    JsonSchema,
+
+    /// TypeScript interfaces and type aliases.
+    #[strum(serialize = "typescript")]
+    TypeScript,
+
+    /// An example YAML document, answered in YAML.
+    #[strum(serialize = "yaml")]
+    Yaml,
+
+    /// An example XML document, answered in XML tags.
+    #[strum(serialize = "xml")]
+    Xml,
+}
+
+/// A rendered YAML value or XML element content.
+enum Shape {
+    /// Fits on the line of its key, e.g. `string or null`.
+    Inline(String),
+    /// Spans several lines. The note is rendered as a comment next to its key.
+    Block { text: String, note: Option<String> },
+}
+
+fn indent(text: &str) -> String {
+    text.replace('\n', "\n  ")
+}
+
+fn unsupported(message: String) -> minijinja::Error {
+    minijinja::Error::new(minijinja::ErrorKind::BadSerialization, message)
+}
+
+fn primitive_name(t: &TypeValue) -> Result<&'static str, minijinja::Error> {
+    Ok(match t {
+        TypeValue::String => "string",
+        TypeValue::Int => "int",
+        TypeValue::Float => "float",
+        TypeValue::Bool => "bool",
+        TypeValue::Null => "null",
+        TypeValue::Media(media_type) => {
+            return Err(unsupported(format!(
+                "type '{media_type}' is not supported in outputs"
+            )))
+        }
+    })
+}
+
+fn without_constraints(field_type: &FieldType) -> &FieldType {
+    match field_type {
+        FieldType::Constrained { base, .. } => without_constraints(base),
+        _ => field_type,
+    }
+}
+
+impl OutputFormatContent {
+    fn class_or_err(&self, name: &str) -> Result<&Class, minijinja::Error> {
+        self.classes
+            .get(name)
+            .ok_or_else(|| unsupported(format!("Class {name} not found")))
+    }
+
+    fn enum_or_err(&self, name: &str) -> Result<&Enum, minijinja::Error> {
+        self.enums
+            .get(name)
+            .ok_or_else(|| unsupported(format!("Enum {name} not found")))
+    }
+
+    /// Same rule as the BAML style: small enums without descriptions are
+    /// rendered in place, others are hoisted above the schema.
+    fn inline_enum(enm: &Enum, options: &RenderOptions) -> bool {
+        enm.values.len() <= 6
+            && enm.values.iter().all(|(_, d)| d.is_none())
+            && !matches!(options.always_hoist_enums, RenderSetting::Always(true))
+    }
+
+    /// Render the output format in `options.style`, or `None` if the BAML
+    /// renderer should be used.
+    pub(super) fn render_with_style(
+        &self,
+        options: &RenderOptions,
+    ) -> Option<Result<Option<String>, minijinja::Error>> {
+        if options.style == OutputStyle::Baml
+            || matches!(
+                without_constraints(&self.target),
+                FieldType::Primitive(_) | FieldType::Literal(_) | FieldType::Enum(_)
+            )
+        {
+            return None;
+        }
+
+        let rendered = match options.style {
+            OutputStyle::Baml => return None,
+            OutputStyle::JsonSchema => self
+                .render_json_schema()
+                .map(|m| (vec![], m, "Answer in JSON using this JSON schema:\n")),
+            OutputStyle::TypeScript => self
+                .render_typescript(options)
+                .map(|(d, m)| (d, m, "Answer in JSON matching this TypeScript type: ")),
+            OutputStyle::Yaml => self
+                .render_yaml(options)
+                .map(|(d, m)| (d, m, "Answer in YAML using this schema:\n")),
+            OutputStyle::Xml => self
+                .render_xml(options)
+                .map(|(d, m)| (d, m, "Answer in XML using this schema:\n")),
+        };
+
+        Some(rendered.map(|(definitions, message, auto_prefix)| {
+            let mut output = String::new();
+            if !definitions.is_empty() {
+                output.push_str(&definitions.join("\n\n"));
+                output.push_str("\n\n");
+            }
+            match &options.prefix {
+                RenderSetting::Always(prefix) => output.push_str(prefix),
+                RenderSetting::Never => {}
+                RenderSetting::Auto => output.push_str(auto_prefix),
+            }
+            output.push_str(&message);
+            Some(output.trim_end_matches('\n').to_string())
+        }))
+    }
+
+    fn render_json_schema(&self) -> Result<String, minijinja::Error> {
+        let mut defs = IndexMap::new();
+        let mut schema = self.json_schema(&self.target, &mut defs)?;
+        if !defs.is_empty() {
+            schema["$defs"] = json!(defs);
+        }
+        serde_json::to_string_pretty(&schema).map_err(|e| unsupported(e.to_string()))
+    }
+
+    /// Recursive classes are referenced from `$defs`, everything else is
+    /// inlined.
+    fn json_schema(
+        &self,
+        field_type: &FieldType,
+        defs: &mut IndexMap<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, minijinja::Error> {
+        Ok(match field_type {
+            FieldType::Primitive(t) => json!({
+                "type": match t {
+                    TypeValue::Int => "integer",
+                    TypeValue::Float => "number",
+                    TypeValue::Bool => "boolean",
+                    t => primitive_name(t)?,
+                }
+            }),
+            FieldType::Literal(v) => match v {
+                LiteralValue::String(s) => json!({ "const": s }),
+                LiteralValue::Int(i) => json!({ "const": i }),
+                LiteralValue::Bool(b) => json!({ "const": b }),
+            },
+            FieldType::Constrained { base, .. } => self.json_schema(base, defs)?,
+            FieldType::Enum(e) => {
+                let enm = self.enum_or_err(e)?;
+                if enm.values.iter().all(|(_, d)| d.is_none()) {
+                    json!({
+                        "type": "string",
+                        "enum": enm.values.iter().map(|(n, _)| n.rendered_name()).collect::<Vec<_>>(),
+                    })
+                } else {
+                    json!({
+                        "type": "string",
+                        "oneOf": enm.values.iter().map(|(n, d)| match d {
+                            Some(d) => json!({ "const": n.rendered_name(), "description": d }),
+                            None => json!({ "const": n.rendered_name() }),
+                        }).collect::<Vec<_>>(),
+                    })
+                }
+            }
+            FieldType::Class(cls) if self.recursive_classes.contains(cls) => {
+                let class = self.class_or_err(cls)?;
+                let name = class.name.rendered_name().to_string();
+                if !defs.contains_key(&name) {
+                    // Reserve the name first, to stop at the next reference.
+                    defs.insert(name.clone(), serde_json::Value::Null);
+                    let schema = self.class_json_schema(class, defs)?;
+                    defs.insert(name.clone(), schema);
+                }
+                json!({ "$ref": format!("#/$defs/{name}") })
+            }
+            FieldType::Class(cls) => self.class_json_schema(self.class_or_err(cls)?, defs)?,
+            FieldType::List(inner) => json!({
+                "type": "array",
+                "items": self.json_schema(inner, defs)?,
+            }),
+            FieldType::Map(key_type, value_type) => {
+                let mut schema = json!({
+                    "type": "object",
+                    "additionalProperties": self.json_schema(value_type, defs)?,
+                });
+                if !matches!(
+                    without_constraints(key_type),
+                    FieldType::Primitive(TypeValue::String)
+                ) {
+                    schema["propertyNames"] = self.json_schema(key_type, defs)?;
+                }
+                schema
+            }
+            FieldType::Union(items) => json!({
+                "anyOf": items
+                    .iter()
+                    .map(|t| self.json_schema(t, defs))
+                    .collect::<Result<Vec<_>, _>>()?,
+            }),
+            FieldType::Optional(inner) if inner.is_optional() => self.json_schema(inner, defs)?,
+            FieldType::Optional(inner) => json!({
+                "anyOf": [self.json_schema(inner, defs)?, { "type": "null" }],
+            }),
+            FieldType::Tuple(_) => {
+                return Err(unsupported(
+                    "Tuple type is not supported in outputs".to_string(),
+                ))
+            }
+        })
+    }
+
+    fn class_json_schema(
+        &self,
+        class: &Class,
+        defs: &mut IndexMap<String, serde_json::Value>,
+    ) -> Result<serde_json::Value, minijinja::Error> {
+        let mut properties = serde_json::Map::new();
+        let mut required = vec![];
+        for (name, field_type, description) in class.fields.iter() {
+            let mut schema = self.json_schema(field_type, defs)?;
+            if let (Some(description), Some(schema)) = (description, schema.as_object_mut()) {
+                schema.insert("description".to_string(), json!(description));
+            }
+            properties.insert(name.rendered_name().to_string(), schema);
+            if !field_type.is_optional() {
+                required.push(name.rendered_name());
+            }
+        }
+        Ok(json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        }))
+    }
+
+    /// Every class is declared as an interface, so the target is always a
+    /// single-line type expression.
+    fn render_typescript(
+        &self,
+        options: &RenderOptions,
+    ) -> Result<(Vec<String>, String), minijinja::Error> {
+        let mut classes = IndexSet::new();
+        let mut enums = IndexSet::new();
+        let message = self.typescript(&self.target, options, &mut classes, &mut enums)?;
+
+        // Declaring a class can reference more classes.
+        let mut interfaces = vec![];
+        let mut i = 0;
+        while let Some(cls) = classes.get_index(i).cloned() {
+            let class = self.class_or_err(&cls)?;
+            let mut interface = format!("interface {} {{\n", class.name.rendered_name());
+            for (name, field_type, description) in class.fields.iter() {
+                if let Some(description) = description {
+                    interface.push_str(&format!("  // {}\n", description.replace('\n', "\n  // ")));
+                }
+                let r#type = self.typescript(field_type, options, &mut classes, &mut enums)?;
+                interface.push_str(&format!("  {}: {};\n", name.rendered_name(), r#type));
+            }
+            interface.push('}');
+            interfaces.push(interface);
+            i += 1;
+        }
+
+        let mut definitions = enums
+            .iter()
+            .map(|e| -> Result<String, minijinja::Error> {
+                let enm = self.enum_or_err(e)?;
+                let values = enm
+                    .values
+                    .iter()
+                    .map(|(name, description)| match description {
+                        Some(d) => format!(
+                            "  // {}\n  | \"{}\"",
+                            d.replace('\n', "\n  // "),
+                            name.rendered_name()
+                        ),
+                        None => format!("  | \"{}\"", name.rendered_name()),
+                    })
+                    .collect::<Vec<_>>();
+                Ok(format!(
+                    "type {} =\n{};",
+                    enm.name.rendered_name(),
+                    values.join("\n")
+                ))
+            })
+            .collect::<Result<Vec<_>, minijinja::Error>>()?;
+        definitions.extend(interfaces);
+
+        Ok((definitions, message))
+    }
+
+    fn typescript(
+        &self,
+        field_type: &FieldType,
+        options: &RenderOptions,
+        classes: &mut IndexSet<String>,
+        enums: &mut IndexSet<String>,
+    ) -> Result<String, minijinja::Error> {
+        Ok(match field_type {
+            FieldType::Primitive(t) => match t {
+                TypeValue::Int | TypeValue::Float => "number".to_string(),
+                TypeValue::Bool => "boolean".to_string(),
+                t => primitive_name(t)?.to_string(),
+            },
+            FieldType::Literal(v) => v.to_string(),
+            FieldType::Constrained { base, .. } => {
+                self.typescript(base, options, classes, enums)?
+            }
+            FieldType::Enum(e) => {
+                let enm = self.enum_or_err(e)?;
+                if Self::inline_enum(enm, options) {
+                    enm.values
+                        .iter()
+                        .map(|(n, _)| format!("\"{}\"", n.rendered_name()))
+                        .collect::<Vec<_>>()
+                        .join(" | ")
+                } else {
+                    enums.insert(e.clone());
+                    enm.name.rendered_name().to_string()
+                }
+            }
+            FieldType::Class(cls) => {
+                let class = self.class_or_err(cls)?;
+                classes.insert(cls.clone());
+                class.name.rendered_name().to_string()
+            }
+            FieldType::List(inner) => {
+                let inner = self.typescript(inner, options, classes, enums)?;
+                if inner.contains(" | ") {
+                    format!("({inner})[]")
+                } else {
+                    format!("{inner}[]")
+                }
+            }
+            FieldType::Map(key_type, value_type) => format!(
+                "Record<{}, {}>",
+                self.typescript(key_type, options, classes, enums)?,
+                self.typescript(value_type, options, classes, enums)?
+            ),
+            FieldType::Union(items) => items
+                .iter()
+                .map(|t| self.typescript(t, options, classes, enums))
+                .collect::<Result<Vec<_>, _>>()?
+                .join(" | "),
+            FieldType::Optional(inner) if inner.is_optional() => {
+                self.typescript(inner, options, classes, enums)?
+            }
+            FieldType::Optional(inner) => {
+                format!(
+                    "{} | null",
+                    self.typescript(inner, options, classes, enums)?
+                )
+            }
+            FieldType::Tuple(_) => {
+                return Err(unsupported(
+                    "Tuple type is not supported in outputs".to_string(),
+                ))
+            }
+        })
+    }
+
+    /// Hoisted enums, in the same format as the BAML style.
+    fn hoisted_enum_definitions(
+        &self,
+        enums: &IndexSet<String>,
+        options: &RenderOptions,
+    ) -> Result<Vec<String>, minijinja::Error> {
+        enums
+            .iter()
+            .map(|e| -> Result<String, minijinja::Error> {
+                Ok(self.enum_to_string(self.enum_or_err(e)?, options))
+            })
+            .collect()
+    }
+
+    /// Scalars, enums and references to recursive classes, which render the
+    /// same in YAML and XML.
+    fn inline_shape(
+        &self,
+        field_type: &FieldType,
+        options: &RenderOptions,
+        enums: &mut IndexSet<String>,
+    ) -> Result<Option<Shape>, minijinja::Error> {
+        Ok(Some(match field_type {
+            FieldType::Primitive(t) => Shape::Inline(primitive_name(t)?.to_string()),
+            FieldType::Literal(v) => Shape::Inline(v.to_string()),
+            FieldType::Enum(e) => {
+                let enm = self.enum_or_err(e)?;
+                if Self::inline_enum(enm, options) {
+                    Shape::Inline(
+                        enm.values
+                            .iter()
+                            .map(|(n, _)| format!("'{}'", n.rendered_name()))
+                            .collect::<Vec<_>>()
+                            .join(&options.or_splitter),
+                    )
+                } else {
+                    enums.insert(e.clone());
+                    Shape::Inline(enm.name.rendered_name().to_string())
+                }
+            }
+            FieldType::Class(cls) if self.recursive_classes.contains(cls) => {
+                Shape::Inline(self.class_or_err(cls)?.name.rendered_name().to_string())
+            }
+            FieldType::Tuple(_) => {
+                return Err(unsupported(
+                    "Tuple type is not supported in outputs".to_string(),
+                ))
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    /// A union is inline if all its variants are, otherwise its variants are
+    /// listed one after the other, separated by `separator`.
+    fn union_shape(shapes: Vec<Shape>, options: &RenderOptions, separator: &str) -> Shape {
+        if shapes.iter().all(|s| matches!(s, Shape::Inline(_))) {
+            Shape::Inline(
+                shapes
+                    .into_iter()
+                    .filter_map(|s| match s {
+                        Shape::Inline(s) => Some(s),
+                        Shape::Block { .. } => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(&options.or_splitter),
+            )
+        } else {
+            Shape::Block {
+                text: shapes
+                    .into_iter()
+                    .map(|s| match s {
+                        Shape::Inline(s) => s,
+                        Shape::Block { text, .. } => text,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(separator),
+                note: Some("one of these".to_string()),
+            }
+        }
+    }
+
+    fn optional_shape(shape: Shape, options: &RenderOptions, absent: &str) -> Shape {
+        match shape {
+            Shape::Inline(s) => Shape::Inline(format!("{s}{}null", options.or_splitter)),
+            Shape::Block { text, note } => Shape::Block {
+                text,
+                note: Some(match note {
+                    Some(note) => format!("{note}, {absent}"),
+                    None => absent.to_string(),
+                }),
+            },
+        }
+    }
+
+    fn render_yaml(
+        &self,
+        options: &RenderOptions,
+    ) -> Result<(Vec<String>, String), minijinja::Error> {
+        let mut enums = IndexSet::new();
+        let message = match self.yaml(&self.target, options, &mut enums, true)? {
+            Shape::Inline(s) => s,
+            Shape::Block { text, note: None } => text,
+            Shape::Block {
+                text,
+                note: Some(note),
+            } => format!("# {note}\n{text}"),
+        };
+
+        let classes = self
+            .recursive_classes
+            .iter()
+            .map(|cls| -> Result<String, minijinja::Error> {
+                let class = self.class_or_err(cls)?;
+                Ok(format!(
+                    "# {}\n{}",
+                    class.name.rendered_name(),
+                    self.yaml_fields(class, options, &mut enums)?
+                ))
+            })
+            .collect::<Result<Vec<_>, minijinja::Error>>()?;
+
+        let mut definitions = self.hoisted_enum_definitions(&enums, options)?;
+        definitions.extend(classes);
+        Ok((definitions, message))
+    }
+
+    /// `top_level` renders the fields of the target class even if it's
+    /// recursive.
+    fn yaml(
+        &self,
+        field_type: &FieldType,
+        options: &RenderOptions,
+        enums: &mut IndexSet<String>,
+        top_level: bool,
+    ) -> Result<Shape, minijinja::Error> {
+        if !(top_level && matches!(without_constraints(field_type), FieldType::Class(_))) {
+            if let Some(shape) = self.inline_shape(field_type, options, enums)? {
+                return Ok(shape);
+            }
+        }
+        Ok(match field_type {
+            FieldType::Constrained { base, .. } => self.yaml(base, options, enums, top_level)?,
+            FieldType::Class(cls) => Shape::Block {
+                text: self.yaml_fields(self.class_or_err(cls)?, options, enums)?,
+                note: None,
+            },
+            FieldType::List(inner) => Shape::Block {
+                text: match self.yaml(inner, options, enums, false)? {
+                    Shape::Inline(s) => format!("- {s}"),
+                    Shape::Block { text, note: None } => format!("- {}", indent(&text)),
+                    Shape::Block {
+                        text,
+                        note: Some(note),
+                    } => format!("- # {note}\n  {}", indent(&text)),
+                },
+                note: None,
+            },
+            FieldType::Map(key_type, value_type) => {
+                let key = match self.yaml(key_type, options, enums, false)? {
+                    Shape::Inline(s) => s,
+                    Shape::Block { .. } => {
+                        return Err(unsupported(
+                            "Map keys must be strings, enums or literals".to_string(),
+                        ))
+                    }
+                };
+                Shape::Block {
+                    text: Self::yaml_entry(
+                        &format!("<{key}>"),
+                        self.yaml(value_type, options, enums, false)?,
+                    ),
+                    note: None,
+                }
+            }
+            FieldType::Union(items) => Self::union_shape(
+                items
+                    .iter()
+                    .map(|t| self.yaml(t, options, enums, false))
+                    .collect::<Result<_, _>>()?,
+                options,
+                "\n# or\n",
+            ),
+            FieldType::Optional(inner) if inner.is_optional() => {
+                self.yaml(inner, options, enums, false)?
+            }
+            FieldType::Optional(inner) => {
+                Self::optional_shape(self.yaml(inner, options, enums, false)?, options, "or null")
+            }
+            _ => self
+                .inline_shape(field_type, options, enums)?
+                .ok_or_else(|| unsupported(format!("Cannot render {field_type} as YAML")))?,
+        })
+    }
+
+    fn yaml_entry(key: &str, value: Shape) -> String {
+        match value {
+            Shape::Inline(s) => format!("{key}: {s}"),
+            Shape::Block { text, note } => format!(
+                "{key}:{}\n  {}",
+                note.map(|n| format!(" # {n}")).unwrap_or_default(),
+                indent(&text)
+            ),
+        }
+    }
+
+    fn yaml_fields(
+        &self,
+        class: &Class,
+        options: &RenderOptions,
+        enums: &mut IndexSet<String>,
+    ) -> Result<String, minijinja::Error> {
+        let mut lines = vec![];
+        for (name, field_type, description) in class.fields.iter() {
+            if let Some(description) = description {
+                lines.push(format!("# {}", description.replace('\n', "\n# ")));
+            }
+            let value = self.yaml(field_type, options, enums, false)?;
+            lines.push(Self::yaml_entry(name.rendered_name(), value));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    fn render_xml(
+        &self,
+        options: &RenderOptions,
+    ) -> Result<(Vec<String>, String), minijinja::Error> {
+        let mut enums = IndexSet::new();
+        let root = match without_constraints(&self.target) {
+            FieldType::Class(cls) => self.class_or_err(cls)?.name.rendered_name(),
+            _ => "answer",
+        };
+        let message = Self::xml_element(root, self.xml(&self.target, options, &mut enums, true)?);
+
+        let classes = self
+            .recursive_classes
+            .iter()
+            .map(|cls| -> Result<String, minijinja::Error> {
+                let class = self.class_or_err(cls)?;
+                Ok(Self::xml_element(
+                    class.name.rendered_name(),
+                    Shape::Block {
+                        text: self.xml_fields(class, options, &mut enums)?,
+                        note: None,
+                    },
+                ))
+            })
+            .collect::<Result<Vec<_>, minijinja::Error>>()?;
+
+        let mut definitions = self.hoisted_enum_definitions(&enums, options)?;
+        definitions.extend(classes);
+        Ok((definitions, message))
+    }
+
+    /// `top_level` renders the fields of the target class even if it's
+    /// recursive.
+    fn xml(
+        &self,
+        field_type: &FieldType,
+        options: &RenderOptions,
+        enums: &mut IndexSet<String>,
+        top_level: bool,
+    ) -> Result<Shape, minijinja::Error> {
+        if !(top_level && matches!(without_constraints(field_type), FieldType::Class(_))) {
+            if let Some(shape) = self.inline_shape(field_type, options, enums)? {
+                return Ok(shape);
+            }
+        }
+        Ok(match field_type {
+            FieldType::Constrained { base, .. } => self.xml(base, options, enums, top_level)?,
+            FieldType::Class(cls) => Shape::Block {
+                text: self.xml_fields(self.class_or_err(cls)?, options, enums)?,
+                note: None,
+            },
+            FieldType::List(inner) => Shape::Block {
+                text: Self::xml_element("item", self.xml(inner, options, enums, false)?),
+                note: Some("one <item> per element".to_string()),
+            },
+            FieldType::Map(key_type, value_type) => {
+                let key = match self.xml(key_type, options, enums, false)? {
+                    Shape::Inline(s) => s,
+                    Shape::Block { .. } => {
+                        return Err(unsupported(
+                            "Map keys must be strings, enums or literals".to_string(),
+                        ))
+                    }
+                };
+                let value =
+                    Self::xml_element("entry", self.xml(value_type, options, enums, false)?);
+                Shape::Block {
+                    text: value.replacen("<entry>", &format!("<entry key=\"{key}\">"), 1),
+                    note: Some("one <entry> per key".to_string()),
+                }
+            }
+            FieldType::Union(items) => Self::union_shape(
+                items
+                    .iter()
+                    .map(|t| self.xml(t, options, enums, false))
+                    .collect::<Result<_, _>>()?,
+                options,
+                "\n<!-- or -->\n",
+            ),
+            FieldType::Optional(inner) if inner.is_optional() => {
+                self.xml(inner, options, enums, false)?
+            }
+            FieldType::Optional(inner) => Self::optional_shape(
+                self.xml(inner, options, enums, false)?,
+                options,
+                "may be omitted",
+            ),
+            _ => self
+                .inline_shape(field_type, options, enums)?
+                .ok_or_else(|| unsupported(format!("Cannot render {field_type} as XML")))?,
+        })
+    }
+
+    fn xml_element(tag: &str, content: Shape) -> String {
+        match content {
+            Shape::Inline(s) => format!("<{tag}>{s}</{tag}>"),
+            Shape::Block { text, note } => format!(
+                "<{tag}>{}\n  {}\n</{tag}>",
+                note.map(|n| format!("<!-- {n} -->")).unwrap_or_default(),
+                indent(&text)
+            ),
+        }
+    }
+
+    fn xml_fields(
+        &self,
+        class: &Class,
+        options: &RenderOptions,
+        enums: &mut IndexSet<String>,
+    ) -> Result<String, minijinja::Error> {
+        let mut lines = vec![];
+        for (name, field_type, description) in class.fields.iter() {
+            if let Some(description) = description {
+                lines.push(format!("<!-- {} -->", description.replace('\n', "\n     ")));
+            }
+            let value = self.xml(field_type, options, enums, false)?;
+            lines.push(Self::xml_element(name.rendered_name(), value));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexSet;
+
+    use super::*;
+    use crate::output_format::types::Name;
+
+    fn render(content: &OutputFormatContent, style: OutputStyle) -> String {
+        content
+            .render(RenderOptions::new(
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(style),
+            ))
+            .unwrap()
+            .unwrap()
+    }
+
+    fn person() -> OutputFormatContent {
+        let enums = vec![Enum {
+            name: Name::new("Hobby".to_string()),
+            values: vec![
+                (Name::new("SPORTS".to_string()), None),
+                (Name::new("MUSIC".to_string()), None),
+            ],
+            constraints: Vec::new(),
+        }];
+        let classes = vec![Class {
+            name: Name::new("Person".to_string()),
+            fields: vec![
+                (
+                    Name::new("name".to_string()),
+                    FieldType::string(),
+                    Some("The person's name".to_string()),
+                ),
+                (Name::new("age".to_string()), FieldType::int(), None),
+                (
+                    Name::new("hobbies".to_string()),
+                    FieldType::r#enum("Hobby").as_list(),
+                    None,
+                ),
+                (
+                    Name::new_with_alias("nickname".to_string(), Some("nick".to_string())),
+                    FieldType::string().as_optional(),
+                    None,
+                ),
+            ],
+            constraints: Vec::new(),
+            streaming_behavior: Default::default(),
+        }];
+
+        OutputFormatContent::target(FieldType::class("Person"))
+            .enums(enums)
+            .classes(classes)
+            .build()
+    }
+
+    fn linked_list() -> OutputFormatContent {
+        let classes = vec![Class {
+            name: Name::new("Node".to_string()),
+            fields: vec![
+                (Name::new("data".to_string()), FieldType::int(), None),
+                (
+                    Name::new("next".to_string()),
+                    FieldType::class("Node").as_optional(),
+                    None,
+                ),
+            ],
+            constraints: Vec::new(),
+            streaming_behavior: Default::default(),
+        }];
+
+        OutputFormatContent::target(FieldType::class("Node"))
+            .classes(classes)
+            .recursive_classes(IndexSet::from_iter(["Node".to_string()]))
+            .build()
+    }
+
+    #[test]
+    fn render_json_schema() {
+        let rendered = render(&person(), OutputStyle::JsonSchema);
+        let schema = rendered
+            .strip_prefix("Answer in JSON using this JSON schema:\n")
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(schema).unwrap(),
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "The person's name" },
+                    "age": { "type": "integer" },
+                    "hobbies": {
+                        "type": "array",
+                        "items": { "type": "string", "enum": ["SPORTS", "MUSIC"] }
+                    },
+                    "nick": { "anyOf": [{ "type": "string" }, { "type": "null" }] }
+                },
+                "required": ["name", "age", "hobbies"],
+                "additionalProperties": false
+            })
+        );
+    }
+
+    #[test]
+    fn render_recursive_json_schema() {
+        let rendered = render(&linked_list(), OutputStyle::JsonSchema);
+        let schema = rendered
+            .strip_prefix("Answer in JSON using this JSON schema:\n")
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(schema).unwrap(),
+            json!({
+                "$ref": "#/$defs/Node",
+                "$defs": {
+                    "Node": {
+                        "type": "object",
+                        "properties": {
+                            "data": { "type": "integer" },
+                            "next": { "anyOf": [{ "$ref": "#/$defs/Node" }, { "type": "null" }] }
+                        },
+                        "required": ["data"],
+                        "additionalProperties": false
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn render_typescript() {
+        #[rustfmt::skip]
+        assert_eq!(
+            render(&person(), OutputStyle::TypeScript),
+r#"interface Person {
+  // The person's name
+  name: string;
+  age: number;
+  hobbies: ("SPORTS" | "MUSIC")[];
+  nick: string | null;
+}
+
+Answer in JSON matching this TypeScript type: Person"#
+        );
+
+        #[rustfmt::skip]
+        assert_eq!(
+            render(&linked_list(), OutputStyle::TypeScript),
+r#"interface Node {
+  data: number;
+  next: Node | null;
+}
+
+Answer in JSON matching this TypeScript type: Node"#
+        );
+    }
+
+    #[test]
+    fn render_yaml() {
+        #[rustfmt::skip]
+        assert_eq!(
+            render(&person(), OutputStyle::Yaml),
+r#"Answer in YAML using this schema:
+# The person's name
+name: string
+age: int
+hobbies:
+  - 'SPORTS' or 'MUSIC'
+nick: string or null"#
+        );
+    }
+
+    #[test]
+    fn render_xml() {
+        #[rustfmt::skip]
+        assert_eq!(
+            render(&person(), OutputStyle::Xml),
+r#"Answer in XML using this schema:
+<Person>
+  <!-- The person's name -->
+  <name>string</name>
+  <age>int</age>
+  <hobbies><!-- one <item> per element -->
+    <item>'SPORTS' or 'MUSIC'</item>
+  </hobbies>
+  <nick>string or null</nick>
+</Person>"#
+        );
+    }
+
+    #[test]
+    fn render_primitive_ignores_style() {
+        let content = OutputFormatContent::target(FieldType::int()).build();
+        assert_eq!(render(&content, OutputStyle::Xml), "Answer as an int");
+    }
+}
@@ -1,9 +1,11 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use baml_types::{Constraint, FieldType, TypeValue};
+use baml_types::{Constraint, FieldType, StreamingBehavior, TypeValue};
 use indexmap::{IndexMap, IndexSet};
 
+use super::style::OutputStyle;
+
 #[derive(Debug)]
 pub struct Name {
     name: String,
@@ -51,13 +53,32 @@ pub struct Class {
     // fields have name, type and description.
     pub fields: Vec<(Name, FieldType, Option<String>)>,
     pub constraints: Vec<Constraint>,
+    pub streaming_behavior: ClassStreamingBehavior,
+}
+
+/// The `@@stream.*` attributes of a class and the `@stream.*` attributes of
+/// its fields, keyed by the real (non-aliased) field name.
+#[derive(Debug, Default)]
+pub struct ClassStreamingBehavior {
+    pub class: StreamingBehavior,
+    pub fields: IndexMap<String, StreamingBehavior>,
+}
+
+impl ClassStreamingBehavior {
+    pub fn is_default(&self) -> bool {
+        self.class.is_default() && self.fields.values().all(StreamingBehavior::is_default)
+    }
+
+    pub fn field(&self, name: &str) -> StreamingBehavior {
+        self.fields.get(name).copied().unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct OutputFormatContent {
     pub enums: Arc<IndexMap<String, Enum>>,
     pub classes: Arc<IndexMap<String, Class>>,
-    recursive_classes: Arc<IndexSet<String>>,
+    pub(super) recursive_classes: Arc<IndexSet<String>>,
     pub target: FieldType,
 }
 
@@ -120,7 +141,7 @@ impl Builder {
     }
 }
 
-enum RenderSetting<T> {
+pub(crate) enum RenderSetting<T> {
     Auto,
     Always(T),
     Never,
@@ -142,12 +163,13 @@ pub(crate) enum MapStyle {
 }
 
 pub(crate) struct RenderOptions {
-    prefix: RenderSetting<String>,
+    pub(crate) prefix: RenderSetting<String>,
     pub(crate) or_splitter: String,
     enum_value_prefix: RenderSetting<String>,
     hoisted_class_prefix: RenderSetting<String>,
-    always_hoist_enums: RenderSetting<bool>,
+    pub(crate) always_hoist_enums: RenderSetting<bool>,
     map_style: MapStyle,
+    pub(crate) style: OutputStyle,
 }
 
 impl Default for RenderOptions {
@@ -159,6 +181,7 @@ impl Default for RenderOptions {
             hoisted_class_prefix: RenderSetting::Auto,
             always_hoist_enums: RenderSetting::Auto,
             map_style: MapStyle::TypeParameters,
+            style: OutputStyle::default(),
         }
     }
 }
@@ -174,6 +197,7 @@ impl RenderOptions {
         always_hoist_enums: Option<bool>,
         map_style: Option<MapStyle>,
         hoisted_class_prefix: Option<Option<String>>,
+        style: Option<OutputStyle>,
     ) -> Self {
         Self {
             prefix: prefix.map_or(RenderSetting::Auto, |p| {
@@ -189,6 +213,7 @@ impl RenderOptions {
             hoisted_class_prefix: hoisted_class_prefix.map_or(RenderSetting::Auto, |p| {
                 p.map_or(RenderSetting::Never, RenderSetting::Always)
             }),
+            style: style.unwrap_or_default(),
         }
     }
 
@@ -355,7 +380,7 @@ impl OutputFormatContent {
         }
     }
 
-    fn enum_to_string(&self, enm: &Enum, options: &RenderOptions) -> String {
+    pub(super) fn enum_to_string(&self, enm: &Enum, options: &RenderOptions) -> String {
         EnumRender {
             name: enm.name.rendered_name().to_string(),
             delimiter: "----".into(),
@@ -545,6 +570,10 @@ impl OutputFormatContent {
         &self,
         options: RenderOptions,
     ) -> Result<Option<String>, minijinja::Error> {
+        if let Some(rendered) = self.render_with_style(&options) {
+            return rendered;
+        }
+
         let prefix = self.prefix(&options);
 
         let mut render_state = RenderState {
@@ -737,6 +766,7 @@ mod tests {
                 ),
             ],
             constraints: Vec::new(),
+            streaming_behavior: Default::default(),
         }];
 
         let content = OutputFormatContent::target(FieldType::class("Person"))
@@ -769,6 +799,7 @@ mod tests {
                 (Name::new("year".to_string()), FieldType::int(), None),
             ],
             constraints: Vec::new(),
+            streaming_behavior: Default::default(),
         }];
 
         let content = OutputFormatContent::target(FieldType::class("Education"))
@@ -797,6 +828,7 @@ mod tests {
                     (Name::new("severity".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Enhancement".to_string()),
@@ -809,6 +841,7 @@ mod tests {
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Documentation".to_string()),
@@ -817,6 +850,7 @@ mod tests {
                     (Name::new("format".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -865,6 +899,7 @@ r#"Answer in JSON using any of these schemas:
                     (Name::new("date".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Bug".to_string()),
@@ -877,6 +912,7 @@ r#"Answer in JSON using any of these schemas:
                     (Name::new("severity".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Enhancement".to_string()),
@@ -889,6 +925,7 @@ r#"Answer in JSON using any of these schemas:
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Documentation".to_string()),
@@ -897,6 +934,7 @@ r#"Answer in JSON using any of these schemas:
                     (Name::new("format".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -939,6 +977,7 @@ r#"Answer in JSON using this schema:
                 ),
             ],
             constraints: Vec::new(),
+            streaming_behavior: Default::default(),
         }];
 
         let content = OutputFormatContent::target(FieldType::class("Node"))
@@ -974,6 +1013,7 @@ Answer in JSON using this schema: Node"#
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("LinkedList".to_string()),
@@ -986,6 +1026,7 @@ Answer in JSON using this schema: Node"#
                     (Name::new("len".to_string()), FieldType::int(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1023,6 +1064,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("B".to_string()),
@@ -1032,6 +1074,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("C".to_string()),
@@ -1041,6 +1084,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1083,6 +1127,7 @@ Answer in JSON using this schema: A"#
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("B".to_string()),
@@ -1092,6 +1137,7 @@ Answer in JSON using this schema: A"#
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("C".to_string()),
@@ -1101,6 +1147,7 @@ Answer in JSON using this schema: A"#
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1114,6 +1161,7 @@ Answer in JSON using this schema: A"#
                     (Name::new("field".to_string()), FieldType::bool(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1168,6 +1216,7 @@ Answer in JSON using this schema:
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("B".to_string()),
@@ -1177,6 +1226,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("C".to_string()),
@@ -1186,6 +1236,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1199,6 +1250,7 @@ Answer in JSON using this schema:
                     (Name::new("field".to_string()), FieldType::bool(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Nested".to_string()),
@@ -1207,6 +1259,7 @@ Answer in JSON using this schema:
                     (Name::new("field".to_string()), FieldType::bool(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1261,6 +1314,7 @@ Answer in JSON using this schema:
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Forest".to_string()),
@@ -1270,6 +1324,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1312,6 +1367,7 @@ Answer in JSON using this schema: Tree"#
                 None,
             )],
             constraints: Vec::new(),
+            streaming_behavior: Default::default(),
         }];
 
         let content = OutputFormatContent::target(FieldType::class("SelfReferential"))
@@ -1348,6 +1404,7 @@ Answer in JSON using this schema: SelfReferential"#
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
@@ -1360,6 +1417,7 @@ Answer in JSON using this schema: SelfReferential"#
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1412,6 +1470,7 @@ Node or Tree"#
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Node".to_string()),
@@ -1424,6 +1483,7 @@ Node or Tree"#
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
@@ -1436,6 +1496,7 @@ Node or Tree"#
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1484,6 +1545,7 @@ Answer in JSON using this schema:
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
@@ -1496,6 +1558,7 @@ Answer in JSON using this schema:
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1504,6 +1567,7 @@ Answer in JSON using this schema:
                     (Name::new("tag".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1564,6 +1628,7 @@ Node or Tree or {
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Node".to_string()),
@@ -1576,6 +1641,7 @@ Node or Tree or {
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
@@ -1588,6 +1654,7 @@ Node or Tree or {
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1596,6 +1663,7 @@ Node or Tree or {
                     (Name::new("tag".to_string()), FieldType::string(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1644,6 +1712,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("B".to_string()),
@@ -1653,6 +1722,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("C".to_string()),
@@ -1662,6 +1732,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1675,6 +1746,7 @@ Answer in JSON using this schema:
                     (Name::new("field".to_string()), FieldType::bool(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1727,6 +1799,7 @@ Answer in JSON using this interface:
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
@@ -1739,6 +1812,7 @@ Answer in JSON using this interface:
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1786,6 +1860,7 @@ Node or int or string or Tree"#
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Tree".to_string()),
@@ -1798,6 +1873,7 @@ Node or int or string or Tree"#
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1814,6 +1890,7 @@ Node or int or string or Tree"#
                     (Name::new("field".to_string()), FieldType::bool(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1861,6 +1938,7 @@ Answer in JSON using this schema:
                 ),
             ],
             constraints: Vec::new(),
+            streaming_behavior: Default::default(),
         }];
 
         let content = OutputFormatContent::target(FieldType::list(FieldType::class("Node")))
@@ -1893,6 +1971,7 @@ Node[]"#
                 None,
             )],
             constraints: Vec::new(),
+            streaming_behavior: Default::default(),
         }];
 
         let content = OutputFormatContent::target(FieldType::class("RecursiveMap"))
@@ -1924,6 +2003,7 @@ Answer in JSON using this schema: RecursiveMap"#
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -1933,6 +2013,7 @@ Answer in JSON using this schema: RecursiveMap"#
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -1970,6 +2051,7 @@ Answer in JSON using this schema:
                 ),
             ],
             constraints: Vec::new(),
+            streaming_behavior: Default::default(),
         }];
 
         let content = OutputFormatContent::target(FieldType::map(
@@ -2006,6 +2088,7 @@ map<string, Node>"#
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Node".to_string()),
@@ -2018,6 +2101,7 @@ map<string, Node>"#
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -2057,6 +2141,7 @@ Answer in JSON using this schema:
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Node".to_string()),
@@ -2069,6 +2154,7 @@ Answer in JSON using this schema:
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -2108,6 +2194,7 @@ Answer in JSON using this schema:
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -2116,6 +2203,7 @@ Answer in JSON using this schema:
                     (Name::new("data".to_string()), FieldType::int(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
@@ -2167,6 +2255,7 @@ map<string, Node or int or {
                     None,
                 )],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("Node".to_string()),
@@ -2179,6 +2268,7 @@ map<string, Node or int or {
                     ),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
             Class {
                 name: Name::new("NonRecursive".to_string()),
@@ -2187,6 +2277,7 @@ map<string, Node or int or {
                     (Name::new("data".to_string()), FieldType::int(), None),
                 ],
                 constraints: Vec::new(),
+                streaming_behavior: Default::default(),
             },
         ];
 
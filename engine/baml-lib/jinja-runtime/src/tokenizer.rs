@@ -0,0 +1,249 @@
+#[cfg(feature = "tiktoken")]
+use std::sync::OnceLock;
+
+use serde::Serialize;
+#[cfg(feature = "tiktoken")]
+use tiktoken_rs::CoreBPE;
+
+use crate::{ChatMessagePart, RenderedPrompt};
+
+/// Counts the tokens of a prompt the way the client's model would.
+///
+/// OpenAI models use their BPE encoding, which is bundled with the runtime by
+/// the `tiktoken` feature. Without it (e.g. in wasm, where the tables would
+/// bloat the bundle), and for other providers, which don't publish their
+/// tokenizers, counts are estimated from the number of characters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tokenizer {
+    /// GPT-4o, o1 and newer OpenAI models.
+    O200kBase,
+    /// GPT-4, GPT-3.5 and the OpenAI embedding models.
+    Cl100kBase,
+    /// An estimate for models whose tokenizer isn't bundled.
+    Approximate { chars_per_token: f32 },
+}
+
+/// Tokens every chat message costs on top of its content, for the role and
+/// the delimiters around it.
+const TOKENS_PER_MESSAGE: usize = 3;
+/// Tokens every chat prompt costs for priming the assistant's reply.
+const TOKENS_PER_CHAT: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenEstimate {
+    /// The number of tokens in the prompt. Media parts are not counted.
+    pub tokens: usize,
+    /// The tokenizer the count comes from, e.g. `o200k_base`.
+    pub tokenizer: &'static str,
+    /// Whether the count is exact, or estimated from the prompt's length.
+    pub exact: bool,
+}
+
+impl Tokenizer {
+    /// The tokenizer of a client, from its provider and `model` option.
+    pub fn for_client(provider: &str, model: Option<&str>) -> Tokenizer {
+        let model = model.unwrap_or_default();
+        let is_openai = matches!(provider, "openai" | "azure-openai")
+            || (provider == "openai-generic" && model.starts_with("gpt-"));
+
+        if is_openai {
+            let legacy = ["gpt-4-", "gpt-3.5", "text-embedding-"]
+                .iter()
+                .any(|prefix| model.starts_with(prefix));
+            return if model == "gpt-4" || legacy {
+                Tokenizer::Cl100kBase
+            } else {
+                Tokenizer::O200kBase
+            };
+        }
+
+        // Claude's tokenizer splits text into slightly shorter tokens than
+        // the usual estimate of four characters per token.
+        let is_claude = provider == "anthropic" || model.contains("claude");
+        Tokenizer::Approximate {
+            chars_per_token: if is_claude { 3.5 } else { 4.0 },
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tokenizer::O200kBase => "o200k_base",
+            Tokenizer::Cl100kBase => "cl100k_base",
+            Tokenizer::Approximate { .. } => "approximate",
+        }
+    }
+
+    #[cfg(feature = "tiktoken")]
+    fn bpe(&self) -> Option<&'static CoreBPE> {
+        static O200K_BASE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+        static CL100K_BASE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+
+        match self {
+            Tokenizer::O200kBase => O200K_BASE
+                .get_or_init(|| tiktoken_rs::o200k_base().ok())
+                .as_ref(),
+            Tokenizer::Cl100kBase => CL100K_BASE
+                .get_or_init(|| tiktoken_rs::cl100k_base().ok())
+                .as_ref(),
+            Tokenizer::Approximate { .. } => None,
+        }
+    }
+
+    /// Whether counts come from the model's own encoding.
+    #[cfg(feature = "tiktoken")]
+    fn is_exact(&self) -> bool {
+        self.bpe().is_some()
+    }
+
+    #[cfg(not(feature = "tiktoken"))]
+    fn is_exact(&self) -> bool {
+        false
+    }
+
+    fn chars_per_token(&self) -> f32 {
+        match self {
+            Tokenizer::Approximate { chars_per_token } => *chars_per_token,
+            // Only used if the encoding isn't bundled or fails to load.
+            _ => 4.0,
+        }
+    }
+
+    /// The number of tokens in `text`.
+    pub fn count_tokens(&self, text: &str) -> usize {
+        #[cfg(feature = "tiktoken")]
+        if let Some(bpe) = self.bpe() {
+            return bpe.encode_ordinary(text).len();
+        }
+        (text.chars().count() as f32 / self.chars_per_token()).ceil() as usize
+    }
+
+    /// The longest prefix of `text` that is at most `max_tokens` tokens.
+    pub fn truncate<'a>(&self, text: &'a str, max_tokens: usize) -> &'a str {
+        #[cfg(feature = "tiktoken")]
+        if let Some(bpe) = self.bpe() {
+            return Self::truncate_bpe(bpe, text, max_tokens);
+        }
+        let max_chars = (max_tokens as f32 * self.chars_per_token()).floor() as usize;
+        match text.char_indices().nth(max_chars) {
+            Some((end, _)) => &text[..end],
+            None => text,
+        }
+    }
+
+    #[cfg(feature = "tiktoken")]
+    fn truncate_bpe<'a>(bpe: &CoreBPE, text: &'a str, max_tokens: usize) -> &'a str {
+        let tokens = bpe.encode_ordinary(text);
+        if tokens.len() <= max_tokens {
+            return text;
+        }
+        // A token may end in the middle of a character, in which case the
+        // prefix doesn't decode; drop tokens until it does.
+        (0..=max_tokens)
+            .rev()
+            .find_map(|len| bpe.decode(tokens[..len].to_vec()).ok())
+            .map(|prefix| &text[..prefix.len()])
+            .unwrap_or_default()
+    }
+
+    /// The number of tokens the prompt will be sent as.
+    pub fn estimate(&self, prompt: &RenderedPrompt) -> TokenEstimate {
+        let tokens = match prompt {
+            RenderedPrompt::Completion(text) => self.count_tokens(text),
+            RenderedPrompt::Chat(messages) => {
+                TOKENS_PER_CHAT
+                    + messages
+                        .iter()
+                        .map(|message| {
+                            TOKENS_PER_MESSAGE
+                                + self.count_tokens(&message.role)
+                                + message
+                                    .parts
+                                    .iter()
+                                    .map(|part| self.count_part_tokens(part))
+                                    .sum::<usize>()
+                        })
+                        .sum::<usize>()
+            }
+        };
+
+        TokenEstimate {
+            tokens,
+            tokenizer: self.name(),
+            exact: self.is_exact(),
+        }
+    }
+
+    fn count_part_tokens(&self, part: &ChatMessagePart) -> usize {
+        match part {
+            ChatMessagePart::Text(text) => self.count_tokens(text),
+            ChatMessagePart::Media(_) => 0,
+            ChatMessagePart::WithMeta(part, _) => self.count_part_tokens(part),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_client() {
+        assert_eq!(
+            Tokenizer::for_client("openai", Some("gpt-4o-mini")),
+            Tokenizer::O200kBase
+        );
+        assert_eq!(
+            Tokenizer::for_client("azure-openai", Some("gpt-4")),
+            Tokenizer::Cl100kBase
+        );
+        assert_eq!(
+            Tokenizer::for_client("openai", Some("gpt-3.5-turbo")),
+            Tokenizer::Cl100kBase
+        );
+        assert_eq!(
+            Tokenizer::for_client("anthropic", Some("claude-3-5-sonnet-latest")),
+            Tokenizer::Approximate {
+                chars_per_token: 3.5
+            }
+        );
+        assert_eq!(
+            Tokenizer::for_client("openai-generic", Some("llama3")),
+            Tokenizer::Approximate {
+                chars_per_token: 4.0
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tiktoken")]
+    fn test_count_and_truncate_bpe() {
+        let tokenizer = Tokenizer::O200kBase;
+        let text = "The quick brown fox jumps over the lazy dog.";
+        let count = tokenizer.count_tokens(text);
+        assert!(count > 5 && count < text.len(), "count: {count}");
+
+        let truncated = tokenizer.truncate(text, 3);
+        assert!(text.starts_with(truncated));
+        assert_eq!(tokenizer.count_tokens(truncated), 3);
+        assert_eq!(tokenizer.truncate(text, 1000), text);
+    }
+
+    #[test]
+    #[cfg(not(feature = "tiktoken"))]
+    fn test_openai_counts_are_estimated_without_tiktoken() {
+        let estimate =
+            Tokenizer::O200kBase.estimate(&RenderedPrompt::Completion("abcdefghijkl".to_string()));
+        assert_eq!(estimate.tokens, 3);
+        assert!(!estimate.exact);
+    }
+
+    #[test]
+    fn test_count_and_truncate() {
+        let tokenizer = Tokenizer::Approximate {
+            chars_per_token: 4.0,
+        };
+        assert_eq!(tokenizer.count_tokens("abcdefghi"), 3);
+        assert_eq!(tokenizer.truncate("abcdefghi", 2), "abcdefgh");
+        assert_eq!(tokenizer.truncate("héllo wörld", 1), "héll");
+    }
+}
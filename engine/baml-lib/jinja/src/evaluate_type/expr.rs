@@ -6,7 +6,7 @@ use std::str::FromStr;
 
 use super::{
     pretty_print::pretty_print,
-    types::{PredefinedTypes, Type},
+    types::{check_args, PredefinedTypes, Type},
     ScopeTracker, TypeError,
 };
 
@@ -18,23 +18,7 @@ fn parse_as_function_call(
 ) -> (Type, Vec<TypeError>) {
     match t {
         Type::FunctionRef(name) => {
-            let mut positional_args = Vec::new();
-            let mut kwargs = HashMap::new();
-            for arg in &expr.args {
-                match arg {
-                    ast::Expr::Kwargs(kkwargs) => {
-                        for (k, v) in &kkwargs.pairs {
-                            let t = tracker_visit_expr(v, state, types);
-                            kwargs.insert(*k, t);
-                        }
-                    }
-                    _ => {
-                        let t = tracker_visit_expr(arg, state, types);
-                        positional_args.push(t);
-                    }
-                }
-            }
-
+            let (positional_args, kwargs) = visit_args(&expr.args, state, types);
             types.check_function_args((name, expr), &positional_args, &kwargs)
         }
         Type::Both(x, y) => {
@@ -88,6 +72,135 @@ fn parse_as_function_call(
     }
 }
 
+/// The types of the positional and keyword arguments of a call or filter.
+fn visit_args<'a>(
+    args: &[ast::Expr<'a>],
+    state: &mut ScopeTracker,
+    types: &PredefinedTypes,
+) -> (Vec<Type>, HashMap<&'a str, Type>) {
+    let mut positional_args = Vec::new();
+    let mut kwargs = HashMap::new();
+    for arg in args {
+        match arg {
+            ast::Expr::Kwargs(kkwargs) => {
+                for (k, v) in &kkwargs.pairs {
+                    let t = tracker_visit_expr(v, state, types);
+                    kwargs.insert(*k, t);
+                }
+            }
+            _ => {
+                let t = tracker_visit_expr(arg, state, types);
+                positional_args.push(t);
+            }
+        }
+    }
+    (positional_args, kwargs)
+}
+
+/// The type of one of the filters BAML adds to minijinja's (see
+/// `jinja_helpers::get_env` in baml-core), checking its input and arguments.
+/// Returns `None` for any other filter.
+fn helper_filter_type(
+    expr: &ast::Spanned<ast::Filter>,
+    inner: &Type,
+    state: &mut ScopeTracker,
+    types: &PredefinedTypes,
+) -> Option<Type> {
+    let optional = |t: Type| Type::Union(vec![t, Type::None]);
+    let any_list = Type::List(Box::new(Type::Unknown));
+    let item = match inner {
+        Type::List(t) => *t.clone(),
+        _ => Type::Unknown,
+    };
+
+    let (input, params, ret) = match expr.name {
+        "format" => (
+            Type::Union(vec![Type::Number, Type::String]),
+            vec![("spec", Type::String)],
+            Type::String,
+        ),
+        "to_json" | "to_yaml" => (
+            Type::Unknown,
+            vec![("indent", optional(Type::Int))],
+            Type::String,
+        ),
+        "dedent" => (Type::String, vec![], Type::String),
+        "truncate_words" => (
+            Type::String,
+            vec![("count", Type::Int), ("end", optional(Type::String))],
+            Type::String,
+        ),
+        "similarity" => (Type::String, vec![("other", Type::String)], Type::Float),
+        // minijinja's own, typed here as it keeps the type of the items.
+        "unique" => (
+            any_list.clone(),
+            vec![
+                ("case_sensitive", optional(Type::Bool)),
+                ("attribute", optional(Type::String)),
+            ],
+            Type::List(Box::new(item.clone())),
+        ),
+        "enumerate" => (
+            any_list.clone(),
+            vec![("start", optional(Type::Int))],
+            Type::List(Box::new(Type::Tuple(vec![Type::Int, item.clone()]))),
+        ),
+        "zip" => {
+            // Takes any number of lists, so its arguments are checked here.
+            let (others, kwargs) = visit_args(&expr.args, state, types);
+            if others.is_empty() || !kwargs.is_empty() {
+                state.errors.push(TypeError {
+                    message: "Filter 'zip' expects one or more lists to zip with".to_string(),
+                    span: expr.span(),
+                });
+            }
+            let mut items = vec![item];
+            for (i, other) in others.into_iter().enumerate() {
+                if !other.is_subtype_of(&any_list) {
+                    state.errors.push(TypeError::new_wrong_arg_type(
+                        "zip",
+                        expr.span(),
+                        &format!("list {}", i + 1),
+                        expr.span(),
+                        any_list.clone(),
+                        other.clone(),
+                    ));
+                }
+                items.push(match other {
+                    Type::List(t) => *t,
+                    _ => Type::Unknown,
+                });
+            }
+            (any_list, vec![], Type::List(Box::new(Type::Tuple(items))))
+        }
+        _ => return None,
+    };
+
+    if !inner.is_subtype_of(&input) {
+        state.errors.push(TypeError::new_invalid_type(
+            expr.expr.as_ref().unwrap(),
+            inner,
+            &input.name(),
+            expr.span(),
+        ));
+    }
+    if expr.name != "zip" {
+        let (positional_args, kwargs) = visit_args(&expr.args, state, types);
+        let params = params
+            .into_iter()
+            .map(|(name, t)| (name.to_string(), t))
+            .collect::<Vec<_>>();
+        state.errors.extend(check_args(
+            expr.name,
+            expr.span(),
+            &params,
+            &positional_args,
+            &kwargs,
+        ));
+    }
+    Some(ret)
+}
+
 fn tracker_visit_expr(
     expr: &ast::Expr<'_>,
     state: &mut ScopeTracker,
@@ -162,6 +275,9 @@ fn tracker_visit_expr(
         ast::Expr::Filter(expr) => {
             // Filters have a name
             let inner = tracker_visit_expr(expr.expr.as_ref().unwrap(), state, types);
+            if let Some(t) = helper_filter_type(expr, &inner, state, types) {
+                return t;
+            }
 
             let mut ensure_type = |error_string: &str| {
                 state.errors.push(TypeError::new_invalid_type(
@@ -181,8 +297,11 @@ fn tracker_visit_expr(
                 "escape",
                 "first",
                 "last",
+                "dedent",
                 "default",
+                "enumerate",
                 "float",
+                "format",
                 "indent",
                 "int",
                 "dictsort",
@@ -205,16 +324,23 @@ fn tracker_visit_expr(
                 "safe",
                 "select",
                 "selectattr",
+                "similarity",
                 "slice",
                 "sort",
                 "split",
                 "sum",
                 "title",
+                "to_json",
+                "to_yaml",
+                "token_count",
                 "tojson",
                 "json",
                 "trim",
+                "truncate_tokens",
+                "truncate_words",
                 "unique",
                 "urlencode",
+                "zip",
             ];
             match expr.name {
                 "abs" => {
@@ -303,9 +429,10 @@ fn tracker_visit_expr(
                     }
                 },
                 "title" => Type::String,
+                "token_count" => Type::Int,
                 "tojson" | "json" => Type::String,
                 "trim" => Type::String,
-                "unique" => Type::Unknown,
+                "truncate_tokens" => Type::String,
                 "urlencode" => Type::String,
                 other => {
                     state.errors.push(TypeError::new_invalid_filter(
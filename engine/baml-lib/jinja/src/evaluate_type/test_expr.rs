@@ -269,9 +269,19 @@ fn test_output_format() {
         vec!["Function 'baml::OutputFormat' expects argument 'hoisted_class_prefix' to be of type (none | string), but got literal[1]"]
     );
 
+    assert_eq!(
+        assert_evaluates_to!("ctx.output_format(style='yaml', map_style='angle')", &types),
+        Type::String
+    );
+
+    assert_eq!(
+        assert_fails_to!("ctx.output_format(style=1)", &types),
+        vec!["Function 'baml::OutputFormat' expects argument 'style' to be of type (none | string), but got literal[1]"]
+    );
+
     assert_eq!(
         assert_fails_to!("ctx.output_format(prefix='1', unknown=1)", &types),
-        vec!["Function 'baml::OutputFormat' does not have an argument 'unknown'. Did you mean one of these: 'always_hoist_enums', 'enum_value_prefix', 'or_splitter'?"]
+        vec!["Function 'baml::OutputFormat' does not have an argument 'unknown'. Did you mean one of these: 'map_style', 'or_splitter', 'style'?"]
     );
 }
 
@@ -295,3 +305,60 @@ fn sum_filter() {
         vec![r#"'[hi,1]' is a list[(literal["hi"] | literal[1])], expected (int|float)[]"#]
     );
 }
+
+#[test]
+fn helper_filters() {
+    let mut types = PredefinedTypes::default(JinjaContext::Prompt);
+    types.add_variable("names", Type::List(Box::new(Type::String)));
+    types.add_variable("scores", Type::List(Box::new(Type::Float)));
+    types.add_variable("bio", Type::String);
+    types.add_variable("total", Type::Int);
+
+    assert_eq!(
+        assert_evaluates_to!(r#"total|format(",")"#, types),
+        Type::String
+    );
+    assert_eq!(
+        assert_evaluates_to!(r#"bio|format("%B %d, %Y")"#, types),
+        Type::String
+    );
+    assert_eq!(
+        assert_evaluates_to!("names|to_yaml(indent=4)", types),
+        Type::String
+    );
+    assert_eq!(
+        assert_evaluates_to!("bio|truncate_words(10, end='…')", types),
+        Type::String
+    );
+    assert_eq!(
+        assert_evaluates_to!("bio|similarity('hello')", types),
+        Type::Float
+    );
+    assert_eq!(
+        assert_evaluates_to!("names|unique", types),
+        Type::List(Box::new(Type::String))
+    );
+    assert_eq!(
+        assert_evaluates_to!("names|enumerate(start=1)", types),
+        Type::List(Box::new(Type::Tuple(vec![Type::Int, Type::String])))
+    );
+    assert_eq!(
+        assert_evaluates_to!("names|zip(scores)", types),
+        Type::List(Box::new(Type::Tuple(vec![Type::String, Type::Float])))
+    );
+
+    assert_eq!(
+        assert_fails_to!("names|dedent", types),
+        vec!["'names' is a list[string], expected string"]
+    );
+    assert_eq!(
+        assert_fails_to!("bio|truncate_words('ten')", types),
+        vec![
+            r#"Function 'truncate_words' expects argument 'count' to be of type int, but got literal["ten"]"#
+        ]
+    );
+    assert_eq!(
+        assert_fails_to!("names|zip(bio)", types),
+        vec!["Function 'zip' expects argument 'list 1' to be of type list[<unknown>], but got string"]
+    );
+}
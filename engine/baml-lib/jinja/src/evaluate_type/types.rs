@@ -256,6 +256,16 @@ impl PredefinedTypes {
                     "baml::Chat".into(),
                     (Type::String, vec![("role".into(), Type::String)]),
                 ),
+                (
+                    "baml::ChatHistory".into(),
+                    (
+                        Type::String,
+                        vec![(
+                            "messages".into(),
+                            Type::List(Box::new(Type::ClassRef("ChatMessage".into()))),
+                        )],
+                    ),
+                ),
                 (
                     "baml::OutputFormat".into(),
                     (
@@ -278,6 +288,11 @@ impl PredefinedTypes {
                                 "hoisted_class_prefix".into(),
                                 Type::merge(vec![Type::String, Type::None]),
                             ),
+                            (
+                                "map_style".into(),
+                                Type::merge(vec![Type::String, Type::None]),
+                            ),
+                            ("style".into(), Type::merge(vec![Type::String, Type::None])),
                         ],
                     ),
                 ),
@@ -288,6 +303,7 @@ impl PredefinedTypes {
                     HashMap::from([
                         ("name".into(), Type::String),
                         ("provider".into(), Type::String),
+                        ("model".into(), Type::merge(vec![Type::String, Type::None])),
                     ]),
                 ),
                 (
@@ -312,6 +328,10 @@ impl PredefinedTypes {
                     HashMap::from([
                         ("chat".into(), Type::FunctionRef("baml::Chat".into())),
                         ("role".into(), Type::FunctionRef("baml::Chat".into())),
+                        (
+                            "chat_history".into(),
+                            Type::FunctionRef("baml::ChatHistory".into()),
+                        ),
                     ]),
                 ),
                 (
@@ -512,73 +532,86 @@ impl PredefinedTypes {
             );
         }
         let (ret, args) = val.unwrap();
-        let mut errors = Vec::new();
+        let errors = check_args(func, span, args, positional_args, kwargs);
+        (ret.clone(), errors)
+    }
+}
 
-        // Check how many args are required.
-        let mut optional_args = vec![];
-        for (name, t) in args.iter().rev() {
-            if !t.is_optional() {
-                break;
-            }
-            optional_args.push(name);
+/// Checks the arguments of a call against the parameters of a function (or
+/// filter). Trailing parameters that accept `none` are optional.
+pub(super) fn check_args(
+    func: &str,
+    span: Span,
+    args: &[(String, Type)],
+    positional_args: &[Type],
+    kwargs: &HashMap<&str, Type>,
+) -> Vec<TypeError> {
+    let mut errors = Vec::new();
+
+    // Check how many args are required.
+    let mut optional_args = vec![];
+    for (name, t) in args.iter().rev() {
+        if !t.is_optional() {
+            break;
         }
-        let required_args = args.len() - optional_args.len();
-
-        // Check count
-        if positional_args.len() + kwargs.len() < required_args
-            || (positional_args.len() + kwargs.len()) > args.len()
-        {
-            errors.push(TypeError::new_wrong_arg_count(
-                func,
-                span,
-                args.len(),
-                positional_args.len() + kwargs.len(),
-            ));
-        } else {
-            let mut unused_args = args.iter().map(|(name, _)| name).collect::<HashSet<_>>();
-            // Check types
-            for (i, (name, t)) in args.iter().enumerate() {
-                if i < positional_args.len() {
-                    unused_args.remove(name);
-                    let arg_t = &positional_args[i];
-                    if !arg_t.is_subtype_of(t) {
-                        errors.push(TypeError::new_wrong_arg_type(
-                            func,
-                            span,
-                            name,
-                            span,
-                            t.clone(),
-                            arg_t.clone(),
-                        ));
-                    }
-                } else if let Some(arg_t) = kwargs.get(name.as_str()) {
-                    unused_args.remove(name);
-                    if !arg_t.is_subtype_of(t) {
-                        errors.push(TypeError::new_wrong_arg_type(
-                            func,
-                            span,
-                            name,
-                            span,
-                            t.clone(),
-                            arg_t.clone(),
-                        ));
-                    }
-                } else if !optional_args.contains(&name) {
-                    errors.push(TypeError::new_missing_arg(func, span, name));
-                }
-            }
+        optional_args.push(name);
+    }
+    let required_args = args.len() - optional_args.len();
 
-            kwargs.iter().for_each(|(name, _)| {
-                if !args.iter().any(|(arg_name, _)| arg_name == name) {
-                    errors.push(TypeError::new_unknown_arg(
+    // Check count
+    if positional_args.len() + kwargs.len() < required_args
+        || (positional_args.len() + kwargs.len()) > args.len()
+    {
+        errors.push(TypeError::new_wrong_arg_count(
+            func,
+            span,
+            args.len(),
+            positional_args.len() + kwargs.len(),
+        ));
+    } else {
+        let mut unused_args = args.iter().map(|(name, _)| name).collect::<HashSet<_>>();
+        // Check types
+        for (i, (name, t)) in args.iter().enumerate() {
+            if i < positional_args.len() {
+                unused_args.remove(name);
+                let arg_t = &positional_args[i];
+                if !arg_t.is_subtype_of(t) {
+                    errors.push(TypeError::new_wrong_arg_type(
                         func,
                         span,
                         name,
-                        unused_args.clone(),
+                        span,
+                        t.clone(),
+                        arg_t.clone(),
                     ));
                 }
-            });
+            } else if let Some(arg_t) = kwargs.get(name.as_str()) {
+                unused_args.remove(name);
+                if !arg_t.is_subtype_of(t) {
+                    errors.push(TypeError::new_wrong_arg_type(
+                        func,
+                        span,
+                        name,
+                        span,
+                        t.clone(),
+                        arg_t.clone(),
+                    ));
+                }
+            } else if !optional_args.contains(&name) {
+                errors.push(TypeError::new_missing_arg(func, span, name));
+            }
         }
-        (ret.clone(), errors)
+
+        kwargs.iter().for_each(|(name, _)| {
+            if !args.iter().any(|(arg_name, _)| arg_name == name) {
+                errors.push(TypeError::new_unknown_arg(
+                    func,
+                    span,
+                    name,
+                    unused_args.clone(),
+                ));
+            }
+        });
     }
+    errors
 }
@@ -32,8 +32,12 @@ pub(super) fn coerce_array(
 
     match &value {
         Some(crate::jsonish::Value::Array(arr)) => {
+            let hold_back_incomplete =
+                ctx.allow_partials && ctx.type_streaming_behavior(inner).done;
             for (i, item) in arr.iter().enumerate() {
                 match inner.coerce(&ctx.enter_scope(&format!("{i}")), inner, Some(item)) {
+                    // Items of `@@stream.done` classes only show up once complete.
+                    Ok(v) if hold_back_incomplete && v.is_incomplete() => {}
                     Ok(v) => items.push(v),
                     // TODO(vbv): document why we penalize in proportion to how deep into an array a parse error is
                     Err(e) => flags.add_flag(Flag::ArrayItemParseError(i, e)),
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use baml_types::{BamlMap, Constraint};
+use baml_types::{BamlMap, CompletionState, Constraint};
 use internal_baml_core::ir::FieldType;
 use internal_baml_jinja::types::{Class, Name};
 
@@ -323,6 +323,10 @@ impl TypeCoercer for Class {
                     }
                 }
 
+                if ctx.allow_partials {
+                    apply_streaming_behavior(self, ctx, &mut ordered_valid_fields)?;
+                }
+
                 let completed_instance = Ok(BamlValueWithFlags::Class(
                     self.name.real_name().into(),
                     flags,
@@ -373,6 +377,77 @@ pub fn apply_constraints(
     }
 }
 
+/// Apply the `@stream.*` attributes of each field to a partial instance of
+/// `class`. Fails if a `@stream.not_null` field has no value yet, which holds
+/// back the whole instance.
+///
+/// See Note [Streaming Completion State]
+fn apply_streaming_behavior(
+    class: &Class,
+    ctx: &ParsingContext,
+    fields: &mut BamlMap<String, BamlValueWithFlags>,
+) -> Result<(), ParsingError> {
+    for (name, t, ..) in class.fields.iter() {
+        let key = name.real_name();
+        let behavior = class
+            .streaming_behavior
+            .field(key)
+            .combine(&ctx.type_streaming_behavior(t));
+        if behavior.is_default() {
+            continue;
+        }
+        let Some(value) = fields.get_mut(key) else {
+            continue;
+        };
+
+        if behavior.done && value.is_incomplete() {
+            *value = BamlValueWithFlags::Null(
+                DeserializerConditions::new().with_flag(Flag::OptionalDefaultFromNoValue),
+            );
+        }
+
+        let state = completion_state(value);
+
+        if behavior.not_null && matches!(value, BamlValueWithFlags::Null(_)) {
+            return Err(ctx.error_missing_required_field(vec![], vec![key.to_string()], None));
+        }
+
+        if behavior.state {
+            let inner = std::mem::replace(value, BamlValueWithFlags::Null(Default::default()));
+            let mut wrapped = BamlMap::new();
+            wrapped.insert("value".to_string(), (DeserializerConditions::new(), inner));
+            wrapped.insert(
+                "state".to_string(),
+                (
+                    DeserializerConditions::new(),
+                    BamlValueWithFlags::String(state.to_string().into()),
+                ),
+            );
+            *value = BamlValueWithFlags::Map(DeserializerConditions::new(), wrapped);
+        }
+    }
+
+    Ok(())
+}
+
+fn completion_state(value: &BamlValueWithFlags) -> CompletionState {
+    let no_value = match value {
+        BamlValueWithFlags::Null(flags) => flags
+            .flags()
+            .iter()
+            .any(|f| matches!(f, Flag::OptionalDefaultFromNoValue)),
+        _ => false,
+    };
+
+    if no_value {
+        CompletionState::Pending
+    } else if value.is_incomplete() {
+        CompletionState::Incomplete
+    } else {
+        CompletionState::Complete
+    }
+}
+
 fn update_map<'a>(
     required_values: &'a mut BamlMap<String, Option<Result<BamlValueWithFlags, ParsingError>>>,
     optional_values: &'a mut BamlMap<String, Option<Result<BamlValueWithFlags, ParsingError>>>,
@@ -13,7 +13,7 @@ use std::collections::{HashMap, HashSet};
 
 use anyhow::Result;
 
-use baml_types::{BamlValue, Constraint, JinjaExpression};
+use baml_types::{BamlValue, Constraint, JinjaExpression, StreamingBehavior};
 use internal_baml_jinja::types::OutputFormatContent;
 
 use internal_baml_core::ir::{jinja_helpers::evaluate_predicate, FieldType};
@@ -115,6 +115,28 @@ impl ParsingContext<'_> {
         }
     }
 
+    pub(crate) fn error_incomplete_value(&self, target: &FieldType) -> ParsingError {
+        ParsingError {
+            reason: format!("{} is marked @stream.done and is still streaming", target),
+            scope: self.scope.clone(),
+            causes: vec![],
+        }
+    }
+
+    /// The `@@stream.*` behavior of the class a type refers to, if any.
+    pub(crate) fn type_streaming_behavior(&self, target: &FieldType) -> StreamingBehavior {
+        match target {
+            FieldType::Class(name) => self
+                .of
+                .find_class(name)
+                .map(|c| c.streaming_behavior.class)
+                .unwrap_or_default(),
+            FieldType::Optional(inner) => self.type_streaming_behavior(inner),
+            FieldType::Constrained { base, .. } => self.type_streaming_behavior(base),
+            _ => StreamingBehavior::default(),
+        }
+    }
+
     pub(crate) fn error_unexpected_null(&self, target: &FieldType) -> ParsingError {
         ParsingError {
             reason: format!("Expected {}, got null", target),
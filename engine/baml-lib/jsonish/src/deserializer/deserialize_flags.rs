@@ -146,7 +146,13 @@ impl std::fmt::Display for Flag {
                 write!(f, "Default value")?;
             }
             Flag::ObjectFromFixedJson(fixes) => {
-                write!(f, "JSON (Fixed {} mistakes)", fixes.len())?;
+                if fixes.contains(&crate::jsonish::Fixes::ParsedYaml) {
+                    write!(f, "Object from YAML")?;
+                } else if fixes.contains(&crate::jsonish::Fixes::ParsedXml) {
+                    write!(f, "Object from XML")?;
+                } else {
+                    write!(f, "JSON (Fixed {} mistakes)", fixes.len())?;
+                }
             }
             Flag::ObjectFromMarkdown(_) => {
                 write!(f, "Object from markdown")?;
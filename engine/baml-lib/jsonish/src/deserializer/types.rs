@@ -4,6 +4,8 @@ use baml_types::{BamlMap, BamlMedia, BamlValue, BamlValueWithMeta, Constraint, J
 use serde_json::json;
 use strsim::jaro;
 
+use crate::jsonish::Fixes;
+
 use super::{
     coercer::ParsingError,
     deserialize_flags::{DeserializerConditions, Flag},
@@ -57,6 +59,15 @@ impl BamlValueWithFlags {
         }
     }
 
+    /// Whether more tokens may still change this value.
+    ///
+    /// See Note [Streaming Completion State]
+    pub fn is_incomplete(&self) -> bool {
+        self.conditions().flags().iter().any(
+            |f| matches!(f, Flag::ObjectFromFixedJson(fixes) if fixes.contains(&Fixes::Incomplete)),
+        )
+    }
+
     pub fn conditions(&self) -> &DeserializerConditions {
         match self {
             BamlValueWithFlags::String(v) => &v.flags,
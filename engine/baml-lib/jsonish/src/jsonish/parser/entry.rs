@@ -4,7 +4,7 @@ use crate::jsonish::{
     parser::{
         fixing_parser,
         markdown_parser::{self, MarkdownResult},
-        multi_json_parser,
+        multi_json_parser, xml_parser, yaml_parser, ParsingMode, ResponseFormat,
     },
     value::Fixes,
     Value,
@@ -29,6 +29,21 @@ pub fn parse(str: &str, mut options: ParseOptions) -> Result<Value> {
         }
     };
 
+    // YAML and XML responses are also tried as JSON (e.g. an XML tag may
+    // just wrap a JSON object), so every candidate is kept.
+    let alternates = parse_alternate_formats(str, &options);
+    if !alternates.is_empty() {
+        let mut items = alternates;
+        match parse(str, options.next_from_mode(ParsingMode::JsonOnly)) {
+            Ok(Value::AnyOf(others, _)) => items.extend(others),
+            Ok(other) => items.push(other),
+            Err(e) => {
+                log::debug!("Error parsing as JSON: {:?}", e);
+            }
+        }
+        return Ok(Value::AnyOf(items, str.to_string()));
+    }
+
     if options.allow_markdown_json {
         match markdown_parser::parse(str, &options) {
             Ok(items) => match items.len() {
@@ -185,3 +200,33 @@ pub fn parse(str: &str, mut options: ParseOptions) -> Result<Value> {
 
     Err(anyhow::anyhow!("Failed to parse JSON"))
 }
+
+/// Parses the response as YAML or XML, if the prompt asked for it.
+fn parse_alternate_formats(str: &str, options: &ParseOptions) -> Vec<Value> {
+    let (yaml, xml) = match options.format {
+        ResponseFormat::Json => return vec![],
+        ResponseFormat::Yaml => (
+            yaml_parser::parse(str, options),
+            Err(anyhow::anyhow!("Expected YAML")),
+        ),
+        ResponseFormat::Xml => (
+            Err(anyhow::anyhow!("Expected XML")),
+            xml_parser::parse(str),
+        ),
+    };
+
+    let mut values = vec![];
+    match yaml {
+        Ok(v) => values.push(Value::FixedJson(v.into(), vec![Fixes::ParsedYaml])),
+        Err(e) => log::debug!("Error parsing YAML: {:?}", e),
+    }
+    match xml {
+        Ok(items) => values.extend(
+            items
+                .into_iter()
+                .map(|v| Value::FixedJson(v.into(), vec![Fixes::ParsedXml])),
+        ),
+        Err(e) => log::debug!("Error parsing XML: {:?}", e),
+    }
+    values
+}
@@ -2,15 +2,34 @@ mod entry;
 mod fixing_parser;
 mod markdown_parser;
 mod multi_json_parser;
+mod xml_parser;
+mod yaml_parser;
 
 pub use entry::parse;
 
+use super::Value;
+
+/// The format a response is expected to be in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// JSON only, unless the prompt asked for another style.
+    #[default]
+    Json,
+    /// YAML, e.g. for prompts using `ctx.output_format(style="yaml")`.
+    /// Prose around the document is skipped.
+    Yaml,
+    /// XML tags, e.g. for prompts using `ctx.output_format(style="xml")`.
+    /// Prose around the elements is skipped.
+    Xml,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct ParseOptions {
     all_finding_all_json_objects: bool,
     allow_markdown_json: bool,
     allow_fixes: bool,
     allow_as_string: bool,
+    format: ResponseFormat,
     depth: usize,
 }
 
@@ -21,6 +40,7 @@ impl Default for ParseOptions {
             allow_markdown_json: true,
             allow_fixes: true,
             allow_as_string: true,
+            format: ResponseFormat::Json,
             depth: 0,
         }
     }
@@ -30,9 +50,15 @@ pub(super) enum ParsingMode {
     JsonMarkdown,
     JsonMarkdownString,
     AllJsonObjects,
+    JsonOnly,
 }
 
 impl ParseOptions {
+    pub fn with_format(mut self, format: ResponseFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     pub(super) fn next_from_mode(&self, curr_mode: ParsingMode) -> Self {
         let mut new = *self;
         match curr_mode {
@@ -48,7 +74,33 @@ impl ParseOptions {
                 new.all_finding_all_json_objects = false;
                 new.allow_as_string = false;
             }
+            ParsingMode::JsonOnly => {
+                new.format = ResponseFormat::Json;
+            }
         }
         new
     }
 }
+
+/// Converts an unquoted YAML or XML scalar. Numbers, booleans and nulls keep
+/// their text so string targets get exactly what the model wrote (e.g. `007`).
+pub(super) fn plain_scalar(s: &str) -> Value {
+    let s = s.trim();
+    let typed = match s {
+        "true" | "True" | "TRUE" => Value::Boolean(true),
+        "false" | "False" | "FALSE" => Value::Boolean(false),
+        "null" | "Null" | "NULL" | "~" => Value::Null,
+        _ => {
+            if let Ok(n) = s.parse::<i64>() {
+                Value::Number(n.into())
+            } else if let Ok(n) = s.parse::<u64>() {
+                Value::Number(n.into())
+            } else if let Some(n) = s.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+                Value::Number(n)
+            } else {
+                return Value::String(s.to_string());
+            }
+        }
+    };
+    Value::AnyOf(vec![typed], s.to_string())
+}
@@ -0,0 +1,373 @@
+use crate::jsonish::Value;
+
+use super::plain_scalar;
+use anyhow::Result;
+
+/// Parses an XML (or XML-tag style) response into one value per top-level
+/// element, plus an object of all of them when there is more than one (e.g.
+/// `<thinking>...</thinking><answer>...</answer>`).
+///
+/// An element becomes:
+///   - a list if its children are `<item>`s, or all share the same tag.
+///   - a map if its children are `<entry key="...">`s.
+///   - an object of its children otherwise.
+///   - its text if it has no children, or null for `<tag/>`.
+///   - an empty string, list or map (or null) for `<tag></tag>`.
+///
+/// Unclosed elements at the end of the response are closed, so partial
+/// responses can be parsed while streaming. Text around the elements is
+/// skipped.
+pub fn parse(str: &str) -> Result<Vec<Value>> {
+    let mut stack: Vec<Element> = vec![];
+    let mut roots: Vec<Element> = vec![];
+    let mut remaining = str;
+
+    while !remaining.is_empty() {
+        let Some(start) = remaining.find('<') else {
+            if let Some(el) = stack.last_mut() {
+                el.text.push_str(&decode_entities(remaining));
+            }
+            break;
+        };
+        let text = &remaining[..start];
+        if let Some(el) = stack.last_mut() {
+            el.text.push_str(&decode_entities(text));
+        }
+        remaining = &remaining[start..];
+
+        if let Some(rest) = remaining.strip_prefix("<!--") {
+            remaining = skip_past(rest, "-->");
+        } else if let Some(rest) = remaining.strip_prefix("<![CDATA[") {
+            let end = rest.find("]]>").unwrap_or(rest.len());
+            if let Some(el) = stack.last_mut() {
+                el.text.push_str(&rest[..end]);
+            }
+            remaining = skip_past(rest, "]]>");
+        } else if remaining.starts_with("<?") || remaining.starts_with("<!") {
+            remaining = skip_past(remaining, ">");
+        } else if let Some(rest) = remaining.strip_prefix("</") {
+            let Some(end) = rest.find('>') else {
+                // The closing tag is still being streamed in.
+                break;
+            };
+            let name = rest[..end].trim();
+            remaining = &rest[end + 1..];
+
+            // Close everything up to the matching element, if there is one.
+            if let Some(index) = stack.iter().rposition(|el| el.name == name) {
+                while stack.len() > index {
+                    let Some(mut el) = stack.pop() else {
+                        break;
+                    };
+                    el.closed = stack.len() == index;
+                    attach(el, &mut stack, &mut roots);
+                }
+            }
+        } else if let Some((el, rest)) = parse_open_tag(&remaining[1..]) {
+            remaining = rest;
+            if el.closed {
+                attach(el, &mut stack, &mut roots);
+            } else {
+                stack.push(el);
+            }
+        } else if remaining[1..].starts_with(is_name_start) && !remaining.contains('>') {
+            // An opening tag that is still being streamed in.
+            break;
+        } else {
+            // A lone `<`, e.g. in `a < b`.
+            if let Some(el) = stack.last_mut() {
+                el.text.push('<');
+            }
+            remaining = &remaining[1..];
+        }
+    }
+
+    while let Some(el) = stack.pop() {
+        attach(el, &mut stack, &mut roots);
+    }
+
+    match roots.len() {
+        0 => anyhow::bail!("No XML elements found"),
+        1 => Ok(roots.into_iter().map(Element::into_value).collect()),
+        _ => {
+            let all = Value::Object(
+                roots
+                    .iter()
+                    .map(|el| (el.name.clone(), el.clone().into_value()))
+                    .collect(),
+            );
+            Ok(roots
+                .into_iter()
+                .map(Element::into_value)
+                .chain(std::iter::once(all))
+                .collect())
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+    /// Whether the element has a closing tag, or is self-closing.
+    closed: bool,
+    self_closing: bool,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn into_value(self) -> Value {
+        if self.children.is_empty() {
+            let text = self.text.trim();
+            return if self.self_closing || (text.is_empty() && !self.closed) {
+                Value::Null
+            } else if text.is_empty() {
+                // `<tag></tag>` is whatever is empty for the target.
+                Value::AnyOf(
+                    vec![Value::Null, Value::Array(vec![]), Value::Object(vec![])],
+                    String::new(),
+                )
+            } else {
+                plain_scalar(text)
+            };
+        }
+
+        let first = &self.children[0].name;
+        let same_tag = self.children.iter().all(|c| &c.name == first);
+
+        if same_tag
+            && first == "entry"
+            && self.children.iter().all(|c| c.attribute("key").is_some())
+        {
+            return Value::Object(
+                self.children
+                    .into_iter()
+                    .map(|c| {
+                        let key = c.attribute("key").unwrap_or_default().to_string();
+                        (key, c.into_value())
+                    })
+                    .collect(),
+            );
+        }
+
+        if same_tag && (first == "item" || self.children.len() > 1) {
+            return Value::Array(self.children.into_iter().map(Element::into_value).collect());
+        }
+
+        // Repeated tags become a list.
+        let mut fields: Vec<(String, Vec<Value>)> = vec![];
+        for child in self.children {
+            let name = child.name.clone();
+            let value = child.into_value();
+            match fields.iter_mut().find(|(k, _)| *k == name) {
+                Some((_, values)) => values.push(value),
+                None => fields.push((name, vec![value])),
+            }
+        }
+        Value::Object(
+            fields
+                .into_iter()
+                .map(|(k, mut values)| {
+                    let value = if values.len() == 1 {
+                        values.remove(0)
+                    } else {
+                        Value::Array(values)
+                    };
+                    (k, value)
+                })
+                .collect(),
+        )
+    }
+}
+
+fn attach(el: Element, stack: &mut [Element], roots: &mut Vec<Element>) {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(el),
+        None => roots.push(el),
+    }
+}
+
+fn skip_past<'a>(str: &'a str, end: &str) -> &'a str {
+    match str.find(end) {
+        Some(i) => &str[i + end.len()..],
+        None => "",
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')
+}
+
+/// Parses `name attr="value" ...>` (after the `<`), returning the element and
+/// the text after the tag.
+fn parse_open_tag(str: &str) -> Option<(Element, &str)> {
+    if !str.starts_with(is_name_start) {
+        return None;
+    }
+    let name_end = str.find(|c: char| !is_name_char(c))?;
+    let name = str[..name_end].to_string();
+    let mut rest = &str[name_end..];
+    let mut attributes = vec![];
+
+    loop {
+        rest = rest.trim_start();
+        if let Some(after) = rest.strip_prefix("/>") {
+            return Some((
+                Element {
+                    name,
+                    attributes,
+                    children: vec![],
+                    text: String::new(),
+                    closed: true,
+                    self_closing: true,
+                },
+                after,
+            ));
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            return Some((
+                Element {
+                    name,
+                    attributes,
+                    children: vec![],
+                    text: String::new(),
+                    closed: false,
+                    self_closing: false,
+                },
+                after,
+            ));
+        }
+
+        // `key="value"`, `key='value'` or `key=value`.
+        let key_end = rest.find(|c: char| !is_name_char(c))?;
+        if key_end == 0 {
+            return None;
+        }
+        let key = rest[..key_end].to_string();
+        rest = rest[key_end..].trim_start();
+        let Some(after) = rest.strip_prefix('=') else {
+            attributes.push((key, String::new()));
+            continue;
+        };
+        rest = after.trim_start();
+        let value = match rest.chars().next()? {
+            quote @ ('"' | '\'') => {
+                let end = rest[1..].find(quote)? + 1;
+                let value = &rest[1..end];
+                rest = &rest[end + 1..];
+                value
+            }
+            _ => {
+                let end = rest.find(|c: char| c.is_whitespace() || c == '>' || c == '/')?;
+                let value = &rest[..end];
+                rest = &rest[end..];
+                value
+            }
+        };
+        attributes.push((key, decode_entities(value)));
+    }
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    #[test]
+    fn test_parse() {
+        let values = parse(
+            r#"<Resume>
+  <!-- The person's full name -->
+  <name>Grace &amp; co</name>
+  <skills>
+    <item>COBOL</item>
+    <item><![CDATA[<compilers>]]></item>
+  </skills>
+  <links>
+    <entry key="github">gh</entry>
+  </links>
+  <nickname/>
+</Resume>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            values,
+            vec![Value::Object(vec![
+                ("name".to_string(), string("Grace & co")),
+                (
+                    "skills".to_string(),
+                    Value::Array(vec![string("COBOL"), string("<compilers>")])
+                ),
+                (
+                    "links".to_string(),
+                    Value::Object(vec![("github".to_string(), string("gh"))])
+                ),
+                ("nickname".to_string(), Value::Null),
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_multiple_roots() {
+        let values = parse("<thinking>hmm</thinking>\n<answer>yes</answer>").unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(
+            values[2],
+            Value::Object(vec![
+                ("thinking".to_string(), string("hmm")),
+                ("answer".to_string(), string("yes")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_partial() {
+        let values = parse("<Resume>\n  <name>Grace</name>\n  <skills>\n    <item>CO").unwrap();
+        assert_eq!(
+            values,
+            vec![Value::Object(vec![
+                ("name".to_string(), string("Grace")),
+                ("skills".to_string(), Value::Array(vec![string("CO")])),
+            ])]
+        );
+
+        let values = parse("<Resume>\n  <name>Grace</name>\n  <ski").unwrap();
+        assert_eq!(
+            values,
+            vec![Value::Object(vec![("name".to_string(), string("Grace"))])]
+        );
+    }
+
+    #[test]
+    fn test_text_around_elements() {
+        assert!(parse("The answer is <b>42</b>").is_ok());
+        assert!(parse("if a < b then c").is_err());
+    }
+}
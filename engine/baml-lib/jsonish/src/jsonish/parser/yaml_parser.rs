@@ -0,0 +1,444 @@
+use crate::jsonish::Value;
+
+use super::{fixing_parser, plain_scalar, ParseOptions};
+use anyhow::Result;
+
+/// Parses a YAML response.
+///
+/// Only the subset of YAML that models actually write is supported: block
+/// mappings and sequences, flow collections (`[a, b]`, `{a: 1}`), quoted and
+/// plain scalars, block scalars (`|` and `>`) and comments. Anchors, tags and
+/// multiple documents are not.
+///
+/// The last line may be cut off mid-stream: a key or `-` that has no value
+/// yet is dropped. Lines that don't fit (e.g. prose after the document) are
+/// skipped.
+pub fn parse(str: &str, options: &ParseOptions) -> Result<Value> {
+    let lines = str
+        .lines()
+        .map(|line| {
+            let content = line.trim_start_matches([' ', '\t']);
+            (line.len() - content.len(), content.trim_end().to_string())
+        })
+        .filter(|(indent, content)| {
+            // Document markers.
+            !(*indent == 0 && matches!(content.as_str(), "---" | "..."))
+        })
+        .collect::<Vec<_>>();
+
+    let mut parser = YamlParser {
+        lines,
+        pos: 0,
+        options,
+    };
+
+    loop {
+        let Some((_, first)) = parser.peek() else {
+            anyhow::bail!("No YAML mapping or sequence found");
+        };
+        if is_sequence_item(&first) || split_key(&first).is_some() {
+            break;
+        }
+        // Skip any text before the document.
+        parser.pos += 1;
+    }
+
+    Ok(parser.parse_node(0)?.unwrap_or(Value::Null))
+}
+
+struct YamlParser<'a> {
+    /// `(indent, content)` of every line.
+    lines: Vec<(usize, String)>,
+    pos: usize,
+    options: &'a ParseOptions,
+}
+
+impl YamlParser<'_> {
+    /// The next line that isn't blank or a comment.
+    fn peek(&mut self) -> Option<(usize, String)> {
+        while let Some((indent, content)) = self.lines.get(self.pos) {
+            if !is_blank(content) {
+                return Some((*indent, content.clone()));
+            }
+            self.pos += 1;
+        }
+        None
+    }
+
+    fn is_last_line(&self) -> bool {
+        self.lines[self.pos + 1..].iter().all(|(_, c)| is_blank(c))
+    }
+
+    /// Parses the node starting on the next line, if it is indented at least
+    /// `min_indent`.
+    fn parse_node(&mut self, min_indent: usize) -> Result<Option<Value>> {
+        let Some((indent, content)) = self.peek() else {
+            return Ok(None);
+        };
+        if indent < min_indent {
+            return Ok(None);
+        }
+
+        if is_sequence_item(&content) {
+            self.parse_sequence(indent).map(Some)
+        } else if split_key(&content).is_some() {
+            self.parse_mapping(indent).map(Some)
+        } else {
+            self.pos += 1;
+            Ok(Some(
+                self.parse_value(&content, min_indent.saturating_sub(1)),
+            ))
+        }
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Result<Value> {
+        let mut fields = vec![];
+
+        while let Some((line_indent, content)) = self.peek() {
+            if line_indent < indent || (line_indent == indent && is_sequence_item(&content)) {
+                break;
+            }
+
+            let key = match split_key(&content) {
+                Some((key, rest)) if line_indent == indent => Some((key, rest.to_string())),
+                _ => None,
+            };
+            let Some((key, rest)) = key else {
+                // Skip it, this is either garbage or a key still being
+                // streamed in.
+                self.pos += 1;
+                continue;
+            };
+
+            let is_last_line = self.is_last_line();
+            self.pos += 1;
+
+            let value = if rest.is_empty() {
+                match self.parse_nested(indent)? {
+                    Some(value) => value,
+                    // No value yet.
+                    None if is_last_line => continue,
+                    None => Value::Null,
+                }
+            } else {
+                self.parse_value(&rest, indent)
+            };
+            fields.push((key, value));
+        }
+
+        Ok(Value::Object(fields))
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Result<Value> {
+        let mut items = vec![];
+
+        while let Some((line_indent, content)) = self.peek() {
+            if line_indent < indent {
+                break;
+            }
+            if line_indent == indent && !is_sequence_item(&content) {
+                break;
+            }
+            if line_indent > indent {
+                self.pos += 1;
+                continue;
+            }
+
+            let rest = content[1..].trim_start();
+            let column = indent + content.len() - rest.len();
+
+            if rest.is_empty() {
+                let is_last_line = self.is_last_line();
+                self.pos += 1;
+                match self.parse_node(indent + 1)? {
+                    Some(value) => items.push(value),
+                    // The item hasn't been streamed in yet.
+                    None if is_last_line => {}
+                    None => items.push(Value::Null),
+                }
+            } else if is_sequence_item(rest) || split_key(rest).is_some() {
+                // `- key: value` or `- - item`: the rest of the line starts a
+                // node indented at its own column.
+                self.lines[self.pos] = (column, rest.to_string());
+                if let Some(value) = self.parse_node(column)? {
+                    items.push(value);
+                }
+            } else {
+                let rest = rest.to_string();
+                self.pos += 1;
+                items.push(self.parse_value(&rest, indent));
+            }
+        }
+
+        Ok(Value::Array(items))
+    }
+
+    /// The value of a key with nothing after the `:`.
+    fn parse_nested(&mut self, indent: usize) -> Result<Option<Value>> {
+        match self.peek() {
+            Some((line_indent, _)) if line_indent > indent => self.parse_node(indent + 1),
+            // Sequences may be at the same indentation as their key.
+            Some((line_indent, content)) if line_indent == indent && is_sequence_item(&content) => {
+                self.parse_sequence(indent).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Lines following the current one that are indented more than `indent`.
+    fn continuation_lines(&mut self, indent: usize) -> Vec<(usize, String)> {
+        let mut lines = vec![];
+        while let Some((line_indent, content)) = self.lines.get(self.pos) {
+            if !content.is_empty() && *line_indent <= indent {
+                break;
+            }
+            lines.push((*line_indent, content.clone()));
+            self.pos += 1;
+        }
+        // Blank lines after the value belong to whatever comes next.
+        while matches!(lines.last(), Some((_, c)) if c.is_empty()) {
+            lines.pop();
+            self.pos -= 1;
+        }
+        lines
+    }
+
+    /// A value written after a `key:` or `-` on a line indented `indent`.
+    fn parse_value(&mut self, rest: &str, indent: usize) -> Value {
+        if let Some(header) = rest.strip_prefix(['|', '>']) {
+            let folded = rest.starts_with('>');
+            let keep_trailing = header.contains('+');
+            return Value::String(block_scalar(
+                &self.continuation_lines(indent),
+                folded,
+                keep_trailing,
+            ));
+        }
+
+        if rest.starts_with(['[', '{']) {
+            let mut text = rest.to_string();
+            for (_, line) in self.continuation_lines(indent) {
+                text.push('\n');
+                text.push_str(&line);
+            }
+            return match fixing_parser::parse(&text, self.options) {
+                Ok(values) => match values.into_iter().next() {
+                    Some((value, _)) => value,
+                    None => Value::String(text),
+                },
+                Err(_) => Value::String(text),
+            };
+        }
+
+        let mut text = rest.to_string();
+        for (_, line) in self.continuation_lines(indent) {
+            text.push(if line.is_empty() { '\n' } else { ' ' });
+            text.push_str(&line);
+        }
+
+        match text.chars().next() {
+            Some(quote @ ('"' | '\'')) => Value::String(quoted_scalar(&text, quote)),
+            _ => plain_scalar(strip_comment(&text)),
+        }
+    }
+}
+
+fn is_blank(content: &str) -> bool {
+    content.is_empty() || content.starts_with('#')
+}
+
+fn is_sequence_item(content: &str) -> bool {
+    content == "-" || content.starts_with("- ") || content.starts_with("-\t")
+}
+
+/// Splits `key: rest` into the key and the (possibly empty) rest.
+fn split_key(content: &str) -> Option<(String, &str)> {
+    let (key, after) = match content.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = content[1..].find(quote)? + 1;
+            let key = content[1..end].to_string();
+            (key, content[end + 1..].trim_start())
+        }
+        '-' | '[' | '{' | '#' | '|' | '>' | '&' | '*' | '!' | '%' | '@' | '`' => return None,
+        _ => {
+            let colon = content
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|i| matches!(content[i + 1..].chars().next(), None | Some(' ' | '\t')))?;
+            let key = content[..colon].trim_end();
+            (key.to_string(), &content[colon..])
+        }
+    };
+
+    if key.is_empty() {
+        return None;
+    }
+    let rest = after.strip_prefix(':')?.trim();
+    Some((key, if rest.starts_with('#') { "" } else { rest }))
+}
+
+/// Drops a trailing ` # comment` from a plain scalar.
+fn strip_comment(text: &str) -> &str {
+    match text.find(" #") {
+        Some(i) => &text[..i],
+        None => text,
+    }
+}
+
+/// Unquotes a scalar. The closing quote may be missing while streaming.
+fn quoted_scalar(text: &str, quote: char) -> String {
+    let body = &text[1..];
+    if quote == '\'' {
+        // `''` is an escaped quote.
+        let mut out = String::new();
+        let mut chars = body.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\'' {
+                if chars.peek() == Some(&'\'') {
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            out.push(c);
+        }
+        return out;
+    }
+
+    let mut end = None;
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => {
+                end = Some(i);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let inner = &body[..end.unwrap_or(body.len())];
+    serde_json::from_str::<String>(&format!("\"{}\"", inner)).unwrap_or_else(|_| inner.to_string())
+}
+
+/// The text of a `|` (literal) or `>` (folded) block scalar.
+fn block_scalar(lines: &[(usize, String)], folded: bool, keep_trailing: bool) -> String {
+    let indent = lines
+        .iter()
+        .filter(|(_, c)| !c.is_empty())
+        .map(|(i, _)| *i)
+        .min()
+        .unwrap_or(0);
+    let lines = lines
+        .iter()
+        .map(|(i, c)| {
+            if c.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", " ".repeat(i - indent), c)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut text = if folded {
+        let mut text = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 {
+                text.push(if line.is_empty() || lines[i - 1].is_empty() {
+                    '\n'
+                } else {
+                    ' '
+                });
+            }
+            text.push_str(line);
+        }
+        text
+    } else {
+        lines.join("\n")
+    };
+
+    // A single trailing newline is usually not something the caller wants to
+    // see, so unlike YAML we only keep it for `|+` and `>+`.
+    if keep_trailing {
+        text.push('\n');
+    }
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse_str(str: &str) -> Value {
+        parse(str, &ParseOptions::default()).unwrap()
+    }
+
+    fn string(s: &str) -> Value {
+        Value::String(s.to_string())
+    }
+
+    #[test]
+    fn test_mapping() {
+        let value = parse_str(
+            "name: Grace\nskills:\n  - COBOL\n  - \"compilers\"\nbio: |\n  line one\n  line two\n",
+        );
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                ("name".to_string(), string("Grace")),
+                (
+                    "skills".to_string(),
+                    Value::Array(vec![string("COBOL"), string("compilers")])
+                ),
+                ("bio".to_string(), string("line one\nline two")),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_sequence_of_mappings() {
+        let value = parse_str("- a: 1\n  b: x\n- a: 2\n  b: [y, z]");
+        let Value::Array(items) = value else {
+            panic!("Expected an array, got {:#?}", value);
+        };
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items[1],
+            Value::Object(vec![
+                (
+                    "a".to_string(),
+                    Value::AnyOf(vec![Value::Number(2.into())], "2".to_string())
+                ),
+                (
+                    "b".to_string(),
+                    Value::Array(vec![string("y"), string("z")])
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_partial() {
+        let value = parse_str("name: Gra");
+        assert_eq!(
+            value,
+            Value::Object(vec![("name".to_string(), string("Gra"))])
+        );
+
+        let value = parse_str("name: Grace\nskills:\n  - COBOL\n  -");
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                ("name".to_string(), string("Grace")),
+                ("skills".to_string(), Value::Array(vec![string("COBOL")])),
+            ])
+        );
+
+        let value = parse_str("name: Grace\nski");
+        assert_eq!(
+            value,
+            Value::Object(vec![("name".to_string(), string("Grace"))])
+        );
+    }
+}
@@ -9,6 +9,12 @@ use baml_types::BamlMap;
 pub enum Fixes {
     GreppedForJSON,
     InferredArray,
+    /// The value was still being streamed in when it was parsed.
+    Incomplete,
+    /// The response was YAML rather than JSON.
+    ParsedYaml,
+    /// The response was XML rather than JSON.
+    ParsedXml,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -13,20 +13,36 @@ use internal_baml_core::ir::TypeValue;
 use internal_baml_jinja::types::OutputFormatContent;
 
 use deserializer::deserialize_flags::Flag;
-use jsonish::Value;
+use jsonish::{Fixes, Value};
+
+pub use jsonish::ResponseFormat;
 
 pub fn from_str(
     of: &OutputFormatContent,
     target: &FieldType,
     raw_string: &str,
     allow_partials: bool,
+) -> Result<BamlValueWithFlags> {
+    from_str_with_format(of, target, raw_string, allow_partials, ResponseFormat::Json)
+}
+
+/// Like [`from_str`], for a response the model was asked to write in `format`.
+pub fn from_str_with_format(
+    of: &OutputFormatContent,
+    target: &FieldType,
+    raw_string: &str,
+    allow_partials: bool,
+    format: ResponseFormat,
 ) -> Result<BamlValueWithFlags> {
     if matches!(target, FieldType::Primitive(TypeValue::String)) {
         return Ok(BamlValueWithFlags::String(raw_string.to_string().into()));
     }
 
     // When the schema is just a string, i should really just return the raw_string w/o parsing it.
-    let mut value = jsonish::parse(raw_string, jsonish::ParseOptions::default())?;
+    let mut value = jsonish::parse(
+        raw_string,
+        jsonish::ParseOptions::default().with_format(format),
+    )?;
     // let schema = deserializer::schema::from_jsonish_value(&value, None);
 
     // See Note [Streaming Number Invalidation]
@@ -34,6 +50,15 @@ pub fn from_str(
         invalidate_numbers_in_progress(&mut value, raw_string);
     }
 
+    // See Note [Streaming Completion State]
+    let uses_streaming_behavior = of
+        .classes
+        .values()
+        .any(|c| !c.streaming_behavior.is_default());
+    if allow_partials && uses_streaming_behavior {
+        mark_last_value_incomplete(&mut value);
+    }
+
     // Pick the schema that is the most specific.
     // log::info!("Parsed: {}", schema);
     log::debug!("Parsed JSONish (step 1 of parsing): {:#?}", value);
@@ -59,6 +84,13 @@ pub fn from_str(
                 anyhow::bail!("Failed to coerce value: {:?}", v.conditions().flags());
             }
 
+            if allow_partials && ctx.type_streaming_behavior(target).done && v.is_incomplete() {
+                anyhow::bail!(
+                    "Failed to coerce value: {}",
+                    ctx.error_incomplete_value(target)
+                );
+            }
+
             Ok(v)
         }
         Err(e) => anyhow::bail!("Failed to coerce value: {}", e),
@@ -124,6 +156,58 @@ fn last_value_as_number(value: &mut Value) -> Vec<&mut Value> {
     }
 }
 
+/// Wrap every value that may still be streaming in with `Fixes::Incomplete`.
+///
+/// See Note [Streaming Completion State]
+fn mark_last_value_incomplete(value: &mut Value) {
+    match value {
+        Value::Array(items) => {
+            if let Some(item) = items.last_mut() {
+                mark_last_value_incomplete(item);
+            }
+        }
+        Value::Object(fields) => {
+            if let Some((_k, v)) = fields.last_mut() {
+                mark_last_value_incomplete(v);
+            }
+        }
+        Value::Markdown(_, sub_value) => mark_last_value_incomplete(sub_value),
+        Value::FixedJson(fixed_val, _fixes) => mark_last_value_incomplete(fixed_val),
+        Value::AnyOf(variants, _) => variants.iter_mut().for_each(mark_last_value_incomplete),
+        Value::String(_) | Value::Number(_) | Value::Boolean(_) | Value::Null => {}
+    }
+
+    let inner = std::mem::replace(value, Value::Null);
+    *value = Value::FixedJson(Box::new(inner), vec![Fixes::Incomplete]);
+}
+
+/*
+ * Note: Streaming Completion State
+ *
+ * The `@stream.*` attributes let users control how a value shows up while the
+ * response is still streaming:
+ *
+ *   - `@stream.done` (and `@@stream.done` on a class): the value is only
+ *     emitted once it is complete. Until then it is reported as missing, and
+ *     incomplete list items are dropped.
+ *   - `@stream.not_null`: the enclosing class instance is held back until
+ *     this field has a value.
+ *   - `@stream.with_state`: the value is wrapped as `{ value, state }` where
+ *     `state` is one of `Pending`, `Incomplete` or `Complete`.
+ *
+ * We can only tell whether a value is complete from the tokens we have seen.
+ * Like Note [Streaming Number Invalidation], we treat the "last" value in the
+ * parse (and every value enclosing it) as still in progress: while streaming,
+ * every other value has been followed by more tokens and so must be closed.
+ * `mark_last_value_incomplete` wraps each value on that path in
+ * `Value::FixedJson(.., [Fixes::Incomplete])`, and the coercer surfaces this
+ * as a flag via `BamlValueWithFlags::is_incomplete`.
+ *
+ * Marking is only done when parsing partials, and only when some class in the
+ * output format uses a `@stream.*` attribute, so other schemas parse exactly
+ * as before. The final (non-partial) parse is always complete.
+ */
+
 /*
  * Note: Streaming Number Invalidation
  *
@@ -1,11 +1,21 @@
 macro_rules! test_failing_deserializer {
     ($name:ident, $file_content:expr, $raw_string:expr, $target_type:expr) => {
+        test_failing_deserializer!(
+            $name,
+            $file_content,
+            $raw_string,
+            $target_type,
+            format = $crate::ResponseFormat::Json
+        );
+    };
+    ($name:ident, $file_content:expr, $raw_string:expr, $target_type:expr, format = $format:expr) => {
         #[test_log::test]
         fn $name() {
             let ir = load_test_ir($file_content);
             let target = render_output_format(&ir, &$target_type, &Default::default()).unwrap();
 
-            let result = from_str(&target, &$target_type, $raw_string, false);
+            let result =
+                $crate::from_str_with_format(&target, &$target_type, $raw_string, false, $format);
 
             assert!(
                 result.is_err(),
@@ -22,6 +32,8 @@ macro_rules! test_failing_deserializer {
 /// - `file_content`: A BAML schema used for the test.
 /// - `raw_string`: An example payload coming from an LLM to parse.
 /// - `target_type`: The type to try to parse `raw_string` into.
+/// - `format`: Optionally, `format = ResponseFormat::Yaml` (or `Xml`) to parse
+///   the payload as the prompt's output style asked for. Defaults to JSON.
 /// - `json`: The expected JSON encoding that the parser should return.
 ///
 /// Example
@@ -36,17 +48,18 @@ macro_rules! test_failing_deserializer {
 /// );
 /// ```
 macro_rules! test_deserializer {
-    ($name:ident, $file_content:expr, $raw_string:expr, $target_type:expr, $($json:tt)+) => {
+    ($name:ident, $file_content:expr, $raw_string:expr, $target_type:expr, format = $format:expr, $($json:tt)+) => {
         #[test_log::test]
         fn $name() {
             let ir = load_test_ir($file_content);
             let target = render_output_format(&ir, &$target_type, &Default::default()).unwrap();
 
-            let result = from_str(
+            let result = $crate::from_str_with_format(
                 &target,
                 &$target_type,
                 $raw_string,
                 false,
+                $format,
             );
 
             assert!(result.is_ok(), "Failed to parse: {:?}", result);
@@ -62,6 +75,16 @@ macro_rules! test_deserializer {
             assert_json_diff::assert_json_eq!(json_value, expected);
         }
     };
+    ($name:ident, $file_content:expr, $raw_string:expr, $target_type:expr, $($json:tt)+) => {
+        test_deserializer!(
+            $name,
+            $file_content,
+            $raw_string,
+            $target_type,
+            format = $crate::ResponseFormat::Json,
+            $($json)+
+        );
+    };
 }
 
 macro_rules! test_deserializer_with_expected_score {
@@ -84,17 +107,18 @@ macro_rules! test_deserializer_with_expected_score {
 }
 
 macro_rules! test_partial_deserializer {
-    ($name:ident, $file_content:expr, $raw_string:expr, $target_type:expr, $($json:tt)+) => {
+    ($name:ident, $file_content:expr, $raw_string:expr, $target_type:expr, format = $format:expr, $($json:tt)+) => {
         #[test_log::test]
         fn $name() {
             let ir = load_test_ir($file_content);
             let target = render_output_format(&ir, &$target_type, &Default::default()).unwrap();
 
-            let result = from_str(
+            let result = $crate::from_str_with_format(
                 &target,
                 &$target_type,
                 $raw_string,
                 true,
+                $format,
             );
 
             assert!(result.is_ok(), "Failed to parse: {:?}", result);
@@ -110,4 +134,14 @@ macro_rules! test_partial_deserializer {
             assert_json_diff::assert_json_eq!(json_value, expected);
         }
     };
+    ($name:ident, $file_content:expr, $raw_string:expr, $target_type:expr, $($json:tt)+) => {
+        test_partial_deserializer!(
+            $name,
+            $file_content,
+            $raw_string,
+            $target_type,
+            format = $crate::ResponseFormat::Json,
+            $($json)+
+        );
+    };
 }
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use internal_baml_jinja::types::{Class, Enum, Name, OutputFormatContent};
+use internal_baml_jinja::types::{Class, ClassStreamingBehavior, Enum, Name, OutputFormatContent};
 
 #[macro_use]
 pub mod macros;
@@ -14,7 +14,10 @@ mod test_lists;
 mod test_literals;
 mod test_maps;
 mod test_partials;
+mod test_streaming;
 mod test_unions;
+mod test_xml;
+mod test_yaml;
 
 use indexmap::IndexSet;
 use std::{
@@ -64,6 +67,17 @@ fn render_output_format(
         .build())
 }
 
+fn class_streaming_behavior(walker: &ClassWalker<'_>) -> ClassStreamingBehavior {
+    ClassStreamingBehavior {
+        class: walker.streaming_behavior(),
+        fields: walker
+            .walk_fields()
+            .map(|f| (f.name().to_string(), f.streaming_behavior()))
+            .filter(|(_, behavior)| !behavior.is_default())
+            .collect(),
+    }
+}
+
 fn find_existing_class_field(
     class_name: &str,
     field_name: &str,
@@ -222,10 +236,16 @@ fn relevant_data_models<'a>(
                         }
                     }
 
+                    let streaming_behavior = walker
+                        .as_ref()
+                        .map(class_streaming_behavior)
+                        .unwrap_or_default();
+
                     classes.push(Class {
                         name: Name::new_with_alias(cls.to_string(), walker?.alias(env_values)?),
                         fields,
                         constraints,
+                        streaming_behavior,
                     });
                 }
             }
@@ -0,0 +1,131 @@
+use super::*;
+
+const STREAM_DONE_FILE: &str = r#"
+class Foo {
+  name string @stream.done
+  tags string[]
+}
+"#;
+
+test_partial_deserializer!(
+    test_stream_done_incomplete,
+    STREAM_DONE_FILE,
+    r#"{"tags": ["a", "b"], "name": "Hel"#,
+    FieldType::class("Foo"),
+    {"name": null, "tags": ["a", "b"]}
+);
+
+test_partial_deserializer!(
+    test_stream_done_complete,
+    STREAM_DONE_FILE,
+    r#"{"name": "Hello", "tags": ["a", "b"#,
+    FieldType::class("Foo"),
+    {"name": "Hello", "tags": ["a", "b"]}
+);
+
+const STREAM_DONE_CLASS_FILE: &str = r#"
+class Item {
+  a string
+  b string
+  @@stream.done
+}
+
+class Foo {
+  items Item[]
+}
+"#;
+
+test_partial_deserializer!(
+    test_stream_done_class_drops_incomplete_items,
+    STREAM_DONE_CLASS_FILE,
+    r#"{"items": [{"a": "x", "b": "y"}, {"a": "z"#,
+    FieldType::class("Foo"),
+    {"items": [{"a": "x", "b": "y"}]}
+);
+
+test_deserializer!(
+    test_stream_done_class_final,
+    STREAM_DONE_CLASS_FILE,
+    r#"{"items": [{"a": "x", "b": "y"}, {"a": "z", "b": "w"}]}"#,
+    FieldType::class("Foo"),
+    {"items": [{"a": "x", "b": "y"}, {"a": "z", "b": "w"}]}
+);
+
+#[test_log::test]
+fn test_stream_done_class_at_root() {
+    let ir = load_test_ir(STREAM_DONE_CLASS_FILE);
+    let target_type = FieldType::class("Item");
+    let target = render_output_format(&ir, &target_type, &Default::default()).unwrap();
+
+    let result = from_str(&target, &target_type, r#"{"a": "x", "b": "y"#, true);
+    assert!(result.is_err(), "Failed not to parse: {:?}", result);
+
+    let result = from_str(&target, &target_type, r#"{"a": "x", "b": "y"}"#, false);
+    assert!(result.is_ok(), "Failed to parse: {:?}", result);
+}
+
+const STREAM_NOT_NULL_FILE: &str = r#"
+class Item {
+  id string @stream.not_null
+  body string
+}
+
+class Foo {
+  items Item[]
+}
+"#;
+
+test_partial_deserializer!(
+    test_stream_not_null_holds_back_item,
+    STREAM_NOT_NULL_FILE,
+    r#"{"items": [{"body": "x", "id": "1"}, {"body": "hello"#,
+    FieldType::class("Foo"),
+    {"items": [{"id": "1", "body": "x"}]}
+);
+
+test_partial_deserializer!(
+    test_stream_not_null_present,
+    STREAM_NOT_NULL_FILE,
+    r#"{"items": [{"id": "1", "body": "hel"#,
+    FieldType::class("Foo"),
+    {"items": [{"id": "1", "body": "hel"}]}
+);
+
+const STREAM_WITH_STATE_FILE: &str = r#"
+class Foo {
+  name string @stream.with_state
+  tags string[]
+}
+"#;
+
+test_partial_deserializer!(
+    test_stream_with_state_incomplete,
+    STREAM_WITH_STATE_FILE,
+    r#"{"tags": [], "name": "Hel"#,
+    FieldType::class("Foo"),
+    {"name": {"value": "Hel", "state": "Incomplete"}, "tags": []}
+);
+
+test_partial_deserializer!(
+    test_stream_with_state_pending,
+    STREAM_WITH_STATE_FILE,
+    r#"{"tags": ["a"#,
+    FieldType::class("Foo"),
+    {"name": {"value": null, "state": "Pending"}, "tags": ["a"]}
+);
+
+test_partial_deserializer!(
+    test_stream_with_state_complete,
+    STREAM_WITH_STATE_FILE,
+    r#"{"name": "Hello", "tags": ["a"#,
+    FieldType::class("Foo"),
+    {"name": {"value": "Hello", "state": "Complete"}, "tags": ["a"]}
+);
+
+test_deserializer!(
+    test_stream_with_state_final,
+    STREAM_WITH_STATE_FILE,
+    r#"{"name": "Hello", "tags": ["a"]}"#,
+    FieldType::class("Foo"),
+    {"name": "Hello", "tags": ["a"]}
+);
@@ -0,0 +1,191 @@
+use crate::ResponseFormat;
+
+use super::*;
+
+const RESUME_FILE: &str = r#"
+enum Level {
+  Junior
+  Senior
+}
+
+class Job {
+  company string
+  years int
+}
+
+class Resume {
+  name string
+  level Level
+  skills string[]
+  jobs Job[]
+  links map<string, string>
+  bio string?
+}
+"#;
+
+test_deserializer!(
+    test_xml_class,
+    RESUME_FILE,
+    r#"<Resume>
+  <!-- The candidate's full name -->
+  <name>Grace Hopper</name>
+  <level>Senior</level>
+  <skills>
+    <item>COBOL</item>
+    <item>compilers &amp; linkers</item>
+  </skills>
+  <jobs>
+    <item>
+      <company>US Navy</company>
+      <years>43</years>
+    </item>
+  </jobs>
+  <links>
+    <entry key="wiki">https://en.wikipedia.org/wiki/Grace_Hopper</entry>
+  </links>
+</Resume>"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Xml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": ["COBOL", "compilers & linkers"],
+        "jobs": [{"company": "US Navy", "years": 43}],
+        "links": {"wiki": "https://en.wikipedia.org/wiki/Grace_Hopper"},
+        "bio": null
+    }
+);
+
+test_deserializer!(
+    test_xml_repeated_tags,
+    RESUME_FILE,
+    r#"<resume>
+<name>Grace Hopper</name>
+<level>Junior</level>
+<skills><skill>COBOL</skill><skill>FLOW-MATIC</skill></skills>
+<jobs></jobs>
+<links></links>
+<bio/>
+</resume>"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Xml,
+    {
+        "name": "Grace Hopper",
+        "level": "Junior",
+        "skills": ["COBOL", "FLOW-MATIC"],
+        "jobs": [],
+        "links": {},
+        "bio": null
+    }
+);
+
+test_deserializer!(
+    test_xml_answer_tags,
+    "",
+    r#"<thinking>
+The user wants three numbers.
+</thinking>
+<answer>
+  <item>1</item>
+  <item>2</item>
+  <item>3</item>
+</answer>"#,
+    FieldType::list(FieldType::int()),
+    format = ResponseFormat::Xml,
+    [1, 2, 3]
+);
+
+test_deserializer!(
+    test_xml_string_keeps_text,
+    r#"
+class Contact {
+  zip string
+  age int
+}
+"#,
+    r#"<Contact><zip>02134</zip><age>41</age></Contact>"#,
+    FieldType::class("Contact"),
+    format = ResponseFormat::Xml,
+    {"zip": "02134", "age": 41}
+);
+
+test_partial_deserializer!(
+    test_xml_partial,
+    RESUME_FILE,
+    r#"<Resume>
+  <name>Grace Hopper</name>
+  <level>Senior</level>
+  <skills>
+    <item>COBOL</item>
+    <item>compi"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Xml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": ["COBOL", "compi"],
+        "jobs": [],
+        "links": {},
+        "bio": null
+    }
+);
+
+test_partial_deserializer!(
+    test_xml_partial_tag,
+    RESUME_FILE,
+    r#"<Resume>
+  <name>Grace Hopper</name>
+  <level>Senior</level>
+  <ski"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Xml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": [],
+        "jobs": [],
+        "links": {},
+        "bio": null
+    }
+);
+
+test_deserializer!(
+    test_xml_with_prose,
+    RESUME_FILE,
+    r#"Here is the resume you asked for:
+
+<Resume>
+  <name>Grace Hopper</name>
+  <level>Senior</level>
+  <skills><item>COBOL</item></skills>
+</Resume>"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Xml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": ["COBOL"],
+        "jobs": [],
+        "links": {},
+        "bio": null
+    }
+);
+
+// Unless the prompt asked for XML, responses are only parsed as JSON.
+test_deserializer!(
+    test_xml_not_parsed_by_default,
+    "",
+    r#"<b>42</b>"#,
+    FieldType::string(),
+    "<b>42</b>"
+);
+
+test_failing_deserializer!(
+    test_xml_class_not_parsed_by_default,
+    RESUME_FILE,
+    r#"<Resume>
+  <name>Grace Hopper</name>
+  <level>Senior</level>
+</Resume>"#,
+    FieldType::class("Resume")
+);
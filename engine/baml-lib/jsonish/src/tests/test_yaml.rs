@@ -0,0 +1,216 @@
+use crate::ResponseFormat;
+
+use super::*;
+
+const RESUME_FILE: &str = r#"
+enum Level {
+  Junior
+  Senior
+}
+
+class Job {
+  company string
+  years int
+}
+
+class Resume {
+  name string
+  level Level
+  skills string[]
+  jobs Job[]
+  bio string?
+}
+"#;
+
+test_deserializer!(
+    test_yaml_class,
+    RESUME_FILE,
+    r#"name: Grace Hopper
+level: Senior
+skills:
+  - COBOL
+  - "compilers"
+jobs:
+  - company: US Navy
+    years: 43
+  - company: Remington Rand
+    years: 3
+bio: |
+  Rear admiral.
+  Computer pioneer.
+"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Yaml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": ["COBOL", "compilers"],
+        "jobs": [
+            {"company": "US Navy", "years": 43},
+            {"company": "Remington Rand", "years": 3}
+        ],
+        "bio": "Rear admiral.\nComputer pioneer."
+    }
+);
+
+test_deserializer!(
+    test_yaml_flow_collections_and_comments,
+    RESUME_FILE,
+    r#"# The candidate
+name: 'Grace ''Amazing'' Hopper' # nickname included
+level: Junior
+skills: [COBOL, FLOW-MATIC]
+jobs: []
+"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Yaml,
+    {
+        "name": "Grace 'Amazing' Hopper",
+        "level": "Junior",
+        "skills": ["COBOL", "FLOW-MATIC"],
+        "jobs": [],
+        "bio": null
+    }
+);
+
+test_deserializer!(
+    test_yaml_in_markdown,
+    RESUME_FILE,
+    r#"Here is the resume:
+
+```yaml
+name: Grace Hopper
+level: Senior
+skills:
+- COBOL
+jobs: []
+```
+"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Yaml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": ["COBOL"],
+        "jobs": [],
+        "bio": null
+    }
+);
+
+test_deserializer!(
+    test_yaml_list,
+    "",
+    r#"- 1
+- 2
+- 3"#,
+    FieldType::list(FieldType::int()),
+    format = ResponseFormat::Yaml,
+    [1, 2, 3]
+);
+
+test_deserializer!(
+    test_yaml_map,
+    "",
+    r#"zip: 02134
+city: Boston"#,
+    FieldType::map(FieldType::string(), FieldType::string()),
+    format = ResponseFormat::Yaml,
+    {"zip": "02134", "city": "Boston"}
+);
+
+test_deserializer!(
+    test_yaml_ignored_for_json,
+    RESUME_FILE,
+    r#"{"name": "Grace Hopper", "level": "Senior", "skills": [], "jobs": []}"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Yaml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": [],
+        "jobs": [],
+        "bio": null
+    }
+);
+
+test_partial_deserializer!(
+    test_yaml_partial,
+    RESUME_FILE,
+    r#"name: Grace Hopper
+level: Senior
+skills:
+  - COBOL
+  - compi"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Yaml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": ["COBOL", "compi"],
+        "jobs": [],
+        "bio": null
+    }
+);
+
+test_partial_deserializer!(
+    test_yaml_partial_number,
+    RESUME_FILE,
+    r#"name: Grace Hopper
+level: Senior
+skills: []
+jobs:
+  - company: US Navy
+    years: 4"#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Yaml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": [],
+        "jobs": [{"company": "US Navy", "years": null}],
+        "bio": null
+    }
+);
+
+test_deserializer!(
+    test_yaml_with_prose,
+    RESUME_FILE,
+    r#"Sure, here you go.
+
+name: Grace Hopper
+level: Senior
+skills:
+  - COBOL
+jobs: []
+
+Let me know if you need anything else."#,
+    FieldType::class("Resume"),
+    format = ResponseFormat::Yaml,
+    {
+        "name": "Grace Hopper",
+        "level": "Senior",
+        "skills": ["COBOL"],
+        "jobs": [],
+        "bio": null
+    }
+);
+
+// Unless the prompt asked for YAML, responses are only parsed as JSON.
+test_deserializer!(
+    test_yaml_not_parsed_by_default,
+    "",
+    r#"name: Grace Hopper
+level: Senior"#,
+    FieldType::string(),
+    "name: Grace Hopper\nlevel: Senior"
+);
+
+test_failing_deserializer!(
+    test_yaml_class_not_parsed_by_default,
+    RESUME_FILE,
+    r#"name: Grace Hopper
+level: Senior
+skills: []
+jobs: []"#,
+    FieldType::class("Resume")
+);
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::{AllowedRoleMetadata, FinishReasonFilter, RolesSelection, SupportedRequestModes, UnresolvedAllowedRoleMetadata, UnresolvedFinishReasonFilter, UnresolvedRolesSelection};
+use crate::{AllowedRoleMetadata, FinishReasonFilter, HttpConfig, RolesSelection, SupportedRequestModes, UnresolvedAllowedRoleMetadata, UnresolvedFinishReasonFilter, UnresolvedHttpConfig, UnresolvedRolesSelection};
 use anyhow::Result;
 
 use baml_types::{EvaluationContext, StringOr, UnresolvedValue};
@@ -15,6 +15,8 @@ pub struct UnresolvedAnthropic<Meta> {
     role_selection: UnresolvedRolesSelection,
     allowed_metadata: UnresolvedAllowedRoleMetadata,
     supported_request_modes: SupportedRequestModes,
+    max_context_tokens: Option<usize>,
+    http: UnresolvedHttpConfig,
     headers: IndexMap<String, StringOr>,
     properties: IndexMap<String, (Meta, UnresolvedValue<Meta>)>,
     finish_reason_filter: UnresolvedFinishReasonFilter,
@@ -28,6 +30,8 @@ impl<Meta> UnresolvedAnthropic<Meta> {
             role_selection: self.role_selection.clone(),
             allowed_metadata: self.allowed_metadata.clone(),
             supported_request_modes: self.supported_request_modes.clone(),
+            max_context_tokens: self.max_context_tokens,
+            http: self.http.clone(),
             headers: self
                 .headers
                 .iter()
@@ -49,6 +53,8 @@ pub struct ResolvedAnthropic {
     role_selection: RolesSelection,
     pub allowed_metadata: AllowedRoleMetadata,
     pub supported_request_modes: SupportedRequestModes,
+    pub max_context_tokens: Option<usize>,
+    pub http: HttpConfig,
     pub headers: IndexMap<String, String>,
     pub properties: IndexMap<String, serde_json::Value>,
     pub proxy_url: Option<String>,
@@ -84,6 +90,7 @@ impl<Meta: Clone> UnresolvedAnthropic<Meta> {
         env_vars.extend(self.role_selection.required_env_vars());
         env_vars.extend(self.allowed_metadata.required_env_vars());
         env_vars.extend(self.supported_request_modes.required_env_vars());
+        env_vars.extend(self.http.required_env_vars());
         env_vars.extend(self.headers.values().flat_map(|v| v.required_env_vars()));
         env_vars.extend(
             self.properties
@@ -128,6 +135,8 @@ impl<Meta: Clone> UnresolvedAnthropic<Meta> {
             role_selection: self.role_selection.resolve(ctx)?,
             allowed_metadata: self.allowed_metadata.resolve(ctx)?,
             supported_request_modes: self.supported_request_modes.clone(),
+            max_context_tokens: self.max_context_tokens,
+            http: self.http.resolve(ctx)?,
             headers,
             properties,
             proxy_url: super::helpers::get_proxy_url(ctx),
@@ -146,6 +155,8 @@ impl<Meta: Clone> UnresolvedAnthropic<Meta> {
         let role_selection = properties.ensure_roles_selection();
         let allowed_metadata = properties.ensure_allowed_metadata();
         let supported_request_modes = properties.ensure_supported_request_modes();
+        let max_context_tokens = properties.ensure_max_context_tokens();
+        let http = properties.ensure_http_config();
         let headers = properties.ensure_headers().unwrap_or_default();
         let finish_reason_filter = properties.ensure_finish_reason_filter();
         let (properties, errors) = properties.finalize();
@@ -159,6 +170,8 @@ impl<Meta: Clone> UnresolvedAnthropic<Meta> {
             role_selection,
             allowed_metadata,
             supported_request_modes,
+            max_context_tokens,
+            http,
             headers,
             properties,
             finish_reason_filter,
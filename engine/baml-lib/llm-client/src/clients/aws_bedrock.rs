@@ -16,6 +16,7 @@ pub struct UnresolvedAwsBedrock {
     role_selection: UnresolvedRolesSelection,
     allowed_role_metadata: UnresolvedAllowedRoleMetadata,
     supported_request_modes: SupportedRequestModes,
+    max_context_tokens: Option<usize>,
     inference_config: Option<UnresolvedInferenceConfiguration>,
     finish_reason_filter: UnresolvedFinishReasonFilter,
 }
@@ -67,6 +68,7 @@ pub struct ResolvedAwsBedrock {
     role_selection: RolesSelection,
     pub allowed_role_metadata: AllowedRoleMetadata,
     pub supported_request_modes: SupportedRequestModes,
+    pub max_context_tokens: Option<usize>,
     pub finish_reason_filter: FinishReasonFilter,
 }
 
@@ -123,6 +125,7 @@ impl UnresolvedAwsBedrock {
             role_selection,
             allowed_role_metadata: self.allowed_role_metadata.resolve(ctx)?,
             supported_request_modes: self.supported_request_modes.clone(),
+            max_context_tokens: self.max_context_tokens,
             inference_config: self
                 .inference_config
                 .as_ref()
@@ -174,6 +177,7 @@ impl UnresolvedAwsBedrock {
         let role_selection = properties.ensure_roles_selection();
         let allowed_metadata = properties.ensure_allowed_metadata();
         let supported_request_modes = properties.ensure_supported_request_modes();
+        let max_context_tokens = properties.ensure_max_context_tokens();
 
         let inference_config = {
             let mut inference_config = UnresolvedInferenceConfiguration {
@@ -234,6 +238,15 @@ impl UnresolvedAwsBedrock {
         };
         let finish_reason_filter = properties.ensure_finish_reason_filter();
 
+        // The AWS SDK sends the requests with an HTTP client of its own, which
+        // the `http` options don't apply to.
+        if let Some((key_span, _)) = properties.ensure_any("http") {
+            properties.push_error(
+                "http is not supported by the aws-bedrock provider: its requests are sent by the AWS SDK, which doesn't use these options.",
+                key_span,
+            );
+        }
+
         // TODO: Handle inference_configuration
         let errors = properties.finalize_empty();
         if !errors.is_empty() {
@@ -248,6 +261,7 @@ impl UnresolvedAwsBedrock {
             role_selection,
             allowed_role_metadata: allowed_metadata,
             supported_request_modes,
+            max_context_tokens,
             inference_config,
             finish_reason_filter,
         })
@@ -3,7 +3,8 @@ use std::collections::HashSet;
 use crate::{AllowedRoleMetadata, SupportedRequestModes, UnresolvedAllowedRoleMetadata};
 use anyhow::Result;
 use crate::{
-    FinishReasonFilter, RolesSelection, UnresolvedFinishReasonFilter, UnresolvedRolesSelection
+    FinishReasonFilter, HttpConfig, RolesSelection, UnresolvedFinishReasonFilter, UnresolvedHttpConfig,
+    UnresolvedRolesSelection,
 };
 
 use baml_types::{EvaluationContext, StringOr, UnresolvedValue};
@@ -20,6 +21,8 @@ pub struct UnresolvedGoogleAI<Meta> {
     model: Option<StringOr>,
     allowed_metadata: UnresolvedAllowedRoleMetadata,
     supported_request_modes: SupportedRequestModes,
+    max_context_tokens: Option<usize>,
+    http: UnresolvedHttpConfig,
     finish_reason_filter: UnresolvedFinishReasonFilter,
     properties: IndexMap<String, (Meta, UnresolvedValue<Meta>)>,
 }
@@ -38,6 +41,8 @@ impl<Meta> UnresolvedGoogleAI<Meta> {
                 .collect(),
             allowed_metadata: self.allowed_metadata.clone(),
             supported_request_modes: self.supported_request_modes.clone(),
+            max_context_tokens: self.max_context_tokens,
+            http: self.http.clone(),
             properties: self
                 .properties
                 .iter()
@@ -56,6 +61,8 @@ pub struct ResolvedGoogleAI {
     pub headers: IndexMap<String, String>,
     pub allowed_metadata: AllowedRoleMetadata,
     pub supported_request_modes: SupportedRequestModes,
+    pub max_context_tokens: Option<usize>,
+    pub http: HttpConfig,
     pub properties: IndexMap<String, serde_json::Value>,
     pub proxy_url: Option<String>,
     pub finish_reason_filter: FinishReasonFilter,
@@ -92,6 +99,7 @@ impl<Meta: Clone> UnresolvedGoogleAI<Meta> {
         env_vars.extend(self.role_selection.required_env_vars());
         env_vars.extend(self.allowed_metadata.required_env_vars());
         env_vars.extend(self.supported_request_modes.required_env_vars());
+        env_vars.extend(self.http.required_env_vars());
         env_vars.extend(
             self.properties
                 .values()
@@ -127,6 +135,8 @@ impl<Meta: Clone> UnresolvedGoogleAI<Meta> {
             headers,
             allowed_metadata: self.allowed_metadata.resolve(ctx)?,
             supported_request_modes: self.supported_request_modes.clone(),
+            max_context_tokens: self.max_context_tokens,
+            http: self.http.resolve(ctx)?,
             properties: self
                 .properties
                 .iter()
@@ -151,6 +161,8 @@ impl<Meta: Clone> UnresolvedGoogleAI<Meta> {
 
         let allowed_metadata = properties.ensure_allowed_metadata();
         let supported_request_modes = properties.ensure_supported_request_modes();
+        let max_context_tokens = properties.ensure_max_context_tokens();
+        let http = properties.ensure_http_config();
         let headers = properties.ensure_headers().unwrap_or_default();
         let finish_reason_filter = properties.ensure_finish_reason_filter();
         let (properties, errors) = properties.finalize();
@@ -167,6 +179,8 @@ impl<Meta: Clone> UnresolvedGoogleAI<Meta> {
             headers,
             allowed_metadata,
             supported_request_modes,
+            max_context_tokens,
+            http,
             properties,
             finish_reason_filter,
         })
@@ -5,7 +5,7 @@ use indexmap::IndexMap;
 
 use crate::{
     SupportedRequestModes, UnresolvedAllowedRoleMetadata, UnresolvedFinishReasonFilter,
-    UnresolvedRolesSelection,
+    UnresolvedHttpConfig, UnresolvedRolesSelection, UnresolvedTokenAuth,
 };
 
 #[derive(Debug, Clone)]
@@ -244,6 +244,17 @@ impl<Meta: Clone> PropertyHandler<Meta> {
         }
     }
 
+    pub fn ensure_max_context_tokens(&mut self) -> Option<usize> {
+        let (_, value, meta) = self.ensure_int("max_context_tokens", false)?;
+        match usize::try_from(value) {
+            Ok(value) if value > 0 => Some(value),
+            _ => {
+                self.push_error("max_context_tokens must be a positive integer.", meta);
+                None
+            }
+        }
+    }
+
     pub fn ensure_finish_reason_filter(&mut self) -> UnresolvedFinishReasonFilter {
         let allow_list = self.ensure_array("finish_reason_allow_list", false);
         let deny_list = self.ensure_array("finish_reason_deny_list", false);
@@ -293,6 +304,135 @@ impl<Meta: Clone> PropertyHandler<Meta> {
         }
     }
 
+    pub fn ensure_http_config(&mut self) -> UnresolvedHttpConfig {
+        let Some((_, options, span)) = self.ensure_map("http", false) else {
+            return UnresolvedHttpConfig::default();
+        };
+        let mut http = PropertyHandler::new(options, span);
+
+        let proxy = http.ensure_string("proxy", false).map(|(_, value, meta)| {
+            if let StringOr::Value(url) = &value {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    http.push_error(
+                        format!("proxy must be an http:// or https:// URL. Got: {url}"),
+                        meta,
+                    );
+                }
+            }
+            value
+        });
+        let ca_cert = http.ensure_string("ca_cert", false).map(|(_, v, _)| v);
+        let client_cert = http.ensure_string("client_cert", false);
+        let client_key = http.ensure_string("client_key", false);
+        match (&client_cert, &client_key) {
+            (Some((key_span, ..)), None) | (None, Some((key_span, ..))) => http.push_error(
+                "client_cert and client_key must be set together",
+                key_span.clone(),
+            ),
+            _ => {}
+        }
+        let pool_max_idle_per_host =
+            http.ensure_int("pool_max_idle_per_host", false)
+                .and_then(|(_, value, meta)| {
+                    let value = usize::try_from(value).ok();
+                    if value.is_none() {
+                        http.push_error("pool_max_idle_per_host must not be negative.", meta);
+                    }
+                    value
+                });
+        let pool_idle_timeout_ms =
+            http.ensure_int("pool_idle_timeout_ms", false)
+                .and_then(|(_, value, meta)| {
+                    let value = u64::try_from(value).ok();
+                    if value.is_none() {
+                        http.push_error("pool_idle_timeout_ms must not be negative.", meta);
+                    }
+                    value
+                });
+        let http1_only = http
+            .ensure_bool("http1_only", false)
+            .is_some_and(|(_, value, _)| value);
+
+        self.errors.extend(http.finalize_empty());
+
+        UnresolvedHttpConfig {
+            proxy,
+            ca_cert,
+            client_cert: client_cert.map(|(_, v, _)| v),
+            client_key: client_key.map(|(_, v, _)| v),
+            pool_max_idle_per_host,
+            pool_idle_timeout_ms,
+            http1_only,
+        }
+    }
+
+    pub fn ensure_token_auth(&mut self) -> Option<(Meta, UnresolvedTokenAuth)> {
+        let (key_span, options, span) = self.ensure_map("auth", false)?;
+        let mut auth = PropertyHandler::new(options, span);
+
+        let r#type = auth.ensure_string("type", true);
+        let client_id = auth.ensure_string("client_id", true).map(|(_, v, _)| v);
+        let client_secret = auth.ensure_string("client_secret", true).map(|(_, v, _)| v);
+        let scope = auth.ensure_string("scope", false).map(|(_, v, _)| v);
+
+        let token_auth = match r#type {
+            Some((_, StringOr::Value(r#type), meta)) => match r#type.as_str() {
+                "azure_ad" => {
+                    let tenant_id = auth.ensure_string("tenant_id", true).map(|(_, v, _)| v);
+                    let authority_host = auth
+                        .ensure_string("authority_host", false)
+                        .map(|(_, v, _)| v);
+                    match (tenant_id, client_id, client_secret) {
+                        (Some(tenant_id), Some(client_id), Some(client_secret)) => {
+                            Some(UnresolvedTokenAuth::AzureAd {
+                                tenant_id,
+                                client_id,
+                                client_secret,
+                                scope,
+                                authority_host,
+                            })
+                        }
+                        _ => None,
+                    }
+                }
+                "client_credentials" => {
+                    let token_url = auth.ensure_string("token_url", true).map(|(_, v, _)| v);
+                    let audience = auth.ensure_string("audience", false).map(|(_, v, _)| v);
+                    match (token_url, client_id, client_secret) {
+                        (Some(token_url), Some(client_id), Some(client_secret)) => {
+                            Some(UnresolvedTokenAuth::ClientCredentials {
+                                token_url,
+                                client_id,
+                                client_secret,
+                                scope,
+                                audience,
+                            })
+                        }
+                        _ => None,
+                    }
+                }
+                other => {
+                    auth.push_error(
+                        format!(
+                            "auth.type must be one of: azure_ad, client_credentials. Got: {other}"
+                        ),
+                        meta,
+                    );
+                    None
+                }
+            },
+            Some((_, _, meta)) => {
+                auth.push_error("auth.type must be a string literal.", meta);
+                None
+            }
+            None => None,
+        };
+
+        self.errors.extend(auth.finalize_empty());
+
+        token_auth.map(|token_auth| (key_span, token_auth))
+    }
+
     pub fn ensure_any(&mut self, key: &str) -> Option<(Meta, UnresolvedValue<Meta>)> {
         self.options.shift_remove(key)
     }
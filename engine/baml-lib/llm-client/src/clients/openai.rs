@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::{AllowedRoleMetadata, FinishReasonFilter, RolesSelection, SupportedRequestModes, UnresolvedAllowedRoleMetadata, UnresolvedFinishReasonFilter, UnresolvedRolesSelection};
+use crate::{AllowedRoleMetadata, ClientCredentials, FinishReasonFilter, HttpConfig, RolesSelection, SupportedRequestModes, UnresolvedAllowedRoleMetadata, UnresolvedFinishReasonFilter, UnresolvedHttpConfig, UnresolvedRolesSelection, UnresolvedTokenAuth};
 use anyhow::Result;
 
 use baml_types::{GetEnvVar, StringOr, UnresolvedValue};
@@ -12,9 +12,12 @@ use super::helpers::{Error, PropertyHandler, UnresolvedUrl};
 pub struct UnresolvedOpenAI<Meta> {
     base_url: Option<either::Either<UnresolvedUrl, (StringOr, StringOr)>>,
     api_key: Option<StringOr>,
+    auth: Option<UnresolvedTokenAuth>,
     role_selection: UnresolvedRolesSelection,
     allowed_role_metadata: UnresolvedAllowedRoleMetadata,
     supported_request_modes: SupportedRequestModes,
+    max_context_tokens: Option<usize>,
+    http: UnresolvedHttpConfig,
     headers: IndexMap<String, StringOr>,
     properties: IndexMap<String, (Meta, UnresolvedValue<Meta>)>,
     query_params: IndexMap<String, StringOr>,
@@ -26,9 +29,12 @@ impl<Meta> UnresolvedOpenAI<Meta> {
         UnresolvedOpenAI {
             base_url: self.base_url.clone(),
             api_key: self.api_key.clone(),
+            auth: self.auth.clone(),
             role_selection: self.role_selection.clone(),
             allowed_role_metadata: self.allowed_role_metadata.clone(),
             supported_request_modes: self.supported_request_modes.clone(),
+            max_context_tokens: self.max_context_tokens,
+            http: self.http.clone(),
             headers: self
                 .headers
                 .iter()
@@ -52,9 +58,13 @@ impl<Meta> UnresolvedOpenAI<Meta> {
 pub struct ResolvedOpenAI {
     pub base_url: String,
     pub api_key: Option<String>,
+    /// Sent as a bearer token in place of `api_key`.
+    pub auth: Option<ClientCredentials>,
     role_selection: RolesSelection,
     pub allowed_metadata: AllowedRoleMetadata,
     supported_request_modes: SupportedRequestModes,
+    pub max_context_tokens: Option<usize>,
+    pub http: HttpConfig,
     pub headers: IndexMap<String, String>,
     pub properties: IndexMap<String, serde_json::Value>,
     pub query_params: IndexMap<String, String>,
@@ -112,9 +122,13 @@ impl<Meta: Clone> UnresolvedOpenAI<Meta> {
         if let Some(key) = self.api_key.as_ref() {
             env_vars.extend(key.required_env_vars())
         }
+        if let Some(auth) = self.auth.as_ref() {
+            env_vars.extend(auth.required_env_vars())
+        }
         env_vars.extend(self.role_selection.required_env_vars());
         env_vars.extend(self.allowed_role_metadata.required_env_vars());
         env_vars.extend(self.supported_request_modes.required_env_vars());
+        env_vars.extend(self.http.required_env_vars());
         self.headers
             .iter()
             .for_each(|(_, v)| env_vars.extend(v.required_env_vars()));
@@ -158,6 +172,12 @@ impl<Meta: Clone> UnresolvedOpenAI<Meta> {
             .map(|key| key.resolve(ctx))
             .transpose()?;
 
+        let auth = self
+            .auth
+            .as_ref()
+            .map(|auth| auth.resolve(ctx))
+            .transpose()?;
+
         let role_selection = self.role_selection.resolve(ctx)?;
 
         let headers = self
@@ -194,9 +214,12 @@ impl<Meta: Clone> UnresolvedOpenAI<Meta> {
         Ok(ResolvedOpenAI {
             base_url,
             api_key,
+            auth,
             role_selection,
             allowed_metadata: self.allowed_role_metadata.resolve(ctx)?,
             supported_request_modes: self.supported_request_modes.clone(),
+            max_context_tokens: self.max_context_tokens,
+            http: self.http.resolve(ctx)?,
             headers,
             properties,
             query_params,
@@ -265,10 +288,7 @@ impl<Meta: Clone> UnresolvedOpenAI<Meta> {
             }
         };
 
-        let api_key = properties
-            .ensure_api_key()
-            .map(|v| v.clone())
-            .unwrap_or_else(|| StringOr::EnvVar("AZURE_OPENAI_API_KEY".to_string()));
+        let (api_key, auth) = Self::ensure_api_key_or_auth(&mut properties);
 
         let mut query_params = IndexMap::new();
         if let Some((_, v, _)) = properties.ensure_string("api_version", false) {
@@ -277,7 +297,13 @@ impl<Meta: Clone> UnresolvedOpenAI<Meta> {
 
         let mut instance = Self::create_common(properties, base_url, None)?;
         instance.query_params = query_params;
-        instance.headers.entry("api-key".to_string()).or_insert(api_key);
+        // Azure AD tokens are sent as a bearer token instead of the api-key header.
+        if auth.is_none() {
+            let api_key = api_key
+                .unwrap_or_else(|| StringOr::EnvVar("AZURE_OPENAI_API_KEY".to_string()));
+            instance.headers.entry("api-key".to_string()).or_insert(api_key);
+        }
+        instance.auth = auth;
 
         Ok(instance)
     }
@@ -285,13 +311,16 @@ impl<Meta: Clone> UnresolvedOpenAI<Meta> {
     pub fn create_generic(mut properties: PropertyHandler<Meta>) -> Result<Self, Vec<Error<Meta>>> {
         let base_url = properties.ensure_base_url(true);
 
-        let api_key = properties.ensure_api_key();
+        let (api_key, auth) = Self::ensure_api_key_or_auth(&mut properties);
 
-        Self::create_common(
+        let mut instance = Self::create_common(
             properties,
             base_url.map(|url| either::Either::Left(url.1)),
             api_key,
-        )
+        )?;
+        instance.auth = auth;
+
+        Ok(instance)
     }
 
     pub fn create_ollama(mut properties: PropertyHandler<Meta>) -> Result<Self, Vec<Error<Meta>>> {
@@ -309,6 +338,23 @@ impl<Meta: Clone> UnresolvedOpenAI<Meta> {
         Ok(instance)
     }
 
+    fn ensure_api_key_or_auth(
+        properties: &mut PropertyHandler<Meta>,
+    ) -> (Option<StringOr>, Option<UnresolvedTokenAuth>) {
+        let api_key = properties.ensure_string("api_key", false);
+        let auth = properties.ensure_token_auth();
+
+        match (api_key, auth) {
+            (Some((key_1_span, ..)), Some((key_2_span, _))) => {
+                for key in [key_1_span, key_2_span] {
+                    properties.push_error("Only one of api_key or auth must be provided", key);
+                }
+                (None, None)
+            }
+            (api_key, auth) => (api_key.map(|(_, v, _)| v), auth.map(|(_, auth)| auth)),
+        }
+    }
+
     fn create_common(
         mut properties: PropertyHandler<Meta>,
         base_url: Option<either::Either<UnresolvedUrl, (StringOr, StringOr)>>,
@@ -317,6 +363,8 @@ impl<Meta: Clone> UnresolvedOpenAI<Meta> {
         let role_selection = properties.ensure_roles_selection();
         let allowed_metadata = properties.ensure_allowed_metadata();
         let supported_request_modes = properties.ensure_supported_request_modes();
+        let max_context_tokens = properties.ensure_max_context_tokens();
+        let http = properties.ensure_http_config();
         let headers = properties.ensure_headers().unwrap_or_default();
         let finish_reason_filter = properties.ensure_finish_reason_filter();
         let (properties, errors) = properties.finalize();
@@ -328,9 +376,12 @@ impl<Meta: Clone> UnresolvedOpenAI<Meta> {
         Ok(Self {
             base_url,
             api_key,
+            auth: None,
             role_selection,
             allowed_role_metadata: allowed_metadata,
             supported_request_modes,
+            max_context_tokens,
+            http,
             headers,
             properties,
             query_params: IndexMap::new(),
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use crate::{AllowedRoleMetadata, FinishReasonFilter, RolesSelection, SupportedRequestModes, UnresolvedAllowedRoleMetadata, UnresolvedFinishReasonFilter, UnresolvedRolesSelection};
+use crate::{AllowedRoleMetadata, FinishReasonFilter, HttpConfig, RolesSelection, SupportedRequestModes, UnresolvedAllowedRoleMetadata, UnresolvedFinishReasonFilter, UnresolvedHttpConfig, UnresolvedRolesSelection};
 use anyhow::{Context, Result};
 
 use baml_types::{GetEnvVar, StringOr, UnresolvedValue};
@@ -17,7 +17,7 @@ enum UnresolvedServiceAccountDetails<Meta> {
     Json(StringOr),
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct ServiceAccount {
     pub token_uri: String,
     pub project_id: String,
@@ -123,6 +123,8 @@ pub struct UnresolvedVertex<Meta> {
     role_selection: UnresolvedRolesSelection,
     allowed_role_metadata: UnresolvedAllowedRoleMetadata,
     supported_request_modes: SupportedRequestModes,
+    max_context_tokens: Option<usize>,
+    http: UnresolvedHttpConfig,
     finish_reason_filter: UnresolvedFinishReasonFilter,
     properties: IndexMap<String, (Meta, UnresolvedValue<Meta>)>,
 }
@@ -135,6 +137,8 @@ pub struct ResolvedVertex {
     role_selection: RolesSelection,
     pub allowed_metadata: AllowedRoleMetadata,
     pub supported_request_modes: SupportedRequestModes,
+    pub max_context_tokens: Option<usize>,
+    pub http: HttpConfig,
     pub properties: IndexMap<String, serde_json::Value>,
     pub proxy_url: Option<String>,
     pub finish_reason_filter: FinishReasonFilter,
@@ -175,6 +179,7 @@ impl<Meta: Clone> UnresolvedVertex<Meta> {
         env_vars.extend(self.role_selection.required_env_vars());
         env_vars.extend(self.allowed_role_metadata.required_env_vars());
         env_vars.extend(self.supported_request_modes.required_env_vars());
+        env_vars.extend(self.http.required_env_vars());
         env_vars.extend(
             self.properties
                 .values()
@@ -194,6 +199,8 @@ impl<Meta: Clone> UnresolvedVertex<Meta> {
             role_selection: self.role_selection.clone(),
             allowed_role_metadata: self.allowed_role_metadata.clone(),
             supported_request_modes: self.supported_request_modes.clone(),
+            max_context_tokens: self.max_context_tokens,
+            http: self.http.clone(),
             properties: self
                 .properties
                 .iter()
@@ -249,6 +256,8 @@ impl<Meta: Clone> UnresolvedVertex<Meta> {
             role_selection,
             allowed_metadata: self.allowed_role_metadata.resolve(ctx)?,
             supported_request_modes: self.supported_request_modes.clone(),
+            max_context_tokens: self.max_context_tokens,
+            http: self.http.resolve(ctx)?,
             properties: self
                 .properties
                 .iter()
@@ -346,6 +355,8 @@ impl<Meta: Clone> UnresolvedVertex<Meta> {
         let role_selection = properties.ensure_roles_selection();
         let allowed_metadata = properties.ensure_allowed_metadata();
         let supported_request_modes = properties.ensure_supported_request_modes();
+        let max_context_tokens = properties.ensure_max_context_tokens();
+        let http = properties.ensure_http_config();
         let headers = properties.ensure_headers().unwrap_or_default();
         let finish_reason_filter = properties.ensure_finish_reason_filter();
 
@@ -367,6 +378,8 @@ impl<Meta: Clone> UnresolvedVertex<Meta> {
             role_selection,
             allowed_role_metadata: allowed_metadata,
             supported_request_modes,
+            max_context_tokens,
+            http,
             properties,
             finish_reason_filter,
         })
@@ -412,3 +412,173 @@ impl AllowedRoleMetadata {
         }
     }
 }
+
+/// Transport settings for the HTTP client a provider sends its requests with,
+/// from the `http` client option.
+#[derive(Clone, Debug, Default)]
+pub struct UnresolvedHttpConfig {
+    pub proxy: Option<StringOr>,
+    pub ca_cert: Option<StringOr>,
+    pub client_cert: Option<StringOr>,
+    pub client_key: Option<StringOr>,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout_ms: Option<u64>,
+    pub http1_only: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HttpConfig {
+    /// Proxy for all requests, e.g. `http://proxy.internal:8080`.
+    pub proxy: Option<String>,
+    /// Path to a PEM bundle of root certificates, trusted in addition to the
+    /// system's.
+    pub ca_cert: Option<String>,
+    /// Paths to the PEM certificate and PKCS#8 key used for mTLS.
+    pub client_identity: Option<(String, String)>,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout: Option<std::time::Duration>,
+    pub http1_only: bool,
+}
+
+impl UnresolvedHttpConfig {
+    pub fn required_env_vars(&self) -> HashSet<String> {
+        [
+            &self.proxy,
+            &self.ca_cert,
+            &self.client_cert,
+            &self.client_key,
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(|s| s.required_env_vars())
+        .collect()
+    }
+
+    pub fn resolve(&self, ctx: &impl GetEnvVar) -> Result<HttpConfig> {
+        let resolve = |s: &Option<StringOr>| s.as_ref().map(|s| s.resolve(ctx)).transpose();
+
+        let client_identity = match (resolve(&self.client_cert)?, resolve(&self.client_key)?) {
+            (Some(cert), Some(key)) => Some((cert, key)),
+            (None, None) => None,
+            _ => anyhow::bail!("http.client_cert and http.client_key must be set together"),
+        };
+
+        Ok(HttpConfig {
+            proxy: resolve(&self.proxy)?,
+            ca_cert: resolve(&self.ca_cert)?,
+            client_identity,
+            pool_max_idle_per_host: self.pool_max_idle_per_host,
+            pool_idle_timeout: self
+                .pool_idle_timeout_ms
+                .map(std::time::Duration::from_millis),
+            http1_only: self.http1_only,
+        })
+    }
+}
+
+/// Where a provider gets short-lived bearer tokens from, from the `auth`
+/// client option.
+#[derive(Clone, Debug)]
+pub enum UnresolvedTokenAuth {
+    /// Microsoft Entra ID (Azure AD) client credentials.
+    AzureAd {
+        tenant_id: StringOr,
+        client_id: StringOr,
+        client_secret: StringOr,
+        scope: Option<StringOr>,
+        authority_host: Option<StringOr>,
+    },
+    /// Any OAuth2 token endpoint supporting the client credentials grant.
+    ClientCredentials {
+        token_url: StringOr,
+        client_id: StringOr,
+        client_secret: StringOr,
+        scope: Option<StringOr>,
+        audience: Option<StringOr>,
+    },
+}
+
+/// An OAuth2 client credentials grant, sent as a form to `token_url`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientCredentials {
+    pub token_url: String,
+    pub client_id: String,
+    pub client_secret: String,
+    pub scope: Option<String>,
+    pub audience: Option<String>,
+}
+
+const AZURE_AD_AUTHORITY_HOST: &str = "https://login.microsoftonline.com";
+const AZURE_AD_DEFAULT_SCOPE: &str = "https://cognitiveservices.azure.com/.default";
+
+impl UnresolvedTokenAuth {
+    pub fn required_env_vars(&self) -> HashSet<String> {
+        let (required, optional) = match self {
+            Self::AzureAd {
+                tenant_id,
+                client_id,
+                client_secret,
+                scope,
+                authority_host,
+            } => (
+                [tenant_id, client_id, client_secret],
+                [scope, authority_host],
+            ),
+            Self::ClientCredentials {
+                token_url,
+                client_id,
+                client_secret,
+                scope,
+                audience,
+            } => ([token_url, client_id, client_secret], [scope, audience]),
+        };
+        required
+            .into_iter()
+            .chain(optional.into_iter().flatten())
+            .flat_map(|s| s.required_env_vars())
+            .collect()
+    }
+
+    pub fn resolve(&self, ctx: &impl GetEnvVar) -> Result<ClientCredentials> {
+        let resolve = |s: &Option<StringOr>| s.as_ref().map(|s| s.resolve(ctx)).transpose();
+
+        match self {
+            Self::AzureAd {
+                tenant_id,
+                client_id,
+                client_secret,
+                scope,
+                authority_host,
+            } => {
+                let authority_host =
+                    resolve(authority_host)?.unwrap_or_else(|| AZURE_AD_AUTHORITY_HOST.to_string());
+                Ok(ClientCredentials {
+                    token_url: format!(
+                        "{}/{}/oauth2/v2.0/token",
+                        authority_host.trim_end_matches('/'),
+                        tenant_id.resolve(ctx)?
+                    ),
+                    client_id: client_id.resolve(ctx)?,
+                    client_secret: client_secret.resolve(ctx)?,
+                    scope: Some(
+                        resolve(scope)?.unwrap_or_else(|| AZURE_AD_DEFAULT_SCOPE.to_string()),
+                    ),
+                    audience: None,
+                })
+            }
+            Self::ClientCredentials {
+                token_url,
+                client_id,
+                client_secret,
+                scope,
+                audience,
+            } => Ok(ClientCredentials {
+                token_url: token_url.resolve(ctx)?,
+                client_id: client_id.resolve(ctx)?,
+                client_secret: client_secret.resolve(ctx)?,
+                scope: resolve(scope)?,
+                audience: resolve(audience)?,
+            }),
+        }
+    }
+}
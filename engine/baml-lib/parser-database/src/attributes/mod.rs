@@ -4,6 +4,7 @@ use internal_baml_schema_ast::ast::{Top, TopId, TypeExpId, TypeExpressionBlock};
 mod alias;
 pub mod constraint;
 mod description;
+mod streaming;
 mod to_string_attribute;
 use crate::interner::StringId;
 use crate::{context::Context, types::ClassAttributes, types::EnumAttributes};
@@ -25,8 +26,20 @@ pub struct Attributes {
     /// Whether the node should be skipped during prompt rendering and parsing.
     pub skip: Option<bool>,
 
+    /// Whether the value of the field is redacted from traces (@sensitive).
+    pub sensitive: Option<bool>,
+
     /// @check and @assert attributes attached to the node.
     pub constraints: Vec<Constraint>,
+
+    /// Whether the node should only be streamed once it is complete (@stream.done).
+    pub stream_done: Option<bool>,
+
+    /// Whether the parent should be held back until the node is present (@stream.not_null).
+    pub stream_not_null: Option<bool>,
+
+    /// Whether the node should be streamed along with its completion state (@stream.with_state).
+    pub stream_with_state: Option<bool>,
 }
 
 impl Attributes {
@@ -69,6 +82,46 @@ impl Attributes {
     pub fn set_skip(&mut self) {
         self.skip.replace(true);
     }
+
+    /// Get @sensitive.
+    pub fn sensitive(&self) -> &Option<bool> {
+        &self.sensitive
+    }
+
+    /// Set @sensitive.
+    pub fn set_sensitive(&mut self) {
+        self.sensitive.replace(true);
+    }
+
+    /// Get @stream.done.
+    pub fn stream_done(&self) -> &Option<bool> {
+        &self.stream_done
+    }
+
+    /// Set @stream.done.
+    pub fn set_stream_done(&mut self) {
+        self.stream_done.replace(true);
+    }
+
+    /// Get @stream.not_null.
+    pub fn stream_not_null(&self) -> &Option<bool> {
+        &self.stream_not_null
+    }
+
+    /// Set @stream.not_null.
+    pub fn set_stream_not_null(&mut self) {
+        self.stream_not_null.replace(true);
+    }
+
+    /// Get @stream.with_state.
+    pub fn stream_with_state(&self) -> &Option<bool> {
+        &self.stream_with_state
+    }
+
+    /// Set @stream.with_state.
+    pub fn set_stream_with_state(&mut self) {
+        self.stream_with_state.replace(true);
+    }
 }
 pub(super) fn resolve_attributes(ctx: &mut Context<'_>) {
     for top in ctx.ast.iter_tops() {
@@ -115,7 +168,9 @@ fn resolve_type_exp_block_attributes<'db>(
 
             for (field_idx, field) in ast_typexpr.iter_fields() {
                 ctx.assert_all_attributes_processed((type_id, field_idx).into());
-                if let Some(attrs) = to_string_attribute::visit(ctx, &field.span, false) {
+                let mut attrs = to_string_attribute::visit(ctx, &field.span, false);
+                streaming::visit(ctx, &mut attrs, false);
+                if let Some(attrs) = attrs {
                     class_attributes.field_serilizers.insert(field_idx, attrs);
                 }
                 ctx.validate_visited_attributes();
@@ -124,6 +179,7 @@ fn resolve_type_exp_block_attributes<'db>(
             // Now validate the class attributes.
             ctx.assert_all_attributes_processed(type_id.into());
             class_attributes.serilizer = to_string_attribute::visit(ctx, &span, true);
+            streaming::visit(ctx, &mut class_attributes.serilizer, true);
             ctx.validate_visited_attributes();
 
             ctx.types.class_attributes.insert(type_id, class_attributes);
@@ -0,0 +1,33 @@
+use crate::{context::Context, types::Attributes};
+
+/// Visit the `@stream.*` attributes of a class field, or the `@@stream.*`
+/// attributes of a class, merging them into `attributes`.
+///
+/// Only `@@stream.done` is meaningful at the block level: holding back the
+/// parent or reporting a completion state is a property of a field.
+pub(super) fn visit(ctx: &mut Context<'_>, attributes: &mut Option<Attributes>, as_block: bool) {
+    if ctx.visit_optional_single_attr("stream.done") {
+        attributes
+            .get_or_insert_with(Default::default)
+            .set_stream_done();
+        ctx.validate_visited_arguments();
+    }
+
+    if as_block {
+        return;
+    }
+
+    if ctx.visit_optional_single_attr("stream.not_null") {
+        attributes
+            .get_or_insert_with(Default::default)
+            .set_stream_not_null();
+        ctx.validate_visited_arguments();
+    }
+
+    if ctx.visit_optional_single_attr("stream.with_state") {
+        attributes
+            .get_or_insert_with(Default::default)
+            .set_stream_with_state();
+        ctx.validate_visited_arguments();
+    }
+}
@@ -31,6 +31,12 @@ pub(super) fn visit(ctx: &mut Context<'_>, span: &Span, as_block: bool) -> Optio
         ctx.validate_visited_arguments();
     }
 
+    if !as_block && ctx.visit_optional_single_attr("sensitive") {
+        attributes.set_sensitive();
+        modified = true;
+        ctx.validate_visited_arguments();
+    }
+
     if let Some((attribute_name, span)) = ctx.visit_repeated_attr_from_names(&["assert", "check"]) {
         visit_constraint_attributes(attribute_name, span, &mut attributes, ctx);
         modified = true;
@@ -0,0 +1,60 @@
+//! Built-in classes.
+//!
+//! BAML ships a few classes that users can reference without declaring them,
+//! like `ChatMessage` for passing conversation history to a function. They are
+//! declared in BAML source below and added to the AST before names are
+//! resolved, but only when the project references them, so projects that don't
+//! use them get no extra classes in their generated clients. A project that
+//! declares its own class or enum with the same name keeps its declaration.
+
+use internal_baml_diagnostics::{Diagnostics, SourceFile};
+use internal_baml_schema_ast::ast::{SchemaAst, Top, WithName};
+
+/// The built-in classes, by name.
+const BUILTIN_CLASSES: &[(&str, &str)] = &[(
+    "ChatMessage",
+    // A single message of a conversation, as expanded by `_.chat_history()`
+    // in prompts. Roles the client doesn't allow are remapped to its default
+    // role.
+    r#"
+class ChatMessage {
+  role string
+  content string | (string | image | audio)[]
+}
+"#,
+)];
+
+/// Adds the built-in classes the AST references to it.
+pub(crate) fn add_builtin_classes(ast: &mut SchemaAst, diagnostics: &mut Diagnostics) {
+    for (name, source) in BUILTIN_CLASSES {
+        if !is_referenced(ast, name) || is_declared(ast, name) {
+            continue;
+        }
+
+        let source = SourceFile::from((
+            diagnostics.root_path.join("baml_builtins.baml"),
+            source.to_string(),
+        ));
+        match internal_baml_schema_ast::parse_schema(&diagnostics.root_path, &source) {
+            Ok((builtins, builtin_diagnostics)) => {
+                diagnostics.push(builtin_diagnostics);
+                ast.tops.extend(builtins.tops);
+            }
+            Err(builtin_diagnostics) => diagnostics.push(builtin_diagnostics),
+        }
+    }
+}
+
+fn is_referenced(ast: &mut SchemaAst, name: &str) -> bool {
+    ast.tops.iter_mut().any(|top| {
+        top.field_types_mut()
+            .into_iter()
+            .any(|field_type| field_type.flat_idns().iter().any(|idn| idn.name() == name))
+    })
+}
+
+fn is_declared(ast: &SchemaAst, name: &str) -> bool {
+    ast.tops
+        .iter()
+        .any(|top| matches!(top, Top::Class(_) | Top::Enum(_)) && top.name() == name)
+}
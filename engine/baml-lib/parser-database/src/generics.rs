@@ -0,0 +1,440 @@
+//! Generic classes.
+//!
+//! Generic classes like `class Page<T> { items T[] }` are monomorphized before
+//! anything else looks at the AST: every instantiation like `Page<Resume>`
+//! becomes a concrete class (`PageOfResume`) whose fields have the type
+//! parameters replaced by the type arguments, and the instantiation itself is
+//! replaced by a reference to that class. The generic class declarations are
+//! removed from the AST, so the rest of the pipeline (name resolution, the IR,
+//! the output format and the parser) only ever sees concrete classes.
+//!
+//! Generators only see the concrete classes too, so generated clients have a
+//! `PageOfResume` class rather than a generic `Page[T]`. Emitting generic
+//! classes in the target languages is out of scope.
+
+use std::collections::{HashMap, HashSet};
+
+use baml_types::{LiteralValue, TypeValue};
+use internal_baml_diagnostics::{DatamodelError, Diagnostics};
+use internal_baml_schema_ast::ast::{
+    FieldType, Identifier, SchemaAst, Top, TypeExpressionBlock, WithIdentifier, WithName, WithSpan,
+};
+
+/// Upper bound on how deeply the type arguments of an instantiation nest:
+/// `Page<Resume>` has depth 1, `Page<Page<Resume[]>>` has depth 3.
+///
+/// A generic class that instantiates itself with a bigger type argument
+/// (`class Nested<T> { inner Nested<T[]>? }`) would otherwise expand forever.
+/// A schema can use any number of different instantiations.
+const MAX_TYPE_ARGUMENT_DEPTH: usize = 32;
+
+/// Upper bound on the number of concrete classes created for all generic
+/// classes together.
+///
+/// The depth limit alone doesn't bound it: every level of
+/// `class X<T> { a X<T[]>? b X<map<string, T>>? }` doubles the number of
+/// instances.
+const MAX_INSTANCES: usize = 1000;
+
+/// Replaces every instantiation of a generic class with a concrete class.
+pub(crate) fn instantiate_generic_classes(ast: &mut SchemaAst, diagnostics: &mut Diagnostics) {
+    let templates = take_generic_classes(ast, diagnostics);
+    if templates.is_empty() {
+        return;
+    }
+
+    let mut instantiator = Instantiator {
+        templates: &templates,
+        declared: ast
+            .tops
+            .iter()
+            .filter(|top| matches!(top, Top::Class(_) | Top::Enum(_)))
+            .map(|top| top.name().to_string())
+            .collect(),
+        instances: HashMap::new(),
+        too_deep: HashSet::new(),
+        too_many: false,
+        new_classes: Vec::new(),
+    };
+
+    // New classes are pushed to the end of the AST, so they get visited as
+    // well. That's how instantiations inside generic classes are resolved.
+    let mut top_idx = 0;
+    while top_idx < ast.tops.len() {
+        for field_type in ast.tops[top_idx].field_types_mut() {
+            instantiator.instantiate(field_type, diagnostics);
+        }
+
+        ast.tops.extend(
+            std::mem::take(&mut instantiator.new_classes)
+                .into_iter()
+                .map(Top::Class),
+        );
+        top_idx += 1;
+    }
+}
+
+/// Removes the generic classes from the AST.
+fn take_generic_classes(
+    ast: &mut SchemaAst,
+    diagnostics: &mut Diagnostics,
+) -> HashMap<String, TypeExpressionBlock> {
+    let mut templates = HashMap::new();
+
+    for top in std::mem::take(&mut ast.tops) {
+        match top {
+            Top::Class(class) if !class.type_parameters.is_empty() => {
+                validate_generic_class(&class, diagnostics);
+
+                if templates.contains_key(class.name()) {
+                    diagnostics.push_error(DatamodelError::new_duplicate_top_error(
+                        class.name(),
+                        "class",
+                        "class",
+                        class.identifier().span().clone(),
+                    ));
+                } else {
+                    templates.insert(class.name().to_string(), class);
+                }
+            }
+            Top::Enum(enm) if !enm.type_parameters.is_empty() => {
+                diagnostics.push_error(DatamodelError::new_validation_error(
+                    "Enums can't have type parameters. Only classes can be generic.",
+                    enm.type_parameters[0].span().clone(),
+                ));
+                ast.tops.push(Top::Enum(enm));
+            }
+            top => ast.tops.push(top),
+        }
+    }
+
+    for top in &ast.tops {
+        if matches!(top, Top::Class(_) | Top::Enum(_)) && templates.contains_key(top.name()) {
+            diagnostics.push_error(DatamodelError::new_duplicate_top_error(
+                top.name(),
+                top.get_type(),
+                "class",
+                top.identifier().span().clone(),
+            ));
+        }
+    }
+
+    templates
+}
+
+fn validate_generic_class(class: &TypeExpressionBlock, diagnostics: &mut Diagnostics) {
+    if let Some(parent) = class.extends.first() {
+        diagnostics.push_error(DatamodelError::new_validation_error(
+            "Generic classes can't extend other classes.",
+            parent.span().clone(),
+        ));
+    }
+
+    for (idx, param) in class.type_parameters.iter().enumerate() {
+        if class.type_parameters[..idx]
+            .iter()
+            .any(|other| other.name() == param.name())
+        {
+            diagnostics.push_error(DatamodelError::new_validation_error(
+                &format!(
+                    "Type parameter `{}` is declared more than once.",
+                    param.name()
+                ),
+                param.span().clone(),
+            ));
+        }
+    }
+}
+
+struct Instantiator<'t> {
+    templates: &'t HashMap<String, TypeExpressionBlock>,
+    /// The names of the classes and enums declared in the schema, which
+    /// concrete classes must not reuse.
+    declared: HashSet<String>,
+    /// The concrete classes created so far, with the depth of their type
+    /// arguments.
+    instances: HashMap<String, usize>,
+    /// Generic classes whose type arguments grow forever, reported once each.
+    too_deep: HashSet<&'t str>,
+    /// Whether [`MAX_INSTANCES`] was reached and reported.
+    too_many: bool,
+    /// The concrete classes created while visiting the current top.
+    new_classes: Vec<TypeExpressionBlock>,
+}
+
+impl<'t> Instantiator<'t> {
+    /// Replaces every generic type in `field_type` with a reference to the
+    /// concrete class, creating the class if it doesn't exist yet.
+    fn instantiate(&mut self, field_type: &mut FieldType, diagnostics: &mut Diagnostics) {
+        match field_type {
+            FieldType::Generic(arity, name, args, span, attributes) => {
+                for arg in args.iter_mut() {
+                    self.instantiate(arg, diagnostics);
+                }
+
+                let Some((template_name, template)) = self.templates.get_key_value(name.name())
+                else {
+                    diagnostics.push_error(DatamodelError::new_validation_error(
+                        &format!("Generic class `{}` does not exist.", name.name()),
+                        name.span().clone(),
+                    ));
+                    return;
+                };
+
+                if template.type_parameters.len() != args.len() {
+                    diagnostics.push_error(DatamodelError::new_validation_error(
+                        &format!(
+                            "Generic class `{}` takes {} type argument(s) but {} were given.",
+                            template_name,
+                            template.type_parameters.len(),
+                            args.len()
+                        ),
+                        span.clone(),
+                    ));
+                    return;
+                }
+
+                let instance_name = instance_name(template_name, args);
+
+                if self.declared.contains(&instance_name) {
+                    diagnostics.push_error(DatamodelError::new_validation_error(
+                        &format!(
+                            "`{}<...>` is generated as the class `{}`, which is already declared. Rename that class.",
+                            template_name, instance_name
+                        ),
+                        span.clone(),
+                    ));
+                    return;
+                }
+
+                if !self.instances.contains_key(&instance_name) {
+                    let depth = 1 + args
+                        .iter()
+                        .map(|arg| type_depth(arg, &self.instances))
+                        .max()
+                        .unwrap_or(0);
+                    if depth > MAX_TYPE_ARGUMENT_DEPTH {
+                        if self.too_deep.insert(template_name.as_str()) {
+                            diagnostics.push_error(DatamodelError::new_validation_error(
+                                &format!(
+                                    "Generic class `{}` is instantiated with ever growing type arguments.",
+                                    template_name
+                                ),
+                                template.identifier().span().clone(),
+                            ));
+                        }
+                        return;
+                    }
+
+                    if self.instances.len() >= MAX_INSTANCES {
+                        if !self.too_many {
+                            self.too_many = true;
+                            diagnostics.push_error(DatamodelError::new_validation_error(
+                                &format!(
+                                    "Generic classes are instantiated with more than {} different type arguments. Check `{}` for type arguments that grow with every instantiation.",
+                                    MAX_INSTANCES, template_name
+                                ),
+                                template.identifier().span().clone(),
+                            ));
+                        }
+                        return;
+                    }
+
+                    self.instances.insert(instance_name.clone(), depth);
+                    self.new_classes
+                        .push(monomorphize(template, &instance_name, args));
+                }
+
+                *field_type = FieldType::Symbol(
+                    *arity,
+                    Identifier::Local(instance_name, span.clone()),
+                    attributes.take(),
+                );
+            }
+
+            FieldType::Symbol(_, name, _) => {
+                if let Some(template) = self.templates.get(name.name()) {
+                    diagnostics.push_error(DatamodelError::new_validation_error(
+                        &format!(
+                            "Generic class `{}` needs {} type argument(s), like `{}<...>`.",
+                            name.name(),
+                            template.type_parameters.len(),
+                            name.name()
+                        ),
+                        name.span().clone(),
+                    ));
+                }
+            }
+
+            FieldType::List(_, inner, ..) => self.instantiate(inner, diagnostics),
+
+            FieldType::Map(_, kv, ..) => {
+                self.instantiate(&mut kv.0, diagnostics);
+                self.instantiate(&mut kv.1, diagnostics);
+            }
+
+            FieldType::Union(_, items, ..) | FieldType::Tuple(_, items, ..) => {
+                for item in items {
+                    self.instantiate(item, diagnostics);
+                }
+            }
+
+            FieldType::Primitive(..) | FieldType::Literal(..) => {}
+        }
+    }
+}
+
+/// How deeply `field_type` nests types. Arguments are instantiated before
+/// the class that takes them, so instantiations inside `field_type` are
+/// already references to concrete classes in `instances`.
+fn type_depth(field_type: &FieldType, instances: &HashMap<String, usize>) -> usize {
+    match field_type {
+        FieldType::Symbol(_, name, _) => instances.get(name.name()).copied().unwrap_or(0),
+        FieldType::Primitive(..) | FieldType::Literal(..) => 0,
+        FieldType::List(_, inner, dims, ..) => type_depth(inner, instances) + *dims as usize,
+        FieldType::Map(_, kv, ..) => {
+            1 + type_depth(&kv.0, instances).max(type_depth(&kv.1, instances))
+        }
+        FieldType::Generic(_, _, items, ..)
+        | FieldType::Union(_, items, ..)
+        | FieldType::Tuple(_, items, ..) => {
+            1 + items
+                .iter()
+                .map(|item| type_depth(item, instances))
+                .max()
+                .unwrap_or(0)
+        }
+    }
+}
+
+/// Creates the concrete class for `template` instantiated with `args`.
+fn monomorphize(
+    template: &TypeExpressionBlock,
+    instance_name: &str,
+    args: &[FieldType],
+) -> TypeExpressionBlock {
+    let params = template
+        .type_parameters
+        .iter()
+        .map(|param| param.name())
+        .zip(args)
+        .collect::<HashMap<_, _>>();
+
+    let mut class = template.clone();
+    class.name = Identifier::Local(instance_name.to_string(), template.name.span().clone());
+    class.type_parameters.clear();
+
+    for field in class.fields.iter_mut() {
+        if let Some(field_type) = field.expr.as_mut() {
+            substitute(field_type, &params);
+        }
+    }
+
+    class
+}
+
+/// Replaces the type parameters in `field_type` with their type arguments.
+fn substitute(field_type: &mut FieldType, params: &HashMap<&str, &FieldType>) {
+    match field_type {
+        FieldType::Symbol(arity, name, attributes) => {
+            if let Some(arg) = params.get(name.name()) {
+                let mut arg = if arity.is_optional() {
+                    arg.to_nullable()
+                } else {
+                    (*arg).clone()
+                };
+                if let Some(attributes) = attributes.take() {
+                    arg.extend_attributes(attributes);
+                }
+                *field_type = arg;
+            }
+        }
+        FieldType::Generic(_, _, args, ..) => {
+            for arg in args {
+                substitute(arg, params);
+            }
+        }
+        FieldType::List(_, inner, ..) => substitute(inner, params),
+        FieldType::Map(_, kv, ..) => {
+            substitute(&mut kv.0, params);
+            substitute(&mut kv.1, params);
+        }
+        FieldType::Union(_, items, ..) | FieldType::Tuple(_, items, ..) => {
+            for item in items {
+                substitute(item, params);
+            }
+        }
+        FieldType::Primitive(..) | FieldType::Literal(..) => {}
+    }
+}
+
+/// The name of the concrete class for a generic class instantiation.
+///
+/// Must be a valid identifier in every target language, so `Page<Resume[]>`
+/// becomes `PageOfResumeList` and `Pair<int, string?>` becomes
+/// `PairOfIntAndOptionalString`.
+fn instance_name(template: &str, args: &[FieldType]) -> String {
+    format!(
+        "{}Of{}",
+        template,
+        args.iter().map(type_name).collect::<Vec<_>>().join("And")
+    )
+}
+
+fn type_name(field_type: &FieldType) -> String {
+    let name = match field_type {
+        FieldType::Symbol(_, name, _) => name.name().to_string(),
+        FieldType::Generic(_, name, args, ..) => instance_name(name.name(), args),
+        FieldType::Primitive(_, primitive, ..) => match primitive {
+            TypeValue::String => "String".to_string(),
+            TypeValue::Int => "Int".to_string(),
+            TypeValue::Float => "Float".to_string(),
+            TypeValue::Bool => "Bool".to_string(),
+            TypeValue::Null => "Null".to_string(),
+            TypeValue::Media(_) => capitalize(&primitive.to_string()),
+        },
+        FieldType::Literal(_, literal, ..) => match literal {
+            LiteralValue::String(s) => capitalize(
+                &s.chars()
+                    .filter(|c| c.is_ascii_alphanumeric())
+                    .collect::<String>(),
+            ),
+            LiteralValue::Int(i) if *i < 0 => format!("Minus{}", i.unsigned_abs()),
+            LiteralValue::Int(i) => i.to_string(),
+            LiteralValue::Bool(b) => capitalize(&b.to_string()),
+        },
+        FieldType::List(_, inner, dims, ..) => {
+            format!("{}{}", type_name(inner), "List".repeat(*dims as usize))
+        }
+        FieldType::Map(_, kv, ..) => format!("MapOf{}To{}", type_name(&kv.0), type_name(&kv.1)),
+        FieldType::Union(_, items, ..) => {
+            items.iter().map(type_name).collect::<Vec<_>>().join("Or")
+        }
+        FieldType::Tuple(_, items, ..) => format!(
+            "TupleOf{}",
+            items.iter().map(type_name).collect::<Vec<_>>().join("And")
+        ),
+    };
+
+    // Unions are optional if any of their items is, that's already part of
+    // the name. `null` is optional by definition.
+    let optional = match field_type {
+        FieldType::Union(arity, ..) => arity.is_optional(),
+        FieldType::Primitive(_, TypeValue::Null, ..) => false,
+        _ => field_type.is_optional(),
+    };
+
+    if optional {
+        format!("Optional{name}")
+    } else {
+        name
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
@@ -29,10 +29,13 @@
 pub mod walkers;
 
 mod attributes;
+mod builtins;
 mod coerce_expression;
 mod context;
+mod generics;
 mod interner;
 mod names;
+mod namespaces;
 mod tarjan;
 mod types;
 
@@ -42,10 +45,12 @@ pub use coerce_expression::{coerce, coerce_array, coerce_opt};
 use either::Either;
 pub use internal_baml_schema_ast::ast;
 use internal_baml_schema_ast::ast::SchemaAst;
+pub use namespaces::QualifiedName;
 pub use tarjan::Tarjan;
 pub use types::{
-    Attributes, ClientProperties, ContantDelayStrategy, ExponentialBackoffStrategy, PrinterType,
-    PromptAst, PromptVariable, RetryPolicy, RetryPolicyStrategy, StaticType,
+    Attributes, CandidateStrategy, Candidates, ClientProperties, ContantDelayStrategy,
+    ExponentialBackoffStrategy, PrinterType, PromptAst, PromptVariable, RetryPolicy,
+    RetryPolicyStrategy, StaticType,
 };
 
 use self::{context::Context, interner::StringId, types::Types};
@@ -62,6 +67,11 @@ use names::Names;
 ///
 /// Validations are carried out in the following order:
 ///
+/// - Namespaces are resolved: declarations in a `namespace` or an imported
+///   package are renamed to their canonical names (`shared.Resume` becomes
+///   `Shared_Resume`), and so are the qualified references to them.
+/// - Generic classes are instantiated: every `Page<Resume>` is replaced by a
+///   concrete class, and the generic declarations are removed from the AST.
 /// - The AST is walked a first time to resolve names: to each relevant
 ///   identifier, we attach an ID that can be used to reference the
 ///   corresponding item (model, enum, field, ...)
@@ -76,6 +86,8 @@ pub struct ParserDatabase {
     interner: interner::StringInterner,
     names: Names,
     types: Types,
+    /// The qualified names of namespaced declarations, by canonical name.
+    qualified_names: HashMap<String, QualifiedName>,
 }
 
 impl Default for ParserDatabase {
@@ -88,20 +100,46 @@ impl ParserDatabase {
     /// Create a new, empty ParserDatabase.
     pub fn new() -> Self {
         ParserDatabase {
-            ast: ast::SchemaAst { tops: vec![] },
+            ast: ast::SchemaAst::new(),
             interner: Default::default(),
             names: Default::default(),
             types: Default::default(),
+            qualified_names: Default::default(),
         }
     }
 
     /// See the docs on [ParserDatabase](/struct.ParserDatabase.html).
     pub fn add_ast(&mut self, ast: SchemaAst) {
         self.ast.tops.extend(ast.tops);
+        self.ast.namespaces.extend(ast.namespaces);
+        self.ast.imports.extend(ast.imports);
+    }
+
+    /// Gives namespaced declarations their canonical names and resolves the
+    /// references to them, which is the first step of
+    /// [`ParserDatabase::validate`]. Tools that map names back to the source,
+    /// like the language server, can stop there: generic classes are still
+    /// as written.
+    pub fn resolve_namespaces(&mut self, diag: &mut Diagnostics) {
+        self.qualified_names = namespaces::resolve_namespaces(&mut self.ast, diag);
     }
 
     /// See the docs on [ParserDatabase](/struct.ParserDatabase.html).
     pub fn validate(&mut self, diag: &mut Diagnostics) -> Result<(), Diagnostics> {
+        // Namespaced declarations get their canonical names, and qualified
+        // references are resolved to them.
+        self.resolve_namespaces(diag);
+        diag.to_result()?;
+
+        // Generic classes are replaced by concrete classes before anything
+        // else looks at the AST.
+        generics::instantiate_generic_classes(&mut self.ast, diag);
+        diag.to_result()?;
+
+        // Built-in classes like `ChatMessage` are only added when used.
+        builtins::add_builtin_classes(&mut self.ast, diag);
+        diag.to_result()?;
+
         let mut ctx = Context::new(
             &self.ast,
             &mut self.interner,
@@ -216,6 +254,11 @@ impl ParserDatabase {
     pub fn ast(&self) -> &ast::SchemaAst {
         &self.ast
     }
+
+    /// The name a declaration has in the source, if it is in a namespace.
+    pub fn qualified_name(&self, canonical: &str) -> Option<&QualifiedName> {
+        self.qualified_names.get(canonical)
+    }
     /// The total number of enums in the schema. This is O(1).
     pub fn enums_count(&self) -> usize {
         self.types.enum_attributes.len()
@@ -242,7 +285,7 @@ mod test {
 
     use super::*;
     use internal_baml_diagnostics::{Diagnostics, SourceFile};
-    use internal_baml_schema_ast::parse_schema;
+    use internal_baml_schema_ast::{ast::WithName, parse_schema};
 
     fn assert_finite_cycles(baml: &'static str, expected: &[&[&str]]) -> Result<(), Diagnostics> {
         let mut db = ParserDatabase::new();
@@ -479,4 +522,409 @@ mod test {
             &[&["RecMap"]],
         )
     }
+
+    #[test]
+    fn find_inherited_recursive_class() -> Result<(), Diagnostics> {
+        assert_finite_cycles(
+            r#"
+                class Base {
+                    next Child?
+                }
+
+                class Child extends Base {
+                    data int
+                }
+            "#,
+            &[&["Child"]],
+        )
+    }
+
+    #[test]
+    fn inherited_fields_come_first() -> Result<(), Diagnostics> {
+        let mut db = ParserDatabase::new();
+        let source = SourceFile::new_static(
+            PathBuf::from("test.baml"),
+            r#"
+                class A {
+                    a int
+                    shared string
+                }
+
+                class B {
+                    b int
+                    shared int
+                }
+
+                class C extends A, B {
+                    c int
+                    a float
+                }
+            "#,
+        );
+        let (ast, mut diag) = parse_schema(source.path_buf(), &source)?;
+
+        db.add_ast(ast);
+        db.validate(&mut diag)?;
+        db.finalize(&mut diag);
+
+        let Some(Either::Left(class)) = db.find_type_by_str("C") else {
+            panic!("Class `C` not found");
+        };
+
+        assert_eq!(
+            class
+                .parents()
+                .map(|p| p.ast_type_block().name())
+                .collect::<Vec<_>>(),
+            ["A", "B"]
+        );
+        assert_eq!(
+            class
+                .walk_all_fields()
+                .map(|f| (f.name(), f.model().ast_type_block().name()))
+                .collect::<Vec<_>>(),
+            [("a", "C"), ("shared", "A"), ("b", "B"), ("c", "C")]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn generic_classes_are_instantiated() -> Result<(), Diagnostics> {
+        let mut db = ParserDatabase::new();
+        let source = SourceFile::new_static(
+            PathBuf::from("test.baml"),
+            r#"
+                class Page<T> {
+                    items T[]
+                    next Page<T>?
+                }
+
+                class Resume {
+                    name string
+                }
+
+                class Search {
+                    resumes Page<Resume>
+                    tags Page<string?>
+                }
+            "#,
+        );
+        let (ast, mut diag) = parse_schema(source.path_buf(), &source)?;
+
+        db.add_ast(ast);
+        db.validate(&mut diag)?;
+        db.finalize(&mut diag);
+
+        assert!(db.find_type_by_str("Page").is_none());
+
+        let Some(Either::Left(class)) = db.find_type_by_str("PageOfResume") else {
+            panic!("Class `PageOfResume` not found");
+        };
+        assert_eq!(
+            class
+                .static_fields()
+                .map(|f| {
+                    let field_type = f.ast_field().expr.as_ref().unwrap();
+                    let idns = field_type.flat_idns();
+                    (f.name(), idns[0].name(), field_type.is_optional())
+                })
+                .collect::<Vec<_>>(),
+            [("items", "Resume", false), ("next", "PageOfResume", true)]
+        );
+
+        assert!(db.find_type_by_str("PageOfOptionalString").is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn generic_instantiations_are_bounded_by_depth() -> Result<(), Diagnostics> {
+        // Any number of different instantiations is fine.
+        let items = (0..100)
+            .map(|i| format!("class Item{i} {{\n  id int\n}}\n"))
+            .collect::<String>();
+        let pages = (0..100)
+            .map(|i| format!("  page{i} Page<Item{i}>\n"))
+            .collect::<String>();
+        let source = SourceFile::from((
+            PathBuf::from("test.baml"),
+            format!("class Page<T> {{\n  items T[]\n}}\n{items}class Search {{\n{pages}}}\n"),
+        ));
+        let (ast, mut diag) = parse_schema(source.path_buf(), &source)?;
+        let mut db = ParserDatabase::new();
+        db.add_ast(ast);
+        db.validate(&mut diag)?;
+        db.finalize(&mut diag);
+        assert!(db.find_type_by_str("PageOfItem99").is_some());
+
+        // Type arguments that grow with every instantiation are not.
+        let source = SourceFile::new_static(
+            PathBuf::from("test.baml"),
+            r#"
+                class Nested<T> {
+                    inner Nested<T[]>?
+                }
+
+                class Root {
+                    nested Nested<int>
+                }
+            "#,
+        );
+        let (ast, mut diag) = parse_schema(source.path_buf(), &source)?;
+        let mut db = ParserDatabase::new();
+        db.add_ast(ast);
+        let diag = db.validate(&mut diag).unwrap_err();
+        assert!(diag
+            .to_pretty_string()
+            .contains("ever growing type arguments"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn generic_instances_are_capped() -> Result<(), Diagnostics> {
+        // Every level doubles the number of instances, long before the depth
+        // limit is reached.
+        let source = SourceFile::new_static(
+            PathBuf::from("test.baml"),
+            r#"
+                class X<T> {
+                    a X<T[]>?
+                    b X<map<string, T>>?
+                }
+
+                class Root {
+                    x X<int>
+                }
+            "#,
+        );
+        let (ast, mut diag) = parse_schema(source.path_buf(), &source)?;
+        let mut db = ParserDatabase::new();
+        db.add_ast(ast);
+        let diag = db.validate(&mut diag).unwrap_err();
+        assert!(diag
+            .to_pretty_string()
+            .contains("instantiated with more than 1000 different type arguments"));
+
+        Ok(())
+    }
+
+    const SHARED: &str = r##"
+        class Resume {
+            name string
+            skills Skill[]
+        }
+
+        enum Skill {
+            Rust
+            Python
+        }
+
+        template_string Instructions #"
+            Extract the resume. {{ internal.Rules() }}
+        "#
+
+        client<llm> GPT4 {
+            provider openai
+            options {
+                model gpt-4o
+            }
+        }
+
+        generator lang_python {
+            output_type "python/pydantic"
+            output_dir "../"
+        }
+    "##;
+
+    const SHARED_INTERNAL: &str = r##"
+        namespace internal
+
+        template_string Rules #"
+            Be brief.
+        "#
+    "##;
+
+    fn validate_files(files: &[(&str, &'static str)]) -> Result<ParserDatabase, Diagnostics> {
+        let mut db = ParserDatabase::new();
+        let mut diag = Diagnostics::new(PathBuf::from("project/baml_src"));
+        for (path, contents) in files {
+            let source = SourceFile::new_static(PathBuf::from(*path), *contents);
+            let (ast, file_diag) = parse_schema(source.path_buf(), &source)?;
+            diag.push(file_diag);
+            db.add_ast(ast);
+        }
+        db.validate(&mut diag)?;
+        db.finalize(&mut diag);
+        Ok(db)
+    }
+
+    #[test]
+    fn imported_packages_are_namespaced() -> Result<(), Diagnostics> {
+        let db = validate_files(&[
+            (
+                "project/baml_src/main.baml",
+                r##"
+                    import "../../shared/baml_src" as shared
+
+                    class Candidate {
+                        resume shared.Resume
+                    }
+
+                    function Screen(text: string) -> Candidate {
+                        client shared.GPT4
+                        prompt #"
+                            {{ shared.Instructions() }}
+                            {{ text }}
+                        "#
+                    }
+                "##,
+            ),
+            ("shared/baml_src/resume.baml", SHARED),
+            ("shared/baml_src/internal.baml", SHARED_INTERNAL),
+        ])?;
+
+        let Some(Either::Left(candidate)) = db.find_type_by_str("Candidate") else {
+            panic!("Class `Candidate` not found");
+        };
+        let field_type = candidate
+            .static_fields()
+            .next()
+            .unwrap()
+            .ast_field()
+            .expr
+            .as_ref()
+            .unwrap();
+        assert_eq!(field_type.flat_idns()[0].name(), "Shared_Resume");
+
+        let Some(Either::Left(resume)) = db.find_type_by_str("Shared_Resume") else {
+            panic!("Class `Shared_Resume` not found");
+        };
+        let skills = resume.static_fields().nth(1).unwrap().ast_field();
+        assert_eq!(
+            skills.expr.as_ref().unwrap().flat_idns()[0].name(),
+            "Shared_Skill"
+        );
+        assert_eq!(
+            db.qualified_name("Shared_Resume"),
+            Some(&QualifiedName {
+                namespace: vec!["shared".to_string()],
+                name: "Resume".to_string(),
+            })
+        );
+        assert_eq!(db.qualified_name("Candidate"), None);
+
+        let screen = db.find_function_by_name("Screen").unwrap();
+        assert_eq!(screen.metadata().client.as_ref().unwrap().0, "Shared_GPT4");
+        assert!(screen
+            .jinja_prompt()
+            .contains("{{ Shared_Instructions() }}"));
+
+        let instructions = db
+            .walk_templates()
+            .find(|t| t.name() == "Shared_Instructions")
+            .unwrap();
+        assert!(instructions
+            .template_string()
+            .contains("{{ Shared_Internal_Rules() }}"));
+
+        // The package's generator is not the project's.
+        assert!(db
+            .ast()
+            .iter_tops()
+            .all(|(_, top)| !matches!(top, ast::Top::Generator(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn internal_declarations_are_private() {
+        let result = validate_files(&[
+            (
+                "project/baml_src/main.baml",
+                r##"
+                    import "../../shared/baml_src" as shared
+
+                    template_string Prompt #"
+                        {{ shared.internal.Rules() }}
+                    "#
+                "##,
+            ),
+            ("shared/baml_src/resume.baml", SHARED),
+            ("shared/baml_src/internal.baml", SHARED_INTERNAL),
+        ]);
+
+        let Err(diag) = result else {
+            panic!("Expected an error");
+        };
+        assert!(
+            diag.to_pretty_string()
+                .contains("`shared.internal.Rules` is internal to `shared`."),
+            "{}",
+            diag.to_pretty_string()
+        );
+    }
+
+    #[test]
+    fn package_directories_are_namespaces() -> Result<(), Diagnostics> {
+        let db = validate_files(&[
+            (
+                "project/baml_src/main.baml",
+                r##"
+                    import "../../shared/baml_src" as shared
+
+                    class Candidate {
+                        resume shared.resumes.Resume
+                    }
+                "##,
+            ),
+            (
+                "shared/baml_src/resumes/types.baml",
+                r##"
+                    class Resume {
+                        name string
+                    }
+                "##,
+            ),
+        ])?;
+
+        assert!(db.find_type_by_str("Shared_Resumes_Resume").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn canonical_names_must_be_unique() {
+        let result = validate_files(&[
+            (
+                "project/baml_src/main.baml",
+                r##"
+                class Shared_Resume {
+                    name string
+                }
+            "##,
+            ),
+            (
+                "project/baml_src/shared.baml",
+                r##"
+                namespace shared
+
+                class Resume {
+                    name string
+                }
+            "##,
+            ),
+        ]);
+
+        let Err(diag) = result else {
+            panic!("Expected an error");
+        };
+        assert!(
+            diag.to_pretty_string().contains(
+                "`shared.Resume` would be generated as `Shared_Resume`, like `Shared_Resume`."
+            ),
+            "{}",
+            diag.to_pretty_string()
+        );
+    }
 }
@@ -6,6 +6,12 @@ use crate::{
 };
 
 pub(crate) fn validate_attribute_name(ast_attr: &ast::Attribute, diagnostics: &mut Diagnostics) {
+    // `@stream.*` attributes are the only namespaced attributes we accept.
+    if let ast::Identifier::Ref(ref_identifier, _) = ast_attr.identifier() {
+        if ref_identifier.path == ["stream"] {
+            return;
+        }
+    }
     validate_name("attribute", ast_attr.identifier(), diagnostics, false);
 }
 
@@ -0,0 +1,614 @@
+//! Namespaces and imported packages.
+//!
+//! A file is in the namespace it declares with `namespace shared.resumes`,
+//! and a file of a package imported with `import "../shared/baml_src" as
+//! shared` is in the `shared` namespace, followed by whatever the file
+//! declares or, if it declares nothing, the directories it is in within the
+//! package (`resumes/types.baml` is in `shared.resumes`). Everything else is
+//! in the root namespace, which is what every project without namespaces
+//! looks like: the project's own directories don't make namespaces, since
+//! that would rename the declarations of every project that uses them.
+//!
+//! Namespaces only exist in the source: before anything else looks at the
+//! AST, each namespaced declaration is renamed to its canonical name
+//! (`shared.resumes.Resume` becomes `Shared_Resumes_Resume`) and every
+//! reference to it is replaced by that name. Two declarations can't have
+//! the same canonical name. The rest of the pipeline only ever sees the
+//! canonical names, apart from generators that mirror the namespaces with
+//! aliases, which look up [`QualifiedName`]s.
+//!
+//! References are resolved from the namespace of the file they are in
+//! outwards: `Resume` in `shared.resumes` is `shared.resumes.Resume`,
+//! `shared.Resume` or `Resume`, whichever exists first. Declarations in a
+//! namespace with an `internal` segment can only be referenced from inside
+//! the namespace that contains it: `shared.internal.Helper` is private to
+//! `shared`.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use internal_baml_diagnostics::{DatamodelError, Diagnostics, Span};
+use internal_baml_schema_ast::ast::{
+    Expression, FieldType, Identifier, Import, RawString, SchemaAst, Top, WithIdentifier, WithName,
+    WithSpan,
+};
+
+/// The namespace segment that makes what is inside it private.
+const INTERNAL: &str = "internal";
+
+/// An imported `baml_src` directory.
+struct Package {
+    root: PathBuf,
+    alias: String,
+}
+
+/// A top-level declaration, by its qualified name.
+struct Declaration {
+    canonical: String,
+    namespace: Vec<String>,
+    kind: String,
+}
+
+/// The name a namespaced declaration has in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QualifiedName {
+    /// The namespace, outermost segment first.
+    pub namespace: Vec<String>,
+    /// The name within the namespace.
+    pub name: String,
+}
+
+impl std::fmt::Display for QualifiedName {
+    /// As written in the source, `shared.resumes.Resume`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&qualified_name(&self.namespace, &self.name))
+    }
+}
+
+/// Renames namespaced declarations to their canonical names and resolves
+/// the references to them. Returns the qualified names of the renamed
+/// declarations, by canonical name.
+pub(crate) fn resolve_namespaces(
+    ast: &mut SchemaAst,
+    diagnostics: &mut Diagnostics,
+) -> HashMap<String, QualifiedName> {
+    let mut qualified_names = HashMap::new();
+    if ast.namespaces.is_empty() && ast.imports.is_empty() {
+        return qualified_names;
+    }
+
+    let packages = packages(&ast.imports, diagnostics);
+    let declared = declared_namespaces(&ast.namespaces, diagnostics);
+
+    // A package's generators are for the package's own clients.
+    ast.tops.retain(|top| {
+        !matches!(top, Top::Generator(_)) || package_of(top.span(), &packages).is_none()
+    });
+
+    let mut invalid_directories = HashSet::new();
+    let namespaces = ast
+        .tops
+        .iter()
+        .map(|top| {
+            let file = normalize(top.span().file.path_buf());
+            let package = package_of(top.span(), &packages);
+            let mut namespace = package
+                .map(|package| vec![package.alias.clone()])
+                .unwrap_or_default();
+            match (declared.get(&file), package) {
+                (Some(declared), _) => namespace.extend(declared.iter().cloned()),
+                (None, Some(package)) => match directory_namespace(&file, package) {
+                    Ok(directories) => namespace.extend(directories),
+                    Err(invalid) => {
+                        if invalid_directories.insert(file) {
+                            diagnostics.push_error(invalid_directory_error(
+                                &invalid,
+                                top.identifier().span(),
+                            ));
+                        }
+                    }
+                },
+                (None, None) => {}
+            }
+            namespace
+        })
+        .collect::<Vec<_>>();
+
+    let mut declarations = HashMap::new();
+    let mut canonical_names = HashMap::new();
+    for (top, namespace) in ast.tops.iter().zip(&namespaces) {
+        let qualified = qualified_name(namespace, top.name());
+        let canonical = canonical_name(namespace, top.name());
+
+        // Declaring the same qualified name twice is reported like any other
+        // duplicate, once the canonical names are in the AST.
+        match canonical_names.get(&canonical) {
+            Some(other) if *other != qualified => {
+                diagnostics.push_error(DatamodelError::new_validation_error(
+                    &format!(
+                        "`{}` would be generated as `{}`, like `{}`. Rename one of them.",
+                        qualified, canonical, other
+                    ),
+                    top.identifier().span().clone(),
+                ));
+            }
+            Some(_) => {}
+            None => {
+                canonical_names.insert(canonical.clone(), qualified.clone());
+            }
+        }
+
+        declarations.insert(
+            qualified,
+            Declaration {
+                canonical,
+                namespace: namespace.clone(),
+                kind: top.get_type().to_string(),
+            },
+        );
+    }
+
+    for (top, namespace) in ast.tops.iter_mut().zip(&namespaces) {
+        let mut scope = Scope {
+            declarations: &declarations,
+            namespace,
+            diagnostics: &mut *diagnostics,
+        };
+
+        if !namespace.is_empty() {
+            let identifier = top.identifier_mut();
+            let canonical = canonical_name(namespace, identifier.name());
+            qualified_names.insert(
+                canonical.clone(),
+                QualifiedName {
+                    namespace: namespace.clone(),
+                    name: identifier.name().to_string(),
+                },
+            );
+            *identifier = Identifier::Local(canonical, identifier.span().clone());
+        }
+
+        for field_type in top.field_types_mut() {
+            scope.rewrite_field_type(field_type);
+        }
+
+        match top {
+            Top::Class(class) => {
+                for parent in class.extends.iter_mut() {
+                    scope.rewrite_identifier(parent, &["class"]);
+                }
+            }
+            Top::Function(function) => {
+                for field in function.fields.iter_mut() {
+                    let name = field.name().to_string();
+                    match (name.as_str(), field.expr.as_mut()) {
+                        ("client", Some(expr)) => scope.rewrite_expression(expr, &["client<llm>"]),
+                        ("prompt", Some(Expression::RawStringValue(prompt))) => {
+                            scope.rewrite_jinja(prompt)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Top::Client(client) => {
+                for field in client.fields.iter_mut() {
+                    let name = field.name().to_string();
+                    match (name.as_str(), field.expr.as_mut()) {
+                        ("retry_policy", Some(expr)) => {
+                            scope.rewrite_expression(expr, &["retry_policy"])
+                        }
+                        ("options", Some(Expression::Map(options, _))) => {
+                            for (key, value) in options.iter_mut() {
+                                if key.as_string_value().is_some_and(|(k, _)| k == "strategy") {
+                                    scope.rewrite_expression(value, &["client<llm>"]);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Top::TestCase(test) => {
+                for field in test.fields.iter_mut() {
+                    let name = field.name().to_string();
+                    if let ("function" | "functions", Some(expr)) =
+                        (name.as_str(), field.expr.as_mut())
+                    {
+                        scope.rewrite_expression(expr, &["function"]);
+                    }
+                }
+            }
+            Top::TemplateString(template) => {
+                if let Expression::RawStringValue(value) = &mut template.value {
+                    scope.rewrite_jinja(value);
+                }
+            }
+            Top::Enum(_) | Top::Generator(_) | Top::RetryPolicy(_) => {}
+        }
+    }
+
+    qualified_names
+}
+
+/// The imported packages, by the directory they are in.
+fn packages(imports: &[Import], diagnostics: &mut Diagnostics) -> Vec<Package> {
+    let mut packages: Vec<Package> = vec![];
+
+    for import in imports {
+        let alias = import.alias.name();
+        if !is_valid_segment(alias) {
+            diagnostics.push_error(invalid_segment_error(alias, import.alias.span()));
+            continue;
+        }
+
+        let importer = normalize(import.span.file.path_buf());
+        let root = normalize(
+            &importer
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .join(&import.path),
+        );
+        if importer.starts_with(&root) {
+            diagnostics.push_error(DatamodelError::new_validation_error(
+                "A file can't import the directory it is in.",
+                import.span.clone(),
+            ));
+            continue;
+        }
+
+        match packages
+            .iter()
+            .find(|package| package.alias == alias || package.root == root)
+        {
+            Some(package) if package.alias == alias && package.root == root => {}
+            Some(package) if package.alias == alias => {
+                diagnostics.push_error(DatamodelError::new_validation_error(
+                    &format!(
+                        "`{}` is already the alias of `{}`.",
+                        alias,
+                        package.root.display()
+                    ),
+                    import.alias.span().clone(),
+                ));
+            }
+            Some(package) => {
+                diagnostics.push_error(DatamodelError::new_validation_error(
+                    &format!(
+                        "`{}` is already imported as `{}`.",
+                        import.path, package.alias
+                    ),
+                    import.span.clone(),
+                ));
+            }
+            None => packages.push(Package {
+                root,
+                alias: alias.to_string(),
+            }),
+        }
+    }
+
+    packages
+}
+
+/// The `namespace` declarations, by file.
+fn declared_namespaces(
+    namespaces: &[Identifier],
+    diagnostics: &mut Diagnostics,
+) -> HashMap<PathBuf, Vec<String>> {
+    let mut declared = HashMap::new();
+
+    for namespace in namespaces {
+        let segments = namespace
+            .name()
+            .split('.')
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        if let Some(invalid) = segments.iter().find(|s| !is_valid_segment(s)) {
+            diagnostics.push_error(invalid_segment_error(invalid, namespace.span()));
+            continue;
+        }
+
+        let file = normalize(namespace.span().file.path_buf());
+        if declared.contains_key(&file) {
+            diagnostics.push_error(DatamodelError::new_validation_error(
+                "A file can only declare one namespace.",
+                namespace.span().clone(),
+            ));
+            continue;
+        }
+        declared.insert(file, segments);
+    }
+
+    declared
+}
+
+/// The package a declaration comes from. Packages can be nested, the
+/// innermost one wins.
+fn package_of<'p>(span: &Span, packages: &'p [Package]) -> Option<&'p Package> {
+    let file = normalize(span.file.path_buf());
+    packages
+        .iter()
+        .filter(|package| file.starts_with(&package.root))
+        .max_by_key(|package| package.root.components().count())
+}
+
+/// The namespace a file of `package` is in when it doesn't declare one: the
+/// directories between the package's root and the file. Fails with the first
+/// directory that can't be a namespace segment.
+fn directory_namespace(file: &Path, package: &Package) -> Result<Vec<String>, String> {
+    let Some(directory) = file
+        .parent()
+        .and_then(|dir| dir.strip_prefix(&package.root).ok())
+    else {
+        return Ok(vec![]);
+    };
+    directory
+        .components()
+        .map(|component| {
+            let segment = component.as_os_str().to_string_lossy().to_string();
+            if is_valid_segment(&segment) {
+                Ok(segment)
+            } else {
+                Err(segment)
+            }
+        })
+        .collect()
+}
+
+/// Resolves references from the declarations of one namespace.
+struct Scope<'a> {
+    declarations: &'a HashMap<String, Declaration>,
+    namespace: &'a [String],
+    diagnostics: &'a mut Diagnostics,
+}
+
+impl Scope<'_> {
+    /// The canonical name of the declaration `name` refers to, if it is one
+    /// of `kinds`.
+    fn resolve(&mut self, name: &str, kinds: &[&str], span: &Span) -> Option<String> {
+        let declarations = self.declarations;
+        let declaration = (0..=self.namespace.len()).rev().find_map(|depth| {
+            declarations
+                .get(&qualified_name(&self.namespace[..depth], name))
+                .filter(|declaration| kinds.contains(&declaration.kind.as_str()))
+        })?;
+
+        if let Some(owner) = internal_owner(&declaration.namespace) {
+            if !self.namespace.starts_with(owner) {
+                self.diagnostics
+                    .push_error(DatamodelError::new_validation_error(
+                        &format!(
+                            "`{}` is internal to `{}`.",
+                            qualified_name(&declaration.namespace, name.rsplit('.').next()?),
+                            owner.join(".")
+                        ),
+                        span.clone(),
+                    ));
+                return None;
+            }
+        }
+
+        Some(declaration.canonical.clone())
+    }
+
+    fn rewrite_identifier(&mut self, identifier: &mut Identifier, kinds: &[&str]) {
+        if !matches!(identifier, Identifier::Local(..) | Identifier::Ref(..)) {
+            return;
+        }
+        let span = identifier.span().clone();
+        if let Some(canonical) = self.resolve(identifier.name(), kinds, &span) {
+            if canonical != identifier.name() {
+                *identifier = Identifier::Local(canonical, span);
+            }
+        }
+    }
+
+    fn rewrite_expression(&mut self, expr: &mut Expression, kinds: &[&str]) {
+        match expr {
+            Expression::Identifier(identifier) => self.rewrite_identifier(identifier, kinds),
+            Expression::Array(items, _) => {
+                for item in items {
+                    self.rewrite_expression(item, kinds);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn rewrite_field_type(&mut self, field_type: &mut FieldType) {
+        match field_type {
+            FieldType::Symbol(_, name, _) => self.rewrite_identifier(name, &["class", "enum"]),
+            FieldType::Generic(_, name, args, ..) => {
+                self.rewrite_identifier(name, &["class"]);
+                for arg in args {
+                    self.rewrite_field_type(arg);
+                }
+            }
+            FieldType::List(_, inner, ..) => self.rewrite_field_type(inner),
+            FieldType::Map(_, kv, ..) => {
+                self.rewrite_field_type(&mut kv.0);
+                self.rewrite_field_type(&mut kv.1);
+            }
+            FieldType::Union(_, items, ..) | FieldType::Tuple(_, items, ..) => {
+                for item in items {
+                    self.rewrite_field_type(item);
+                }
+            }
+            FieldType::Primitive(..) | FieldType::Literal(..) => {}
+        }
+    }
+
+    /// Template strings are called from Jinja, `{{ shared.Helper(x) }}`.
+    fn rewrite_jinja(&mut self, raw: &mut RawString) {
+        let span = raw.span().clone();
+        // The inner value is the dedented raw value, so it mentions the same
+        // names. Resolve each once, errors included.
+        let mut resolved = HashMap::<String, Option<String>>::new();
+        raw.raw_value = rewrite_jinja_names(&raw.raw_value, &mut |name| {
+            resolved
+                .entry(name.to_string())
+                .or_insert_with(|| {
+                    self.resolve(name, &["template_string"], &span)
+                        .filter(|canonical| canonical != name)
+                })
+                .clone()
+        });
+        raw.inner_value = rewrite_jinja_names(&raw.inner_value, &mut |name| {
+            resolved.get(name).cloned().flatten()
+        });
+    }
+}
+
+/// Replaces the dotted names inside `{{ }}` and `{% %}` that `resolve`
+/// knows about. Attributes (`x.name`) and string literals are left alone.
+fn rewrite_jinja_names(text: &str, resolve: &mut impl FnMut(&str) -> Option<String>) -> String {
+    let b = text.as_bytes();
+    let is_word_start = |c: u8| c.is_ascii_alphabetic() || c == b'_';
+    let is_word_char = |c: u8| c.is_ascii_alphanumeric() || c == b'_';
+
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut close: Option<&[u8]> = None;
+    let mut i = 0;
+
+    while i < b.len() {
+        let Some(end) = close else {
+            if b[i..].starts_with(b"{{") {
+                close = Some(b"}}");
+                i += 2;
+            } else if b[i..].starts_with(b"{%") {
+                close = Some(b"%}");
+                i += 2;
+            } else {
+                i += 1;
+            }
+            continue;
+        };
+
+        match b[i] {
+            _ if b[i..].starts_with(end) => {
+                close = None;
+                i += 2;
+            }
+            q @ (b'"' | b'\'') => {
+                i += 1;
+                while i < b.len() && b[i] != q {
+                    i += 1;
+                }
+                i += 1;
+            }
+            c if c.is_ascii_digit() => {
+                while i < b.len() && is_word_char(b[i]) {
+                    i += 1;
+                }
+            }
+            c if is_word_start(c) => {
+                let start = i;
+                while i < b.len()
+                    && (is_word_char(b[i])
+                        || (b[i] == b'.' && b.get(i + 1).is_some_and(|c| is_word_start(*c))))
+                {
+                    i += 1;
+                }
+                if start > 0 && b[start - 1] == b'.' {
+                    continue;
+                }
+                if let Some(canonical) = resolve(&text[start..i]) {
+                    out.push_str(&text[copied..start]);
+                    out.push_str(&canonical);
+                    copied = i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    out.push_str(&text[copied.min(text.len())..]);
+    out
+}
+
+fn qualified_name(namespace: &[String], name: &str) -> String {
+    namespace
+        .iter()
+        .map(String::as_str)
+        .chain(std::iter::once(name))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// The name a namespaced declaration gets in the IR and the generated
+/// clients. Must be a valid identifier in every target language.
+fn canonical_name(namespace: &[String], name: &str) -> String {
+    namespace
+        .iter()
+        .map(|segment| capitalize(segment))
+        .chain(std::iter::once(name.to_string()))
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// The namespace that may use the declarations of `namespace`, if they are
+/// internal.
+fn internal_owner(namespace: &[String]) -> Option<&[String]> {
+    namespace
+        .iter()
+        .position(|segment| segment == INTERNAL)
+        .map(|idx| &namespace[..idx])
+}
+
+fn is_valid_segment(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn invalid_segment_error(segment: &str, span: &Span) -> DatamodelError {
+    DatamodelError::new_validation_error(
+        &format!(
+            "`{}` can't be used as a namespace. Use letters, digits and underscores.",
+            segment
+        ),
+        span.clone(),
+    )
+}
+
+fn invalid_directory_error(directory: &str, span: &Span) -> DatamodelError {
+    DatamodelError::new_validation_error(
+        &format!(
+            "The directory `{}` can't be used as a namespace. Use letters, digits and underscores, or declare the namespace of the file.",
+            directory
+        ),
+        span.clone(),
+    )
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Resolves `.` and `..` without touching the file system, so paths compare
+/// the same however they were spelled.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
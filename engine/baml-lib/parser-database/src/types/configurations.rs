@@ -1,5 +1,6 @@
 use baml_types::Constraint;
 use baml_types::UnresolvedValue;
+use indexmap::IndexMap;
 use internal_baml_diagnostics::{DatamodelError, DatamodelWarning, Span};
 use internal_baml_schema_ast::ast::{
     Attribute, ValExpId, ValueExprBlock, WithIdentifier, WithName, WithSpan,
@@ -12,6 +13,7 @@ use crate::{coerce, coerce_array, coerce_expression::coerce_map, context::Contex
 
 use super::{
     Attributes, ContantDelayStrategy, ExponentialBackoffStrategy, RetryPolicy, RetryPolicyStrategy,
+    TestDataset,
 };
 
 fn dedent(s: &str) -> String {
@@ -205,6 +207,7 @@ pub(crate) fn visit_test_case<'db>(
 ) {
     let mut functions = None;
     let mut args = None;
+    let mut dataset = None;
 
     config
         .iter_fields()
@@ -253,10 +256,22 @@ pub(crate) fn visit_test_case<'db>(
                 }
                 None => {}
             },
+            ("dataset", Some(val)) => match val.to_unresolved_value(ctx.diagnostics) {
+                Some(UnresolvedValue::<Span>::Map(kv, span)) => {
+                    dataset = visit_test_dataset(kv, span, ctx)
+                }
+                Some(other) => {
+                    ctx.push_error(DatamodelError::new_validation_error(
+                        "`dataset` must be a map",
+                        other.meta().clone(),
+                    ));
+                }
+                None => {}
+            },
             (name, Some(_)) => ctx.push_error(DatamodelError::new_property_not_known_error(
                 name,
                 f.identifier().span().clone(),
-                ["functions", "args"].to_vec(),
+                ["functions", "args", "dataset"].to_vec(),
             )),
         });
 
@@ -272,6 +287,13 @@ pub(crate) fn visit_test_case<'db>(
         })
         .collect();
 
+    // The rows of a dataset provide the arguments, so `args` is optional.
+    let args = match (args, &dataset) {
+        (Some(args), _) => Some(args),
+        (None, Some(dataset)) => Some((dataset.span.clone(), IndexMap::new())),
+        (None, None) => None,
+    };
+
     match (functions, args) {
         (None, _) => ctx.push_error(DatamodelError::new_validation_error(
             "Missing `functions` property",
@@ -288,9 +310,80 @@ pub(crate) fn visit_test_case<'db>(
                     functions,
                     args,
                     args_field_span: args_field_span.clone(),
+                    dataset,
                     constraints,
                 },
             );
         }
     }
 }
+
+fn visit_test_dataset(
+    properties: IndexMap<String, (Span, UnresolvedValue<Span>)>,
+    span: Span,
+    ctx: &mut Context<'_>,
+) -> Option<TestDataset> {
+    let mut file = None;
+    let mut args = IndexMap::new();
+    let mut expected = None;
+
+    fn static_str(value: &UnresolvedValue<Span>, ctx: &mut Context<'_>) -> Option<(String, Span)> {
+        match value.as_static_str() {
+            Ok(s) => Some((s.to_string(), value.meta().clone())),
+            Err(e) => {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    &e.to_string(),
+                    value.meta().clone(),
+                ));
+                None
+            }
+        }
+    }
+
+    for (name, (name_span, value)) in properties {
+        match name.as_str() {
+            "file" => file = static_str(&value, ctx),
+            "expected" => expected = static_str(&value, ctx),
+            "args" => match value {
+                UnresolvedValue::Map(columns, _) => {
+                    for (arg, (_, column)) in columns {
+                        if let Some(column) = static_str(&column, ctx) {
+                            args.insert(arg, column);
+                        }
+                    }
+                }
+                other => ctx.push_error(DatamodelError::new_validation_error(
+                    "`args` of a dataset must map argument names to keys or columns",
+                    other.meta().clone(),
+                )),
+            },
+            _ => ctx.push_error(DatamodelError::new_property_not_known_error(
+                &name,
+                name_span,
+                ["file", "args", "expected"].to_vec(),
+            )),
+        }
+    }
+
+    let Some(file) = file else {
+        ctx.push_error(DatamodelError::new_validation_error(
+            "Missing `file` property in `dataset`",
+            span,
+        ));
+        return None;
+    };
+    if !file.0.ends_with(".jsonl") && !file.0.ends_with(".csv") {
+        ctx.push_error(DatamodelError::new_validation_error(
+            "Dataset files must be .jsonl or .csv",
+            file.1.clone(),
+        ));
+        return None;
+    }
+
+    Some(TestDataset {
+        file,
+        args,
+        expected,
+        span,
+    })
+}
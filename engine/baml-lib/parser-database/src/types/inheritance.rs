@@ -0,0 +1,145 @@
+use std::collections::{HashMap, HashSet};
+
+use internal_baml_schema_ast::ast::{self, WithIdentifier, WithName, WithSpan};
+
+use crate::{context::Context, DatamodelError, Tarjan};
+
+/// Resolves the `extends` clause of every class.
+///
+/// Parents must be classes, and a class can't (transitively) extend itself.
+/// Once resolved, the dependencies of every ancestor are added to the
+/// dependencies of the class, since the class inherits their fields.
+pub(super) fn resolve_class_inheritance(ctx: &mut Context<'_>) {
+    let mut parents = HashMap::new();
+
+    for (top_id, top) in ctx.ast.iter_tops() {
+        match (top_id, top) {
+            (ast::TopId::Class(class_id), ast::Top::Class(class)) => {
+                parents.insert(class_id, resolve_parents(class, ctx));
+            }
+            (ast::TopId::Enum(_), ast::Top::Enum(enm)) => {
+                if let Some(parent) = enm.extends.first() {
+                    ctx.push_error(DatamodelError::new_validation_error(
+                        "Enums can't extend other types. Only classes support `extends`.",
+                        parent.span().clone(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Every class is a key of `parents`, as required by Tarjan.
+    let graph = HashMap::from_iter(
+        parents
+            .iter()
+            .map(|(id, p)| (*id, HashSet::from_iter(p.iter().copied()))),
+    );
+
+    for component in Tarjan::components(&graph) {
+        let cycle = component
+            .iter()
+            .map(|id| ctx.ast[*id].name().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+
+        ctx.push_error(DatamodelError::new_validation_error(
+            &format!("These classes form an inheritance cycle: {}", cycle),
+            ctx.ast[component[0]].identifier().span().clone(),
+        ));
+
+        // Don't keep cyclic edges around, everything that walks the
+        // inheritance tree relies on it being acyclic.
+        for id in component {
+            parents.remove(&id);
+        }
+    }
+
+    // Inherited fields make the ancestors' dependencies our dependencies.
+    let inherited_dependencies = parents
+        .keys()
+        .map(|id| {
+            let deps = ancestors(*id, &parents)
+                .into_iter()
+                .flat_map(|ancestor| {
+                    ctx.types
+                        .class_dependencies
+                        .get(&ancestor)
+                        .into_iter()
+                        .flatten()
+                        .cloned()
+                })
+                .collect::<HashSet<_>>();
+            (*id, deps)
+        })
+        .collect::<Vec<_>>();
+
+    for (id, deps) in inherited_dependencies {
+        ctx.types
+            .class_dependencies
+            .entry(id)
+            .or_default()
+            .extend(deps);
+    }
+
+    ctx.types.class_parents = parents.into_iter().filter(|(_, p)| !p.is_empty()).collect();
+}
+
+fn resolve_parents(class: &ast::TypeExpressionBlock, ctx: &mut Context<'_>) -> Vec<ast::TypeExpId> {
+    let mut resolved = Vec::new();
+
+    for parent in &class.extends {
+        let top = ctx
+            .interner
+            .lookup(parent.name())
+            .and_then(|name| ctx.names.tops.get(&name))
+            .copied();
+
+        match top {
+            Some(ast::TopId::Class(parent_id)) => {
+                if resolved.contains(&parent_id) {
+                    ctx.push_error(DatamodelError::new_validation_error(
+                        &format!("Class `{}` is extended more than once.", parent.name()),
+                        parent.span().clone(),
+                    ));
+                } else {
+                    resolved.push(parent_id);
+                }
+            }
+            Some(ast::TopId::Enum(_)) => {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    &format!(
+                        "`{}` is an enum. Classes can only extend other classes.",
+                        parent.name()
+                    ),
+                    parent.span().clone(),
+                ));
+            }
+            _ => {
+                ctx.push_error(DatamodelError::new_validation_error(
+                    &format!("Class `{}` does not exist.", parent.name()),
+                    parent.span().clone(),
+                ));
+            }
+        }
+    }
+
+    resolved
+}
+
+/// All the (transitive) ancestors of a class. The graph must be acyclic.
+fn ancestors(
+    id: ast::TypeExpId,
+    parents: &HashMap<ast::TypeExpId, Vec<ast::TypeExpId>>,
+) -> HashSet<ast::TypeExpId> {
+    let mut visited = HashSet::new();
+    let mut stack = parents.get(&id).cloned().unwrap_or_default();
+
+    while let Some(ancestor) = stack.pop() {
+        if visited.insert(ancestor) {
+            stack.extend(parents.get(&ancestor).into_iter().flatten().copied());
+        }
+    }
+
+    visited
+}
@@ -18,6 +18,7 @@ use internal_baml_schema_ast::ast::{
 use internal_llm_client::{ClientProvider, PropertyHandler, UnresolvedClientProperty};
 
 mod configurations;
+mod inheritance;
 mod prompt;
 mod types;
 
@@ -63,6 +64,8 @@ pub(super) fn resolve_types(ctx: &mut Context<'_>) {
             _ => {}
         }
     }
+
+    inheritance::resolve_class_inheritance(ctx);
 }
 #[derive(Debug, Clone)]
 /// Variables used inside of raw strings.
@@ -147,9 +150,24 @@ pub struct TestCase {
     // The span is the span of the argument (the expression has its own span)
     pub args: IndexMap<String, (Span, UnresolvedValue<Span>)>,
     pub args_field_span: Span,
+    pub dataset: Option<TestDataset>,
     pub constraints: Vec<(Constraint, Span, Span)>,
 }
 
+/// A JSONL or CSV file whose rows are run as separate cases of a test.
+#[derive(Debug)]
+pub struct TestDataset {
+    /// The path of the file, relative to the file declaring the test.
+    pub file: (String, Span),
+    /// Argument name to the key (JSONL) or column (CSV) it is read from.
+    /// Arguments that aren't listed are read from the key of the same name.
+    pub args: IndexMap<String, (String, Span)>,
+    /// The key or column holding the expected output, available to
+    /// `@@check` and `@@assert` as `_.expected`.
+    pub expected: Option<(String, Span)>,
+    pub span: Span,
+}
+
 #[derive(Debug, Clone)]
 pub struct Printer {
     pub template: (String, Span),
@@ -212,11 +230,59 @@ pub struct ExponentialBackoffStrategy {
     pub max_delay_ms: u32,
 }
 
+/// How many responses to sample for a function, and how to pick one.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct Candidates {
+    /// The number of candidate responses.
+    pub count: usize,
+    /// The strategy to pick the result with.
+    pub strategy: CandidateStrategy,
+}
+
+impl Candidates {
+    /// The most candidates a function can sample per call, like OpenAI's
+    /// limit on `n`. Each one is billed as its own response.
+    pub const MAX_COUNT: usize = 128;
+}
+
+/// The strategy to pick a function's result from its candidate responses.
+/// Candidates that fail to parse are never picked, unless they all do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub enum CandidateStrategy {
+    /// The first candidate that parses.
+    FirstValid,
+    /// The parsed value most candidates agree on.
+    MajorityVote,
+    /// The candidate that needed the fewest fixes to parse.
+    BestScore,
+    /// The candidate that passes the most `@check`s.
+    MostChecks,
+}
+
+impl CandidateStrategy {
+    pub const ALL: [CandidateStrategy; 4] = [
+        CandidateStrategy::FirstValid,
+        CandidateStrategy::MajorityVote,
+        CandidateStrategy::BestScore,
+        CandidateStrategy::MostChecks,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CandidateStrategy::FirstValid => "first_valid",
+            CandidateStrategy::MajorityVote => "majority_vote",
+            CandidateStrategy::BestScore => "best_score",
+            CandidateStrategy::MostChecks => "most_checks",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionType {
     pub dependencies: (HashSet<String>, HashSet<String>),
     pub prompt: Option<RawString>,
     pub client: Option<(String, Span)>,
+    pub candidates: Option<Candidates>,
 }
 
 #[derive(Debug, Clone)]
@@ -234,6 +300,9 @@ pub(super) struct Types {
     pub(super) class_attributes: HashMap<ast::TypeExpId, ClassAttributes>,
     pub(super) class_dependencies: HashMap<ast::TypeExpId, HashSet<String>>,
     pub(super) enum_dependencies: HashMap<ast::TypeExpId, HashSet<String>>,
+    /// Direct parents of each class, in `extends` order. Classes that don't
+    /// extend anything (or are part of an inheritance cycle) are not present.
+    pub(super) class_parents: HashMap<ast::TypeExpId, Vec<ast::TypeExpId>>,
 
     /// Strongly connected components of the dependency graph.
     ///
@@ -373,6 +442,7 @@ fn visit_function<'db>(idx: ValExpId, function: &'db ast::ValueExprBlock, ctx: &
 
     let mut prompt = None;
     let mut client = None;
+    let mut candidates = None;
     function
         .iter_fields()
         .for_each(|(_idx, field)| match field.name() {
@@ -389,6 +459,12 @@ fn visit_function<'db>(idx: ValExpId, function: &'db ast::ValueExprBlock, ctx: &
                     None => None,
                 }
             }
+            "candidates" => {
+                candidates = match &field.expr {
+                    Some(val) => visit_candidates(field.span(), val, ctx.diagnostics),
+                    None => None,
+                }
+            }
             config => ctx.push_error(DatamodelError::new_validation_error(
                 &format!("Unknown field `{}` in function", config),
                 field.span().clone(),
@@ -403,6 +479,7 @@ fn visit_function<'db>(idx: ValExpId, function: &'db ast::ValueExprBlock, ctx: &
                     dependencies: (input_deps.clone(), output_deps),
                     prompt: Some(prompt.clone()),
                     client: Some(client),
+                    candidates,
                 },
             );
 
@@ -436,6 +513,79 @@ fn visit_function<'db>(idx: ValExpId, function: &'db ast::ValueExprBlock, ctx: &
     }
 }
 
+/// Parses a function's `candidates { count 5 strategy majority_vote }`.
+fn visit_candidates(
+    field_span: &Span,
+    val: &Expression,
+    diagnostics: &mut Diagnostics,
+) -> Option<Candidates> {
+    let fields = crate::coerce_expression::coerce_map(val, &coerce::string_with_span, diagnostics)?;
+
+    let mut count = None;
+    let mut strategy = CandidateStrategy::FirstValid;
+    for ((name, name_span), val) in fields {
+        match name {
+            "count" => {
+                count = Some(match coerce::integer(val, diagnostics) {
+                    Some(n) if n > Candidates::MAX_COUNT as i64 => {
+                        diagnostics.push_error(DatamodelError::new_validation_error(
+                            &format!("`count` must be at most {}", Candidates::MAX_COUNT),
+                            val.span().clone(),
+                        ));
+                        None
+                    }
+                    Some(n) if n >= 1 => Some(n as usize),
+                    Some(_) => {
+                        diagnostics.push_error(DatamodelError::new_validation_error(
+                            "`count` must be at least 1",
+                            val.span().clone(),
+                        ));
+                        None
+                    }
+                    None => None,
+                })
+            }
+            "strategy" => {
+                if let Some((value, span)) = coerce::string_with_span(val, diagnostics) {
+                    match CandidateStrategy::ALL.iter().find(|s| s.name() == value) {
+                        Some(s) => strategy = *s,
+                        None => diagnostics.push_error(DatamodelError::new_validation_error(
+                            &format!(
+                                "Unknown candidate strategy: {value}. Options are {}",
+                                CandidateStrategy::ALL
+                                    .iter()
+                                    .map(|s| format!("`{}`", s.name()))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                            span.clone(),
+                        )),
+                    }
+                }
+            }
+            _ => diagnostics.push_error(DatamodelError::new_property_not_known_error(
+                name,
+                name_span.clone(),
+                ["count", "strategy"].to_vec(),
+            )),
+        }
+    }
+
+    match count {
+        Some(Some(count)) => Some(Candidates { count, strategy }),
+        // An invalid count was reported already.
+        Some(None) => None,
+        None => {
+            diagnostics.push_error(DatamodelError::new_missing_required_property_error(
+                "count",
+                "candidates",
+                field_span.clone(),
+            ));
+            None
+        }
+    }
+}
+
 fn visit_client<'db>(idx: ValExpId, client: &'db ast::ValueExprBlock, ctx: &mut Context<'db>) {
     let mut provider = None;
     let mut retry_policy = None;
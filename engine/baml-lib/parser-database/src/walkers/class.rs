@@ -4,6 +4,7 @@ use super::{field::FieldWalker, EnumWalker};
 use crate::types::Attributes;
 use baml_types::Constraint;
 use either::Either;
+use indexmap::IndexMap;
 use internal_baml_schema_ast::ast::Identifier;
 use internal_baml_schema_ast::ast::SubType;
 use internal_baml_schema_ast::ast::{self, ArgumentId, WithIdentifier, WithName, WithSpan};
@@ -32,6 +33,46 @@ impl<'db> ClassWalker<'db> {
             .into_iter()
     }
 
+    /// The classes this class directly extends, in declaration order.
+    pub fn parents(self) -> impl ExactSizeIterator<Item = ClassWalker<'db>> {
+        self.db
+            .types
+            .class_parents
+            .get(&self.id)
+            .into_iter()
+            .flatten()
+            .map(move |id| self.walk(*id))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// All the fields of the class, including the ones inherited from its
+    /// parents.
+    ///
+    /// Inherited fields come first, in `extends` order. If more than one
+    /// parent declares the same field the first one wins, and fields declared
+    /// in the class itself override inherited ones while keeping the position
+    /// of the inherited field.
+    pub fn walk_all_fields(self) -> impl ExactSizeIterator<Item = FieldWalker<'db>> {
+        let mut fields = IndexMap::new();
+        self.collect_all_fields(&mut fields);
+        fields.into_values()
+    }
+
+    fn collect_all_fields(self, fields: &mut IndexMap<&'db str, FieldWalker<'db>>) {
+        for parent in self.parents() {
+            let mut inherited = IndexMap::new();
+            parent.collect_all_fields(&mut inherited);
+            for (name, field) in inherited {
+                fields.entry(name).or_insert(field);
+            }
+        }
+
+        for field in self.static_fields() {
+            fields.insert(field.ast_field().name(), field);
+        }
+    }
+
     /// Iterate all the scalar fields in a given class in the order they were defined.
     pub fn dependencies(self) -> &'db HashSet<String> {
         &self.db.types.class_dependencies[&self.id]
@@ -71,7 +112,7 @@ impl<'db> ClassWalker<'db> {
     pub fn add_to_types(self, types: &mut internal_baml_jinja_types::PredefinedTypes) {
         types.add_class(
             self.name(),
-            self.static_fields()
+            self.walk_all_fields()
                 .filter_map(|f| {
                     f.r#type()
                         .as_ref()
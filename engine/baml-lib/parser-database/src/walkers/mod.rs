@@ -263,6 +263,9 @@ impl<'db> crate::ParserDatabase {
                 }
                 t
             }
+            // Generic types are replaced by concrete classes before the types
+            // are resolved.
+            FieldType::Generic(..) => Type::Unknown,
             FieldType::List(arity, inner, dims, ..) => {
                 let mut t = self.to_jinja_type(inner);
                 for _ in 0..*dims {
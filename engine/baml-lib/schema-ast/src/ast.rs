@@ -8,6 +8,7 @@ mod expression;
 mod field;
 
 mod identifier;
+mod import;
 mod indentation_type;
 mod newline_type;
 
@@ -24,6 +25,7 @@ pub use config::ConfigBlockProperty;
 pub use expression::{Expression, RawString};
 pub use field::{Field, FieldArity, FieldType};
 pub use identifier::{Identifier, RefIdentifier};
+pub use import::Import;
 pub use indentation_type::IndentationType;
 pub use internal_baml_diagnostics::Span;
 pub use newline_type::NewlineType;
@@ -46,6 +48,11 @@ pub use value_expression_block::{BlockArg, BlockArgs, ValueExprBlock, ValueExprB
 pub struct SchemaAst {
     /// All models, enums, composite types, datasources, generators and type aliases.
     pub tops: Vec<Top>,
+    /// The `namespace` declarations. A file should have at most one; the
+    /// span of each says which file it is for.
+    pub namespaces: Vec<Identifier>,
+    /// The `import` declarations.
+    pub imports: Vec<Import>,
 }
 
 impl Default for SchemaAst {
@@ -56,7 +63,11 @@ impl Default for SchemaAst {
 
 impl SchemaAst {
     pub fn new() -> Self {
-        SchemaAst { tops: Vec::new() }
+        SchemaAst {
+            tops: Vec::new(),
+            namespaces: Vec::new(),
+            imports: Vec::new(),
+        }
     }
 
     /// Iterate over all the top-level items in the schema.
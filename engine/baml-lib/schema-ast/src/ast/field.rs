@@ -112,6 +112,15 @@ impl FieldArity {
 #[derive(Debug, Clone)]
 pub enum FieldType {
     Symbol(FieldArity, Identifier, Option<Vec<Attribute>>),
+    /// An instantiation of a generic class, like `Page<Resume>`. These are
+    /// replaced by concrete classes before the types are resolved.
+    Generic(
+        FieldArity,
+        Identifier,
+        Vec<FieldType>,
+        Span,
+        Option<Vec<Attribute>>,
+    ),
     Primitive(FieldArity, TypeValue, Span, Option<Vec<Attribute>>),
     Literal(FieldArity, LiteralValue, Span, Option<Vec<Attribute>>),
     // The second field is the number of dims for the list
@@ -147,6 +156,7 @@ impl FieldType {
             FieldType::Primitive(.., span, _) => span,
             FieldType::Literal(.., span, _) => span,
             FieldType::Symbol(.., idn, _) => idn.span(),
+            FieldType::Generic(.., span, _) => span,
             FieldType::Union(.., span, _) => span,
             FieldType::Tuple(.., span, _) => span,
             FieldType::Map(.., span, _) => span,
@@ -161,6 +171,7 @@ impl FieldType {
         }
         match &mut as_nullable {
             FieldType::Symbol(ref mut arity, ..) => *arity = FieldArity::Optional,
+            FieldType::Generic(ref mut arity, ..) => *arity = FieldArity::Optional,
             FieldType::Primitive(ref mut arity, ..) => *arity = FieldArity::Optional,
             FieldType::Literal(ref mut arity, ..) => *arity = FieldArity::Optional,
             FieldType::Union(ref mut arity, ..) => *arity = FieldArity::Optional,
@@ -174,7 +185,7 @@ impl FieldType {
 
     pub fn is_optional(&self) -> bool {
         match self {
-            FieldType::Symbol(arity, ..) => arity.is_optional(),
+            FieldType::Symbol(arity, ..) | FieldType::Generic(arity, ..) => arity.is_optional(),
             FieldType::Union(arity, f, _, _) => {
                 arity.is_optional() || f.iter().any(|t| t.is_optional())
             }
@@ -192,6 +203,11 @@ impl FieldType {
             FieldType::Symbol(_, idn, ..) => {
                 vec![&idn]
             }
+            FieldType::Generic(_, idn, args, ..) => {
+                let mut idns = vec![idn];
+                idns.extend(args.iter().flat_map(|t| t.flat_idns()));
+                idns
+            }
 
             FieldType::Union(_, f, _, _) => f.iter().flat_map(|t| t.flat_idns()).collect(),
             FieldType::Tuple(_, f, ..) => f.iter().flat_map(|t| t.flat_idns()).collect(),
@@ -209,6 +225,7 @@ impl FieldType {
     pub fn attributes(&self) -> &[Attribute] {
         match self {
             FieldType::Symbol(.., attr)
+            | FieldType::Generic(.., attr)
             | FieldType::Primitive(.., attr)
             | FieldType::Literal(.., attr)
             | FieldType::Union(.., attr)
@@ -221,6 +238,7 @@ impl FieldType {
     pub fn reset_attributes(&mut self) {
         match self {
             FieldType::Symbol(.., attr)
+            | FieldType::Generic(.., attr)
             | FieldType::Primitive(.., attr)
             | FieldType::Literal(.., attr)
             | FieldType::Union(.., attr)
@@ -233,6 +251,7 @@ impl FieldType {
     pub fn set_attributes(&mut self, attributes: Vec<Attribute>) {
         match self {
             FieldType::Symbol(.., attr)
+            | FieldType::Generic(.., attr)
             | FieldType::Primitive(.., attr)
             | FieldType::Literal(.., attr)
             | FieldType::Union(.., attr)
@@ -245,6 +264,7 @@ impl FieldType {
     pub fn extend_attributes(&mut self, attributes: Vec<Attribute>) {
         match self {
             FieldType::Symbol(.., attr)
+            | FieldType::Generic(.., attr)
             | FieldType::Primitive(.., attr)
             | FieldType::Literal(.., attr)
             | FieldType::Union(.., attr)
@@ -287,6 +307,25 @@ impl FieldType {
             (Symbol(..), _) => {
                 panic!("Different types:\n{self}\n---\n{other}")
             }
+            (
+                Generic(arity1, ident1, args1, _, attrs1),
+                Generic(arity2, ident2, args2, _, attrs2),
+            ) => {
+                assert_eq!(arity1, arity2);
+                ident1.assert_eq_up_to_span(ident2);
+                assert_eq!(
+                    args1.len(),
+                    args2.len(),
+                    "Different number of type arguments"
+                );
+                for (a1, a2) in args1.iter().zip(args2) {
+                    a1.assert_eq_up_to_span(a2);
+                }
+                attrs_eq(attrs1, attrs2);
+            }
+            (Generic(..), _) => {
+                panic!("Different types:\n{self}\n---\n{other}")
+            }
             (Primitive(arity1, prim_ty1, _, attrs1), Primitive(arity2, prim_ty2, _, attrs2)) => {
                 assert_eq!(arity1, arity2);
                 assert_eq!(prim_ty1, prim_ty2);
@@ -362,6 +401,16 @@ impl std::fmt::Display for FieldType {
                     if arity.is_optional() { "?" } else { "" }
                 )
             }
+            FieldType::Generic(arity, idn, args, ..) => write!(
+                f,
+                "{}<{}>{}",
+                idn.name(),
+                args.iter()
+                    .map(|t| t.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                if arity.is_optional() { "?" } else { "" }
+            ),
             FieldType::Union(arity, ft, ..) => {
                 let ft = ft.iter().map(|t| t.to_string()).collect::<Vec<_>>();
                 write!(
@@ -0,0 +1,21 @@
+use super::{Identifier, Span, WithSpan};
+
+/// An `import` of another `baml_src` directory.
+///
+/// ```ignore
+/// import "../../shared/baml_src" as shared
+/// ```
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// The directory, relative to the file with the import.
+    pub path: String,
+    /// The namespace the package's declarations are put in.
+    pub alias: Identifier,
+    pub span: Span,
+}
+
+impl WithSpan for Import {
+    fn span(&self) -> &Span {
+        &self.span
+    }
+}
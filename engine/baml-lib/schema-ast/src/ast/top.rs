@@ -1,6 +1,6 @@
 use super::{
-    traits::WithSpan, Identifier, Span, TemplateString, TypeExpressionBlock, ValueExprBlock,
-    WithIdentifier,
+    traits::WithSpan, BlockArgs, FieldType, Identifier, Span, TemplateString, TypeExpressionBlock,
+    ValueExprBlock, WithIdentifier,
 };
 
 /// Enum for distinguishing between top-level entries
@@ -68,6 +68,45 @@ impl Top {
             _ => None,
         }
     }
+
+    /// The name of the item, for passes that rename declarations.
+    pub fn identifier_mut(&mut self) -> &mut Identifier {
+        match self {
+            Top::Enum(x) | Top::Class(x) => &mut x.name,
+            Top::Function(x)
+            | Top::Client(x)
+            | Top::Generator(x)
+            | Top::TestCase(x)
+            | Top::RetryPolicy(x) => &mut x.name,
+            Top::TemplateString(x) => &mut x.name,
+        }
+    }
+
+    /// Every field type declared in the item: class fields plus the inputs
+    /// and outputs of functions and template strings. Used by passes that
+    /// rewrite types in place.
+    pub fn field_types_mut(&mut self) -> Vec<&mut FieldType> {
+        fn block_args(args: &mut Option<BlockArgs>) -> impl Iterator<Item = &mut FieldType> {
+            args.iter_mut()
+                .flat_map(|args| args.args.iter_mut().map(|(_, arg)| &mut arg.field_type))
+        }
+
+        match self {
+            Top::Enum(block) | Top::Class(block) => block
+                .fields
+                .iter_mut()
+                .filter_map(|field| field.expr.as_mut())
+                .collect(),
+            Top::Function(block)
+            | Top::Client(block)
+            | Top::Generator(block)
+            | Top::TestCase(block)
+            | Top::RetryPolicy(block) => block_args(&mut block.input)
+                .chain(block.output.iter_mut().map(|output| &mut output.field_type))
+                .collect(),
+            Top::TemplateString(template) => block_args(&mut template.input).collect(),
+        }
+    }
 }
 
 impl WithIdentifier for Top {
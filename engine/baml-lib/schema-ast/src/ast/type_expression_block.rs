@@ -42,6 +42,20 @@ pub struct TypeExpressionBlock {
     ///       ^^^
     /// ```
     pub name: Identifier,
+    /// The type parameters of a generic class. Empty for everything else.
+    ///
+    /// ```ignore
+    /// class Page<T> { ... }
+    ///            ^
+    /// ```
+    pub type_parameters: Vec<Identifier>,
+    /// The classes this class inherits fields from. Always empty for enums.
+    ///
+    /// ```ignore
+    /// class Bar extends Foo, Baz { ... }
+    ///                   ^^^  ^^^
+    /// ```
+    pub extends: Vec<Identifier>,
     /// The values of the enum, or fields of the class.
     ///
     /// ```ignore
@@ -1,9 +1,4 @@
-use std::{
-    borrow::BorrowMut,
-    cell::{RefCell, RefMut},
-    rc::Rc,
-    sync::Arc,
-};
+use std::cell::RefCell;
 
 use crate::parser::{BAMLParser, Rule};
 use anyhow::{anyhow, Result};
@@ -18,6 +13,24 @@ pub struct FormatOptions {
     pub fail_on_unhandled_rule: bool,
 }
 
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 2,
+            fail_on_unhandled_rule: false,
+        }
+    }
+}
+
+/// Lines longer than this are wrapped where the grammar allows it (argument
+/// lists and arrays).
+const LINE_WIDTH: usize = 100;
+
+/// Delimiters of the placeholders that stand in for verbatim text while the
+/// document is rendered (see `Formatter::verbatim`).
+const VERBATIM_START: char = '\u{E000}';
+const VERBATIM_END: char = '\u{E001}';
+
 pub fn format_schema(source: &str, format_options: FormatOptions) -> Result<String> {
     let mut schema = BAMLParser::parse(Rule::schema, source)?;
     let schema_pair = schema.next().ok_or(anyhow!("Expected a schema"))?;
@@ -28,13 +41,15 @@ pub fn format_schema(source: &str, format_options: FormatOptions) -> Result<Stri
     let formatter = Formatter {
         indent_width: format_options.indent_width,
         fail_on_unhandled_rule: format_options.fail_on_unhandled_rule,
+        verbatim: RefCell::new(vec![]),
     };
 
     let doc = formatter.schema_to_doc(schema_pair.into_inner())?;
     let mut w = Vec::new();
-    doc.render(10, &mut w)
+    doc.render(LINE_WIDTH, &mut w)
         .map_err(|_| anyhow!("Failed to render doc"))?;
-    Ok(String::from_utf8(w).map_err(|_| anyhow!("Failed to convert to string"))?)
+    let rendered = String::from_utf8(w).map_err(|_| anyhow!("Failed to convert to string"))?;
+    Ok(formatter.restore_verbatim(&rendered))
 }
 
 macro_rules! next_pair {
@@ -72,134 +87,448 @@ macro_rules! next_pair {
     }};
 }
 
+/// The source lines an item of a block (or of the whole file) came from, so
+/// that the blank lines the user put between items can be kept.
+#[derive(Clone, Copy)]
+struct Lines {
+    first: usize,
+    last: usize,
+}
+
+impl Lines {
+    fn of(pair: &Pair<'_, Rule>) -> Self {
+        Self::spanning(pair, pair)
+    }
+
+    fn spanning(first: &Pair<'_, Rule>, last: &Pair<'_, Rule>) -> Self {
+        let (first_line, _) = first.as_span().start_pos().line_col();
+        let (mut last_line, _) = last.as_span().end_pos().line_col();
+        // Rules that swallow their trailing newline end on the next line.
+        if last.as_str().ends_with('\n') {
+            last_line -= 1;
+        }
+        Lines {
+            first: first_line,
+            last: last_line.max(first_line),
+        }
+    }
+}
+
+struct Item<'a> {
+    lines: Lines,
+    doc: RcDoc<'a, ()>,
+    /// Top-level blocks are always followed by a blank line.
+    is_block: bool,
+}
+
+impl<'a> Item<'a> {
+    fn new(lines: Lines, doc: RcDoc<'a, ()>) -> Self {
+        Item {
+            lines,
+            doc,
+            is_block: false,
+        }
+    }
+
+    fn block(lines: Lines, doc: RcDoc<'a, ()>) -> Self {
+        Item {
+            lines,
+            doc,
+            is_block: true,
+        }
+    }
+}
+
+/// Puts every item on its own line, keeping (at most) one blank line where
+/// the source had one or more.
+fn join_items(items: Vec<Item<'_>>) -> RcDoc<'_, ()> {
+    let mut doc = RcDoc::nil();
+    let mut prev: Option<(Lines, bool)> = None;
+
+    for item in items {
+        if let Some((prev_lines, prev_is_block)) = prev {
+            doc = doc.append(RcDoc::hardline());
+            if prev_is_block || item.lines.first > prev_lines.last + 1 {
+                doc = doc.append(RcDoc::hardline());
+            }
+        }
+        prev = Some((item.lines, item.is_block));
+        doc = doc.append(item.doc);
+    }
+
+    doc
+}
+
+fn with_trailing_comments<'a>(doc: RcDoc<'a, ()>, comments: Vec<&'a str>) -> RcDoc<'a, ()> {
+    if comments.is_empty() {
+        doc
+    } else {
+        doc.append(RcDoc::space())
+            .append(RcDoc::text(comments.join(" ")))
+    }
+}
+
+/// Comments in a block header (e.g. between the arguments of a function) have
+/// no good place to go once the header is reflowed, so blocks that have them
+/// are left as they are.
+fn header_has_comments(pair: &Pair<'_, Rule>) -> bool {
+    pair.clone()
+        .into_inner()
+        .take_while(|p| p.as_rule() != Rule::BLOCK_OPEN && p.as_rule() != Rule::raw_string_literal)
+        .flat_map(|p| p.into_inner().flatten())
+        .any(|p| matches!(p.as_rule(), Rule::comment_block | Rule::trailing_comment))
+}
+
 struct Formatter {
     indent_width: isize,
     fail_on_unhandled_rule: bool,
+    /// Text that must come out exactly as it went in: prompts and other raw
+    /// strings, jinja expressions, block comments and anything we don't
+    /// understand. The document only holds a placeholder for each of these,
+    /// so that their contents are neither re-indented nor trimmed.
+    verbatim: RefCell<Vec<String>>,
 }
 
 impl Formatter {
-    fn schema_to_doc<'a>(&self, mut pairs: Pairs<'a, Rule>) -> Result<RcDoc<'a, ()>> {
-        let mut doc = RcDoc::nil();
+    fn schema_to_doc<'a>(&self, pairs: Pairs<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        let mut items = vec![];
 
-        for pair in &mut pairs {
-            match pair.as_rule() {
+        for pair in pairs {
+            let lines = Lines::of(&pair);
+            let item = match pair.as_rule() {
                 Rule::type_expression_block => {
-                    doc = doc.append(self.type_expression_block_to_doc(pair.into_inner())?);
+                    Item::block(lines, self.type_expression_block_to_doc(pair)?)
                 }
-                Rule::EOI => {
-                    // skip
+                Rule::value_expression_block => {
+                    Item::block(lines, self.value_expression_block_to_doc(pair)?)
                 }
-                _ => {
-                    doc = doc.append(self.unhandled_rule_to_doc(pair)?);
+                Rule::template_declaration => {
+                    Item::block(lines, self.template_declaration_to_doc(pair)?)
                 }
-            }
+                Rule::type_alias => Item::new(lines, self.type_alias_to_doc(pair)?),
+                // `namespace a.b` and `import "path" as name`: one space
+                // between each part.
+                Rule::namespace_declaration | Rule::import_declaration => Item::new(
+                    lines,
+                    RcDoc::intersperse(pair.into_inner().map(pair_to_doc_text), RcDoc::space()),
+                ),
+                Rule::comment_block => Item::new(lines, self.comment_block_to_doc(pair)),
+                Rule::raw_string_literal => Item::new(lines, self.verbatim(pair.as_str())),
+                Rule::empty_lines | Rule::EOI => {
+                    // skip
+                    continue;
+                }
+                _ => Item::new(lines, self.unhandled_rule_to_doc(pair)?),
+            };
+            items.push(item);
         }
-        Ok(doc)
+
+        if items.is_empty() {
+            return Ok(RcDoc::nil());
+        }
+        Ok(join_items(items).append(RcDoc::hardline()))
     }
 
-    fn type_expression_block_to_doc<'a>(
-        &self,
-        mut pairs: Pairs<'a, Rule>,
-    ) -> Result<RcDoc<'a, ()>> {
+    fn type_expression_block_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        if header_has_comments(&pair) {
+            return Ok(self.verbatim(pair.as_str().trim_end()));
+        }
+
+        let mut pairs = pair.into_inner();
         let class_or_enum = next_pair!(pairs, Rule::identifier)?;
         let ident = next_pair!(pairs, Rule::identifier)?;
-        next_pair!(pairs, Rule::named_argument_list, optional);
+
+        let mut header = RcDoc::nil()
+            .append(pair_to_doc_text(class_or_enum))
+            .append(RcDoc::space())
+            .append(pair_to_doc_text(ident));
+
+        if let Some(type_parameters) = next_pair!(pairs, Rule::type_parameters, optional) {
+            let params = type_parameters.into_inner().map(pair_to_doc_text);
+            header = header
+                .append(RcDoc::text("<"))
+                .append(RcDoc::intersperse(params, RcDoc::text(", ")))
+                .append(RcDoc::text(">"));
+        }
+        if let Some(args) = next_pair!(pairs, Rule::named_argument_list, optional) {
+            header = header.append(self.named_argument_list_to_doc(args)?);
+        }
+        if let Some(extends) = next_pair!(pairs, Rule::extends_clause, optional) {
+            let parents = extends
+                .into_inner()
+                .filter(|p| p.as_rule() == Rule::identifier)
+                .map(pair_to_doc_text);
+            header = header
+                .append(RcDoc::text(" extends "))
+                .append(RcDoc::intersperse(parents, RcDoc::text(", ")));
+        }
+
         next_pair!(pairs, Rule::BLOCK_OPEN)?;
         let contents = next_pair!(pairs, Rule::type_expression_contents)?;
         next_pair!(pairs, Rule::BLOCK_CLOSE)?;
 
-        Ok(RcDoc::nil()
-            .append(pair_to_doc_text(class_or_enum))
-            .append(RcDoc::space())
-            .append(pair_to_doc_text(ident))
-            .append(RcDoc::space())
-            .append(RcDoc::text("{"))
-            .append(
-                self.type_expression_contents_to_doc(contents.into_inner())?
-                    .nest(self.indent_width)
-                    .group(),
-            )
-            .append(RcDoc::text("}")))
+        let items = self.type_expression_contents_to_items(contents.into_inner())?;
+        Ok(header.append(RcDoc::space()).append(self.braces(items)))
     }
 
-    fn type_expression_contents_to_doc<'a>(
+    fn type_expression_contents_to_items<'a>(
         &self,
-        mut pairs: Pairs<'a, Rule>,
-    ) -> Result<RcDoc<'a, ()>> {
-        let mut content_docs = vec![];
+        pairs: Pairs<'a, Rule>,
+    ) -> Result<Vec<Item<'a>>> {
+        let mut items = vec![];
 
-        for pair in &mut pairs {
-            match pair.as_rule() {
-                Rule::type_expression => {
-                    content_docs.push(self.type_expression_to_doc(pair.into_inner())?);
-                }
-                Rule::block_attribute => {
-                    content_docs.push(pair_to_doc_text(pair));
-                }
-                Rule::comment_block => {
-                    content_docs.push(pair_to_doc_text(pair));
-                }
+        for pair in pairs {
+            let lines = Lines::of(&pair);
+            let doc = match pair.as_rule() {
+                Rule::type_expression => self.type_expression_to_doc(pair)?,
+                Rule::block_attribute => self.attribute_to_doc(pair)?,
+                Rule::comment_block => self.comment_block_to_doc(pair),
                 Rule::empty_lines => {
                     // skip
+                    continue;
+                }
+                _ => self.unhandled_rule_to_doc(pair)?,
+            };
+            items.push(Item::new(lines, doc));
+        }
+
+        Ok(items)
+    }
+
+    fn type_expression_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        let mut pairs = pair.into_inner();
+        let ident = next_pair!(pairs, Rule::identifier)?;
+
+        // Comments may sit between the type and its attributes; they all move
+        // to the end of the line so that they don't comment anything out.
+        let mut comments = vec![];
+        let mut doc = pair_to_doc_text(ident);
+
+        if let Some(field_type_chain) = next_pair!(pairs, Rule::field_type_chain, optional) {
+            doc = doc
+                .append(RcDoc::space())
+                .append(self.field_type_chain_to_doc(field_type_chain, &mut comments)?);
+        }
+
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::field_attribute => {
+                    doc = doc
+                        .append(RcDoc::space())
+                        .append(self.attribute_to_doc(pair)?);
+                }
+                Rule::trailing_comment => {
+                    comments.push(pair.as_str().trim());
                 }
                 _ => {
-                    content_docs.push(self.unhandled_rule_to_doc(pair)?);
+                    doc = doc
+                        .append(RcDoc::space())
+                        .append(self.unhandled_rule_to_doc(pair)?);
                 }
             }
         }
 
-        let doc = if content_docs.len() > 0 {
-            content_docs
-                .into_iter()
-                .fold(RcDoc::hardline(), |acc, doc| {
-                    acc.append(doc).append(RcDoc::hardline())
-                })
-        } else {
-            RcDoc::nil()
-        };
-
-        Ok(doc)
+        Ok(with_trailing_comments(doc, comments))
     }
 
-    fn type_expression_to_doc<'a>(&self, mut pairs: Pairs<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+    fn value_expression_block_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        if header_has_comments(&pair) {
+            return Ok(self.verbatim(pair.as_str().trim_end()));
+        }
+
+        let mut pairs = pair.into_inner();
+        let keyword = next_pair!(pairs, Rule::value_expression_keyword)?;
         let ident = next_pair!(pairs, Rule::identifier)?;
-        let field_type_chain = next_pair!(pairs, Rule::field_type_chain)?;
 
-        let mut doc = RcDoc::nil()
-            .append(pair_to_doc_text(ident))
+        let mut header = RcDoc::nil()
+            .append(pair_to_doc_text(keyword))
             .append(RcDoc::space())
-            .append(self.field_type_chain_to_doc(field_type_chain.into_inner())?);
+            .append(pair_to_doc_text(ident));
+
+        if let Some(args) = next_pair!(pairs, Rule::named_argument_list, optional) {
+            header = header.append(self.named_argument_list_to_doc(args)?);
+        }
+        let arrow = next_pair!(pairs, Rule::ARROW, optional);
+        if let Some(output) = next_pair!(pairs, Rule::field_type_chain, optional) {
+            header = header
+                .append(RcDoc::text(if arrow.is_some() { " -> " } else { " " }))
+                .append(self.field_type_chain_to_doc(output, &mut vec![])?);
+        } else if arrow.is_some() {
+            header = header.append(RcDoc::text(" ->"));
+        }
+
+        next_pair!(pairs, Rule::SPACER_TEXT, optional);
+        next_pair!(pairs, Rule::BLOCK_OPEN)?;
+        let contents = next_pair!(pairs, Rule::value_expression_contents)?;
+        next_pair!(pairs, Rule::BLOCK_CLOSE)?;
+
+        let items = self.value_expression_contents_to_items(contents.into_inner())?;
+        Ok(header.append(RcDoc::space()).append(self.braces(items)))
+    }
+
+    fn value_expression_contents_to_items<'a>(
+        &self,
+        pairs: Pairs<'a, Rule>,
+    ) -> Result<Vec<Item<'a>>> {
+        let mut items = vec![];
 
         for pair in pairs {
-            match pair.as_rule() {
-                Rule::NEWLINE => {
+            let lines = Lines::of(&pair);
+            let doc = match pair.as_rule() {
+                Rule::value_expression => self.value_expression_to_doc(pair)?,
+                Rule::block_attribute => self.attribute_to_doc(pair)?,
+                Rule::comment_block => self.comment_block_to_doc(pair),
+                Rule::empty_lines => {
                     // skip
+                    continue;
+                }
+                _ => self.unhandled_rule_to_doc(pair)?,
+            };
+            items.push(Item::new(lines, doc));
+        }
+
+        Ok(items)
+    }
+
+    fn value_expression_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        let mut pairs = pair.into_inner();
+        let ident = next_pair!(pairs, Rule::identifier)?;
+
+        let mut comments = vec![];
+        let mut doc = pair_to_doc_text(ident);
+
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::expression => {
+                    doc = doc
+                        .append(RcDoc::space())
+                        .append(self.expression_to_doc(pair)?);
                 }
                 Rule::field_attribute => {
-                    doc = doc.append(pair_to_doc_text(pair).nest(self.indent_width).group());
+                    doc = doc
+                        .append(RcDoc::space())
+                        .append(self.attribute_to_doc(pair)?);
                 }
                 Rule::trailing_comment => {
-                    doc = doc.append(pair_to_doc_text(pair).nest(self.indent_width).group());
+                    comments.push(pair.as_str().trim());
                 }
                 _ => {
-                    doc = doc.append(self.unhandled_rule_to_doc(pair)?);
+                    doc = doc
+                        .append(RcDoc::space())
+                        .append(self.unhandled_rule_to_doc(pair)?);
                 }
             }
         }
 
-        Ok(doc)
+        Ok(with_trailing_comments(doc, comments))
     }
 
-    fn field_type_chain_to_doc<'a>(&self, pairs: Pairs<'a, Rule>) -> Result<RcDoc<'a, ()>> {
-        let mut docs = vec![];
+    fn template_declaration_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        if header_has_comments(&pair) {
+            return Ok(self.verbatim(pair.as_str().trim_end()));
+        }
+
+        let mut pairs = pair.into_inner();
+        let keyword = next_pair!(pairs, Rule::TEMPLATE_KEYWORD)?;
+        let ident = next_pair!(pairs, Rule::identifier)?;
+
+        let mut doc = RcDoc::nil()
+            .append(pair_to_doc_text(keyword))
+            .append(RcDoc::space())
+            .append(pair_to_doc_text(ident));
+
+        let assignment = next_pair!(pairs, Rule::assignment, optional);
+        if assignment.is_some() {
+            doc = doc.append(RcDoc::text(" ="));
+        }
+        if let Some(args) = next_pair!(pairs, Rule::named_argument_list, optional) {
+            if assignment.is_some() {
+                doc = doc.append(RcDoc::space());
+            }
+            doc = doc.append(self.named_argument_list_to_doc(args)?);
+        }
+        let template = next_pair!(pairs, Rule::raw_string_literal)?;
+
+        Ok(doc
+            .append(RcDoc::space())
+            .append(self.verbatim(template.as_str())))
+    }
+
+    fn type_alias_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        let mut pairs = pair.into_inner();
+        let keyword = next_pair!(pairs, Rule::TYPE_KEYWORD)?;
+        let ident = next_pair!(pairs, Rule::identifier)?;
+        let base_type = next_pair!(pairs, Rule::base_type)?;
+
+        let mut comments = vec![];
+        let mut doc = RcDoc::nil()
+            .append(pair_to_doc_text(keyword))
+            .append(RcDoc::space())
+            .append(pair_to_doc_text(ident))
+            .append(RcDoc::space())
+            .append(self.field_type_to_doc(base_type, &mut comments)?);
 
         for pair in pairs {
+            doc = doc.append(RcDoc::space()).append(match pair.as_rule() {
+                Rule::field_attribute => self.attribute_to_doc(pair)?,
+                _ => self.unhandled_rule_to_doc(pair)?,
+            });
+        }
+
+        Ok(with_trailing_comments(doc, comments))
+    }
+
+    fn named_argument_list_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        let mut args = vec![];
+        for pair in pair.into_inner() {
+            if pair.as_rule() != Rule::named_argument {
+                continue;
+            }
+            let mut pairs = pair.into_inner();
+            let ident = next_pair!(pairs, Rule::identifier)?;
+            let mut doc = pair_to_doc_text(ident);
+            if let Some(field_type_chain) = next_pair!(pairs, Rule::field_type_chain, optional) {
+                doc = doc
+                    .append(RcDoc::text(": "))
+                    .append(self.field_type_chain_to_doc(field_type_chain, &mut vec![])?);
+            } else if next_pair!(pairs, Rule::colon, optional).is_some() {
+                doc = doc.append(RcDoc::text(":"));
+            }
+            args.push(doc);
+        }
+
+        if args.is_empty() {
+            return Ok(RcDoc::text("()"));
+        }
+
+        Ok(RcDoc::text("(")
+            .append(
+                RcDoc::line_()
+                    .append(RcDoc::intersperse(
+                        args,
+                        RcDoc::text(",").append(RcDoc::line()),
+                    ))
+                    .nest(self.indent_width),
+            )
+            .append(RcDoc::line_())
+            .append(RcDoc::text(")"))
+            .group())
+    }
+
+    fn field_type_chain_to_doc<'a>(
+        &self,
+        pair: Pair<'a, Rule>,
+        comments: &mut Vec<&'a str>,
+    ) -> Result<RcDoc<'a, ()>> {
+        let mut docs = vec![];
+
+        for pair in pair.into_inner() {
             match pair.as_rule() {
                 Rule::field_type_with_attr => {
-                    docs.push(self.field_type_with_attr_to_doc(pair.into_inner())?);
+                    docs.push(self.field_type_to_doc(pair, comments)?);
                 }
                 Rule::field_operator => {
-                    docs.push(RcDoc::text("|"));
+                    // joined below
                 }
                 _ => {
                     docs.push(self.unhandled_rule_to_doc(pair)?);
@@ -207,76 +536,372 @@ impl Formatter {
             }
         }
 
-        Ok(RcDoc::intersperse(docs, RcDoc::space())
-            .nest(self.indent_width)
-            .group())
+        Ok(RcDoc::intersperse(docs, RcDoc::text(" | ")))
     }
 
-    fn field_type_with_attr_to_doc<'a>(&self, mut pairs: Pairs<'a, Rule>) -> Result<RcDoc<'a, ()>> {
-        let mut docs = vec![];
+    /// Formats any of the rules a type is made of, normalizing the spacing
+    /// between its parts. Trailing comments found inside the type are pushed
+    /// onto `comments` for the caller to put at the end of the line.
+    fn field_type_to_doc<'a>(
+        &self,
+        pair: Pair<'a, Rule>,
+        comments: &mut Vec<&'a str>,
+    ) -> Result<RcDoc<'a, ()>> {
+        match pair.as_rule() {
+            Rule::field_type
+            | Rule::non_union
+            | Rule::base_type
+            | Rule::base_type_without_array
+            | Rule::array_notation
+            | Rule::field_type_with_attr
+            | Rule::base_type_with_attr => {
+                let mut doc = RcDoc::nil();
+                for pair in pair.into_inner() {
+                    doc = match pair.as_rule() {
+                        Rule::optional_token => doc.append(RcDoc::text("?")),
+                        Rule::array_suffix => doc.append(RcDoc::text("[]")),
+                        Rule::field_attribute => doc
+                            .append(RcDoc::space())
+                            .append(self.attribute_to_doc(pair)?),
+                        Rule::trailing_comment => {
+                            comments.push(pair.as_str().trim());
+                            doc
+                        }
+                        _ => doc.append(self.field_type_to_doc(pair, comments)?),
+                    };
+                }
+                Ok(doc)
+            }
+            Rule::union => {
+                let mut docs = vec![];
+                for pair in pair.into_inner() {
+                    if pair.as_rule() != Rule::field_operator {
+                        docs.push(self.field_type_to_doc(pair, comments)?);
+                    }
+                }
+                Ok(RcDoc::intersperse(docs, RcDoc::text(" | ")))
+            }
+            Rule::map => {
+                let mut docs = vec![];
+                for pair in pair.into_inner() {
+                    docs.push(self.field_type_to_doc(pair, comments)?);
+                }
+                Ok(RcDoc::text("map<")
+                    .append(RcDoc::intersperse(docs, RcDoc::text(", ")))
+                    .append(RcDoc::text(">")))
+            }
+            Rule::generic_type => {
+                let mut pairs = pair.into_inner();
+                let ident = next_pair!(pairs, Rule::identifier)?;
+                let mut docs = vec![];
+                for pair in pairs {
+                    docs.push(self.field_type_to_doc(pair, comments)?);
+                }
+                Ok(pair_to_doc_text(ident)
+                    .append(RcDoc::text("<"))
+                    .append(RcDoc::intersperse(docs, RcDoc::text(", ")))
+                    .append(RcDoc::text(">")))
+            }
+            Rule::group | Rule::tuple | Rule::parenthesized_type => {
+                let mut docs: Vec<RcDoc<'a, ()>> = vec![];
+                for pair in pair.into_inner() {
+                    match pair.as_rule() {
+                        Rule::openParan | Rule::closeParan => {}
+                        // Only groups have attributes outside of their
+                        // `field_type_with_attr`s.
+                        Rule::field_attribute => {
+                            let last = docs.pop().unwrap_or_else(RcDoc::nil);
+                            docs.push(
+                                last.append(RcDoc::space())
+                                    .append(self.attribute_to_doc(pair)?),
+                            );
+                        }
+                        _ => docs.push(self.field_type_to_doc(pair, comments)?),
+                    }
+                }
+                Ok(RcDoc::text("(")
+                    .append(RcDoc::intersperse(docs, RcDoc::text(", ")))
+                    .append(RcDoc::text(")")))
+            }
+            Rule::identifier | Rule::literal_type => Ok(pair_to_doc_text(pair)),
+            _ => self.unhandled_rule_to_doc(pair),
+        }
+    }
+
+    /// `@name(args)` and `@@name(args)`.
+    fn attribute_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        let prefix = if pair.as_rule() == Rule::block_attribute {
+            "@@"
+        } else {
+            "@"
+        };
+        let mut pairs = pair.into_inner();
+        let ident = next_pair!(pairs, Rule::identifier)?;
+        let mut doc = RcDoc::text(prefix).append(pair_to_doc_text(ident));
+
+        if let Some(arguments) = next_pair!(pairs, Rule::arguments_list, optional) {
+            let mut args = vec![];
+            for pair in arguments.into_inner() {
+                args.push(self.expression_to_doc(pair)?);
+            }
+            doc = doc
+                .append(RcDoc::text("("))
+                .append(RcDoc::intersperse(args, RcDoc::text(", ")))
+                .append(RcDoc::text(")"));
+        }
+
+        Ok(doc)
+    }
+
+    fn expression_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        match pair.as_rule() {
+            Rule::expression | Rule::string_literal => match pair.into_inner().next() {
+                Some(inner) => self.expression_to_doc(inner),
+                None => Ok(RcDoc::nil()),
+            },
+            Rule::map_expression => self.map_expression_to_doc(pair),
+            Rule::array_expression => self.array_expression_to_doc(pair),
+            Rule::raw_string_literal
+            | Rule::jinja_expression
+            | Rule::unterminated_string_literal => Ok(self.verbatim(pair.as_str())),
+            Rule::numeric_literal
+            | Rule::quoted_string_literal
+            | Rule::unquoted_string_literal
+            | Rule::identifier => Ok(pair_to_doc_text(pair)),
+            _ => self.unhandled_rule_to_doc(pair),
+        }
+    }
 
-        for pair in &mut pairs {
+    /// Maps always have one entry per line.
+    fn map_expression_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        let mut items = vec![];
+
+        for pair in pair.into_inner() {
             match pair.as_rule() {
-                Rule::field_type => {
-                    docs.push(self.field_type_to_doc(pair.into_inner())?);
-                }
-                Rule::field_attribute | Rule::trailing_comment => {
-                    docs.push(pair_to_doc_text(pair));
+                Rule::map_entry => items.extend(self.map_entry_to_items(pair)?),
+                Rule::comment_block => {
+                    items.push(Item::new(Lines::of(&pair), self.comment_block_to_doc(pair)))
                 }
-                _ => {
-                    docs.push(self.unhandled_rule_to_doc(pair)?);
+                Rule::empty_lines => {
+                    // skip
                 }
+                _ => items.push(Item::new(
+                    Lines::of(&pair),
+                    self.unhandled_rule_to_doc(pair)?,
+                )),
             }
         }
 
-        Ok(RcDoc::intersperse(docs, RcDoc::space())
-            .nest(self.indent_width)
-            .group())
+        Ok(self.braces(items))
     }
 
-    fn field_type_to_doc<'a>(&self, pairs: Pairs<'a, Rule>) -> Result<RcDoc<'a, ()>> {
-        let mut docs = vec![];
-        self.field_type_to_doc_impl(pairs, &mut docs)?;
-        Ok(docs
-            .into_iter()
-            .fold(RcDoc::nil(), |acc, doc| acc.append(doc)))
+    /// The comments above an entry, followed by the entry itself.
+    fn map_entry_to_items<'a>(&self, pair: Pair<'a, Rule>) -> Result<Vec<Item<'a>>> {
+        let entry_pair = pair.clone();
+        let mut items = vec![];
+        let mut entry: Option<(Lines, RcDoc<'a, ()>)> = None;
+        let mut comments = vec![];
+
+        for pair in pair.into_inner() {
+            match (pair.as_rule(), entry.take()) {
+                (Rule::comment_block, None) => {
+                    items.push(Item::new(Lines::of(&pair), self.comment_block_to_doc(pair)))
+                }
+                (Rule::empty_lines, e) => entry = e,
+                (Rule::map_key, None) => {
+                    entry = Some((Lines::spanning(&pair, &entry_pair), pair_to_doc_text(pair)))
+                }
+                (Rule::expression, Some((lines, doc))) => {
+                    entry = Some((
+                        lines,
+                        doc.append(RcDoc::space())
+                            .append(self.expression_to_doc(pair)?),
+                    ))
+                }
+                (Rule::trailing_comment, e) => {
+                    comments.push(pair.as_str().trim());
+                    entry = e;
+                }
+                (_, Some((lines, doc))) => {
+                    entry = Some((
+                        lines,
+                        doc.append(RcDoc::space())
+                            .append(self.unhandled_rule_to_doc(pair)?),
+                    ))
+                }
+                (_, None) => items.push(Item::new(
+                    Lines::of(&pair),
+                    self.unhandled_rule_to_doc(pair)?,
+                )),
+            }
+        }
+
+        if let Some((lines, doc)) = entry {
+            items.push(Item::new(lines, with_trailing_comments(doc, comments)));
+        }
+        Ok(items)
     }
 
-    fn field_type_to_doc_impl<'a>(
-        &self,
-        pairs: Pairs<'a, Rule>,
-        docs: &mut Vec<RcDoc<'a, ()>>,
-    ) -> Result<()> {
-        for pair in pairs {
+    /// Arrays stay on one line when they fit and have no comments; otherwise
+    /// they get one element per line.
+    fn array_expression_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
+        enum Element<'a> {
+            Value(Lines, RcDoc<'a, ()>, Option<&'a str>),
+            Comment(Lines, RcDoc<'a, ()>),
+        }
+
+        let mut elements = vec![];
+        let mut has_comments = false;
+
+        for pair in pair.into_inner() {
+            let lines = Lines::of(&pair);
             match pair.as_rule() {
-                Rule::field_type | Rule::union => {
-                    self.field_type_to_doc_impl(pair.into_inner(), docs)?;
+                Rule::expression => {
+                    elements.push(Element::Value(lines, self.expression_to_doc(pair)?, None))
                 }
-                Rule::field_operator => {
-                    docs.push(RcDoc::space());
-                    docs.push(RcDoc::text("|"));
-                    docs.push(RcDoc::space());
+                Rule::trailing_comment => {
+                    has_comments = true;
+                    if let Some(Element::Value(_, _, comment)) = elements.last_mut() {
+                        *comment = Some(pair.as_str().trim());
+                    }
                 }
-                Rule::base_type_with_attr | Rule::non_union => {
-                    docs.push(pair_to_doc_text(pair));
+                Rule::comment_block => {
+                    has_comments = true;
+                    elements.push(Element::Comment(lines, self.comment_block_to_doc(pair)));
                 }
-                _ => {
-                    docs.push(self.unhandled_rule_to_doc(pair)?);
+                Rule::empty_lines => {
+                    // skip
                 }
+                _ => elements.push(Element::Value(
+                    lines,
+                    self.unhandled_rule_to_doc(pair)?,
+                    None,
+                )),
             }
         }
 
-        Ok(())
+        if elements.is_empty() {
+            return Ok(RcDoc::text("[]"));
+        }
+
+        if !has_comments {
+            let values = elements.into_iter().filter_map(|e| match e {
+                Element::Value(_, doc, _) => Some(doc),
+                Element::Comment(..) => None,
+            });
+            return Ok(RcDoc::text("[")
+                .append(
+                    RcDoc::line_()
+                        .append(RcDoc::intersperse(
+                            values,
+                            RcDoc::text(",").append(RcDoc::line()),
+                        ))
+                        .nest(self.indent_width),
+                )
+                .append(RcDoc::line_())
+                .append(RcDoc::text("]"))
+                .group());
+        }
+
+        // A comma after an element would turn the comment following it into
+        // a comment on the next element, so newlines separate the elements.
+        let items = elements
+            .into_iter()
+            .map(|e| match e {
+                Element::Value(lines, doc, comment) => Item::new(
+                    lines,
+                    with_trailing_comments(doc, comment.into_iter().collect()),
+                ),
+                Element::Comment(lines, doc) => Item::new(lines, doc),
+            })
+            .collect();
+
+        Ok(RcDoc::text("[")
+            .append(
+                RcDoc::hardline()
+                    .append(join_items(items))
+                    .nest(self.indent_width),
+            )
+            .append(RcDoc::hardline())
+            .append(RcDoc::text("]")))
+    }
+
+    fn comment_block_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> RcDoc<'a, ()> {
+        let comments = pair.into_inner().map(|pair| match pair.as_rule() {
+            Rule::block_comment => self.verbatim(pair.as_str().trim()),
+            _ => pair_to_doc_text(pair),
+        });
+        RcDoc::intersperse(comments, RcDoc::hardline())
+    }
+
+    /// `{}`, or the items indented on their own lines between braces.
+    fn braces<'a>(&self, items: Vec<Item<'a>>) -> RcDoc<'a, ()> {
+        if items.is_empty() {
+            return RcDoc::text("{}");
+        }
+
+        RcDoc::text("{")
+            .append(
+                RcDoc::hardline()
+                    .append(join_items(items))
+                    .nest(self.indent_width),
+            )
+            .append(RcDoc::hardline())
+            .append(RcDoc::text("}"))
     }
 
     fn unhandled_rule_to_doc<'a>(&self, pair: Pair<'a, Rule>) -> Result<RcDoc<'a, ()>> {
         if self.fail_on_unhandled_rule {
             Err(anyhow!("Unhandled rule: {:?}", pair.as_rule()))
         } else {
-            // Don't trim the str repr of unhandled rules, so
-            // we can see the original source.
-            Ok(RcDoc::text(pair.as_str()))
+            // Don't trim the start of unhandled rules, so we can see the
+            // original source.
+            Ok(self.verbatim(pair.as_str().trim_end()))
+        }
+    }
+
+    fn verbatim<'a>(&self, text: &str) -> RcDoc<'a, ()> {
+        let mut verbatim = self.verbatim.borrow_mut();
+        verbatim.push(text.to_string());
+        RcDoc::text(format!(
+            "{VERBATIM_START}{}{VERBATIM_END}",
+            verbatim.len() - 1
+        ))
+    }
+
+    /// Trims trailing whitespace from the rendered document, then swaps the
+    /// verbatim placeholders back for the text they stand for.
+    fn restore_verbatim(&self, rendered: &str) -> String {
+        let verbatim = self.verbatim.borrow();
+        let trimmed = rendered
+            .split('\n')
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut out = String::with_capacity(trimmed.len());
+        let mut rest = trimmed.as_str();
+        while let Some(start) = rest.find(VERBATIM_START) {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + VERBATIM_START.len_utf8()..];
+            let Some(end) = after.find(VERBATIM_END) else {
+                rest = &rest[start..];
+                break;
+            };
+            match after[..end]
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| verbatim.get(i))
+            {
+                Some(text) => out.push_str(text),
+                None => out.push_str(
+                    &rest[start..start + VERBATIM_START.len_utf8() + end + VERBATIM_END.len_utf8()],
+                ),
+            }
+            rest = &after[end + VERBATIM_END.len_utf8()..];
         }
+        out.push_str(rest);
+        out
     }
 }
 
@@ -298,7 +923,16 @@ mod tests {
                 fail_on_unhandled_rule: true,
             },
         )?;
-        assert_eq!(expected.unindent().trim_end(), formatted);
+        assert_eq!(format!("{}\n", expected.unindent().trim_end()), formatted);
+
+        let reformatted = format_schema(
+            &formatted,
+            FormatOptions {
+                indent_width: 4,
+                fail_on_unhandled_rule: true,
+            },
+        )?;
+        assert_eq!(formatted, reformatted, "formatting is not idempotent");
         Ok(())
     }
 
@@ -354,4 +988,172 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_format_types_and_attributes() -> anyhow::Result<()> {
+        assert_format_eq(
+            r#"
+                class Foo<T>   extends Bar,Baz {
+                    a   map< string ,int[] >?   @alias( "b" )   // why
+                    b (string|null)[]
+                    c Page< T,int >
+
+
+                    d   "x"|"y"
+                    @@dynamic
+                }
+                enum Color {
+                    RED   @alias("red")
+                    GREEN
+                }
+            "#,
+            r#"
+                class Foo<T> extends Bar, Baz {
+                    a map<string, int[]>? @alias("b") // why
+                    b (string | null)[]
+                    c Page<T, int>
+
+                    d "x" | "y"
+                    @@dynamic
+                }
+
+                enum Color {
+                    RED @alias("red")
+                    GREEN
+                }
+            "#,
+        )
+    }
+
+    #[test]
+    fn test_format_functions_and_clients() -> anyhow::Result<()> {
+        assert_format_eq(
+            r##"
+                // The main extractor.
+                function   Extract(text:string,   n: int)->Resume {
+                  client  GPT4
+                  prompt #"
+                      Extract   from {{ text }}
+                        (indented on purpose)
+                  "#
+                }
+                client<llm> GPT4 {
+                  provider openai
+                  options { model "gpt-4o"
+                    api_key env.OPENAI_API_KEY   // from the env
+                    headers {
+                      "x-a" "b"
+                    }
+                  }
+                }
+            "##,
+            r##"
+                // The main extractor.
+                function Extract(text: string, n: int) -> Resume {
+                    client GPT4
+                    prompt #"
+                      Extract   from {{ text }}
+                        (indented on purpose)
+                  "#
+                }
+
+                client<llm> GPT4 {
+                    provider openai
+                    options {
+                        model "gpt-4o"
+                        api_key env.OPENAI_API_KEY // from the env
+                        headers {
+                            "x-a" "b"
+                        }
+                    }
+                }
+            "##,
+        )
+    }
+
+    #[test]
+    fn test_format_namespaces_and_imports() -> anyhow::Result<()> {
+        assert_format_eq(
+            r#"
+                namespace   shared.resumes
+                import   "../common/baml_src"   as common
+
+                class Foo {
+                  a common.Bar
+                }
+            "#,
+            r#"
+                namespace shared.resumes
+                import "../common/baml_src" as common
+
+                class Foo {
+                    a common.Bar
+                }
+            "#,
+        )
+    }
+
+    #[test]
+    fn test_format_tests_generators_and_templates() -> anyhow::Result<()> {
+        assert_format_eq(
+            r##"
+                template_string   Greet(name :string) #"Hello {{ name }}"#
+                generator lang {
+                  output_type "python/pydantic"
+                }
+                test Basic {
+                  functions [ A,B ]
+                  args {
+                    items [
+                      1,
+                      // the second one
+                      2   // two
+                    ]
+                  }
+                  @@check(ok, {{ this.ok }})
+                }
+            "##,
+            r##"
+                template_string Greet(name: string) #"Hello {{ name }}"#
+
+                generator lang {
+                    output_type "python/pydantic"
+                }
+
+                test Basic {
+                    functions [A, B]
+                    args {
+                        items [
+                            1
+                            // the second one
+                            2 // two
+                        ]
+                    }
+                    @@check(ok, {{ this.ok }})
+                }
+            "##,
+        )
+    }
+
+    #[test]
+    fn test_format_keeps_header_comments_verbatim() -> anyhow::Result<()> {
+        assert_format_eq(
+            r#"
+                function Foo(
+                  // the input
+                  a: string
+                ) -> string {
+                  client GPT4
+                }
+            "#,
+            r#"
+                function Foo(
+                  // the input
+                  a: string
+                ) -> string {
+                  client GPT4
+                }
+            "#,
+        )
+    }
 }
@@ -276,6 +276,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn generic_type_arguments() {
+        let root_path = "test_file.baml";
+
+        let input = r#"Page<Resume, map<string, int>>[]"#;
+        let source = SourceFile::new_static(root_path.into(), input);
+        let mut diagnostics = Diagnostics::new(root_path.into());
+        diagnostics.set_source(&source);
+        let parsed = BAMLParser::parse(Rule::field_type_chain, input)
+            .unwrap()
+            .next()
+            .unwrap();
+        let result = parse_field_type_chain(parsed, &mut diagnostics).unwrap();
+        let FieldType::List(_, inner, 1, _, _) = &result else {
+            panic!("Expected list, got {result}");
+        };
+        let FieldType::Generic(_, name, args, _, _) = inner.as_ref() else {
+            panic!("Expected generic type, got {inner}");
+        };
+        assert_eq!(name.name(), "Page");
+        assert_eq!(args.len(), 2);
+        assert!(matches!(&args[0], FieldType::Symbol(_, idn, _) if idn.name() == "Resume"));
+        assert!(matches!(&args[1], FieldType::Map(..)));
+    }
+
     #[test]
     fn field_union_association() {
         let root_path = "test_file.baml";
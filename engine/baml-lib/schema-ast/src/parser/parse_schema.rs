@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
 
 use super::{
+    helpers::Pair, parse_identifier::parse_identifier,
     parse_template_string::parse_template_string,
     parse_type_expression_block::parse_type_expression_block,
     parse_value_expression_block::parse_value_expression_block, BAMLParser, Rule,
@@ -51,6 +52,8 @@ pub fn parse_schema(
             pretty_print(datamodel.clone(), 0);
 
             let mut top_level_definitions = Vec::new();
+            let mut namespaces = Vec::new();
+            let mut imports = Vec::new();
 
             let mut pending_block_comment = None;
             let mut pairs = datamodel.into_inner().peekable();
@@ -105,6 +108,13 @@ pub fn parse_schema(
                         }
                     }
 
+                    Rule::namespace_declaration => {
+                        namespaces.push(parse_namespace_declaration(current, &mut diagnostics));
+                    }
+                    Rule::import_declaration => {
+                        imports.push(parse_import_declaration(current, &mut diagnostics));
+                    }
+
                     Rule::EOI => {}
                     Rule::CATCH_ALL => {
                         diagnostics.push_error(DatamodelError::new_validation_error(
@@ -136,6 +146,8 @@ pub fn parse_schema(
             Ok((
                 SchemaAst {
                     tops: top_level_definitions,
+                    namespaces,
+                    imports,
                 },
                 diagnostics,
             ))
@@ -167,6 +179,43 @@ pub fn parse_schema(
     }
 }
 
+fn parse_namespace_declaration(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Identifier {
+    let identifier = pair
+        .into_inner()
+        .find(|p| p.as_rule() == Rule::identifier)
+        .unwrap();
+    parse_identifier(identifier, diagnostics)
+}
+
+fn parse_import_declaration(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Import {
+    let span = diagnostics.span(pair.as_span());
+    let mut path = String::new();
+    let mut alias = None;
+    for current in pair.into_inner() {
+        match current.as_rule() {
+            Rule::quoted_string_literal => {
+                path = current
+                    .into_inner()
+                    .next()
+                    .map(|content| content.as_str().to_string())
+                    .unwrap_or_default();
+            }
+            Rule::single_word => {
+                alias = Some(Identifier::from((
+                    current.as_str(),
+                    diagnostics.span(current.as_span()),
+                )));
+            }
+            _ => {}
+        }
+    }
+    Import {
+        path,
+        alias: alias.unwrap(),
+        span,
+    }
+}
+
 fn get_expected_from_error(positives: &[Rule]) -> String {
     use std::fmt::Write as _;
     let mut out = String::with_capacity(positives.len() * 6);
@@ -25,6 +25,8 @@ pub(crate) fn parse_type_expression_block(
     let mut fields: Vec<Field<FieldType>> = Vec::new();
     let mut sub_type: Option<SubType> = None;
     let mut input = None;
+    let mut extends: Vec<Identifier> = Vec::new();
+    let mut type_parameters: Vec<Identifier> = Vec::new();
 
     for current in pair.into_inner() {
         match current.as_rule() {
@@ -60,6 +62,25 @@ pub(crate) fn parse_type_expression_block(
             }
 
             Rule::BLOCK_OPEN | Rule::BLOCK_CLOSE => {}
+            Rule::type_parameters => {
+                for item in current.into_inner() {
+                    match item.as_rule() {
+                        Rule::identifier => {
+                            type_parameters.push(parse_identifier(item, diagnostics))
+                        }
+                        _ => parsing_catch_all(item, "type_parameters"),
+                    }
+                }
+            }
+            Rule::extends_clause => {
+                for item in current.into_inner() {
+                    match item.as_rule() {
+                        Rule::EXTENDS_KEYWORD => {}
+                        Rule::identifier => extends.push(parse_identifier(item, diagnostics)),
+                        _ => parsing_catch_all(item, "extends_clause"),
+                    }
+                }
+            }
             Rule::named_argument_list => match parse_named_argument_list(current, diagnostics) {
                 Ok(arg) => input = Some(arg),
                 Err(err) => diagnostics.push_error(err),
@@ -115,6 +136,8 @@ pub(crate) fn parse_type_expression_block(
     match name {
         Some(name) => TypeExpressionBlock {
             name,
+            type_parameters,
+            extends,
             fields,
             input,
             attributes,
@@ -165,6 +188,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn class_extends() {
+        let root_path = "test_file.baml";
+
+        let input = r#"class Foo extends Bar, Baz { a int }"#;
+        let source = SourceFile::new_static(root_path.into(), input);
+        let mut diagnostics = Diagnostics::new(root_path.into());
+        diagnostics.set_source(&source);
+        let parsed = BAMLParser::parse(Rule::type_expression_block, input)
+            .unwrap()
+            .next()
+            .unwrap();
+        let result = parse_type_expression_block(parsed, None, &mut diagnostics);
+        assert_eq!(result.name.to_string(), "Foo");
+        assert_eq!(
+            result
+                .extends
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>(),
+            vec!["Bar", "Baz"]
+        );
+        assert_eq!(result.fields.len(), 1);
+        assert!(!diagnostics.has_errors());
+    }
+
+    #[test]
+    fn generic_class() {
+        let root_path = "test_file.baml";
+
+        let input = r#"class Page<T, U> { items T[] extra U? }"#;
+        let source = SourceFile::new_static(root_path.into(), input);
+        let mut diagnostics = Diagnostics::new(root_path.into());
+        diagnostics.set_source(&source);
+        let parsed = BAMLParser::parse(Rule::type_expression_block, input)
+            .unwrap()
+            .next()
+            .unwrap();
+        let result = parse_type_expression_block(parsed, None, &mut diagnostics);
+        assert_eq!(result.name.to_string(), "Page");
+        assert_eq!(
+            result
+                .type_parameters
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>(),
+            vec!["T", "U"]
+        );
+        assert_eq!(result.fields.len(), 2);
+        assert!(!diagnostics.has_errors());
+    }
+
     #[test]
     // This test checks that parsing a particular malformed Enum produces
     // a field that is an enum variant with a data payload. This is not
@@ -168,6 +168,7 @@ fn parse_base_type(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Option<Fiel
             }
             Rule::array_notation => parse_array(current, diagnostics),
             Rule::map => parse_map(current, diagnostics),
+            Rule::generic_type => parse_generic_type(current, diagnostics),
             Rule::group => parse_group(current, diagnostics),
             Rule::tuple => parse_tuple(current, diagnostics),
             Rule::parenthesized_type => parse_parenthesized_type(current, diagnostics),
@@ -294,6 +295,37 @@ fn parse_map(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Option<FieldType>
     }
 }
 
+fn parse_generic_type(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Option<FieldType> {
+    assert_correct_parser!(pair, Rule::generic_type);
+
+    let span = diagnostics.span(pair.as_span());
+    let mut name = None;
+    let mut args = Vec::new();
+
+    for current in pair.into_inner() {
+        match current.as_rule() {
+            Rule::identifier => name = Some(parse_identifier(current, diagnostics)),
+            Rule::field_type => {
+                if let Some(f) = parse_field_type(current, diagnostics) {
+                    args.push(f)
+                }
+            }
+            _ => unreachable_rule!(current, Rule::generic_type),
+        }
+    }
+
+    match name {
+        Some(name) => Some(FieldType::Generic(
+            FieldArity::Required,
+            name,
+            args,
+            span,
+            None,
+        )),
+        None => unreachable!("Generic types must have a name"),
+    }
+}
+
 fn parse_group(pair: Pair<'_>, diagnostics: &mut Diagnostics) -> Option<FieldType> {
     assert_correct_parser!(pair, Rule::group);
     let mut attributes = Vec::new();
@@ -1,8 +1,7 @@
 use anyhow::Result;
 use notify_debouncer_full::{new_debouncer, notify::*};
-use std::ops::DerefMut;
 use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use crate::{cli::generate::GenerateArgs, BamlRuntime};
 
@@ -109,20 +108,14 @@ Thanks for trying out BAML!
                                 n => format!("{} files changed", n),
                             }
                         );
-                        let start = Instant::now();
-                        match BamlRuntime::from_directory(&self.from, std::env::vars().collect()) {
-                            Ok(mut new_runtime) => {
-                                let elapsed = start.elapsed();
+                        match server.reload().await {
+                            Ok(elapsed) => {
                                 let _ = GenerateArgs {
                                     from: self.from.clone(),
                                     no_version_check: false,
                                 }
                                 .run(defaults);
 
-                                std::mem::swap(
-                                    server.b.write().await.deref_mut(),
-                                    &mut new_runtime,
-                                );
                                 log::info!(
                                     "Reloaded runtime in {}ms ({})",
                                     elapsed.as_millis(),
@@ -87,6 +87,7 @@ impl InitArgs {
                 GeneratorOutputType::PythonPydantic => "Python clients".to_string(),
                 GeneratorOutputType::Typescript => "TypeScript clients".to_string(),
                 GeneratorOutputType::RubySorbet => "Ruby clients".to_string(),
+                GeneratorOutputType::Go => "Go clients".to_string(),
                 GeneratorOutputType::OpenApi => match &self.openapi_client_type {
                     Some(s) => format!("{} clients via OpenAPI", s),
                     None => "REST clients".to_string(),
@@ -99,6 +100,7 @@ impl InitArgs {
                 GeneratorOutputType::PythonPydantic => "python",
                 GeneratorOutputType::Typescript => "typescript",
                 GeneratorOutputType::RubySorbet => "ruby",
+                GeneratorOutputType::Go => "go",
                 GeneratorOutputType::OpenApi => "openapi",
             }
         );
@@ -113,7 +115,9 @@ fn generate_main_baml_content(
     openapi_client_type: Option<&str>,
 ) -> String {
     let default_client_mode = match output_type {
-        GeneratorOutputType::OpenApi | GeneratorOutputType::RubySorbet => "".to_string(),
+        GeneratorOutputType::OpenApi
+        | GeneratorOutputType::RubySorbet
+        | GeneratorOutputType::Go => "".to_string(),
         GeneratorOutputType::PythonPydantic | GeneratorOutputType::Typescript => format!(
             r#"
     // Valid values: "sync", "async"
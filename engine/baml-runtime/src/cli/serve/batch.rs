@@ -0,0 +1,185 @@
+use axum::extract;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::internal::llm_client::ResponseBamlValue;
+
+use super::{
+    error::BamlError, json_response::Json, parse_args, parse_baml_options, BamlOptions, Server,
+};
+
+/// Upper bound for `max_concurrency`, so that a single request can't
+/// exhaust the LLM provider's rate limits on its own.
+const MAX_BATCH_CONCURRENCY: usize = 64;
+
+/// Upper bound for the number of inputs, so that a single request can't
+/// queue an unbounded number of LLM calls.
+const MAX_BATCH_INPUTS: usize = 1000;
+
+fn default_max_concurrency() -> usize {
+    4
+}
+
+#[derive(Deserialize)]
+pub(super) struct BatchRequest {
+    /// The arguments of each call, in the same shape as the body of `/call`.
+    inputs: Vec<serde_json::Value>,
+    #[serde(default = "default_max_concurrency")]
+    max_concurrency: usize,
+    /// Options for every call. An input with its own `__baml_options__`
+    /// uses those instead.
+    #[serde(rename = "__baml_options__")]
+    baml_options: Option<BamlOptions>,
+}
+
+#[derive(Serialize)]
+pub(super) struct BatchResponse {
+    /// One result per input, in the same order as the inputs.
+    results: Vec<BatchResult>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum BatchResult {
+    Ok { value: ResponseBamlValue },
+    Err(BamlError),
+}
+
+impl Server {
+    /// Call a BAML function once per input, running at most
+    /// `max_concurrency` calls at a time. A failed call doesn't fail the
+    /// batch: its error is reported in place of its result.
+    ///
+    /// Every call of a batch uses the same runtime, even if `baml_src` is
+    /// reloaded while the batch runs.
+    pub(super) async fn batch_handler(
+        self: Arc<Self>,
+        extract::Path(b_fn): extract::Path<String>,
+        extract::Json(request): extract::Json<BatchRequest>,
+    ) -> Result<Json<BatchResponse>, BamlError> {
+        if !(1..=MAX_BATCH_CONCURRENCY).contains(&request.max_concurrency) {
+            return Err(BamlError::InvalidArgument {
+                message: format!(
+                    "max_concurrency must be between 1 and {}, got {}",
+                    MAX_BATCH_CONCURRENCY, request.max_concurrency
+                ),
+            });
+        }
+
+        if request.inputs.len() > MAX_BATCH_INPUTS {
+            return Err(BamlError::InvalidArgument {
+                message: format!(
+                    "A batch can have at most {} inputs, got {}",
+                    MAX_BATCH_INPUTS,
+                    request.inputs.len()
+                ),
+            });
+        }
+
+        let runtime = self.runtime().await;
+        let results = futures::stream::iter(request.inputs)
+            .map(|input| {
+                let b_fn = b_fn.clone();
+                let default_options = request.baml_options.clone();
+                let server = self.clone();
+                let runtime = runtime.clone();
+                async move {
+                    let b_options = parse_baml_options(&input)?.or(default_options);
+                    let args = parse_args(&b_fn, input)?;
+                    server.call_function(&runtime, b_fn, &args, b_options).await
+                }
+            })
+            .buffered(request.max_concurrency)
+            .map(|result| match result {
+                Ok(value) => BatchResult::Ok { value },
+                Err(e) => BatchResult::Err(e),
+            })
+            .collect()
+            .await;
+
+        Ok(Json(BatchResponse { results }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde_json::json;
+
+    use super::super::test_server::{echo_baml, FakeLlm, TestServer};
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reports_each_result_in_place() {
+        let llm = FakeLlm::start("hello").await;
+        let server = TestServer::start(&echo_baml(&llm.base_url)).await;
+
+        let (status, body) = server
+            .post(
+                "/batch/Echo",
+                json!({"inputs": [{"text": "a"}, "not a map", {"text": "b"}]}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        let results = body["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], json!({"value": "hello"}));
+        assert_eq!(results[1]["error"], "invalid_argument");
+        assert_eq!(results[2], json!({"value": "hello"}));
+        assert_eq!(llm.requests(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_too_many_inputs() {
+        let llm = FakeLlm::start("hello").await;
+        let server = TestServer::start(&echo_baml(&llm.base_url)).await;
+
+        let inputs = vec![json!({"text": "a"}); MAX_BATCH_INPUTS + 1];
+        let (status, body) = server
+            .post("/batch/Echo", json!({ "inputs": inputs }))
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_argument");
+        assert_eq!(llm.requests(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_out_of_range_concurrency() {
+        let llm = FakeLlm::start("hello").await;
+        let server = TestServer::start(&echo_baml(&llm.base_url)).await;
+
+        let (status, _) = server
+            .post(
+                "/batch/Echo",
+                json!({"inputs": [{"text": "a"}], "max_concurrency": 0}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(llm.requests(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn keeps_its_runtime_across_reloads() {
+        let old_llm = FakeLlm::start("old").await;
+        let new_llm = FakeLlm::start("new").await;
+        old_llm.hold();
+        let server = TestServer::start(&echo_baml(&old_llm.base_url)).await;
+
+        let batch = tokio::spawn(server.post(
+            "/batch/Echo",
+            json!({"inputs": [{"text": "a"}, {"text": "b"}], "max_concurrency": 1}),
+        ));
+        old_llm.wait_for_request().await;
+
+        // The second input starts after the reload.
+        server.write(&echo_baml(&new_llm.base_url));
+        server.server.reload().await.unwrap();
+        old_llm.release();
+
+        let (status, body) = batch.await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["results"], json!([{"value": "old"}, {"value": "old"}]));
+        assert_eq!(new_llm.requests(), 0);
+    }
+}
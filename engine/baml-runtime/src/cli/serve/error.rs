@@ -41,7 +41,7 @@ pub enum BamlError {
 }
 
 impl BamlError {
-    pub(crate) fn from_anyhow(err: anyhow::Error) -> Self {
+    pub(crate) fn from_anyhow(err: &anyhow::Error) -> Self {
         if let Some(er) = err.downcast_ref::<ExposedError>() {
             match er {
                 ExposedError::ValidationError {
@@ -103,6 +103,17 @@ impl BamlError {
             }
         }
     }
+
+    /// The name of the variant, as shown to users in the `error` field.
+    pub(crate) fn class(&self) -> &'static str {
+        match self {
+            BamlError::InvalidArgument { .. } => "invalid_argument",
+            BamlError::ClientError { .. } => "client_error",
+            BamlError::ValidationFailure { .. } => "validation_failure",
+            BamlError::FinishReasonError { .. } => "finish_reason_error",
+            BamlError::InternalError { .. } => "internal_error",
+        }
+    }
 }
 
 impl IntoResponse for BamlError {
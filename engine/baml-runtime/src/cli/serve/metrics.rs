@@ -0,0 +1,295 @@
+use anyhow::Result;
+use indexmap::IndexMap;
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use crate::{internal::llm_client::LLMResponse, FunctionResult};
+
+use super::error::BamlError;
+
+/// Upper bounds (in seconds) of the latency histogram buckets.
+const LATENCY_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+/// Request metrics for `baml-cli serve`, exposed in the Prometheus text
+/// format on `/metrics`.
+#[derive(Default)]
+pub(super) struct Metrics {
+    functions: Mutex<IndexMap<String, FunctionMetrics>>,
+    in_flight: AtomicI64,
+    reloads: AtomicU64,
+    failed_reloads: AtomicU64,
+}
+
+#[derive(Default)]
+struct FunctionMetrics {
+    /// Number of calls, by outcome: `ok` or one of the `BamlError` classes.
+    calls: IndexMap<&'static str, u64>,
+    /// Cumulative counts, one per entry of `LATENCY_BUCKETS`.
+    latency_buckets: Vec<u64>,
+    latency_sum: f64,
+    latency_count: u64,
+    prompt_tokens: u64,
+    output_tokens: u64,
+    retries: u64,
+}
+
+/// Decrements the in-flight gauge when the request is done, even if the
+/// handler panics or the client goes away.
+pub(super) struct InFlightGuard(Arc<Metrics>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Metrics {
+    pub(super) fn track_request(self: &Arc<Self>) -> InFlightGuard {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlightGuard(self.clone())
+    }
+
+    pub(super) fn in_flight(&self) -> i64 {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn record_reload(&self, succeeded: bool) {
+        if succeeded {
+            self.reloads.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failed_reloads.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records a single call (or stream) of a BAML function.
+    pub(super) fn record_call(
+        &self,
+        function: &str,
+        latency: Duration,
+        result: &Result<FunctionResult>,
+    ) {
+        let Ok(mut functions) = self.functions.lock() else {
+            return;
+        };
+        let m = functions.entry(function.to_string()).or_default();
+
+        *m.calls.entry(outcome(result)).or_default() += 1;
+
+        let seconds = latency.as_secs_f64();
+        m.latency_buckets.resize(LATENCY_BUCKETS.len(), 0);
+        for (count, bound) in m.latency_buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= *bound {
+                *count += 1;
+            }
+        }
+        m.latency_sum += seconds;
+        m.latency_count += 1;
+
+        if let Ok(function_result) = result {
+            let chain = function_result.event_chain();
+            m.retries += chain.len().saturating_sub(1) as u64;
+            for (_, response, _, _) in chain {
+                if let LLMResponse::Success(response) = response {
+                    m.prompt_tokens += response.metadata.prompt_tokens.unwrap_or(0);
+                    m.output_tokens += response.metadata.output_tokens.unwrap_or(0);
+                }
+            }
+        }
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub(super) fn render(&self) -> String {
+        let mut out = String::new();
+        let functions = match self.functions.lock() {
+            Ok(functions) => functions,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        header(
+            &mut out,
+            "baml_function_calls_total",
+            "counter",
+            "Number of BAML function calls, by outcome.",
+        );
+        for (name, m) in functions.iter() {
+            for (outcome, count) in m.calls.iter() {
+                let _ = writeln!(
+                    out,
+                    "baml_function_calls_total{{function=\"{}\",outcome=\"{}\"}} {}",
+                    escape(name),
+                    outcome,
+                    count
+                );
+            }
+        }
+
+        header(
+            &mut out,
+            "baml_function_latency_seconds",
+            "histogram",
+            "Latency of BAML function calls, including retries and fallbacks.",
+        );
+        for (name, m) in functions.iter() {
+            let name = escape(name);
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(m.latency_buckets.iter()) {
+                let _ = writeln!(
+                    out,
+                    "baml_function_latency_seconds_bucket{{function=\"{}\",le=\"{}\"}} {}",
+                    name, bound, count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "baml_function_latency_seconds_bucket{{function=\"{}\",le=\"+Inf\"}} {}",
+                name, m.latency_count
+            );
+            let _ = writeln!(
+                out,
+                "baml_function_latency_seconds_sum{{function=\"{}\"}} {}",
+                name, m.latency_sum
+            );
+            let _ = writeln!(
+                out,
+                "baml_function_latency_seconds_count{{function=\"{}\"}} {}",
+                name, m.latency_count
+            );
+        }
+
+        header(
+            &mut out,
+            "baml_function_tokens_total",
+            "counter",
+            "Tokens used by BAML function calls, as reported by the LLM provider.",
+        );
+        for (name, m) in functions.iter() {
+            let name = escape(name);
+            let _ = writeln!(
+                out,
+                "baml_function_tokens_total{{function=\"{}\",kind=\"prompt\"}} {}",
+                name, m.prompt_tokens
+            );
+            let _ = writeln!(
+                out,
+                "baml_function_tokens_total{{function=\"{}\",kind=\"output\"}} {}",
+                name, m.output_tokens
+            );
+        }
+
+        header(
+            &mut out,
+            "baml_function_retries_total",
+            "counter",
+            "LLM requests made by BAML function calls beyond the first one (retries and fallbacks).",
+        );
+        for (name, m) in functions.iter() {
+            let _ = writeln!(
+                out,
+                "baml_function_retries_total{{function=\"{}\"}} {}",
+                escape(name),
+                m.retries
+            );
+        }
+
+        header(
+            &mut out,
+            "baml_in_flight_requests",
+            "gauge",
+            "Number of requests currently being handled.",
+        );
+        let _ = writeln!(out, "baml_in_flight_requests {}", self.in_flight());
+
+        header(
+            &mut out,
+            "baml_runtime_reloads_total",
+            "counter",
+            "Number of times baml_src was reloaded, by outcome.",
+        );
+        let _ = writeln!(
+            out,
+            "baml_runtime_reloads_total{{outcome=\"ok\"}} {}",
+            self.reloads.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "baml_runtime_reloads_total{{outcome=\"error\"}} {}",
+            self.failed_reloads.load(Ordering::Relaxed)
+        );
+
+        out
+    }
+}
+
+/// `ok`, or the class of the `BamlError` the request was answered with.
+fn outcome(result: &Result<FunctionResult>) -> &'static str {
+    match result {
+        Ok(function_result) => match function_result.llm_response() {
+            LLMResponse::Success(_) => match function_result.result_with_constraints_content() {
+                Ok(_) => "ok",
+                Err(e) => BamlError::from_anyhow(e).class(),
+            },
+            LLMResponse::LLMFailure(_) => "client_error",
+            LLMResponse::UserFailure(_) => "invalid_argument",
+            LLMResponse::InternalFailure(_) => "internal_error",
+        },
+        Err(e) => BamlError::from_anyhow(e).class(),
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Escapes a label value, see
+/// https://prometheus.io/docs/instrumenting/exposition_formats/#text-based-format
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_failed_calls() {
+        let metrics = Metrics::default();
+        metrics.record_call(
+            "ExtractResume",
+            Duration::from_millis(300),
+            &Err(anyhow::anyhow!("boom")),
+        );
+
+        let rendered = metrics.render();
+        assert!(rendered.contains(
+            "baml_function_calls_total{function=\"ExtractResume\",outcome=\"internal_error\"} 1"
+        ));
+        assert!(rendered.contains(
+            "baml_function_latency_seconds_bucket{function=\"ExtractResume\",le=\"0.25\"} 0"
+        ));
+        assert!(rendered.contains(
+            "baml_function_latency_seconds_bucket{function=\"ExtractResume\",le=\"0.5\"} 1"
+        ));
+        assert!(
+            rendered.contains("baml_function_latency_seconds_count{function=\"ExtractResume\"} 1")
+        );
+        assert!(rendered.contains("baml_function_retries_total{function=\"ExtractResume\"} 0"));
+    }
+
+    #[test]
+    fn tracks_in_flight_requests() {
+        let metrics = Arc::new(Metrics::default());
+        let guard = metrics.track_request();
+        assert_eq!(metrics.in_flight(), 1);
+        drop(guard);
+        assert_eq!(metrics.in_flight(), 0);
+    }
+}
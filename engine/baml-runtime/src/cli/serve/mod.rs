@@ -1,11 +1,24 @@
 mod arg_validation;
+mod batch;
 mod error;
 mod json_response;
+mod metrics;
+mod parse;
 mod ping;
+mod render;
+#[cfg(test)]
+mod test_server;
+mod type_builder;
 use error::BamlError;
 use indexmap::IndexMap;
 use internal_baml_codegen::GeneratorArgs;
 use json_response::Json;
+use metrics::Metrics;
+use notify_debouncer_full::{
+    new_debouncer,
+    notify::{RecursiveMode, Watcher},
+    DebounceEventResult,
+};
 
 use anyhow::{Context, Result};
 use arg_validation::BamlServeValidate;
@@ -28,15 +41,27 @@ use core::pin::Pin;
 use futures::Stream;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{path::PathBuf, sync::Arc, task::Poll};
-use tokio::{net::TcpListener, sync::RwLock};
+use std::{
+    collections::HashMap,
+    future::IntoFuture,
+    path::PathBuf,
+    sync::Arc,
+    task::Poll,
+    time::{Duration, Instant},
+};
+use tokio::{
+    net::TcpListener,
+    sync::{mpsc::UnboundedReceiver, Notify, RwLock},
+};
 use tokio_stream::StreamExt;
+use type_builder::TypeBuilderSpec;
 
 use crate::{
     client_registry::ClientRegistry,
     errors::ExposedError,
     internal::llm_client::{LLMResponse, ResponseBamlValue},
-    BamlRuntime, FunctionResult, RuntimeContextManager,
+    type_builder::TypeBuilder,
+    BamlRuntime, FunctionResult, InternalRuntimeInterface, RuntimeContextManager,
 };
 use internal_baml_codegen::openapi::OpenApiSchema;
 
@@ -54,11 +79,57 @@ pub struct ServeArgs {
         default_value_t = false
     )]
     no_version_check: bool,
+    #[arg(
+        long,
+        help = "reload baml_src when it changes, without restarting the server",
+        default_value_t = false
+    )]
+    watch: bool,
+    #[arg(
+        long,
+        help = "seconds to wait for in-flight requests to finish on shutdown",
+        default_value = "30"
+    )]
+    drain_timeout: u64,
 }
 
-#[derive(Deserialize, Clone, Debug)]
+#[derive(Deserialize, Clone, Debug, Default)]
 pub struct BamlOptions {
     pub client_registry: Option<ClientRegistry>,
+    /// Properties and values to add to the `@@dynamic` classes and enums.
+    type_builder: Option<TypeBuilderSpec>,
+    /// Tags to record on the trace of the call.
+    tags: Option<HashMap<String, BamlValue>>,
+    /// A span of the caller, to record the trace of the call under.
+    parent_span_id: Option<uuid::Uuid>,
+}
+
+impl BamlOptions {
+    /// The context manager of a call, carrying its tags and parent span.
+    fn ctx_manager(&self, runtime: &BamlRuntime) -> RuntimeContextManager {
+        let ctx_mgr = RuntimeContextManager::new_from_env_vars(std::env::vars().collect(), None)
+            .with_secrets(runtime.secrets().clone())
+            .with_baml_src_dir(runtime.internal().diagnostics().root_path.clone());
+        if let Some(parent_span_id) = self.parent_span_id {
+            ctx_mgr.set_parent_span(parent_span_id, "parent");
+        }
+        if let Some(tags) = &self.tags {
+            ctx_mgr.upsert_tags(tags.clone());
+        }
+        ctx_mgr
+    }
+
+    /// The type builder of a call, checked against the classes and enums of
+    /// `runtime`.
+    fn type_builder(&self, runtime: &BamlRuntime) -> Result<Option<TypeBuilder>, BamlError> {
+        self.type_builder
+            .as_ref()
+            .map(|spec| {
+                spec.validate(&runtime.inner.ir)?;
+                TypeBuilder::try_from(spec)
+            })
+            .transpose()
+    }
 }
 
 impl ServeArgs {
@@ -112,7 +183,29 @@ Thanks for trying out BAML!
 
         let (server, tcp_listener) = t.block_on(Server::new(self.from.clone(), self.port))?;
 
-        t.block_on(server.serve(tcp_listener))?;
+        // The watcher stops when it's dropped, so keep it around until the
+        // server is done.
+        let _debouncer = if self.watch {
+            let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+            let mut debouncer = new_debouncer(
+                Duration::from_millis(200),
+                None,
+                move |r: DebounceEventResult| {
+                    let _ = tx.send(r);
+                },
+            )?;
+            debouncer
+                .watcher()
+                .watch(self.from.as_path(), RecursiveMode::Recursive)?;
+            t.spawn(server.clone().reload_on_change(rx));
+            Some(debouncer)
+        } else {
+            None
+        };
+
+        t.block_on(
+            server.serve_until_shutdown(tcp_listener, Duration::from_secs(self.drain_timeout)),
+        )?;
 
         Ok(())
     }
@@ -126,7 +219,10 @@ Thanks for trying out BAML!
 pub(super) struct Server {
     src_dir: PathBuf,
     port: u16,
-    pub(super) b: Arc<RwLock<BamlRuntime>>,
+    /// Requests clone the current runtime and release the lock at once, so
+    /// a reload only waits to swap the pointer, never for in-flight calls.
+    b: RwLock<Arc<BamlRuntime>>,
+    metrics: Arc<Metrics>,
 }
 
 #[derive(Debug)]
@@ -212,10 +308,11 @@ impl Server {
             Arc::new(Self {
                 src_dir: src_dir.clone(),
                 port,
-                b: Arc::new(RwLock::new(BamlRuntime::from_directory(
+                b: RwLock::new(Arc::new(BamlRuntime::from_directory(
                     &src_dir,
                     std::env::vars().collect(),
                 )?)),
+                metrics: Default::default(),
             }),
             tcp_listener,
         ))
@@ -282,13 +379,16 @@ impl Server {
         next.run(request).await
     }
 
-    pub async fn serve(self: Arc<Self>, tcp_listener: TcpListener) -> Result<()> {
+    fn router(self: &Arc<Self>) -> axum::Router {
         // build our application with a route
         let app = axum::Router::new();
 
         let app = app.route("/_debug/ping", any(ping::ping_handler));
         let app = app.route("/_debug/status", any(status_handler));
 
+        let s = self.clone();
+        let app = app.route("/metrics", get(move || s.clone().metrics_handler()));
+
         let s = self.clone();
         let app = app.route(
             "/call/:msg",
@@ -300,6 +400,25 @@ impl Server {
             "/stream/:msg",
             post(move |b_fn, b_args| s.clone().baml_stream_axum2(b_fn, b_args)),
         );
+
+        let s = self.clone();
+        let app = app.route(
+            "/render/:msg",
+            post(move |b_fn, query, b_args| s.clone().render_handler(b_fn, query, b_args)),
+        );
+
+        let s = self.clone();
+        let app = app.route(
+            "/parse/:msg",
+            post(move |b_fn, request| s.clone().parse_handler(b_fn, request)),
+        );
+
+        let s = self.clone();
+        let app = app.route(
+            "/batch/:msg",
+            post(move |b_fn, request| s.clone().batch_handler(b_fn, request)),
+        );
+
         let s = self.clone();
         let app = app.route("/docs", get(move || s.clone().docs_handler()));
 
@@ -309,25 +428,29 @@ impl Server {
             get(move || s.clone().openapi_json_handler()),
         );
 
-        let service = axum::serve(
-            tcp_listener,
-            app.layer(axum::middleware::from_fn(Server::auth_middleware)),
-        );
-        // TODO: we do not handle this ourselves, because tokio's default
-        // handling is pretty good on unix.
-        //
-        // Not totally sure if this WAI on
-        // windows, but there are some potential pitfalls that we can run into
-        // if we try to handle this ourselves, see
-        // https://docs.rs/tokio/latest/tokio/signal/fn.ctrl_c.html#caveats
-        //
-        // Namely- we need to ensure resilient delivery of Ctrl-C to everything, and I
-        // suspect we need to do a bit of work to ensure that we handle that bookkeeping
-        // correctly. Shutting down the BAML runtime, tokio runtime, _and_ axum is not
-        // super straightforward, because I don't know how much is handled for us
-        // out of the box.
-        //
-        // .with_graceful_shutdown(signal::ctrl_c());
+        let s = self.clone();
+        app.layer(axum::middleware::from_fn(Server::auth_middleware))
+            .layer(axum::middleware::from_fn(
+                move |request: extract::Request, next: Next| {
+                    let s = s.clone();
+                    async move {
+                        let in_flight = s.metrics.track_request();
+                        // Streams are still running when the response head
+                        // is sent, so the request is done once the body is.
+                        next.run(request).await.map(|body| {
+                            axum::body::Body::from_stream(body.into_data_stream().map(
+                                move |chunk| {
+                                    let _ = &in_flight;
+                                    chunk
+                                },
+                            ))
+                        })
+                    }
+                },
+            ))
+    }
+
+    fn log_listening(&self) {
         log::info!(
             r#"BAML-over-HTTP listening on port {}, serving from {}
 
@@ -339,12 +462,138 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
             self.src_dir.display(),
             self.port,
         );
+    }
+
+    /// Serve until the process is killed. Used by `baml-cli dev`, which
+    /// owns the main thread and relies on the default Ctrl-C handling.
+    pub async fn serve(self: Arc<Self>, tcp_listener: TcpListener) -> Result<()> {
+        let service = axum::serve(tcp_listener, self.router());
+        self.log_listening();
 
         service.await?;
 
         Ok(())
     }
 
+    /// Serve until Ctrl-C or SIGTERM, then stop accepting connections and
+    /// give in-flight requests up to `drain_timeout` to finish.
+    pub async fn serve_until_shutdown(
+        self: Arc<Self>,
+        tcp_listener: TcpListener,
+        drain_timeout: Duration,
+    ) -> Result<()> {
+        let shutdown = Arc::new(Notify::new());
+
+        let notify = shutdown.clone();
+        let service = axum::serve(tcp_listener, self.router())
+            .with_graceful_shutdown(async move {
+                shutdown_signal().await;
+                notify.notify_one();
+            })
+            .into_future();
+        self.log_listening();
+
+        tokio::select! {
+            result = service => result?,
+            _ = async {
+                shutdown.notified().await;
+                log::info!(
+                    "Shutting down, waiting up to {}s for {} in-flight request(s)",
+                    drain_timeout.as_secs(),
+                    self.metrics.in_flight()
+                );
+                tokio::time::sleep(drain_timeout).await;
+            } => {
+                log::warn!(
+                    "Shut down with {} request(s) still in flight after {}s",
+                    self.metrics.in_flight(),
+                    drain_timeout.as_secs()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records a call in the metrics, unless `b_fn` isn't a function of
+    /// `runtime`: every name a client makes up would otherwise become a
+    /// label value.
+    fn record_call(
+        &self,
+        runtime: &BamlRuntime,
+        b_fn: &str,
+        latency: Duration,
+        result: &Result<FunctionResult>,
+    ) {
+        if runtime.function_names().any(|name| name == b_fn) {
+            self.metrics.record_call(b_fn, latency, result);
+        }
+    }
+
+    /// The runtime new requests should use. A request keeps using the one it
+    /// got for as long as it runs, even if `baml_src` is reloaded meanwhile,
+    /// so it never sees a mix of the old and new runtime.
+    pub(super) async fn runtime(&self) -> Arc<BamlRuntime> {
+        self.b.read().await.clone()
+    }
+
+    /// Load `baml_src` again and swap it in for the current runtime.
+    /// In-flight requests finish with the runtime they started with.
+    pub(super) async fn reload(&self) -> Result<Duration> {
+        let start = Instant::now();
+        let src_dir = self.src_dir.clone();
+        let runtime = tokio::task::spawn_blocking(move || {
+            BamlRuntime::from_directory(&src_dir, std::env::vars().collect())
+        })
+        .await
+        .context("Failed to reload baml_src")
+        .and_then(|runtime| runtime);
+        self.metrics.record_reload(runtime.is_ok());
+
+        let runtime = Arc::new(runtime?);
+        let elapsed = start.elapsed();
+        *self.b.write().await = runtime;
+
+        Ok(elapsed)
+    }
+
+    async fn reload_on_change(self: Arc<Self>, mut rx: UnboundedReceiver<DebounceEventResult>) {
+        while let Some(result) = rx.recv().await {
+            match result {
+                Ok(events) => match self.reload().await {
+                    Ok(elapsed) => log::info!(
+                        "Reloaded runtime in {}ms ({})",
+                        elapsed.as_millis(),
+                        match events.len() {
+                            1 => "1 file changed".to_string(),
+                            n => format!("{} files changed", n),
+                        }
+                    ),
+                    // Keep serving the last runtime that loaded.
+                    Err(e) => log::warn!("Failed to reload runtime: {:?}", e),
+                },
+                Err(errors) => {
+                    log::warn!(
+                        "Encountered errors while watching {}: {:?}",
+                        self.src_dir.display(),
+                        errors
+                    );
+                }
+            }
+        }
+    }
+
+    async fn metrics_handler(self: Arc<Self>) -> Response {
+        (
+            [(
+                axum::http::header::CONTENT_TYPE,
+                "text/plain; version=0.0.4; charset=utf-8",
+            )],
+            self.metrics.render(),
+        )
+            .into_response()
+    }
+
     async fn baml_call(
         self: Arc<Self>,
         b_fn: String,
@@ -356,21 +605,44 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
             Err(e) => return e.into_response(),
         };
 
-        let ctx_mgr = RuntimeContextManager::new_from_env_vars(std::env::vars().collect(), None);
-        let client_registry = b_options.and_then(|options| options.client_registry);
+        let runtime = self.runtime().await;
+        match self.call_function(&runtime, b_fn, &args, b_options).await {
+            Ok(parsed) => (StatusCode::OK, Json::<ResponseBamlValue>(parsed)).into_response(),
+            Err(e) => e.into_response(),
+        }
+    }
 
-        let locked = self.b.read().await;
-        let (result, _trace_id) = locked
-            .call_function(b_fn, &args, &ctx_mgr, None, client_registry.as_ref())
+    /// Call a BAML function and classify the outcome. Shared by `/call` and
+    /// `/batch`.
+    async fn call_function(
+        &self,
+        runtime: &BamlRuntime,
+        b_fn: String,
+        args: &IndexMap<String, BamlValue>,
+        b_options: Option<BamlOptions>,
+    ) -> Result<ResponseBamlValue, BamlError> {
+        let b_options = b_options.unwrap_or_default();
+        let type_builder = b_options.type_builder(runtime)?;
+
+        let start = Instant::now();
+        let ctx_mgr = b_options.ctx_manager(runtime);
+        let (result, _trace_id) = runtime
+            .call_function(
+                b_fn.clone(),
+                args,
+                &ctx_mgr,
+                type_builder.as_ref(),
+                b_options.client_registry.as_ref(),
+            )
             .await;
+        self.record_call(runtime, &b_fn, start.elapsed(), &result);
 
         match result {
             Ok(function_result) => match function_result.llm_response() {
                 LLMResponse::Success(_) => {
                     match function_result.result_with_constraints_content() {
                         // Just because the LLM returned 2xx doesn't mean that it returned parse-able content!
-                        Ok(parsed) => (StatusCode::OK, Json::<ResponseBamlValue>(parsed.clone()))
-                            .into_response(),
+                        Ok(parsed) => Ok(parsed.clone()),
                         Err(e) => {
                             if let Some(ExposedError::ValidationError {
                                 prompt,
@@ -378,35 +650,30 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
                                 message,
                             }) = e.downcast_ref::<ExposedError>()
                             {
-                                BamlError::ValidationFailure {
+                                Err(BamlError::ValidationFailure {
                                     message: message.clone(),
                                     prompt: prompt.clone(),
                                     raw_output: raw_response.clone(),
-                                }
-                                .into_response()
+                                })
                             } else {
-                                BamlError::InternalError {
+                                Err(BamlError::InternalError {
                                     message: format!("Error parsing: {:?}", e),
-                                }
-                                .into_response()
+                                })
                             }
                         }
                     }
                 }
-                LLMResponse::LLMFailure(failure) => BamlError::ClientError {
+                LLMResponse::LLMFailure(failure) => Err(BamlError::ClientError {
                     message: format!("{:?}", failure.message),
-                }
-                .into_response(),
-                LLMResponse::UserFailure(message) => BamlError::InvalidArgument {
+                }),
+                LLMResponse::UserFailure(message) => Err(BamlError::InvalidArgument {
                     message: message.clone(),
-                }
-                .into_response(),
-                LLMResponse::InternalFailure(message) => BamlError::InternalError {
+                }),
+                LLMResponse::InternalFailure(message) => Err(BamlError::InternalError {
                     message: message.clone(),
-                }
-                .into_response(),
+                }),
             },
-            Err(e) => BamlError::from_anyhow(e).into_response(),
+            Err(e) => Err(BamlError::from_anyhow(&e)),
         }
     }
 
@@ -415,22 +682,14 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
         extract::Path(b_fn): extract::Path<String>,
         extract::Json(b_args): extract::Json<serde_json::Value>,
     ) -> Response {
-        let mut b_options = None;
-        if let Some(options_value) = b_args.get("__baml_options__") {
-            match serde_json::from_value::<BamlOptions>(options_value.clone()) {
-                Ok(opts) => b_options = Some(opts),
-                Err(_) => {
-                    return BamlError::InvalidArgument {
-                        message: "Failed to parse __baml_options__".to_string(),
-                    }
-                    .into_response()
-                }
-            }
-        }
+        let b_options = match parse_baml_options(&b_args) {
+            Ok(b_options) => b_options,
+            Err(e) => return e.into_response(),
+        };
         self.baml_call(b_fn, b_args, b_options).await
     }
 
-    fn baml_stream(
+    async fn baml_stream(
         self: Arc<Self>,
         b_fn: String,
         b_args: serde_json::Value,
@@ -443,18 +702,22 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
             Err(e) => return e.into_response(),
         };
 
-        let client_registry = b_options.and_then(|options| options.client_registry);
+        let runtime = self.runtime().await;
+        let b_options = b_options.unwrap_or_default();
+        let type_builder = match b_options.type_builder(&runtime) {
+            Ok(type_builder) => type_builder,
+            Err(e) => return e.into_response(),
+        };
 
         tokio::spawn(async move {
-            let ctx_mgr =
-                RuntimeContextManager::new_from_env_vars(std::env::vars().collect(), None);
-
-            let result_stream = self.b.read().await.stream_function(
-                b_fn,
+            let start = Instant::now();
+            let ctx_mgr = b_options.ctx_manager(&runtime);
+            let result_stream = runtime.stream_function(
+                b_fn.clone(),
                 &args,
                 &ctx_mgr,
-                None,
-                client_registry.as_ref(),
+                type_builder.as_ref(),
+                b_options.client_registry.as_ref(),
             );
 
             match result_stream {
@@ -472,10 +735,11 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
                                 }
                             }),
                             &ctx_mgr,
-                            None,
-                            None,
+                            type_builder.as_ref(),
+                            b_options.client_registry.as_ref(),
                         )
                         .await;
+                    self.record_call(&runtime, &b_fn, start.elapsed(), &result);
 
                     match result {
                         Ok(function_result) => match function_result.llm_response() {
@@ -526,7 +790,7 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
                             }
                             .into_response(),
                         },
-                        Err(e) => BamlError::from_anyhow(e).into_response(),
+                        Err(e) => BamlError::from_anyhow(&e).into_response(),
                     }
                 }
                 Err(e) => BamlError::InternalError {
@@ -550,19 +814,11 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
         extract::Path(path): extract::Path<String>,
         extract::Json(body): extract::Json<serde_json::Value>,
     ) -> Response {
-        let mut b_options = None;
-        if let Some(options_value) = body.get("__baml_options__") {
-            match serde_json::from_value::<BamlOptions>(options_value.clone()) {
-                Ok(opts) => b_options = Some(opts),
-                Err(_) => {
-                    return BamlError::InvalidArgument {
-                        message: "Failed to parse __baml_options__".to_string(),
-                    }
-                    .into_response()
-                }
-            }
-        }
-        self.baml_stream(path, body, b_options)
+        let b_options = match parse_baml_options(&body) {
+            Ok(b_options) => b_options,
+            Err(e) => return e.into_response(),
+        };
+        self.baml_stream(path, body, b_options).await
     }
 
     /// Serve an HTML page that loads swagger-ui from local static files.
@@ -613,7 +869,7 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
 
     /// Render the openapi spec. This endpoint is used by the swagger ui.
     async fn openapi_json_handler(self: Arc<Self>) -> Result<String, BamlError> {
-        let locked = self.b.read().await;
+        let runtime = self.runtime().await;
         let fake_generator = GeneratorArgs::new(
             "fake_directory",
             "fake_directory",
@@ -626,7 +882,7 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
         .map_err(|_| BamlError::InternalError {
             message: "Failed to make placeholder generator".to_string(),
         })?;
-        let schema: OpenApiSchema = (locked.inner.ir.as_ref(), &fake_generator)
+        let schema: OpenApiSchema = (runtime.inner.ir.as_ref(), &fake_generator)
             .try_into()
             .map_err(|e| {
                 log::warn!("Failed to generate openapi schema: {}", e);
@@ -643,6 +899,38 @@ Tip: test that the server is up using `curl http://localhost:{}/_debug/ping`
     }
 }
 
+/// Resolves on Ctrl-C, or on SIGTERM on unix (what docker and kubernetes
+/// send to stop a container).
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::warn!("Failed to listen for Ctrl-C: {:?}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                log::warn!("Failed to listen for SIGTERM: {:?}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
 struct EventStream {
     receiver: tokio::sync::mpsc::UnboundedReceiver<FunctionResult>,
 }
@@ -666,6 +954,19 @@ impl Stream for EventStream {
     }
 }
 
+/// The `__baml_options__` of a request body, if it has any.
+fn parse_baml_options(body: &serde_json::Value) -> Result<Option<BamlOptions>, BamlError> {
+    body.get("__baml_options__")
+        .map(|options| {
+            serde_json::from_value::<BamlOptions>(options.clone()).map_err(|_| {
+                BamlError::InvalidArgument {
+                    message: "Failed to parse __baml_options__".to_string(),
+                }
+            })
+        })
+        .transpose()
+}
+
 fn parse_args(
     b_fn: &str,
     b_args: serde_json::Value,
@@ -716,3 +1017,40 @@ fn parse_args(
 
     Ok(args)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::test_server::{echo_baml, FakeLlm, TestServer};
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn reload_does_not_wait_for_in_flight_requests() {
+        let llm = FakeLlm::start("hello").await;
+        llm.hold();
+        let baml = echo_baml(&llm.base_url);
+        let server = TestServer::start(&baml).await;
+
+        let call = tokio::spawn(server.post("/call/Echo", json!({"text": "hi"})));
+        llm.wait_for_request().await;
+
+        server.write(&format!(
+            "{baml}\nfunction Echo2(text: string) -> string {{\n  client Fake\n  prompt #\"{{{{ text }}}}\"#\n}}\n"
+        ));
+        tokio::time::timeout(Duration::from_secs(5), server.server.reload())
+            .await
+            .expect("the reload waited for the in-flight request")
+            .unwrap();
+        assert!(server
+            .server
+            .runtime()
+            .await
+            .function_names()
+            .any(|name| name == "Echo2"));
+
+        // The request finishes with the runtime it started with.
+        llm.release();
+        let (status, body) = call.await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, json!("hello"));
+    }
+}
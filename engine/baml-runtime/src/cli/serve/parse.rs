@@ -0,0 +1,119 @@
+use axum::extract;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::{
+    internal::{
+        llm_client::{parsed_value_to_response, ResponseBamlValue},
+        prompt_renderer::PromptRenderer,
+    },
+    InternalRuntimeInterface,
+};
+
+use super::{error::BamlError, json_response::Json, BamlOptions, Server};
+
+#[derive(Deserialize)]
+pub(super) struct ParseRequest {
+    /// The raw LLM response, e.g. as returned by the caller's own LLM client.
+    raw_output: String,
+    /// Accept incomplete responses, like `/stream` does for partial results.
+    #[serde(default)]
+    allow_partials: bool,
+    /// The type builder and client registry of the call that produced the
+    /// response, e.g. to parse into `@@dynamic` classes.
+    #[serde(rename = "__baml_options__", default)]
+    baml_options: BamlOptions,
+}
+
+impl Server {
+    /// Parse a raw LLM response into the return type of a BAML function,
+    /// without calling the LLM. Checks and asserts are run as usual.
+    pub(super) async fn parse_handler(
+        self: Arc<Self>,
+        extract::Path(b_fn): extract::Path<String>,
+        extract::Json(request): extract::Json<ParseRequest>,
+    ) -> Result<Json<ResponseBamlValue>, BamlError> {
+        let b_options = &request.baml_options;
+        let runtime = self.runtime().await;
+        let type_builder = b_options.type_builder(&runtime)?;
+        let ctx = b_options
+            .ctx_manager(&runtime)
+            .create_ctx(type_builder.as_ref(), b_options.client_registry.as_ref())
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+
+        let internal = runtime.internal();
+        let function =
+            internal
+                .get_function(&b_fn, &ctx)
+                .map_err(|e| BamlError::InvalidArgument {
+                    message: format!("{:?}", e),
+                })?;
+        let renderer = PromptRenderer::from_function(&function, internal.ir(), &ctx)
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+
+        match renderer.parse(&request.raw_output, request.allow_partials) {
+            Ok(parsed) => Ok(Json(parsed_value_to_response(&parsed))),
+            Err(e) => Err(BamlError::ValidationFailure {
+                prompt: "N/A".to_string(),
+                raw_output: request.raw_output,
+                message: format!("Failed to parse LLM response: {}", e),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde_json::json;
+
+    use super::super::test_server::{echo_baml, FakeLlm, TestServer};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn parses_without_calling_the_llm() {
+        let llm = FakeLlm::start("hello").await;
+        let server = TestServer::start(&echo_baml(&llm.base_url)).await;
+
+        let (status, body) = server
+            .post("/parse/Echo", json!({"raw_output": "hi there"}))
+            .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body, json!("hi there"));
+        assert_eq!(llm.requests(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_type_builders_of_unknown_classes() {
+        let llm = FakeLlm::start("hello").await;
+        let server = TestServer::start(&echo_baml(&llm.base_url)).await;
+
+        let (status, body) = server
+            .post(
+                "/parse/Echo",
+                json!({
+                    "raw_output": "hi there",
+                    "__baml_options__": {
+                        "type_builder": { "classes": { "Resme": { "properties": {} } } }
+                    }
+                }),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+        assert!(
+            body["message"].as_str().unwrap().contains("`Resme`"),
+            "{body}"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_unknown_functions() {
+        let llm = FakeLlm::start("hello").await;
+        let server = TestServer::start(&echo_baml(&llm.base_url)).await;
+
+        let (status, body) = server
+            .post("/parse/Missing", json!({"raw_output": "hi there"}))
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+        assert_eq!(body["error"], "invalid_argument");
+    }
+}
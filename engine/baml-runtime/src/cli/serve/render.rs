@@ -0,0 +1,119 @@
+use axum::extract;
+use internal_baml_jinja::TokenEstimate;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::{InternalRuntimeInterface, RenderCurlSettings, RenderedPrompt};
+
+use super::{error::BamlError, json_response::Json, parse_args, parse_baml_options, Server};
+
+#[derive(Deserialize)]
+pub(super) struct RenderQuery {
+    /// Render the curl command for a streaming request.
+    stream: Option<bool>,
+    /// Which client of a fallback/round-robin strategy to render for.
+    node_index: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub(super) struct RenderResponse {
+    prompt: RenderedPrompt,
+    /// The size of the prompt, counted with the client's tokenizer.
+    tokens: TokenEstimate,
+    /// The request BAML would send to the LLM provider. Only chat prompts
+    /// can be rendered as curl.
+    curl: Option<String>,
+}
+
+impl Server {
+    /// Render the prompt of a BAML function without calling the LLM.
+    pub(super) async fn render_handler(
+        self: Arc<Self>,
+        extract::Path(b_fn): extract::Path<String>,
+        extract::Query(query): extract::Query<RenderQuery>,
+        extract::Json(b_args): extract::Json<serde_json::Value>,
+    ) -> Result<Json<RenderResponse>, BamlError> {
+        let b_options = parse_baml_options(&b_args)?.unwrap_or_default();
+        let args = parse_args(&b_fn, b_args)?;
+        let runtime = self.runtime().await;
+        let type_builder = b_options.type_builder(&runtime)?;
+        let ctx = b_options
+            .ctx_manager(&runtime)
+            .create_ctx(type_builder.as_ref(), b_options.client_registry.as_ref())
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+
+        let (prompt, _, _) = runtime
+            .internal()
+            .render_prompt(&b_fn, &ctx, &args, query.node_index)
+            .await
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+        let tokens = runtime
+            .internal()
+            .estimate_prompt_tokens(&b_fn, &ctx, &prompt, query.node_index)
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+
+        let curl = match &prompt {
+            RenderedPrompt::Chat(messages) => Some(
+                runtime
+                    .internal()
+                    .render_raw_curl(
+                        &b_fn,
+                        &ctx,
+                        messages,
+                        RenderCurlSettings {
+                            stream: query.stream.unwrap_or(false),
+                            // The caller can't run our shell commands, so
+                            // inline the media instead.
+                            as_shell_commands: false,
+                        },
+                        query.node_index,
+                    )
+                    .await
+                    .map_err(|e| BamlError::from_anyhow(&e))?,
+            ),
+            RenderedPrompt::Completion(_) => None,
+        };
+
+        Ok(Json(RenderResponse {
+            prompt,
+            tokens,
+            curl,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+    use serde_json::json;
+
+    use super::super::test_server::{echo_baml, FakeLlm, TestServer};
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn renders_without_calling_the_llm() {
+        let llm = FakeLlm::start("hello").await;
+        let server = TestServer::start(&echo_baml(&llm.base_url)).await;
+
+        let (status, body) = server.post("/render/Echo", json!({"text": "hi"})).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert!(body["prompt"].to_string().contains("Repeat: hi"), "{body}");
+        assert!(body["tokens"].is_object(), "{body}");
+        let curl = body["curl"].as_str().unwrap();
+        assert!(curl.contains(&llm.base_url), "{curl}");
+        assert!(curl.contains("Repeat: hi"), "{curl}");
+        assert_eq!(llm.requests(), 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn rejects_unknown_functions() {
+        let llm = FakeLlm::start("hello").await;
+        let server = TestServer::start(&echo_baml(&llm.base_url)).await;
+
+        let (status, body) = server.post("/render/Missing", json!({"text": "hi"})).await;
+        assert!(
+            status.is_client_error() || status.is_server_error(),
+            "{body}"
+        );
+        assert!(body["error"].is_string(), "{body}");
+    }
+}
@@ -0,0 +1,178 @@
+//! An in-process server, and a fake OpenAI-compatible LLM for it to call, for
+//! the handler tests.
+
+use std::{
+    future::Future,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use axum::{http::StatusCode, routing::post};
+use serde_json::json;
+use tokio::sync::{Notify, Semaphore};
+
+use super::Server;
+
+/// Answers every chat completion with the same content. Requests can be held
+/// until [`FakeLlm::release`], to keep them in flight.
+pub(super) struct FakeLlm {
+    pub base_url: String,
+    state: Arc<FakeLlmState>,
+}
+
+struct FakeLlmState {
+    content: String,
+    held: AtomicBool,
+    released: Semaphore,
+    requests: AtomicUsize,
+    arrived: Notify,
+}
+
+impl FakeLlm {
+    pub async fn start(content: &str) -> Self {
+        let state = Arc::new(FakeLlmState {
+            content: content.to_string(),
+            held: AtomicBool::new(false),
+            released: Semaphore::new(0),
+            requests: AtomicUsize::new(0),
+            arrived: Notify::new(),
+        });
+
+        let s = state.clone();
+        let app = axum::Router::new().route(
+            "/chat/completions",
+            post(move || {
+                let state = s.clone();
+                async move {
+                    state.requests.fetch_add(1, Ordering::SeqCst);
+                    state.arrived.notify_one();
+                    if state.held.load(Ordering::SeqCst) {
+                        if let Ok(permit) = state.released.acquire().await {
+                            permit.forget();
+                        }
+                    }
+                    axum::Json(json!({
+                        "id": "chatcmpl-test",
+                        "object": "chat.completion",
+                        "created": 0,
+                        "model": "fake",
+                        "choices": [{
+                            "index": 0,
+                            "message": {"role": "assistant", "content": state.content},
+                            "finish_reason": "stop",
+                        }],
+                        "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2},
+                    }))
+                }
+            }),
+        );
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        FakeLlm { base_url, state }
+    }
+
+    /// Holds the requests that arrive from now on until [`Self::release`].
+    pub fn hold(&self) {
+        self.state.held.store(true, Ordering::SeqCst);
+    }
+
+    pub fn release(&self) {
+        self.state.held.store(false, Ordering::SeqCst);
+        self.state.released.add_permits(1024);
+    }
+
+    /// Resolves once a request has arrived, including one that arrived before.
+    pub async fn wait_for_request(&self) {
+        self.state.arrived.notified().await;
+    }
+
+    pub fn requests(&self) -> usize {
+        self.state.requests.load(Ordering::SeqCst)
+    }
+}
+
+/// `function Echo(text: string) -> string`, called with the fake LLM at
+/// `base_url`.
+pub(super) fn echo_baml(base_url: &str) -> String {
+    format!(
+        r##"
+client<llm> Fake {{
+  provider openai-generic
+  options {{
+    base_url "{base_url}"
+    api_key "test"
+    model "fake"
+  }}
+}}
+
+function Echo(text: string) -> string {{
+  client Fake
+  prompt #"Repeat: {{{{ text }}}}"#
+}}
+"##
+    )
+}
+
+/// A server for a `baml_src` in a temporary directory, listening on a free
+/// port.
+pub(super) struct TestServer {
+    pub server: Arc<Server>,
+    url: String,
+    src_dir: PathBuf,
+}
+
+impl TestServer {
+    pub async fn start(baml: &str) -> Self {
+        let src_dir = std::env::temp_dir().join(format!("baml-serve-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&src_dir).unwrap();
+        std::fs::write(src_dir.join("main.baml"), baml).unwrap();
+
+        let (server, listener) = Server::new(src_dir.clone(), 0).await.unwrap();
+        let url = format!("http://127.0.0.1:{}", listener.local_addr().unwrap().port());
+        tokio::spawn(server.clone().serve(listener));
+
+        TestServer {
+            server,
+            url,
+            src_dir,
+        }
+    }
+
+    /// Replaces `baml_src`, for a later [`Server::reload`].
+    pub fn write(&self, baml: &str) {
+        std::fs::write(self.src_dir.join("main.baml"), baml).unwrap();
+    }
+
+    /// POSTs `body` to `path`, returning the status and the JSON body (or
+    /// `null` if the body isn't JSON).
+    pub fn post(
+        &self,
+        path: &str,
+        body: serde_json::Value,
+    ) -> impl Future<Output = (StatusCode, serde_json::Value)> + 'static {
+        let url = format!("{}{}", self.url, path);
+        async move {
+            let response = reqwest::Client::new()
+                .post(url)
+                .json(&body)
+                .send()
+                .await
+                .unwrap();
+            let status = StatusCode::from_u16(response.status().as_u16()).unwrap();
+            let body = response.json().await.unwrap_or(serde_json::Value::Null);
+            (status, body)
+        }
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.src_dir);
+    }
+}
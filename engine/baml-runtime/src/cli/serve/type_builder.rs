@@ -0,0 +1,332 @@
+use baml_types::{BamlValue, FieldType};
+use indexmap::IndexMap;
+use internal_baml_core::ir::{repr::IntermediateRepr, IRHelper};
+use serde::Deserialize;
+
+use crate::type_builder::{TypeBuilder, WithMeta};
+
+use super::error::BamlError;
+
+/// The `type_builder` of `__baml_options__`: the properties and values to
+/// add to `@@dynamic` classes and enums for a single call.
+///
+/// ```json
+/// {
+///   "classes": {
+///     "Resume": {
+///       "properties": {
+///         "skills": { "type": { "list": "string" }, "description": "..." }
+///       }
+///     }
+///   },
+///   "enums": {
+///     "Category": { "values": { "Travel": { "alias": "k1" } } }
+///   }
+/// }
+/// ```
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TypeBuilderSpec {
+    #[serde(default)]
+    classes: IndexMap<String, ClassSpec>,
+    #[serde(default)]
+    enums: IndexMap<String, EnumSpec>,
+}
+
+impl TypeBuilderSpec {
+    /// Rejects classes and enums that don't exist or aren't `@@dynamic`,
+    /// which the type builder would otherwise silently ignore, e.g. typos.
+    pub(super) fn validate(&self, ir: &IntermediateRepr) -> Result<(), BamlError> {
+        for name in self.classes.keys() {
+            let dynamic = ir
+                .find_class(name)
+                .map(|c| c.item.attributes.get("dynamic_type").is_some());
+            ensure_dynamic("class", name, dynamic)?;
+        }
+        for name in self.enums.keys() {
+            let dynamic = ir
+                .find_enum(name)
+                .map(|e| e.item.attributes.get("dynamic_type").is_some());
+            ensure_dynamic("enum", name, dynamic)?;
+        }
+        Ok(())
+    }
+}
+
+fn ensure_dynamic(kind: &str, name: &str, dynamic: anyhow::Result<bool>) -> Result<(), BamlError> {
+    match dynamic {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(BamlError::InvalidArgument {
+            message: format!(
+                "type_builder: {kind} `{name}` can't be changed because it isn't @@dynamic"
+            ),
+        }),
+        Err(_) => Err(BamlError::InvalidArgument {
+            message: format!("type_builder: {kind} `{name}` does not exist"),
+        }),
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+struct ClassSpec {
+    #[serde(default)]
+    properties: IndexMap<String, PropertySpec>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+struct PropertySpec {
+    /// Omitted to only change the alias or description of an existing
+    /// property.
+    r#type: Option<FieldTypeSpec>,
+    alias: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+struct EnumSpec {
+    #[serde(default)]
+    values: IndexMap<String, EnumValueSpec>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+struct EnumValueSpec {
+    alias: Option<String>,
+    description: Option<String>,
+    skip: Option<bool>,
+}
+
+/// A BAML type, e.g. `"string"`, `{"class": "Resume"}` or
+/// `{"map": {"key": "string", "value": {"list": "int"}}}`.
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "snake_case")]
+enum FieldTypeSpec {
+    String,
+    Int,
+    Float,
+    Bool,
+    Null,
+    /// A string, integer or boolean.
+    Literal(serde_json::Value),
+    Class(String),
+    Enum(String),
+    List(Box<FieldTypeSpec>),
+    Optional(Box<FieldTypeSpec>),
+    Map {
+        key: Box<FieldTypeSpec>,
+        value: Box<FieldTypeSpec>,
+    },
+    Union(Vec<FieldTypeSpec>),
+}
+
+impl TryFrom<&FieldTypeSpec> for FieldType {
+    type Error = BamlError;
+
+    fn try_from(spec: &FieldTypeSpec) -> Result<Self, BamlError> {
+        Ok(match spec {
+            FieldTypeSpec::String => FieldType::string(),
+            FieldTypeSpec::Int => FieldType::int(),
+            FieldTypeSpec::Float => FieldType::float(),
+            FieldTypeSpec::Bool => FieldType::bool(),
+            FieldTypeSpec::Null => FieldType::null(),
+            FieldTypeSpec::Literal(value) => match value {
+                serde_json::Value::String(s) => FieldType::literal_string(s.clone()),
+                serde_json::Value::Bool(b) => FieldType::literal_bool(*b),
+                serde_json::Value::Number(n) if n.is_i64() => {
+                    FieldType::literal_int(n.as_i64().unwrap_or_default())
+                }
+                _ => {
+                    return Err(BamlError::InvalidArgument {
+                        message: format!(
+                            "Literal types must be a string, an integer or a boolean, got {}",
+                            value
+                        ),
+                    })
+                }
+            },
+            FieldTypeSpec::Class(name) => FieldType::class(name),
+            FieldTypeSpec::Enum(name) => FieldType::r#enum(name),
+            FieldTypeSpec::List(inner) => FieldType::try_from(inner.as_ref())?.as_list(),
+            FieldTypeSpec::Optional(inner) => FieldType::try_from(inner.as_ref())?.as_optional(),
+            FieldTypeSpec::Map { key, value } => FieldType::map(
+                FieldType::try_from(key.as_ref())?,
+                FieldType::try_from(value.as_ref())?,
+            ),
+            FieldTypeSpec::Union(variants) => FieldType::union(
+                variants
+                    .iter()
+                    .map(FieldType::try_from)
+                    .collect::<Result<_, _>>()?,
+            ),
+        })
+    }
+}
+
+impl TryFrom<&TypeBuilderSpec> for TypeBuilder {
+    type Error = BamlError;
+
+    fn try_from(spec: &TypeBuilderSpec) -> Result<Self, BamlError> {
+        let tb = TypeBuilder::new();
+
+        for (class_name, class) in spec.classes.iter() {
+            let cls = tb.class(class_name);
+            let cls = cls.lock().unwrap();
+            for (property_name, property) in class.properties.iter() {
+                let prop = cls.property(property_name);
+                let prop = prop.lock().unwrap();
+                if let Some(r#type) = &property.r#type {
+                    prop.r#type(FieldType::try_from(r#type)?);
+                }
+                if let Some(alias) = &property.alias {
+                    prop.with_meta("alias", BamlValue::String(alias.clone()));
+                }
+                if let Some(description) = &property.description {
+                    prop.with_meta("description", BamlValue::String(description.clone()));
+                }
+            }
+        }
+
+        for (enum_name, enm) in spec.enums.iter() {
+            let enm_builder = tb.r#enum(enum_name);
+            let enm_builder = enm_builder.lock().unwrap();
+            for (value_name, value) in enm.values.iter() {
+                let val = enm_builder.value(value_name);
+                let val = val.lock().unwrap();
+                if let Some(alias) = &value.alias {
+                    val.with_meta("alias", BamlValue::String(alias.clone()));
+                }
+                if let Some(description) = &value.description {
+                    val.with_meta("description", BamlValue::String(description.clone()));
+                }
+                if let Some(skip) = value.skip {
+                    val.with_meta("skip", BamlValue::Bool(skip));
+                }
+            }
+        }
+
+        Ok(tb)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_builder_spec() {
+        let spec: TypeBuilderSpec = serde_json::from_value(serde_json::json!({
+            "classes": {
+                "Resume": {
+                    "properties": {
+                        "skills": { "type": { "list": "string" }, "description": "Hard skills" },
+                        "name": { "alias": "full_name" },
+                        "level": {
+                            "type": { "union": [{ "literal": "junior" }, { "literal": "senior" }] }
+                        }
+                    }
+                }
+            },
+            "enums": {
+                "Category": { "values": { "Travel": { "alias": "k1" }, "Spam": { "skip": true } } }
+            }
+        }))
+        .unwrap();
+
+        let (classes, enums) = TypeBuilder::try_from(&spec).unwrap().to_overrides();
+        assert_eq!(
+            classes["Resume"].new_fields.keys().collect::<Vec<_>>(),
+            vec!["skills", "level"]
+        );
+        assert!(classes["Resume"].update_fields.contains_key("name"));
+        assert_eq!(
+            enums["Category"].values.keys().collect::<Vec<_>>(),
+            vec!["Travel", "Spam"]
+        );
+    }
+
+    #[test]
+    fn test_type_builder_spec_validation() {
+        let ir = internal_baml_core::ir::repr::make_test_ir(
+            r#"
+            class Resume {
+                name string
+                @@dynamic
+            }
+            class Job {
+                title string
+            }
+            enum Category {
+                Travel
+                @@dynamic
+            }
+            "#,
+        )
+        .unwrap();
+        let validate = |spec: serde_json::Value| {
+            serde_json::from_value::<TypeBuilderSpec>(spec)
+                .unwrap()
+                .validate(&ir)
+        };
+
+        assert!(validate(serde_json::json!({
+            "classes": { "Resume": { "properties": { "skills": { "type": "string" } } } },
+            "enums": { "Category": { "values": { "Food": {} } } }
+        }))
+        .is_ok());
+
+        let Err(BamlError::InvalidArgument { message }) =
+            validate(serde_json::json!({ "classes": { "Resume2": {} } }))
+        else {
+            panic!("expected an invalid argument error");
+        };
+        assert!(
+            message.contains("class `Resume2` does not exist"),
+            "{message}"
+        );
+
+        let Err(BamlError::InvalidArgument { message }) =
+            validate(serde_json::json!({ "classes": { "Job": {} } }))
+        else {
+            panic!("expected an invalid argument error");
+        };
+        assert!(
+            message.contains("class `Job` can't be changed"),
+            "{message}"
+        );
+
+        let Err(BamlError::InvalidArgument { message }) =
+            validate(serde_json::json!({ "enums": { "Categories": {} } }))
+        else {
+            panic!("expected an invalid argument error");
+        };
+        assert!(
+            message.contains("enum `Categories` does not exist"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_field_type_spec() {
+        let spec: FieldTypeSpec = serde_json::from_value(serde_json::json!({
+            "map": { "key": "string", "value": { "optional": { "class": "Resume" } } }
+        }))
+        .unwrap();
+        assert_eq!(
+            FieldType::try_from(&spec).unwrap(),
+            FieldType::map(
+                FieldType::string(),
+                FieldType::class("Resume").as_optional()
+            )
+        );
+
+        let spec: FieldTypeSpec =
+            serde_json::from_value(serde_json::json!({ "literal": 1.5 })).unwrap();
+        assert!(matches!(
+            FieldType::try_from(&spec),
+            Err(BamlError::InvalidArgument { .. })
+        ));
+    }
+}
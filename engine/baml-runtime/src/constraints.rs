@@ -14,10 +14,14 @@ use crate::internal::llm_client::LLMCompleteResponse;
 ///
 /// When a check in a test is evaluated, its results are added to the context
 /// so that future constraints can refer to it.
+///
+/// For tests run over a dataset, `expected` is the expected output of the
+/// row, available to the constraints as `_.expected`.
 pub fn evaluate_test_constraints(
     args: &IndexMap<String, BamlValue>,
     value: &BamlValueWithMeta<Vec<ResponseCheck>>,
     response: &LLMCompleteResponse,
+    expected: Option<&BamlValue>,
     constraints: Vec<Constraint>,
 ) -> TestConstraintsResult {
     // Fold over all the constraints, updating both our success state, and
@@ -26,7 +30,7 @@ pub fn evaluate_test_constraints(
     constraints
         .into_iter()
         .fold(Accumulator::new(), |acc, constraint| {
-            step_constraints(args, value, response, acc, constraint)
+            step_constraints(args, value, response, expected, acc, constraint)
         })
         .result
 }
@@ -106,6 +110,7 @@ fn step_constraints(
     args: &IndexMap<String, BamlValue>,
     value: &BamlValueWithMeta<Vec<ResponseCheck>>,
     response: &LLMCompleteResponse,
+    expected: Option<&BamlValue>,
     acc: Accumulator,
     constraint: Constraint,
 ) -> Accumulator {
@@ -138,6 +143,7 @@ fn step_constraints(
             ),
         ]
         .into_iter()
+        .chain(expected.map(|expected| ("expected", minijinja::Value::from_serialize(expected))))
         .collect::<HashMap<_, _>>(),
     );
 
@@ -360,7 +366,7 @@ mod tests {
         let value = mk_value();
         let constraints = constraints.into();
         let response = mk_response();
-        evaluate_test_constraints(&args, &value, &response, constraints)
+        evaluate_test_constraints(&args, &value, &response, None, constraints)
     }
 
     #[test]
@@ -462,4 +468,29 @@ mod tests {
         // This test fails because there is a typo: `__` (double underscore).
         assert!(matches!(res, TestConstraintsResult::InternalError { .. }));
     }
+
+    #[test]
+    fn test_expected() {
+        let expected = BamlValue::Int(2);
+        let res = evaluate_test_constraints(
+            &IndexMap::new(),
+            &mk_value(),
+            &mk_response(),
+            Some(&expected),
+            vec![
+                mk_check("same_count", "this.kids|length == _.expected"),
+                mk_check("many_kids", "_.expected > 5"),
+            ],
+        );
+        assert_eq!(
+            res,
+            TestConstraintsResult::Completed {
+                checks: vec![
+                    ("same_count".to_string(), true),
+                    ("many_kids".to_string(), false)
+                ],
+                failed_assert: None
+            }
+        );
+    }
 }
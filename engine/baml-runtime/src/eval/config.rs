@@ -0,0 +1,144 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::client_registry::{ClientProperty, ClientProvider, ClientRegistry};
+
+/// What an eval runs and how it scores the outputs, read from
+/// `baml_src/baml-eval.yaml`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EvalConfig {
+    /// The functions whose tests are run. All of them if empty.
+    #[serde(default)]
+    pub functions: Vec<String>,
+    /// The tests that are run. All of them if empty.
+    #[serde(default)]
+    pub tests: Vec<String>,
+    /// The variants every test is run with. A single variant without
+    /// overrides if empty.
+    #[serde(default)]
+    pub variants: Vec<VariantConfig>,
+    #[serde(default)]
+    pub scorers: Vec<ScorerConfig>,
+}
+
+impl EvalConfig {
+    /// The file, in `baml_src`, that the config is read from.
+    pub const FILE_NAME: &'static str = "baml-eval.yaml";
+
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for variant in &self.variants {
+            if !names.insert(variant.name.as_str()) {
+                anyhow::bail!("Variant `{}` is defined more than once", variant.name);
+            }
+            variant.client_registry()?;
+        }
+        let mut names = HashSet::new();
+        for scorer in &self.scorers {
+            if !names.insert(scorer.name.as_str()) {
+                anyhow::bail!("Scorer `{}` is defined more than once", scorer.name);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn variants(&self) -> Vec<VariantConfig> {
+        if self.variants.is_empty() {
+            vec![VariantConfig {
+                name: "default".to_string(),
+                client: None,
+                from: None,
+            }]
+        } else {
+            self.variants.clone()
+        }
+    }
+}
+
+/// A way of running the tests, to compare clients or prompt revisions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VariantConfig {
+    pub name: String,
+    /// A client of `baml_src`, or `provider/model`, used instead of the
+    /// client of the function under test.
+    #[serde(default)]
+    pub client: Option<String>,
+    /// Another `baml_src` directory to run the tests from, such as a checkout
+    /// of a previous prompt revision. Relative to the directory of the config.
+    #[serde(default)]
+    pub from: Option<PathBuf>,
+}
+
+impl VariantConfig {
+    pub fn client_registry(&self) -> Result<Option<ClientRegistry>> {
+        let Some(client) = &self.client else {
+            return Ok(None);
+        };
+        let mut registry = ClientRegistry::new();
+        if let Some((provider, model)) = client.split_once('/') {
+            let provider = provider.parse::<ClientProvider>().map_err(|e| {
+                anyhow::anyhow!(
+                    "Invalid client `{}` of variant `{}`: {}",
+                    client,
+                    self.name,
+                    e
+                )
+            })?;
+            registry.add_client(ClientProperty::from_shorthand(&provider, model));
+        }
+        registry.set_primary(client.clone());
+        Ok(Some(registry))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScorerConfig {
+    pub name: String,
+    #[serde(flatten)]
+    pub scorer: Scorer,
+}
+
+/// Scores an output between 0 and 1.
+///
+/// `field` is a dotted path, like `experience.0.company`, read from the output
+/// and from the expected output when it is an object. Without `field`, the
+/// whole values are compared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Scorer {
+    /// 1 if the output is the expected output. Surrounding whitespace of
+    /// strings is ignored.
+    ExactMatch {
+        #[serde(default)]
+        field: Option<String>,
+        #[serde(default)]
+        ignore_case: bool,
+    },
+    /// 1 if the output is within `tolerance` of the expected number.
+    Numeric {
+        #[serde(default)]
+        field: Option<String>,
+        #[serde(default)]
+        tolerance: f64,
+    },
+    /// The Jaccard similarity of the output and expected lists.
+    SetOverlap {
+        #[serde(default)]
+        field: Option<String>,
+        #[serde(default)]
+        ignore_case: bool,
+    },
+    /// A Jinja expression evaluating to a boolean or a number, with the same
+    /// variables as `@@assert`.
+    Expression { expression: String },
+    /// A BAML function returning a boolean, a number, or a class with a
+    /// `score` field. Its `output` and `expected` parameters get the output
+    /// and expected output, and its other parameters the test arguments of
+    /// the same name.
+    Judge { function: String },
+}
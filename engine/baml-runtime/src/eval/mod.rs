@@ -0,0 +1,167 @@
+//! Evals run the tests of functions with several variants, such as other
+//! clients or prompt revisions, score their outputs and aggregate the scores,
+//! so that runs can be compared beyond the pass/fail of `@@check` and
+//! `@@assert`.
+
+mod config;
+mod results;
+mod scorers;
+
+use anyhow::Result;
+use baml_types::{BamlMap, BamlValue};
+
+pub use config::{EvalConfig, Scorer, ScorerConfig, VariantConfig};
+pub use results::{Aggregate, CaseChange, CaseResult, CaseStatus, EvalRun, VariantDiff};
+
+use crate::{
+    client_registry::ClientRegistry, internal::llm_client::LLMResponse, test_dataset::DatasetRow,
+    BamlRuntime, FunctionResult, InternalRuntimeInterface, RuntimeContextManager, TestStatus,
+};
+use scorers::{judge_score, ScoreInput};
+
+/// Runs test cases with a variant and scores them.
+pub struct Evaluator<'a> {
+    pub runtime: &'a BamlRuntime,
+    pub variant: &'a VariantConfig,
+    pub client_registry: Option<ClientRegistry>,
+    pub scorers: &'a [ScorerConfig],
+}
+
+impl<'a> Evaluator<'a> {
+    pub fn new(
+        runtime: &'a BamlRuntime,
+        variant: &'a VariantConfig,
+        scorers: &'a [ScorerConfig],
+    ) -> Result<Self> {
+        Ok(Self {
+            runtime,
+            variant,
+            client_registry: variant.client_registry()?,
+            scorers,
+        })
+    }
+
+    /// Runs a test, or a row of its dataset, and scores its output. Errors are
+    /// recorded in the result rather than returned, so that one case doesn't
+    /// stop the eval.
+    pub async fn run_case(
+        &self,
+        function: &str,
+        test: &str,
+        row: Option<&DatasetRow>,
+        ctx: &RuntimeContextManager,
+    ) -> CaseResult {
+        let expected = row.and_then(|row| row.expected.as_ref());
+        let mut result = CaseResult {
+            variant: self.variant.name.clone(),
+            function: function.to_string(),
+            test: test.to_string(),
+            row: row.map(|row| row.line),
+            status: CaseStatus::Error,
+            message: None,
+            output: None,
+            expected: expected.and_then(|e| serde_json::to_value(e).ok()),
+            scores: Default::default(),
+            scorer_errors: Default::default(),
+            latency_ms: None,
+            total_tokens: None,
+        };
+
+        let (response, _) = self
+            .runtime
+            .run_test_row(
+                function,
+                test,
+                row,
+                ctx,
+                self.client_registry.as_ref(),
+                None::<fn(FunctionResult)>,
+            )
+            .await;
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                result.message = Some(format!("{:#}", e));
+                return result;
+            }
+        };
+
+        if let LLMResponse::Success(complete) = response.function_response.llm_response() {
+            result.latency_ms = Some(complete.latency.as_millis() as u64);
+            result.total_tokens = complete.metadata.total_tokens;
+        }
+        let output = match response.function_response.parsed_content() {
+            Ok(output) => BamlValue::from(output),
+            Err(e) => {
+                result.message = Some(format!("{:#}", e));
+                return result;
+            }
+        };
+        (result.status, result.message) = match response.status() {
+            TestStatus::Pass | TestStatus::NeedsHumanEval(_) => (CaseStatus::Passed, None),
+            TestStatus::Fail(reason) => (CaseStatus::Failed, Some(reason.to_string())),
+        };
+        result.output = serde_json::to_value(&output).ok();
+
+        let input = ScoreInput {
+            args: &response.params,
+            output: &output,
+            expected,
+            latency_ms: result.latency_ms,
+        };
+        for scorer in self.scorers {
+            let score = match &scorer.scorer {
+                Scorer::Judge { function } => self.judge(function, &input, ctx).await.map(Some),
+                other => other.score(&input),
+            };
+            match score {
+                Ok(Some(score)) => {
+                    result.scores.insert(scorer.name.clone(), score);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    result
+                        .scorer_errors
+                        .insert(scorer.name.clone(), format!("{:#}", e));
+                }
+            }
+        }
+        result
+    }
+
+    /// Calls a judge function with the output, the expected output and the
+    /// test arguments it has parameters for, with the same client registry as
+    /// the cases.
+    async fn judge(
+        &self,
+        judge: &str,
+        input: &ScoreInput<'_>,
+        ctx: &RuntimeContextManager,
+    ) -> Result<f64> {
+        let func = self.runtime.inner.ir().find_function(judge)?;
+        let mut params = BamlMap::new();
+        for (name, _) in func.inputs().iter() {
+            let value = match name.as_str() {
+                "output" => Some(input.output.clone()),
+                "expected" => Some(input.expected.cloned().unwrap_or(BamlValue::Null)),
+                other => input.args.get(other).cloned(),
+            };
+            if let Some(value) = value {
+                params.insert(name.clone(), value);
+            }
+        }
+
+        let (response, _) = self
+            .runtime
+            .call_function(
+                judge.to_string(),
+                &params,
+                ctx,
+                None,
+                self.client_registry.as_ref(),
+            )
+            .await;
+        let value = BamlValue::from(response?.parsed_content()?);
+        judge_score(&value)
+    }
+}
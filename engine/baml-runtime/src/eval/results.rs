@@ -0,0 +1,350 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use super::config::VariantConfig;
+
+/// Scores closer than this are considered equal when comparing runs.
+const SCORE_EPSILON: f64 = 1e-9;
+
+/// The results of an eval, written to a file so that runs can be compared.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalRun {
+    pub id: String,
+    pub started_at: String,
+    pub variants: Vec<VariantConfig>,
+    pub scorers: Vec<String>,
+    pub cases: Vec<CaseResult>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseStatus {
+    /// The function returned, and the checks and asserts of the test passed.
+    Passed,
+    /// The function returned, but a check or assert failed.
+    Failed,
+    /// The LLM call or parsing failed, so the output wasn't scored.
+    Error,
+}
+
+/// The result of a test, or of a row of its dataset, for one variant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaseResult {
+    pub variant: String,
+    pub function: String,
+    pub test: String,
+    /// The line of the dataset row, for tests with a dataset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub row: Option<usize>,
+    pub status: CaseStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<serde_json::Value>,
+    /// Scorer name to score. Scorers that don't apply to the case, or that
+    /// failed, have no score.
+    #[serde(default)]
+    pub scores: IndexMap<String, f64>,
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub scorer_errors: IndexMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_tokens: Option<u64>,
+}
+
+impl CaseResult {
+    /// Identifies the case across variants and runs.
+    pub fn key(&self) -> (&str, &str, Option<usize>) {
+        (&self.function, &self.test, self.row)
+    }
+
+    pub fn label(&self) -> String {
+        match self.row {
+            Some(line) => format!("{}::{} [line {}]", self.function, self.test, line),
+            None => format!("{}::{}", self.function, self.test),
+        }
+    }
+}
+
+/// The results of the cases of a variant.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Aggregate {
+    pub variant: String,
+    pub cases: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    /// Scorer name to the mean of its scores, and how many cases were scored.
+    pub scores: IndexMap<String, (f64, usize)>,
+    pub mean_latency_ms: Option<f64>,
+    pub total_tokens: u64,
+}
+
+impl Aggregate {
+    pub fn pass_rate(&self) -> f64 {
+        if self.cases == 0 {
+            0.0
+        } else {
+            self.passed as f64 / self.cases as f64
+        }
+    }
+}
+
+/// How a variant changed between two runs.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VariantDiff {
+    pub base: Aggregate,
+    pub head: Aggregate,
+    /// Cases that stopped passing or whose scores went down.
+    pub regressions: Vec<CaseChange>,
+    /// Cases that started passing or whose scores went up.
+    pub improvements: Vec<CaseChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CaseChange {
+    pub case: String,
+    pub changes: Vec<String>,
+}
+
+impl EvalRun {
+    pub fn aggregates(&self) -> Vec<Aggregate> {
+        self.variant_names()
+            .into_iter()
+            .map(|variant| self.aggregate(variant))
+            .collect()
+    }
+
+    fn variant_names(&self) -> Vec<&str> {
+        let mut names = self
+            .variants
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect::<Vec<_>>();
+        for case in &self.cases {
+            if !names.contains(&case.variant.as_str()) {
+                names.push(&case.variant);
+            }
+        }
+        names
+    }
+
+    fn cases_of<'a>(&'a self, variant: &'a str) -> impl Iterator<Item = &'a CaseResult> {
+        self.cases.iter().filter(move |c| c.variant == variant)
+    }
+
+    fn aggregate(&self, variant: &str) -> Aggregate {
+        let cases = self.cases_of(variant).collect::<Vec<_>>();
+        let count = |status| cases.iter().filter(|c| c.status == status).count();
+
+        let mut scores = self
+            .scorers
+            .iter()
+            .map(|name| (name.clone(), (0.0, 0)))
+            .collect::<IndexMap<_, _>>();
+        for case in &cases {
+            for (name, score) in &case.scores {
+                let (sum, n) = scores.entry(name.clone()).or_insert((0.0, 0));
+                *sum += score;
+                *n += 1;
+            }
+        }
+        for (sum, n) in scores.values_mut() {
+            if *n > 0 {
+                *sum /= *n as f64;
+            }
+        }
+
+        let latencies = cases
+            .iter()
+            .filter_map(|c| c.latency_ms)
+            .collect::<Vec<_>>();
+        Aggregate {
+            variant: variant.to_string(),
+            cases: cases.len(),
+            passed: count(CaseStatus::Passed),
+            failed: count(CaseStatus::Failed),
+            errors: count(CaseStatus::Error),
+            scores,
+            mean_latency_ms: (!latencies.is_empty())
+                .then(|| latencies.iter().sum::<u64>() as f64 / latencies.len() as f64),
+            total_tokens: cases.iter().filter_map(|c| c.total_tokens).sum(),
+        }
+    }
+
+    /// Compares the variants of `head` to the variants of the same name in
+    /// `self`. If both runs have a single variant, they are compared whatever
+    /// their names, so that a run can be compared to a run of another prompt
+    /// revision or client.
+    pub fn diff(&self, head: &EvalRun) -> Vec<VariantDiff> {
+        let base_variants = self.variant_names();
+        let head_variants = head.variant_names();
+        let pairs = match (base_variants.as_slice(), head_variants.as_slice()) {
+            ([base], [head]) => vec![(*base, *head)],
+            _ => base_variants
+                .iter()
+                .filter(|name| head_variants.contains(name))
+                .map(|name| (*name, *name))
+                .collect(),
+        };
+
+        pairs
+            .into_iter()
+            .map(|(base_variant, head_variant)| {
+                let mut regressions = vec![];
+                let mut improvements = vec![];
+                for head_case in head.cases_of(head_variant) {
+                    let Some(base_case) = self
+                        .cases_of(base_variant)
+                        .find(|c| c.key() == head_case.key())
+                    else {
+                        continue;
+                    };
+                    let (worse, better) = compare_cases(base_case, head_case);
+                    if !worse.is_empty() {
+                        regressions.push(CaseChange {
+                            case: head_case.label(),
+                            changes: worse.into_iter().chain(better).collect(),
+                        });
+                    } else if !better.is_empty() {
+                        improvements.push(CaseChange {
+                            case: head_case.label(),
+                            changes: better,
+                        });
+                    }
+                }
+                VariantDiff {
+                    base: self.aggregate(base_variant),
+                    head: head.aggregate(head_variant),
+                    regressions,
+                    improvements,
+                }
+            })
+            .collect()
+    }
+}
+
+/// The changes that made a case worse, and the ones that made it better.
+fn compare_cases(base: &CaseResult, head: &CaseResult) -> (Vec<String>, Vec<String>) {
+    let mut worse = vec![];
+    let mut better = vec![];
+
+    let passed = |c: &CaseResult| c.status == CaseStatus::Passed;
+    if passed(base) != passed(head) {
+        let change = format!("{:?} -> {:?}", base.status, head.status).to_lowercase();
+        if passed(base) {
+            worse.push(change);
+        } else {
+            better.push(change);
+        }
+    }
+
+    for (name, head_score) in &head.scores {
+        let Some(base_score) = base.scores.get(name) else {
+            continue;
+        };
+        let change = format!("{}: {:.2} -> {:.2}", name, base_score, head_score);
+        if head_score < &(base_score - SCORE_EPSILON) {
+            worse.push(change);
+        } else if head_score > &(base_score + SCORE_EPSILON) {
+            better.push(change);
+        }
+    }
+    (worse, better)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(variant: &str, row: usize, status: CaseStatus, score: f64) -> CaseResult {
+        CaseResult {
+            variant: variant.to_string(),
+            function: "ExtractResume".to_string(),
+            test: "Resumes".to_string(),
+            row: Some(row),
+            status,
+            message: None,
+            output: None,
+            expected: None,
+            scores: [("title".to_string(), score)].into_iter().collect(),
+            scorer_errors: Default::default(),
+            latency_ms: Some(100 * row as u64),
+            total_tokens: Some(10),
+        }
+    }
+
+    fn run(cases: Vec<CaseResult>) -> EvalRun {
+        EvalRun {
+            id: "run".to_string(),
+            started_at: "2024-01-01T00:00:00Z".to_string(),
+            variants: vec![],
+            scorers: vec!["title".to_string(), "judge".to_string()],
+            cases,
+        }
+    }
+
+    #[test]
+    fn aggregates_per_variant() {
+        let run = run(vec![
+            case("gpt-4o", 1, CaseStatus::Passed, 1.0),
+            case("gpt-4o", 2, CaseStatus::Failed, 0.0),
+            case("haiku", 1, CaseStatus::Error, 0.5),
+        ]);
+        let aggregates = run.aggregates();
+        assert_eq!(aggregates.len(), 2);
+
+        let gpt = &aggregates[0];
+        assert_eq!(gpt.variant, "gpt-4o");
+        assert_eq!(
+            (gpt.cases, gpt.passed, gpt.failed, gpt.errors),
+            (2, 1, 1, 0)
+        );
+        assert_eq!(gpt.pass_rate(), 0.5);
+        assert_eq!(gpt.scores["title"], (0.5, 2));
+        assert_eq!(gpt.scores["judge"], (0.0, 0));
+        assert_eq!(gpt.mean_latency_ms, Some(150.0));
+        assert_eq!(gpt.total_tokens, 20);
+
+        assert_eq!(aggregates[1].errors, 1);
+    }
+
+    #[test]
+    fn diffs_runs() {
+        let base = run(vec![
+            case("gpt-4o", 1, CaseStatus::Passed, 1.0),
+            case("gpt-4o", 2, CaseStatus::Failed, 0.0),
+            case("gpt-4o", 3, CaseStatus::Passed, 0.5),
+        ]);
+        let head = run(vec![
+            case("new-prompt", 1, CaseStatus::Failed, 1.0),
+            case("new-prompt", 2, CaseStatus::Passed, 1.0),
+            case("new-prompt", 3, CaseStatus::Passed, 0.5),
+        ]);
+
+        let diff = base.diff(&head);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].base.variant, "gpt-4o");
+        assert_eq!(diff[0].head.variant, "new-prompt");
+        assert_eq!(
+            diff[0].regressions,
+            vec![CaseChange {
+                case: "ExtractResume::Resumes [line 1]".to_string(),
+                changes: vec!["passed -> failed".to_string()],
+            }]
+        );
+        assert_eq!(
+            diff[0].improvements,
+            vec![CaseChange {
+                case: "ExtractResume::Resumes [line 2]".to_string(),
+                changes: vec![
+                    "failed -> passed".to_string(),
+                    "title: 0.00 -> 1.00".to_string()
+                ],
+            }]
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use baml_types::{BamlMap, BamlValue, JinjaExpression};
+use internal_baml_core::ir::jinja_helpers::render_expression;
+use serde_json::Value;
+
+use super::config::Scorer;
+
+/// What a case produced, for the scorers.
+pub(super) struct ScoreInput<'a> {
+    pub args: &'a BamlMap<String, BamlValue>,
+    pub output: &'a BamlValue,
+    pub expected: Option<&'a BamlValue>,
+    pub latency_ms: Option<u64>,
+}
+
+impl Scorer {
+    /// Scores the output of a case, or returns `None` if the scorer doesn't
+    /// apply to it, like comparisons for a case without an expected output.
+    /// Judges are run by the caller, since they call a BAML function.
+    pub(super) fn score(&self, input: &ScoreInput) -> Result<Option<f64>> {
+        match self {
+            Scorer::ExactMatch { field, ignore_case } => Ok(compared(input, field.as_deref())?
+                .map(|(output, expected)| {
+                    let equal =
+                        normalize(&output, *ignore_case) == normalize(&expected, *ignore_case);
+                    if equal {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                })),
+            Scorer::Numeric { field, tolerance } => {
+                let Some((output, expected)) = compared(input, field.as_deref())? else {
+                    return Ok(None);
+                };
+                let Some(expected) = as_number(&expected) else {
+                    anyhow::bail!("The expected value {} is not a number", expected);
+                };
+                Ok(Some(match as_number(&output) {
+                    Some(output) if (output - expected).abs() <= *tolerance => 1.0,
+                    _ => 0.0,
+                }))
+            }
+            Scorer::SetOverlap { field, ignore_case } => Ok(compared(input, field.as_deref())?
+                .map(|(output, expected)| {
+                    let output = as_set(&output, *ignore_case);
+                    let expected = as_set(&expected, *ignore_case);
+                    let union = output.union(&expected).count();
+                    if union == 0 {
+                        1.0
+                    } else {
+                        output.intersection(&expected).count() as f64 / union as f64
+                    }
+                })),
+            Scorer::Expression { expression } => {
+                let expression = expression
+                    .trim()
+                    .strip_prefix("{{")
+                    .and_then(|e| e.strip_suffix("}}"))
+                    .unwrap_or(expression);
+                let rendered = render_expression(
+                    &JinjaExpression(expression.trim().to_string()),
+                    &expression_context(input),
+                )?;
+                match rendered.as_str() {
+                    "true" => Ok(Some(1.0)),
+                    "false" | "" => Ok(Some(0.0)),
+                    other => other.parse::<f64>().map(Some).map_err(|_| {
+                        anyhow::anyhow!("Expected a boolean or a number, got `{}`", other)
+                    }),
+                }
+            }
+            Scorer::Judge { .. } => Ok(None),
+        }
+    }
+}
+
+/// The score returned by a judge function.
+pub(super) fn judge_score(value: &BamlValue) -> Result<f64> {
+    match value {
+        BamlValue::Bool(b) => Ok(if *b { 1.0 } else { 0.0 }),
+        BamlValue::Int(i) => Ok(*i as f64),
+        BamlValue::Float(f) => Ok(*f),
+        BamlValue::Class(_, fields) | BamlValue::Map(fields) => match fields.get("score") {
+            Some(score) => judge_score(score),
+            None => anyhow::bail!("Judges returning a class must have a `score` field"),
+        },
+        other => anyhow::bail!(
+            "Judges must return a bool, a number or a class with a `score` field, got a {}",
+            other.r#type()
+        ),
+    }
+}
+
+/// The output and expected values at `field`, or `None` without an expected
+/// output.
+fn compared(input: &ScoreInput, field: Option<&str>) -> Result<Option<(Value, Value)>> {
+    let Some(expected) = input.expected else {
+        return Ok(None);
+    };
+    let output = serde_json::to_value(input.output)?;
+    let expected = serde_json::to_value(expected)?;
+    let Some(field) = field else {
+        return Ok(Some((output, expected)));
+    };
+
+    let output = at_path(&output, field).cloned().unwrap_or(Value::Null);
+    let expected = match expected {
+        Value::Object(_) => match at_path(&expected, field) {
+            Some(expected) => expected.clone(),
+            None => return Ok(None),
+        },
+        other => other,
+    };
+    Ok(Some((output, expected)))
+}
+
+fn at_path<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    path.split('.').try_fold(value, |value, key| match value {
+        Value::Object(fields) => fields.get(key),
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|idx| items.get(idx)),
+        _ => None,
+    })
+}
+
+fn normalize(value: &Value, ignore_case: bool) -> Value {
+    match value {
+        Value::String(s) if ignore_case => Value::String(s.trim().to_lowercase()),
+        Value::String(s) => Value::String(s.trim().to_string()),
+        // Compare 3 and 3.0 as equal.
+        Value::Number(n) => n
+            .as_f64()
+            .and_then(serde_json::Number::from_f64)
+            .map_or_else(|| value.clone(), Value::Number),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| normalize(v, ignore_case)).collect())
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(k, v)| (k.clone(), normalize(v, ignore_case)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn as_set(value: &Value, ignore_case: bool) -> HashSet<String> {
+    let items = match value {
+        Value::Array(items) => items.iter().collect(),
+        Value::Null => vec![],
+        other => vec![other],
+    };
+    items
+        .into_iter()
+        .map(|item| normalize(item, ignore_case).to_string())
+        .collect()
+}
+
+/// The variables of `@@assert` expressions: `this`, `_` and the arguments.
+fn expression_context(input: &ScoreInput) -> HashMap<String, minijinja::Value> {
+    let mut underscore = HashMap::new();
+    if let Some(latency_ms) = input.latency_ms {
+        underscore.insert("latency_ms", minijinja::Value::from(latency_ms));
+    }
+    if let Some(expected) = input.expected {
+        underscore.insert("expected", minijinja::Value::from_serialize(expected));
+    }
+
+    input
+        .args
+        .iter()
+        .map(|(name, value)| (name.clone(), minijinja::Value::from_serialize(value)))
+        .chain([
+            (
+                "this".to_string(),
+                minijinja::Value::from_serialize(input.output),
+            ),
+            (
+                "_".to_string(),
+                minijinja::Value::from_serialize(underscore),
+            ),
+        ])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(value: serde_json::Value) -> BamlValue {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn score(
+        scorer: Scorer,
+        output: serde_json::Value,
+        expected: serde_json::Value,
+    ) -> Option<f64> {
+        let args = BamlMap::new();
+        let output = json(output);
+        let expected = json(expected);
+        scorer
+            .score(&ScoreInput {
+                args: &args,
+                output: &output,
+                expected: Some(&expected),
+                latency_ms: Some(100),
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn compares_fields() {
+        let exact = |field: &str| Scorer::ExactMatch {
+            field: Some(field.to_string()),
+            ignore_case: true,
+        };
+        let output =
+            serde_json::json!({"title": " Engineer", "years": 3, "jobs": [{"company": "Acme"}]});
+        let expected = serde_json::json!({"title": "engineer", "years": 3.0, "jobs": [{"company": "Initech"}]});
+        assert_eq!(
+            score(exact("title"), output.clone(), expected.clone()),
+            Some(1.0)
+        );
+        assert_eq!(
+            score(exact("years"), output.clone(), expected.clone()),
+            Some(1.0)
+        );
+        assert_eq!(
+            score(exact("jobs.0.company"), output.clone(), expected.clone()),
+            Some(0.0)
+        );
+        // Fields the expected output doesn't have are not scored.
+        assert_eq!(score(exact("name"), output.clone(), expected), None);
+        // Scalar expected outputs are compared with the field.
+        assert_eq!(
+            score(exact("title"), output, serde_json::json!("ENGINEER")),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn scores_numbers_and_sets() {
+        let numeric = Scorer::Numeric {
+            field: None,
+            tolerance: 0.5,
+        };
+        assert_eq!(
+            score(
+                numeric.clone(),
+                serde_json::json!(3.4),
+                serde_json::json!(3)
+            ),
+            Some(1.0)
+        );
+        assert_eq!(
+            score(numeric, serde_json::json!(4), serde_json::json!("3")),
+            Some(0.0)
+        );
+
+        let overlap = Scorer::SetOverlap {
+            field: Some("skills".to_string()),
+            ignore_case: false,
+        };
+        assert_eq!(
+            score(
+                overlap,
+                serde_json::json!({"skills": ["rust", "go", "sql"]}),
+                serde_json::json!({"skills": ["rust", "sql", "python", "go", "c"]}),
+            ),
+            Some(0.6)
+        );
+    }
+
+    #[test]
+    fn evaluates_expressions() {
+        let expression = |e: &str| Scorer::Expression {
+            expression: e.to_string(),
+        };
+        assert_eq!(
+            score(
+                expression("{{ this.title == _.expected.title and _.latency_ms < 1000 }}"),
+                serde_json::json!({"title": "a"}),
+                serde_json::json!({"title": "a"}),
+            ),
+            Some(1.0)
+        );
+        assert_eq!(
+            score(
+                expression("this.skills|length / 4"),
+                serde_json::json!({"skills": ["a"]}),
+                serde_json::json!({}),
+            ),
+            Some(0.25)
+        );
+    }
+
+    #[test]
+    fn reads_judge_scores() {
+        assert_eq!(judge_score(&BamlValue::Bool(true)).unwrap(), 1.0);
+        assert_eq!(
+            judge_score(&json(serde_json::json!({"score": 0.5, "reason": "ok"}))).unwrap(),
+            0.5
+        );
+        assert!(judge_score(&BamlValue::String("good".to_string())).is_err());
+    }
+}
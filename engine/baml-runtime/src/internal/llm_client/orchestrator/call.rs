@@ -1,6 +1,7 @@
 use anyhow::Result;
 use baml_types::BamlValue;
 use internal_baml_core::ir::repr::IntermediateRepr;
+use internal_baml_jinja::RenderedPrompt;
 use jsonish::BamlValueWithFlags;
 use web_time::Duration;
 
@@ -8,7 +9,9 @@ use crate::{
     internal::{
         llm_client::{
             parsed_value_to_response,
-            traits::{WithClientProperties, WithPrompt, WithSingleCallable},
+            traits::{
+                WithCandidatesCallable, WithClientProperties, WithPrompt, WithSingleCallable,
+            },
             LLMResponse, ResponseBamlValue,
         },
         prompt_renderer::PromptRenderer,
@@ -16,13 +19,16 @@ use crate::{
     RuntimeContext,
 };
 
-use super::{OrchestrationScope, OrchestratorNodeIterator};
+use super::{
+    candidates::{select, ParsedCandidate},
+    ExecutionScope, OrchestrationScope, OrchestratorNode, OrchestratorNodeIterator,
+};
 
 pub async fn orchestrate(
     iter: OrchestratorNodeIterator,
     ir: &IntermediateRepr,
     ctx: &RuntimeContext,
-    prompt: &PromptRenderer,
+    prompt_renderer: &PromptRenderer,
     params: &BamlValue,
     parse_fn: impl Fn(&str) -> Result<BamlValueWithFlags>,
 ) -> (
@@ -38,7 +44,7 @@ pub async fn orchestrate(
     let mut total_sleep_duration = std::time::Duration::from_secs(0);
 
     for node in iter {
-        let prompt = match node.render_prompt(ir, prompt, ctx, params).await {
+        let prompt = match node.render_prompt(ir, prompt_renderer, ctx, params).await {
             Ok(p) => p,
             Err(e) => {
                 results.push((
@@ -50,32 +56,77 @@ pub async fn orchestrate(
                 continue;
             }
         };
+        if let Some(candidates) = prompt_renderer.candidates().filter(|c| c.count > 1) {
+            let responses = node.candidates_call(ctx, &prompt, candidates.count).await;
+            let count = responses.len();
+            let attempts = responses
+                .into_iter()
+                .map(|response| {
+                    let (parsed, response_with_constraints) =
+                        parse_response(&node, &prompt, &response, &parse_fn);
+                    (response, parsed, response_with_constraints)
+                })
+                .collect::<Vec<_>>();
+
+            let selected = select(
+                candidates.strategy,
+                &attempts
+                    .iter()
+                    .map(|(_, parsed, response_with_constraints)| {
+                        match (parsed, response_with_constraints) {
+                            (Some(Ok(parsed)), Some(Ok(response))) => {
+                                Some(ParsedCandidate::new(parsed, response))
+                            }
+                            _ => None,
+                        }
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .or_else(|| {
+                attempts
+                    .iter()
+                    .position(|(r, _, _)| matches!(r, LLMResponse::Success(_)))
+            })
+            .unwrap_or(count.saturating_sub(1));
+
+            // The selected candidate goes last, since the last event is the
+            // function's result.
+            let mut scoped = attempts
+                .into_iter()
+                .enumerate()
+                .map(|(idx, (response, parsed, response_with_constraints))| {
+                    (
+                        node.scope.extend(ExecutionScope::Candidate(idx, count)),
+                        response,
+                        parsed,
+                        response_with_constraints,
+                    )
+                })
+                .collect::<Vec<_>>();
+            if selected < scoped.len() {
+                let chosen = scoped.remove(selected);
+                scoped.push(chosen);
+            }
+            let any_success = scoped
+                .iter()
+                .any(|(_, r, _, _)| matches!(r, LLMResponse::Success(_)));
+            results.extend(scoped);
+
+            let sleep_duration = node.error_sleep_duration().cloned();
+            if any_success {
+                break;
+            } else if let Some(duration) = sleep_duration {
+                total_sleep_duration += duration;
+                async_std::task::sleep(duration).await;
+            }
+            continue;
+        }
+
         let response = node.single_call(ctx, &prompt).await;
-        let parsed_response = match &response {
-            LLMResponse::Success(s) => {
-                if !node
-                    .finish_reason_filter()
-                    .is_allowed(s.metadata.finish_reason.as_ref())
-                {
-                    Some(Err(anyhow::anyhow!(crate::errors::ExposedError::FinishReasonError {
-                        prompt: prompt.to_string(),
-                        raw_output: s.content.clone(),
-                        message: "Finish reason not allowed".to_string(),
-                        finish_reason: s.metadata.finish_reason.clone(),
-                    })))
-                } else {
-                    Some(parse_fn(&s.content))
-                }
-            },
-            _ => None,
-        };
+        let (parsed_response, response_with_constraints) =
+            parse_response(&node, &prompt, &response, &parse_fn);
 
         let sleep_duration = node.error_sleep_duration().cloned();
-        let (parsed_response, response_with_constraints) = match parsed_response {
-            Some(Ok(v)) => (Some(Ok(v.clone())), Some(Ok(parsed_value_to_response(&v)))),
-            Some(Err(e)) => (None, Some(Err(e))),
-            None => (None, None),
-        };
         results.push((
             node.scope,
             response,
@@ -97,3 +148,40 @@ pub async fn orchestrate(
 
     (results, total_sleep_duration)
 }
+
+fn parse_response(
+    node: &OrchestratorNode,
+    prompt: &RenderedPrompt,
+    response: &LLMResponse,
+    parse_fn: &impl Fn(&str) -> Result<BamlValueWithFlags>,
+) -> (
+    Option<Result<BamlValueWithFlags>>,
+    Option<Result<ResponseBamlValue>>,
+) {
+    let parsed_response = match response {
+        LLMResponse::Success(s) => {
+            if !node
+                .finish_reason_filter()
+                .is_allowed(s.metadata.finish_reason.as_ref())
+            {
+                Some(Err(anyhow::anyhow!(
+                    crate::errors::ExposedError::FinishReasonError {
+                        prompt: prompt.to_string(),
+                        raw_output: s.content.clone(),
+                        message: "Finish reason not allowed".to_string(),
+                        finish_reason: s.metadata.finish_reason.clone(),
+                    }
+                )))
+            } else {
+                Some(parse_fn(&s.content))
+            }
+        }
+        _ => None,
+    };
+
+    match parsed_response {
+        Some(Ok(v)) => (Some(Ok(v.clone())), Some(Ok(parsed_value_to_response(&v)))),
+        Some(Err(e)) => (None, Some(Err(e))),
+        None => (None, None),
+    }
+}
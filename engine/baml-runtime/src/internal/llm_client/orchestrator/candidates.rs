@@ -0,0 +1,219 @@
+use baml_types::BamlValue;
+use futures::{Future, StreamExt};
+use internal_baml_core::internal_baml_parser_database::CandidateStrategy;
+use jsonish::BamlValueWithFlags;
+
+use crate::internal::llm_client::ResponseBamlValue;
+
+/// How many of a call's candidate requests are in flight at once.
+const MAX_CONCURRENT_CANDIDATES: usize = 8;
+
+/// Sends the `n` requests of a call's candidates, a few at a time so a large
+/// `count` doesn't trip the provider's rate limits. Responses are in order.
+pub(crate) async fn sample<F, Fut, T>(n: usize, request: F) -> Vec<T>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = T>,
+{
+    futures::stream::iter((0..n).map(request))
+        .buffered(MAX_CONCURRENT_CANDIDATES)
+        .collect()
+        .await
+}
+
+/// What a selection strategy looks at for a candidate that parsed.
+pub(super) struct ParsedCandidate {
+    pub value: BamlValue,
+    /// Lower is better, see `BamlValueWithFlags::score`.
+    pub score: i32,
+    pub checks_passed: usize,
+}
+
+impl ParsedCandidate {
+    pub fn new(parsed: &BamlValueWithFlags, response: &ResponseBamlValue) -> Self {
+        ParsedCandidate {
+            value: parsed.into(),
+            score: parsed.score(),
+            checks_passed: response
+                .iter()
+                .flat_map(|node| node.meta())
+                .filter(|check| check.status == "succeeded")
+                .count(),
+        }
+    }
+}
+
+/// Picks one of the candidates that parsed, returning its index. Ties go to
+/// the earliest candidate. Returns `None` if no candidate parsed.
+pub(super) fn select(
+    strategy: CandidateStrategy,
+    candidates: &[Option<ParsedCandidate>],
+) -> Option<usize> {
+    let parsed = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, c)| c.as_ref().map(|c| (idx, c)));
+
+    match strategy {
+        CandidateStrategy::FirstValid => parsed.map(|(idx, _)| idx).next(),
+        CandidateStrategy::MajorityVote => {
+            let parsed = parsed.collect::<Vec<_>>();
+            // Counting votes pairwise keeps the comparison structural, so
+            // maps with the same entries in a different order still agree.
+            parsed
+                .iter()
+                .map(|(idx, c)| {
+                    let votes = parsed.iter().filter(|(_, o)| o.value == c.value).count();
+                    (*idx, votes)
+                })
+                .fold(
+                    None,
+                    |best: Option<(usize, usize)>, (idx, votes)| match best {
+                        Some((_, best_votes)) if best_votes >= votes => best,
+                        _ => Some((idx, votes)),
+                    },
+                )
+                .map(|(idx, _)| idx)
+        }
+        CandidateStrategy::BestScore => parsed
+            .fold(None, |best: Option<(usize, i32)>, (idx, c)| match best {
+                Some((_, best_score)) if best_score <= c.score => best,
+                _ => Some((idx, c.score)),
+            })
+            .map(|(idx, _)| idx),
+        CandidateStrategy::MostChecks => parsed
+            .fold(None, |best: Option<(usize, usize)>, (idx, c)| match best {
+                Some((_, best_checks)) if best_checks >= c.checks_passed => best,
+                _ => Some((idx, c.checks_passed)),
+            })
+            .map(|(idx, _)| idx),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn parsed(value: &str, score: i32, checks_passed: usize) -> Option<ParsedCandidate> {
+        Some(ParsedCandidate {
+            value: BamlValue::String(value.to_string()),
+            score,
+            checks_passed,
+        })
+    }
+
+    #[test]
+    fn no_valid_candidates() {
+        for strategy in CandidateStrategy::ALL {
+            assert_eq!(select(strategy, &[None, None]), None);
+            assert_eq!(select(strategy, &[]), None);
+        }
+    }
+
+    #[test]
+    fn first_valid() {
+        let candidates = [None, parsed("a", 5, 0), parsed("b", 0, 3)];
+        assert_eq!(select(CandidateStrategy::FirstValid, &candidates), Some(1));
+    }
+
+    #[test]
+    fn majority_vote() {
+        let candidates = [
+            parsed("a", 0, 0),
+            parsed("b", 0, 0),
+            None,
+            parsed("b", 0, 0),
+            parsed("a", 0, 0),
+            parsed("b", 0, 0),
+        ];
+        assert_eq!(
+            select(CandidateStrategy::MajorityVote, &candidates),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn majority_vote_tie_goes_to_earliest() {
+        let candidates = [
+            parsed("a", 0, 0),
+            parsed("b", 0, 0),
+            parsed("b", 0, 0),
+            parsed("a", 0, 0),
+        ];
+        assert_eq!(
+            select(CandidateStrategy::MajorityVote, &candidates),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn majority_vote_ignores_map_order() {
+        let map = |entries: &[(&str, i64)]| {
+            Some(ParsedCandidate {
+                value: BamlValue::Map(
+                    entries
+                        .iter()
+                        .map(|(k, v)| (k.to_string(), BamlValue::Int(*v)))
+                        .collect(),
+                ),
+                score: 0,
+                checks_passed: 0,
+            })
+        };
+        let candidates = [
+            map(&[("x", 1)]),
+            map(&[("x", 2), ("y", 3)]),
+            map(&[("y", 3), ("x", 2)]),
+        ];
+        assert_eq!(
+            select(CandidateStrategy::MajorityVote, &candidates),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn best_score() {
+        let candidates = [
+            parsed("a", 3, 0),
+            None,
+            parsed("b", 1, 0),
+            parsed("c", 1, 0),
+        ];
+        assert_eq!(select(CandidateStrategy::BestScore, &candidates), Some(2));
+    }
+
+    #[test]
+    fn most_checks() {
+        let candidates = [
+            parsed("a", 0, 1),
+            parsed("b", 0, 2),
+            None,
+            parsed("c", 0, 2),
+        ];
+        assert_eq!(select(CandidateStrategy::MostChecks, &candidates), Some(1));
+    }
+
+    #[tokio::test]
+    async fn sample_bounds_requests_in_flight() {
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        let responses = sample(50, |i| {
+            let (in_flight, max_in_flight) = (&in_flight, &max_in_flight);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(now, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                i
+            }
+        })
+        .await;
+
+        assert_eq!(responses, (0..50).collect::<Vec<_>>());
+        assert_eq!(
+            max_in_flight.load(Ordering::SeqCst),
+            MAX_CONCURRENT_CANDIDATES
+        );
+    }
+}
@@ -1,4 +1,5 @@
 mod call;
+mod candidates;
 mod stream;
 
 use web_time::Duration; // Add this line
@@ -13,12 +14,15 @@ use super::traits::{WithClientProperties, WithRenderRawCurl};
 use super::LLMCompleteResponse;
 use super::{
     strategy::roundrobin::RoundRobinStrategy,
-    traits::{StreamResponse, WithPrompt, WithSingleCallable, WithStreamable},
+    traits::{
+        StreamResponse, WithCandidatesCallable, WithPrompt, WithSingleCallable, WithStreamable,
+    },
     LLMResponse,
 };
 
 pub use super::primitive::LLMPrimitiveProvider;
 pub use call::orchestrate as orchestrate_call;
+pub(crate) use candidates::sample as sample_candidates;
 pub use stream::orchestrate_stream;
 
 use anyhow::Result;
@@ -46,6 +50,9 @@ impl std::fmt::Display for ExecutionScope {
             ExecutionScope::Fallback(strategy, index) => {
                 write!(f, "Fallback({}, {})", strategy, index)
             }
+            ExecutionScope::Candidate(index, count) => {
+                write!(f, "Candidate({}, {})", index, count)
+            }
         }
     }
 }
@@ -129,7 +136,12 @@ impl OrchestrationScope {
     }
 
     pub fn direct_client_name(&self) -> Option<&String> {
-        match self.scope.last() {
+        match self
+            .scope
+            .iter()
+            .rev()
+            .find(|scope| !matches!(scope, ExecutionScope::Candidate(..)))
+        {
             Some(ExecutionScope::Direct(d)) => Some(d),
             _ => None,
         }
@@ -145,6 +157,8 @@ pub enum ExecutionScope {
     RoundRobin(Arc<RoundRobinStrategy>, usize),
     // StrategyName, ClientIndex
     Fallback(String, usize),
+    // CandidateIndex, CandidateCount
+    Candidate(usize, usize),
 }
 
 pub type OrchestratorNodeIterator = Vec<OrchestratorNode>;
@@ -205,6 +219,26 @@ impl WithSingleCallable for OrchestratorNode {
     }
 }
 
+impl WithCandidatesCallable for OrchestratorNode {
+    async fn candidates_call(
+        &self,
+        ctx: &RuntimeContext,
+        prompt: &RenderedPrompt,
+        n: usize,
+    ) -> Vec<LLMResponse> {
+        self.scope
+            .scope
+            .iter()
+            .filter_map(|scope| match scope {
+                ExecutionScope::RoundRobin(a, _) => Some(a),
+                _ => None,
+            })
+            .map(|a| a.increment_index())
+            .for_each(drop);
+        self.provider.candidates_call(ctx, prompt, n).await
+    }
+}
+
 impl WithStreamable for OrchestratorNode {
     async fn stream(&self, ctx: &RuntimeContext, prompt: &RenderedPrompt) -> StreamResponse {
         self.scope
@@ -240,4 +274,8 @@ impl WithClientProperties for OrchestratorNode {
     fn allowed_roles(&self) -> Vec<String> {
         self.provider.allowed_roles()
     }
+
+    fn max_context_tokens(&self) -> Option<usize> {
+        self.provider.max_context_tokens()
+    }
 }
@@ -31,7 +31,7 @@ use crate::{
         ErrorCode, LLMCompleteResponse, LLMCompleteResponseMetadata, LLMErrorResponse, LLMResponse,
         ModelFeatures,
     },
-    request::create_client,
+    request::create_client_with_config,
 };
 use serde_json::json;
 
@@ -95,6 +95,9 @@ impl WithClientProperties for AnthropicClient {
     fn allowed_roles(&self) -> Vec<String> {
         self.properties.allowed_roles()
     }
+    fn max_context_tokens(&self) -> Option<usize> {
+        self.properties.max_context_tokens
+    }
 }
 
 impl WithClient for AnthropicClient {
@@ -267,6 +270,11 @@ impl AnthropicClient {
             context: RenderContext_Client {
                 name: client.name.clone(),
                 provider: client.provider.to_string(),
+                model: properties
+                    .properties
+                    .get("model")
+                    .and_then(|m| m.as_str())
+                    .map(str::to_string),
                 default_role: properties.default_role(),
                 allowed_roles: properties.allowed_roles(),
             },
@@ -278,7 +286,7 @@ impl AnthropicClient {
                 allowed_metadata: properties.allowed_metadata.clone(),
             },
             retry_policy: client.retry_policy.clone(),
-            client: create_client()?,
+            client: create_client_with_config(&properties.http, ctx.baml_src_dir())?,
             properties,
         })
     }
@@ -290,6 +298,11 @@ impl AnthropicClient {
             context: RenderContext_Client {
                 name: client.name().into(),
                 provider: client.elem().provider.to_string(),
+                model: properties
+                    .properties
+                    .get("model")
+                    .and_then(|m| m.as_str())
+                    .map(str::to_string),
                 default_role: properties.default_role(),
                 allowed_roles: properties.allowed_roles(),
             },
@@ -305,7 +318,7 @@ impl AnthropicClient {
                 .retry_policy_id
                 .as_ref()
                 .map(|s| s.to_string()),
-            client: create_client()?,
+            client: create_client_with_config(&properties.http, ctx.baml_src_dir())?,
             properties,
         })
     }
@@ -71,6 +71,7 @@ impl AwsClient {
             context: RenderContext_Client {
                 name: client.name.clone(),
                 provider: client.provider.to_string(),
+                model: Some(properties.model.clone()),
                 default_role: properties.default_role(),
                 allowed_roles: properties.allowed_roles(),
             },
@@ -94,6 +95,7 @@ impl AwsClient {
             context: RenderContext_Client {
                 name: client.name().into(),
                 provider: client.elem().provider.to_string(),
+                model: Some(properties.model.clone()),
                 default_role: properties.default_role(),
                 allowed_roles: properties.allowed_roles(),
             },
@@ -291,6 +293,9 @@ impl WithClientProperties for AwsClient {
     fn allowed_roles(&self) -> Vec<String> {
         self.properties.allowed_roles()
     }
+    fn max_context_tokens(&self) -> Option<usize> {
+        self.properties.max_context_tokens
+    }
 }
 
 impl WithClient for AwsClient {
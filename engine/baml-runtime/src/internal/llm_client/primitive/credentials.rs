@@ -0,0 +1,322 @@
+#[cfg(target_arch = "wasm32")]
+use crate::internal::wasm_jwt::encode_jwt;
+use anyhow::{Context, Result};
+use futures::lock::Mutex;
+use internal_llm_client::{vertex::ServiceAccount, ClientCredentials};
+#[cfg(not(target_arch = "wasm32"))]
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use web_time::{Duration, Instant};
+
+/// Tokens are refreshed this long before they expire, so they don't expire
+/// while a request is in flight.
+const REFRESH_BEFORE_EXPIRY: Duration = Duration::from_secs(60);
+
+/// How long a token is used for when the token endpoint doesn't say.
+const DEFAULT_EXPIRES_IN: Duration = Duration::from_secs(300);
+
+// This is currently hardcoded, but we could make it a property if we wanted
+// https://developers.google.com/identity/protocols/oauth2/scopes
+const DEFAULT_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+/// Where a client gets its bearer tokens from.
+pub(super) enum TokenSource {
+    /// A token that is used as is.
+    Static(String),
+    /// An OAuth2 client credentials grant, e.g. from Azure AD.
+    ClientCredentials(ClientCredentials),
+    /// A JWT signed with a GCP service account's key, exchanged for an
+    /// access token.
+    ServiceAccount(ServiceAccount),
+}
+
+struct CachedToken {
+    access_token: String,
+    refresh_at: Instant,
+}
+
+/// Obtains short-lived bearer tokens and reuses them until shortly before
+/// they expire. Concurrent requests wait for a single refresh.
+pub(super) struct TokenProvider {
+    source: TokenSource,
+    cached: Mutex<Option<CachedToken>>,
+}
+
+impl TokenProvider {
+    pub fn new(source: TokenSource) -> Self {
+        Self {
+            source,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Requests tokens through `client`, the same transport as the LLM
+    /// requests.
+    pub async fn token(&self, client: &reqwest::Client) -> Result<String> {
+        if let TokenSource::Static(token) = &self.source {
+            return Ok(token.clone());
+        }
+
+        let mut cached = self.cached.lock().await;
+        if let Some(token) = cached
+            .as_ref()
+            .filter(|token| token.refresh_at > Instant::now())
+        {
+            return Ok(token.access_token.clone());
+        }
+
+        let requested_at = Instant::now();
+        let (access_token, expires_in) = self.request_token(client).await?;
+        let expires_in = expires_in.unwrap_or(DEFAULT_EXPIRES_IN);
+        // Tokens shorter lived than twice the margin are refreshed halfway.
+        let refresh_at = requested_at + expires_in - REFRESH_BEFORE_EXPIRY.min(expires_in / 2);
+
+        *cached = Some(CachedToken {
+            access_token: access_token.clone(),
+            refresh_at,
+        });
+        Ok(access_token)
+    }
+
+    async fn request_token(&self, client: &reqwest::Client) -> Result<(String, Option<Duration>)> {
+        let (token_url, req) = match &self.source {
+            TokenSource::Static(token) => return Ok((token.clone(), None)),
+            TokenSource::ClientCredentials(credentials) => {
+                let mut params = vec![
+                    ("grant_type", "client_credentials"),
+                    ("client_id", credentials.client_id.as_str()),
+                    ("client_secret", credentials.client_secret.as_str()),
+                ];
+                if let Some(scope) = &credentials.scope {
+                    params.push(("scope", scope.as_str()));
+                }
+                if let Some(audience) = &credentials.audience {
+                    params.push(("audience", audience.as_str()));
+                }
+                (
+                    &credentials.token_url,
+                    client.post(&credentials.token_url).form(&params),
+                )
+            }
+            TokenSource::ServiceAccount(service_account) => {
+                let jwt = encode_service_account_jwt(service_account).await?;
+                let params = [
+                    ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                    ("assertion", &jwt),
+                ];
+                (
+                    &service_account.token_uri,
+                    client.post(&service_account.token_uri).form(&params),
+                )
+            }
+        };
+
+        let res = req
+            .send()
+            .await
+            .with_context(|| format!("Failed to request a token from {token_url}"))?;
+        let status = res.status();
+        let body = res.text().await?;
+        if !status.is_success() {
+            anyhow::bail!("Token request to {token_url} failed with {status}: {body}");
+        }
+
+        parse_token_response(&body)
+            .with_context(|| format!("Invalid token response from {token_url}"))
+    }
+}
+
+fn parse_token_response(body: &str) -> Result<(String, Option<Duration>)> {
+    let res: Value = serde_json::from_str(body)?;
+    let res = res
+        .as_object()
+        .context("Token response is not a JSON object")?;
+
+    let access_token = res
+        .get("access_token")
+        .context("Access token not found in response")?
+        .as_str()
+        .context("Access token is not a string")?
+        .to_string();
+
+    // Some endpoints, e.g. Azure AD v1, send expires_in as a string.
+    let expires_in = match res.get("expires_in") {
+        Some(Value::Number(secs)) => secs.as_u64(),
+        Some(Value::String(secs)) => secs.parse().ok(),
+        _ => None,
+    };
+
+    Ok((access_token, expires_in.map(Duration::from_secs)))
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    iss: String,
+    scope: String,
+    aud: String,
+    exp: i64,
+    iat: i64,
+}
+
+impl Claims {
+    fn from_service_account(service_account: &ServiceAccount) -> Claims {
+        let now = chrono::Utc::now();
+        Claims {
+            iss: service_account.client_email.clone(),
+            scope: DEFAULT_SCOPE.to_string(),
+            aud: service_account.token_uri.clone(),
+            exp: (now + chrono::Duration::hours(1)).timestamp(),
+            iat: now.timestamp(),
+        }
+    }
+}
+
+async fn encode_service_account_jwt(service_account: &ServiceAccount) -> Result<String> {
+    let claims = Claims::from_service_account(service_account);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let jwt = encode(
+        &Header::new(Algorithm::RS256),
+        &claims,
+        &EncodingKey::from_rsa_pem(service_account.private_key.as_bytes())?,
+    )?;
+
+    #[cfg(target_arch = "wasm32")]
+    let jwt = encode_jwt(&serde_json::to_value(claims)?, &service_account.private_key)
+        .await
+        .map_err(|e| anyhow::anyhow!(format!("{e:?}")))?;
+
+    Ok(jwt)
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    use axum::{extract::State, http::StatusCode, routing::post, Form, Json, Router};
+
+    use super::*;
+
+    /// Requests received by the fake token endpoint.
+    type Received = Arc<Mutex<Vec<HashMap<String, String>>>>;
+
+    /// Serves `response` from a local token endpoint, returning its URL.
+    async fn fake_token_endpoint(response: (StatusCode, Value), received: Received) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/token", listener.local_addr().unwrap());
+        let app = Router::new()
+            .route(
+                "/token",
+                post(
+                    move |State(received): State<Received>,
+                          Form(form): Form<HashMap<String, String>>| {
+                        let (status, body) = response.clone();
+                        async move {
+                            received.lock().unwrap().push(form);
+                            (status, Json(body))
+                        }
+                    },
+                ),
+            )
+            .with_state(received);
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        url
+    }
+
+    fn client_credentials(token_url: String) -> TokenProvider {
+        TokenProvider::new(TokenSource::ClientCredentials(ClientCredentials {
+            token_url,
+            client_id: "client-id".to_string(),
+            client_secret: "client-secret".to_string(),
+            scope: Some("https://cognitiveservices.azure.com/.default".to_string()),
+            audience: None,
+        }))
+    }
+
+    #[tokio::test]
+    async fn reuses_tokens_until_they_expire() {
+        let received = Received::default();
+        let url = fake_token_endpoint(
+            (
+                StatusCode::OK,
+                serde_json::json!({"access_token": "token-1", "expires_in": 3599}),
+            ),
+            received.clone(),
+        )
+        .await;
+        let provider = client_credentials(url);
+        let client = reqwest::Client::new();
+
+        assert_eq!(provider.token(&client).await.unwrap(), "token-1");
+        assert_eq!(provider.token(&client).await.unwrap(), "token-1");
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["grant_type"], "client_credentials");
+        assert_eq!(received[0]["client_id"], "client-id");
+        assert_eq!(received[0]["client_secret"], "client-secret");
+        assert_eq!(
+            received[0]["scope"],
+            "https://cognitiveservices.azure.com/.default"
+        );
+        assert!(!received[0].contains_key("audience"));
+    }
+
+    #[tokio::test]
+    async fn refreshes_tokens_before_they_expire() {
+        let received = Received::default();
+        // Expires within the refresh margin, so it's refreshed on every use.
+        let url = fake_token_endpoint(
+            (
+                StatusCode::OK,
+                serde_json::json!({"access_token": "token", "expires_in": "0"}),
+            ),
+            received.clone(),
+        )
+        .await;
+        let provider = client_credentials(url);
+        let client = reqwest::Client::new();
+
+        provider.token(&client).await.unwrap();
+        provider.token(&client).await.unwrap();
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reports_token_endpoint_errors() {
+        let url = fake_token_endpoint(
+            (
+                StatusCode::UNAUTHORIZED,
+                serde_json::json!({"error": "invalid_client"}),
+            ),
+            Received::default(),
+        )
+        .await;
+        let provider = client_credentials(url);
+
+        let err = provider
+            .token(&reqwest::Client::new())
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("401"), "{err}");
+        assert!(err.contains("invalid_client"), "{err}");
+    }
+
+    #[test]
+    fn parses_token_responses() {
+        assert_eq!(
+            parse_token_response(r#"{"access_token": "a", "expires_in": 60}"#).unwrap(),
+            ("a".to_string(), Some(Duration::from_secs(60)))
+        );
+        assert_eq!(
+            parse_token_response(r#"{"access_token": "a"}"#).unwrap(),
+            ("a".to_string(), None)
+        );
+        assert!(parse_token_response(r#"{"token_type": "Bearer"}"#).is_err());
+    }
+}
@@ -6,9 +6,10 @@ use crate::internal::llm_client::ResolveMediaUrls;
 use crate::RuntimeContext;
 use crate::{
     internal::llm_client::{
+        orchestrator::sample_candidates,
         primitive::{
             google::types::{FinishReason, GoogleResponse},
-            request::{make_parsed_request, make_request, RequestBuilder},
+            request::{make_parsed_request, make_request, RequestBuilder, WithBodyFields},
         },
         traits::{
             SseResponseTrait, StreamResponse, WithChat, WithClient, WithNoCompletion,
@@ -17,7 +18,7 @@ use crate::{
         ErrorCode, LLMCompleteResponse, LLMCompleteResponseMetadata, LLMErrorResponse, LLMResponse,
         ModelFeatures,
     },
-    request::create_client,
+    request::create_client_with_config,
 };
 use anyhow::{Context, Result};
 use baml_types::{BamlMap, BamlMedia, BamlMediaContent};
@@ -84,6 +85,9 @@ impl WithClientProperties for GoogleAIClient {
     fn allowed_roles(&self) -> Vec<String> {
         self.properties.allowed_roles()
     }
+    fn max_context_tokens(&self) -> Option<usize> {
+        self.properties.max_context_tokens
+    }
 }
 
 impl WithClient for GoogleAIClient {
@@ -208,6 +212,7 @@ impl GoogleAIClient {
             context: RenderContext_Client {
                 name: client.name().into(),
                 provider: client.elem().provider.to_string(),
+                model: Some(properties.model.clone()),
                 default_role: properties.default_role(),
                 allowed_roles: properties.allowed_roles(),
             },
@@ -223,7 +228,7 @@ impl GoogleAIClient {
                 .retry_policy_id
                 .as_ref()
                 .map(|s| s.to_string()),
-            client: create_client()?,
+            client: create_client_with_config(&properties.http, ctx.baml_src_dir())?,
             properties,
         })
     }
@@ -236,6 +241,7 @@ impl GoogleAIClient {
             context: RenderContext_Client {
                 name: client.name.clone(),
                 provider: client.provider.to_string(),
+                model: Some(properties.model.clone()),
                 default_role: properties.default_role(),
                 allowed_roles: properties.allowed_roles(),
             },
@@ -247,7 +253,7 @@ impl GoogleAIClient {
                 allowed_metadata: properties.allowed_metadata.clone(),
             },
             retry_policy: client.retry_policy.clone(),
-            client: create_client()?,
+            client: create_client_with_config(&properties.http, ctx.baml_src_dir())?,
             properties,
         })
     }
@@ -336,7 +342,56 @@ impl WithChat for GoogleAIClient {
             });
         }
 
-        let Some(content) = response.candidates[0].content.as_ref() else {
+        self.candidate_to_response(prompt, &response, 0, system_now, instant_now)
+    }
+
+    async fn chat_candidates(
+        &self,
+        ctx: &RuntimeContext,
+        prompt: &[RenderedChatMessage],
+        n: usize,
+    ) -> Vec<LLMResponse> {
+        if n <= 1 {
+            return sample_candidates(n, |_| self.chat(ctx, prompt)).await;
+        }
+
+        let (response, system_now, instant_now) = match make_parsed_request::<GoogleResponse>(
+            &WithBodyFields::new(self, json!({ "generationConfig": { "candidateCount": n } })),
+            either::Either::Right(prompt),
+            false,
+        )
+        .await
+        {
+            Ok(v) => v,
+            Err(e) => return vec![e],
+        };
+
+        let mut responses = (0..response.candidates.len().min(n))
+            .map(|i| self.candidate_to_response(prompt, &response, i, system_now, instant_now))
+            .collect::<Vec<_>>();
+        if responses.len() < n {
+            let missing = n - responses.len();
+            responses.extend(
+                sample_candidates(missing, |_| self.chat(ctx, prompt)).await,
+            );
+        }
+        responses
+    }
+}
+
+impl GoogleAIClient {
+    /// Usage covers the whole request, so it's only reported on the first
+    /// candidate.
+    fn candidate_to_response(
+        &self,
+        prompt: &[RenderedChatMessage],
+        response: &GoogleResponse,
+        index: usize,
+        system_now: web_time::SystemTime,
+        instant_now: web_time::Instant,
+    ) -> LLMResponse {
+        let candidate = &response.candidates[index];
+        let Some(content) = candidate.content.as_ref() else {
             return LLMResponse::LLMFailure(LLMErrorResponse {
                 client: self.context.name.to_string(),
                 model: None,
@@ -348,6 +403,7 @@ impl WithChat for GoogleAIClient {
                 code: ErrorCode::Other(200),
             });
         };
+        let usage = Some(&response.usage_metadata).filter(|_| index == 0);
 
         LLMResponse::Success(LLMCompleteResponse {
             client: self.context.name.to_string(),
@@ -358,17 +414,14 @@ impl WithChat for GoogleAIClient {
             request_options: self.properties.properties.clone(),
             model: self.properties.model.clone(),
             metadata: LLMCompleteResponseMetadata {
-                baml_is_complete: matches!(
-                    response.candidates[0].finish_reason,
-                    Some(FinishReason::Stop)
-                ),
-                finish_reason: response.candidates[0]
+                baml_is_complete: matches!(candidate.finish_reason, Some(FinishReason::Stop)),
+                finish_reason: candidate
                     .finish_reason
                     .as_ref()
                     .map(|r| serde_json::to_string(r).unwrap_or("".into())),
-                prompt_tokens: response.usage_metadata.prompt_token_count,
-                output_tokens: response.usage_metadata.candidates_token_count,
-                total_tokens: response.usage_metadata.total_token_count,
+                prompt_tokens: usage.and_then(|u| u.prompt_token_count),
+                output_tokens: usage.and_then(|u| u.candidates_token_count),
+                total_tokens: usage.and_then(|u| u.total_token_count),
             },
         })
     }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use baml_types::{BamlMap, BamlValue};
 use internal_baml_core::ir::{repr::IntermediateRepr, ClientWalker};
+use internal_baml_jinja::Tokenizer;
 use internal_llm_client::{AllowedRoleMetadata, ClientProvider, OpenAIClientProviderVariant};
 
 use crate::{
@@ -21,14 +22,15 @@ use super::{
         OrchestratorNodeIterator,
     },
     traits::{
-        WithClient, WithClientProperties, WithPrompt, WithRenderRawCurl, WithRetryPolicy,
-        WithSingleCallable, WithStreamable,
+        WithCandidatesCallable, WithClient, WithClientProperties, WithPrompt, WithRenderRawCurl,
+        WithRetryPolicy, WithSingleCallable, WithStreamable,
     },
     LLMResponse,
 };
 
 mod anthropic;
 mod aws;
+mod credentials;
 mod google;
 mod openai;
 pub(super) mod request;
@@ -103,6 +105,9 @@ impl WithClientProperties for LLMPrimitiveProvider {
     fn allowed_roles(&self) -> Vec<String> {
         match_llm_provider!(self, allowed_roles)
     }
+    fn max_context_tokens(&self) -> Option<usize> {
+        match_llm_provider!(self, max_context_tokens)
+    }
 }
 
 impl TryFrom<(&ClientProperty, &RuntimeContext)> for LLMPrimitiveProvider {
@@ -230,6 +235,17 @@ impl WithSingleCallable for LLMPrimitiveProvider {
     }
 }
 
+impl WithCandidatesCallable for LLMPrimitiveProvider {
+    async fn candidates_call(
+        &self,
+        ctx: &RuntimeContext,
+        prompt: &internal_baml_jinja::RenderedPrompt,
+        n: usize,
+    ) -> Vec<LLMResponse> {
+        match_llm_provider!(self, candidates_call, async, ctx, prompt, n)
+    }
+}
+
 impl WithStreamable for LLMPrimitiveProvider {
     async fn stream(
         &self,
@@ -275,4 +291,10 @@ impl LLMPrimitiveProvider {
     pub fn request_options(&self) -> &BamlMap<String, serde_json::Value> {
         match_llm_provider!(self, request_options)
     }
+
+    /// The tokenizer used to estimate the size of this client's prompts.
+    pub fn tokenizer(&self) -> Tokenizer {
+        let context = match_llm_provider!(self, context);
+        Tokenizer::for_client(&context.provider, context.model.as_deref())
+    }
 }
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::internal::llm_client::ResolveMediaUrls;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use baml_types::{BamlMap, BamlMedia, BamlMediaContent, BamlMediaType};
 use internal_baml_core::ir::ClientWalker;
 use internal_baml_jinja::{ChatMessagePart, RenderContext_Client, RenderedChatMessage};
@@ -17,19 +17,21 @@ use super::properties;
 use super::types::{ChatCompletionResponse, ChatCompletionResponseDelta};
 
 use crate::client_registry::ClientProperty;
+use crate::internal::llm_client::primitive::credentials::{TokenProvider, TokenSource};
 use crate::internal::llm_client::primitive::request::{
-    make_parsed_request, make_request, RequestBuilder,
+    make_parsed_request, make_request, RequestBuilder, WithBodyFields,
 };
 use crate::internal::llm_client::traits::{
     SseResponseTrait, StreamResponse, ToProviderMessage, ToProviderMessageExt,
     WithClientProperties, WithStreamChat,
 };
 use crate::internal::llm_client::{
+    orchestrator::sample_candidates,
     traits::{WithChat, WithClient, WithNoCompletion, WithRetryPolicy},
     LLMResponse, ModelFeatures,
 };
 
-use crate::request::create_client;
+use crate::request::create_client_with_config;
 use crate::RuntimeContext;
 use eventsource_stream::Eventsource;
 use futures::StreamExt;
@@ -44,6 +46,7 @@ pub struct OpenAIClient {
     properties: ResolvedOpenAI,
     // clients
     client: reqwest::Client,
+    credentials: Option<TokenProvider>,
 }
 
 impl WithRetryPolicy for OpenAIClient {
@@ -72,6 +75,10 @@ impl WithClientProperties for OpenAIClient {
     fn supports_streaming(&self) -> bool {
         self.properties.supports_streaming()
     }
+
+    fn max_context_tokens(&self) -> Option<usize> {
+        self.properties.max_context_tokens
+    }
 }
 
 impl WithClient for OpenAIClient {
@@ -186,12 +193,62 @@ impl WithChat for OpenAIClient {
             });
         }
 
-        let usage = response.usage.as_ref();
+        self.choice_to_response(prompt, &response, 0, system_start, instant_start)
+    }
+
+    async fn chat_candidates(
+        &self,
+        ctx: &RuntimeContext,
+        prompt: &[RenderedChatMessage],
+        n: usize,
+    ) -> Vec<LLMResponse> {
+        // Only OpenAI itself and Azure are known to honor `n`; other
+        // OpenAI-compatible servers often ignore it.
+        if n <= 1 || !matches!(self.provider.as_str(), "openai" | "azure") {
+            return sample_candidates(n, |_| self.chat(ctx, prompt)).await;
+        }
+
+        let (response, system_start, instant_start) =
+            match make_parsed_request::<ChatCompletionResponse>(
+                &WithBodyFields::new(self, json!({ "n": n })),
+                either::Either::Right(prompt),
+                false,
+            )
+            .await
+            {
+                Ok(v) => v,
+                Err(e) => return vec![e],
+            };
+
+        let mut responses = (0..response.choices.len().min(n))
+            .map(|i| self.choice_to_response(prompt, &response, i, system_start, instant_start))
+            .collect::<Vec<_>>();
+        if responses.len() < n {
+            let missing = n - responses.len();
+            responses.extend(sample_candidates(missing, |_| self.chat(ctx, prompt)).await);
+        }
+        responses
+    }
+}
+
+impl OpenAIClient {
+    /// Usage covers the whole request, so it's only reported on the first
+    /// choice.
+    fn choice_to_response(
+        &self,
+        prompt: &[RenderedChatMessage],
+        response: &ChatCompletionResponse,
+        index: usize,
+        system_start: web_time::SystemTime,
+        instant_start: web_time::Instant,
+    ) -> LLMResponse {
+        let choice = &response.choices[index];
+        let usage = response.usage.as_ref().filter(|_| index == 0);
 
         LLMResponse::Success(LLMCompleteResponse {
             client: self.context.name.to_string(),
             prompt: internal_baml_jinja::RenderedPrompt::Chat(prompt.to_vec()),
-            content: response.choices[0]
+            content: choice
                 .message
                 .content
                 .as_ref()
@@ -199,17 +256,11 @@ impl WithChat for OpenAIClient {
                 .to_string(),
             start_time: system_start,
             latency: instant_start.elapsed(),
-            model: response.model,
+            model: response.model.clone(),
             request_options: self.properties.properties.clone(),
             metadata: LLMCompleteResponseMetadata {
-                baml_is_complete: match response.choices.get(0) {
-                    Some(c) => c.finish_reason.as_ref().is_some_and(|f| f == "stop"),
-                    None => false,
-                },
-                finish_reason: match response.choices.get(0) {
-                    Some(c) => c.finish_reason.clone(),
-                    None => None,
-                },
+                baml_is_complete: choice.finish_reason.as_ref().is_some_and(|f| f == "stop"),
+                finish_reason: choice.finish_reason.clone(),
                 prompt_tokens: usage.map(|u| u.prompt_tokens),
                 output_tokens: usage.map(|u| u.completion_tokens),
                 total_tokens: usage.map(|u| u.total_tokens),
@@ -254,6 +305,13 @@ impl RequestBuilder for OpenAIClient {
         if let Some(key) = &self.properties.api_key {
             req = req.bearer_auth(key);
         }
+        if let Some(credentials) = &self.credentials {
+            let token = credentials
+                .token(&self.client)
+                .await
+                .context("Failed to get access token")?;
+            req = req.bearer_auth(token);
+        }
 
         // Don't attach BAML creds to localhost requests, i.e. ollama
         if allow_proxy {
@@ -366,7 +424,8 @@ impl SseResponseTrait for OpenAIClient {
                             }
                             inner.model = event.model;
                             inner.metadata.finish_reason = choice.finish_reason.clone();
-                            inner.metadata.baml_is_complete = choice.finish_reason.as_ref().is_some_and(|s| s == "stop");
+                            inner.metadata.baml_is_complete =
+                                choice.finish_reason.as_ref().is_some_and(|s| s == "stop");
                         }
                         inner.latency = instant_start.elapsed();
                         if let Some(usage) = event.usage.as_ref() {
@@ -398,13 +457,18 @@ impl WithStreamChat for OpenAIClient {
 }
 
 macro_rules! make_openai_client {
-    ($client:ident, $properties:ident, $provider:expr, dynamic) => {
+    ($client:ident, $properties:ident, $ctx:ident, $provider:expr, dynamic) => {
         Ok(Self {
             name: $client.name.clone(),
             provider: $provider.into(),
             context: RenderContext_Client {
                 name: $client.name.clone(),
                 provider: $client.provider.to_string(),
+                model: $properties
+                    .properties
+                    .get("model")
+                    .and_then(|m| m.as_str())
+                    .map(str::to_string),
                 default_role: $properties.default_role(),
                 allowed_roles: $properties.allowed_roles(),
             },
@@ -415,18 +479,27 @@ macro_rules! make_openai_client {
                 resolve_media_urls: ResolveMediaUrls::Never,
                 allowed_metadata: $properties.allowed_metadata.clone(),
             },
+            client: create_client_with_config(&$properties.http, $ctx.baml_src_dir())?,
+            credentials: $properties
+                .auth
+                .clone()
+                .map(|auth| TokenProvider::new(TokenSource::ClientCredentials(auth))),
             properties: $properties,
             retry_policy: $client.retry_policy.clone(),
-            client: create_client()?,
         })
     };
-    ($client:ident, $properties:ident, $provider:expr) => {
+    ($client:ident, $properties:ident, $ctx:ident, $provider:expr) => {
         Ok(Self {
             name: $client.name().into(),
             provider: $provider.into(),
             context: RenderContext_Client {
                 name: $client.name().into(),
                 provider: $client.elem().provider.to_string(),
+                model: $properties
+                    .properties
+                    .get("model")
+                    .and_then(|m| m.as_str())
+                    .map(str::to_string),
                 default_role: $properties.default_role(),
                 allowed_roles: $properties.allowed_roles(),
             },
@@ -437,13 +510,17 @@ macro_rules! make_openai_client {
                 resolve_media_urls: ResolveMediaUrls::Never,
                 allowed_metadata: $properties.allowed_metadata.clone(),
             },
+            client: create_client_with_config(&$properties.http, $ctx.baml_src_dir())?,
+            credentials: $properties
+                .auth
+                .clone()
+                .map(|auth| TokenProvider::new(TokenSource::ClientCredentials(auth))),
             properties: $properties,
             retry_policy: $client
                 .elem()
                 .retry_policy_id
                 .as_ref()
                 .map(|s| s.to_string()),
-            client: create_client()?,
         })
     };
 }
@@ -452,31 +529,31 @@ impl OpenAIClient {
     pub fn new(client: &ClientWalker, ctx: &RuntimeContext) -> Result<OpenAIClient> {
         let properties =
             properties::resolve_properties(&client.elem().provider, client.options(), ctx)?;
-        make_openai_client!(client, properties, "openai")
+        make_openai_client!(client, properties, ctx, "openai")
     }
 
     pub fn new_generic(client: &ClientWalker, ctx: &RuntimeContext) -> Result<OpenAIClient> {
         let properties =
             properties::resolve_properties(&client.elem().provider, client.options(), ctx)?;
-        make_openai_client!(client, properties, "openai-generic")
+        make_openai_client!(client, properties, ctx, "openai-generic")
     }
 
     pub fn new_ollama(client: &ClientWalker, ctx: &RuntimeContext) -> Result<OpenAIClient> {
         let properties =
             properties::resolve_properties(&client.elem().provider, client.options(), ctx)?;
-        make_openai_client!(client, properties, "ollama")
+        make_openai_client!(client, properties, ctx, "ollama")
     }
 
     pub fn new_azure(client: &ClientWalker, ctx: &RuntimeContext) -> Result<OpenAIClient> {
         let properties =
             properties::resolve_properties(&client.elem().provider, client.options(), ctx)?;
-        make_openai_client!(client, properties, "azure")
+        make_openai_client!(client, properties, ctx, "azure")
     }
 
     pub fn dynamic_new(client: &ClientProperty, ctx: &RuntimeContext) -> Result<OpenAIClient> {
         let properties =
             properties::resolve_properties(&client.provider, &client.unresolved_options()?, ctx)?;
-        make_openai_client!(client, properties, "openai", dynamic)
+        make_openai_client!(client, properties, ctx, "openai", dynamic)
     }
 
     pub fn dynamic_new_generic(
@@ -485,7 +562,7 @@ impl OpenAIClient {
     ) -> Result<OpenAIClient> {
         let properties =
             properties::resolve_properties(&client.provider, &client.unresolved_options()?, ctx)?;
-        make_openai_client!(client, properties, "openai-generic", dynamic)
+        make_openai_client!(client, properties, ctx, "openai-generic", dynamic)
     }
 
     pub fn dynamic_new_ollama(
@@ -494,7 +571,7 @@ impl OpenAIClient {
     ) -> Result<OpenAIClient> {
         let properties =
             properties::resolve_properties(&client.provider, &client.unresolved_options()?, ctx)?;
-        make_openai_client!(client, properties, "ollama", dynamic)
+        make_openai_client!(client, properties, ctx, "ollama", dynamic)
     }
 
     pub fn dynamic_new_azure(
@@ -503,7 +580,7 @@ impl OpenAIClient {
     ) -> Result<OpenAIClient> {
         let properties =
             properties::resolve_properties(&client.provider, &client.unresolved_options()?, ctx)?;
-        make_openai_client!(client, properties, "azure", dynamic)
+        make_openai_client!(client, properties, ctx, "azure", dynamic)
     }
 }
 
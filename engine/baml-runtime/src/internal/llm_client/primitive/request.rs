@@ -22,6 +22,82 @@ pub trait RequestBuilder {
     fn http_client(&self) -> &reqwest::Client;
 }
 
+/// Wraps a client so every request it builds has `fields` deep-merged into
+/// its JSON body. Used to ask providers for things the client's own options
+/// don't cover, like several candidates per request.
+pub struct WithBodyFields<'a, C> {
+    client: &'a C,
+    fields: serde_json::Value,
+}
+
+impl<'a, C> WithBodyFields<'a, C> {
+    pub fn new(client: &'a C, fields: serde_json::Value) -> Self {
+        Self { client, fields }
+    }
+}
+
+impl<C: WithClient> WithClient for WithBodyFields<'_, C> {
+    fn context(&self) -> &internal_baml_jinja::RenderContext_Client {
+        self.client.context()
+    }
+
+    fn model_features(&self) -> &crate::internal::llm_client::ModelFeatures {
+        self.client.model_features()
+    }
+}
+
+impl<C: RequestBuilder> RequestBuilder for WithBodyFields<'_, C> {
+    async fn build_request(
+        &self,
+        prompt: either::Either<&String, &[RenderedChatMessage]>,
+        allow_proxy: bool,
+        stream: bool,
+    ) -> Result<reqwest::RequestBuilder> {
+        let req = self
+            .client
+            .build_request(prompt, allow_proxy, stream)
+            .await?
+            .build()?;
+
+        let mut body = match req.body().and_then(|b| b.as_bytes()) {
+            Some(bytes) => serde_json::from_slice::<serde_json::Value>(bytes)
+                .context("Request body is not JSON")?,
+            None => serde_json::Value::Object(Default::default()),
+        };
+        merge_json(&mut body, &self.fields);
+
+        Ok(self
+            .http_client()
+            .request(req.method().clone(), req.url().clone())
+            .headers(req.headers().clone())
+            .json(&body))
+    }
+
+    fn request_options(&self) -> &BamlMap<String, serde_json::Value> {
+        self.client.request_options()
+    }
+
+    fn http_client(&self) -> &reqwest::Client {
+        self.client.http_client()
+    }
+}
+
+fn merge_json(target: &mut serde_json::Value, fields: &serde_json::Value) {
+    match (target, fields) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(fields)) => {
+            for (key, value) in fields {
+                match target.get_mut(key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (target, fields) => *target = fields.clone(),
+    }
+}
+
 fn to_prompt(
     prompt: either::Either<&String, &[RenderedChatMessage]>,
 ) -> internal_baml_jinja::RenderedPrompt {
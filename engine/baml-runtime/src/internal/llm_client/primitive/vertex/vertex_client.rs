@@ -3,12 +3,11 @@ use crate::internal::llm_client::traits::{
     ToProviderMessage, ToProviderMessageExt, WithClientProperties,
 };
 use crate::internal::llm_client::ResolveMediaUrls;
-#[cfg(target_arch = "wasm32")]
-use crate::internal::wasm_jwt::{encode_jwt, JwtError};
 use crate::RuntimeContext;
 use crate::{
     internal::llm_client::{
         primitive::{
+            credentials::{TokenProvider, TokenSource},
             request::{make_parsed_request, make_request, RequestBuilder},
             vertex::types::{FinishReason, VertexResponse},
         },
@@ -19,20 +18,15 @@ use crate::{
         ErrorCode, LLMCompleteResponse, LLMCompleteResponseMetadata, LLMErrorResponse, LLMResponse,
         ModelFeatures,
     },
-    request::create_client,
+    request::create_client_with_config,
 };
 use anyhow::{Context, Result};
-use chrono::{Duration, Utc};
 use futures::StreamExt;
-use internal_llm_client::vertex::{ResolvedServiceAccountDetails, ResolvedVertex, ServiceAccount};
+use internal_llm_client::vertex::{ResolvedServiceAccountDetails, ResolvedVertex};
 use internal_llm_client::{
     AllowedRoleMetadata, ClientProvider, ResolvedClientProperty, UnresolvedClientProperty,
 };
 #[cfg(not(target_arch = "wasm32"))]
-use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
-#[cfg(not(target_arch = "wasm32"))]
 use std::fs::File;
 #[cfg(not(target_arch = "wasm32"))]
 use std::io::BufReader;
@@ -52,32 +46,7 @@ pub struct VertexClient {
     pub context: RenderContext_Client,
     pub features: ModelFeatures,
     properties: ResolvedVertex,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct Claims {
-    iss: String,
-    scope: String,
-    aud: String,
-    exp: i64,
-    iat: i64,
-}
-
-// This is currently hardcoded, but we could make it a property if we wanted
-// https://developers.google.com/identity/protocols/oauth2/scopes
-const DEFAULT_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
-
-impl Claims {
-    fn from_service_account(service_account: &ServiceAccount) -> Claims {
-        let now = Utc::now();
-        Claims {
-            iss: service_account.client_email.clone(),
-            scope: DEFAULT_SCOPE.to_string(),
-            aud: service_account.token_uri.clone(),
-            exp: (now + Duration::hours(1)).timestamp(),
-            iat: now.timestamp(),
-        }
-    }
+    credentials: TokenProvider,
 }
 
 fn resolve_properties(
@@ -122,6 +91,9 @@ impl WithClientProperties for VertexClient {
     fn allowed_roles(&self) -> Vec<String> {
         self.properties.allowed_roles()
     }
+    fn max_context_tokens(&self) -> Option<usize> {
+        self.properties.max_context_tokens
+    }
 }
 
 impl WithClient for VertexClient {
@@ -250,6 +222,7 @@ impl VertexClient {
             context: RenderContext_Client {
                 name: client.name().into(),
                 provider: client.elem().provider.to_string(),
+                model: Some(properties.model.clone()),
                 default_role: properties.default_role(),
                 allowed_roles: properties.allowed_roles(),
             },
@@ -265,7 +238,8 @@ impl VertexClient {
                 .retry_policy_id
                 .as_ref()
                 .map(|s| s.to_string()),
-            client: create_client()?,
+            client: create_client_with_config(&properties.http, ctx.baml_src_dir())?,
+            credentials: token_provider(&properties.authorization),
             properties,
         })
     }
@@ -278,6 +252,7 @@ impl VertexClient {
             context: RenderContext_Client {
                 name: client.name.clone(),
                 provider: client.provider.to_string(),
+                model: Some(properties.model.clone()),
                 default_role: properties.default_role(),
                 allowed_roles: properties.allowed_roles(),
             },
@@ -289,50 +264,22 @@ impl VertexClient {
                 allowed_metadata: properties.allowed_metadata.clone(),
             },
             retry_policy: client.retry_policy.clone(),
-            client: create_client()?,
+            client: create_client_with_config(&properties.http, ctx.baml_src_dir())?,
+            credentials: token_provider(&properties.authorization),
             properties,
         })
     }
 }
 
-async fn get_access_token(service_account: &ServiceAccount) -> Result<String> {
-    // Create the JWT
-    let claims = Claims::from_service_account(service_account);
-
-    #[cfg(not(target_arch = "wasm32"))]
-    let jwt = encode(
-        &Header::new(Algorithm::RS256),
-        &claims,
-        &EncodingKey::from_rsa_pem(service_account.private_key.as_bytes())?,
-    )?;
-
-    #[cfg(target_arch = "wasm32")]
-    let jwt = encode_jwt(&serde_json::to_value(claims)?, &service_account.private_key)
-        .await
-        .map_err(|e| anyhow::anyhow!(format!("{e:?}")))?;
-
-    // Make the token request
-    let client = reqwest::Client::new();
-    let params = [
-        ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
-        ("assertion", &jwt),
-    ];
-    let res: Value = client
-        .post(&service_account.token_uri)
-        .form(&params)
-        .send()
-        .await?
-        .json()
-        .await?;
-
-    Ok(res
-        .as_object()
-        .context("Token exchange did not return a JSON object")?
-        .get("access_token")
-        .context("Access token not found in response")?
-        .as_str()
-        .context("Access token is not a string")?
-        .to_string())
+fn token_provider(authorization: &ResolvedServiceAccountDetails) -> TokenProvider {
+    TokenProvider::new(match authorization {
+        ResolvedServiceAccountDetails::RawAuthorizationHeader(token) => {
+            TokenSource::Static(token.clone())
+        }
+        ResolvedServiceAccountDetails::Json(service_account) => {
+            TokenSource::ServiceAccount(service_account.clone())
+        }
+    })
 }
 
 impl RequestBuilder for VertexClient {
@@ -365,12 +312,11 @@ impl RequestBuilder for VertexClient {
             _ => self.client.post(baml_original_url),
         };
 
-        let access_token = match &self.properties.authorization {
-            ResolvedServiceAccountDetails::RawAuthorizationHeader(token) => token.to_string(),
-            ResolvedServiceAccountDetails::Json(token) => get_access_token(token)
-                .await
-                .context("Failed to get access token")?,
-        };
+        let access_token = self
+            .credentials
+            .token(&self.client)
+            .await
+            .context("Failed to get access token")?;
 
         req = req.header("Authorization", format!("Bearer {}", access_token));
 
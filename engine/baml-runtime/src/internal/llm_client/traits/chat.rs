@@ -1,7 +1,10 @@
 use anyhow::Result;
 use internal_baml_jinja::{ChatOptions, RenderedChatMessage};
 
-use crate::{internal::llm_client::LLMResponse, RuntimeContext};
+use crate::{
+    internal::llm_client::{orchestrator::sample_candidates, LLMResponse},
+    RuntimeContext,
+};
 
 use super::StreamResponse;
 
@@ -21,6 +24,19 @@ where
 pub trait WithChat: Sync + Send + WithChatOptions {
     #[allow(async_fn_in_trait)]
     async fn chat(&self, ctx: &RuntimeContext, prompt: &[RenderedChatMessage]) -> LLMResponse;
+
+    /// Samples `n` responses to the same prompt. By default this sends `n`
+    /// requests concurrently; providers that can return several choices from
+    /// one request override it.
+    #[allow(async_fn_in_trait)]
+    async fn chat_candidates(
+        &self,
+        ctx: &RuntimeContext,
+        prompt: &[RenderedChatMessage],
+        n: usize,
+    ) -> Vec<LLMResponse> {
+        sample_candidates(n, |_| self.chat(ctx, prompt)).await
+    }
 }
 
 pub trait WithStreamChat: Sync + Send {
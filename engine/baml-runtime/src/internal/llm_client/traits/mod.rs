@@ -11,8 +11,10 @@ pub use self::{
     chat::{WithChat, WithStreamChat},
     completion::{WithCompletion, WithNoCompletion, WithStreamCompletion},
 };
-use super::{primitive::request::RequestBuilder, LLMResponse, ModelFeatures};
-use crate::{internal::llm_client::ResolveMediaUrls, RenderCurlSettings};
+use super::{
+    orchestrator::sample_candidates, primitive::request::RequestBuilder, LLMResponse, ModelFeatures,
+};
+use crate::{internal::llm_client::ResolveMediaUrls, RenderCurlSettings, REDACTED_SECRET};
 use crate::{internal::prompt_renderer::PromptRenderer, RuntimeContext};
 use baml_types::{BamlMedia, BamlMediaContent, BamlMediaType, BamlValue, MediaBase64, MediaUrl};
 use base64::{prelude::BASE64_STANDARD, Engine};
@@ -20,7 +22,7 @@ use futures::stream::StreamExt;
 use infer;
 use internal_baml_core::ir::repr::IntermediateRepr;
 use internal_baml_jinja::{ChatMessagePart, RenderedChatMessage};
-use internal_baml_jinja::{RenderContext_Client, RenderedPrompt};
+use internal_baml_jinja::{RenderContext_Client, RenderedPrompt, Tokenizer};
 
 use shell_escape::escape;
 use std::borrow::Cow;
@@ -39,6 +41,7 @@ pub trait WithClientProperties {
     fn finish_reason_filter(&self) -> &FinishReasonFilter;
     fn default_role(&self) -> String;
     fn allowed_roles(&self) -> Vec<String>;
+    fn max_context_tokens(&self) -> Option<usize>;
 }
 
 pub trait WithSingleCallable {
@@ -46,6 +49,17 @@ pub trait WithSingleCallable {
     async fn single_call(&self, ctx: &RuntimeContext, prompt: &RenderedPrompt) -> LLMResponse;
 }
 
+pub trait WithCandidatesCallable {
+    /// Samples `n` responses to the same prompt, one per candidate.
+    #[allow(async_fn_in_trait)]
+    async fn candidates_call(
+        &self,
+        ctx: &RuntimeContext,
+        prompt: &RenderedPrompt,
+        n: usize,
+    ) -> Vec<LLMResponse>;
+}
+
 pub trait WithClient {
     fn context(&self) -> &RenderContext_Client;
 
@@ -151,6 +165,9 @@ where
     #[allow(async_fn_in_trait)]
     async fn single_call(&self, ctx: &RuntimeContext, prompt: &RenderedPrompt) -> LLMResponse {
         log::warn!("debug single_call start: {:?}", prompt);
+        if let Err(e) = check_context_window(self, prompt) {
+            return e;
+        }
         if let RenderedPrompt::Chat(chat) = &prompt {
             match process_media_urls(
                 self.model_features().resolve_media_urls,
@@ -175,6 +192,69 @@ where
     }
 }
 
+impl<T> WithCandidatesCallable for T
+where
+    T: WithClient + WithChat + WithCompletion + WithClientProperties,
+{
+    #[allow(async_fn_in_trait)]
+    async fn candidates_call(
+        &self,
+        ctx: &RuntimeContext,
+        prompt: &RenderedPrompt,
+        n: usize,
+    ) -> Vec<LLMResponse> {
+        if let Err(e) = check_context_window(self, prompt) {
+            return vec![e];
+        }
+
+        match prompt {
+            RenderedPrompt::Chat(chat) => match process_media_urls(
+                self.model_features().resolve_media_urls,
+                true,
+                None,
+                ctx,
+                chat,
+            )
+            .await
+            {
+                Ok(messages) => self.chat_candidates(ctx, &messages, n).await,
+                Err(e) => vec![LLMResponse::InternalFailure(format!(
+                    "Error occurred:\n\n{:?}",
+                    e
+                ))],
+            },
+            RenderedPrompt::Completion(p) => {
+                sample_candidates(n, |_| self.completion(ctx, p)).await
+            }
+        }
+    }
+}
+
+/// Fails before the request is sent if the prompt doesn't fit in the
+/// client's `max_context_tokens`.
+fn check_context_window<T>(client: &T, prompt: &RenderedPrompt) -> Result<(), LLMResponse>
+where
+    T: WithClient + WithClientProperties,
+{
+    let Some(max_context_tokens) = client.max_context_tokens() else {
+        return Ok(());
+    };
+
+    let context = client.context();
+    let estimate =
+        Tokenizer::for_client(&context.provider, context.model.as_deref()).estimate(prompt);
+    if estimate.tokens > max_context_tokens {
+        return Err(LLMResponse::UserFailure(format!(
+            "The prompt is {}{} tokens, which exceeds the max_context_tokens of client {} ({})",
+            if estimate.exact { "" } else { "about " },
+            estimate.tokens,
+            context.name,
+            max_context_tokens
+        )));
+    }
+    Ok(())
+}
+
 fn escape_single_quotes(s: &str) -> String {
     escape(Cow::Borrowed(s)).to_string()
 }
@@ -309,7 +389,7 @@ where
             .unwrap_or_default(); // Add this line to handle the Option
         let request_str = to_curl_command(&url_str, "POST", request.headers(), body);
 
-        Ok(request_str)
+        Ok(ctx.secrets().redact(&request_str, REDACTED_SECRET))
     }
 }
 
@@ -348,6 +428,8 @@ where
 {
     #[allow(async_fn_in_trait)]
     async fn stream(&self, ctx: &RuntimeContext, prompt: &RenderedPrompt) -> StreamResponse {
+        check_context_window(self, prompt)?;
+
         let prompt = {
             if let RenderedPrompt::Chat(ref chat) = prompt {
                 match process_media_urls(
@@ -1,16 +1,19 @@
 mod render_output_format;
+use std::sync::Mutex;
+
 use internal_llm_client::ClientSpec;
-use jsonish::BamlValueWithFlags;
+use jsonish::{BamlValueWithFlags, ResponseFormat};
 use render_output_format::render_output_format;
 
 use anyhow::Result;
 use baml_types::{BamlValue, FieldType};
 use internal_baml_core::{
     error_unsupported,
+    internal_baml_parser_database::Candidates,
     ir::{repr::IntermediateRepr, FunctionWalker, IRHelper},
 };
 use internal_baml_jinja::{
-    types::OutputFormatContent, RenderContext, RenderContext_Client, RenderedPrompt,
+    types::OutputFormatContent, OutputStyle, RenderContext, RenderContext_Client, RenderedPrompt,
     TemplateStringMacro,
 };
 
@@ -21,6 +24,9 @@ pub struct PromptRenderer {
     client_spec: ClientSpec,
     output_defs: OutputFormatContent,
     output_type: FieldType,
+    /// Set from the style the last rendered prompt asked for the output in.
+    response_format: Mutex<ResponseFormat>,
+    candidates: Option<Candidates>,
 }
 
 impl PromptRenderer {
@@ -42,6 +48,8 @@ impl PromptRenderer {
             },
             output_defs: render_output_format(ir, ctx, &func_v2.output)?,
             output_type: func_v2.output.clone(),
+            response_format: Mutex::new(ResponseFormat::Json),
+            candidates: config.candidates,
         })
     }
 
@@ -49,12 +57,21 @@ impl PromptRenderer {
         &self.client_spec
     }
 
+    /// How many responses to sample per call, and how to pick one. Only used
+    /// by non-streaming calls.
+    pub fn candidates(&self) -> Option<&Candidates> {
+        self.candidates.as_ref()
+    }
+
     pub fn parse(&self, raw_string: &str, allow_partials: bool) -> Result<BamlValueWithFlags> {
-        jsonish::from_str(
+        jsonish::from_str_with_format(
             &self.output_defs,
             &self.output_type,
             raw_string,
             allow_partials,
+            self.response_format
+                .lock()
+                .map_or(ResponseFormat::Json, |format| *format),
         )
     }
 
@@ -73,7 +90,7 @@ impl PromptRenderer {
             error_unsupported!("function", self.function_name, "no valid prompt found")
         };
 
-        internal_baml_jinja::render_prompt(
+        let (prompt, style) = internal_baml_jinja::render_prompt_with_output_style(
             &config.prompt_template,
             params,
             RenderContext {
@@ -94,6 +111,15 @@ impl PromptRenderer {
                 .collect::<Vec<_>>(),
             ir,
             ctx.env_vars(),
-        )
+        )?;
+
+        if let Ok(mut response_format) = self.response_format.lock() {
+            *response_format = match style {
+                Some(OutputStyle::Yaml) => ResponseFormat::Yaml,
+                Some(OutputStyle::Xml) => ResponseFormat::Xml,
+                _ => ResponseFormat::Json,
+            };
+        }
+        Ok(prompt)
     }
 }
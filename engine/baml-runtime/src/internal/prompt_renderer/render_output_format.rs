@@ -6,7 +6,7 @@ use indexmap::IndexSet;
 use internal_baml_core::ir::{
     repr::IntermediateRepr, ClassWalker, EnumWalker, FieldType, IRHelper,
 };
-use internal_baml_jinja::types::{Class, Enum, Name, OutputFormatContent};
+use internal_baml_jinja::types::{Class, ClassStreamingBehavior, Enum, Name, OutputFormatContent};
 
 use crate::{
     runtime_context::{RuntimeClassOverride, RuntimeEnumOverride},
@@ -97,6 +97,17 @@ fn find_new_class_field(
     Ok((name, field_overrides.0.clone(), desc))
 }
 
+fn class_streaming_behavior(walker: &ClassWalker<'_>) -> ClassStreamingBehavior {
+    ClassStreamingBehavior {
+        class: walker.streaming_behavior(),
+        fields: walker
+            .walk_fields()
+            .map(|f| (f.name().to_string(), f.streaming_behavior()))
+            .filter(|(_, behavior)| !behavior.is_default())
+            .collect(),
+    }
+}
+
 fn find_existing_class_field(
     class_name: &str,
     field_name: &str,
@@ -359,10 +370,16 @@ fn relevant_data_models<'a>(
                         }
                     }
 
+                    let streaming_behavior = walker
+                        .as_ref()
+                        .map(class_streaming_behavior)
+                        .unwrap_or_default();
+
                     classes.push(Class {
                         name: Name::new_with_alias(cls.to_string(), alias.value()),
                         fields,
                         constraints,
+                        streaming_behavior,
                     });
                 } else {
                     recursive_classes.insert(cls.to_owned());
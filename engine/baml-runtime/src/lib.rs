@@ -10,9 +10,11 @@ pub mod cli;
 pub mod client_registry;
 pub mod constraints;
 pub mod errors;
+pub mod eval;
 pub mod request;
 mod runtime;
 pub mod runtime_interface;
+pub mod test_dataset;
 pub mod tracing;
 pub mod type_builder;
 mod types;
@@ -44,6 +46,7 @@ pub use runtime_context::BamlSrcReader;
 use runtime_interface::ExperimentalTracingInterface;
 use runtime_interface::RuntimeConstructor;
 use runtime_interface::RuntimeInterface;
+use test_dataset::DatasetRow;
 use tracing::{BamlTracer, TracingSpan};
 use type_builder::TypeBuilder;
 pub use types::*;
@@ -51,7 +54,7 @@ pub use types::*;
 use clap::Parser;
 
 #[cfg(feature = "internal")]
-pub use internal_baml_jinja::{ChatMessagePart, RenderedPrompt};
+pub use internal_baml_jinja::{ChatMessagePart, RenderedPrompt, TokenEstimate};
 #[cfg(feature = "internal")]
 pub use runtime_interface::InternalRuntimeInterface;
 
@@ -74,6 +77,7 @@ pub struct BamlRuntime {
     pub(crate) inner: InternalBamlRuntime,
     tracer: Arc<BamlTracer>,
     env_vars: HashMap<String, String>,
+    secrets: Arc<SecretStore>,
     #[cfg(not(target_arch = "wasm32"))]
     pub async_runtime: Arc<tokio::runtime::Runtime>,
 }
@@ -83,6 +87,12 @@ impl BamlRuntime {
         &self.env_vars
     }
 
+    /// Resolves the secret references in environment variables, see
+    /// [`SecretStore`].
+    pub fn secrets(&self) -> &Arc<SecretStore> {
+        &self.secrets
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     fn get_tokio_singleton() -> Result<Arc<tokio::runtime::Runtime>> {
         match TOKIO_SINGLETON.get_or_init(|| tokio::runtime::Runtime::new().map(Arc::new)) {
@@ -136,10 +146,13 @@ impl BamlRuntime {
             .iter()
             .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
             .collect();
+        let secrets = Arc::new(SecretStore::from_env_vars(&copy)?);
+        let inner = InternalBamlRuntime::from_directory(&path)?;
         Ok(BamlRuntime {
-            inner: InternalBamlRuntime::from_directory(&path)?,
-            tracer: BamlTracer::new(None, env_vars.into_iter())?.into(),
+            tracer: BamlTracer::new(None, env_vars.into_iter(), inner.ir(), &secrets)?.into(),
+            inner,
             env_vars: copy,
+            secrets,
             #[cfg(not(target_arch = "wasm32"))]
             async_runtime: Self::get_tokio_singleton()?,
         })
@@ -154,10 +167,13 @@ impl BamlRuntime {
             .iter()
             .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
             .collect();
+        let secrets = Arc::new(SecretStore::from_env_vars(&copy)?);
+        let inner = InternalBamlRuntime::from_file_content(root_path, files)?;
         Ok(BamlRuntime {
-            inner: InternalBamlRuntime::from_file_content(root_path, files)?,
-            tracer: BamlTracer::new(None, env_vars.into_iter())?.into(),
+            tracer: BamlTracer::new(None, env_vars.into_iter(), inner.ir(), &secrets)?.into(),
+            inner,
             env_vars: copy,
+            secrets,
             #[cfg(not(target_arch = "wasm32"))]
             async_runtime: Self::get_tokio_singleton()?,
         })
@@ -173,7 +189,9 @@ impl BamlRuntime {
         language: BamlValue,
         baml_src_reader: BamlSrcReader,
     ) -> RuntimeContextManager {
-        let ctx = RuntimeContextManager::new_from_env_vars(self.env_vars.clone(), baml_src_reader);
+        let ctx = RuntimeContextManager::new_from_env_vars(self.env_vars.clone(), baml_src_reader)
+            .with_secrets(self.secrets.clone())
+            .with_baml_src_dir(self.inner.diagnostics().root_path.clone());
         let tags: HashMap<String, BamlValue> = [("baml.language", language)]
             .into_iter()
             .map(|(k, v)| (k.to_string(), v))
@@ -191,9 +209,24 @@ impl BamlRuntime {
         ctx: &RuntimeContext,
         strict: bool,
     ) -> Result<(BamlMap<String, BamlValue>, Vec<Constraint>)> {
-        let params = self
-            .inner
-            .get_test_params(function_name, test_name, ctx, strict)?;
+        self.get_test_row_params_and_constraints(function_name, test_name, None, ctx, strict)
+    }
+
+    pub(crate) fn get_test_row_params_and_constraints(
+        &self,
+        function_name: &str,
+        test_name: &str,
+        row: Option<&DatasetRow>,
+        ctx: &RuntimeContext,
+        strict: bool,
+    ) -> Result<(BamlMap<String, BamlValue>, Vec<Constraint>)> {
+        let params = self.inner.get_test_params(
+            function_name,
+            test_name,
+            ctx,
+            strict,
+            row.map(|row| &row.args),
+        )?;
         let constraints = self
             .inner
             .get_test_constraints(function_name, test_name, ctx)?;
@@ -212,6 +245,25 @@ impl BamlRuntime {
         Ok(params)
     }
 
+    /// The rows of the dataset of a test, or `None` if the test has no
+    /// dataset. Each row is run with [`BamlRuntime::run_test_row`].
+    pub async fn test_dataset_rows(
+        &self,
+        function_name: &str,
+        test_name: &str,
+        ctx: &RuntimeContextManager,
+    ) -> Result<Option<Vec<DatasetRow>>> {
+        let func = self.inner.ir().find_function(function_name)?;
+        let test = self.inner.ir().find_test(&func, test_name)?;
+        let Some(dataset) = test.dataset() else {
+            return Ok(None);
+        };
+        let rctx = ctx.create_ctx(None, None)?;
+        test_dataset::load_rows(dataset, func.inputs(), func.output(), &rctx.baml_src)
+            .await
+            .map(Some)
+    }
+
     pub async fn run_test<F>(
         &self,
         function_name: &str,
@@ -219,16 +271,40 @@ impl BamlRuntime {
         ctx: &RuntimeContextManager,
         on_event: Option<F>,
     ) -> (Result<TestResponse>, Option<uuid::Uuid>)
+    where
+        F: Fn(FunctionResult),
+    {
+        self.run_test_row(function_name, test_name, None, ctx, None, on_event)
+            .await
+    }
+
+    /// Runs a test with the arguments and expected output of a row of its
+    /// dataset. `cb` overrides the clients of the function, to compare clients
+    /// in evals.
+    pub async fn run_test_row<F>(
+        &self,
+        function_name: &str,
+        test_name: &str,
+        row: Option<&DatasetRow>,
+        ctx: &RuntimeContextManager,
+        cb: Option<&ClientRegistry>,
+        on_event: Option<F>,
+    ) -> (Result<TestResponse>, Option<uuid::Uuid>)
     where
         F: Fn(FunctionResult),
     {
         let span = self.tracer.start_span(test_name, ctx, &Default::default());
 
         let run_to_response = || async {
-            let rctx = ctx.create_ctx(None, None)?;
-            let (params, constraints) =
-                self.get_test_params_and_constraints(function_name, test_name, &rctx, true)?;
-            let rctx_stream = ctx.create_ctx(None, None)?;
+            let rctx = ctx.create_ctx(None, cb)?;
+            let (params, constraints) = self.get_test_row_params_and_constraints(
+                function_name,
+                test_name,
+                row,
+                &rctx,
+                true,
+            )?;
+            let rctx_stream = ctx.create_ctx(None, cb)?;
             let mut stream = self.inner.stream_function_impl(
                 function_name.into(),
                 &params,
@@ -237,7 +313,7 @@ impl BamlRuntime {
                 #[cfg(not(target_arch = "wasm32"))]
                 self.async_runtime.clone(),
             )?;
-            let (response_res, span_uuid) = stream.run(on_event, ctx, None, None).await;
+            let (response_res, span_uuid) = stream.run(on_event, ctx, None, cb).await;
             let res = response_res?;
             let (_, llm_resp, _, val) = res
                 .event_chain()
@@ -259,9 +335,13 @@ impl BamlRuntime {
                 TestConstraintsResult::empty()
             } else {
                 match val {
-                    Some(Ok(value)) => {
-                        evaluate_test_constraints(&params, value, complete_resp, constraints)
-                    }
+                    Some(Ok(value)) => evaluate_test_constraints(
+                        &params,
+                        value,
+                        complete_resp,
+                        row.and_then(|row| row.expected.as_ref()),
+                        constraints,
+                    ),
                     _ => TestConstraintsResult::empty(),
                 }
             };
@@ -270,6 +350,7 @@ impl BamlRuntime {
                 function_response: res,
                 function_span: span_uuid,
                 constraints_result: test_constraints_result,
+                params,
             })
         };
 
@@ -592,7 +673,45 @@ pub fn baml_src_files(dir: &std::path::PathBuf) -> Result<Vec<PathBuf>> {
         ));
     }
 
-    let src_files = walkdir::WalkDir::new(dir)
+    let mut src_files = files_in(dir, &VALID_EXTENSIONS);
+
+    // Packages imported with `import "../shared/baml_src" as shared` are part
+    // of the project. Packages can import packages too.
+    let mut seen = src_files
+        .iter()
+        .map(|f| f.canonicalize().unwrap_or_else(|_| f.clone()))
+        .collect::<std::collections::HashSet<_>>();
+    let mut idx = 0;
+    while idx < src_files.len() {
+        for package in imported_dirs(&src_files[idx]) {
+            if !package.is_dir() {
+                anyhow::bail!(
+                    "{package:#?} is imported by {:#?} but is not a directory",
+                    src_files[idx]
+                );
+            }
+            for file in files_in(&package, &["baml"]) {
+                if seen.insert(file.canonicalize().unwrap_or_else(|_| file.clone())) {
+                    src_files.push(file);
+                }
+            }
+        }
+        idx += 1;
+    }
+
+    if !src_files
+        .iter()
+        .any(|f| f.extension() == Some("baml".as_ref()))
+    {
+        anyhow::bail!("no .baml files found in {dir:#?}");
+    }
+
+    Ok(src_files)
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn files_in(dir: &std::path::Path, extensions: &[&str]) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(dir)
         .into_iter()
         .filter_map(|e| match e {
             Ok(e) => Some(e),
@@ -609,17 +728,32 @@ pub fn baml_src_files(dir: &std::path::PathBuf) -> Result<Vec<PathBuf>> {
             let Some(ext) = ext.to_str() else {
                 return false;
             };
-            VALID_EXTENSIONS.contains(&ext)
+            extensions.contains(&ext)
         })
         .map(|e| e.path().to_path_buf())
-        .collect::<Vec<_>>();
+        .collect()
+}
 
-    if !src_files
-        .iter()
-        .any(|f| f.extension() == Some("baml".as_ref()))
-    {
-        anyhow::bail!("no .baml files found in {dir:#?}");
+/// The directories a `.baml` file imports, relative to the file. Files that
+/// don't parse import nothing: their errors are reported when the project
+/// is validated.
+#[cfg(not(target_arch = "wasm32"))]
+fn imported_dirs(file: &std::path::Path) -> Vec<PathBuf> {
+    if file.extension() != Some("baml".as_ref()) {
+        return vec![];
+    }
+    let Ok(text) = std::fs::read_to_string(file) else {
+        return vec![];
+    };
+    let source =
+        internal_baml_core::internal_baml_diagnostics::SourceFile::from((file.to_path_buf(), text));
+    let base = file.parent().unwrap_or_else(|| std::path::Path::new(""));
+    match internal_baml_core::internal_baml_schema_ast::parse_schema(base, &source) {
+        Ok((ast, _)) => ast
+            .imports
+            .iter()
+            .map(|import| base.join(&import.path))
+            .collect(),
+        Err(_) => vec![],
     }
-
-    Ok(src_files)
 }
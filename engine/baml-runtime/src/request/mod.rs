@@ -1,4 +1,6 @@
 use anyhow::{Context, Result};
+use internal_llm_client::HttpConfig;
+use std::path::Path;
 use web_time::Duration;
 
 fn builder() -> reqwest::ClientBuilder {
@@ -27,6 +29,76 @@ pub fn create_client() -> Result<reqwest::Client> {
     builder().build().context("Failed to create reqwest client")
 }
 
+/// Creates the client an LLM provider sends its requests with, honoring the
+/// provider's `http` options. Relative certificate paths are resolved against
+/// `baml_src_dir`. Browsers manage proxies, certificates and connections
+/// themselves, so the options are ignored in wasm.
+pub fn create_client_with_config(
+    config: &HttpConfig,
+    baml_src_dir: Option<&Path>,
+) -> Result<reqwest::Client> {
+    cfg_if::cfg_if! {
+        if #[cfg(target_arch = "wasm32")] {
+            let _ = (config, baml_src_dir);
+            create_client()
+        } else {
+            let resolve = |path: &str| match baml_src_dir {
+                Some(dir) => dir.join(path),
+                None => Path::new(path).to_path_buf(),
+            };
+            let mut cb = builder();
+
+            if let Some(proxy) = &config.proxy {
+                cb = cb.proxy(
+                    reqwest::Proxy::all(proxy)
+                        .with_context(|| format!("Invalid http.proxy: {proxy}"))?,
+                );
+            }
+
+            if let Some(path) = &config.ca_cert {
+                let file = resolve(path);
+                let pem = std::fs::read(&file)
+                    .with_context(|| format!("Failed to read http.ca_cert: {}", file.display()))?;
+                let certs = reqwest::Certificate::from_pem_bundle(&pem)
+                    .with_context(|| format!("Invalid PEM bundle in http.ca_cert: {path}"))?;
+                if certs.is_empty() {
+                    anyhow::bail!("No certificates in http.ca_cert: {path}");
+                }
+                for cert in certs {
+                    cb = cb.add_root_certificate(cert);
+                }
+            }
+
+            if let Some((cert_path, key_path)) = &config.client_identity {
+                let cert_file = resolve(cert_path);
+                let cert = std::fs::read(&cert_file).with_context(|| {
+                    format!("Failed to read http.client_cert: {}", cert_file.display())
+                })?;
+                let key_file = resolve(key_path);
+                let key = std::fs::read(&key_file).with_context(|| {
+                    format!("Failed to read http.client_key: {}", key_file.display())
+                })?;
+                cb = cb.identity(
+                    reqwest::Identity::from_pkcs8_pem(&cert, &key)
+                        .context("Invalid http.client_cert or http.client_key")?,
+                );
+            }
+
+            if let Some(max_idle) = config.pool_max_idle_per_host {
+                cb = cb.pool_max_idle_per_host(max_idle);
+            }
+            if let Some(timeout) = config.pool_idle_timeout {
+                cb = cb.pool_idle_timeout(timeout);
+            }
+            if config.http1_only {
+                cb = cb.http1_only();
+            }
+
+            cb.build().context("Failed to create reqwest client")
+        }
+    }
+}
+
 pub(crate) fn create_tracing_client() -> Result<reqwest::Client> {
     cfg_if::cfg_if! {
         if #[cfg(target_arch = "wasm32")] {
@@ -41,3 +113,134 @@ pub(crate) fn create_tracing_client() -> Result<reqwest::Client> {
 
     cb.build().context("Failed to create reqwest client")
 }
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    fn temp_file(contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("baml-http-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+        path.display().to_string()
+    }
+
+    fn error_of(config: HttpConfig) -> String {
+        format!(
+            "{:#}",
+            create_client_with_config(&config, None).unwrap_err()
+        )
+    }
+
+    #[test]
+    fn default_config_creates_a_client() {
+        assert!(create_client_with_config(&HttpConfig::default(), None).is_ok());
+    }
+
+    #[test]
+    fn relative_paths_are_resolved_against_baml_src() {
+        let baml_src = std::env::temp_dir().join(format!("baml-src-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(baml_src.join("certs")).unwrap();
+        std::fs::write(baml_src.join("certs/roots.pem"), "not a certificate\n").unwrap();
+
+        let config = HttpConfig {
+            ca_cert: Some("certs/roots.pem".to_string()),
+            ..Default::default()
+        };
+        let error = format!(
+            "{:#}",
+            create_client_with_config(&config, Some(&baml_src)).unwrap_err()
+        );
+        assert!(
+            error.contains("No certificates in http.ca_cert: certs/roots.pem"),
+            "{error}"
+        );
+
+        let config = HttpConfig {
+            ca_cert: Some("certs/missing.pem".to_string()),
+            ..Default::default()
+        };
+        let error = format!(
+            "{:#}",
+            create_client_with_config(&config, Some(&baml_src)).unwrap_err()
+        );
+        assert!(
+            error.contains(&format!(
+                "Failed to read http.ca_cert: {}",
+                baml_src.join("certs/missing.pem").display()
+            )),
+            "{error}"
+        );
+        std::fs::remove_dir_all(baml_src).unwrap();
+    }
+
+    #[test]
+    fn missing_ca_cert_is_reported() {
+        let error = error_of(HttpConfig {
+            ca_cert: Some("/nonexistent/baml-roots.pem".to_string()),
+            ..Default::default()
+        });
+        assert!(
+            error.contains("Failed to read http.ca_cert: /nonexistent/baml-roots.pem"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn invalid_ca_cert_is_reported() {
+        let path =
+            temp_file("-----BEGIN CERTIFICATE-----\nnot base64!\n-----END CERTIFICATE-----\n");
+        let error = error_of(HttpConfig {
+            ca_cert: Some(path.clone()),
+            ..Default::default()
+        });
+        assert!(
+            error.contains(&format!("Invalid PEM bundle in http.ca_cert: {path}")),
+            "{error}"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn ca_cert_without_certificates_is_reported() {
+        let path = temp_file("not a certificate\n");
+        let error = error_of(HttpConfig {
+            ca_cert: Some(path.clone()),
+            ..Default::default()
+        });
+        assert!(
+            error.contains(&format!("No certificates in http.ca_cert: {path}")),
+            "{error}"
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_client_key_is_reported() {
+        let cert = temp_file("-----BEGIN CERTIFICATE-----\n-----END CERTIFICATE-----\n");
+        let error = error_of(HttpConfig {
+            client_identity: Some((cert.clone(), "/nonexistent/baml-client.key".to_string())),
+            ..Default::default()
+        });
+        assert!(
+            error.contains("Failed to read http.client_key: /nonexistent/baml-client.key"),
+            "{error}"
+        );
+        std::fs::remove_file(cert).unwrap();
+    }
+
+    #[test]
+    fn invalid_client_identity_is_reported() {
+        let cert = temp_file("not a certificate\n");
+        let key = temp_file("not a key\n");
+        let error = error_of(HttpConfig {
+            client_identity: Some((cert.clone(), key.clone())),
+            ..Default::default()
+        });
+        assert!(
+            error.contains("Invalid http.client_cert or http.client_key"),
+            "{error}"
+        );
+        std::fs::remove_file(cert).unwrap();
+        std::fs::remove_file(key).unwrap();
+    }
+}
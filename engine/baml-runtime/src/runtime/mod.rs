@@ -27,7 +27,9 @@ use crate::internal::llm_client::{llm_provider::LLMProvider, retry_policy::Calla
 pub struct InternalBamlRuntime {
     pub(crate) ir: Arc<IntermediateRepr>,
     diagnostics: Diagnostics,
-    clients: DashMap<String, Arc<LLMProvider>>,
+    /// Clients by name, with the version of the secrets they were created
+    /// with.
+    clients: DashMap<String, (Arc<LLMProvider>, u64)>,
     retry_policies: DashMap<String, CallablePolicy>,
 }
 
@@ -30,7 +30,7 @@ use internal_baml_core::{
     ir::{repr::IntermediateRepr, ArgCoercer, FunctionWalker, IRHelper},
     validate,
 };
-use internal_baml_jinja::RenderedPrompt;
+use internal_baml_jinja::{RenderedPrompt, TokenEstimate};
 use internal_llm_client::{AllowedRoleMetadata, ClientSpec};
 
 impl<'a> InternalClientLookup<'a> for InternalBamlRuntime {
@@ -66,15 +66,21 @@ impl<'a> InternalClientLookup<'a> for InternalBamlRuntime {
                 #[cfg(not(target_arch = "wasm32"))]
                 let clients = &self.clients;
 
-                if let Some(client) = clients.get(client_name) {
-                    Ok(client.clone())
+                // Clients keep the secrets resolved when they were created, so
+                // they are created again once those expire.
+                let secrets_version = ctx.secrets().version();
+                if let Some(cached) = clients
+                    .get(client_name)
+                    .filter(|cached| cached.1 == secrets_version)
+                {
+                    Ok(cached.0.clone())
                 } else {
                     let walker = self
                         .ir()
                         .find_client(client_name)
                         .context(format!("Could not find client with name: {}", client_name))?;
                     let client = LLMProvider::try_from((&walker, ctx)).map(Arc::new)?;
-                    clients.insert(client_name.into(), client.clone());
+                    clients.insert(client_name.into(), (client.clone(), secrets_version));
                     Ok(client)
                 }
             }
@@ -177,6 +183,37 @@ impl InternalRuntimeInterface for InternalBamlRuntime {
             .map(|prompt| (prompt, node.scope, node.provider.allowed_metadata().clone()));
     }
 
+    fn estimate_prompt_tokens(
+        &self,
+        function_name: &str,
+        ctx: &RuntimeContext,
+        prompt: &RenderedPrompt,
+        node_index: Option<usize>,
+    ) -> Result<TokenEstimate> {
+        let func = self.get_function(function_name, ctx)?;
+
+        let renderer = PromptRenderer::from_function(&func, self.ir(), ctx)?;
+
+        let client_spec = renderer.client_spec();
+        let client = self.get_llm_provider(client_spec, ctx)?;
+        let mut selected =
+            client.iter_orchestrator(&mut Default::default(), Default::default(), ctx, self)?;
+
+        let node_index = node_index.unwrap_or(0);
+
+        if node_index >= selected.len() {
+            return Err(anyhow::anyhow!(
+                "Execution Node out of bounds (token estimate): {} >= {} for client {}",
+                node_index,
+                selected.len(),
+                client_spec,
+            ));
+        }
+
+        let node = selected.swap_remove(node_index);
+        Ok(node.provider.tokenizer().estimate(prompt))
+    }
+
     async fn render_raw_curl(
         &self,
         function_name: &str,
@@ -231,6 +268,7 @@ impl InternalRuntimeInterface for InternalBamlRuntime {
         test_name: &str,
         ctx: &RuntimeContext,
         strict: bool,
+        dataset_args: Option<&BamlMap<String, BamlValue>>,
     ) -> Result<BamlMap<String, BamlValue>> {
         let func = self.get_function(function_name, ctx)?;
         let test = self.ir().find_test(&func, test_name)?;
@@ -241,7 +279,7 @@ impl InternalRuntimeInterface for InternalBamlRuntime {
             Ok(params) => {
                 // Collect all errors and return them as a single error.
                 let mut errors = Vec::new();
-                let params = params
+                let mut params = params
                     .into_iter()
                     .map(|(k, v)| match v {
                         Ok(v) => (k, v),
@@ -259,6 +297,10 @@ impl InternalRuntimeInterface for InternalBamlRuntime {
                     ));
                 }
 
+                if let Some(dataset_args) = dataset_args {
+                    params.extend(dataset_args.clone());
+                }
+
                 let baml_args = self.ir().check_function_params(
                     &func,
                     &params,
@@ -2,7 +2,7 @@ use anyhow::Result;
 use baml_types::{BamlMap, BamlValue, Constraint};
 use internal_baml_core::internal_baml_diagnostics::Diagnostics;
 use internal_baml_core::ir::{repr::IntermediateRepr, FunctionWalker};
-use internal_baml_jinja::RenderedPrompt;
+use internal_baml_jinja::{RenderedPrompt, TokenEstimate};
 use internal_llm_client::{AllowedRoleMetadata, ClientSpec};
 use std::{collections::HashMap, sync::Arc};
 
@@ -140,6 +140,16 @@ pub trait InternalRuntimeInterface {
         node_index: Option<usize>,
     ) -> Result<(RenderedPrompt, OrchestrationScope, AllowedRoleMetadata)>;
 
+    /// Counts the tokens of a prompt rendered by `render_prompt`, with the
+    /// tokenizer of the client at `node_index`.
+    fn estimate_prompt_tokens(
+        &self,
+        function_name: &str,
+        ctx: &RuntimeContext,
+        prompt: &RenderedPrompt,
+        node_index: Option<usize>,
+    ) -> Result<TokenEstimate>;
+
     #[allow(async_fn_in_trait)]
     async fn render_raw_curl(
         &self,
@@ -152,12 +162,15 @@ pub trait InternalRuntimeInterface {
 
     fn ir(&self) -> &IntermediateRepr;
 
+    /// `dataset_args` are the arguments read from a row of the dataset of
+    /// the test, and override its `args`.
     fn get_test_params(
         &self,
         function_name: &str,
         test_name: &str,
         ctx: &RuntimeContext,
         strict: bool,
+        dataset_args: Option<&BamlMap<String, BamlValue>>,
     ) -> Result<BamlMap<String, BamlValue>>;
 
     fn get_test_constraints(
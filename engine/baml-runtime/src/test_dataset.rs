@@ -0,0 +1,337 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Context, Result};
+use baml_types::{BamlMap, BamlValue, FieldType, LiteralValue, TypeValue};
+use internal_baml_core::ir::repr::TestDataset;
+
+use crate::BamlSrcReader;
+
+/// A row of the dataset of a test, run as its own case of the test.
+#[derive(Debug, Clone)]
+pub struct DatasetRow {
+    /// The line the row starts at in the dataset file, for reporting.
+    pub line: usize,
+    /// The arguments read from the row. They override the `args` of the test.
+    pub args: BamlMap<String, BamlValue>,
+    /// The expected output, available to the test constraints as `_.expected`.
+    pub expected: Option<BamlValue>,
+}
+
+/// Reads the rows of `dataset`, keeping the keys or columns mapped to the
+/// arguments in `params` and to the expected output. CSV cells are typed by
+/// the parameter they are mapped to, or by `output` for the expected output.
+pub async fn load_rows(
+    dataset: &TestDataset,
+    params: &[(String, FieldType)],
+    output: &FieldType,
+    baml_src: &BamlSrcReader,
+) -> Result<Vec<DatasetRow>> {
+    let bytes = read_file(&dataset.path, baml_src).await?;
+    let text = String::from_utf8(bytes)
+        .with_context(|| format!("{} is not valid UTF-8", dataset.path.display()))?;
+
+    let records = match dataset.path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => parse_csv(&text, &column_types(dataset, params, output)),
+        _ => parse_jsonl(&text),
+    }
+    .with_context(|| format!("Failed to parse {}", dataset.path.display()))?;
+
+    Ok(records
+        .into_iter()
+        .map(|(line, record)| to_row(dataset, params, line, &record))
+        .collect())
+}
+
+fn to_row(
+    dataset: &TestDataset,
+    params: &[(String, FieldType)],
+    line: usize,
+    record: &BamlMap<String, BamlValue>,
+) -> DatasetRow {
+    let args = params
+        .iter()
+        .filter_map(|(param, _)| {
+            let key = dataset.args.get(param).unwrap_or(param);
+            record.get(key).map(|value| (param.clone(), value.clone()))
+        })
+        .collect();
+    let expected = dataset
+        .expected
+        .as_ref()
+        .and_then(|key| record.get(key).cloned());
+    DatasetRow {
+        line,
+        args,
+        expected,
+    }
+}
+
+/// The type of each key or column mapped to an argument or to the expected
+/// output.
+fn column_types<'a>(
+    dataset: &'a TestDataset,
+    params: &'a [(String, FieldType)],
+    output: &'a FieldType,
+) -> HashMap<&'a str, &'a FieldType> {
+    params
+        .iter()
+        .map(|(param, r#type)| (dataset.args.get(param).unwrap_or(param).as_str(), r#type))
+        .chain(dataset.expected.as_deref().map(|column| (column, output)))
+        .collect()
+}
+
+async fn read_file(path: &Path, baml_src: &BamlSrcReader) -> Result<Vec<u8>> {
+    let read = match baml_src {
+        Some(reader) => reader(&path.to_string_lossy()).await,
+        #[cfg(not(target_arch = "wasm32"))]
+        None => std::fs::read(path).map_err(Into::into),
+        #[cfg(target_arch = "wasm32")]
+        None => Err(anyhow::anyhow!(
+            "Internal error: no baml src reader provided"
+        )),
+    };
+    read.with_context(|| format!("Failed to read dataset {}", path.display()))
+}
+
+/// One JSON object per line. Blank lines are skipped.
+fn parse_jsonl(text: &str) -> Result<Vec<(usize, BamlMap<String, BamlValue>)>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(
+            |(idx, line)| match serde_json::from_str::<BamlValue>(line) {
+                Ok(BamlValue::Map(record)) => Ok((idx + 1, record)),
+                Ok(_) => anyhow::bail!("Line {}: expected a JSON object", idx + 1),
+                Err(e) => anyhow::bail!("Line {}: {}", idx + 1, e),
+            },
+        )
+        .collect()
+}
+
+/// A header row of column names, then one row per record. Cells of columns
+/// whose type is a string (or an enum) are kept as written. Other cells that
+/// are valid JSON, like numbers and arrays, are read as JSON, the others as
+/// strings. Empty cells are left out of the record.
+fn parse_csv(
+    text: &str,
+    types: &HashMap<&str, &FieldType>,
+) -> Result<Vec<(usize, BamlMap<String, BamlValue>)>> {
+    let mut rows = csv_rows(text)?.into_iter();
+    let Some((_, header)) = rows.next() else {
+        return Ok(vec![]);
+    };
+
+    rows.map(|(line, cells)| {
+        if cells.len() > header.len() {
+            anyhow::bail!(
+                "Line {}: expected {} columns, found {}",
+                line,
+                header.len(),
+                cells.len()
+            );
+        }
+        let record = header
+            .iter()
+            .zip(cells)
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(|(column, cell)| {
+                let value = match types.get(column.as_str()) {
+                    Some(r#type) if is_text(r#type) => BamlValue::String(cell),
+                    _ => serde_json::from_str::<BamlValue>(&cell)
+                        .unwrap_or_else(|_| BamlValue::String(cell)),
+                };
+                (column.clone(), value)
+            })
+            .collect();
+        Ok((line, record))
+    })
+    .collect()
+}
+
+/// Whether values of `type` are always written as plain text, so a CSV cell
+/// like `1.50` or `null` must not be read as JSON.
+fn is_text(r#type: &FieldType) -> bool {
+    match r#type {
+        FieldType::Primitive(TypeValue::String)
+        | FieldType::Enum(_)
+        | FieldType::Literal(LiteralValue::String(_)) => true,
+        FieldType::Optional(inner) => is_text(inner),
+        FieldType::Constrained { base, .. } => is_text(base),
+        FieldType::Union(options) => options.iter().all(is_text),
+        _ => false,
+    }
+}
+
+/// Splits RFC 4180 CSV into rows of cells, with the line each row starts at.
+/// Quoted cells can contain commas, newlines and doubled quotes.
+fn csv_rows(text: &str) -> Result<Vec<(usize, Vec<String>)>> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut cell = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut row_line = 1;
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if cell.is_empty() => in_quotes = true,
+            (',', false) => row.push(std::mem::take(&mut cell)),
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            ('\n', false) => {
+                row.push(std::mem::take(&mut cell));
+                if row.iter().any(|cell| !cell.is_empty()) {
+                    rows.push((row_line, std::mem::take(&mut row)));
+                } else {
+                    row.clear();
+                }
+                line += 1;
+                row_line = line;
+            }
+            (c, _) => {
+                if c == '\n' {
+                    line += 1;
+                }
+                cell.push(c);
+            }
+        }
+    }
+
+    if in_quotes {
+        anyhow::bail!("Line {}: unterminated quoted cell", row_line);
+    }
+    row.push(cell);
+    if row.iter().any(|cell| !cell.is_empty()) {
+        rows.push((row_line, row));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset(path: &str) -> TestDataset {
+        TestDataset {
+            path: path.into(),
+            args: [("resume".to_string(), "text".to_string())]
+                .into_iter()
+                .collect(),
+            expected: Some("label".to_string()),
+        }
+    }
+
+    #[test]
+    fn parses_csv() {
+        let rows =
+            csv_rows("text,label\r\n\"Ada, \"\"the\"\"\nfirst\",engineer\n\nBob,\n").unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (1, vec!["text".to_string(), "label".to_string()]),
+                (
+                    2,
+                    vec!["Ada, \"the\"\nfirst".to_string(), "engineer".to_string()]
+                ),
+                (5, vec!["Bob".to_string(), "".to_string()]),
+            ]
+        );
+        assert!(csv_rows("text\n\"Ada").is_err());
+    }
+
+    fn params() -> Vec<(String, FieldType)> {
+        vec![
+            ("resume".to_string(), FieldType::string()),
+            ("count".to_string(), FieldType::int()),
+        ]
+    }
+
+    #[test]
+    fn reads_csv_cells_as_json_or_strings() {
+        let records = parse_csv(
+            "name,age,tags,note\nAda,36,\"[\"\"a\"\"]\",\n",
+            &HashMap::new(),
+        )
+        .unwrap();
+        assert_eq!(records.len(), 1);
+        let (line, record) = &records[0];
+        assert_eq!(*line, 2);
+        assert_eq!(
+            record.get("name"),
+            Some(&BamlValue::String("Ada".to_string()))
+        );
+        assert_eq!(record.get("age"), Some(&BamlValue::Int(36)));
+        assert_eq!(
+            record.get("tags"),
+            Some(&BamlValue::List(vec![BamlValue::String("a".to_string())]))
+        );
+        assert!(!record.contains_key("note"));
+    }
+
+    #[test]
+    fn types_csv_cells_by_parameter() {
+        let dataset = dataset("resumes.csv");
+        let params = vec![
+            ("resume".to_string(), FieldType::string()),
+            ("price".to_string(), FieldType::float()),
+            ("note".to_string(), FieldType::string().as_optional()),
+        ];
+        let output = FieldType::r#enum("Role");
+        let types = column_types(&dataset, &params, &output);
+        let records = parse_csv(
+            "text,price,note,label\n1.50,1.50,null,1e3\n1e3,1e3,,engineer\n",
+            &types,
+        )
+        .unwrap();
+        let rows = records
+            .iter()
+            .map(|(line, record)| to_row(&dataset, &params, *line, record))
+            .collect::<Vec<_>>();
+
+        let string = |s: &str| Some(BamlValue::String(s.to_string()));
+        assert_eq!(rows[0].args.get("resume").cloned(), string("1.50"));
+        assert_eq!(rows[0].args.get("price"), Some(&BamlValue::Float(1.5)));
+        assert_eq!(rows[0].args.get("note").cloned(), string("null"));
+        assert_eq!(rows[0].expected, string("1e3"));
+        assert_eq!(rows[1].args.get("resume").cloned(), string("1e3"));
+        assert_eq!(rows[1].args.get("price"), Some(&BamlValue::Float(1000.0)));
+        assert!(!rows[1].args.contains_key("note"));
+        assert_eq!(rows[1].expected, string("engineer"));
+    }
+
+    #[test]
+    fn maps_keys_to_arguments() {
+        let dataset = dataset("resumes.jsonl");
+        let params = params();
+        let records = parse_jsonl(
+            "{\"text\": \"Ada\", \"label\": \"engineer\", \"other\": 1}\n\n{\"text\": \"Bob\", \"count\": 2}\n",
+        )
+        .unwrap();
+        let rows = records
+            .iter()
+            .map(|(line, record)| to_row(&dataset, &params, *line, record))
+            .collect::<Vec<_>>();
+
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 1);
+        assert_eq!(
+            rows[0].args.get("resume"),
+            Some(&BamlValue::String("Ada".to_string()))
+        );
+        assert!(!rows[0].args.contains_key("other"));
+        assert_eq!(
+            rows[0].expected,
+            Some(BamlValue::String("engineer".to_string()))
+        );
+        assert_eq!(rows[1].line, 3);
+        assert_eq!(rows[1].args.get("count"), Some(&BamlValue::Int(2)));
+        assert_eq!(rows[1].expected, None);
+
+        assert!(parse_jsonl("[1, 2]\n").is_err());
+    }
+}
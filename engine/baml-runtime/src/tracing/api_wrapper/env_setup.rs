@@ -17,8 +17,27 @@ pub struct Config {
     pub log_redaction_enabled: bool,
     #[serde(default = "default_redaction_placeholder")]
     pub log_redaction_placeholder: String,
+    /// Built-in patterns to redact, e.g. `email,credit_card`.
+    #[serde(default)]
+    pub log_redaction_patterns: Vec<String>,
+    /// A JSON array of regular expressions to redact.
+    pub log_redaction_custom_patterns: Option<String>,
     #[serde(default = "default_max_log_chunk_chars")]
     pub max_log_chunk_chars: usize,
+    /// Where to write traces as JSONL files, for `baml-cli traces`.
+    pub trace_dir: Option<String>,
+    #[serde(default = "default_trace_max_file_bytes")]
+    pub trace_max_file_bytes: u64,
+    #[serde(default = "default_trace_max_files")]
+    pub trace_max_files: usize,
+    /// The fraction of traces uploaded to Boundary Studio.
+    #[serde(default = "default_trace_sample_rate")]
+    pub trace_sample_rate: f64,
+    /// Whether traces with errors are uploaded even if not sampled.
+    #[serde(default = "default_trace_sample_keep_errors")]
+    pub trace_sample_keep_errors: bool,
+    /// Traces slower than this are uploaded even if not sampled.
+    pub trace_sample_slow_ms: Option<u64>,
 }
 
 fn default_base_url() -> String {
@@ -51,6 +70,22 @@ fn default_max_log_chunk_chars() -> usize {
     64_000
 }
 
+fn default_trace_max_file_bytes() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_trace_max_files() -> usize {
+    10
+}
+
+fn default_trace_sample_rate() -> f64 {
+    1.0
+}
+
+fn default_trace_sample_keep_errors() -> bool {
+    true
+}
+
 impl Config {
     pub fn from_env_vars<T: AsRef<str>>(env_vars: impl Iterator<Item = (T, T)>) -> Result<Self> {
         let config: Result<Config, envy::Error> = envy::prefixed("BOUNDARY_")
@@ -1,12 +1,16 @@
 mod env_setup;
 
+use std::sync::Arc;
+
 use anyhow::Result;
 pub(super) mod api_interface;
 pub(crate) mod core_types;
 use serde::{de::DeserializeOwned, Deserialize};
 use serde_json::{json, Value};
 
-use crate::request::create_tracing_client;
+use crate::{request::create_tracing_client, SecretStore};
+
+use super::redaction::Redactor;
 
 pub(super) use self::api_interface::{BoundaryAPI, BoundaryTestAPI};
 use self::core_types::{TestCaseStatus, UpdateTestCase};
@@ -14,6 +18,7 @@ use self::core_types::{TestCaseStatus, UpdateTestCase};
 #[derive(Debug, Clone)]
 pub struct APIWrapper {
     pub(super) config: APIConfig,
+    pub(super) redactor: Redactor,
 }
 
 #[derive(Debug, Clone)]
@@ -78,6 +83,48 @@ impl APIConfig {
             Self::Web(config) => config.max_log_chunk_chars,
         }
     }
+
+    pub fn trace_sink(&self) -> Option<&TraceSinkConfig> {
+        match self {
+            Self::LocalOnly(config) => config.trace_sink.as_ref(),
+            Self::Web(config) => config.trace_sink.as_ref(),
+        }
+    }
+
+    pub fn trace_sampling(&self) -> &TraceSamplingConfig {
+        match self {
+            Self::LocalOnly(config) => &config.trace_sampling,
+            Self::Web(config) => &config.trace_sampling,
+        }
+    }
+}
+
+/// Where and how much to keep of the traces written to disk.
+#[derive(Debug, Clone)]
+pub(super) struct TraceSinkConfig {
+    pub dir: std::path::PathBuf,
+    pub max_file_bytes: u64,
+    pub max_files: usize,
+}
+
+/// Which traces are uploaded to Boundary Studio. Traces written to disk are
+/// not sampled.
+#[derive(Debug, Clone)]
+pub(super) struct TraceSamplingConfig {
+    /// The fraction of traces uploaded, decided when the trace starts.
+    pub rate: f64,
+    /// Upload the traces that were not sampled if they contain an error.
+    pub keep_errors: bool,
+    /// Upload the traces that were not sampled if they took this long.
+    pub slow_ms: Option<u64>,
+}
+
+impl TraceSamplingConfig {
+    /// Whether every trace is uploaded, so none needs to be buffered until
+    /// the sampling decision.
+    pub fn keeps_everything(&self) -> bool {
+        self.rate >= 1.0
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -91,6 +138,8 @@ pub(super) struct CompleteAPIConfig {
     pub log_redaction_enabled: bool,
     pub log_redaction_placeholder: String,
     pub max_log_chunk_chars: usize,
+    pub trace_sink: Option<TraceSinkConfig>,
+    pub trace_sampling: TraceSamplingConfig,
 
     client: reqwest::Client,
 }
@@ -108,6 +157,8 @@ pub(super) struct PartialAPIConfig {
     log_redaction_enabled: bool,
     log_redaction_placeholder: String,
     pub max_log_chunk_chars: usize,
+    pub trace_sink: Option<TraceSinkConfig>,
+    pub trace_sampling: TraceSamplingConfig,
 }
 
 impl CompleteAPIConfig {
@@ -298,6 +349,21 @@ impl APIWrapper {
         if config.log_redaction_enabled {
             log::info!("Redaction enabled: {}", config.log_redaction_enabled);
         }
+        let trace_sink = config.trace_dir.as_ref().map(|dir| TraceSinkConfig {
+            dir: dir.into(),
+            max_file_bytes: config.trace_max_file_bytes,
+            max_files: config.trace_max_files,
+        });
+        let trace_sampling = TraceSamplingConfig {
+            rate: config.trace_sample_rate,
+            keep_errors: config.trace_sample_keep_errors,
+            slow_ms: config.trace_sample_slow_ms,
+        };
+        let redactor = Redactor::new(
+            &config.log_redaction_patterns,
+            config.log_redaction_custom_patterns.as_deref(),
+            &config.log_redaction_placeholder,
+        )?;
         Ok(match (&config.secret, &config.project_id) {
             (Some(api_key), Some(project_id)) => Self {
                 config: APIConfig::Web(CompleteAPIConfig {
@@ -311,7 +377,10 @@ impl APIWrapper {
                     log_redaction_enabled: config.log_redaction_enabled,
                     log_redaction_placeholder: config.log_redaction_placeholder,
                     max_log_chunk_chars: config.max_log_chunk_chars,
+                    trace_sink,
+                    trace_sampling,
                 }),
+                redactor,
             },
             _ => Self {
                 config: APIConfig::LocalOnly(PartialAPIConfig {
@@ -324,7 +393,10 @@ impl APIWrapper {
                     log_redaction_enabled: config.log_redaction_enabled,
                     log_redaction_placeholder: config.log_redaction_placeholder,
                     max_log_chunk_chars: config.max_log_chunk_chars,
+                    trace_sink,
+                    trace_sampling,
                 }),
+                redactor,
             },
         })
     }
@@ -333,6 +405,24 @@ impl APIWrapper {
         self.config.project_id().is_some() && self.config.secret().is_some()
     }
 
+    /// Redacts the `@sensitive` fields of the classes in `ir` from traces.
+    pub(super) fn set_sensitive_fields(
+        &mut self,
+        ir: &internal_baml_core::ir::repr::IntermediateRepr,
+    ) {
+        self.redactor.set_sensitive_fields(ir);
+    }
+
+    /// Redacts the secrets resolved by `secrets` from traces.
+    pub(super) fn set_secrets(&mut self, secrets: Arc<SecretStore>) {
+        self.redactor.set_secrets(secrets);
+    }
+
+    /// Whether traces are written to disk, with or without Boundary Cloud.
+    pub fn trace_sink_enabled(&self) -> bool {
+        self.config.trace_sink().is_some()
+    }
+
     pub fn project_id(&self) -> Option<&str> {
         self.config.project_id()
     }
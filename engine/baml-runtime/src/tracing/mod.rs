@@ -1,4 +1,5 @@
 pub mod api_wrapper;
+mod redaction;
 
 use crate::on_log_event::LogEventCallbackSync;
 use crate::InnerTraceStats;
@@ -6,6 +7,7 @@ use anyhow::{Context, Result};
 use baml_types::{BamlMap, BamlMediaType, BamlValue};
 use cfg_if::cfg_if;
 use colored::{ColoredString, Colorize};
+use internal_baml_core::ir::repr::IntermediateRepr;
 use internal_baml_jinja::RenderedPrompt;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -16,7 +18,7 @@ use uuid::Uuid;
 use crate::{
     client_registry::ClientRegistry, internal::llm_client::LLMResponse,
     tracing::api_wrapper::core_types::Role, type_builder::TypeBuilder, FunctionResult,
-    RuntimeContext, RuntimeContextManager, SpanCtx, TestResponse, TraceStats,
+    RuntimeContext, RuntimeContextManager, SecretStore, SpanCtx, TestResponse, TraceStats,
 };
 
 use self::api_wrapper::{
@@ -35,7 +37,9 @@ cfg_if! {
         mod wasm_tracer;
         use self::wasm_tracer::NonThreadedTracer as TracerImpl;
     } else {
+        mod sampling;
         mod threaded_tracer;
+        mod trace_sink;
         use self::threaded_tracer::ThreadedTracer as TracerImpl;
     }
 }
@@ -43,7 +47,10 @@ cfg_if! {
 #[derive(Debug, Clone)]
 pub struct TracingSpan {
     span_id: Uuid,
+    /// The arguments, with `@sensitive` fields redacted.
     params: BamlMap<String, BamlValue>,
+    /// The values of the `@sensitive` fields in the arguments.
+    sensitive_values: Vec<String>,
     start_time: web_time::SystemTime,
 }
 
@@ -190,16 +197,20 @@ impl BamlTracer {
     pub fn new<T: AsRef<str>>(
         options: Option<APIWrapper>,
         env_vars: impl Iterator<Item = (T, T)>,
+        ir: &IntermediateRepr,
+        secrets: &Arc<SecretStore>,
     ) -> Result<Self> {
-        let options = match options {
+        let mut options = match options {
             Some(wrapper) => wrapper,
             None => APIWrapper::from_env_vars(env_vars)?,
         };
+        options.set_sensitive_fields(ir);
+        options.set_secrets(secrets.clone());
 
         let trace_stats = TraceStats::default();
 
         let tracer = BamlTracer {
-            tracer: if options.enabled() {
+            tracer: if options.enabled() || options.trace_sink_enabled() {
                 Some(TracerImpl::new(&options, 20, trace_stats.clone()))
             } else {
                 None
@@ -238,9 +249,15 @@ impl BamlTracer {
         self.trace_stats.guard().start();
         let span_id = ctx.enter(function_name);
         log::trace!("Entering span {:#?} in {:?}", span_id, function_name);
+        let mut sensitive_values = vec![];
         let span = TracingSpan {
             span_id,
-            params: params.clone(),
+            params: self.options.redactor.redact_params(
+                function_name,
+                params,
+                &mut sensitive_values,
+            ),
+            sensitive_values,
             start_time: web_time::SystemTime::now(),
         };
 
@@ -338,12 +355,12 @@ impl BamlTracer {
                 .result_with_constraints()
                 .as_ref()
                 .is_some_and(|r| r.is_ok());
-            log::log!(
-                target: "baml_events",
-                if is_ok { log::Level::Info } else { log::Level::Warn },
-                "{}{}",
-                name.map(|s| format!("Function {}:\n", s)).unwrap_or_default().purple(),
-                response.visualize(self.options.config.max_log_chunk_chars())
+            log_simple_event(
+                is_ok,
+                name,
+                response,
+                &self.options,
+                &self.sensitive_values(response, &span),
             );
         }
 
@@ -429,17 +446,46 @@ impl BamlTracer {
             span.clone(),
         );
 
+        let sensitive_values = self.sensitive_values(response, span);
         if log_json {
-            let log_event = self.build_baml_event_json(response, span);
+            let mut log_event = self.build_baml_event_json(response, span);
+            for text in [
+                &mut log_event.prompt,
+                &mut log_event.llm_reply,
+                &mut log_event.parsed_response,
+                &mut log_event.error,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                *text = self.options.redactor.redact_text(text, &sensitive_values);
+            }
             log_json_event(is_ok, log_event)?;
         } else {
-            log_simple_event(is_ok, name, response, &self.options);
+            log_simple_event(is_ok, name, response, &self.options, &sensitive_values);
         }
 
         Ok(())
     }
 
+    /// The values of the `@sensitive` fields in the arguments and the parsed
+    /// output of a call, to be removed from its console output.
+    fn sensitive_values(&self, response: &FunctionResult, span: &TracingSpan) -> Vec<String> {
+        let mut sensitive_values = span.sensitive_values.clone();
+        if let Some(Ok(value)) = response.result_with_constraints() {
+            let value: BamlValue = value.into();
+            self.options
+                .redactor
+                .redact_value(&value, &mut sensitive_values);
+        }
+        sensitive_values
+    }
+
     fn handle_error_response(&self, error: &anyhow::Error, log_json: bool, span: &TracingSpan) {
+        let error = self
+            .options
+            .redactor
+            .redact_text(&error.to_string(), &span.sensitive_values);
         if log_json {
             let baml_event_json = BamlEventJson {
                 start_time: to_iso_string(&span.start_time),
@@ -455,7 +501,7 @@ impl BamlTracer {
                 tokens: None,
                 parsed_response_type: None,
                 parsed_response: None,
-                error: Some(error.to_string()),
+                error: Some(error),
             };
             rust_tracing::event!(
                 target: "baml_events",
@@ -569,13 +615,17 @@ fn log_simple_event(
     name: Option<&str>,
     response: &FunctionResult,
     options: &APIWrapper,
+    sensitive_values: &[String],
 ) {
     log::log!(
         target: "baml_events",
         if is_ok { log::Level::Info } else { log::Level::Warn },
         "{}{}",
         name.map(|s| format!("Function {}:\n", s)).unwrap_or_default().purple(),
-        response.visualize(options.config.max_log_chunk_chars())
+        options.redactor.redact_text(
+            &response.visualize(options.config.max_log_chunk_chars()),
+            sensitive_values
+        )
     );
 }
 
@@ -733,25 +783,30 @@ impl<T: ToLogSchema> ToLogSchema for Result<T> {
     ) -> LogSchema {
         match self {
             Ok(r) => r.to_log_schema(api, event_chain, tags, span),
-            Err(e) => LogSchema {
-                project_id: api.project_id().map(|s| s.to_string()),
-                event_type: api_wrapper::core_types::EventType::FuncCode,
-                root_event_id: event_chain.first().map(|s| s.span_id).unwrap().to_string(),
-                event_id: event_chain.last().map(|s| s.span_id).unwrap().to_string(),
-                parent_event_id: None,
-                context: (api, event_chain, tags, &span).into(),
-                io: IO {
-                    input: Some((&span.params).into()),
-                    output: None,
-                },
-                error: Some(api_wrapper::core_types::Error {
-                    code: 2,
-                    message: e.to_string(),
-                    traceback: None,
-                    r#override: None,
-                }),
-                metadata: None,
-            },
+            Err(e) => {
+                let mut event = LogSchema {
+                    project_id: api.project_id().map(|s| s.to_string()),
+                    event_type: api_wrapper::core_types::EventType::FuncCode,
+                    root_event_id: event_chain.first().map(|s| s.span_id).unwrap().to_string(),
+                    event_id: event_chain.last().map(|s| s.span_id).unwrap().to_string(),
+                    parent_event_id: None,
+                    context: (api, event_chain, tags, &span).into(),
+                    io: IO {
+                        input: Some((&span.params).into()),
+                        output: None,
+                    },
+                    error: Some(api_wrapper::core_types::Error {
+                        code: 2,
+                        message: e.to_string(),
+                        traceback: None,
+                        r#override: None,
+                    }),
+                    metadata: None,
+                };
+                api.redactor
+                    .redact_event(&mut event, &span.sensitive_values);
+                event
+            }
         }
     }
 }
@@ -765,7 +820,11 @@ impl ToLogSchema for Option<BamlValue> {
         tags: HashMap<String, BamlValue>,
         span: TracingSpan,
     ) -> LogSchema {
-        LogSchema {
+        let mut sensitive_values = span.sensitive_values.clone();
+        let output = self
+            .as_ref()
+            .map(|r| IOValue::from(&api.redactor.redact_value(r, &mut sensitive_values)));
+        let mut event = LogSchema {
             project_id: api.project_id().map(|s| s.to_string()),
             event_type: api_wrapper::core_types::EventType::FuncCode,
             root_event_id: event_chain.first().map(|s| s.span_id).unwrap().to_string(),
@@ -780,11 +839,13 @@ impl ToLogSchema for Option<BamlValue> {
             context: (api, event_chain, tags, &span).into(),
             io: IO {
                 input: Some((&span.params).into()),
-                output: self.as_ref().map(|r| r.into()),
+                output,
             },
             error: None,
             metadata: None,
-        }
+        };
+        api.redactor.redact_event(&mut event, &sensitive_values);
+        event
     }
 }
 
@@ -809,7 +870,16 @@ impl ToLogSchema for FunctionResult {
         tags: HashMap<String, BamlValue>,
         span: TracingSpan,
     ) -> LogSchema {
-        LogSchema {
+        let mut sensitive_values = span.sensitive_values.clone();
+        let output = self
+            .result_with_constraints()
+            .as_ref()
+            .and_then(|r| r.as_ref().ok())
+            .map(|r| {
+                let v: BamlValue = r.into();
+                IOValue::from(&api.redactor.redact_value(&v, &mut sensitive_values))
+            });
+        let mut event = LogSchema {
             project_id: api.project_id().map(|s| s.to_string()),
             event_type: api_wrapper::core_types::EventType::FuncLlm,
             root_event_id: event_chain.first().map(|s| s.span_id).unwrap().to_string(),
@@ -825,18 +895,13 @@ impl ToLogSchema for FunctionResult {
             context: (api, event_chain, tags, &span).into(),
             io: IO {
                 input: Some((&span.params).into()),
-                output: self
-                    .result_with_constraints()
-                    .as_ref()
-                    .and_then(|r| r.as_ref().ok())
-                    .map(|r| {
-                        let v: BamlValue = r.into();
-                        IOValue::from(&v)
-                    }),
+                output,
             },
             error: error_from_result(self),
             metadata: Some(self.into()),
-        }
+        };
+        api.redactor.redact_event(&mut event, &sensitive_values);
+        event
     }
 }
 
@@ -0,0 +1,557 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use baml_types::{BamlMap, BamlValue, FieldType};
+use internal_baml_core::ir::repr::IntermediateRepr;
+use regex::Regex;
+
+use crate::SecretStore;
+
+use super::api_wrapper::core_types::{ContentPart, LogSchema, MetadataType, Template, ValueType};
+
+/// The patterns that can be enabled by name with
+/// `BOUNDARY_LOG_REDACTION_PATTERNS`.
+const BUILTIN_PATTERNS: &[(&str, &str)] = &[
+    ("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"),
+    ("credit_card", r"\b(?:\d[ -]?){12,18}\d\b"),
+    ("ssn", r"\b\d{3}-\d{2}-\d{4}\b"),
+    (
+        "phone",
+        r"(?:\+\d{1,3}[ .-]?)?\(?\b\d{3}\)?[ .-]?\d{3}[ .-]?\d{4}\b",
+    ),
+];
+
+/// Values of `@sensitive` fields shorter than this are redacted from parsed
+/// values, but not searched for in prompts and raw outputs, where they would
+/// match unrelated text.
+const MIN_SCRUBBED_VALUE_CHARS: usize = 4;
+
+/// Redacts `@sensitive` class fields, resolved secrets and text matching the
+/// configured patterns from the traces that leave the process: the uploaded
+/// and written traces, and the `BAML_LOG` console output.
+#[derive(Debug, Clone)]
+pub(crate) struct Redactor {
+    patterns: Vec<Regex>,
+    /// Class name to the names of its `@sensitive` fields.
+    sensitive_fields: HashMap<String, HashSet<String>>,
+    /// Class name to the types of its fields, and function name to the
+    /// types of its parameters. Arguments are usually passed as maps rather
+    /// than class instances, so their `@sensitive` fields are found through
+    /// the declared types.
+    field_types: HashMap<String, HashMap<String, FieldType>>,
+    param_types: HashMap<String, HashMap<String, FieldType>>,
+    secrets: Option<Arc<SecretStore>>,
+    placeholder: String,
+}
+
+impl Redactor {
+    pub fn new(builtin: &[String], custom: Option<&str>, placeholder: &str) -> Result<Self> {
+        let mut patterns = vec![];
+        for name in builtin.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+            let Some((_, pattern)) = BUILTIN_PATTERNS.iter().find(|(n, _)| *n == name) else {
+                anyhow::bail!(
+                    "Unknown redaction pattern `{}` in BOUNDARY_LOG_REDACTION_PATTERNS. Expected one of: {}",
+                    name,
+                    BUILTIN_PATTERNS
+                        .iter()
+                        .map(|(n, _)| *n)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            };
+            patterns.push(Regex::new(pattern)?);
+        }
+
+        if let Some(custom) = custom {
+            let custom: Vec<String> = serde_json::from_str(custom).context(
+                "BOUNDARY_LOG_REDACTION_CUSTOM_PATTERNS must be a JSON array of regular expressions",
+            )?;
+            for pattern in custom {
+                patterns.push(
+                    Regex::new(&pattern)
+                        .with_context(|| format!("Invalid redaction pattern `{}`", pattern))?,
+                );
+            }
+        }
+
+        Ok(Self {
+            patterns,
+            sensitive_fields: HashMap::new(),
+            field_types: HashMap::new(),
+            param_types: HashMap::new(),
+            secrets: None,
+            placeholder: placeholder.to_string(),
+        })
+    }
+
+    /// Collects the `@sensitive` fields of the classes in `ir`, and the
+    /// types of the class fields and function parameters that lead to them.
+    pub fn set_sensitive_fields(&mut self, ir: &IntermediateRepr) {
+        self.sensitive_fields = ir
+            .walk_classes()
+            .filter_map(|class| {
+                let fields = class
+                    .walk_fields()
+                    .filter(|field| field.sensitive())
+                    .map(|field| field.name().to_string())
+                    .collect::<HashSet<_>>();
+                (!fields.is_empty()).then(|| (class.name().to_string(), fields))
+            })
+            .collect();
+        if self.sensitive_fields.is_empty() {
+            self.field_types.clear();
+            self.param_types.clear();
+            return;
+        }
+
+        self.field_types = ir
+            .walk_classes()
+            .map(|class| {
+                let fields = class
+                    .walk_fields()
+                    .map(|field| (field.name().to_string(), field.r#type().clone()))
+                    .collect();
+                (class.name().to_string(), fields)
+            })
+            .collect();
+        self.param_types = ir
+            .walk_functions()
+            .map(|function| {
+                let params = function.inputs().iter().cloned().collect();
+                (function.name().to_string(), params)
+            })
+            .collect();
+    }
+
+    /// Redacts the secrets resolved by `secrets`, e.g. from error messages
+    /// and request options.
+    pub fn set_secrets(&mut self, secrets: Arc<SecretStore>) {
+        self.secrets = Some(secrets);
+    }
+
+    fn has_secrets(&self) -> bool {
+        self.secrets.as_ref().is_some_and(|s| s.has_resolved())
+    }
+
+    fn is_noop(&self) -> bool {
+        self.patterns.is_empty() && self.sensitive_fields.is_empty() && !self.has_secrets()
+    }
+
+    /// Returns `value` with its `@sensitive` fields and pattern matches
+    /// redacted, and adds the string values of the redacted fields to
+    /// `sensitive_values`.
+    pub fn redact_value(&self, value: &BamlValue, sensitive_values: &mut Vec<String>) -> BamlValue {
+        if self.is_noop() {
+            return value.clone();
+        }
+        self.redact_typed(value, None, sensitive_values)
+    }
+
+    /// Redacts the arguments of a call to `function_name`, using the types
+    /// of its parameters to find the `@sensitive` fields of arguments passed
+    /// as maps.
+    pub fn redact_params(
+        &self,
+        function_name: &str,
+        params: &BamlMap<String, BamlValue>,
+        sensitive_values: &mut Vec<String>,
+    ) -> BamlMap<String, BamlValue> {
+        if self.is_noop() {
+            return params.clone();
+        }
+        let param_types = self.param_types.get(function_name);
+        params
+            .iter()
+            .map(|(key, value)| {
+                let r#type = param_types.and_then(|types| types.get(key));
+                (
+                    key.clone(),
+                    self.redact_typed(value, r#type, sensitive_values),
+                )
+            })
+            .collect()
+    }
+
+    /// Redacts `value`, which has the declared type `r#type` if it's known.
+    fn redact_typed(
+        &self,
+        value: &BamlValue,
+        r#type: Option<&FieldType>,
+        sensitive_values: &mut Vec<String>,
+    ) -> BamlValue {
+        match value {
+            BamlValue::String(s) => BamlValue::String(self.redact_text(s, &[])),
+            BamlValue::List(items) => {
+                let item_type = r#type.and_then(item_type);
+                BamlValue::List(
+                    items
+                        .iter()
+                        .map(|item| self.redact_typed(item, item_type, sensitive_values))
+                        .collect(),
+                )
+            }
+            BamlValue::Map(items) => {
+                let mut classes = vec![];
+                if let Some(r#type) = r#type {
+                    classes_of(r#type, &mut classes);
+                }
+                if classes.is_empty() {
+                    let value_type = r#type.and_then(map_value_type);
+                    BamlValue::Map(
+                        items
+                            .iter()
+                            .map(|(key, item)| {
+                                let item = self.redact_typed(item, value_type, sensitive_values);
+                                (key.clone(), item)
+                            })
+                            .collect(),
+                    )
+                } else {
+                    BamlValue::Map(self.redact_fields(&classes, items, sensitive_values))
+                }
+            }
+            BamlValue::Class(name, fields) => BamlValue::Class(
+                name.clone(),
+                self.redact_fields(&[name.as_str()], fields, sensitive_values),
+            ),
+            other => other.clone(),
+        }
+    }
+
+    /// Redacts the fields of an instance of one of `classes`. When it's not
+    /// known which, a field is redacted if it's `@sensitive` in any of them.
+    fn redact_fields(
+        &self,
+        classes: &[&str],
+        fields: &BamlMap<String, BamlValue>,
+        sensitive_values: &mut Vec<String>,
+    ) -> BamlMap<String, BamlValue> {
+        fields
+            .iter()
+            .map(|(key, field)| {
+                let sensitive = classes.iter().any(|class| {
+                    self.sensitive_fields
+                        .get(*class)
+                        .is_some_and(|fields| fields.contains(key))
+                });
+                let field = if sensitive {
+                    collect_strings(field, sensitive_values);
+                    BamlValue::String(self.placeholder.clone())
+                } else {
+                    let r#type = classes.iter().find_map(|class| {
+                        self.field_types
+                            .get(*class)
+                            .and_then(|fields| fields.get(key))
+                    });
+                    self.redact_typed(field, r#type, sensitive_values)
+                };
+                (key.clone(), field)
+            })
+            .collect()
+    }
+
+    /// Replaces the resolved secrets, the pattern matches and the
+    /// `sensitive_values` in `text`.
+    pub fn redact_text(&self, text: &str, sensitive_values: &[String]) -> String {
+        let mut text = match &self.secrets {
+            Some(secrets) => secrets.redact(text, &self.placeholder),
+            None => text.to_string(),
+        };
+        for value in sensitive_values {
+            if value.chars().count() >= MIN_SCRUBBED_VALUE_CHARS {
+                text = text.replace(value.as_str(), &self.placeholder);
+            }
+        }
+        for pattern in &self.patterns {
+            if let std::borrow::Cow::Owned(replaced) =
+                pattern.replace_all(&text, regex::NoExpand(&self.placeholder))
+            {
+                text = replaced;
+            }
+        }
+        text
+    }
+
+    /// Redacts the prompts, raw outputs, values and errors of `event`. Values
+    /// of `@sensitive` fields must already be redacted from its inputs and
+    /// outputs, and are passed as `sensitive_values` to be removed from the
+    /// text the LLM saw and produced.
+    pub fn redact_event(&self, event: &mut LogSchema, sensitive_values: &[String]) {
+        if self.patterns.is_empty() && sensitive_values.is_empty() && !self.has_secrets() {
+            return;
+        }
+        let redact = |s: &mut String| *s = self.redact_text(s, sensitive_values);
+
+        for io in [&mut event.io.input, &mut event.io.output]
+            .into_iter()
+            .flatten()
+        {
+            match &mut io.value {
+                ValueType::String(s) => redact(s),
+                ValueType::List(items) => items.iter_mut().for_each(redact),
+            }
+        }
+
+        if let Some(error) = &mut event.error {
+            redact(&mut error.message);
+        }
+
+        let llm_events = match &mut event.metadata {
+            Some(MetadataType::Single(llm_event)) => std::slice::from_mut(llm_event),
+            Some(MetadataType::Multi(llm_events)) => llm_events.as_mut_slice(),
+            None => Default::default(),
+        };
+        for llm_event in llm_events {
+            match &mut llm_event.input.prompt.template {
+                Template::Single(s) => redact(s),
+                Template::Multiple(chats) => {
+                    for part in chats.iter_mut().flat_map(|chat| chat.content.iter_mut()) {
+                        if let ContentPart::Text(s) = part {
+                            redact(s);
+                        }
+                    }
+                }
+            }
+            for value in llm_event.input.request_options.values_mut() {
+                redact_json(value, &redact);
+            }
+            if let Some(output) = &mut llm_event.output {
+                redact(&mut output.raw_text);
+            }
+            if let Some(error) = &mut llm_event.error {
+                redact(error);
+            }
+        }
+    }
+}
+
+fn redact_json(value: &mut serde_json::Value, redact: &impl Fn(&mut String)) {
+    match value {
+        serde_json::Value::String(s) => redact(s),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| redact_json(v, redact)),
+        serde_json::Value::Object(items) => items.values_mut().for_each(|v| redact_json(v, redact)),
+        _ => {}
+    }
+}
+
+/// The classes a value of type `r#type` may be an instance of.
+fn classes_of<'a>(r#type: &'a FieldType, classes: &mut Vec<&'a str>) {
+    match r#type {
+        FieldType::Class(name) => classes.push(name),
+        FieldType::Union(options) => options.iter().for_each(|o| classes_of(o, classes)),
+        FieldType::Optional(inner) | FieldType::Constrained { base: inner, .. } => {
+            classes_of(inner, classes)
+        }
+        _ => {}
+    }
+}
+
+fn item_type(r#type: &FieldType) -> Option<&FieldType> {
+    match r#type {
+        FieldType::List(inner) => Some(inner.as_ref()),
+        FieldType::Union(options) => options.iter().find_map(item_type),
+        FieldType::Optional(inner) | FieldType::Constrained { base: inner, .. } => item_type(inner),
+        _ => None,
+    }
+}
+
+fn map_value_type(r#type: &FieldType) -> Option<&FieldType> {
+    match r#type {
+        FieldType::Map(_, value) => Some(value.as_ref()),
+        FieldType::Union(options) => options.iter().find_map(map_value_type),
+        FieldType::Optional(inner) | FieldType::Constrained { base: inner, .. } => {
+            map_value_type(inner)
+        }
+        _ => None,
+    }
+}
+
+fn collect_strings(value: &BamlValue, strings: &mut Vec<String>) {
+    match value {
+        BamlValue::String(s) => strings.push(s.clone()),
+        BamlValue::Map(items) | BamlValue::Class(_, items) => {
+            items.values().for_each(|v| collect_strings(v, strings))
+        }
+        BamlValue::List(items) => items.iter().for_each(|v| collect_strings(v, strings)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redactor() -> Redactor {
+        let mut redactor = Redactor::new(
+            &["email".to_string(), "credit_card".to_string()],
+            Some(r#"["ACCT-\\d+"]"#),
+            "<REDACTED>",
+        )
+        .unwrap();
+        redactor.sensitive_fields = [(
+            "Person".to_string(),
+            ["password".to_string()].into_iter().collect(),
+        )]
+        .into_iter()
+        .collect();
+        redactor
+    }
+
+    #[test]
+    fn redacts_patterns_and_sensitive_fields() {
+        let redactor = redactor();
+        let person = BamlValue::Class(
+            "Person".to_string(),
+            [
+                ("name".to_string(), BamlValue::String("Ada".to_string())),
+                (
+                    "email".to_string(),
+                    BamlValue::String("ada@example.com".to_string()),
+                ),
+                (
+                    "password".to_string(),
+                    BamlValue::String("hunter22".to_string()),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        let mut sensitive_values = vec![];
+        let redacted = redactor.redact_value(&person, &mut sensitive_values);
+        assert_eq!(
+            serde_json::to_value(&redacted).unwrap(),
+            serde_json::json!({
+                "name": "Ada",
+                "email": "<REDACTED>",
+                "password": "<REDACTED>",
+            })
+        );
+        assert_eq!(sensitive_values, vec!["hunter22".to_string()]);
+
+        assert_eq!(
+            redactor.redact_text(
+                "Ada (ada@example.com) paid with 4111 1111 1111 1111 from ACCT-42, password hunter22",
+                &sensitive_values
+            ),
+            "Ada (<REDACTED>) paid with <REDACTED> from <REDACTED>, password <REDACTED>"
+        );
+    }
+
+    #[test]
+    fn redacts_sensitive_fields_of_map_arguments() {
+        let mut redactor = redactor();
+        redactor.field_types = [
+            (
+                "Person".to_string(),
+                [
+                    ("name".to_string(), FieldType::string()),
+                    ("password".to_string(), FieldType::string()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            (
+                "Team".to_string(),
+                [(
+                    "members".to_string(),
+                    FieldType::List(Box::new(FieldType::class("Person"))),
+                )]
+                .into_iter()
+                .collect(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        redactor.param_types = [(
+            "Onboard".to_string(),
+            [
+                (
+                    "person".to_string(),
+                    FieldType::Optional(Box::new(FieldType::class("Person"))),
+                ),
+                ("team".to_string(), FieldType::class("Team")),
+            ]
+            .into_iter()
+            .collect(),
+        )]
+        .into_iter()
+        .collect();
+
+        // As the TypeScript, Ruby and C clients and `baml-cli serve` pass them.
+        let person = |password: &str| {
+            BamlValue::Map(
+                [
+                    ("name".to_string(), BamlValue::String("Ada".to_string())),
+                    (
+                        "password".to_string(),
+                        BamlValue::String(password.to_string()),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+        };
+        let params = [
+            ("person".to_string(), person("hunter22")),
+            (
+                "team".to_string(),
+                BamlValue::Map(
+                    [(
+                        "members".to_string(),
+                        BamlValue::List(vec![person("swordfish")]),
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let mut sensitive_values = vec![];
+        let redacted = redactor.redact_params("Onboard", &params, &mut sensitive_values);
+        assert_eq!(
+            serde_json::to_value(&redacted).unwrap(),
+            serde_json::json!({
+                "person": {"name": "Ada", "password": "<REDACTED>"},
+                "team": {"members": [{"name": "Ada", "password": "<REDACTED>"}]},
+            })
+        );
+        assert_eq!(
+            sensitive_values,
+            vec!["hunter22".to_string(), "swordfish".to_string()]
+        );
+    }
+
+    #[test]
+    fn redacts_resolved_secrets() {
+        use baml_types::ResolveSecret;
+
+        let secrets = Arc::new(SecretStore::default());
+        secrets
+            .register("vault", Arc::new(|_| Ok("sk-live-1234".to_string())))
+            .unwrap();
+        let mut redactor = Redactor::new(&[], None, "<REDACTED>").unwrap();
+        redactor.set_secrets(secrets.clone());
+        assert_eq!(
+            redactor.redact_text("Invalid key sk-live-1234", &[]),
+            "Invalid key sk-live-1234"
+        );
+
+        secrets.resolve_secret("vault:openai").unwrap().unwrap();
+        assert_eq!(
+            redactor.redact_text("Invalid key sk-live-1234", &[]),
+            "Invalid key <REDACTED>"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_patterns() {
+        assert!(Redactor::new(&["zipcode".to_string()], None, "<REDACTED>").is_err());
+        assert!(Redactor::new(&[], Some("not json"), "<REDACTED>").is_err());
+    }
+}
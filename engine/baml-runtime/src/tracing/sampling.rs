@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use super::api_wrapper::{core_types::LogSchema, TraceSamplingConfig};
+
+/// Traces waiting for their root span beyond this many are not kept for tail
+/// sampling, so spans that never get a root can't grow the buffer unbounded.
+const MAX_PENDING_TRACES: usize = 10_000;
+
+/// Decides which traces are uploaded.
+///
+/// Head sampling keeps a fraction of the traces, decided from the root event
+/// id so that all the spans of a trace get the same decision. Tail sampling
+/// keeps the other traces if they failed or were slow, which is only known
+/// once the root span finishes: spans finish before their parents, so the
+/// spans of those traces are held back until then.
+pub(super) struct TraceSampler {
+    config: TraceSamplingConfig,
+    /// Root event id to the spans of the trace that finished so far.
+    pending: HashMap<String, Vec<LogSchema>>,
+}
+
+impl TraceSampler {
+    pub fn new(config: TraceSamplingConfig) -> Self {
+        Self {
+            config,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Returns the events to upload now that `event` finished.
+    pub fn submit(&mut self, event: LogSchema) -> Vec<LogSchema> {
+        if self.config.keeps_everything() || self.head_sampled(&event.root_event_id) {
+            return vec![event];
+        }
+        if !self.config.keep_errors && self.config.slow_ms.is_none() {
+            return vec![];
+        }
+
+        if event.parent_event_id.is_some() {
+            if self.pending.len() < MAX_PENDING_TRACES
+                || self.pending.contains_key(&event.root_event_id)
+            {
+                self.pending
+                    .entry(event.root_event_id.clone())
+                    .or_default()
+                    .push(event);
+            } else {
+                log::debug!(
+                    "Too many traces pending a sampling decision, dropping span {}",
+                    event.event_id
+                );
+            }
+            return vec![];
+        }
+
+        let mut trace = self
+            .pending
+            .remove(&event.root_event_id)
+            .unwrap_or_default();
+        let slow = self
+            .config
+            .slow_ms
+            .is_some_and(|slow_ms| event.context.latency_ms >= slow_ms as i128);
+        trace.push(event);
+        let failed = trace.iter().any(|span| span.error.is_some());
+
+        if slow || (self.config.keep_errors && failed) {
+            trace
+        } else {
+            vec![]
+        }
+    }
+
+    fn head_sampled(&self, root_event_id: &str) -> bool {
+        (trace_hash(root_event_id) as f64 / u64::MAX as f64) < self.config.rate
+    }
+}
+
+/// The hash head sampling is decided from: 64-bit FNV-1a of the UTF-8 root
+/// event id, mixed with the MurmurHash3 finalizer so that ids differing only
+/// in their last characters spread evenly. It must not change, nor depend on
+/// the process or the Rust version: every process and SDK that records spans
+/// of a trace has to make the same decision for it.
+fn trace_hash(root_event_id: &str) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in root_event_id.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^= hash >> 33;
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tracing::api_wrapper::core_types::{Error, EventType, LogSchemaContext, IO};
+
+    fn span(
+        root: &str,
+        id: &str,
+        parent: Option<&str>,
+        latency_ms: i128,
+        failed: bool,
+    ) -> LogSchema {
+        LogSchema {
+            project_id: None,
+            event_type: EventType::FuncLlm,
+            root_event_id: root.to_string(),
+            event_id: id.to_string(),
+            parent_event_id: parent.map(str::to_string),
+            context: LogSchemaContext {
+                hostname: "host".to_string(),
+                process_id: "session".to_string(),
+                stage: None,
+                latency_ms,
+                start_time: "2024-01-01T00:00:00Z".to_string(),
+                tags: Default::default(),
+                event_chain: vec![],
+            },
+            io: IO {
+                input: None,
+                output: None,
+            },
+            error: failed.then(|| Error {
+                code: 2,
+                message: "failed".to_string(),
+                traceback: None,
+                r#override: None,
+            }),
+            metadata: None,
+        }
+    }
+
+    fn event_ids(events: Vec<LogSchema>) -> Vec<String> {
+        events.into_iter().map(|e| e.event_id).collect()
+    }
+
+    #[test]
+    fn keeps_failed_and_slow_traces_that_were_not_sampled() {
+        let mut sampler = TraceSampler::new(TraceSamplingConfig {
+            rate: 0.0,
+            keep_errors: true,
+            slow_ms: Some(1000),
+        });
+
+        // A failed child keeps the whole trace.
+        assert!(sampler
+            .submit(span("a", "a1", Some("a"), 10, true))
+            .is_empty());
+        assert_eq!(
+            event_ids(sampler.submit(span("a", "a", None, 20, false))),
+            vec!["a1", "a"]
+        );
+
+        // Fast and successful traces are dropped.
+        assert!(sampler
+            .submit(span("b", "b1", Some("b"), 10, false))
+            .is_empty());
+        assert!(sampler.submit(span("b", "b", None, 20, false)).is_empty());
+
+        // Slow traces are kept.
+        assert_eq!(
+            event_ids(sampler.submit(span("c", "c", None, 1500, false))),
+            vec!["c"]
+        );
+        assert!(sampler.pending.is_empty());
+    }
+
+    #[test]
+    fn head_sampling_decisions_are_stable() {
+        assert_eq!(trace_hash("trace-0"), 0xaf8e0c4f282468a6);
+        assert_eq!(
+            trace_hash("0e8f9a52-1f1c-4a36-9c0b-7d7e5f2a3b10"),
+            0x4e775636dc49a589
+        );
+
+        let sampler = TraceSampler::new(TraceSamplingConfig {
+            rate: 0.5,
+            keep_errors: false,
+            slow_ms: None,
+        });
+        for (root_event_id, kept) in [
+            ("trace-0", false),
+            ("trace-1", false),
+            ("trace-2", true),
+            ("trace-3", true),
+            ("0e8f9a52-1f1c-4a36-9c0b-7d7e5f2a3b10", true),
+            ("3f2c8b1e-5d4a-4e7b-8a9c-1b2d3e4f5a6b", true),
+            ("9b7e1c3d-2a4f-4b6e-8d0c-5f1a2b3c4d5e", false),
+        ] {
+            assert_eq!(sampler.head_sampled(root_event_id), kept, "{root_event_id}");
+        }
+    }
+
+    #[test]
+    fn head_sampling_is_decided_per_trace() {
+        let mut sampler = TraceSampler::new(TraceSamplingConfig {
+            rate: 0.5,
+            keep_errors: false,
+            slow_ms: None,
+        });
+
+        let kept = (0..1000)
+            .filter(|i| {
+                let root = format!("trace-{}", i);
+                let child = sampler.submit(span(&root, "child", Some(&root), 10, false));
+                let parent = sampler.submit(span(&root, &root, None, 10, false));
+                assert_eq!(child.len(), parent.len());
+                !parent.is_empty()
+            })
+            .count();
+        assert!((400..600).contains(&kept), "kept {} of 1000 traces", kept);
+    }
+}
@@ -9,7 +9,11 @@ use crate::{
     TraceStats,
 };
 
-use super::api_wrapper::{core_types::LogSchema, APIConfig, APIWrapper, BoundaryAPI};
+use super::{
+    api_wrapper::{core_types::LogSchema, APIConfig, APIWrapper, BoundaryAPI},
+    sampling::TraceSampler,
+    trace_sink::TraceSink,
+};
 
 const MAX_TRACE_SEND_CONCURRENCY: usize = 10;
 
@@ -33,6 +37,8 @@ struct DeliveryThread {
     max_batch_size: usize,
     max_concurrency: Arc<tokio::sync::Semaphore>,
     stats: TraceStats,
+    trace_sink: Option<TraceSink>,
+    sampler: TraceSampler,
 }
 
 impl DeliveryThread {
@@ -44,6 +50,8 @@ impl DeliveryThread {
         stats: TraceStats,
     ) -> Self {
         let rt = tokio::runtime::Runtime::new().unwrap();
+        let trace_sink = api_config.config.trace_sink().cloned().map(TraceSink::new);
+        let sampler = TraceSampler::new(api_config.config.trace_sampling().clone());
 
         Self {
             api_config: Arc::new(api_config),
@@ -53,6 +61,8 @@ impl DeliveryThread {
             max_batch_size,
             max_concurrency: tokio::sync::Semaphore::new(MAX_TRACE_SEND_CONCURRENCY).into(),
             stats,
+            trace_sink,
+            sampler,
         }
     }
 
@@ -95,7 +105,7 @@ impl DeliveryThread {
         futures::future::join_all(work).await;
     }
 
-    fn run(&self) {
+    fn run(&mut self) {
         let mut batch = Vec::with_capacity(self.max_batch_size);
         let mut now = Instant::now();
         loop {
@@ -104,7 +114,12 @@ impl DeliveryThread {
                 match self.span_rx.recv_timeout(Duration::from_millis(100)) {
                     Ok(TxEventSignal::Submit(work)) => {
                         self.stats.guard().submit();
-                        batch.push(work);
+                        if let Some(sink) = &mut self.trace_sink {
+                            if let Err(e) = sink.write(&work) {
+                                log::warn!("Unable to write BAML trace to disk: {:#}", e);
+                            }
+                        }
+                        batch.extend(self.sampler.submit(work));
                         (batch.len() >= self.max_batch_size, None, false)
                     }
                     Ok(TxEventSignal::Flush(id)) => (false, Some(id), false),
@@ -285,8 +300,6 @@ impl ThreadedTracer {
             log_event_result?;
         }
 
-        // TODO: do the redaction
-
         // Redact the event
         event = redact_event(event, &self.api_config.config);
 
@@ -0,0 +1,156 @@
+//! Writes traces to rotating JSONL files, one `LogSchema` per line, so they
+//! can be inspected with `baml-cli traces` without Boundary Cloud.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use anyhow::{Context, Result};
+
+use super::api_wrapper::{core_types::LogSchema, TraceSinkConfig};
+
+pub(super) struct TraceSink {
+    config: TraceSinkConfig,
+    /// The file being written and how big it is.
+    file: Option<(File, u64)>,
+}
+
+impl TraceSink {
+    pub fn new(config: TraceSinkConfig) -> Self {
+        Self { config, file: None }
+    }
+
+    pub fn write(&mut self, event: &LogSchema) -> Result<()> {
+        let mut line = serde_json::to_vec(event)?;
+        line.push(b'\n');
+        let len = line.len() as u64;
+
+        let full = match &self.file {
+            Some((_, size)) => *size > 0 && size + len > self.config.max_file_bytes,
+            None => true,
+        };
+        if full {
+            self.rotate()?;
+        }
+
+        let Some((file, size)) = self.file.as_mut() else {
+            unreachable!("rotate opens a file");
+        };
+        file.write_all(&line)?;
+        *size += len;
+        Ok(())
+    }
+
+    /// Starts a new file, and deletes the oldest ones beyond `max_files`.
+    fn rotate(&mut self) -> Result<()> {
+        let dir = &self.config.dir;
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create trace directory {}", dir.display()))?;
+
+        let millis = web_time::SystemTime::now()
+            .duration_since(web_time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        // Zero-padded, so the names sort by age.
+        let path = dir.join(format!(
+            "traces-{:013}-{}.jsonl",
+            millis,
+            std::process::id()
+        ));
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open trace file {}", path.display()))?;
+        self.file = Some((file, 0));
+
+        let mut files = trace_files(dir)?;
+        while files.len() > self.config.max_files.max(1) {
+            let oldest = files.remove(0);
+            if let Err(e) = fs::remove_file(&oldest) {
+                log::debug!(
+                    "Failed to remove old trace file {}: {}",
+                    oldest.display(),
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The trace files in `dir`, oldest first.
+fn trace_files(dir: &std::path::Path) -> Result<Vec<PathBuf>> {
+    let mut files = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("traces-") && name.ends_with(".jsonl"))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::tracing::api_wrapper::core_types::{EventType, LogSchemaContext, IO};
+
+    fn event(id: &str) -> LogSchema {
+        LogSchema {
+            project_id: None,
+            event_type: EventType::FuncLlm,
+            root_event_id: id.to_string(),
+            event_id: id.to_string(),
+            parent_event_id: None,
+            context: LogSchemaContext {
+                hostname: "test".to_string(),
+                process_id: "session".to_string(),
+                stage: None,
+                latency_ms: 0,
+                start_time: "2024-01-01T00:00:00Z".to_string(),
+                tags: HashMap::new(),
+                event_chain: vec![],
+            },
+            io: IO {
+                input: None,
+                output: None,
+            },
+            error: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn rotates_and_keeps_the_newest_files() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("baml-traces-{}", uuid::Uuid::new_v4()));
+        let line_len = serde_json::to_vec(&event("0"))?.len() as u64 + 1;
+        let mut sink = TraceSink::new(TraceSinkConfig {
+            dir: dir.clone(),
+            max_file_bytes: line_len * 2,
+            max_files: 2,
+        });
+
+        for i in 0..5 {
+            sink.write(&event(&i.to_string()))?;
+            // Files are named by the millisecond they were started in.
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+
+        let files = trace_files(&dir)?;
+        let lines = files
+            .iter()
+            .map(|f| Ok(fs::read_to_string(f)?.lines().count()))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(lines, vec![2, 1]);
+
+        fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     collections::HashMap,
+    path::PathBuf,
     sync::{Arc, Mutex},
 };
 
@@ -7,7 +8,10 @@ use anyhow::{Context, Result};
 use baml_types::BamlValue;
 use std::fmt;
 
-use crate::{client_registry::ClientRegistry, type_builder::TypeBuilder, RuntimeContext, SpanCtx};
+use crate::{
+    client_registry::ClientRegistry, type_builder::TypeBuilder, RuntimeContext, SecretStore,
+    SpanCtx,
+};
 
 use super::runtime_context::BamlSrcReader;
 
@@ -16,8 +20,10 @@ type BamlContext = (uuid::Uuid, String, HashMap<String, BamlValue>);
 #[derive(Clone)]
 pub struct RuntimeContextManager {
     baml_src_reader: Arc<BamlSrcReader>,
+    baml_src_dir: Option<PathBuf>,
     context: Arc<Mutex<Vec<BamlContext>>>,
     env_vars: HashMap<String, String>,
+    secrets: Arc<SecretStore>,
     global_tags: Arc<Mutex<HashMap<String, BamlValue>>>,
 }
 
@@ -34,9 +40,11 @@ impl RuntimeContextManager {
     pub fn deep_clone(&self) -> Self {
         Self {
             baml_src_reader: self.baml_src_reader.clone(),
+            baml_src_dir: self.baml_src_dir.clone(),
 
             context: Arc::new(Mutex::new(self.context.lock().unwrap().clone())),
             env_vars: self.env_vars.clone(),
+            secrets: self.secrets.clone(),
             global_tags: Arc::new(Mutex::new(self.global_tags.lock().unwrap().clone())),
         }
     }
@@ -47,12 +55,29 @@ impl RuntimeContextManager {
     ) -> Self {
         Self {
             baml_src_reader: Arc::new(baml_src_reader),
+            baml_src_dir: None,
             context: Default::default(),
             env_vars,
+            secrets: Default::default(),
             global_tags: Default::default(),
         }
     }
 
+    /// Resolves the secret references in the environment variables with
+    /// `secrets`, the store of the runtime, so they are cached and redacted
+    /// from its traces.
+    pub fn with_secrets(mut self, secrets: Arc<SecretStore>) -> Self {
+        self.secrets = secrets;
+        self
+    }
+
+    /// Sets the directory that relative paths in client options, like
+    /// `http.ca_cert`, are resolved against.
+    pub fn with_baml_src_dir(mut self, baml_src_dir: PathBuf) -> Self {
+        self.baml_src_dir = Some(baml_src_dir);
+        self
+    }
+
     pub fn upsert_tags(&self, tags: HashMap<String, BamlValue>) {
         let mut ctx = self.context.lock().unwrap();
         if let Some((.., last_tags)) = ctx.last_mut() {
@@ -62,6 +87,15 @@ impl RuntimeContextManager {
         }
     }
 
+    /// Nests the spans of this context under `span_id`, a span recorded by
+    /// another process, e.g. the caller of `baml-cli serve`.
+    pub fn set_parent_span(&self, span_id: uuid::Uuid, name: &str) {
+        self.context
+            .lock()
+            .unwrap()
+            .insert(0, (span_id, name.to_string(), Default::default()));
+    }
+
     fn clone_last_tags(&self) -> HashMap<String, BamlValue> {
         self.context
             .lock()
@@ -130,11 +164,13 @@ impl RuntimeContextManager {
         let mut ctx = RuntimeContext::new(
             self.baml_src_reader.clone(),
             self.env_vars.clone(),
+            self.secrets.clone(),
             tags,
             Default::default(),
             cls,
             enm,
-        );
+        )
+        .with_baml_src_dir(self.baml_src_dir.clone());
 
         let client_overrides = match cb {
             Some(cb) => Some(
@@ -155,11 +191,13 @@ impl RuntimeContextManager {
         RuntimeContext::new(
             self.baml_src_reader.clone(),
             self.env_vars.clone(),
+            self.secrets.clone(),
             ctx.last().map(|(.., x)| x).cloned().unwrap_or_default(),
             Default::default(),
             Default::default(),
             Default::default(),
         )
+        .with_baml_src_dir(self.baml_src_dir.clone())
     }
 
     pub fn context_depth(&self) -> usize {
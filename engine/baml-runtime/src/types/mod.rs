@@ -3,12 +3,14 @@ mod context_manager;
 pub mod on_log_event;
 mod response;
 pub(crate) mod runtime_context;
+mod secrets;
 mod stream;
 mod trace_stats;
 
 pub use context_manager::RuntimeContextManager;
 pub use response::{FunctionResult, TestFailReason, TestResponse, TestStatus};
 pub use runtime_context::{RuntimeContext, SpanCtx};
+pub use secrets::{SecretResolverFn, SecretStore, REDACTED_SECRET};
 pub use stream::FunctionResultStream;
 pub use trace_stats::{InnerTraceStats, TraceStats};
 
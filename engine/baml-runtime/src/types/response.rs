@@ -2,12 +2,15 @@ pub use crate::internal::llm_client::LLMResponse;
 use crate::{
     constraints::TestConstraintsResult,
     errors::ExposedError,
-    internal::llm_client::{orchestrator::OrchestrationScope, ResponseBamlValue},
+    internal::llm_client::{
+        orchestrator::{ExecutionScope, OrchestrationScope},
+        ResponseBamlValue,
+    },
 };
 use anyhow::Result;
 use colored::*;
 
-use baml_types::BamlValue;
+use baml_types::{BamlMap, BamlValue};
 use jsonish::BamlValueWithFlags;
 
 #[derive(Debug)]
@@ -23,11 +26,20 @@ pub struct FunctionResult {
 impl std::fmt::Display for FunctionResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // print out the number of previous tries only if there was more than 1
-        if self.event_chain.len() > 1 {
+        let candidates = self.candidates().len();
+        let previous_tries = self.event_chain.len() - candidates.max(1);
+        if previous_tries > 0 {
             writeln!(
                 f,
                 "{}",
-                format!("({} other previous tries)", self.event_chain.len() - 1).yellow()
+                format!("({} other previous tries)", previous_tries).yellow()
+            )?;
+        }
+        if candidates > 0 {
+            writeln!(
+                f,
+                "{}",
+                format!("(selected from {} candidates)", candidates).yellow()
             )?;
         }
         writeln!(f, "{}", self.llm_response())?;
@@ -99,6 +111,46 @@ impl FunctionResult {
         &self.event_chain.last().unwrap().0
     }
 
+    /// All the responses sampled by the final attempt when the function asks
+    /// for several `candidates`, with the selected one last. Empty otherwise.
+    pub fn candidates(
+        &self,
+    ) -> &[(
+        OrchestrationScope,
+        LLMResponse,
+        Option<Result<BamlValueWithFlags>>,
+        Option<Result<ResponseBamlValue>>,
+    )] {
+        let count = match self.scope().scope.last() {
+            Some(ExecutionScope::Candidate(_, count)) => (*count).min(self.event_chain.len()),
+            _ => 0,
+        };
+        &self.event_chain[self.event_chain.len() - count..]
+    }
+
+    /// Each of the [`candidates`](Self::candidates) as a result of its own,
+    /// for the language clients. Errors are copied as their message.
+    pub fn candidate_results(&self) -> Vec<FunctionResult> {
+        fn copy<T: Clone>(result: &Result<T>) -> Result<T> {
+            match result {
+                Ok(value) => Ok(value.clone()),
+                Err(e) => Err(anyhow::anyhow!("{:#}", e)),
+            }
+        }
+
+        self.candidates()
+            .iter()
+            .map(|(scope, response, parsed, value)| {
+                FunctionResult::new(
+                    scope.clone(),
+                    response.clone(),
+                    parsed.as_ref().map(copy),
+                    value.as_ref().map(copy),
+                )
+            })
+            .collect()
+    }
+
     pub fn parsed(&self) -> &Option<Result<BamlValueWithFlags>> {
         &self.event_chain.last().unwrap().2
     }
@@ -186,6 +238,8 @@ pub struct TestResponse {
     pub function_response: FunctionResult,
     pub function_span: Option<uuid::Uuid>,
     pub constraints_result: TestConstraintsResult,
+    /// The arguments the function was called with.
+    pub params: BamlMap<String, BamlValue>,
 }
 
 impl std::fmt::Display for TestResponse {
@@ -243,6 +297,32 @@ impl PartialEq for TestFailReason<'_> {
 
 impl Eq for TestFailReason<'_> {}
 
+impl std::fmt::Display for TestFailReason<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TestUnspecified(e) => write!(f, "{:#}", e),
+            Self::TestLLMFailure(response) => write!(f, "{}", response),
+            Self::TestParseFailure(e) | Self::TestFinishReasonFailed(e) => write!(f, "{:#}", e),
+            Self::TestConstraintsFailure {
+                checks,
+                failed_assert,
+            } => {
+                let failures = failed_assert
+                    .iter()
+                    .map(|name| format!("Assertion failed: {}", name))
+                    .chain(
+                        checks
+                            .iter()
+                            .filter(|(_, passed)| !passed)
+                            .map(|(name, _)| format!("Check failed: {}", name)),
+                    )
+                    .collect::<Vec<_>>();
+                write!(f, "{}", failures.join("\n"))
+            }
+        }
+    }
+}
+
 impl TestResponse {
     pub fn status(&self) -> TestStatus {
         let func_res = &self.function_response;
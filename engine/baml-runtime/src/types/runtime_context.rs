@@ -2,10 +2,12 @@ use anyhow::Result;
 use baml_types::{BamlValue, EvaluationContext, UnresolvedValue};
 use indexmap::IndexMap;
 use internal_baml_core::ir::FieldType;
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use crate::internal::llm_client::llm_provider::LLMProvider;
 
+use super::SecretStore;
+
 #[derive(Debug, Clone)]
 pub struct SpanCtx {
     pub span_id: uuid::Uuid,
@@ -51,7 +53,11 @@ cfg_if::cfg_if!(
 pub struct RuntimeContext {
     // path to baml_src in the local filesystem
     pub baml_src: Arc<BamlSrcReader>,
+    // the baml_src directory, which relative paths in client options are
+    // resolved against
+    baml_src_dir: Option<PathBuf>,
     env: HashMap<String, String>,
+    secrets: Arc<SecretStore>,
     pub tags: HashMap<String, BamlValue>,
     pub client_overrides: Option<(Option<String>, HashMap<String, Arc<LLMProvider>>)>,
     pub class_override: IndexMap<String, RuntimeClassOverride>,
@@ -60,13 +66,26 @@ pub struct RuntimeContext {
 
 impl RuntimeContext {
     pub fn eval_ctx(&self, strict: bool) -> EvaluationContext<'_> {
-        EvaluationContext::new(&self.env, !strict)
+        EvaluationContext::new(&self.env, !strict).with_secrets(&*self.secrets)
+    }
+
+    pub fn secrets(&self) -> &Arc<SecretStore> {
+        &self.secrets
     }
 
     pub fn env_vars(&self) -> &HashMap<String, String> {
         &self.env
     }
 
+    pub fn baml_src_dir(&self) -> Option<&std::path::Path> {
+        self.baml_src_dir.as_deref()
+    }
+
+    pub(crate) fn with_baml_src_dir(mut self, baml_src_dir: Option<PathBuf>) -> Self {
+        self.baml_src_dir = baml_src_dir;
+        self
+    }
+
     pub fn proxy_url(&self) -> Option<&str> {
         self.env.get("BOUNDARY_PROXY_URL").map(|s| s.as_str())
     }
@@ -74,6 +93,7 @@ impl RuntimeContext {
     pub fn new(
         baml_src: Arc<BamlSrcReader>,
         env: HashMap<String, String>,
+        secrets: Arc<SecretStore>,
         tags: HashMap<String, BamlValue>,
         client_overrides: Option<(Option<String>, HashMap<String, Arc<LLMProvider>>)>,
         class_override: IndexMap<String, RuntimeClassOverride>,
@@ -81,7 +101,9 @@ impl RuntimeContext {
     ) -> RuntimeContext {
         RuntimeContext {
             baml_src,
+            baml_src_dir: None,
             env,
+            secrets,
             tags,
             client_overrides,
             class_override,
@@ -96,7 +118,7 @@ impl RuntimeContext {
         // otherwise, will return a value with the missing environment variables replaced with the string "${key}"
         strict: bool,
     ) -> Result<T> {
-        let ctx = EvaluationContext::new(&self.env, strict);
+        let ctx = EvaluationContext::new(&self.env, strict).with_secrets(&*self.secrets);
         match expr.resolve_serde::<T>(&ctx) {
             Ok(v) => Ok(v),
             Err(e) => anyhow::bail!(
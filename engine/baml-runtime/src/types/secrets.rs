@@ -0,0 +1,514 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
+
+use anyhow::Result;
+use baml_types::ResolveSecret;
+use web_time::{Duration, Instant};
+
+/// Fetches a secret from its location, the part of the reference after the
+/// scheme, e.g. `secret/openai` for `vault:secret/openai`.
+pub type SecretResolverFn = Arc<dyn Fn(&str) -> Result<String> + Send + Sync>;
+
+/// Schemes that can't be registered, whether or not they are supported on
+/// this platform.
+const BUILTIN_SCHEMES: &[&str] = &["file", "exec"];
+
+/// Set to `1` or `true` to allow `exec:` references. Off by default, since
+/// anything that can set an environment variable could run commands.
+#[cfg(not(target_arch = "wasm32"))]
+const ALLOW_EXEC_ENV_VAR: &str = "BAML_SECRETS_ALLOW_EXEC";
+
+/// How long a resolved secret is reused for, unless set with
+/// `BAML_SECRETS_TTL_SECS`.
+const DEFAULT_TTL: Duration = Duration::from_secs(300);
+
+/// Resolved secrets shorter than this are not redacted, where they would
+/// match unrelated text.
+const MIN_REDACTED_SECRET_CHARS: usize = 4;
+
+/// Replaces resolved secrets in rendered requests.
+pub const REDACTED_SECRET: &str = "<BAML_SECRET_REDACTED>";
+
+struct CachedSecret {
+    value: String,
+    expires_at: Instant,
+}
+
+/// Resolves environment variables whose value is a secret reference:
+///
+/// - `file:<path>`: the contents of the file.
+/// - `exec:<command>`: the output of the command, e.g. a credential helper.
+///   Only with [`SecretStore::with_exec`].
+/// - `<scheme>:<location>`: the secret returned by the resolver registered
+///   for `scheme` with [`SecretStore::register`].
+///
+/// Trailing newlines are stripped from the secret. Values with any other
+/// scheme, e.g. `https://...`, are used as is.
+///
+/// Resolved secrets are cached for the store's TTL, and remembered so they
+/// can be redacted from traces and rendered requests. Each reference is
+/// resolved by one caller at a time; the others wait for its secret.
+pub struct SecretStore {
+    resolvers: RwLock<HashMap<String, SecretResolverFn>>,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, CachedSecret>>,
+    /// Held while a reference is being resolved.
+    resolving: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    /// The latest secret of every reference resolved so far, including the
+    /// ones whose cache expired: clients created with them may still use
+    /// them.
+    resolved: RwLock<HashMap<String, String>>,
+    version: AtomicU64,
+}
+
+impl std::fmt::Debug for SecretStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never prints the secrets.
+        f.debug_struct("SecretStore")
+            .field(
+                "schemes",
+                &self.resolvers.read().unwrap().keys().collect::<Vec<_>>(),
+            )
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl Default for SecretStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+impl SecretStore {
+    pub fn new(ttl: Duration) -> Self {
+        // Files and commands can't be read or run from the browser.
+        #[cfg(not(target_arch = "wasm32"))]
+        let resolvers =
+            HashMap::from([("file".to_string(), Arc::new(read_file) as SecretResolverFn)]);
+        #[cfg(target_arch = "wasm32")]
+        let resolvers = HashMap::new();
+
+        Self {
+            resolvers: RwLock::new(resolvers),
+            ttl,
+            cache: Default::default(),
+            resolving: Default::default(),
+            resolved: Default::default(),
+            version: AtomicU64::new(0),
+        }
+    }
+
+    /// Resolves `exec:<command>` references by running the command.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_exec(self) -> Self {
+        self.resolvers
+            .write()
+            .unwrap()
+            .insert("exec".to_string(), Arc::new(run_command));
+        self
+    }
+
+    /// A store with the TTL set by `BAML_SECRETS_TTL_SECS`. `0` disables the
+    /// cache. `exec:` references are resolved if `BAML_SECRETS_ALLOW_EXEC` is
+    /// set.
+    pub fn from_env_vars(env_vars: &HashMap<String, String>) -> Result<Self> {
+        let ttl = match env_vars.get("BAML_SECRETS_TTL_SECS") {
+            Some(secs) => Duration::from_secs(secs.trim().parse().map_err(|_| {
+                anyhow::anyhow!(
+                    "BAML_SECRETS_TTL_SECS must be a number of seconds, got `{}`",
+                    secs
+                )
+            })?),
+            None => DEFAULT_TTL,
+        };
+        let store = Self::new(ttl);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if env_vars
+            .get(ALLOW_EXEC_ENV_VAR)
+            .is_some_and(|v| matches!(v.trim(), "1" | "true"))
+        {
+            return Ok(store.with_exec());
+        }
+        Ok(store)
+    }
+
+    /// Resolves references to `<scheme>:<location>` with `resolver`,
+    /// replacing the resolver previously registered for `scheme`.
+    pub fn register(&self, scheme: &str, resolver: SecretResolverFn) -> Result<()> {
+        if BUILTIN_SCHEMES.contains(&scheme) {
+            anyhow::bail!("The secret scheme `{}` is built in", scheme);
+        }
+        if scheme.is_empty()
+            || !scheme
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        {
+            anyhow::bail!(
+                "Invalid secret scheme `{}`: expected lowercase letters, digits, `-` and `_`",
+                scheme
+            );
+        }
+
+        self.resolvers
+            .write()
+            .unwrap()
+            .insert(scheme.to_string(), resolver);
+        self.refresh();
+        Ok(())
+    }
+
+    /// Registers the resolvers registered with `other`, e.g. when the
+    /// runtime is recreated with new environment variables.
+    pub fn inherit_resolvers(&self, other: &SecretStore) {
+        // Whether `exec:` is allowed depends on the new environment.
+        let registered = other
+            .resolvers
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|(scheme, _)| !BUILTIN_SCHEMES.contains(&scheme.as_str()))
+            .map(|(scheme, resolver)| (scheme.clone(), resolver.clone()))
+            .collect::<Vec<_>>();
+        self.resolvers.write().unwrap().extend(registered);
+    }
+
+    /// Forgets the cached secrets, so they are resolved again when next
+    /// used.
+    pub fn refresh(&self) {
+        self.cache.lock().unwrap().clear();
+        self.version.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Changes whenever a cached secret expires or the store is refreshed.
+    /// Values built from resolved secrets, e.g. clients, must be rebuilt
+    /// when it does.
+    pub fn version(&self) -> u64 {
+        let now = Instant::now();
+        let mut cache = self.cache.lock().unwrap();
+        let cached = cache.len();
+        cache.retain(|_, secret| secret.expires_at > now);
+        if cache.len() != cached {
+            self.version.fetch_add(1, Ordering::SeqCst);
+        }
+        self.version.load(Ordering::SeqCst)
+    }
+
+    /// Replaces the secrets resolved so far in `text` with `placeholder`.
+    pub fn redact(&self, text: &str, placeholder: &str) -> String {
+        let resolved = self.resolved.read().unwrap();
+        let mut text = text.to_string();
+        for secret in resolved.values() {
+            if secret.chars().count() >= MIN_REDACTED_SECRET_CHARS {
+                text = text.replace(secret.as_str(), placeholder);
+            }
+        }
+        text
+    }
+
+    pub fn has_resolved(&self) -> bool {
+        !self.resolved.read().unwrap().is_empty()
+    }
+}
+
+impl SecretStore {
+    fn cached(&self, value: &str) -> Option<String> {
+        self.cache
+            .lock()
+            .unwrap()
+            .get(value)
+            .filter(|cached| cached.expires_at > Instant::now())
+            .map(|cached| cached.value.clone())
+    }
+
+    fn resolve_uncached(
+        &self,
+        value: &str,
+        location: &str,
+        resolver: &SecretResolverFn,
+    ) -> Result<String> {
+        let lock = self
+            .resolving
+            .lock()
+            .unwrap()
+            .entry(value.to_string())
+            .or_default()
+            .clone();
+        let _resolving = lock.lock().unwrap();
+
+        // Someone else may have resolved it while we waited.
+        if let Some(secret) = self.cached(value) {
+            return Ok(secret);
+        }
+
+        // Only this reference's lock is held while the resolver runs, so it
+        // can call back into the runtime.
+        let secret = resolver(location)?
+            .trim_end_matches(['\r', '\n'])
+            .to_string();
+        // A refreshed secret replaces the one it was resolved from, so
+        // rotated secrets aren't redacted forever.
+        self.resolved
+            .write()
+            .unwrap()
+            .insert(value.to_string(), secret.clone());
+        self.cache.lock().unwrap().insert(
+            value.to_string(),
+            CachedSecret {
+                value: secret.clone(),
+                expires_at: Instant::now() + self.ttl,
+            },
+        );
+        Ok(secret)
+    }
+}
+
+impl ResolveSecret for SecretStore {
+    fn resolve_secret(&self, value: &str) -> Option<Result<String>> {
+        let (scheme, location) = value.split_once(':')?;
+        let Some(resolver) = self.resolvers.read().unwrap().get(scheme).cloned() else {
+            #[cfg(not(target_arch = "wasm32"))]
+            if scheme == "exec" {
+                return Some(Err(anyhow::anyhow!(
+                    "`exec:` secrets are disabled, set {}=1 to allow them",
+                    ALLOW_EXEC_ENV_VAR
+                )));
+            }
+            return None;
+        };
+
+        if let Some(secret) = self.cached(value) {
+            return Some(Ok(secret));
+        }
+        Some(blocking(|| {
+            self.resolve_uncached(value, location, &resolver)
+        }))
+    }
+}
+
+/// Runs `f`, which may block for a while (reading files, running commands,
+/// waiting for other callers), without stalling the other tasks of the
+/// async worker thread it's called from.
+fn blocking<T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(not(target_arch = "wasm32"))]
+    if tokio::runtime::Handle::try_current()
+        .is_ok_and(|h| h.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread)
+    {
+        return tokio::task::block_in_place(f);
+    }
+    f()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_file(path: &str) -> Result<String> {
+    std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path, e))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn run_command(command: &str) -> Result<String> {
+    // On a multi-threaded runtime this runs inside `blocking`, which lets us
+    // wait for the command on tokio's blocking pool.
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            let command = command.to_string();
+            handle
+                .block_on(tokio::task::spawn_blocking(move || {
+                    wait_for_command(&command)
+                }))
+                .map_err(|e| anyhow::anyhow!("Failed to run a secret command: {}", e))?
+        }
+        _ => wait_for_command(command),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn wait_for_command(command: &str) -> Result<String> {
+    #[cfg(windows)]
+    let output = std::process::Command::new("cmd")
+        .args(["/C", command])
+        .output();
+    #[cfg(not(windows))]
+    let output = std::process::Command::new("sh")
+        .args(["-c", command])
+        .output();
+
+    let output = output.map_err(|e| anyhow::anyhow!("Failed to run `{}`: {}", command, e))?;
+    if !output.status.success() {
+        // stderr is not included: credential helpers may print secrets to it.
+        anyhow::bail!("`{}` exited with {}", command, output.status);
+    }
+    String::from_utf8(output.stdout)
+        .map_err(|_| anyhow::anyhow!("`{}` printed a secret that is not UTF-8", command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counting_resolver(calls: Arc<AtomicU64>) -> SecretResolverFn {
+        Arc::new(move |location| {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!("{location}-secret-{n}\n"))
+        })
+    }
+
+    #[test]
+    fn resolves_registered_schemes() {
+        let store = SecretStore::default();
+        let calls = Arc::new(AtomicU64::new(0));
+        store
+            .register("vault", counting_resolver(calls.clone()))
+            .unwrap();
+
+        assert_eq!(
+            store.resolve_secret("vault:openai").unwrap().unwrap(),
+            "openai-secret-0"
+        );
+        assert!(store.resolve_secret("https://api.openai.com").is_none());
+        assert!(store.resolve_secret("sk-1234").is_none());
+
+        // Cached until refreshed.
+        assert_eq!(
+            store.resolve_secret("vault:openai").unwrap().unwrap(),
+            "openai-secret-0"
+        );
+        let version = store.version();
+        store.refresh();
+        assert_ne!(store.version(), version);
+        assert_eq!(
+            store.resolve_secret("vault:openai").unwrap().unwrap(),
+            "openai-secret-1"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn expires_cached_secrets() {
+        let store = SecretStore::new(Duration::ZERO);
+        let calls = Arc::new(AtomicU64::new(0));
+        store
+            .register("vault", counting_resolver(calls.clone()))
+            .unwrap();
+
+        store.resolve_secret("vault:openai").unwrap().unwrap();
+        store.resolve_secret("vault:openai").unwrap().unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn redacts_resolved_secrets() {
+        let store = SecretStore::default();
+        store
+            .register("vault", Arc::new(|location| Ok(format!("sk-{location}"))))
+            .unwrap();
+        assert!(!store.has_resolved());
+
+        store.resolve_secret("vault:abcd").unwrap().unwrap();
+        assert_eq!(
+            store.redact("Authorization: Bearer sk-abcd", REDACTED_SECRET),
+            "Authorization: Bearer <BAML_SECRET_REDACTED>"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_schemes() {
+        let store = SecretStore::default();
+        let resolver: SecretResolverFn = Arc::new(|_| Ok(String::new()));
+        assert!(store.register("file", resolver.clone()).is_err());
+        assert!(store.register("Vault", resolver.clone()).is_err());
+        assert!(store.register("", resolver).is_err());
+    }
+
+    #[test]
+    fn resolves_files_and_commands() {
+        let path = std::env::temp_dir().join(format!("baml-secret-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "sk-from-file\n").unwrap();
+
+        let store = SecretStore::default().with_exec();
+        assert_eq!(
+            store
+                .resolve_secret(&format!("file:{}", path.display()))
+                .unwrap()
+                .unwrap(),
+            "sk-from-file"
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        #[cfg(not(windows))]
+        {
+            assert_eq!(
+                store
+                    .resolve_secret("exec:echo sk-from-command")
+                    .unwrap()
+                    .unwrap(),
+                "sk-from-command"
+            );
+            assert!(store.resolve_secret("exec:exit 1").unwrap().is_err());
+        }
+    }
+
+    #[test]
+    fn runs_commands_only_when_allowed() {
+        let store = SecretStore::default();
+        let err = store.resolve_secret("exec:echo sk").unwrap().unwrap_err();
+        assert!(err.to_string().contains(ALLOW_EXEC_ENV_VAR), "{err}");
+        let resolver: SecretResolverFn = Arc::new(|_| Ok(String::new()));
+        assert!(store.register("exec", resolver).is_err());
+
+        let env_vars = HashMap::from([(ALLOW_EXEC_ENV_VAR.to_string(), "1".to_string())]);
+        let store = SecretStore::from_env_vars(&env_vars).unwrap();
+        #[cfg(not(windows))]
+        assert_eq!(store.resolve_secret("exec:echo sk").unwrap().unwrap(), "sk");
+    }
+
+    #[test]
+    fn resolves_each_reference_once() {
+        let store = Arc::new(SecretStore::default());
+        let calls = Arc::new(AtomicU64::new(0));
+        let counted = calls.clone();
+        store
+            .register(
+                "vault",
+                Arc::new(move |location| {
+                    counted.fetch_add(1, Ordering::SeqCst);
+                    std::thread::sleep(Duration::from_millis(50));
+                    Ok(format!("{location}-secret"))
+                }),
+            )
+            .unwrap();
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let store = store.clone();
+                std::thread::spawn(move || store.resolve_secret("vault:openai").unwrap().unwrap())
+            })
+            .collect();
+        for thread in threads {
+            assert_eq!(thread.join().unwrap(), "openai-secret");
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn forgets_replaced_secrets() {
+        let store = SecretStore::default();
+        let calls = Arc::new(AtomicU64::new(0));
+        store
+            .register("vault", counting_resolver(calls.clone()))
+            .unwrap();
+
+        store.resolve_secret("vault:openai").unwrap().unwrap();
+        store.refresh();
+        store.resolve_secret("vault:openai").unwrap().unwrap();
+        assert_eq!(
+            store.redact("openai-secret-0 openai-secret-1", REDACTED_SECRET),
+            "openai-secret-0 <BAML_SECRET_REDACTED>"
+        );
+    }
+}
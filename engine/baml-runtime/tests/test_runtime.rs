@@ -498,4 +498,33 @@ test TestTree {
 
         Ok(())
     }
+
+    #[test]
+    fn test_imports_are_read_from_the_ast() -> anyhow::Result<()> {
+        let root = std::env::temp_dir().join(format!("baml-imports-{}", uuid::Uuid::new_v4()));
+        let project = root.join("project/baml_src");
+        let shared = root.join("shared/baml_src");
+        std::fs::create_dir_all(&project)?;
+        std::fs::create_dir_all(&shared)?;
+        std::fs::write(
+            project.join("main.baml"),
+            r##"
+import "../../shared/baml_src" as shared
+
+template_string Docs #"
+import "../../missing" as missing
+"#
+"##,
+        )?;
+        std::fs::write(shared.join("resume.baml"), "class Resume {\n  name string\n}\n")?;
+
+        let files = baml_runtime::baml_src_files(&project);
+        std::fs::remove_dir_all(&root)?;
+
+        // The `import` in the template string is text, not an import.
+        let files = files?;
+        assert_eq!(files.len(), 2);
+        assert!(files.iter().any(|f| f.ends_with("shared/baml_src/resume.baml")));
+        Ok(())
+    }
 }
@@ -9,3 +9,12 @@ pub fn version() -> String {
     // register_panic_hook();
     env!("CARGO_PKG_VERSION").to_string()
 }
+
+/// Formats a single BAML file, for format-on-save in editors. Returns `None`
+/// if the file can't be parsed.
+#[wasm_bindgen]
+pub fn format_document(content: &str) -> Option<String> {
+    use internal_baml_core::internal_baml_schema_ast::{format_schema, FormatOptions};
+
+    format_schema(content, FormatOptions::default()).ok()
+}
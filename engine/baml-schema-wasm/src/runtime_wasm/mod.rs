@@ -379,6 +379,9 @@ pub struct WasmTestCase {
     pub span: WasmSpan,
     #[wasm_bindgen(readonly)]
     pub parent_functions: Vec<WasmParentFunction>,
+    /// The dataset file whose rows are run as cases of the test, if any.
+    #[wasm_bindgen(readonly)]
+    pub dataset: Option<String>,
 }
 
 #[wasm_bindgen(getter_with_clone, inspectable)]
@@ -1063,9 +1066,12 @@ impl WasmRuntime {
                                 Err(e) => (Vec::new(), Some(e)),
                             };
 
-                            // Any missing params should be set to an error
+                            // Any missing params should be set to an error, unless
+                            // they are read from a dataset.
                             f.inputs().iter().for_each(|(param_name, t)| {
-                                if !params.iter().any(|p| p.name == *param_name) && !t.is_optional()
+                                if tc.dataset().is_none()
+                                    && !params.iter().any(|p| p.name == *param_name)
+                                    && !t.is_optional()
                                 {
                                     params.insert(
                                         0,
@@ -1105,6 +1111,9 @@ impl WasmRuntime {
                                         }
                                     })
                                     .collect(),
+                                dataset: tc
+                                    .dataset()
+                                    .map(|d| d.path.to_string_lossy().into_owned()),
                             }
                         })
                         .collect(),
@@ -1377,11 +1386,14 @@ impl WasmRuntime {
                     Ok(p) => (p, None),
                     Err(e) => (Vec::new(), Some(e)),
                 };
-                // Any missing params should be set to an error
-                // Any missing params should be set to an error
+                // Any missing params should be set to an error, unless they
+                // are read from a dataset.
                 tc.function().inputs().iter().for_each(|func_params| {
                     let (param_name, t) = func_params;
-                    if !params.iter().any(|p| p.name == *param_name) && !t.is_optional() {
+                    if tc.dataset().is_none()
+                        && !params.iter().any(|p| p.name == *param_name)
+                        && !t.is_optional()
+                    {
                         params.push(WasmParam {
                             name: param_name.to_string(),
                             value: None,
@@ -1416,6 +1428,7 @@ impl WasmRuntime {
                             }
                         })
                         .collect(),
+                    dataset: tc.dataset().map(|d| d.path.to_string_lossy().into_owned()),
                 }
             })
             .collect()
@@ -1556,13 +1569,18 @@ impl WasmFunction {
             .get_test_params(&self.name, &test_name, &ctx, false)
             .map_err(|e| JsError::new(format!("{e:?}").as_str()))?;
 
-        rt.runtime
+        let (prompt, scope, allowed) = rt
+            .runtime
             .internal()
             .render_prompt(&self.name, &ctx, &params, wasm_call_context.node_index)
             .await
-            .as_ref()
-            .map(|(p, scope, allowed)| (p, scope, allowed).into())
-            .map_err(|e| JsError::new(format!("{e:?}").as_str()))
+            .map_err(|e| JsError::new(format!("{e:?}").as_str()))?;
+        let tokens = rt
+            .runtime
+            .internal()
+            .estimate_prompt_tokens(&self.name, &ctx, &prompt, wasm_call_context.node_index)
+            .map_err(|e| JsError::new(format!("{e:?}").as_str()))?;
+        Ok((&prompt, &scope, &allowed, &tokens).into())
     }
 
     #[wasm_bindgen]
@@ -1668,6 +1686,82 @@ impl WasmFunction {
         })
     }
 
+    /// The lines of the rows of the dataset of a test, each run with
+    /// `run_test_row`. Empty if the test has no dataset.
+    #[wasm_bindgen]
+    pub async fn dataset_row_lines(
+        &self,
+        rt: &mut WasmRuntime,
+        test_name: String,
+        get_baml_src_cb: js_sys::Function,
+    ) -> Result<Vec<u32>, JsValue> {
+        let rt = &rt.runtime;
+        let ctx = rt.create_ctx_manager(
+            BamlValue::String("wasm".to_string()),
+            js_fn_to_baml_src_reader(get_baml_src_cb),
+        );
+        let rows = rt
+            .test_dataset_rows(&self.name, &test_name, &ctx)
+            .await
+            .map_err(|e| JsValue::from_str(&format!("{:#}", e)))?;
+        Ok(rows
+            .unwrap_or_default()
+            .iter()
+            .map(|row| row.line as u32)
+            .collect())
+    }
+
+    /// Runs a test with the row of its dataset at `row_line`.
+    #[wasm_bindgen]
+    pub async fn run_test_row(
+        &self,
+        rt: &mut WasmRuntime,
+        test_name: String,
+        row_line: u32,
+        on_partial_response: js_sys::Function,
+        get_baml_src_cb: js_sys::Function,
+    ) -> Result<WasmTestResponse, JsValue> {
+        let rt = &rt.runtime;
+
+        let function_name = self.name.clone();
+
+        let cb = Box::new(move |r| {
+            let this = JsValue::NULL;
+            let res = WasmFunctionResponse {
+                function_response: r,
+            }
+            .into();
+            on_partial_response.call1(&this, &res).unwrap();
+        });
+
+        let ctx = rt.create_ctx_manager(
+            BamlValue::String("wasm".to_string()),
+            js_fn_to_baml_src_reader(get_baml_src_cb),
+        );
+        let row = rt
+            .test_dataset_rows(&function_name, &test_name, &ctx)
+            .await
+            .map_err(|e| JsValue::from_str(&format!("{:#}", e)))?
+            .unwrap_or_default()
+            .into_iter()
+            .find(|row| row.line as u32 == row_line)
+            .ok_or_else(|| {
+                JsValue::from_str(&format!(
+                    "No dataset row at line {} for test {}",
+                    row_line, test_name
+                ))
+            })?;
+        let (test_response, span) = rt
+            .run_test_row(&function_name, &test_name, Some(&row), &ctx, None, Some(cb))
+            .await;
+
+        Ok(WasmTestResponse {
+            test_response,
+            span,
+            tracing_project_id: rt.env_vars().get("BOUNDARY_PROJECT_ID").cloned(),
+        })
+    }
+
     pub fn orchestration_graph(&self, rt: &WasmRuntime) -> Result<Vec<WasmScope>, JsValue> {
         let rt: &BamlRuntime = &rt.runtime;
 
@@ -1732,6 +1826,11 @@ impl ToJsValue for ExecutionScope {
                 set_property(&obj, "name", JsValue::from_str(name));
                 set_property(&obj, "index", JsValue::from_f64(*index as f64));
             }
+            ExecutionScope::Candidate(index, count) => {
+                set_property(&obj, "type", JsValue::from_str("Candidate"));
+                set_property(&obj, "index", JsValue::from_f64(*index as f64));
+                set_property(&obj, "count", JsValue::from_f64(*count as f64));
+            }
         }
         obj.into()
     }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use baml_runtime::{
     internal::llm_client::orchestrator::{ExecutionScope, OrchestrationScope},
-    ChatMessagePart, RenderedPrompt,
+    ChatMessagePart, RenderedPrompt, TokenEstimate,
 };
 use internal_llm_client::AllowedRoleMetadata;
 use serde_json::json;
@@ -22,6 +22,7 @@ pub struct WasmPrompt {
     prompt: RenderedPrompt,
     pub client_name: String,
     allowed: AllowedRoleMetadata,
+    tokens: TokenEstimate,
 }
 
 impl From<OrchestrationScope> for WasmScope {
@@ -30,18 +31,27 @@ impl From<OrchestrationScope> for WasmScope {
     }
 }
 
-impl From<(&RenderedPrompt, &OrchestrationScope, &AllowedRoleMetadata)> for WasmPrompt {
+impl
+    From<(
+        &RenderedPrompt,
+        &OrchestrationScope,
+        &AllowedRoleMetadata,
+        &TokenEstimate,
+    )> for WasmPrompt
+{
     fn from(
-        (prompt, client_name, allowed): (
+        (prompt, client_name, allowed, tokens): (
             &RenderedPrompt,
             &OrchestrationScope,
             &AllowedRoleMetadata,
+            &TokenEstimate,
         ),
     ) -> Self {
         WasmPrompt {
             prompt: prompt.clone(),
             client_name: client_name.name(),
             allowed: allowed.clone(),
+            tokens: tokens.clone(),
         }
     }
 }
@@ -219,6 +229,24 @@ impl WasmPrompt {
         matches!(self.prompt, RenderedPrompt::Completion(_))
     }
 
+    /// The number of tokens in the prompt, estimated with the client's
+    /// tokenizer. Media parts are not counted.
+    #[wasm_bindgen]
+    pub fn token_count(&self) -> usize {
+        self.tokens.tokens
+    }
+
+    /// Whether `token_count` is exact, or estimated from the prompt's length.
+    #[wasm_bindgen]
+    pub fn token_count_is_exact(&self) -> bool {
+        self.tokens.exact
+    }
+
+    #[wasm_bindgen]
+    pub fn tokenizer(&self) -> String {
+        self.tokens.tokenizer.to_string()
+    }
+
     #[wasm_bindgen]
     pub fn as_chat(&self) -> Option<Vec<WasmChatMessage>> {
         if let RenderedPrompt::Chat(s) = &self.prompt {
@@ -37,8 +37,23 @@ pub(crate) enum Commands {
     #[command(about = "Deploy a BAML project to Boundary Cloud")]
     Deploy(crate::deploy::DeployArgs),
 
-    #[command(about = "Format BAML source files", name = "fmt", hide = true)]
+    #[command(about = "Format BAML source files", name = "fmt")]
     Format(crate::format::FormatArgs),
+
+    #[command(about = "Checks BAML source files for likely mistakes")]
+    Lint(crate::lint::LintArgs),
+
+    #[command(about = "Starts a language server over stdio, for editor integrations")]
+    Lsp(crate::lsp::LspArgs),
+
+    #[command(about = "Inspects the traces written to BOUNDARY_TRACE_DIR")]
+    Traces(crate::traces::TracesArgs),
+
+    #[command(about = "Runs the tests in the baml_src directory, one case per dataset row")]
+    Test(crate::test::TestArgs),
+
+    #[command(about = "Scores the outputs of tests across clients or prompt revisions")]
+    Eval(crate::eval::EvalArgs),
 }
 
 impl RuntimeCli {
@@ -69,6 +84,17 @@ impl RuntimeCli {
                 t.block_on(async { args.run_async().await })
             }
             Commands::Format(args) => args.run(),
+            Commands::Lint(args) => {
+                args.from = BamlRuntime::parse_baml_src_path(&args.from)?;
+                args.run()
+            }
+            Commands::Lsp(args) => args.run(),
+            Commands::Traces(args) => args.run(),
+            Commands::Test(args) => {
+                args.from = BamlRuntime::parse_baml_src_path(&args.from)?;
+                args.run()
+            }
+            Commands::Eval(args) => args.run(),
         }
     }
 }
@@ -0,0 +1,600 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use baml_runtime::{
+    eval::{Aggregate, CaseStatus, EvalConfig, EvalRun, Evaluator},
+    BamlRuntime,
+};
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use futures::StreamExt;
+
+use crate::test::{collect_cases, ctx_manager};
+
+#[derive(Args, Debug)]
+pub struct EvalArgs {
+    #[arg(
+        long,
+        help = "The directory eval runs are written to",
+        default_value = "./.baml_evals"
+    )]
+    pub dir: PathBuf,
+
+    #[command(subcommand)]
+    pub command: EvalCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EvalCommand {
+    #[command(about = "Runs the tests with every variant and scores the outputs")]
+    Run(RunArgs),
+
+    #[command(about = "Lists the eval runs, newest first")]
+    List,
+
+    #[command(about = "Shows the scores of an eval run, per variant and per case")]
+    Show {
+        #[arg(help = "The run id, a prefix of it, or the path of a run file")]
+        run: String,
+    },
+
+    #[command(about = "Compares two eval runs, listing the cases that regressed or improved")]
+    Diff {
+        #[arg(help = "The run to compare to")]
+        base: String,
+        #[arg(help = "The run to compare")]
+        head: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    #[arg(long, help = "path/to/baml_src", default_value = "./baml_src")]
+    pub from: PathBuf,
+
+    #[arg(long, help = "The eval config. Defaults to baml_src/baml-eval.yaml")]
+    config: Option<PathBuf>,
+
+    #[arg(long, help = "Only run the tests of this function. Can be repeated")]
+    function: Vec<String>,
+
+    #[arg(long, help = "Only run the tests with this name. Can be repeated")]
+    test: Vec<String>,
+
+    #[arg(long, help = "Only run this variant. Can be repeated")]
+    variant: Vec<String>,
+
+    #[arg(
+        long,
+        help = "How many test cases to run at the same time",
+        default_value_t = 10
+    )]
+    parallel: usize,
+}
+
+impl EvalArgs {
+    pub fn run(&mut self) -> Result<()> {
+        match &mut self.command {
+            EvalCommand::Run(args) => {
+                args.from = BamlRuntime::parse_baml_src_path(&args.from)?;
+                let run = args.run()?;
+                let path = self.dir.join(format!("{}.json", run.id));
+                fs::create_dir_all(&self.dir)
+                    .with_context(|| format!("Failed to create {}", self.dir.display()))?;
+                fs::write(&path, serde_json::to_string_pretty(&run)?)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
+                print_aggregates(&run.aggregates());
+                println!();
+                println!("Wrote {}", path.display());
+            }
+            EvalCommand::List => list_runs(&self.dir)?,
+            EvalCommand::Show { run } => show_run(&read_run(&self.dir, run)?),
+            EvalCommand::Diff { base, head } => print!(
+                "{}",
+                diff_runs(&read_run(&self.dir, base)?, &read_run(&self.dir, head)?)
+            ),
+        }
+        Ok(())
+    }
+}
+
+impl RunArgs {
+    fn run(&self) -> Result<EvalRun> {
+        let config_path = self
+            .config
+            .clone()
+            .unwrap_or_else(|| self.from.join(EvalConfig::FILE_NAME));
+        let config = load_config(&config_path)?;
+        let config_dir = config_path.parent().unwrap_or(Path::new("."));
+
+        let variants = config
+            .variants()
+            .into_iter()
+            .filter(|v| self.variant.is_empty() || self.variant.contains(&v.name))
+            .collect::<Vec<_>>();
+        if variants.is_empty() {
+            anyhow::bail!("No variant named {}", self.variant.join(", "));
+        }
+
+        let env_vars = std::env::vars().collect::<std::collections::HashMap<_, _>>();
+        let runtime = BamlRuntime::from_directory(&self.from, env_vars.clone())?;
+        let variant_runtimes = variants
+            .iter()
+            .map(|variant| match &variant.from {
+                Some(from) => {
+                    let from = BamlRuntime::parse_baml_src_path(&config_dir.join(from))?;
+                    BamlRuntime::from_directory(&from, env_vars.clone())
+                        .map(Some)
+                        .with_context(|| format!("Failed to load variant `{}`", variant.name))
+                }
+                None => Ok(None),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let functions = if self.function.is_empty() {
+            &config.functions
+        } else {
+            &self.function
+        };
+        let tests = if self.test.is_empty() {
+            &config.tests
+        } else {
+            &self.test
+        };
+
+        let started_at = chrono::Utc::now();
+        let id = format!(
+            "{}-{}",
+            started_at.format("%Y%m%d-%H%M%S"),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        let mut cases = vec![];
+        runtime.async_runtime.block_on(async {
+            for (variant, variant_runtime) in variants.iter().zip(&variant_runtimes) {
+                let variant_runtime = variant_runtime.as_ref().unwrap_or(&runtime);
+                let evaluator = Evaluator::new(variant_runtime, variant, &config.scorers)?;
+                let variant_cases = collect_cases(variant_runtime, functions, tests).await?;
+                if variant_cases.is_empty() {
+                    anyhow::bail!("No tests found for variant `{}`", variant.name);
+                }
+
+                let mut results = futures::stream::iter(variant_cases.iter())
+                    .map(|case| {
+                        let evaluator = &evaluator;
+                        async move {
+                            let ctx = ctx_manager(variant_runtime);
+                            let result = evaluator
+                                .run_case(&case.function, &case.test, case.row.as_ref(), &ctx)
+                                .await;
+                            (case, result)
+                        }
+                    })
+                    .buffered(self.parallel.max(1));
+                while let Some((case, result)) = results.next().await {
+                    let status = match result.status {
+                        CaseStatus::Passed => "PASS".green(),
+                        CaseStatus::Failed => "FAIL".red(),
+                        CaseStatus::Error => "ERROR".red(),
+                    };
+                    println!("{}  {}  {}", status, variant.name.bold(), case.label());
+                    cases.push(result);
+                }
+            }
+            Ok::<_, anyhow::Error>(())
+        })?;
+
+        println!();
+        Ok(EvalRun {
+            id,
+            started_at: started_at.to_rfc3339(),
+            variants,
+            scorers: config.scorers.iter().map(|s| s.name.clone()).collect(),
+            cases,
+        })
+    }
+}
+
+fn load_config(path: &Path) -> Result<EvalConfig> {
+    if !path.exists() {
+        return Ok(EvalConfig::default());
+    }
+
+    let text =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let config: EvalConfig = serde_yaml::from_str(&text)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    config
+        .validate()
+        .with_context(|| format!("Invalid eval config {}", path.display()))?;
+    Ok(config)
+}
+
+/// Reads a run from a path, or from the run in `dir` whose id starts with
+/// `run`.
+fn read_run(dir: &Path, run: &str) -> Result<EvalRun> {
+    let path = if Path::new(run).is_file() {
+        PathBuf::from(run)
+    } else {
+        let matches = run_files(dir)?
+            .into_iter()
+            .filter(|(id, _)| id.starts_with(run))
+            .collect::<Vec<_>>();
+        match matches.as_slice() {
+            [(_, path)] => path.clone(),
+            [] => anyhow::bail!("No eval run matching `{}` in {}", run, dir.display()),
+            _ => anyhow::bail!("More than one eval run matches `{}`", run),
+        }
+    };
+
+    let text =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&text).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// The ids and paths of the runs in `dir`, newest first.
+fn run_files(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut runs = vec![];
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        if let Some(id) = path.file_stem().and_then(|s| s.to_str()) {
+            runs.push((id.to_string(), path.clone()));
+        }
+    }
+    // Ids start with the time of the run.
+    runs.sort_by(|a, b| b.0.cmp(&a.0));
+    Ok(runs)
+}
+
+fn list_runs(dir: &Path) -> Result<()> {
+    let runs = run_files(dir)?;
+    if runs.is_empty() {
+        println!("No eval runs in {}", dir.display());
+    }
+    for (id, path) in runs {
+        match read_run(dir, &path.to_string_lossy()) {
+            Ok(run) => {
+                let variants = run
+                    .aggregates()
+                    .iter()
+                    .map(|a| format!("{} {:.0}%", a.variant, a.pass_rate() * 100.0))
+                    .collect::<Vec<_>>();
+                println!("{}  {}  {}", id.bold(), run.started_at, variants.join(", "));
+            }
+            Err(e) => println!("{}  {}", id.bold(), format!("{:#}", e).red()),
+        }
+    }
+    Ok(())
+}
+
+fn show_run(run: &EvalRun) {
+    println!("{} {}", "Run".bold(), run.id);
+    println!();
+    print_aggregates(&run.aggregates());
+
+    for case in &run.cases {
+        let status = match case.status {
+            CaseStatus::Passed => "PASS".green(),
+            CaseStatus::Failed => "FAIL".red(),
+            CaseStatus::Error => "ERROR".red(),
+        };
+        let scores = case
+            .scores
+            .iter()
+            .map(|(name, score)| format!("{}={:.2}", name, score))
+            .collect::<Vec<_>>();
+        println!();
+        println!("{}  {}  {}", status, case.variant.bold(), case.label());
+        if !scores.is_empty() {
+            println!("      {}", scores.join("  "));
+        }
+        if let Some(message) = &case.message {
+            for line in message.lines() {
+                println!("      {}", line.dimmed());
+            }
+        }
+        for (name, error) in &case.scorer_errors {
+            println!("      {}", format!("{}: {}", name, error).red());
+        }
+    }
+}
+
+fn print_aggregates(aggregates: &[Aggregate]) {
+    for aggregate in aggregates {
+        println!(
+            "{}  {}/{} passed, {} failed, {} errors",
+            aggregate.variant.bold(),
+            aggregate.passed,
+            aggregate.cases,
+            aggregate.failed,
+            aggregate.errors
+        );
+        for (name, (mean, count)) in &aggregate.scores {
+            if *count > 0 {
+                println!("      {:<20} {:.3}  ({} cases)", name, mean, count);
+            } else {
+                println!("      {:<20} {}", name, "-".dimmed());
+            }
+        }
+        if let Some(latency) = aggregate.mean_latency_ms {
+            println!("      {:<20} {:.0}ms", "mean latency", latency);
+        }
+        if aggregate.total_tokens > 0 {
+            println!("      {:<20} {}", "total tokens", aggregate.total_tokens);
+        }
+    }
+}
+
+/// The changes of the variants the runs have in common.
+fn diff_runs(base: &EvalRun, head: &EvalRun) -> String {
+    let mut out = String::new();
+    let diffs = base.diff(head);
+    if diffs.is_empty() {
+        writeln!(out, "The runs have no variant in common").unwrap();
+    }
+    for diff in diffs {
+        writeln!(
+            out,
+            "{} -> {}",
+            diff.base.variant.bold(),
+            diff.head.variant.bold()
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "      {:<20} {}",
+            "pass rate",
+            delta(diff.base.pass_rate(), diff.head.pass_rate())
+        )
+        .unwrap();
+        for (name, (head_mean, head_count)) in &diff.head.scores {
+            match diff.base.scores.get(name) {
+                Some((base_mean, base_count)) if *base_count > 0 && *head_count > 0 => {
+                    writeln!(out, "      {:<20} {}", name, delta(*base_mean, *head_mean)).unwrap()
+                }
+                _ => {}
+            }
+        }
+        for (changes, label) in [
+            (&diff.regressions, "regressed".red()),
+            (&diff.improvements, "improved".green()),
+        ] {
+            for change in changes {
+                writeln!(out, "  {}  {}", label, change.case).unwrap();
+                for line in &change.changes {
+                    writeln!(out, "      {}", line.dimmed()).unwrap();
+                }
+            }
+        }
+        writeln!(out).unwrap();
+    }
+    out
+}
+
+fn delta(base: f64, head: f64) -> String {
+    let text = format!("{:.3} -> {:.3} ({:+.3})", base, head, head - base);
+    if head > base {
+        text.green().to_string()
+    } else if head < base {
+        text.red().to_string()
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use baml_runtime::eval::CaseResult;
+
+    const SOURCE: &str = r##"client<llm> Local {
+  provider openai-generic
+  options {
+    // Nothing listens there, so every call fails right away.
+    base_url "http://127.0.0.1:9"
+    model "local"
+  }
+}
+
+function Classify(text: string) -> string {
+  client "openai/gpt-4o-mini"
+  prompt #"
+    Classify {{ text }}.
+  "#
+}
+
+test First {
+  functions [Classify]
+  args {
+    text "hello"
+  }
+}
+
+test Second {
+  functions [Classify]
+  args {
+    text "bye"
+  }
+}
+"##;
+
+    const CONFIG: &str = r#"variants:
+  - name: local
+    client: Local
+  - name: other
+    client: Local
+scorers:
+  - name: exact
+    type: exact_match
+"#;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("baml-eval-cmd-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn run_args(variants: &[&str]) -> RunArgs {
+        let from = temp_dir().join("baml_src");
+        fs::create_dir_all(&from).unwrap();
+        fs::write(from.join("main.baml"), SOURCE).unwrap();
+        fs::write(from.join(EvalConfig::FILE_NAME), CONFIG).unwrap();
+        RunArgs {
+            from,
+            config: None,
+            function: vec![],
+            test: vec![],
+            variant: variants.iter().map(|v| v.to_string()).collect(),
+            parallel: 2,
+        }
+    }
+
+    #[test]
+    fn runs_every_test_with_every_variant() {
+        let run = run_args(&[]).run().unwrap();
+        let variants = run
+            .variants
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(variants, ["local", "other"]);
+        assert_eq!(run.scorers, ["exact"]);
+
+        let cases = run
+            .cases
+            .iter()
+            .map(|c| format!("{} {}", c.variant, c.label()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            cases,
+            [
+                "local Classify::First",
+                "local Classify::Second",
+                "other Classify::First",
+                "other Classify::Second",
+            ]
+        );
+        // The failed calls are recorded, not returned, and aren't scored.
+        for case in &run.cases {
+            assert_eq!(case.status, CaseStatus::Error);
+            assert!(case.message.is_some());
+            assert!(case.scores.is_empty());
+        }
+    }
+
+    #[test]
+    fn runs_selected_variants() {
+        let run = run_args(&["other"]).run().unwrap();
+        assert_eq!(run.cases.len(), 2);
+        assert!(run.cases.iter().all(|c| c.variant == "other"));
+
+        let error = run_args(&["missing"]).run().unwrap_err();
+        assert_eq!(error.to_string(), "No variant named missing");
+    }
+
+    fn case(variant: &str, row: usize, status: CaseStatus, score: f64) -> CaseResult {
+        CaseResult {
+            variant: variant.to_string(),
+            function: "Classify".to_string(),
+            test: "Rows".to_string(),
+            row: Some(row),
+            status,
+            message: None,
+            output: None,
+            expected: None,
+            scores: [("exact".to_string(), score)].into_iter().collect(),
+            scorer_errors: Default::default(),
+            latency_ms: None,
+            total_tokens: None,
+        }
+    }
+
+    fn eval_run(id: &str, cases: Vec<CaseResult>) -> EvalRun {
+        EvalRun {
+            id: id.to_string(),
+            started_at: "2024-01-01T00:00:00Z".to_string(),
+            variants: vec![],
+            scorers: vec!["exact".to_string()],
+            cases,
+        }
+    }
+
+    #[test]
+    fn compares_runs() {
+        colored::control::set_override(false);
+        let base = eval_run(
+            "base",
+            vec![
+                case("a", 2, CaseStatus::Passed, 1.0),
+                case("a", 3, CaseStatus::Failed, 0.0),
+            ],
+        );
+        let head = eval_run(
+            "head",
+            vec![
+                case("a", 2, CaseStatus::Failed, 0.5),
+                case("a", 3, CaseStatus::Passed, 1.0),
+            ],
+        );
+        assert_eq!(
+            diff_runs(&base, &head),
+            "a -> a\n      pass rate            0.500 -> 0.500 (+0.000)\n      exact                0.500 -> 0.750 (+0.250)\n  regressed  Classify::Rows [line 2]\n      passed -> failed\n      exact: 1.00 -> 0.50\n  improved  Classify::Rows [line 3]\n      failed -> passed\n      exact: 0.00 -> 1.00\n\n"
+        );
+
+        let mut other = base.clone();
+        other.cases.push(case("b", 2, CaseStatus::Passed, 1.0));
+        assert_eq!(
+            diff_runs(
+                &other,
+                &eval_run("head", vec![case("c", 2, CaseStatus::Passed, 1.0)])
+            ),
+            "The runs have no variant in common\n"
+        );
+    }
+
+    #[test]
+    fn reads_runs_by_id_prefix() {
+        let dir = temp_dir();
+        for id in [
+            "20240101-000000-aaaa",
+            "20240102-000000-bbbb",
+            "20240102-120000-cccc",
+        ] {
+            let run = eval_run(id, vec![]);
+            fs::write(
+                dir.join(format!("{}.json", id)),
+                serde_json::to_string(&run).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let ids = run_files(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                "20240102-120000-cccc",
+                "20240102-000000-bbbb",
+                "20240101-000000-aaaa"
+            ]
+        );
+        assert_eq!(
+            read_run(&dir, "20240101").unwrap().id,
+            "20240101-000000-aaaa"
+        );
+        assert!(read_run(&dir, "20240102").is_err());
+        assert!(read_run(&dir, "2025").is_err());
+    }
+}
@@ -1,32 +1,90 @@
 use std::{fs, path::PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Args;
 use internal_baml_core::internal_baml_schema_ast::{format_schema, FormatOptions};
 
+use crate::colordiff::print_diff;
+
 #[derive(Args, Debug)]
 pub struct FormatArgs {
-    #[arg(long, help = "path/to/baml_src", default_value = "./baml_src")]
+    #[arg(
+        long,
+        help = "path/to/baml_src, or a single .baml file",
+        default_value = "./baml_src"
+    )]
     pub from: PathBuf,
+
+    #[arg(
+        long,
+        help = "Don't write any files; print a diff and exit with an error if any file is not formatted"
+    )]
+    pub check: bool,
 }
 
 impl FormatArgs {
     pub fn run(&self) -> Result<()> {
-        let source = fs::read_to_string(&self.from)?;
-        let formatted = format_schema(
-            &source,
-            FormatOptions {
-                indent_width: 4,
-                fail_on_unhandled_rule: false,
-            },
-        )?;
+        let files = if self.from.is_file() {
+            vec![self.from.clone()]
+        } else {
+            // Imported packages are formatted on their own.
+            baml_runtime::baml_src_files(&self.from)?
+                .into_iter()
+                .filter(|f| f.extension().is_some_and(|ext| ext == "baml"))
+                .filter(|f| f.starts_with(&self.from))
+                .collect()
+        };
+
+        let mut unformatted = 0;
+        let mut failed = 0;
+
+        for file in &files {
+            let source = fs::read_to_string(file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let formatted = match format_schema(&source, FormatOptions::default()) {
+                Ok(formatted) => formatted,
+                Err(e) => {
+                    log::error!("Failed to format {}: {:#}", file.display(), e);
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            if formatted == source {
+                continue;
+            }
+            unformatted += 1;
 
-        let mut to = self.from.clone();
-        to.set_extension("formatted.baml");
-        fs::write(&to, formatted)?;
+            if self.check {
+                log::warn!("{} is not formatted", file.display());
+                print_diff(&source, &formatted);
+            } else {
+                fs::write(file, formatted)
+                    .with_context(|| format!("Failed to write {}", file.display()))?;
+                log::info!("Formatted {}", file.display());
+            }
+        }
 
-        log::info!("Formatted {} to {}", self.from.display(), to.display());
+        if failed > 0 {
+            anyhow::bail!("Failed to format {} file(s)", failed);
+        }
+        if self.check && unformatted > 0 {
+            anyhow::bail!(
+                "{} file(s) are not formatted. Run `baml-cli fmt` to format them.",
+                unformatted
+            );
+        }
 
+        log::info!(
+            "{} file(s) checked, {} {}",
+            files.len(),
+            unformatted,
+            if self.check {
+                "need formatting"
+            } else {
+                "formatted"
+            }
+        );
         Ok(())
     }
 }
@@ -3,8 +3,13 @@ pub(crate) mod auth;
 pub(crate) mod colordiff;
 pub(crate) mod commands;
 pub(crate) mod deploy;
+pub(crate) mod eval;
 pub(crate) mod format;
+pub(crate) mod lint;
+pub(crate) mod lsp;
 pub(crate) mod propelauth;
+pub(crate) mod test;
+pub(crate) mod traces;
 pub(crate) mod tui;
 
 use anyhow::Result;
@@ -0,0 +1,293 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use internal_baml_core::{
+    internal_baml_diagnostics::{Diagnostics, SourceFile, Span},
+    lint::{self, Finding, LintConfig, Severity, RULES},
+};
+use serde_json::json;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
+#[derive(Args, Debug)]
+pub struct LintArgs {
+    #[arg(long, help = "path/to/baml_src", default_value = "./baml_src")]
+    pub from: PathBuf,
+
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    #[arg(long, help = "Exit with an error if there are any warnings")]
+    pub deny_warnings: bool,
+}
+
+impl LintArgs {
+    pub fn run(&self) -> Result<()> {
+        let config = load_config(&self.from)?;
+
+        let files = baml_runtime::baml_src_files(&self.from)?
+            .into_iter()
+            .filter(|f| f.extension().is_some_and(|ext| ext == "baml"))
+            .map(|f| {
+                let text = fs::read_to_string(&f)
+                    .with_context(|| format!("Failed to read {}", f.display()))?;
+                Ok(SourceFile::from((f, text)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let schema = internal_baml_core::validate(&self.from, files);
+        if schema.diagnostics.has_errors() {
+            anyhow::bail!(
+                "Fix these errors before linting:\n{}",
+                schema.diagnostics.to_pretty_string()
+            );
+        }
+
+        let findings = lint::lint(&schema.db, &config);
+        match self.format {
+            OutputFormat::Text => print_text(&self.from, &findings),
+            OutputFormat::Json => {
+                println!("{}", serde_json::to_string_pretty(&to_json(&findings))?)
+            }
+            OutputFormat::Sarif => {
+                println!("{}", serde_json::to_string_pretty(&to_sarif(&findings))?)
+            }
+        }
+
+        let errors = findings
+            .iter()
+            .filter(|f| f.severity == Severity::Error)
+            .count();
+        let warnings = findings.len() - errors;
+        if errors > 0 || (self.deny_warnings && warnings > 0) {
+            anyhow::bail!("Lint failed: {} error(s), {} warning(s)", errors, warnings);
+        }
+        Ok(())
+    }
+}
+
+/// Reads `baml_src/baml-lint.yaml`, if there is one.
+pub(crate) fn load_config(baml_src: &Path) -> Result<LintConfig> {
+    let path = baml_src.join(LintConfig::FILE_NAME);
+    if !path.exists() {
+        return Ok(LintConfig::default());
+    }
+
+    let text =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let config: LintConfig = serde_yaml::from_str(&text)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    if let Some(unknown) = config.unknown_rules().first() {
+        anyhow::bail!(
+            "Unknown lint rule `{}` in {}. Known rules: {}",
+            unknown,
+            path.display(),
+            RULES.iter().map(|r| r.id).collect::<Vec<_>>().join(", ")
+        );
+    }
+    Ok(config)
+}
+
+fn print_text(baml_src: &Path, findings: &[Finding]) {
+    let mut diagnostics = Diagnostics::new(baml_src.to_path_buf());
+    for finding in findings {
+        finding.push_to(&mut diagnostics);
+    }
+    if diagnostics.has_errors() {
+        println!("{}", diagnostics.to_pretty_string());
+    }
+    if diagnostics.has_warnings() {
+        println!("{}", diagnostics.warnings_to_pretty_string());
+    }
+    log::info!("{} finding(s)", findings.len());
+}
+
+/// 1-based line and column numbers, the way editors and SARIF count them.
+fn region(span: &Span) -> serde_json::Value {
+    let ((start_line, start_column), (end_line, end_column)) = span.line_and_column();
+    json!({
+        "startLine": start_line + 1,
+        "startColumn": start_column + 1,
+        "endLine": end_line + 1,
+        "endColumn": end_column + 1,
+    })
+}
+
+fn uri(span: &Span) -> String {
+    let path = span
+        .file
+        .path_buf()
+        .display()
+        .to_string()
+        .replace('\\', "/");
+    path.strip_prefix("./").map(str::to_string).unwrap_or(path)
+}
+
+fn to_json(findings: &[Finding]) -> serde_json::Value {
+    findings
+        .iter()
+        .map(|f| {
+            json!({
+                "rule": f.rule,
+                "severity": f.severity,
+                "message": f.message,
+                "file": uri(&f.span),
+                "region": region(&f.span),
+            })
+        })
+        .collect()
+}
+
+/// A SARIF 2.1.0 log, which is what GitHub code scanning and most CI
+/// dashboards read.
+fn to_sarif(findings: &[Finding]) -> serde_json::Value {
+    let level = |severity: Severity| match severity {
+        Severity::Off => "none",
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    };
+
+    let rules = RULES
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id,
+                "shortDescription": { "text": rule.description },
+                "defaultConfiguration": { "level": level(rule.default_severity) },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let results = findings
+        .iter()
+        .map(|f| {
+            json!({
+                "ruleId": f.rule,
+                "ruleIndex": RULES.iter().position(|r| r.id == f.rule),
+                "level": level(f.severity),
+                "message": { "text": f.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri(&f.span) },
+                        "region": region(&f.span),
+                    },
+                }],
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "baml-cli",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://docs.boundaryml.com",
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn findings(source: &str) -> Vec<Finding> {
+        let path = PathBuf::from("baml_src/main.baml");
+        let schema = internal_baml_core::validate(
+            Path::new("baml_src"),
+            vec![SourceFile::from((path, source.to_string()))],
+        );
+        assert!(
+            !schema.diagnostics.has_errors(),
+            "{}",
+            schema.diagnostics.to_pretty_string()
+        );
+        lint::lint(&schema.db, &LintConfig::default())
+    }
+
+    const SOURCE: &str = r#"class Unused {
+  a string
+}
+
+client<llm> Hardcoded {
+  provider openai
+  options {
+    model gpt-4o
+    api_key "sk-1234"
+  }
+}
+"#;
+
+    #[test]
+    fn json_output() {
+        assert_eq!(
+            to_json(&findings(SOURCE)),
+            json!([
+                {
+                    "rule": "unused-class",
+                    "severity": "warning",
+                    "message": "Class `Unused` is never used",
+                    "file": "baml_src/main.baml",
+                    "region": { "startLine": 1, "startColumn": 7, "endLine": 1, "endColumn": 13 },
+                },
+                {
+                    "rule": "hardcoded-api-key",
+                    "severity": "error",
+                    "message": "`api_key` of client `Hardcoded` is hard-coded, read it from the environment with `env.` instead",
+                    "file": "baml_src/main.baml",
+                    "region": { "startLine": 9, "startColumn": 13, "endLine": 9, "endColumn": 22 },
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn sarif_output() {
+        let sarif = to_sarif(&findings(SOURCE));
+        assert_eq!(sarif["version"], "2.1.0");
+
+        let run = &sarif["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), RULES.len());
+        assert_eq!(rules[0]["id"], "unused-class");
+        assert_eq!(rules[0]["defaultConfiguration"]["level"], "warning");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        for result in results {
+            let index = result["ruleIndex"].as_u64().unwrap() as usize;
+            assert_eq!(rules[index]["id"], result["ruleId"]);
+        }
+        assert_eq!(
+            results[1],
+            json!({
+                "ruleId": "hardcoded-api-key",
+                "ruleIndex": 6,
+                "level": "error",
+                "message": {
+                    "text": "`api_key` of client `Hardcoded` is hard-coded, read it from the environment with `env.` instead",
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "baml_src/main.baml" },
+                        "region": { "startLine": 9, "startColumn": 13, "endLine": 9, "endColumn": 22 },
+                    },
+                }],
+            })
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use lsp_types::{CompletionItem, CompletionItemKind};
+
+use super::{project::Project, symbols::SymbolKind, text};
+
+const PRIMITIVE_TYPES: &[&str] = &[
+    "string", "int", "float", "bool", "null", "image", "audio", "map",
+];
+
+/// What Jinja knows about `ctx` and `_` in prompts.
+const CTX_ATTRIBUTES: &[&str] = &["output_format", "client", "tags"];
+const ROLE_FUNCTIONS: &[&str] = &["role", "chat"];
+
+pub(super) fn completions(project: &Project, file: &Path, offset: usize) -> Vec<CompletionItem> {
+    let Some(text) = project.text(file) else {
+        return vec![];
+    };
+    let scan = text::scan(text);
+
+    if let Some(jinja) = scan
+        .jinja
+        .iter()
+        .find(|r| r.start <= offset && offset <= r.end)
+    {
+        let before = text[jinja.start..offset].trim_end_matches(is_word_char);
+        return if before.ends_with("ctx.") {
+            items(CTX_ATTRIBUTES.iter().copied(), CompletionItemKind::PROPERTY)
+        } else if before.ends_with("_.") {
+            items(ROLE_FUNCTIONS.iter().copied(), CompletionItemKind::FUNCTION)
+        } else if before.ends_with('.') {
+            vec![]
+        } else {
+            jinja_variables(project, file, offset)
+        };
+    }
+
+    // Plain prompt text.
+    if scan
+        .raw_strings
+        .iter()
+        .any(|r| r.start <= offset && offset <= r.end)
+    {
+        return vec![];
+    }
+
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..offset];
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    let after_client = match words.as_slice() {
+        ["client"] => line.ends_with(char::is_whitespace),
+        ["client", _] => !line.ends_with(char::is_whitespace),
+        _ => false,
+    };
+    if after_client {
+        return symbols_of_kind(project, |kind| kind == SymbolKind::Client);
+    }
+
+    let mut types = items(PRIMITIVE_TYPES.iter().copied(), CompletionItemKind::KEYWORD);
+    types.extend(symbols_of_kind(project, SymbolKind::is_type));
+    types
+}
+
+/// The arguments of the function or template string the prompt belongs to,
+/// the globals Jinja adds, and the template strings that can be called.
+fn jinja_variables(project: &Project, file: &Path, offset: usize) -> Vec<CompletionItem> {
+    let params = project
+        .symbols
+        .iter()
+        .find(|s| s.file.as_path() == file && s.span.contains(&offset))
+        .map(|s| s.params.iter().map(String::as_str).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let mut completions = items(params, CompletionItemKind::VARIABLE);
+    completions.extend(items(["ctx", "_"], CompletionItemKind::VARIABLE));
+    completions.extend(symbols_of_kind(project, |kind| {
+        kind == SymbolKind::TemplateString
+    }));
+    completions
+}
+
+fn symbols_of_kind(project: &Project, filter: impl Fn(SymbolKind) -> bool) -> Vec<CompletionItem> {
+    project
+        .symbols
+        .iter()
+        .filter(|s| filter(s.kind))
+        .map(|s| CompletionItem {
+            label: s.qualified_name.clone(),
+            kind: Some(match s.kind {
+                SymbolKind::Class => CompletionItemKind::CLASS,
+                SymbolKind::Enum => CompletionItemKind::ENUM,
+                SymbolKind::TemplateString | SymbolKind::Function => CompletionItemKind::FUNCTION,
+                _ => CompletionItemKind::VALUE,
+            }),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn items<'a>(
+    labels: impl IntoIterator<Item = &'a str>,
+    kind: CompletionItemKind,
+) -> Vec<CompletionItem> {
+    labels
+        .into_iter()
+        .map(|label| CompletionItem {
+            label: label.to_string(),
+            kind: Some(kind),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
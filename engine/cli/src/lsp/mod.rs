@@ -0,0 +1,321 @@
+//! `baml-cli lsp`: a language server over stdio, so that editors other than
+//! VSCode (Neovim, Helix, Zed, ...) get diagnostics, navigation, completions,
+//! rename and formatting.
+
+mod completion;
+mod project;
+mod references;
+mod symbols;
+#[cfg(test)]
+mod tests;
+mod text;
+
+use std::{collections::HashMap, path::PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use internal_baml_core::internal_baml_schema_ast::{format_schema, FormatOptions};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{
+        Completion, Formatting, GotoDefinition, HoverRequest, References, Rename, Request as _,
+    },
+    CompletionOptions, CompletionParams, CompletionResponse, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DocumentFormattingParams,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf, PublishDiagnosticsParams,
+    ReferenceParams, RenameParams, ServerCapabilities, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit,
+};
+
+use self::project::{Project, Workspace};
+
+#[derive(Args, Debug)]
+pub struct LspArgs {}
+
+impl LspArgs {
+    pub fn run(&self) -> Result<()> {
+        // stdout is the protocol channel: everything else must go to stderr,
+        // which is where our logger writes.
+        let (connection, io_threads) = Connection::stdio();
+        serve(connection)?;
+        io_threads.join()?;
+        Ok(())
+    }
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".to_string()]),
+            ..Default::default()
+        }),
+        rename_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+/// Runs the server until the client shuts it down.
+pub(crate) fn serve(connection: Connection) -> Result<()> {
+    let (id, _) = connection.initialize_start()?;
+    connection.initialize_finish(
+        id,
+        serde_json::json!({
+            "capabilities": capabilities(),
+            "serverInfo": {
+                "name": "baml-cli",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        }),
+    )?;
+
+    let mut server = Server::default();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                connection
+                    .sender
+                    .send(Message::Response(server.handle_request(request)))?;
+            }
+            Message::Notification(notification) => {
+                for notification in server.handle_notification(notification)? {
+                    connection
+                        .sender
+                        .send(Message::Notification(notification))?;
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+struct Server {
+    workspace: Workspace,
+}
+
+impl Server {
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            GotoDefinition::METHOD => self.on::<GotoDefinition>(request, Self::definition),
+            References::METHOD => self.on::<References>(request, Self::references),
+            HoverRequest::METHOD => self.on::<HoverRequest>(request, Self::hover),
+            Completion::METHOD => self.on::<Completion>(request, Self::completion),
+            Rename::METHOD => self.on::<Rename>(request, Self::rename),
+            Formatting::METHOD => self.on::<Formatting>(request, Self::format),
+            method => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("Unsupported request: {}", method),
+                )
+            }
+        };
+
+        match result {
+            Ok(result) => Response::new_ok(id, result),
+            Err(e) => Response::new_err(id, ErrorCode::RequestFailed as i32, format!("{:#}", e)),
+        }
+    }
+
+    fn on<R: lsp_types::request::Request>(
+        &self,
+        request: Request,
+        handler: fn(&Self, R::Params) -> Result<R::Result>,
+    ) -> Result<serde_json::Value> {
+        let params = serde_json::from_value(request.params)
+            .with_context(|| format!("Invalid params for {}", R::METHOD))?;
+        Ok(serde_json::to_value(handler(self, params)?)?)
+    }
+
+    /// Returns the diagnostics to publish.
+    fn handle_notification(&mut self, notification: Notification) -> Result<Vec<Notification>> {
+        let project = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let file = file_path(&params.text_document.uri)?;
+                Some(self.workspace.set_file(&file, params.text_document.text))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let file = file_path(&params.text_document.uri)?;
+                // We only ask for full syncs, so the last change is the
+                // whole document.
+                match params.content_changes.into_iter().last() {
+                    Some(change) => Some(self.workspace.set_file(&file, change.text)),
+                    None => None,
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                self.workspace
+                    .revert_file(&file_path(&params.text_document.uri)?)
+            }
+            _ => None,
+        };
+
+        let Some(project) = project else {
+            return Ok(vec![]);
+        };
+        project
+            .diagnostics
+            .iter()
+            .map(|(file, diagnostics)| {
+                Ok(Notification::new(
+                    PublishDiagnostics::METHOD.to_string(),
+                    PublishDiagnosticsParams {
+                        uri: file_url(file)?,
+                        diagnostics: diagnostics.clone(),
+                        version: None,
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    /// The project, file and byte offset a request is about.
+    fn locate(&self, position: &TextDocumentPositionParams) -> Result<(&Project, PathBuf, usize)> {
+        let file = file_path(&position.text_document.uri)?;
+        let project = self
+            .workspace
+            .project(&file)
+            .ok_or_else(|| anyhow!("{} is not open", file.display()))?;
+        let text = project
+            .text(&file)
+            .ok_or_else(|| anyhow!("{} is not open", file.display()))?;
+        let offset = text::offset(text, position.position);
+        Ok((project, file, offset))
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Result<Option<GotoDefinitionResponse>> {
+        let (project, file, offset) = self.locate(&params.text_document_position_params)?;
+        let Some((symbol, _)) = project.symbol_at(&file, offset) else {
+            return Ok(None);
+        };
+        let text = project.text(&symbol.file).unwrap_or_default();
+        Ok(Some(GotoDefinitionResponse::Scalar(Location::new(
+            file_url(&symbol.file)?,
+            text::range(text, &symbol.name_span),
+        ))))
+    }
+
+    fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let (project, file, offset) = self.locate(&params.text_document_position)?;
+        let Some((symbol, _)) = project.symbol_at(&file, offset) else {
+            return Ok(None);
+        };
+
+        let locations = project
+            .references(symbol)
+            .into_iter()
+            .filter(|(path, _, span)| {
+                params.context.include_declaration
+                    || !(*path == symbol.file && *span == symbol.name_span)
+            })
+            .map(|(path, text, span)| Ok(Location::new(file_url(path)?, text::range(text, &span))))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(locations))
+    }
+
+    fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let (project, file, offset) = self.locate(&params.text_document_position_params)?;
+        let Some((symbol, word)) = project.symbol_at(&file, offset) else {
+            return Ok(None);
+        };
+        Ok(Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: symbol.hover.clone(),
+            }),
+            range: project.text(&file).map(|text| text::range(text, &word)),
+        }))
+    }
+
+    fn completion(&self, params: CompletionParams) -> Result<Option<CompletionResponse>> {
+        let (project, file, offset) = self.locate(&params.text_document_position)?;
+        Ok(Some(CompletionResponse::Array(completion::completions(
+            project, &file, offset,
+        ))))
+    }
+
+    fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let (project, file, offset) = self.locate(&params.text_document_position)?;
+        let Some((symbol, _)) = project.symbol_at(&file, offset) else {
+            anyhow::bail!("Only classes, enums, functions, clients, template strings, retry policies, generators and tests can be renamed");
+        };
+
+        let new_name = params.new_name;
+        let mut chars = new_name.chars();
+        let is_identifier = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_identifier {
+            anyhow::bail!("`{}` is not a valid name", new_name);
+        }
+        // Namespaced symbols keep their namespace.
+        let new_qualified_name = match symbol.qualified_name.rsplit_once('.') {
+            Some((namespace, _)) => format!("{}.{}", namespace, new_name),
+            None => new_name.clone(),
+        };
+        if project.symbol(&new_qualified_name).is_some() {
+            anyhow::bail!("`{}` is already defined", new_qualified_name);
+        }
+
+        let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+        for (path, text, span) in project.references(symbol) {
+            // Only the last segment of `shared.Resume` is the name.
+            let span = match text[span.clone()].rfind('.') {
+                Some(dot) => span.start + dot + 1..span.end,
+                None => span,
+            };
+            changes
+                .entry(file_url(path)?)
+                .or_default()
+                .push(TextEdit::new(text::range(text, &span), new_name.clone()));
+        }
+        Ok(Some(WorkspaceEdit::new(changes)))
+    }
+
+    fn format(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        let file = file_path(&params.text_document.uri)?;
+        let Some(text) = self.workspace.project(&file).and_then(|p| p.text(&file)) else {
+            return Ok(None);
+        };
+        // Files that don't parse are left alone; the diagnostics say why.
+        let Ok(formatted) = format_schema(text, FormatOptions::default()) else {
+            return Ok(None);
+        };
+        if formatted == text {
+            return Ok(Some(vec![]));
+        }
+        Ok(Some(vec![TextEdit::new(
+            text::range(text, &(0..text.len())),
+            formatted,
+        )]))
+    }
+}
+
+fn file_path(uri: &Url) -> Result<PathBuf> {
+    uri.to_file_path()
+        .map_err(|_| anyhow!("Not a file: {}", uri))
+}
+
+fn file_url(path: &std::path::Path) -> Result<Url> {
+    Url::from_file_path(path).map_err(|_| anyhow!("Not an absolute path: {}", path.display()))
+}
@@ -0,0 +1,252 @@
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use indexmap::IndexMap;
+use internal_baml_core::{
+    internal_baml_diagnostics::{Diagnostics, SourceFile, Span},
+    internal_baml_parser_database::ParserDatabase,
+    internal_baml_schema_ast::parse_schema,
+    ir::repr::IntermediateRepr,
+    lint::{self, LintConfig, Severity},
+};
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+
+use super::{
+    references::{self, Reference},
+    symbols::{self, Symbol},
+    text,
+};
+
+/// All the `baml_src` directories that the editor has opened files from.
+#[derive(Default)]
+pub(super) struct Workspace {
+    projects: IndexMap<PathBuf, Project>,
+}
+
+impl Workspace {
+    /// Sets the (possibly unsaved) contents of `file`, loading its project
+    /// from disk if this is the first file we've seen from it.
+    pub(super) fn set_file(&mut self, file: &Path, text: String) -> &Project {
+        let root = project_root(file);
+        let project = self
+            .projects
+            .entry(root.clone())
+            .or_insert_with(|| Project::load(root));
+        project.files.insert(file.to_path_buf(), text);
+        project.analyze();
+        project
+    }
+
+    /// Goes back to the saved contents of `file`, once the editor closes it.
+    pub(super) fn revert_file(&mut self, file: &Path) -> Option<&Project> {
+        let project = self.projects.get_mut(&project_root(file))?;
+        match fs::read_to_string(file) {
+            Ok(text) => {
+                project.files.insert(file.to_path_buf(), text);
+            }
+            Err(_) => {
+                project.files.shift_remove(file);
+            }
+        }
+        project.analyze();
+        Some(project)
+    }
+
+    pub(super) fn project(&self, file: &Path) -> Option<&Project> {
+        self.projects.get(&project_root(file))
+    }
+}
+
+/// The `baml_src` directory `file` belongs to, or its parent directory if it
+/// isn't in one.
+fn project_root(file: &Path) -> PathBuf {
+    file.ancestors()
+        .find(|dir| dir.file_name().is_some_and(|name| name == "baml_src"))
+        .or_else(|| file.parent())
+        .unwrap_or(file)
+        .to_path_buf()
+}
+
+/// A `baml_src` directory, with the unsaved contents of the files open in
+/// the editor, and the result of validating it.
+pub(super) struct Project {
+    root: PathBuf,
+    files: IndexMap<PathBuf, String>,
+    lint_config: LintConfig,
+    /// Every file in the project has an entry, so that fixed errors get
+    /// cleared in the editor.
+    pub(super) diagnostics: IndexMap<PathBuf, Vec<Diagnostic>>,
+    pub(super) symbols: Vec<Symbol>,
+    references: Vec<Reference>,
+}
+
+impl Project {
+    fn load(root: PathBuf) -> Self {
+        // A project that isn't on disk yet is fine, it just starts out empty.
+        let files = baml_runtime::baml_src_files(&root)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|f| f.extension().is_some_and(|ext| ext == "baml"))
+            .filter_map(|f| {
+                let text = fs::read_to_string(&f).ok()?;
+                Some((f, text))
+            })
+            .collect();
+        let lint_config = crate::lint::load_config(&root).unwrap_or_else(|e| {
+            log::warn!("Ignoring the lint config: {:#}", e);
+            LintConfig::default()
+        });
+
+        Project {
+            root,
+            files,
+            lint_config,
+            diagnostics: IndexMap::new(),
+            symbols: vec![],
+            references: vec![],
+        }
+    }
+
+    fn analyze(&mut self) {
+        let sources = self
+            .files
+            .iter()
+            .map(|(path, text)| SourceFile::from((path.clone(), text.as_str())))
+            .collect();
+        let schema = internal_baml_core::validate(&self.root, sources);
+
+        self.diagnostics = self.files.keys().map(|f| (f.clone(), vec![])).collect();
+        for error in schema.diagnostics.errors() {
+            self.push_diagnostic(
+                error.span(),
+                error.message(),
+                DiagnosticSeverity::ERROR,
+                None,
+            );
+        }
+        for warning in schema.diagnostics.warnings() {
+            self.push_diagnostic(
+                warning.span(),
+                warning.message(),
+                DiagnosticSeverity::WARNING,
+                None,
+            );
+        }
+        // Lint rules assume a valid project.
+        if !schema.diagnostics.has_errors() {
+            for finding in lint::lint(&schema.db, &self.lint_config) {
+                let severity = match finding.severity {
+                    Severity::Error => DiagnosticSeverity::ERROR,
+                    _ => DiagnosticSeverity::WARNING,
+                };
+                self.push_diagnostic(
+                    &finding.span,
+                    &finding.message,
+                    severity,
+                    Some(finding.rule),
+                );
+            }
+        }
+
+        // The validated AST has generic classes replaced by their
+        // instantiations, so names are looked up in an AST that only has its
+        // namespaces resolved, the same way the compiler does.
+        let mut db = ParserDatabase::new();
+        for (path, text) in &self.files {
+            let source = SourceFile::from((path.clone(), text.as_str()));
+            if let Ok((ast, _)) = parse_schema(&self.root, &source) {
+                db.add_ast(ast);
+            }
+        }
+        db.resolve_namespaces(&mut Diagnostics::new(self.root.clone()));
+        self.symbols = symbols::collect(&db, &self.files);
+        self.references = references::collect(&db, &self.files);
+
+        if !schema.diagnostics.has_errors() {
+            match IntermediateRepr::from_parser_database(&schema.db, schema.configuration) {
+                Ok(ir) => {
+                    for symbol in &mut self.symbols {
+                        if let Some(hover) = symbols::resolved_hover(&ir, symbol) {
+                            symbol.hover = hover;
+                        }
+                    }
+                }
+                Err(e) => log::warn!("Hovers show the source only: {:#}", e),
+            }
+        }
+    }
+
+    fn push_diagnostic(
+        &mut self,
+        span: &Span,
+        message: &str,
+        severity: DiagnosticSeverity,
+        rule: Option<&str>,
+    ) {
+        let file = span.file.path_buf();
+        let (Some(text), Some(diagnostics)) =
+            (self.files.get(file), self.diagnostics.get_mut(file))
+        else {
+            return;
+        };
+        diagnostics.push(Diagnostic {
+            range: text::range(text, &(span.start..span.end)),
+            severity: Some(severity),
+            code: rule.map(|rule| NumberOrString::String(rule.to_string())),
+            source: Some("baml".to_string()),
+            message: message.to_string(),
+            ..Default::default()
+        });
+    }
+
+    pub(super) fn text(&self, file: &Path) -> Option<&str> {
+        self.files.get(file).map(String::as_str)
+    }
+
+    /// The symbol with the qualified name `name`.
+    pub(super) fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|s| s.qualified_name == name)
+    }
+
+    /// The symbol declared or referred to at `offset` in `file`, and where
+    /// its name is.
+    pub(super) fn symbol_at(&self, file: &Path, offset: usize) -> Option<(&Symbol, Range<usize>)> {
+        let at_offset = |span: &Range<usize>| span.start <= offset && offset <= span.end;
+        if let Some(symbol) = self
+            .symbols
+            .iter()
+            .find(|s| s.file == file && at_offset(&s.name_span))
+        {
+            return Some((symbol, symbol.name_span.clone()));
+        }
+        let reference = self
+            .references
+            .iter()
+            .find(|r| r.file == file && at_offset(&r.span))?;
+        Some((self.resolve(reference)?, reference.span.clone()))
+    }
+
+    /// Every use of `symbol` in the project, starting with its declaration.
+    pub(super) fn references(&self, symbol: &Symbol) -> Vec<(&Path, &str, Range<usize>)> {
+        let uses = self
+            .references
+            .iter()
+            .filter(|r| self.resolve(r).is_some_and(|s| std::ptr::eq(s, symbol)))
+            .map(|r| (r.file.as_path(), r.span.clone()));
+        std::iter::once((symbol.file.as_path(), symbol.name_span.clone()))
+            .chain(uses)
+            .filter_map(|(path, span)| Some((path, self.text(path)?, span)))
+            .collect()
+    }
+
+    fn resolve(&self, reference: &Reference) -> Option<&Symbol> {
+        reference.names.iter().find_map(|name| {
+            self.symbols
+                .iter()
+                .find(|s| s.qualified_name == *name && reference.kinds.contains(&s.kind))
+        })
+    }
+}
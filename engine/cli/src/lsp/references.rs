@@ -0,0 +1,214 @@
+use std::{ops::Range, path::PathBuf};
+
+use indexmap::IndexMap;
+use internal_baml_core::{
+    ast::{Expression, FieldType, Identifier, Top, WithName, WithSpan},
+    internal_baml_parser_database::ParserDatabase,
+};
+
+use super::{symbols::SymbolKind, text};
+
+const TYPES: &[SymbolKind] = &[SymbolKind::Class, SymbolKind::Enum];
+
+/// A place where a top-level declaration is referred to by name. Found
+/// through the AST, so fields, arguments, enum values and prompt variables
+/// that happen to have the same name don't count.
+pub(super) struct Reference {
+    /// The qualified names of the declarations the name can refer to, the
+    /// one that takes precedence first.
+    pub(super) names: Vec<String>,
+    /// The kinds of declaration the name can refer to here.
+    pub(super) kinds: &'static [SymbolKind],
+    pub(super) file: PathBuf,
+    pub(super) span: Range<usize>,
+}
+
+impl Reference {
+    /// `identifier` has been resolved to the canonical name of what it refers
+    /// to, if it is namespaced.
+    fn new(db: &ParserDatabase, identifier: &Identifier, kinds: &'static [SymbolKind]) -> Self {
+        let span = identifier.span();
+        Reference {
+            names: vec![qualified_name(db, identifier.name())],
+            kinds,
+            file: span.file.path_buf().clone(),
+            span: span.start..span.end,
+        }
+    }
+}
+
+/// The name `canonical` is written as in the source, `shared.resumes.Resume`
+/// for `Shared_Resumes_Resume`.
+pub(super) fn qualified_name(db: &ParserDatabase, canonical: &str) -> String {
+    db.qualified_name(canonical)
+        .map_or_else(|| canonical.to_string(), ToString::to_string)
+}
+
+/// `db` has the namespaces of `files` resolved, but not their generic
+/// classes instantiated: that rewrites `Page<Resume>` into a reference to a
+/// class that isn't in the source.
+pub(super) fn collect(db: &ParserDatabase, files: &IndexMap<PathBuf, String>) -> Vec<Reference> {
+    let mut references = vec![];
+
+    for (_, top) in db.ast().iter_tops() {
+        match top {
+            Top::Class(class) | Top::Enum(class) => {
+                references.extend(class.extends.iter().map(|id| Reference::new(db, id, TYPES)));
+                for field in &class.fields {
+                    let Some(field_type) = &field.expr else {
+                        continue;
+                    };
+                    references.extend(
+                        type_references(db, field_type).filter(|r| {
+                            !class.type_parameters.iter().any(|p| r.names == [p.name()])
+                        }),
+                    );
+                }
+            }
+            Top::Function(function) => {
+                let args = function.input().into_iter().flat_map(|args| &args.args);
+                for (_, arg) in args {
+                    references.extend(type_references(db, &arg.field_type));
+                }
+                if let Some(output) = function.output() {
+                    references.extend(type_references(db, &output.field_type));
+                }
+                for field in function.fields() {
+                    if let (Some(Expression::Identifier(client)), "client") =
+                        (&field.expr, field.name())
+                    {
+                        references.push(Reference::new(db, client, &[SymbolKind::Client]));
+                    }
+                }
+            }
+            Top::TemplateString(template) => {
+                let args = template.input().into_iter().flat_map(|args| &args.args);
+                for (_, arg) in args {
+                    references.extend(type_references(db, &arg.field_type));
+                }
+            }
+            Top::Client(client) => {
+                for field in client.fields() {
+                    match (&field.expr, field.name()) {
+                        (Some(Expression::Identifier(policy)), "retry_policy") => {
+                            references.push(Reference::new(db, policy, &[SymbolKind::RetryPolicy]));
+                        }
+                        (Some(Expression::Map(options, _)), "options") => {
+                            // Fallback and round-robin clients list the
+                            // clients they use.
+                            let strategy =
+                                options.iter().find_map(|(key, value)| match (key, value) {
+                                    (
+                                        Expression::Identifier(key),
+                                        Expression::Array(clients, _),
+                                    ) if key.name() == "strategy" => Some(clients),
+                                    _ => None,
+                                });
+                            references.extend(identifiers(db, strategy, &[SymbolKind::Client]));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Top::TestCase(test) => {
+                for field in test.fields() {
+                    if let (Some(Expression::Array(functions, _)), "functions") =
+                        (&field.expr, field.name())
+                    {
+                        references.extend(identifiers(
+                            db,
+                            Some(functions),
+                            &[SymbolKind::Function],
+                        ));
+                    }
+                }
+            }
+            Top::Generator(_) | Top::RetryPolicy(_) => {}
+        }
+    }
+
+    // Prompts can only refer to template strings, by calling them. The
+    // compiler rewrites the names in its copy of the prompt, not in the
+    // source, so they are resolved here the same way: from the namespace of
+    // the prompt outwards.
+    for (file, text) in files {
+        let scan = text::scan(text);
+        for word in scan.words {
+            let in_jinja = scan
+                .jinja
+                .iter()
+                .any(|j| j.start <= word.start && word.end <= j.end);
+            if !in_jinja || !text[word.end..].trim_start().starts_with('(') {
+                continue;
+            }
+
+            // `shared.Helper(x)` is one name.
+            let mut start = word.start;
+            while let Some(before) = text[..start].strip_suffix('.') {
+                let segment =
+                    before.trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+                if segment.len() == before.len() {
+                    break;
+                }
+                start = segment.len();
+            }
+            let name = &text[start..word.end];
+
+            let namespace = db
+                .ast()
+                .tops
+                .iter()
+                .find(|top| {
+                    let span = top.span();
+                    span.file.path_buf() == file && span.start <= start && word.end <= span.end
+                })
+                .and_then(|top| db.qualified_name(top.name()))
+                .map(|qualified| qualified.namespace.as_slice())
+                .unwrap_or_default();
+            let names = (0..=namespace.len())
+                .rev()
+                .map(|depth| {
+                    namespace[..depth]
+                        .iter()
+                        .map(String::as_str)
+                        .chain(std::iter::once(name))
+                        .collect::<Vec<_>>()
+                        .join(".")
+                })
+                .collect();
+
+            references.push(Reference {
+                names,
+                kinds: &[SymbolKind::TemplateString],
+                file: file.clone(),
+                span: start..word.end,
+            });
+        }
+    }
+
+    references
+}
+
+fn type_references<'a>(
+    db: &'a ParserDatabase,
+    field_type: &'a FieldType,
+) -> impl Iterator<Item = Reference> + 'a {
+    field_type
+        .flat_idns()
+        .into_iter()
+        .map(move |id| Reference::new(db, id, TYPES))
+}
+
+fn identifiers<'a>(
+    db: &'a ParserDatabase,
+    values: Option<&'a Vec<Expression>>,
+    kinds: &'static [SymbolKind],
+) -> impl Iterator<Item = Reference> + 'a {
+    values
+        .into_iter()
+        .flatten()
+        .filter_map(move |value| match value {
+            Expression::Identifier(id) => Some(Reference::new(db, id, kinds)),
+            _ => None,
+        })
+}
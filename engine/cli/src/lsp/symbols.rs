@@ -0,0 +1,165 @@
+use std::{ops::Range, path::PathBuf};
+
+use indexmap::IndexMap;
+use internal_baml_core::{
+    ast::{Top, WithIdentifier, WithName, WithSpan},
+    internal_baml_parser_database::ParserDatabase,
+    ir::{repr::IntermediateRepr, IRHelper},
+};
+
+use super::references::qualified_name;
+
+/// Hovers show at most this many lines of a declaration.
+const MAX_HOVER_LINES: usize = 30;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum SymbolKind {
+    Class,
+    Enum,
+    Function,
+    Client,
+    TemplateString,
+    RetryPolicy,
+    Generator,
+    Test,
+}
+
+impl SymbolKind {
+    fn of(top: &Top) -> Self {
+        match top {
+            Top::Class(_) => SymbolKind::Class,
+            Top::Enum(_) => SymbolKind::Enum,
+            Top::Function(_) => SymbolKind::Function,
+            Top::Client(_) => SymbolKind::Client,
+            Top::TemplateString(_) => SymbolKind::TemplateString,
+            Top::RetryPolicy(_) => SymbolKind::RetryPolicy,
+            Top::Generator(_) => SymbolKind::Generator,
+            Top::TestCase(_) => SymbolKind::Test,
+        }
+    }
+
+    pub(super) fn is_type(self) -> bool {
+        matches!(self, SymbolKind::Class | SymbolKind::Enum)
+    }
+}
+
+/// A top-level declaration.
+pub(super) struct Symbol {
+    /// The canonical name, which the compiler and the IR use.
+    pub(super) name: String,
+    /// The name as written in the source, `shared.resumes.Resume` for
+    /// namespaced declarations. It can be referred to by that name from
+    /// anywhere.
+    pub(super) qualified_name: String,
+    pub(super) kind: SymbolKind,
+    pub(super) file: PathBuf,
+    /// Where the name is in `file`, in bytes.
+    pub(super) name_span: Range<usize>,
+    /// Where the whole declaration is in `file`, in bytes.
+    pub(super) span: Range<usize>,
+    /// The arguments of functions and template strings.
+    pub(super) params: Vec<String>,
+    /// Markdown shown when hovering over the symbol.
+    pub(super) hover: String,
+}
+
+/// `db` has the namespaces of `files` resolved, so declarations have their
+/// canonical names.
+pub(super) fn collect(db: &ParserDatabase, files: &IndexMap<PathBuf, String>) -> Vec<Symbol> {
+    let mut symbols = vec![];
+
+    for (_, top) in db.ast().iter_tops() {
+        let name_span = top.identifier().span();
+        let Some(text) = files.get(name_span.file.path_buf()) else {
+            continue;
+        };
+
+        let span = top.span();
+        let kind = SymbolKind::of(top);
+        let params = match top {
+            Top::Function(f) => f.input(),
+            Top::TemplateString(t) => t.input(),
+            _ => None,
+        }
+        .map(|args| {
+            args.args
+                .iter()
+                .map(|(name, _)| name.name().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+        symbols.push(Symbol {
+            name: top.name().to_string(),
+            qualified_name: qualified_name(db, top.name()),
+            kind,
+            file: name_span.file.path_buf().clone(),
+            name_span: name_span.start..name_span.end,
+            span: span.start..span.end,
+            params,
+            hover: hover(kind, text.get(span.start..span.end).unwrap_or_default()),
+        });
+    }
+
+    symbols
+}
+
+/// A hover for `symbol` that shows the types as the compiler sees them:
+/// classes with their inherited fields, functions with their resolved
+/// signature. `None` for other symbols, and for those `ir` doesn't have, like
+/// generic classes, which only exist in the IR once instantiated.
+pub(super) fn resolved_hover(ir: &IntermediateRepr, symbol: &Symbol) -> Option<String> {
+    let source = match symbol.kind {
+        SymbolKind::Class => {
+            let class = ir.find_class(&symbol.name).ok()?;
+            let fields = class
+                .walk_fields()
+                .map(|field| format!("  {} {}\n", field.name(), field.r#type()))
+                .collect::<String>();
+            format!("class {} {{\n{}}}", symbol.qualified_name, fields)
+        }
+        SymbolKind::Function => {
+            let function = ir.find_function(&symbol.name).ok()?;
+            let args = function
+                .inputs()
+                .iter()
+                .map(|(name, r#type)| format!("{}: {}", name, r#type))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let signature = format!(
+                "function {}({}) -> {}",
+                symbol.qualified_name,
+                args,
+                function.output()
+            );
+            return Some(match function.client_name() {
+                Some(client) => format!("```baml\n{}\n```\n\nclient: `{}`", signature, client),
+                None => format!("```baml\n{}\n```", signature),
+            });
+        }
+        _ => return None,
+    };
+    Some(hover(symbol.kind, &source))
+}
+
+fn hover(kind: SymbolKind, source: &str) -> String {
+    if kind == SymbolKind::Function {
+        // Prompts are long; the signature and the client are what matter.
+        let signature = source.split('{').next().unwrap_or(source).trim_end();
+        let client = source
+            .lines()
+            .map(str::trim)
+            .find_map(|line| line.strip_prefix("client "));
+        return match client {
+            Some(client) => format!("```baml\n{}\n```\n\nclient: `{}`", signature, client.trim()),
+            None => format!("```baml\n{}\n```", signature),
+        };
+    }
+
+    let mut lines = source.lines().collect::<Vec<_>>();
+    if lines.len() > MAX_HOVER_LINES {
+        lines.truncate(MAX_HOVER_LINES);
+        lines.push("  ...");
+    }
+    format!("```baml\n{}\n```", lines.join("\n"))
+}
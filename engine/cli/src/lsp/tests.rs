@@ -0,0 +1,498 @@
+//! Drives the server through an in-process client, the way an editor would.
+
+use std::{path::PathBuf, thread::JoinHandle};
+
+use lsp_server::{Connection, Message, RequestId};
+use lsp_types::{
+    notification::{DidOpenTextDocument, Exit, Initialized, Notification as _, PublishDiagnostics},
+    request::{
+        Completion, Formatting, GotoDefinition, HoverRequest, Initialize, References, Rename,
+        Request as _, Shutdown,
+    },
+    CompletionParams, CompletionResponse, Diagnostic, DiagnosticSeverity,
+    DidOpenTextDocumentParams, DocumentFormattingParams, FormattingOptions, GotoDefinitionParams,
+    GotoDefinitionResponse, HoverContents, HoverParams, InitializeParams, InitializedParams,
+    NumberOrString, Position, PublishDiagnosticsParams, ReferenceContext, ReferenceParams,
+    RenameParams, TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, Url,
+};
+
+use super::{serve, text};
+
+const TYPES: &str = r#"class Resume {
+  name string @description("The full name")
+  skills Skill[]
+}
+
+enum Skill {
+  Rust
+  Python
+}
+"#;
+
+const FUNCTIONS: &str = r##"function ExtractResume(resume_text: string) -> Resume {
+  client GPT4
+  prompt #"
+    Extract the Resume from {{ resume_text }}.
+
+    {{ ctx.output_format }}
+  "#
+}
+
+client<llm> GPT4 {
+  provider openai
+  options {
+    model gpt-4o
+  }
+}
+"##;
+
+struct Client {
+    connection: Connection,
+    server: Option<JoinHandle<anyhow::Result<()>>>,
+    next_id: i32,
+    root: PathBuf,
+}
+
+impl Client {
+    fn start() -> Self {
+        let (server, connection) = Connection::memory();
+        let server = std::thread::spawn(move || serve(server));
+        let mut client = Client {
+            connection,
+            server: Some(server),
+            next_id: 0,
+            // Never created: the server only reads the files we open.
+            root: std::env::temp_dir()
+                .join(format!("baml-lsp-test-{}", uuid::Uuid::new_v4()))
+                .join("baml_src"),
+        };
+        client.request::<Initialize>(InitializeParams::default());
+        client.notify::<Initialized>(InitializedParams {});
+        client
+    }
+
+    fn url(&self, file: &str) -> Url {
+        Url::from_file_path(self.root.join(file)).unwrap()
+    }
+
+    fn request<R: lsp_types::request::Request>(&mut self, params: R::Params) -> R::Result {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        self.connection
+            .sender
+            .send(Message::Request(lsp_server::Request::new(
+                id.clone(),
+                R::METHOD.to_string(),
+                params,
+            )))
+            .unwrap();
+
+        loop {
+            match self.connection.receiver.recv().unwrap() {
+                Message::Response(response) if response.id == id => {
+                    if let Some(error) = response.error {
+                        panic!("{} failed: {}", R::METHOD, error.message);
+                    }
+                    return serde_json::from_value(response.result.unwrap_or_default()).unwrap();
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    fn notify<N: lsp_types::notification::Notification>(&self, params: N::Params) {
+        self.connection
+            .sender
+            .send(Message::Notification(lsp_server::Notification::new(
+                N::METHOD.to_string(),
+                params,
+            )))
+            .unwrap();
+    }
+
+    /// Opens a document, and returns the diagnostics published for it.
+    fn open(&mut self, file: &str, text: &str) -> Vec<Diagnostic> {
+        let uri = self.url(file);
+        self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(uri.clone(), "baml".to_string(), 1, text.into()),
+        });
+
+        loop {
+            if let Message::Notification(notification) = self.connection.receiver.recv().unwrap() {
+                if notification.method != PublishDiagnostics::METHOD {
+                    continue;
+                }
+                let params: PublishDiagnosticsParams =
+                    serde_json::from_value(notification.params).unwrap();
+                if params.uri == uri {
+                    return params.diagnostics;
+                }
+            }
+        }
+    }
+
+    fn position(&self, file: &str, text: &str, needle: &str) -> TextDocumentPositionParams {
+        let offset = text
+            .find(needle)
+            .unwrap_or_else(|| panic!("{needle} not found"));
+        TextDocumentPositionParams::new(
+            TextDocumentIdentifier::new(self.url(file)),
+            text::position(text, offset),
+        )
+    }
+
+    fn open_project(&mut self) {
+        // Until functions.baml is open, the types are unused.
+        self.open("types.baml", TYPES);
+        assert_eq!(self.open("functions.baml", FUNCTIONS), vec![]);
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        self.request::<Shutdown>(());
+        self.notify::<Exit>(());
+        if let Some(server) = self.server.take() {
+            server.join().unwrap().unwrap();
+        }
+    }
+}
+
+fn complete(client: &mut Client, position: TextDocumentPositionParams) -> Vec<String> {
+    let response = client.request::<Completion>(CompletionParams {
+        text_document_position: position,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        context: None,
+    });
+    match response {
+        Some(CompletionResponse::Array(items)) => items.into_iter().map(|i| i.label).collect(),
+        _ => vec![],
+    }
+}
+
+#[test]
+fn publishes_diagnostics() {
+    let mut client = Client::start();
+    let diagnostics = client.open("main.baml", "class Foo {\n  bar Bar\n}\n");
+
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert!(diagnostics[0].message.contains("Bar"));
+    assert_eq!(diagnostics[0].range.start.line, 1);
+}
+
+#[test]
+fn publishes_lint_findings() {
+    let mut client = Client::start();
+    let diagnostics = client.open("types.baml", TYPES);
+
+    let codes = diagnostics
+        .iter()
+        .map(|d| d.code.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        vec![Some(NumberOrString::String("unused-class".to_string()))],
+        "{:?}",
+        diagnostics
+    );
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+}
+
+#[test]
+fn goes_to_definition_and_finds_references() {
+    let mut client = Client::start();
+    client.open_project();
+
+    let position = client.position("functions.baml", FUNCTIONS, "Resume {");
+    let definition = client.request::<GotoDefinition>(GotoDefinitionParams {
+        text_document_position_params: position.clone(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let Some(GotoDefinitionResponse::Scalar(location)) = definition.clone() else {
+        panic!("expected a single location, got {definition:?}");
+    };
+    assert_eq!(location.uri, client.url("types.baml"));
+    assert_eq!(
+        location.range,
+        lsp_types::Range::new(Position::new(0, 6), Position::new(0, 12))
+    );
+
+    // The prompt mentions "Resume" too, but only in plain text.
+    let references = client
+        .request::<References>(ReferenceParams {
+            text_document_position: position,
+            context: ReferenceContext {
+                include_declaration: false,
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .unwrap();
+    assert_eq!(references.len(), 1);
+    assert_eq!(references[0].uri, client.url("functions.baml"));
+}
+
+#[test]
+fn hovers_with_the_declaration() {
+    let mut client = Client::start();
+    client.open_project();
+
+    let hover = client
+        .request::<HoverRequest>(HoverParams {
+            text_document_position_params: client.position("functions.baml", FUNCTIONS, "Resume {"),
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap();
+    let HoverContents::Markup(contents) = hover.contents else {
+        panic!("expected markdown");
+    };
+    assert!(
+        contents.value.contains("skills Skill[]"),
+        "{}",
+        contents.value
+    );
+
+    let hover = client
+        .request::<HoverRequest>(HoverParams {
+            text_document_position_params: client.position("types.baml", TYPES, "Skill["),
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap();
+    let HoverContents::Markup(contents) = hover.contents else {
+        panic!("expected markdown");
+    };
+    assert!(contents.value.contains("enum Skill"));
+}
+
+#[test]
+fn completes_types_clients_and_prompt_variables() {
+    let mut client = Client::start();
+    client.open_project();
+
+    let mut in_prompt = client.position("functions.baml", FUNCTIONS, "resume_text }}");
+    in_prompt.position.character += 2;
+    let labels = complete(&mut client, in_prompt);
+    assert!(labels.contains(&"resume_text".to_string()), "{labels:?}");
+    assert!(labels.contains(&"ctx".to_string()), "{labels:?}");
+    assert!(!labels.contains(&"Resume".to_string()), "{labels:?}");
+
+    let mut after_ctx = client.position("functions.baml", FUNCTIONS, "output_format }}");
+    after_ctx.position.character += 3;
+    assert!(complete(&mut client, after_ctx).contains(&"output_format".to_string()));
+
+    let after_client = client.position("functions.baml", FUNCTIONS, "GPT4\n  prompt");
+    assert_eq!(
+        complete(&mut client, after_client),
+        vec!["GPT4".to_string()]
+    );
+
+    let in_type = client.position("types.baml", TYPES, "Skill[]");
+    let labels = complete(&mut client, in_type);
+    assert!(labels.contains(&"string".to_string()), "{labels:?}");
+    assert!(labels.contains(&"Resume".to_string()), "{labels:?}");
+    assert!(labels.contains(&"Skill".to_string()), "{labels:?}");
+    assert!(!labels.contains(&"GPT4".to_string()), "{labels:?}");
+}
+
+#[test]
+fn renames_across_files() {
+    let mut client = Client::start();
+    client.open_project();
+
+    let edit = client
+        .request::<Rename>(RenameParams {
+            text_document_position: client.position("types.baml", TYPES, "Skill["),
+            new_name: "Ability".to_string(),
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap();
+    let changes = edit.changes.unwrap();
+
+    let types = &changes[&client.url("types.baml")];
+    assert_eq!(types.len(), 2);
+    assert!(types.iter().all(|e| e.new_text == "Ability"));
+    assert!(!changes.contains_key(&client.url("functions.baml")));
+}
+
+#[test]
+fn renames_only_what_refers_to_the_symbol() {
+    const SHADOWED: &str = r##"class Foo {
+  Foo string
+}
+
+class Bar extends Foo {
+  foos Foo[]
+}
+
+enum Kind {
+  Foo
+}
+
+function MakeBar(Foo: string) -> Bar {
+  client "openai/gpt-4o"
+  prompt #"
+    {{ Foo }} {{ ctx.output_format }}
+  "#
+}
+"##;
+    let mut client = Client::start();
+    client.open("main.baml", SHADOWED);
+
+    let edit = client
+        .request::<Rename>(RenameParams {
+            text_document_position: client.position("main.baml", SHADOWED, "Foo[]"),
+            new_name: "Base".to_string(),
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap();
+    let changes = edit.changes.unwrap();
+    let mut lines = changes[&client.url("main.baml")]
+        .iter()
+        .map(|e| e.range.start.line)
+        .collect::<Vec<_>>();
+    lines.sort();
+    // The declaration, `extends Foo` and `Foo[]`; not the field, the enum
+    // value, the argument or the prompt variable.
+    assert_eq!(lines, vec![0, 4, 5]);
+
+    let hover = client
+        .request::<HoverRequest>(HoverParams {
+            text_document_position_params: client.position(
+                "main.baml",
+                SHADOWED,
+                "Bar {\n  client",
+            ),
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap();
+    let HoverContents::Markup(contents) = hover.contents else {
+        panic!("expected markdown");
+    };
+    // Inherited fields are included.
+    assert!(
+        contents.value.contains("  Foo string\n  foos Foo[]\n"),
+        "{}",
+        contents.value
+    );
+}
+
+#[test]
+fn resolves_namespaced_types() {
+    const SHARED: &str = r#"namespace shared
+
+class Resume {
+  name string
+}
+"#;
+    const MAIN: &str = r#"class Resume {
+  title string
+}
+
+class Search {
+  mine Resume
+  theirs shared.Resume
+}
+"#;
+    let mut client = Client::start();
+    client.open("shared.baml", SHARED);
+    client.open("main.baml", MAIN);
+
+    let definition = |client: &mut Client, needle: &str| {
+        let response = client.request::<GotoDefinition>(GotoDefinitionParams {
+            text_document_position_params: client.position("main.baml", MAIN, needle),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        let Some(GotoDefinitionResponse::Scalar(location)) = response else {
+            panic!("expected a single location, got {response:?}");
+        };
+        location
+    };
+    let location = definition(&mut client, "shared.Resume");
+    assert_eq!(location.uri, client.url("shared.baml"));
+    assert_eq!(
+        location.range,
+        lsp_types::Range::new(Position::new(2, 6), Position::new(2, 12))
+    );
+    let location = definition(&mut client, "Resume\n  theirs");
+    assert_eq!(location.uri, client.url("main.baml"));
+    assert_eq!(location.range.start, Position::new(0, 6));
+
+    let hover = client
+        .request::<HoverRequest>(HoverParams {
+            text_document_position_params: client.position("main.baml", MAIN, "shared.Resume"),
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap();
+    let HoverContents::Markup(contents) = hover.contents else {
+        panic!("expected markdown");
+    };
+    assert!(
+        contents
+            .value
+            .contains("class shared.Resume {\n  name string\n}"),
+        "{}",
+        contents.value
+    );
+
+    let in_type = client.position("main.baml", MAIN, "Resume\n  theirs");
+    let labels = complete(&mut client, in_type);
+    assert!(labels.contains(&"Resume".to_string()), "{labels:?}");
+    assert!(labels.contains(&"shared.Resume".to_string()), "{labels:?}");
+
+    // Only the name is renamed, not the namespace in front of it.
+    let edit = client
+        .request::<Rename>(RenameParams {
+            text_document_position: client.position("shared.baml", SHARED, "Resume"),
+            new_name: "Profile".to_string(),
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap();
+    let changes = edit.changes.unwrap();
+    assert_eq!(
+        changes[&client.url("shared.baml")]
+            .iter()
+            .map(|e| e.range)
+            .collect::<Vec<_>>(),
+        vec![lsp_types::Range::new(
+            Position::new(2, 6),
+            Position::new(2, 12)
+        )]
+    );
+    assert_eq!(
+        changes[&client.url("main.baml")]
+            .iter()
+            .map(|e| e.range)
+            .collect::<Vec<_>>(),
+        vec![lsp_types::Range::new(
+            Position::new(6, 16),
+            Position::new(6, 22)
+        )]
+    );
+}
+
+#[test]
+fn formats_documents() {
+    let mut client = Client::start();
+    client.open("main.baml", "class Foo {\n      bar   string|int\n}");
+
+    let edits = client
+        .request::<Formatting>(DocumentFormattingParams {
+            text_document: TextDocumentIdentifier::new(client.url("main.baml")),
+            options: FormattingOptions {
+                tab_size: 2,
+                insert_spaces: true,
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap();
+
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].new_text, "class Foo {\n  bar string | int\n}\n");
+}
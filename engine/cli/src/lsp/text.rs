@@ -0,0 +1,234 @@
+use std::ops::Range;
+
+use lsp_types::Position;
+
+/// Converts a byte offset, which is what spans in the engine use, to an LSP
+/// position, which counts UTF-16 code units.
+pub(super) fn position(text: &str, offset: usize) -> Position {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let character = before[line_start..].encode_utf16().count();
+    Position::new(line as u32, character as u32)
+}
+
+/// The inverse of `position`. Positions past the end of a line are clamped
+/// to the end of that line.
+pub(super) fn offset(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return text.len(),
+        }
+    }
+
+    let line = &text[line_start..];
+    let line = &line[..line.find('\n').unwrap_or(line.len())];
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units >= position.character as usize {
+            return line_start + i;
+        }
+        units += c.len_utf16();
+    }
+    line_start + line.len()
+}
+
+pub(super) fn range(text: &str, span: &Range<usize>) -> lsp_types::Range {
+    lsp_types::Range::new(position(text, span.start), position(text, span.end))
+}
+
+/// Where the words of a BAML file are, for the features that need to know
+/// about prompts (completions, and template strings called from prompts).
+#[derive(Default)]
+pub(super) struct Scan {
+    /// Every identifier outside comments and quoted strings. In raw strings
+    /// (prompts), only the identifiers inside `{{ }}` and `{% %}` count.
+    pub words: Vec<Range<usize>>,
+    /// The contents of raw strings.
+    pub raw_strings: Vec<Range<usize>>,
+    /// The contents of Jinja expressions and statements in raw strings. An
+    /// unclosed one runs to the end of its raw string.
+    pub jinja: Vec<Range<usize>>,
+}
+
+pub(super) fn scan(text: &str) -> Scan {
+    let b = text.as_bytes();
+    let mut scan = Scan::default();
+    let mut i = 0;
+
+    while i < b.len() {
+        match b[i] {
+            b'/' if b.get(i + 1) == Some(&b'/') => {
+                i = text[i..].find('\n').map_or(b.len(), |n| i + n);
+            }
+            b'{' if text[i..].starts_with("{//") => {
+                i = text[i..].find("//}").map_or(b.len(), |n| i + n + 3);
+            }
+            b'"' => {
+                i += 1;
+                while i < b.len() && b[i] != b'"' && b[i] != b'\n' {
+                    i += if b[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+            }
+            b'#' => {
+                let hashes = b[i..].iter().take_while(|c| **c == b'#').count();
+                if b.get(i + hashes) != Some(&b'"') {
+                    i += hashes;
+                    continue;
+                }
+                let start = i + hashes + 1;
+                let close = format!("\"{}", "#".repeat(hashes));
+                let end = text[start..].find(&close).map_or(b.len(), |n| start + n);
+                scan.raw_strings.push(start..end);
+                scan_jinja(text, start..end, &mut scan);
+                i = (end + close.len()).min(b.len());
+            }
+            c if c.is_ascii_digit() => {
+                while i < b.len() && is_word_char(b[i]) {
+                    i += 1;
+                }
+            }
+            c if is_word_start(c) => {
+                let start = i;
+                while i < b.len() && (is_word_char(b[i]) || b[i] == b'-') {
+                    i += 1;
+                }
+                // Skip `env.FOO` and the like: only the first part of a path
+                // can name something.
+                if start == 0 || b[start - 1] != b'.' {
+                    scan.words.push(start..i);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    scan
+}
+
+fn scan_jinja(text: &str, raw: Range<usize>, scan: &mut Scan) {
+    let b = text.as_bytes();
+    let mut i = raw.start;
+
+    while i + 1 < raw.end {
+        let close = match (b[i], b[i + 1]) {
+            (b'{', b'{') => "}}",
+            (b'{', b'%') => "%}",
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        let start = i + 2;
+        let end = text[start..raw.end]
+            .find(close)
+            .map_or(raw.end, |n| start + n);
+        scan.jinja.push(start..end);
+
+        let mut j = start;
+        while j < end {
+            match b[j] {
+                q @ (b'"' | b'\'') => {
+                    j += 1;
+                    while j < end && b[j] != q {
+                        j += 1;
+                    }
+                    j += 1;
+                }
+                c if c.is_ascii_digit() => {
+                    while j < end && is_word_char(b[j]) {
+                        j += 1;
+                    }
+                }
+                c if is_word_start(c) => {
+                    let word_start = j;
+                    while j < end && is_word_char(b[j]) {
+                        j += 1;
+                    }
+                    if b[word_start - 1] != b'.' {
+                        scan.words.push(word_start..j);
+                    }
+                }
+                _ => j += 1,
+            }
+        }
+        i = end + close.len();
+    }
+}
+
+fn is_word_start(c: u8) -> bool {
+    c.is_ascii_alphabetic() || c == b'_'
+}
+
+fn is_word_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<&str> {
+        scan(text).words.into_iter().map(|w| &text[w]).collect()
+    }
+
+    #[test]
+    fn skips_comments_strings_and_prompt_text() {
+        let text = r##"
+class Foo { // Bar
+  a Baz @alias("Qux")
+}
+function F(x: Foo) -> Foo {
+  client my-client
+  prompt #"
+    Use Foo {{ x.name }} {{ Tmpl(x) }} {% for y in ys %}
+  "#
+  key env.API_KEY
+}
+"##;
+        assert_eq!(
+            words(text),
+            vec![
+                "class",
+                "Foo",
+                "a",
+                "Baz",
+                "alias",
+                "function",
+                "F",
+                "x",
+                "Foo",
+                "Foo",
+                "client",
+                "my-client",
+                "prompt",
+                "x",
+                "Tmpl",
+                "x",
+                "for",
+                "y",
+                "in",
+                "ys",
+                "key",
+                "env",
+            ]
+        );
+    }
+
+    #[test]
+    fn converts_positions() {
+        let text = "ab\nçd😀e\n";
+        let offset_of_e = text.find('e').unwrap();
+        assert_eq!(position(text, offset_of_e), Position::new(1, 4));
+        assert_eq!(offset(text, Position::new(1, 4)), offset_of_e);
+        assert_eq!(offset(text, Position::new(0, 10)), 2);
+        assert_eq!(offset(text, Position::new(5, 0)), text.len());
+    }
+}
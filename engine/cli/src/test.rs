@@ -0,0 +1,271 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use baml_runtime::{
+    test_dataset::DatasetRow, BamlRuntime, FunctionResult, InternalRuntimeInterface,
+    RuntimeContextManager, TestStatus,
+};
+use baml_types::BamlValue;
+use clap::Args;
+use colored::Colorize;
+use futures::{future::BoxFuture, StreamExt};
+
+#[derive(Args, Debug)]
+pub struct TestArgs {
+    #[arg(long, help = "path/to/baml_src", default_value = "./baml_src")]
+    pub from: PathBuf,
+
+    #[arg(long, help = "Only run the tests of this function. Can be repeated")]
+    function: Vec<String>,
+
+    #[arg(long, help = "Only run the tests with this name. Can be repeated")]
+    test: Vec<String>,
+
+    #[arg(
+        long,
+        help = "How many test cases to run at the same time",
+        default_value_t = 10
+    )]
+    parallel: usize,
+}
+
+/// A test, or a row of the dataset of a test.
+pub(crate) struct TestCase {
+    pub function: String,
+    pub test: String,
+    pub row: Option<DatasetRow>,
+}
+
+impl TestCase {
+    pub fn label(&self) -> String {
+        match &self.row {
+            Some(row) => format!("{}::{} [line {}]", self.function, self.test, row.line),
+            None => format!("{}::{}", self.function, self.test),
+        }
+    }
+}
+
+impl TestArgs {
+    pub fn run(&self) -> Result<()> {
+        let runtime = BamlRuntime::from_directory(&self.from, std::env::vars().collect())?;
+        runtime.async_runtime.block_on(self.run_tests(&runtime))
+    }
+
+    async fn run_tests(&self, runtime: &BamlRuntime) -> Result<()> {
+        let cases = collect_cases(runtime, &self.function, &self.test).await?;
+        if cases.is_empty() {
+            anyhow::bail!("No tests found in {}", self.from.display());
+        }
+
+        let total = cases.len();
+        let mut failed = 0;
+        let mut results = futures::stream::iter(cases.iter())
+            .map(|case| async move {
+                let ctx = ctx_manager(runtime);
+                let (response, _) = runtime
+                    .run_test_row(
+                        &case.function,
+                        &case.test,
+                        case.row.as_ref(),
+                        &ctx,
+                        None,
+                        None::<fn(FunctionResult)>,
+                    )
+                    .await;
+                (case, response)
+            })
+            .buffered(self.parallel.max(1));
+
+        while let Some((case, response)) = results.next().await {
+            let failure = match &response {
+                Ok(response) => match response.status() {
+                    TestStatus::Pass | TestStatus::NeedsHumanEval(_) => None,
+                    TestStatus::Fail(reason) => Some(reason.to_string()),
+                },
+                Err(e) => Some(format!("{:#}", e)),
+            };
+            match failure {
+                None => println!("{}  {}", "PASS".green(), case.label()),
+                Some(message) => {
+                    failed += 1;
+                    println!("{}  {}", "FAIL".red(), case.label());
+                    for line in message.lines() {
+                        println!("      {}", line.dimmed());
+                    }
+                }
+            }
+        }
+
+        println!();
+        println!("{} passed, {} failed", total - failed, failed);
+        if failed > 0 {
+            anyhow::bail!("{} of {} test case(s) failed", failed, total);
+        }
+        Ok(())
+    }
+}
+
+/// The cases of the tests of `functions` named `tests`, one per dataset row
+/// for tests with a dataset. Empty filters match everything.
+pub(crate) async fn collect_cases(
+    runtime: &BamlRuntime,
+    functions: &[String],
+    tests: &[String],
+) -> Result<Vec<TestCase>> {
+    let mut selected = vec![];
+    for test in runtime.internal().ir().walk_tests() {
+        let function = test.function().name().to_string();
+        let test_name = test.test_case().name.clone();
+        if (!functions.is_empty() && !functions.contains(&function))
+            || (!tests.is_empty() && !tests.contains(&test_name))
+        {
+            continue;
+        }
+        selected.push((function, test_name));
+    }
+
+    let mut cases = vec![];
+    for (function, test) in selected {
+        match runtime
+            .test_dataset_rows(&function, &test, &ctx_manager(runtime))
+            .await?
+        {
+            Some(rows) => cases.extend(rows.into_iter().map(|row| TestCase {
+                function: function.clone(),
+                test: test.clone(),
+                row: Some(row),
+            })),
+            None => cases.push(TestCase {
+                function,
+                test,
+                row: None,
+            }),
+        }
+    }
+    Ok(cases)
+}
+
+/// Each test case gets its own context, so that concurrent cases don't share
+/// a span stack.
+pub(crate) fn ctx_manager(runtime: &BamlRuntime) -> RuntimeContextManager {
+    runtime.create_ctx_manager(
+        BamlValue::String("cli".to_string()),
+        Some(Box::new(read_baml_src as _)),
+    )
+}
+
+/// Reads media and dataset files referenced by tests.
+fn read_baml_src(path: &str) -> BoxFuture<'static, Result<Vec<u8>>> {
+    let path = path.to_string();
+    Box::pin(async move { Ok(std::fs::read(path)?) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r##"function Classify(text: string, price: float) -> string {
+  client "openai/gpt-4o-mini"
+  prompt #"
+    Classify {{ text }} at {{ price }}.
+  "#
+}
+
+function Other(text: string) -> string {
+  client "openai/gpt-4o-mini"
+  prompt #"{{ text }}"#
+}
+
+test Inline {
+  functions [Classify, Other]
+  args {
+    text "hello"
+    price 1.5
+  }
+}
+
+test Rows {
+  functions [Classify]
+  dataset {
+    file "rows.csv"
+    args {
+      text "input"
+    }
+    expected "label"
+  }
+}
+"##;
+
+    fn runtime() -> BamlRuntime {
+        let dir = std::env::temp_dir()
+            .join(format!("baml-test-cmd-{}", uuid::Uuid::new_v4()))
+            .join("baml_src");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("main.baml"), SOURCE).unwrap();
+        std::fs::write(
+            dir.join("rows.csv"),
+            "input,price,label\n1.50,1.50,1e3\n,2,\n",
+        )
+        .unwrap();
+        BamlRuntime::from_directory(&dir, std::collections::HashMap::<String, String>::new())
+            .unwrap()
+    }
+
+    fn cases(runtime: &BamlRuntime, functions: &[&str], tests: &[&str]) -> Vec<TestCase> {
+        let to_vec = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        runtime
+            .async_runtime
+            .block_on(collect_cases(runtime, &to_vec(functions), &to_vec(tests)))
+            .unwrap()
+    }
+
+    #[test]
+    fn collects_one_case_per_dataset_row() {
+        let runtime = runtime();
+        let labels = cases(&runtime, &[], &[])
+            .iter()
+            .map(TestCase::label)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            vec![
+                "Classify::Inline",
+                "Classify::Rows [line 2]",
+                "Classify::Rows [line 3]",
+                "Other::Inline",
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_by_function_and_test() {
+        let runtime = runtime();
+        let labels = |functions: &[&str], tests: &[&str]| {
+            cases(&runtime, functions, tests)
+                .iter()
+                .map(TestCase::label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels(&["Other"], &[]), vec!["Other::Inline"]);
+        assert_eq!(labels(&["Classify"], &["Inline"]), vec!["Classify::Inline"]);
+        assert!(labels(&["Missing"], &[]).is_empty());
+    }
+
+    #[test]
+    fn types_csv_cells_by_parameter() {
+        let runtime = runtime();
+        let cases = cases(&runtime, &[], &["Rows"]);
+        let row = cases[0].row.as_ref().unwrap();
+        assert_eq!(
+            row.args.get("text"),
+            Some(&BamlValue::String("1.50".to_string()))
+        );
+        assert_eq!(row.args.get("price"), Some(&BamlValue::Float(1.5)));
+        assert_eq!(row.expected, Some(BamlValue::String("1e3".to_string())));
+
+        let row = cases[1].row.as_ref().unwrap();
+        assert!(!row.args.contains_key("text"));
+        assert_eq!(row.args.get("price"), Some(&BamlValue::Int(2)));
+        assert_eq!(row.expected, None);
+    }
+}
@@ -0,0 +1,578 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    io::{BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand, ValueEnum};
+use colored::Colorize;
+use serde_json::Value;
+
+use crate::tui::TreeNode;
+
+#[derive(Args, Debug)]
+pub struct TracesArgs {
+    #[arg(
+        long,
+        env = "BOUNDARY_TRACE_DIR",
+        help = "The directory traces are written to",
+        default_value = "./.baml_traces"
+    )]
+    pub dir: PathBuf,
+
+    #[command(subcommand)]
+    pub command: TracesCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TracesCommand {
+    #[command(about = "Lists the sessions, one per process that wrote traces")]
+    Sessions,
+
+    #[command(about = "Lists the traces of top-level calls, newest first")]
+    List(TraceFilter),
+
+    #[command(about = "Shows the span tree of a trace, with prompts, raw outputs and retries")]
+    Show {
+        #[arg(help = "The trace id, or a prefix of it")]
+        id: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Status {
+    Ok,
+    Error,
+}
+
+#[derive(Args, Debug)]
+pub struct TraceFilter {
+    #[arg(long, help = "Only traces of this session")]
+    session: Option<String>,
+
+    #[arg(long, help = "Only traces of this function")]
+    function: Option<String>,
+
+    #[arg(
+        long = "tag",
+        value_name = "KEY=VALUE",
+        help = "Only traces with this tag"
+    )]
+    tags: Vec<String>,
+
+    #[arg(long, value_enum)]
+    status: Option<Status>,
+
+    #[arg(long, default_value_t = 50)]
+    limit: usize,
+}
+
+impl TracesArgs {
+    pub fn run(&self) -> Result<()> {
+        match &self.command {
+            TracesCommand::Sessions => print_sessions(&self.dir),
+            TracesCommand::List(filter) => print_traces(&self.dir, filter),
+            TracesCommand::Show { id } => print_trace(&self.dir, id),
+        }
+    }
+}
+
+/// Calls `f` with every event in the trace files of `dir`, oldest file
+/// first. Files are read a line at a time, so only what `f` keeps is held in
+/// memory.
+fn for_each_event(dir: &Path, mut f: impl FnMut(Value) -> Result<()>) -> Result<()> {
+    if !dir.is_dir() {
+        anyhow::bail!(
+            "{} does not exist. Set BOUNDARY_TRACE_DIR when running BAML functions to write traces there.",
+            dir.display()
+        );
+    }
+
+    let mut files = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    for file in files {
+        let reader = fs::File::open(&file)
+            .map(BufReader::new)
+            .with_context(|| format!("Failed to read {}", file.display()))?;
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line.with_context(|| format!("Failed to read {}", file.display()))?;
+            match serde_json::from_str(&line) {
+                Ok(event) => f(event)?,
+                // The last line of a file that is being written can be cut.
+                Err(e) => log::debug!("Skipping {}:{}: {}", file.display(), idx + 1, e),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn str_at<'v>(event: &'v Value, pointer: &str) -> &'v str {
+    event.pointer(pointer).and_then(Value::as_str).unwrap_or("")
+}
+
+fn function_name(event: &Value) -> &str {
+    event
+        .pointer("/context/event_chain")
+        .and_then(Value::as_array)
+        .and_then(|chain| chain.last())
+        .map_or("", |link| str_at(link, "/function_name"))
+}
+
+fn status(event: &Value) -> Status {
+    if event.get("error").is_some_and(|e| !e.is_null()) {
+        Status::Error
+    } else {
+        Status::Ok
+    }
+}
+
+fn status_label(event: &Value) -> colored::ColoredString {
+    match status(event) {
+        Status::Ok => "ok".green(),
+        Status::Error => "error".red(),
+    }
+}
+
+fn is_root(event: &Value) -> bool {
+    event.get("parent_event_id").map_or(true, Value::is_null)
+}
+
+/// A process that wrote traces.
+#[derive(Debug, PartialEq)]
+struct Session {
+    traces: usize,
+    first: String,
+    last: String,
+}
+
+/// Counts `event` in the session of its process if it starts a trace.
+fn add_to_sessions(sessions: &mut BTreeMap<String, Session>, event: &Value) {
+    if !is_root(event) {
+        return;
+    }
+    let start = str_at(event, "/context/start_time");
+    let session = sessions
+        .entry(str_at(event, "/context/process_id").to_string())
+        .or_insert_with(|| Session {
+            traces: 0,
+            first: start.to_string(),
+            last: start.to_string(),
+        });
+    session.traces += 1;
+    if start < session.first.as_str() {
+        session.first = start.to_string();
+    }
+    if start > session.last.as_str() {
+        session.last = start.to_string();
+    }
+}
+
+fn print_sessions(dir: &Path) -> Result<()> {
+    let mut sessions = BTreeMap::new();
+    for_each_event(dir, |event| {
+        add_to_sessions(&mut sessions, &event);
+        Ok(())
+    })?;
+
+    let mut sessions = sessions.into_iter().collect::<Vec<_>>();
+    sessions.sort_by(|(_, a), (_, b)| b.first.cmp(&a.first));
+    for (id, session) in sessions {
+        println!(
+            "{}  {} trace(s)  {} .. {}",
+            id.bold(),
+            session.traces,
+            session.first.dimmed(),
+            session.last.dimmed()
+        );
+    }
+    Ok(())
+}
+
+fn matches(event: &Value, filter: &TraceFilter) -> Result<bool> {
+    if filter
+        .session
+        .as_ref()
+        .is_some_and(|s| s != str_at(event, "/context/process_id"))
+    {
+        return Ok(false);
+    }
+    if filter
+        .function
+        .as_ref()
+        .is_some_and(|f| f != function_name(event))
+    {
+        return Ok(false);
+    }
+    if filter.status.is_some_and(|s| s != status(event)) {
+        return Ok(false);
+    }
+    for tag in &filter.tags {
+        let (key, value) = tag
+            .split_once('=')
+            .with_context(|| format!("Expected --tag KEY=VALUE, got `{}`", tag))?;
+        if event.pointer(&format!("/context/tags/{}", key)) != Some(&Value::from(value)) {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Sorts `roots` newest first and keeps the first `limit`.
+fn keep_newest(roots: &mut Vec<Value>, limit: usize) {
+    roots.sort_by(|a, b| str_at(b, "/context/start_time").cmp(str_at(a, "/context/start_time")));
+    roots.truncate(limit);
+}
+
+fn print_traces(dir: &Path, filter: &TraceFilter) -> Result<()> {
+    let mut roots = vec![];
+    for_each_event(dir, |event| {
+        if is_root(&event) && matches(&event, filter)? {
+            roots.push(event);
+            // Only the newest traces are shown, so that's all that's kept.
+            if roots.len() >= 2 * filter.limit.max(1) {
+                keep_newest(&mut roots, filter.limit);
+            }
+        }
+        Ok(())
+    })?;
+    keep_newest(&mut roots, filter.limit);
+
+    for event in &roots {
+        println!(
+            "{}  {}  {:<5}  {:>6}ms  {}",
+            str_at(event, "/root_event_id").bold(),
+            str_at(event, "/context/start_time").dimmed(),
+            status_label(event),
+            event
+                .pointer("/context/latency_ms")
+                .and_then(Value::as_i64)
+                .unwrap_or_default(),
+            function_name(event)
+        );
+    }
+    Ok(())
+}
+
+fn print_trace(dir: &Path, id: &str) -> Result<()> {
+    // Every event of the trace has its root's id.
+    let mut events = vec![];
+    for_each_event(dir, |event| {
+        if str_at(&event, "/root_event_id").starts_with(id) {
+            events.push(event);
+        }
+        Ok(())
+    })?;
+
+    let (roots, rest): (Vec<_>, Vec<_>) = events.into_iter().partition(is_root);
+    let root = match <[Value; 1]>::try_from(roots) {
+        Ok([root]) => root,
+        Err(roots) if roots.is_empty() => anyhow::bail!("No trace with id `{}`", id),
+        Err(roots) => anyhow::bail!("`{}` is the prefix of {} traces", id, roots.len()),
+    };
+
+    let root_id = str_at(&root, "/root_event_id").to_string();
+    let rest = rest
+        .into_iter()
+        .filter(|e| str_at(e, "/root_event_id") == root_id)
+        .collect();
+    print!("{}", span_node(&span_tree(root, rest)).render());
+    Ok(())
+}
+
+/// A span of a trace, with the spans started inside it.
+#[derive(Debug)]
+struct Span {
+    event: Value,
+    children: Vec<Span>,
+}
+
+/// Assembles the spans of a trace from its root and the rest of its events,
+/// which can be in any order. Children keep the order they were written in.
+fn span_tree(root: Value, events: Vec<Value>) -> Span {
+    let mut by_parent = HashMap::<String, Vec<Value>>::new();
+    for event in events {
+        by_parent
+            .entry(str_at(&event, "/parent_event_id").to_string())
+            .or_default()
+            .push(event);
+    }
+    build_span(root, &mut by_parent)
+}
+
+fn build_span(event: Value, by_parent: &mut HashMap<String, Vec<Value>>) -> Span {
+    let children = by_parent
+        .remove(str_at(&event, "/event_id"))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| build_span(child, by_parent))
+        .collect();
+    Span { event, children }
+}
+
+fn span_node(span: &Span) -> TreeNode {
+    let event = &span.event;
+    let mut node = TreeNode::new(format!(
+        "{} {} {}ms",
+        function_name(event).bold(),
+        status_label(event),
+        event
+            .pointer("/context/latency_ms")
+            .and_then(Value::as_i64)
+            .unwrap_or_default()
+    ));
+
+    let tags = event
+        .pointer("/context/tags")
+        .and_then(Value::as_object)
+        .filter(|tags| !tags.is_empty());
+    if let Some(tags) = tags {
+        node.line(format!("tags: {}", Value::from(tags.clone())));
+    }
+
+    // One entry per attempt: retries and fallbacks.
+    let attempts = match event.get("metadata") {
+        Some(Value::Array(attempts)) => attempts.iter().collect(),
+        Some(attempt @ Value::Object(_)) => vec![attempt],
+        _ => vec![],
+    };
+    for (idx, attempt) in attempts.iter().enumerate() {
+        node.line(
+            format!(
+                "--- attempt {} of {}: {} ({}) ---",
+                idx + 1,
+                attempts.len(),
+                str_at(attempt, "/model_name"),
+                str_at(attempt, "/provider")
+            )
+            .blue()
+            .to_string(),
+        );
+        node.line("prompt:".dimmed().to_string());
+        node.block(&prompt_text(attempt.pointer("/input/prompt/template")), 1);
+        if let Some(raw) = attempt.pointer("/output/raw_text").and_then(Value::as_str) {
+            node.line("raw output:".dimmed().to_string());
+            node.block(raw, 1);
+        }
+        if let Some(error) = attempt.get("error").and_then(Value::as_str) {
+            node.line("error:".dimmed().to_string());
+            node.block(&error.red().to_string(), 1);
+        }
+    }
+
+    if let Some(output) = event.pointer("/io/output/value") {
+        node.line("parsed:".dimmed().to_string());
+        node.block(&value_text(output), 1);
+    }
+    if let Some(message) = event.pointer("/error/message").and_then(Value::as_str) {
+        node.line("error:".dimmed().to_string());
+        node.block(&message.red().to_string(), 1);
+    }
+
+    node.children = span.children.iter().map(span_node).collect();
+    node
+}
+
+/// A prompt is a string, or a list of chat messages.
+fn prompt_text(template: Option<&Value>) -> String {
+    match template {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(messages)) => messages
+            .iter()
+            .map(|message| {
+                let role = message.get("role").and_then(Value::as_str).unwrap_or("?");
+                let content = message
+                    .get("content")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .map(|part| match part.get("text").and_then(Value::as_str) {
+                        Some(text) => text.to_string(),
+                        None => "<media>".to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!("{}\n{}", format!("[{}]", role).yellow(), content)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Values are stored as JSON strings.
+fn value_text(value: &Value) -> String {
+    let pretty = |s: &str| {
+        serde_json::from_str::<Value>(s)
+            .ok()
+            .and_then(|v| serde_json::to_string_pretty(&v).ok())
+            .unwrap_or_else(|| s.to_string())
+    };
+    match value {
+        Value::String(s) => pretty(s.as_str()),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(pretty)
+                    .unwrap_or_else(|| item.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(id: &str, parent: Option<&str>, function: &str, start: &str) -> Value {
+        json!({
+            "event_id": id,
+            "root_event_id": "root",
+            "parent_event_id": parent,
+            "context": {
+                "process_id": "p1",
+                "start_time": start,
+                "event_chain": [{ "function_name": function }],
+                "tags": { "user": "ada" },
+            },
+        })
+    }
+
+    fn filter() -> TraceFilter {
+        TraceFilter {
+            session: None,
+            function: None,
+            tags: vec![],
+            status: None,
+            limit: 50,
+        }
+    }
+
+    #[test]
+    fn filters_traces() {
+        let ok = event("root", None, "ExtractResume", "1");
+        let mut failed = ok.clone();
+        failed["error"] = json!({ "message": "boom" });
+
+        assert!(matches(&ok, &filter()).unwrap());
+        let by_session = |session: &str| TraceFilter {
+            session: Some(session.to_string()),
+            ..filter()
+        };
+        assert!(matches(&ok, &by_session("p1")).unwrap());
+        assert!(!matches(&ok, &by_session("p2")).unwrap());
+
+        let by_function = |function: &str| TraceFilter {
+            function: Some(function.to_string()),
+            ..filter()
+        };
+        assert!(matches(&ok, &by_function("ExtractResume")).unwrap());
+        assert!(!matches(&ok, &by_function("Classify")).unwrap());
+
+        let errors = TraceFilter {
+            status: Some(Status::Error),
+            ..filter()
+        };
+        assert!(!matches(&ok, &errors).unwrap());
+        assert!(matches(&failed, &errors).unwrap());
+
+        let by_tags = |tags: &[&str]| TraceFilter {
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..filter()
+        };
+        assert!(matches(&ok, &by_tags(&["user=ada"])).unwrap());
+        assert!(!matches(&ok, &by_tags(&["user=ada", "env=prod"])).unwrap());
+        assert!(!matches(&ok, &by_tags(&["user=bob"])).unwrap());
+        assert!(matches(&ok, &by_tags(&["user"])).is_err());
+    }
+
+    #[test]
+    fn keeps_newest_traces() {
+        let mut roots = ["2", "4", "1", "3"]
+            .map(|start| event("root", None, "ExtractResume", start))
+            .to_vec();
+        keep_newest(&mut roots, 2);
+        let starts = roots
+            .iter()
+            .map(|e| str_at(e, "/context/start_time"))
+            .collect::<Vec<_>>();
+        assert_eq!(starts, ["4", "3"]);
+    }
+
+    #[test]
+    fn assembles_span_trees() {
+        let root = event("root", None, "ExtractResume", "1");
+        // Children are written before their parents finish.
+        let events = vec![
+            event("c", Some("b"), "Summarize", "3"),
+            event("b", Some("root"), "Classify", "2"),
+            event("d", Some("root"), "Score", "4"),
+            event("x", Some("missing"), "Orphan", "5"),
+        ];
+
+        fn shape(span: &Span) -> String {
+            let children = span.children.iter().map(shape).collect::<Vec<_>>();
+            format!("{}({})", function_name(&span.event), children.join(" "))
+        }
+        assert_eq!(
+            shape(&span_tree(root, events)),
+            "ExtractResume(Classify(Summarize()) Score())"
+        );
+    }
+
+    #[test]
+    fn streams_trace_files() {
+        let dir = std::env::temp_dir().join(format!("baml-traces-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let line = |e: Value| serde_json::to_string(&e).unwrap();
+        fs::write(
+            dir.join("a.jsonl"),
+            [
+                line(event("r1", None, "ExtractResume", "1")),
+                line(event("c1", Some("r1"), "Classify", "2")),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        // The file being written can end with a partial line.
+        fs::write(
+            dir.join("b.jsonl"),
+            format!(
+                "{}\n{{\"event_id\": ",
+                line(event("r2", None, "ExtractResume", "3"))
+            ),
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "not a trace").unwrap();
+
+        let mut sessions = BTreeMap::new();
+        let mut ids = vec![];
+        for_each_event(&dir, |event| {
+            ids.push(str_at(&event, "/event_id").to_string());
+            add_to_sessions(&mut sessions, &event);
+            Ok(())
+        })
+        .unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(ids, ["r1", "c1", "r2"]);
+        assert_eq!(
+            sessions.get("p1"),
+            Some(&Session {
+                traces: 2,
+                first: "1".to_string(),
+                last: "3".to_string(),
+            })
+        );
+        assert!(for_each_event(&dir, |_| Ok(())).is_err());
+    }
+}
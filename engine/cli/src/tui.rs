@@ -46,3 +46,93 @@ impl<F: Future<Output = Result<T>>, T> FutureWithProgress<T> for F {
         result
     }
 }
+
+/// A node of a tree printed with guides, like the span tree of a trace:
+///
+/// ```text
+/// ExtractResume ok 1200ms
+/// │   tags: {"user": "ada"}
+/// ├── Classify ok 300ms
+/// └── Summarize error 20ms
+/// ```
+#[derive(Debug, Default)]
+pub struct TreeNode {
+    /// The node's label, then the lines shown below it.
+    pub lines: Vec<String>,
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    pub fn new(label: impl Into<String>) -> Self {
+        TreeNode {
+            lines: vec![label.into()],
+            children: vec![],
+        }
+    }
+
+    pub fn line(&mut self, line: impl Into<String>) {
+        self.lines.push(line.into());
+    }
+
+    /// Adds every line of `text`, indented by `indent` levels.
+    pub fn block(&mut self, text: &str, indent: usize) {
+        let indent = "  ".repeat(indent);
+        self.lines
+            .extend(text.lines().map(|line| format!("{indent}{line}")));
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.render_into(&mut out, "", "");
+        out
+    }
+
+    fn render_into(&self, out: &mut String, first_prefix: &str, prefix: &str) {
+        let mut lines = self.lines.iter();
+        if let Some(label) = lines.next() {
+            out.push_str(&format!("{first_prefix}{label}\n"));
+        }
+        let guide = if self.children.is_empty() {
+            "    "
+        } else {
+            "│   "
+        };
+        for line in lines {
+            out.push_str(&format!("{prefix}{guide}{line}\n"));
+        }
+
+        for (idx, child) in self.children.iter().enumerate() {
+            let (branch, guide) = if idx + 1 == self.children.len() {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            child.render_into(
+                out,
+                &format!("{prefix}{branch}"),
+                &format!("{prefix}{guide}"),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_trees() {
+        let mut root = TreeNode::new("a");
+        root.line("x");
+        let mut b = TreeNode::new("b");
+        b.block("y\nz", 1);
+        let mut c = TreeNode::new("c");
+        c.children.push(TreeNode::new("d"));
+        root.children = vec![b, c];
+
+        assert_eq!(
+            root.render(),
+            "a\n│   x\n├── b\n│         y\n│         z\n└── c\n    └── d\n"
+        );
+    }
+}
@@ -0,0 +1,178 @@
+/*
+ * C ABI over the BAML runtime. See src/lib.rs for the Rust side.
+ *
+ * - Strings are NUL-terminated UTF-8. Arguments, results, tags and client
+ *   options cross the boundary as JSON.
+ * - Strings returned by this library are owned by the caller, and must be
+ *   released with baml_string_free().
+ * - Handles are released with their own _free function.
+ * - Functions that can fail take a `char **error` as their last argument. On
+ *   failure they return NULL (or -1), and if `error` is not NULL, set it to a
+ *   JSON error with the same shape as the errors returned by `baml-cli serve`,
+ *   e.g. {"error": "invalid_argument", "message": "..."}.
+ */
+
+#ifndef BAML_H
+#define BAML_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct BamlRuntime BamlRuntime;
+typedef struct BamlContextManager BamlContextManager;
+typedef struct BamlTypeBuilder BamlTypeBuilder;
+typedef struct BamlFieldType BamlFieldType;
+typedef struct BamlClientRegistry BamlClientRegistry;
+typedef struct BamlSpan BamlSpan;
+typedef struct BamlCancelToken BamlCancelToken;
+
+/* Called with the JSON of each partial result of a streamed function. The
+ * string is only valid for the duration of the call. */
+typedef void (*BamlStreamCallback)(void *user_data, const char *partial_json);
+
+/* The version of the runtime. Static, must not be freed. */
+const char *baml_version(void);
+
+void baml_string_free(char *s);
+
+/* Runtime */
+
+/* `env_vars_json` is a JSON object, or NULL to use the environment of the
+ * process. */
+BamlRuntime *baml_runtime_from_directory(const char *directory,
+                                         const char *env_vars_json,
+                                         char **error);
+
+/* `files_json` is a JSON object from path to file contents. */
+BamlRuntime *baml_runtime_from_files(const char *root_path,
+                                     const char *files_json,
+                                     const char *env_vars_json, char **error);
+
+void baml_runtime_free(BamlRuntime *runtime);
+
+/* Blocks until all traces have been sent. Returns 0 on success. */
+int32_t baml_runtime_flush(const BamlRuntime *runtime, char **error);
+
+/* Calls a function, blocking until it completes, and returns its parsed
+ * result as JSON. `args_json` is a JSON object from parameter name to value.
+ * `type_builder`, `client_registry` and `cancel` may be NULL. Once `cancel`
+ * is cancelled, the call fails with a "cancelled" error. */
+char *baml_call_function(const BamlRuntime *runtime, const char *function_name,
+                         const char *args_json, const BamlContextManager *ctx,
+                         const BamlTypeBuilder *type_builder,
+                         const BamlClientRegistry *client_registry,
+                         const BamlCancelToken *cancel, char **error);
+
+/* Streams a function, calling `on_partial` on the calling thread with each
+ * partial result, and returns the final result as JSON. `on_partial`,
+ * `type_builder`, `client_registry` and `cancel` may be NULL. */
+char *baml_stream_function(const BamlRuntime *runtime,
+                           const char *function_name, const char *args_json,
+                           const BamlContextManager *ctx,
+                           const BamlTypeBuilder *type_builder,
+                           const BamlClientRegistry *client_registry,
+                           BamlStreamCallback on_partial, void *user_data,
+                           const BamlCancelToken *cancel, char **error);
+
+/* Cancel tokens */
+
+BamlCancelToken *baml_cancel_token_new(void);
+
+/* Aborts the calls `token` was passed to. Can be called from any thread,
+ * and more than once. */
+void baml_cancel_token_cancel(const BamlCancelToken *token);
+
+/* Must not be called while a call using `token` is in progress. */
+void baml_cancel_token_free(BamlCancelToken *token);
+
+/* Context manager */
+
+/* `language` is recorded in the `baml.language` tag of every trace. */
+BamlContextManager *baml_ctx_manager_new(const BamlRuntime *runtime,
+                                         const char *language, char **error);
+
+void baml_ctx_manager_free(BamlContextManager *ctx);
+
+/* A copy with its own span stack, to use on another thread. */
+BamlContextManager *baml_ctx_manager_deep_clone(const BamlContextManager *ctx,
+                                                char **error);
+
+/* Sets tags, a JSON object, on the current span. Returns 0 on success. */
+int32_t baml_ctx_manager_upsert_tags(const BamlContextManager *ctx,
+                                     const char *tags_json, char **error);
+
+/* Returns NULL without setting `error` when tracing is disabled. */
+BamlSpan *baml_span_start(const BamlRuntime *runtime,
+                          const BamlContextManager *ctx, const char *name,
+                          const char *args_json, char **error);
+
+/* Consumes `span`, and returns its ID, or NULL if it wasn't recorded. */
+char *baml_span_finish(const BamlRuntime *runtime,
+                       const BamlContextManager *ctx, BamlSpan *span,
+                       const char *result_json, char **error);
+
+/* Type builder */
+
+BamlTypeBuilder *baml_type_builder_new(void);
+
+void baml_type_builder_free(BamlTypeBuilder *tb);
+
+/* `alias` and `description` may be NULL. Returns 0 on success. */
+int32_t baml_type_builder_add_class_property(
+    const BamlTypeBuilder *tb, const char *class_name, const char *property,
+    const BamlFieldType *type, const char *alias, const char *description,
+    char **error);
+
+/* `alias` and `description` may be NULL. Returns 0 on success. */
+int32_t baml_type_builder_add_enum_value(const BamlTypeBuilder *tb,
+                                         const char *enum_name,
+                                         const char *value, const char *alias,
+                                         const char *description, bool skip,
+                                         char **error);
+
+/* Field types are immutable: functions taking one copy it. */
+void baml_field_type_free(BamlFieldType *t);
+BamlFieldType *baml_field_type_string(void);
+BamlFieldType *baml_field_type_int(void);
+BamlFieldType *baml_field_type_float(void);
+BamlFieldType *baml_field_type_bool(void);
+BamlFieldType *baml_field_type_null(void);
+BamlFieldType *baml_field_type_literal_int(int64_t value);
+BamlFieldType *baml_field_type_literal_bool(bool value);
+BamlFieldType *baml_field_type_literal_string(const char *value, char **error);
+BamlFieldType *baml_field_type_class(const char *name, char **error);
+BamlFieldType *baml_field_type_enum(const char *name, char **error);
+BamlFieldType *baml_field_type_list(const BamlFieldType *inner, char **error);
+BamlFieldType *baml_field_type_optional(const BamlFieldType *inner,
+                                        char **error);
+BamlFieldType *baml_field_type_map(const BamlFieldType *key,
+                                   const BamlFieldType *value, char **error);
+BamlFieldType *baml_field_type_union(const BamlFieldType *const *types,
+                                     size_t count, char **error);
+
+/* Client registry */
+
+BamlClientRegistry *baml_client_registry_new(void);
+
+void baml_client_registry_free(BamlClientRegistry *registry);
+
+/* `options_json` is a JSON object, `retry_policy` may be NULL. Returns 0 on
+ * success. */
+int32_t baml_client_registry_add_client(BamlClientRegistry *registry,
+                                        const char *name, const char *provider,
+                                        const char *options_json,
+                                        const char *retry_policy, char **error);
+
+int32_t baml_client_registry_set_primary(BamlClientRegistry *registry,
+                                         const char *name, char **error);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* BAML_H */
@@ -0,0 +1,82 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::Notify;
+
+use crate::{free_handle, BamlError};
+
+/// Aborts the calls it is passed to, e.g. when the Go `context.Context` of
+/// the call is done. Safe to cancel from any thread, while the call blocks
+/// another.
+#[derive(Default)]
+pub struct BamlCancelToken {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl BamlCancelToken {
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    async fn cancelled(&self) {
+        loop {
+            // Registered before the flag is checked, so a cancel in between
+            // isn't missed.
+            let notified = self.notify.notified();
+            if self.cancelled.load(Ordering::SeqCst) {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Runs `fut` to completion on the runtime's threads, or until `cancel` is
+/// cancelled, in which case `fut` is dropped, aborting its LLM requests.
+pub(crate) fn block_on_cancellable<T>(
+    runtime: &baml_runtime::BamlRuntime,
+    cancel: Option<&BamlCancelToken>,
+    fut: impl Future<Output = T>,
+) -> Result<T, BamlError> {
+    runtime.async_runtime.block_on(async {
+        let Some(cancel) = cancel else {
+            return Ok(fut.await);
+        };
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(BamlError::Cancelled {
+                message: "The call was cancelled".to_string(),
+            }),
+            result = fut => Ok(result),
+        }
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn baml_cancel_token_new() -> *mut BamlCancelToken {
+    Box::into_raw(Box::default())
+}
+
+/// Aborts the calls `token` was passed to, and any call it is passed to
+/// afterwards. Can be called from any thread, and more than once.
+///
+/// # Safety
+///
+/// `token` must be `NULL` or a live cancel token.
+#[no_mangle]
+pub unsafe extern "C" fn baml_cancel_token_cancel(token: *const BamlCancelToken) {
+    if let Some(token) = token.as_ref() {
+        token.cancel();
+    }
+}
+
+/// # Safety
+///
+/// `token` must be `NULL` or a live cancel token, which isn't used
+/// afterwards, e.g. by a call still in progress.
+#[no_mangle]
+pub unsafe extern "C" fn baml_cancel_token_free(token: *mut BamlCancelToken) {
+    free_handle(token)
+}
@@ -0,0 +1,95 @@
+use std::ffi::c_char;
+use std::str::FromStr;
+
+use baml_runtime::client_registry::{self, ClientProperty, ClientProvider};
+use baml_types::{BamlMap, BamlValue};
+
+use crate::{ffi_call, free_handle, json_arg, opt_str_arg, str_arg, BamlError};
+
+/// Clients to add to, or override in, the BAML files for a call.
+pub struct BamlClientRegistry {
+    pub(crate) inner: client_registry::ClientRegistry,
+}
+
+#[no_mangle]
+pub extern "C" fn baml_client_registry_new() -> *mut BamlClientRegistry {
+    Box::into_raw(Box::new(BamlClientRegistry {
+        inner: client_registry::ClientRegistry::new(),
+    }))
+}
+
+/// # Safety
+///
+/// `registry` must be `NULL` or a live client registry, which isn't used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn baml_client_registry_free(registry: *mut BamlClientRegistry) {
+    free_handle(registry)
+}
+
+/// Adds a client, like a `client<llm>` block: `options_json` is a JSON object
+/// of its options, and `retry_policy` the name of a retry policy, or `NULL`.
+/// Returns `0` on success.
+///
+/// # Safety
+///
+/// `registry` must be a live client registry, not used by another thread,
+/// string arguments must be `NULL` or valid NUL-terminated strings, and
+/// `error` must be `NULL` or a valid `char **`.
+#[no_mangle]
+pub unsafe extern "C" fn baml_client_registry_add_client(
+    registry: *mut BamlClientRegistry,
+    name: *const c_char,
+    provider: *const c_char,
+    options_json: *const c_char,
+    retry_policy: *const c_char,
+    error: *mut *mut c_char,
+) -> i32 {
+    ffi_call(error, -1, || {
+        let registry = registry
+            .as_mut()
+            .ok_or_else(|| BamlError::InvalidArgument {
+                message: "registry must not be NULL".to_string(),
+            })?;
+        let name = str_arg(name, "name")?;
+        let provider = str_arg(provider, "provider")?;
+        let provider =
+            ClientProvider::from_str(provider).map_err(|e| BamlError::InvalidArgument {
+                message: format!("Invalid provider: {:?}", e),
+            })?;
+        let options: BamlMap<String, BamlValue> = json_arg(options_json, "options")?;
+        let retry_policy = opt_str_arg(retry_policy, "retry_policy")?;
+
+        registry.inner.add_client(ClientProperty::new(
+            name.to_string(),
+            provider,
+            retry_policy.map(str::to_string),
+            options,
+        ));
+        Ok(0)
+    })
+}
+
+/// Makes `name` the client used by every function in calls made with this
+/// registry. Returns `0` on success.
+///
+/// # Safety
+///
+/// Same as [`baml_client_registry_add_client`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_client_registry_set_primary(
+    registry: *mut BamlClientRegistry,
+    name: *const c_char,
+    error: *mut *mut c_char,
+) -> i32 {
+    ffi_call(error, -1, || {
+        let registry = registry
+            .as_mut()
+            .ok_or_else(|| BamlError::InvalidArgument {
+                message: "registry must not be NULL".to_string(),
+            })?;
+        let name = str_arg(name, "name")?;
+        registry.inner.set_primary(name.to_string());
+        Ok(0)
+    })
+}
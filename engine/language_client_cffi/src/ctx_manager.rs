@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::ffi::c_char;
+
+use baml_types::BamlValue;
+
+use crate::{ffi_call, free_handle, handle, json_arg, str_arg, BamlRuntime};
+
+/// The tracing context of one thread of execution: the stack of spans that
+/// calls are nested under, and their tags. Not safe to use from two threads
+/// at once; use [`baml_ctx_manager_deep_clone`] to hand a copy to another
+/// thread.
+pub struct BamlContextManager {
+    pub(crate) inner: baml_runtime::RuntimeContextManager,
+}
+
+/// Creates a context manager. `language` is recorded in the `baml.language`
+/// tag of every trace, e.g. `"go"`.
+///
+/// # Safety
+///
+/// `runtime` must be a live runtime, string arguments must be `NULL` or valid
+/// NUL-terminated strings, and `error` must be `NULL` or a valid `char **`.
+#[no_mangle]
+pub unsafe extern "C" fn baml_ctx_manager_new(
+    runtime: *const BamlRuntime,
+    language: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut BamlContextManager {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let runtime = handle(runtime, "runtime")?;
+        let language = str_arg(language, "language")?;
+        let inner = runtime
+            .inner
+            .create_ctx_manager(BamlValue::String(language.to_string()), None);
+        Ok(Box::into_raw(Box::new(BamlContextManager { inner })))
+    })
+}
+
+/// # Safety
+///
+/// `ctx` must be `NULL` or a live context manager, which isn't used
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn baml_ctx_manager_free(ctx: *mut BamlContextManager) {
+    free_handle(ctx)
+}
+
+/// Sets tags, a JSON object, on the current span. They are inherited by the
+/// spans started inside it. Returns `0` on success.
+///
+/// # Safety
+///
+/// Same as [`baml_ctx_manager_new`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_ctx_manager_upsert_tags(
+    ctx: *const BamlContextManager,
+    tags_json: *const c_char,
+    error: *mut *mut c_char,
+) -> i32 {
+    ffi_call(error, -1, || {
+        let ctx = handle(ctx, "ctx")?;
+        let tags: HashMap<String, BamlValue> = json_arg(tags_json, "tags")?;
+        ctx.inner.upsert_tags(tags);
+        Ok(0)
+    })
+}
+
+/// A copy of `ctx`, with its own span stack, to use on another thread.
+///
+/// # Safety
+///
+/// Same as [`baml_ctx_manager_new`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_ctx_manager_deep_clone(
+    ctx: *const BamlContextManager,
+    error: *mut *mut c_char,
+) -> *mut BamlContextManager {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let ctx = handle(ctx, "ctx")?;
+        Ok(Box::into_raw(Box::new(BamlContextManager {
+            inner: ctx.inner.deep_clone(),
+        })))
+    })
+}
@@ -0,0 +1,102 @@
+use baml_runtime::{errors::ExposedError, internal::llm_client::LLMResponse, FunctionResult};
+use serde::Serialize;
+
+/// The errors reported through the `char **error` out-parameters, serialized
+/// the same way as the errors returned by `baml-cli serve`, so that clients
+/// of either can share their error handling. See
+/// https://docs.boundaryml.com/get-started/debugging/exception-handling for
+/// an explanation of what each error variant is.
+#[derive(Debug, Serialize)]
+#[serde(tag = "error", rename_all = "snake_case")]
+pub enum BamlError {
+    #[serde(rename_all = "snake_case")]
+    InvalidArgument { message: String },
+    #[serde(rename_all = "snake_case")]
+    ClientError { message: String },
+    #[serde(rename_all = "snake_case")]
+    ValidationFailure {
+        prompt: String,
+        raw_output: String,
+        message: String,
+    },
+    #[serde(rename_all = "snake_case")]
+    FinishReasonError {
+        prompt: String,
+        raw_output: String,
+        message: String,
+        finish_reason: Option<String>,
+    },
+    /// The call was aborted through its cancel token.
+    #[serde(rename_all = "snake_case")]
+    Cancelled { message: String },
+    /// The catch-all for unclassified errors.
+    #[serde(rename_all = "snake_case")]
+    InternalError { message: String },
+}
+
+impl BamlError {
+    pub(crate) fn from_anyhow(err: &anyhow::Error) -> Self {
+        match err.downcast_ref::<ExposedError>() {
+            Some(ExposedError::ValidationError {
+                prompt,
+                raw_output,
+                message,
+            }) => Self::ValidationFailure {
+                prompt: prompt.to_string(),
+                raw_output: raw_output.to_string(),
+                message: message.to_string(),
+            },
+            Some(ExposedError::FinishReasonError {
+                prompt,
+                raw_output,
+                message,
+                finish_reason,
+            }) => Self::FinishReasonError {
+                prompt: prompt.to_string(),
+                raw_output: raw_output.to_string(),
+                message: message.to_string(),
+                finish_reason: finish_reason.clone(),
+            },
+            None => Self::InternalError {
+                message: format!("{:?}", err),
+            },
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|e| {
+            format!(
+                r#"{{"error":"internal_error","message":{:?}}}"#,
+                format!("Failed to serialize error: {}", e)
+            )
+        })
+    }
+}
+
+/// The parsed value of a function call, as JSON.
+pub(crate) fn function_result_to_json(
+    result: anyhow::Result<FunctionResult>,
+) -> Result<String, BamlError> {
+    let result = result.map_err(|e| BamlError::from_anyhow(&e))?;
+    match result.llm_response() {
+        // Just because the LLM returned 2xx doesn't mean that it returned
+        // parse-able content!
+        LLMResponse::Success(_) => {
+            let parsed = result
+                .result_with_constraints_content()
+                .map_err(|e| BamlError::from_anyhow(&e))?;
+            serde_json::to_string(parsed).map_err(|e| BamlError::InternalError {
+                message: format!("Failed to serialize result: {}", e),
+            })
+        }
+        LLMResponse::LLMFailure(failure) => Err(BamlError::ClientError {
+            message: format!("{:?}", failure.message),
+        }),
+        LLMResponse::UserFailure(message) => Err(BamlError::InvalidArgument {
+            message: message.clone(),
+        }),
+        LLMResponse::InternalFailure(message) => Err(BamlError::InternalError {
+            message: message.clone(),
+        }),
+    }
+}
@@ -0,0 +1,358 @@
+//! A C ABI over [`baml_runtime::BamlRuntime`], for languages that can call C
+//! but don't have a native binding (the Go client is built on it). The
+//! matching declarations are in `include/baml.h`.
+//!
+//! Conventions, which the header repeats:
+//!
+//! - Strings are NUL-terminated UTF-8. Arguments, results, tags and client
+//!   options cross the boundary as JSON.
+//! - Strings returned by this library are owned by the caller, and must be
+//!   released with [`baml_string_free`].
+//! - Handles (runtime, context manager, type builder, client registry, field
+//!   type, span, cancel token) are released with their own `_free` function.
+//! - Functions that can fail take a `char **error` as their last argument.
+//!   On failure they return `NULL` (or `-1`), and if `error` is not `NULL`,
+//!   set it to a JSON error with the same shape as the errors returned by
+//!   `baml-cli serve`, e.g. `{"error": "invalid_argument", "message": "..."}`.
+
+mod cancel;
+mod client_registry;
+mod ctx_manager;
+mod error;
+mod runtime;
+mod type_builder;
+
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use serde::de::DeserializeOwned;
+
+pub use cancel::*;
+pub use client_registry::*;
+pub use ctx_manager::*;
+pub use error::BamlError;
+pub use runtime::*;
+pub use type_builder::*;
+
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "\0");
+
+/// The version of the runtime, e.g. `"0.70.1"`. The returned string is
+/// static and must not be freed.
+#[no_mangle]
+pub extern "C" fn baml_version() -> *const c_char {
+    VERSION.as_ptr() as *const c_char
+}
+
+/// Releases a string returned by this library.
+///
+/// # Safety
+///
+/// `s` must be `NULL` or a string returned by this library, which hasn't
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn baml_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Runs `f`, reporting errors (and panics, which must not unwind into C)
+/// through `error`, and returning `default` in their place.
+fn ffi_call<T>(error: *mut *mut c_char, default: T, f: impl FnOnce() -> Result<T, BamlError>) -> T {
+    let result = catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        Err(BamlError::InternalError {
+            message: format!("BAML panicked: {}", message),
+        })
+    });
+
+    match result {
+        Ok(value) => value,
+        Err(e) => {
+            if !error.is_null() {
+                // SAFETY: the caller passes either NULL or a valid `char **`.
+                unsafe { *error = into_c_string(e.to_json()) };
+            }
+            default
+        }
+    }
+}
+
+fn init_logging() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        if let Err(e) = env_logger::try_init_from_env(
+            env_logger::Env::new()
+                .filter("BAML_LOG")
+                .write_style("BAML_LOG_STYLE"),
+        ) {
+            eprintln!("Failed to initialize BAML logger: {:#}", e);
+        }
+    });
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    // Only JSON and identifiers are returned, neither of which can contain a
+    // raw NUL byte.
+    CString::new(s)
+        .expect("returned strings must not contain NUL bytes")
+        .into_raw()
+}
+
+/// # Safety
+///
+/// `ptr` must be `NULL` or a valid NUL-terminated string, which outlives `'a`.
+unsafe fn opt_str_arg<'a>(ptr: *const c_char, name: &str) -> Result<Option<&'a str>, BamlError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map(Some)
+        .map_err(|e| BamlError::InvalidArgument {
+            message: format!("{} must be valid UTF-8: {}", name, e),
+        })
+}
+
+/// # Safety
+///
+/// Same as [`opt_str_arg`].
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, BamlError> {
+    opt_str_arg(ptr, name)?.ok_or_else(|| BamlError::InvalidArgument {
+        message: format!("{} must not be NULL", name),
+    })
+}
+
+/// # Safety
+///
+/// Same as [`opt_str_arg`].
+unsafe fn opt_json_arg<T: DeserializeOwned>(
+    ptr: *const c_char,
+    name: &str,
+) -> Result<Option<T>, BamlError> {
+    opt_str_arg(ptr, name)?
+        .map(|json| {
+            serde_json::from_str(json).map_err(|e| BamlError::InvalidArgument {
+                message: format!("Failed to parse {} as JSON: {}", name, e),
+            })
+        })
+        .transpose()
+}
+
+/// # Safety
+///
+/// Same as [`opt_str_arg`].
+unsafe fn json_arg<T: DeserializeOwned>(ptr: *const c_char, name: &str) -> Result<T, BamlError> {
+    opt_json_arg(ptr, name)?.ok_or_else(|| BamlError::InvalidArgument {
+        message: format!("{} must not be NULL", name),
+    })
+}
+
+/// # Safety
+///
+/// `ptr` must be `NULL` or a live handle created by this library.
+unsafe fn opt_handle<'a, T>(ptr: *const T) -> Option<&'a T> {
+    ptr.as_ref()
+}
+
+/// # Safety
+///
+/// Same as [`opt_handle`].
+unsafe fn handle<'a, T>(ptr: *const T, name: &str) -> Result<&'a T, BamlError> {
+    opt_handle(ptr).ok_or_else(|| BamlError::InvalidArgument {
+        message: format!("{} must not be NULL", name),
+    })
+}
+
+/// Releases a handle created with `Box::into_raw`.
+///
+/// # Safety
+///
+/// `ptr` must be `NULL` or a live handle of type `T` created by this library.
+unsafe fn free_handle<T>(ptr: *mut T) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIN_BAML: &str = r##"
+        class Resume {
+          name string
+          skills string[]
+        }
+
+        client<llm> GPT4 {
+          provider openai
+          options {
+            model gpt-4o
+            api_key env.OPENAI_API_KEY
+          }
+        }
+
+        function ExtractResume(resume: string) -> Resume {
+          client GPT4
+          prompt #"{{ resume }} {{ ctx.output_format }}"#
+        }
+    "##;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    unsafe fn take_error(error: *mut c_char) -> serde_json::Value {
+        assert!(!error.is_null(), "expected an error");
+        let json = serde_json::from_str(CStr::from_ptr(error).to_str().unwrap()).unwrap();
+        baml_string_free(error);
+        json
+    }
+
+    unsafe fn runtime() -> *mut BamlRuntime {
+        let files = serde_json::json!({ "baml_src/main.baml": MAIN_BAML }).to_string();
+        let mut error = std::ptr::null_mut();
+        let rt = baml_runtime_from_files(
+            c("baml_src").as_ptr(),
+            c(&files).as_ptr(),
+            c("{}").as_ptr(),
+            &mut error,
+        );
+        assert!(error.is_null(), "{:?}", take_error(error));
+        assert!(!rt.is_null());
+        rt
+    }
+
+    #[test]
+    fn test_version() {
+        let version = unsafe { CStr::from_ptr(baml_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_invalid_baml_is_reported() {
+        unsafe {
+            let files = serde_json::json!({ "baml_src/main.baml": "class {" }).to_string();
+            let mut error = std::ptr::null_mut();
+            let rt = baml_runtime_from_files(
+                c("baml_src").as_ptr(),
+                c(&files).as_ptr(),
+                c("{}").as_ptr(),
+                &mut error,
+            );
+            assert!(rt.is_null());
+            assert_eq!(take_error(error)["error"], "internal_error");
+        }
+    }
+
+    #[test]
+    fn test_call_function_rejects_invalid_args() {
+        unsafe {
+            let rt = runtime();
+            let ctx = baml_ctx_manager_new(rt, c("go").as_ptr(), std::ptr::null_mut());
+
+            let mut error = std::ptr::null_mut();
+            let result = baml_call_function(
+                rt,
+                c("ExtractResume").as_ptr(),
+                c("[1, 2]").as_ptr(),
+                ctx,
+                std::ptr::null(),
+                std::ptr::null(),
+                std::ptr::null(),
+                &mut error,
+            );
+            assert!(result.is_null());
+            let error = take_error(error);
+            assert_eq!(error["error"], "invalid_argument");
+            assert!(error["message"].as_str().unwrap().contains("args"));
+
+            baml_ctx_manager_free(ctx);
+            baml_runtime_free(rt);
+        }
+    }
+
+    #[test]
+    fn test_cancelled_calls_fail() {
+        unsafe {
+            let rt = runtime();
+            let ctx = baml_ctx_manager_new(rt, c("go").as_ptr(), std::ptr::null_mut());
+            let cancel = baml_cancel_token_new();
+            baml_cancel_token_cancel(cancel);
+
+            let mut error = std::ptr::null_mut();
+            let result = baml_call_function(
+                rt,
+                c("ExtractResume").as_ptr(),
+                c(r#"{"resume": "Grace Hopper"}"#).as_ptr(),
+                ctx,
+                std::ptr::null(),
+                std::ptr::null(),
+                cancel,
+                &mut error,
+            );
+            assert!(result.is_null());
+            assert_eq!(take_error(error)["error"], "cancelled");
+
+            baml_cancel_token_free(cancel);
+            baml_ctx_manager_free(ctx);
+            baml_runtime_free(rt);
+        }
+    }
+
+    #[test]
+    fn test_handles_are_checked() {
+        unsafe {
+            let mut error = std::ptr::null_mut();
+            assert_eq!(baml_runtime_flush(std::ptr::null(), &mut error), -1);
+            assert_eq!(
+                take_error(error)["message"],
+                "runtime must not be NULL".to_string()
+            );
+
+            let registry = baml_client_registry_new();
+            let mut error = std::ptr::null_mut();
+            let status = baml_client_registry_add_client(
+                registry,
+                c("Fast").as_ptr(),
+                c("not-a-provider").as_ptr(),
+                c("{}").as_ptr(),
+                std::ptr::null(),
+                &mut error,
+            );
+            assert_eq!(status, -1);
+            assert_eq!(take_error(error)["error"], "invalid_argument");
+            baml_client_registry_free(registry);
+        }
+    }
+
+    #[test]
+    fn test_type_builder() {
+        unsafe {
+            let tb = baml_type_builder_new();
+            let string = baml_field_type_string();
+            let list = baml_field_type_list(string, std::ptr::null_mut());
+            let status = baml_type_builder_add_class_property(
+                tb,
+                c("Resume").as_ptr(),
+                c("languages").as_ptr(),
+                list,
+                std::ptr::null(),
+                c("Spoken languages").as_ptr(),
+                std::ptr::null_mut(),
+            );
+            assert_eq!(status, 0);
+            baml_field_type_free(list);
+            baml_field_type_free(string);
+
+            let (classes, _) = (*tb).inner.to_overrides();
+            assert!(classes.contains_key("Resume"));
+            baml_type_builder_free(tb);
+        }
+    }
+}
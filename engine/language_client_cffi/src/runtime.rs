@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+use std::ffi::{c_char, c_void, CString};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use baml_runtime::runtime_interface::ExperimentalTracingInterface;
+use baml_types::{BamlMap, BamlValue};
+
+use crate::cancel::block_on_cancellable;
+use crate::error::function_result_to_json;
+use crate::{
+    ffi_call, free_handle, handle, init_logging, into_c_string, json_arg, opt_handle, opt_json_arg,
+    str_arg, BamlCancelToken, BamlClientRegistry, BamlContextManager, BamlError, BamlTypeBuilder,
+};
+
+/// A loaded set of BAML files. Safe to share between threads.
+pub struct BamlRuntime {
+    pub(crate) inner: Arc<baml_runtime::BamlRuntime>,
+}
+
+/// Called with the JSON of each partial result of a streamed function. The
+/// string is only valid for the duration of the call.
+pub type BamlStreamCallback =
+    Option<unsafe extern "C" fn(user_data: *mut c_void, partial_json: *const c_char)>;
+
+/// The environment variables to load the runtime with: the given JSON
+/// object, or the environment of the process if `NULL`.
+unsafe fn env_vars_arg(env_vars_json: *const c_char) -> Result<HashMap<String, String>, BamlError> {
+    Ok(opt_json_arg(env_vars_json, "env_vars")?.unwrap_or_else(|| std::env::vars().collect()))
+}
+
+/// The arguments of a function call, a JSON object from parameter name to
+/// value. Media parameters are passed as objects with `url`, `file` or
+/// `base64` (and `media_type`) keys.
+unsafe fn args_arg(args_json: *const c_char) -> Result<BamlMap<String, BamlValue>, BamlError> {
+    let args: serde_json::Map<String, serde_json::Value> = json_arg(args_json, "args")?;
+    args.into_iter()
+        .map(|(k, v)| {
+            serde_json::from_value(v)
+                .map(|v| (k.clone(), v))
+                .map_err(|e| BamlError::InvalidArgument {
+                    message: format!("Argument {} must be convertible to BamlValue: {}", k, e),
+                })
+        })
+        .collect()
+}
+
+/// Loads the BAML files in `directory` (a `baml_src` directory).
+///
+/// # Safety
+///
+/// String arguments must be `NULL` or valid NUL-terminated strings, and
+/// `error` must be `NULL` or a valid `char **`.
+#[no_mangle]
+pub unsafe extern "C" fn baml_runtime_from_directory(
+    directory: *const c_char,
+    env_vars_json: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut BamlRuntime {
+    init_logging();
+    ffi_call(error, std::ptr::null_mut(), || {
+        let directory = PathBuf::from(str_arg(directory, "directory")?);
+        let env_vars = env_vars_arg(env_vars_json)?;
+        let runtime = baml_runtime::BamlRuntime::from_directory(&directory, env_vars)
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+        Ok(Box::into_raw(Box::new(BamlRuntime {
+            inner: Arc::new(runtime),
+        })))
+    })
+}
+
+/// Loads BAML files from memory: `files_json` is a JSON object from path to
+/// file contents, which is how generated clients embed their `baml_src`.
+///
+/// # Safety
+///
+/// Same as [`baml_runtime_from_directory`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_runtime_from_files(
+    root_path: *const c_char,
+    files_json: *const c_char,
+    env_vars_json: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut BamlRuntime {
+    init_logging();
+    ffi_call(error, std::ptr::null_mut(), || {
+        let root_path = str_arg(root_path, "root_path")?;
+        let files: HashMap<String, String> = json_arg(files_json, "files")?;
+        let env_vars = env_vars_arg(env_vars_json)?;
+        let runtime = baml_runtime::BamlRuntime::from_file_content(root_path, &files, env_vars)
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+        Ok(Box::into_raw(Box::new(BamlRuntime {
+            inner: Arc::new(runtime),
+        })))
+    })
+}
+
+/// # Safety
+///
+/// `runtime` must be `NULL` or a live runtime, which isn't used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn baml_runtime_free(runtime: *mut BamlRuntime) {
+    free_handle(runtime)
+}
+
+/// Calls `function_name`, blocking until it completes, and returns its
+/// parsed result as JSON. `type_builder`, `client_registry` and `cancel` may
+/// be `NULL`. Once `cancel` is cancelled, the call fails with a `cancelled`
+/// error.
+///
+/// # Safety
+///
+/// Handles must be `NULL` or live, string arguments must be `NULL` or valid
+/// NUL-terminated strings, and `error` must be `NULL` or a valid `char **`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn baml_call_function(
+    runtime: *const BamlRuntime,
+    function_name: *const c_char,
+    args_json: *const c_char,
+    ctx: *const BamlContextManager,
+    type_builder: *const BamlTypeBuilder,
+    client_registry: *const BamlClientRegistry,
+    cancel: *const BamlCancelToken,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let runtime = handle(runtime, "runtime")?;
+        let function_name = str_arg(function_name, "function_name")?;
+        let args = args_arg(args_json)?;
+        let ctx = handle(ctx, "ctx")?;
+        let tb = opt_handle(type_builder).map(|tb| &tb.inner);
+        let cb = opt_handle(client_registry).map(|cb| &cb.inner);
+
+        let call =
+            runtime
+                .inner
+                .call_function(function_name.to_string(), &args, &ctx.inner, tb, cb);
+        let (result, _) = block_on_cancellable(&runtime.inner, opt_handle(cancel), call)?;
+        function_result_to_json(result).map(into_c_string)
+    })
+}
+
+/// Streams `function_name`, calling `on_partial` on the calling thread with
+/// each partial result, and returns the final result as JSON once the stream
+/// completes. `on_partial`, `type_builder`, `client_registry` and `cancel`
+/// may be `NULL`.
+///
+/// # Safety
+///
+/// Same as [`baml_call_function`]. `on_partial` must be safe to call with
+/// `user_data`.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn baml_stream_function(
+    runtime: *const BamlRuntime,
+    function_name: *const c_char,
+    args_json: *const c_char,
+    ctx: *const BamlContextManager,
+    type_builder: *const BamlTypeBuilder,
+    client_registry: *const BamlClientRegistry,
+    on_partial: BamlStreamCallback,
+    user_data: *mut c_void,
+    cancel: *const BamlCancelToken,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let runtime = handle(runtime, "runtime")?;
+        let function_name = str_arg(function_name, "function_name")?;
+        let args = args_arg(args_json)?;
+        let ctx = handle(ctx, "ctx")?;
+        let tb = opt_handle(type_builder).map(|tb| &tb.inner);
+        let cb = opt_handle(client_registry).map(|cb| &cb.inner);
+
+        let mut stream = runtime
+            .inner
+            .stream_function(function_name.to_string(), &args, &ctx.inner, tb, cb)
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+
+        let on_event = on_partial.map(|on_partial| {
+            move |partial: baml_runtime::FunctionResult| {
+                // Partial results that can't be parsed yet are skipped.
+                let Ok(parsed) = partial.result_with_constraints_content() else {
+                    return;
+                };
+                match serde_json::to_string(parsed).map(CString::new) {
+                    Ok(Ok(json)) => on_partial(user_data, json.as_ptr()),
+                    Ok(Err(e)) => log::warn!("Failed to pass partial result to C: {}", e),
+                    Err(e) => log::warn!("Failed to serialize partial result: {}", e),
+                }
+            }
+        });
+
+        let run = stream.run(on_event, &ctx.inner, tb, cb);
+        let (result, _) = block_on_cancellable(&runtime.inner, opt_handle(cancel), run)?;
+        function_result_to_json(result).map(into_c_string)
+    })
+}
+
+/// Starts a span for a user function, so that the BAML calls made inside it
+/// are traced as its children. `args_json` is a JSON object, or `NULL`.
+/// Returns `NULL` without setting `error` when tracing is disabled.
+///
+/// # Safety
+///
+/// Same as [`baml_call_function`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_span_start(
+    runtime: *const BamlRuntime,
+    ctx: *const BamlContextManager,
+    name: *const c_char,
+    args_json: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut BamlSpan {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let runtime = handle(runtime, "runtime")?;
+        let ctx = handle(ctx, "ctx")?;
+        let name = str_arg(name, "name")?;
+        let args = if args_json.is_null() {
+            BamlMap::new()
+        } else {
+            args_arg(args_json)?
+        };
+
+        Ok(match runtime.inner.start_span(name, &args, &ctx.inner) {
+            Some(span) => Box::into_raw(Box::new(BamlSpan { inner: span })),
+            None => std::ptr::null_mut(),
+        })
+    })
+}
+
+/// A span started with [`baml_span_start`].
+pub struct BamlSpan {
+    inner: baml_runtime::tracing::TracingSpan,
+}
+
+/// Finishes `span` with the JSON result of the user function (or `NULL`),
+/// and returns the ID of the span, or `NULL` if it wasn't recorded. `span`
+/// is consumed, even on failure.
+///
+/// # Safety
+///
+/// `span` must be `NULL` or a live span, which isn't used afterwards. Other
+/// arguments are the same as [`baml_call_function`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_span_finish(
+    runtime: *const BamlRuntime,
+    ctx: *const BamlContextManager,
+    span: *mut BamlSpan,
+    result_json: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    let span = (!span.is_null()).then(|| Box::from_raw(span).inner);
+    ffi_call(error, std::ptr::null_mut(), || {
+        let runtime = handle(runtime, "runtime")?;
+        let ctx = handle(ctx, "ctx")?;
+        let result: Option<BamlValue> = opt_json_arg(result_json, "result")?;
+
+        let id = runtime
+            .inner
+            .finish_span(span, result, &ctx.inner)
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+        Ok(id.map_or(std::ptr::null_mut(), |id| into_c_string(id.to_string())))
+    })
+}
+
+/// Blocks until all traces have been sent. Returns `0` on success.
+///
+/// # Safety
+///
+/// Same as [`baml_call_function`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_runtime_flush(
+    runtime: *const BamlRuntime,
+    error: *mut *mut c_char,
+) -> i32 {
+    ffi_call(error, -1, || {
+        let runtime = handle(runtime, "runtime")?;
+        runtime
+            .inner
+            .flush()
+            .map_err(|e| BamlError::from_anyhow(&e))?;
+        Ok(0)
+    })
+}
@@ -0,0 +1,281 @@
+use std::ffi::c_char;
+
+use baml_runtime::type_builder::{self, WithMeta};
+use baml_types::{BamlValue, FieldType};
+
+use crate::{ffi_call, free_handle, handle, opt_str_arg, str_arg, BamlError};
+
+/// Additions to the `@@dynamic` classes and enums of the BAML files, for a
+/// call.
+pub struct BamlTypeBuilder {
+    pub(crate) inner: type_builder::TypeBuilder,
+}
+
+/// A type, used as the type of a class property added with
+/// [`baml_type_builder_add_class_property`]. Field types are immutable:
+/// functions taking one copy it, so it can be freed right after.
+pub struct BamlFieldType {
+    inner: FieldType,
+}
+
+fn field_type(inner: FieldType) -> *mut BamlFieldType {
+    Box::into_raw(Box::new(BamlFieldType { inner }))
+}
+
+#[no_mangle]
+pub extern "C" fn baml_type_builder_new() -> *mut BamlTypeBuilder {
+    Box::into_raw(Box::new(BamlTypeBuilder {
+        inner: type_builder::TypeBuilder::new(),
+    }))
+}
+
+/// # Safety
+///
+/// `tb` must be `NULL` or a live type builder, which isn't used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn baml_type_builder_free(tb: *mut BamlTypeBuilder) {
+    free_handle(tb)
+}
+
+/// Adds `property` to `class_name` (or changes its type). `alias` and
+/// `description` may be `NULL`. Returns `0` on success.
+///
+/// # Safety
+///
+/// Handles must be live, string arguments must be `NULL` or valid
+/// NUL-terminated strings, and `error` must be `NULL` or a valid `char **`.
+#[no_mangle]
+pub unsafe extern "C" fn baml_type_builder_add_class_property(
+    tb: *const BamlTypeBuilder,
+    class_name: *const c_char,
+    property: *const c_char,
+    r#type: *const BamlFieldType,
+    alias: *const c_char,
+    description: *const c_char,
+    error: *mut *mut c_char,
+) -> i32 {
+    ffi_call(error, -1, || {
+        let tb = handle(tb, "tb")?;
+        let class_name = str_arg(class_name, "class_name")?;
+        let property = str_arg(property, "property")?;
+        let r#type = handle(r#type, "type")?;
+        let alias = opt_str_arg(alias, "alias")?;
+        let description = opt_str_arg(description, "description")?;
+
+        let class = tb.inner.class(class_name);
+        let class = class.lock().unwrap();
+        let property = class.property(property);
+        let property = property.lock().unwrap();
+        property.r#type(r#type.inner.clone());
+        set_meta(&*property, alias, description);
+        Ok(0)
+    })
+}
+
+/// Adds `value` to `enum_name`. `alias` and `description` may be `NULL`;
+/// `skip` removes the value from the enum. Returns `0` on success.
+///
+/// # Safety
+///
+/// Same as [`baml_type_builder_add_class_property`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_type_builder_add_enum_value(
+    tb: *const BamlTypeBuilder,
+    enum_name: *const c_char,
+    value: *const c_char,
+    alias: *const c_char,
+    description: *const c_char,
+    skip: bool,
+    error: *mut *mut c_char,
+) -> i32 {
+    ffi_call(error, -1, || {
+        let tb = handle(tb, "tb")?;
+        let enum_name = str_arg(enum_name, "enum_name")?;
+        let value = str_arg(value, "value")?;
+        let alias = opt_str_arg(alias, "alias")?;
+        let description = opt_str_arg(description, "description")?;
+
+        let r#enum = tb.inner.r#enum(enum_name);
+        let r#enum = r#enum.lock().unwrap();
+        let value = r#enum.value(value);
+        let value = value.lock().unwrap();
+        set_meta(&*value, alias, description);
+        if skip {
+            value.with_meta("skip", BamlValue::Bool(true));
+        }
+        Ok(0)
+    })
+}
+
+fn set_meta(builder: &impl WithMeta, alias: Option<&str>, description: Option<&str>) {
+    if let Some(alias) = alias {
+        builder.with_meta("alias", BamlValue::String(alias.to_string()));
+    }
+    if let Some(description) = description {
+        builder.with_meta("description", BamlValue::String(description.to_string()));
+    }
+}
+
+/// # Safety
+///
+/// `t` must be `NULL` or a live field type, which isn't used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn baml_field_type_free(t: *mut BamlFieldType) {
+    free_handle(t)
+}
+
+#[no_mangle]
+pub extern "C" fn baml_field_type_string() -> *mut BamlFieldType {
+    field_type(FieldType::string())
+}
+
+#[no_mangle]
+pub extern "C" fn baml_field_type_int() -> *mut BamlFieldType {
+    field_type(FieldType::int())
+}
+
+#[no_mangle]
+pub extern "C" fn baml_field_type_float() -> *mut BamlFieldType {
+    field_type(FieldType::float())
+}
+
+#[no_mangle]
+pub extern "C" fn baml_field_type_bool() -> *mut BamlFieldType {
+    field_type(FieldType::bool())
+}
+
+#[no_mangle]
+pub extern "C" fn baml_field_type_null() -> *mut BamlFieldType {
+    field_type(FieldType::null())
+}
+
+#[no_mangle]
+pub extern "C" fn baml_field_type_literal_int(value: i64) -> *mut BamlFieldType {
+    field_type(FieldType::literal_int(value))
+}
+
+#[no_mangle]
+pub extern "C" fn baml_field_type_literal_bool(value: bool) -> *mut BamlFieldType {
+    field_type(FieldType::literal_bool(value))
+}
+
+/// # Safety
+///
+/// String arguments must be `NULL` or valid NUL-terminated strings, and
+/// `error` must be `NULL` or a valid `char **`.
+#[no_mangle]
+pub unsafe extern "C" fn baml_field_type_literal_string(
+    value: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut BamlFieldType {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let value = str_arg(value, "value")?;
+        Ok(field_type(FieldType::literal_string(value.to_string())))
+    })
+}
+
+/// A class, either from the BAML files or added with the type builder.
+///
+/// # Safety
+///
+/// Same as [`baml_field_type_literal_string`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_field_type_class(
+    name: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut BamlFieldType {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let name = str_arg(name, "name")?;
+        Ok(field_type(FieldType::class(name)))
+    })
+}
+
+/// An enum, either from the BAML files or added with the type builder.
+///
+/// # Safety
+///
+/// Same as [`baml_field_type_literal_string`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_field_type_enum(
+    name: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut BamlFieldType {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let name = str_arg(name, "name")?;
+        Ok(field_type(FieldType::r#enum(name)))
+    })
+}
+
+/// # Safety
+///
+/// `inner` must be a live field type, and `error` must be `NULL` or a valid
+/// `char **`.
+#[no_mangle]
+pub unsafe extern "C" fn baml_field_type_list(
+    inner: *const BamlFieldType,
+    error: *mut *mut c_char,
+) -> *mut BamlFieldType {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let inner = handle(inner, "inner")?;
+        Ok(field_type(inner.inner.clone().as_list()))
+    })
+}
+
+/// # Safety
+///
+/// Same as [`baml_field_type_list`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_field_type_optional(
+    inner: *const BamlFieldType,
+    error: *mut *mut c_char,
+) -> *mut BamlFieldType {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let inner = handle(inner, "inner")?;
+        Ok(field_type(inner.inner.clone().as_optional()))
+    })
+}
+
+/// # Safety
+///
+/// Same as [`baml_field_type_list`].
+#[no_mangle]
+pub unsafe extern "C" fn baml_field_type_map(
+    key: *const BamlFieldType,
+    value: *const BamlFieldType,
+    error: *mut *mut c_char,
+) -> *mut BamlFieldType {
+    ffi_call(error, std::ptr::null_mut(), || {
+        let key = handle(key, "key")?;
+        let value = handle(value, "value")?;
+        Ok(field_type(FieldType::map(
+            key.inner.clone(),
+            value.inner.clone(),
+        )))
+    })
+}
+
+/// A union of the `count` types in `types`.
+///
+/// # Safety
+///
+/// `types` must point to `count` live field types, and `error` must be
+/// `NULL` or a valid `char **`.
+#[no_mangle]
+pub unsafe extern "C" fn baml_field_type_union(
+    types: *const *const BamlFieldType,
+    count: usize,
+    error: *mut *mut c_char,
+) -> *mut BamlFieldType {
+    ffi_call(error, std::ptr::null_mut(), || {
+        if types.is_null() || count == 0 {
+            return Err(BamlError::InvalidArgument {
+                message: "A union needs at least one type".to_string(),
+            });
+        }
+        let types = std::slice::from_raw_parts(types, count)
+            .iter()
+            .map(|t| handle(*t, "types").map(|t| t.inner.clone()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(field_type(FieldType::union(types)))
+    })
+}
@@ -0,0 +1,131 @@
+use baml_types::{BamlMediaType, FieldType, TypeValue};
+
+use crate::field_type_attributes;
+
+use super::go_language_features::{go_name, ToGo};
+
+/// Go types that already have a zero value meaning "absent".
+fn is_nilable(go_type: &str) -> bool {
+    go_type == "any"
+        || go_type.starts_with('*')
+        || go_type.starts_with("[]")
+        || go_type.starts_with("map[")
+}
+
+fn nilable(go_type: String) -> String {
+    if is_nilable(&go_type) {
+        go_type
+    } else {
+        format!("*{go_type}")
+    }
+}
+
+/// The variants of a union other than `null`, and whether there was one.
+fn non_null_variants(variants: &[FieldType]) -> (Vec<&FieldType>, bool) {
+    let non_null: Vec<_> = variants.iter().filter(|t| !t.is_null()).collect();
+    let has_null = non_null.len() < variants.len();
+    (non_null, has_null)
+}
+
+impl ToGo for FieldType {
+    fn to_go(&self) -> String {
+        match self {
+            FieldType::Class(name) | FieldType::Enum(name) => go_name(name),
+            FieldType::Literal(value) => value.literal_base_type().to_go(),
+            FieldType::List(inner) => format!("[]{}", inner.to_go()),
+            // JSON object keys are always strings.
+            FieldType::Map(_, value) => format!("map[string]{}", value.to_go()),
+            FieldType::Primitive(r#type) => String::from(match r#type {
+                TypeValue::Bool => "bool",
+                TypeValue::Float => "float64",
+                TypeValue::Int => "int64",
+                TypeValue::String => "string",
+                TypeValue::Null => "any",
+                TypeValue::Media(BamlMediaType::Image) => "Image",
+                TypeValue::Media(BamlMediaType::Audio) => "Audio",
+            }),
+            // Go has no sum types: unions of several types are decoded as
+            // generic JSON values.
+            FieldType::Union(variants) => match non_null_variants(variants) {
+                (non_null, has_null) if non_null.len() == 1 => {
+                    let go_type = non_null[0].to_go();
+                    if has_null {
+                        nilable(go_type)
+                    } else {
+                        go_type
+                    }
+                }
+                _ => "any".to_string(),
+            },
+            FieldType::Tuple(_) => "[]any".to_string(),
+            FieldType::Optional(inner) => nilable(inner.to_go()),
+            FieldType::Constrained { base, .. } => match field_type_attributes(self) {
+                Some(_) => format!("Checked[{}]", base.to_go()),
+                None => base.to_go(),
+            },
+        }
+    }
+
+    fn to_partial_go(&self) -> String {
+        match self {
+            FieldType::Class(name) => format!("*Partial{}", go_name(name)),
+            FieldType::Enum(_) | FieldType::Literal(_) | FieldType::Primitive(_) => {
+                nilable(self.to_go())
+            }
+            FieldType::List(inner) => format!("[]{}", inner.to_partial_go()),
+            FieldType::Map(_, value) => format!("map[string]{}", value.to_partial_go()),
+            FieldType::Union(variants) => match non_null_variants(variants) {
+                (non_null, _) if non_null.len() == 1 => non_null[0].to_partial_go(),
+                _ => "any".to_string(),
+            },
+            FieldType::Tuple(_) => "[]any".to_string(),
+            FieldType::Optional(inner) => inner.to_partial_go(),
+            FieldType::Constrained { base, .. } => match field_type_attributes(self) {
+                Some(_) => format!("*Checked[{}]", base.to_partial_go()),
+                None => base.to_partial_go(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_go() {
+        assert_eq!(FieldType::string().to_go(), "string");
+        assert_eq!(FieldType::int().as_list().to_go(), "[]int64");
+        assert_eq!(FieldType::class("resume").as_optional().to_go(), "*Resume");
+        assert_eq!(
+            FieldType::string().as_list().as_optional().to_go(),
+            "[]string"
+        );
+        assert_eq!(
+            FieldType::map(FieldType::string(), FieldType::float()).to_go(),
+            "map[string]float64"
+        );
+        assert_eq!(
+            FieldType::union(vec![FieldType::int(), FieldType::null()]).to_go(),
+            "*int64"
+        );
+        assert_eq!(
+            FieldType::union(vec![FieldType::int(), FieldType::string()]).to_go(),
+            "any"
+        );
+    }
+
+    #[test]
+    fn test_to_partial_go() {
+        assert_eq!(FieldType::string().to_partial_go(), "*string");
+        assert_eq!(FieldType::class("Resume").to_partial_go(), "*PartialResume");
+        assert_eq!(
+            FieldType::class("Resume").as_list().to_partial_go(),
+            "[]*PartialResume"
+        );
+        assert_eq!(
+            FieldType::r#enum("Sentiment").as_optional().to_partial_go(),
+            "*Sentiment"
+        );
+    }
+}
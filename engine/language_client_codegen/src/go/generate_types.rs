@@ -0,0 +1,148 @@
+use anyhow::Result;
+
+use internal_baml_core::ir::{
+    repr::{Docstring, Field, IntermediateRepr, Node},
+    ClassWalker, EnumWalker,
+};
+
+use super::go_language_features::{go_name, ToGo};
+
+#[derive(askama::Template)]
+#[template(path = "types.go.j2", escape = "none")]
+pub(crate) struct GoTypes {
+    enums: Vec<GoEnum>,
+    classes: Vec<GoStruct>,
+}
+
+#[derive(askama::Template)]
+#[template(path = "partial_types.go.j2", escape = "none")]
+pub(crate) struct GoPartialTypes {
+    classes: Vec<GoStruct>,
+}
+
+struct GoEnum {
+    name: String,
+    /// The Go constant and the BAML value
+    values: Vec<(String, String)>,
+    docstring: Option<String>,
+}
+
+struct GoStruct {
+    name: String,
+    fields: Vec<GoField>,
+    dynamic: bool,
+    docstring: Option<String>,
+}
+
+struct GoField {
+    name: String,
+    /// The name of the property in BAML, and in JSON.
+    json_name: String,
+    type_ref: String,
+    docstring: Option<String>,
+}
+
+impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir crate::GeneratorArgs)> for GoTypes {
+    type Error = anyhow::Error;
+
+    fn try_from((ir, _): (&'ir IntermediateRepr, &'ir crate::GeneratorArgs)) -> Result<Self> {
+        Ok(GoTypes {
+            enums: ir.walk_enums().map(GoEnum::from).collect(),
+            classes: ir.walk_classes().map(|c| GoStruct::new(c, false)).collect(),
+        })
+    }
+}
+
+impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir crate::GeneratorArgs)> for GoPartialTypes {
+    type Error = anyhow::Error;
+
+    fn try_from((ir, _): (&'ir IntermediateRepr, &'ir crate::GeneratorArgs)) -> Result<Self> {
+        Ok(GoPartialTypes {
+            classes: ir.walk_classes().map(|c| GoStruct::new(c, true)).collect(),
+        })
+    }
+}
+
+impl From<EnumWalker<'_>> for GoEnum {
+    fn from(e: EnumWalker<'_>) -> GoEnum {
+        let name = go_name(e.name());
+        GoEnum {
+            values: e
+                .item
+                .elem
+                .values
+                .iter()
+                .map(|v| {
+                    let value = v.0.elem.0.as_str();
+                    (format!("{}{}", name, go_name(value)), value.to_string())
+                })
+                .collect(),
+            docstring: e
+                .item
+                .elem
+                .docstring
+                .as_ref()
+                .map(|d| render_docstring(d, false)),
+            name,
+        }
+    }
+}
+
+impl GoStruct {
+    fn new(c: ClassWalker<'_>, partial: bool) -> GoStruct {
+        GoStruct {
+            name: go_name(c.name()),
+            dynamic: c.item.attributes.get("dynamic_type").is_some(),
+            fields: c
+                .item
+                .elem
+                .static_fields
+                .iter()
+                .map(|f| GoField {
+                    name: go_name(&f.elem.name),
+                    json_name: f.elem.name.clone(),
+                    type_ref: if partial {
+                        partial_field_type_ref(f)
+                    } else {
+                        f.elem.r#type.elem.to_go()
+                    },
+                    docstring: f.elem.docstring.as_ref().map(|d| render_docstring(d, true)),
+                })
+                .collect(),
+            docstring: c
+                .item
+                .elem
+                .docstring
+                .as_ref()
+                .map(|d| render_docstring(d, false)),
+        }
+    }
+}
+
+/// The type of a field in a partial struct, taking its `@stream.*`
+/// attributes into account.
+fn partial_field_type_ref(field: &Node<Field>) -> String {
+    let field_type = &field.elem.r#type.elem;
+    let behavior = field.attributes.streaming_behavior();
+    let mut type_ref = field_type.to_partial_go();
+
+    // `@stream.not_null` fields are always present in a partial instance.
+    if behavior.not_null && !field_type.is_optional() {
+        if let Some(inner) = type_ref.strip_prefix('*') {
+            type_ref = inner.to_string();
+        }
+    }
+
+    if behavior.state {
+        format!("StreamState[{type_ref}]")
+    } else {
+        type_ref
+    }
+}
+
+/// Render the BAML documentation (a bare string with padding stripped) into
+/// a Go comment.
+fn render_docstring(d: &Docstring, indented: bool) -> String {
+    let separator = if indented { "\n\t// " } else { "\n// " };
+    format!("// {}", d.0.as_str().replace('\n', separator))
+}
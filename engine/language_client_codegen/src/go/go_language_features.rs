@@ -0,0 +1,114 @@
+use crate::dir_writer::LanguageFeatures;
+
+#[derive(Default)]
+pub(super) struct GoLanguageFeatures {}
+
+impl LanguageFeatures for GoLanguageFeatures {
+    // The first line is the marker recognized by `go generate`, linters and
+    // code review tools: https://pkg.go.dev/cmd/go#hdr-Generate_Go_files_by_processing_source
+    const CONTENT_PREFIX: &'static str = r#"
+// Code generated by BAML. DO NOT EDIT.
+//
+// This file was generated by BAML: please do not edit it. Instead, edit the
+// BAML files and re-generate this code.
+//
+// The client links against libbaml_cffi: set CGO_LDFLAGS="-L<dir>" to the
+// directory containing it when building.
+        "#;
+}
+
+pub(super) trait ToGo {
+    /// The Go type of a value of this type.
+    fn to_go(&self) -> String;
+    /// The Go type of a value of this type while it is being streamed.
+    fn to_partial_go(&self) -> String;
+}
+
+const RESERVED: &[&str] = &[
+    // Go keywords
+    "break",
+    "case",
+    "chan",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "else",
+    "fallthrough",
+    "for",
+    "func",
+    "go",
+    "goto",
+    "if",
+    "import",
+    "interface",
+    "map",
+    "package",
+    "range",
+    "return",
+    "select",
+    "struct",
+    "switch",
+    "type",
+    "var",
+    // Names used by the generated functions
+    "ctx",
+    "opts",
+    "onPartial",
+    "result",
+    "err",
+    "partial",
+    "data",
+];
+
+/// An exported Go identifier for a BAML name: `user_name` becomes
+/// `UserName`, and `userID` becomes `UserID`.
+pub(super) fn go_name(name: &str) -> String {
+    let name: String = name
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    match name.chars().next() {
+        Some(c) if c.is_ascii_digit() => format!("X{name}"),
+        Some(_) => name,
+        None => "X".to_string(),
+    }
+}
+
+/// A Go identifier for a function parameter, which keeps the BAML name
+/// unless it is reserved.
+pub(super) fn go_param_name(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{name}_")
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_go_name() {
+        assert_eq!(go_name("Resume"), "Resume");
+        assert_eq!(go_name("user_name"), "UserName");
+        assert_eq!(go_name("userID"), "UserID");
+        assert_eq!(go_name("_private"), "Private");
+        assert_eq!(go_name("3d"), "X3d");
+    }
+
+    #[test]
+    fn test_go_param_name() {
+        assert_eq!(go_param_name("resume"), "resume");
+        assert_eq!(go_param_name("type"), "type_");
+        assert_eq!(go_param_name("ctx"), "ctx_");
+    }
+}
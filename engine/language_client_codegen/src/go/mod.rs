@@ -0,0 +1,137 @@
+mod field_type;
+mod generate_types;
+mod go_language_features;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use indexmap::IndexMap;
+
+use internal_baml_core::ir::repr::IntermediateRepr;
+
+use crate::dir_writer::FileCollector;
+
+use go_language_features::{go_name, go_param_name, GoLanguageFeatures, ToGo};
+
+#[derive(askama::Template)]
+#[template(path = "client.go.j2", escape = "none")]
+struct GoClient {
+    funcs: Vec<GoFunction>,
+}
+struct GoFunction {
+    name: String,
+    baml_name: String,
+    partial_return_type: String,
+    return_type: String,
+    args: Vec<GoArg>,
+}
+struct GoArg {
+    name: String,
+    go_name: String,
+    type_ref: String,
+}
+
+#[derive(askama::Template)]
+#[template(path = "runtime.go.j2", escape = "none")]
+struct GoRuntime {
+    version: String,
+}
+
+#[derive(askama::Template)]
+#[template(path = "type_builder.go.j2", escape = "none")]
+struct GoTypeBuilder {}
+
+#[derive(askama::Template)]
+#[template(path = "inlined.go.j2", escape = "none")]
+struct InlinedBaml {
+    file_map: Vec<(String, String)>,
+}
+
+/// The declarations of libbaml_cffi, which cgo needs next to the Go files.
+#[derive(askama::Template)]
+#[template(path = "baml.h.j2", escape = "none")]
+struct CHeader {
+    header: &'static str,
+}
+
+pub(crate) fn generate(
+    ir: &IntermediateRepr,
+    generator: &crate::GeneratorArgs,
+) -> Result<IndexMap<PathBuf, String>> {
+    let mut collector = FileCollector::<GoLanguageFeatures>::new();
+
+    collector.add_template::<generate_types::GoTypes>("types.go", (ir, generator))?;
+    collector
+        .add_template::<generate_types::GoPartialTypes>("partial_types.go", (ir, generator))?;
+    collector.add_template::<GoClient>("client.go", (ir, generator))?;
+    collector.add_template::<GoRuntime>("runtime.go", (ir, generator))?;
+    collector.add_template::<GoTypeBuilder>("type_builder.go", (ir, generator))?;
+    collector.add_template::<InlinedBaml>("inlined.go", (ir, generator))?;
+    collector.add_template::<CHeader>("baml.h", (ir, generator))?;
+
+    collector.commit(&generator.output_dir())
+}
+
+impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir crate::GeneratorArgs)> for GoClient {
+    type Error = anyhow::Error;
+
+    fn try_from((ir, _): (&'ir IntermediateRepr, &'ir crate::GeneratorArgs)) -> Result<Self> {
+        let funcs = ir
+            .walk_functions()
+            .map(|f| GoFunction {
+                name: go_name(f.name()),
+                baml_name: f.name().to_string(),
+                partial_return_type: f.elem().output().to_partial_go(),
+                return_type: f.elem().output().to_go(),
+                args: f
+                    .inputs()
+                    .iter()
+                    .map(|(name, r#type)| GoArg {
+                        name: name.to_string(),
+                        go_name: go_param_name(name),
+                        type_ref: r#type.to_go(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        Ok(GoClient { funcs })
+    }
+}
+
+impl TryFrom<(&'_ IntermediateRepr, &'_ crate::GeneratorArgs)> for GoRuntime {
+    type Error = anyhow::Error;
+
+    fn try_from((_, args): (&IntermediateRepr, &crate::GeneratorArgs)) -> Result<Self> {
+        Ok(GoRuntime {
+            version: args.version.clone(),
+        })
+    }
+}
+
+impl TryFrom<(&'_ IntermediateRepr, &'_ crate::GeneratorArgs)> for GoTypeBuilder {
+    type Error = anyhow::Error;
+
+    fn try_from(_: (&IntermediateRepr, &crate::GeneratorArgs)) -> Result<Self> {
+        Ok(GoTypeBuilder {})
+    }
+}
+
+impl TryFrom<(&'_ IntermediateRepr, &'_ crate::GeneratorArgs)> for InlinedBaml {
+    type Error = anyhow::Error;
+
+    fn try_from((_ir, args): (&IntermediateRepr, &crate::GeneratorArgs)) -> Result<Self> {
+        Ok(InlinedBaml {
+            file_map: args.file_map()?,
+        })
+    }
+}
+
+impl TryFrom<(&'_ IntermediateRepr, &'_ crate::GeneratorArgs)> for CHeader {
+    type Error = anyhow::Error;
+
+    fn try_from(_: (&IntermediateRepr, &crate::GeneratorArgs)) -> Result<Self> {
+        Ok(CHeader {
+            header: include_str!("../../../language_client_cffi/include/baml.h"),
+        })
+    }
+}
@@ -12,6 +12,7 @@ use std::{
 use version_check::{check_version, GeneratorType, VersionCheckMode};
 
 mod dir_writer;
+mod go;
 pub mod openapi;
 mod python;
 mod ruby;
@@ -182,6 +183,7 @@ impl GenerateClient for GeneratorOutputType {
 
         let files = match self {
             GeneratorOutputType::OpenApi => openapi::generate(ir, gen),
+            GeneratorOutputType::Go => go::generate(ir, gen),
             GeneratorOutputType::PythonPydantic => python::generate(ir, gen),
             GeneratorOutputType::RubySorbet => ruby::generate(ir, gen),
             GeneratorOutputType::Typescript => typescript::generate(ir, gen),
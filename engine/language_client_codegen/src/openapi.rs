@@ -66,6 +66,8 @@ impl LanguageFeatures for OpenApiLanguageFeatures {
 pub struct OpenApiSchema<'ir> {
     paths: Vec<OpenApiMethodDef<'ir>>,
     schemas: IndexMap<&'ir str, TypeSpecWithMeta>,
+    /// Schemas for instances of classes that are still being streamed in.
+    partial_schemas: IndexMap<String, TypeSpecWithMeta>,
 }
 
 impl Serialize for OpenApiSchema<'_> {
@@ -82,6 +84,15 @@ impl Serialize for OpenApiSchema<'_> {
             Ok(schemas) => schemas,
             Err(e) => return Err(serde::ser::Error::custom(e)),
         };
+        let partial_schemas = match self
+            .partial_schemas
+            .iter()
+            .map(|(name, schema)| Ok((name.as_str(), serde_json::to_value(schema)?)))
+            .collect::<core::result::Result<Vec<_>, serde_json::Error>>()
+        {
+            Ok(schemas) => schemas,
+            Err(e) => return Err(serde::ser::Error::custom(e)),
+        };
         json!({
             "openapi": "3.0.0",
             "info": {
@@ -104,8 +115,10 @@ impl Serialize for OpenApiSchema<'_> {
                 .iter()
                 .flat_map(|p| vec![
                     (format!("/call/{}", p.function_name), p.as_json("call", "application/json")),
-                    // TODO: publish a schema for streams
-                    // (format!("/stream/{}", p.function_name), p.as_json("stream", "text/event-stream"))
+                    (format!("/stream/{}", p.function_name), p.as_json("stream", "text/event-stream")),
+                    (format!("/render/{}", p.function_name), p.as_json("render", "application/json")),
+                    (format!("/parse/{}", p.function_name), p.as_json("parse", "application/json")),
+                    (format!("/batch/{}", p.function_name), p.as_json("batch", "application/json")),
                 ])
                 .collect::<IndexMap<_, _>>(),
             "components": {
@@ -208,10 +221,158 @@ impl Serialize for OpenApiSchema<'_> {
                                         }
                                     },
                                     "required": ["clients"]
+                                },
+                                "type_builder": {
+                                    "$ref": "#/components/schemas/BamlTypeBuilder"
+                                },
+                                "tags": {
+                                    "type": "object",
+                                    "additionalProperties": true
+                                },
+                                "parent_span_id": {
+                                    "type": "string",
+                                    "format": "uuid"
                                 }
                             }
                         })
                     ),
+                    (
+                        "BamlTypeBuilder",
+                        json!({
+                            "type": "object",
+                            "properties": {
+                                "classes": {
+                                    "type": "object",
+                                    "additionalProperties": {
+                                        "type": "object",
+                                        "properties": {
+                                            "properties": {
+                                                "type": "object",
+                                                "additionalProperties": {
+                                                    "$ref": "#/components/schemas/BamlTypeBuilderProperty"
+                                                }
+                                            }
+                                        }
+                                    }
+                                },
+                                "enums": {
+                                    "type": "object",
+                                    "additionalProperties": {
+                                        "type": "object",
+                                        "properties": {
+                                            "values": {
+                                                "type": "object",
+                                                "additionalProperties": {
+                                                    "$ref": "#/components/schemas/BamlTypeBuilderEnumValue"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        })
+                    ),
+                    (
+                        "BamlTypeBuilderProperty",
+                        json!({
+                            "type": "object",
+                            "properties": {
+                                "type": { "$ref": "#/components/schemas/BamlTypeBuilderFieldType" },
+                                "alias": { "type": "string" },
+                                "description": { "type": "string" }
+                            }
+                        })
+                    ),
+                    (
+                        "BamlTypeBuilderEnumValue",
+                        json!({
+                            "type": "object",
+                            "properties": {
+                                "alias": { "type": "string" },
+                                "description": { "type": "string" },
+                                "skip": { "type": "boolean" }
+                            }
+                        })
+                    ),
+                    (
+                        "BamlTypeBuilderFieldType",
+                        json!({
+                            "oneOf": [
+                                {
+                                    "type": "string",
+                                    "enum": ["string", "int", "float", "bool", "null"]
+                                },
+                                {
+                                    "type": "object",
+                                    "title": "BamlTypeBuilderLiteralType",
+                                    "properties": {
+                                        "literal": {
+                                            "oneOf": [
+                                                { "type": "string" },
+                                                { "type": "integer" },
+                                                { "type": "boolean" }
+                                            ]
+                                        }
+                                    },
+                                    "required": ["literal"]
+                                },
+                                {
+                                    "type": "object",
+                                    "title": "BamlTypeBuilderClassType",
+                                    "properties": { "class": { "type": "string" } },
+                                    "required": ["class"]
+                                },
+                                {
+                                    "type": "object",
+                                    "title": "BamlTypeBuilderEnumType",
+                                    "properties": { "enum": { "type": "string" } },
+                                    "required": ["enum"]
+                                },
+                                {
+                                    "type": "object",
+                                    "title": "BamlTypeBuilderListType",
+                                    "properties": {
+                                        "list": { "$ref": "#/components/schemas/BamlTypeBuilderFieldType" }
+                                    },
+                                    "required": ["list"]
+                                },
+                                {
+                                    "type": "object",
+                                    "title": "BamlTypeBuilderOptionalType",
+                                    "properties": {
+                                        "optional": { "$ref": "#/components/schemas/BamlTypeBuilderFieldType" }
+                                    },
+                                    "required": ["optional"]
+                                },
+                                {
+                                    "type": "object",
+                                    "title": "BamlTypeBuilderMapType",
+                                    "properties": {
+                                        "map": {
+                                            "type": "object",
+                                            "properties": {
+                                                "key": { "$ref": "#/components/schemas/BamlTypeBuilderFieldType" },
+                                                "value": { "$ref": "#/components/schemas/BamlTypeBuilderFieldType" }
+                                            },
+                                            "required": ["key", "value"]
+                                        }
+                                    },
+                                    "required": ["map"]
+                                },
+                                {
+                                    "type": "object",
+                                    "title": "BamlTypeBuilderUnionType",
+                                    "properties": {
+                                        "union": {
+                                            "type": "array",
+                                            "items": { "$ref": "#/components/schemas/BamlTypeBuilderFieldType" }
+                                        }
+                                    },
+                                    "required": ["union"]
+                                }
+                            ]
+                        })
+                    ),
                     (
                         "ClientProperty",
                         json!({
@@ -235,6 +396,94 @@ impl Serialize for OpenApiSchema<'_> {
                             "required": ["name", "provider", "options"]
                         })
                     ),
+                    (
+                        "StreamState",
+                        json!({
+                            "type": "string",
+                            "enum": ["Pending", "Incomplete", "Complete"]
+                        })
+                    ),
+                    (
+                        "BamlError",
+                        json!({
+                            "type": "object",
+                            "properties": {
+                                "error": {
+                                    "type": "string",
+                                    "enum": [
+                                        "invalid_argument",
+                                        "client_error",
+                                        "validation_failure",
+                                        "finish_reason_error",
+                                        "internal_error"
+                                    ]
+                                },
+                                "message": { "type": "string" },
+                                "prompt": { "type": "string" },
+                                "raw_output": { "type": "string" },
+                                "finish_reason": { "type": "string", "nullable": true }
+                            },
+                            "required": ["error", "message"]
+                        })
+                    ),
+                    (
+                        "ParseRequest",
+                        json!({
+                            "type": "object",
+                            "properties": {
+                                "raw_output": { "type": "string" },
+                                "allow_partials": { "type": "boolean" }
+                            },
+                            "required": ["raw_output"]
+                        })
+                    ),
+                    (
+                        "RenderResponse",
+                        json!({
+                            "type": "object",
+                            "properties": {
+                                "prompt": {
+                                    "oneOf": [
+                                        {
+                                            "type": "object",
+                                            "title": "RenderedCompletionPrompt",
+                                            "properties": {
+                                                "Completion": { "type": "string" }
+                                            },
+                                            "required": ["Completion"]
+                                        },
+                                        {
+                                            "type": "object",
+                                            "title": "RenderedChatPrompt",
+                                            "properties": {
+                                                "Chat": {
+                                                    "type": "array",
+                                                    "items": {
+                                                        "type": "object",
+                                                        "properties": {
+                                                            "role": { "type": "string" },
+                                                            "allow_duplicate_role": { "type": "boolean" },
+                                                            "parts": {
+                                                                "type": "array",
+                                                                "items": {
+                                                                    "type": "object",
+                                                                    "additionalProperties": true
+                                                                }
+                                                            }
+                                                        },
+                                                        "required": ["role", "parts"]
+                                                    }
+                                                }
+                                            },
+                                            "required": ["Chat"]
+                                        }
+                                    ]
+                                },
+                                "curl": { "type": "string", "nullable": true }
+                            },
+                            "required": ["prompt"]
+                        })
+                    ),
                     (  "Check",
                         json!({
                             "type": "object",
@@ -249,6 +498,7 @@ impl Serialize for OpenApiSchema<'_> {
                 ]
                 .into_iter()
                 .chain(schemas.into_iter())
+                .chain(partial_schemas.into_iter())
                 .collect::<IndexMap<_, _>>(),
             }
         })
@@ -260,20 +510,84 @@ struct OpenApiMethodDef<'ir> {
     function_name: &'ir str,
     request_body: TypeSpecWithMeta,
     response: TypeSpecWithMeta,
+    partial_response: TypeSpecWithMeta,
 }
 
 impl OpenApiMethodDef<'_> {
     fn as_json(&self, tag: &str, response_type: &str) -> serde_json::Value {
-        let mut as_json = json!({
-            "requestBody": {
+        let request_body = match tag {
+            "parse" => json!({
+                "required": true,
+                "content": {
+                    "application/json": {
+                        "schema": { "$ref": "#/components/schemas/ParseRequest" }
+                    }
+                }
+            }),
+            "batch" => json!({
+                "required": true,
+                "content": {
+                    "application/json": {
+                        "schema": {
+                            "type": "object",
+                            "title": format!("{}BatchRequest", self.function_name),
+                            "properties": {
+                                "inputs": {
+                                    "type": "array",
+                                    "items": self.request_body,
+                                },
+                                "max_concurrency": {
+                                    "type": "integer",
+                                    "minimum": 1,
+                                    "maximum": 64,
+                                },
+                                "__baml_options__": {
+                                    "$ref": "#/components/schemas/BamlOptions",
+                                },
+                            },
+                            "required": ["inputs"],
+                        }
+                    }
+                }
+            }),
+            _ => json!({
                 "$ref": format!("#/components/requestBodies/{}", self.function_name),
-            },
+            }),
+        };
+        let response = match tag {
+            // Each server-sent event of a stream carries a partial response.
+            "stream" => json!(self.partial_response),
+            "render" => json!({ "$ref": "#/components/schemas/RenderResponse" }),
+            "batch" => json!({
+                "type": "object",
+                "title": format!("{}BatchResponse", self.function_name),
+                "properties": {
+                    "results": {
+                        "type": "array",
+                        "items": {
+                            "oneOf": [
+                                {
+                                    "type": "object",
+                                    "properties": { "value": self.response },
+                                    "required": ["value"],
+                                },
+                                { "$ref": "#/components/schemas/BamlError" },
+                            ]
+                        }
+                    }
+                },
+                "required": ["results"],
+            }),
+            _ => json!(self.response),
+        };
+        let mut as_json = json!({
+            "requestBody": request_body,
             "responses": {
                 "200": {
                     "description": "Successful operation",
                     "content": {
                         response_type: {
-                            "schema": self.response
+                            "schema": response
                         }
                     }
                 }
@@ -288,7 +602,7 @@ impl OpenApiMethodDef<'_> {
                 })
             } else {
                 json!({
-                    "tags": ["stream"],
+                    "tags": [tag],
                     // "operationId": self.function_name,
                 })
             }
@@ -341,6 +655,10 @@ impl<'ir> TryFrom<(&'ir IntermediateRepr, &'_ crate::GeneratorArgs)> for OpenApi
                 .chain(ir.walk_enums().map(|e| Ok((e.name(), e.try_into()?))))
                 .chain(ir.walk_classes().map(|c| Ok((c.name(), c.try_into()?))))
                 .collect::<Result<_>>()?,
+            partial_schemas: ir
+                .walk_classes()
+                .map(|c| Ok((partial_schema_name(c.name()), partial_class_type_spec(c)?)))
+                .collect::<Result<_>>()?,
         })
     }
 }
@@ -446,6 +764,11 @@ impl<'ir> TryFrom<Walker<'ir, &'ir Node<Function>>> for OpenApiMethodDef<'ir> {
                 response_type.meta.title = Some(format!("{}Response", function_name));
                 response_type
             },
+            partial_response: {
+                let mut response_type = value.item.elem.output().to_partial_type_spec(value.db)?;
+                response_type.meta.title = Some(format!("{}PartialResponse", function_name));
+                response_type
+            },
         })
     }
 }
@@ -521,11 +844,147 @@ impl<'ir> TryFrom<ClassWalker<'ir>> for TypeSpecWithMeta {
     }
 }
 
+fn partial_schema_name(class_name: &str) -> String {
+    format!("Partial{class_name}")
+}
+
+/// The schema of a class instance that is still being streamed in: every
+/// field may be missing or null, except for `@stream.not_null` fields, and
+/// `@stream.with_state` fields are wrapped with their `StreamState`.
+fn partial_class_type_spec(c: ClassWalker<'_>) -> Result<TypeSpecWithMeta> {
+    let mut properties = IndexMap::new();
+    let mut required = Vec::new();
+
+    for f in c.item.elem.static_fields.iter() {
+        let name = f.elem.name.to_string();
+        let behavior = f.attributes.streaming_behavior();
+        let field_type = &f.elem.r#type.elem;
+
+        let mut type_spec = field_type.to_partial_type_spec(c.db).context(format!(
+            "Failed to convert {}.{} to OpenAPI type",
+            c.name(),
+            f.elem.name
+        ))?;
+        if behavior.not_null && !field_type.is_optional() {
+            type_spec.meta.nullable = false;
+        }
+        if behavior.state {
+            type_spec = TypeSpecWithMeta {
+                meta: TypeMetadata::default(),
+                type_spec: TypeSpec::Inline(TypeDef::Class {
+                    properties: vec![
+                        ("value".to_string(), type_spec),
+                        (
+                            "state".to_string(),
+                            TypeSpecWithMeta {
+                                meta: TypeMetadata::default(),
+                                type_spec: TypeSpec::Ref {
+                                    r#ref: "#/components/schemas/StreamState".to_string(),
+                                },
+                            },
+                        ),
+                    ]
+                    .into_iter()
+                    .collect(),
+                    required: vec!["value".to_string(), "state".to_string()],
+                    additional_properties: false,
+                }),
+            };
+        }
+        if behavior.not_null || behavior.state {
+            required.push(name.clone());
+        }
+        properties.insert(name, type_spec);
+    }
+
+    Ok(TypeSpecWithMeta {
+        meta: TypeMetadata::default(),
+        type_spec: TypeSpec::Inline(TypeDef::Class {
+            properties,
+            required,
+            additional_properties: false,
+        }),
+    })
+}
+
 trait ToTypeReferenceInTypeDefinition<'ir> {
     fn to_type_spec(&self, ir: &'ir IntermediateRepr) -> Result<TypeSpecWithMeta>;
+
+    /// Like `to_type_spec`, but for values that are still being streamed in.
+    fn to_partial_type_spec(&self, ir: &'ir IntermediateRepr) -> Result<TypeSpecWithMeta>;
 }
 
 impl<'ir> ToTypeReferenceInTypeDefinition<'ir> for FieldType {
+    fn to_partial_type_spec(&self, ir: &'ir IntermediateRepr) -> Result<TypeSpecWithMeta> {
+        let mut type_spec = match self {
+            FieldType::Class(name) => TypeSpecWithMeta {
+                meta: TypeMetadata::default(),
+                type_spec: TypeSpec::Ref {
+                    r#ref: format!("#/components/schemas/{}", partial_schema_name(name)),
+                },
+            },
+            FieldType::List(inner) => TypeSpecWithMeta {
+                meta: TypeMetadata::default(),
+                type_spec: TypeSpec::Inline(TypeDef::Array {
+                    items: inner.to_partial_type_spec(ir)?.into(),
+                }),
+            },
+            FieldType::Map(key, value) => {
+                if !matches!(**key, FieldType::Primitive(TypeValue::String)) {
+                    anyhow::bail!("BAML<->OpenAPI only supports string keys in maps")
+                }
+                TypeSpecWithMeta {
+                    meta: TypeMetadata::default(),
+                    type_spec: TypeSpec::Inline(TypeDef::Map {
+                        additional_properties: Box::new(value.to_partial_type_spec(ir)?),
+                    }),
+                }
+            }
+            FieldType::Union(union) => {
+                let one_of = union
+                    .iter()
+                    .filter(|t| !t.is_null())
+                    .map(|t| t.to_partial_type_spec(ir))
+                    .collect::<Result<Vec<_>>>()?;
+
+                if one_of.is_empty() {
+                    anyhow::bail!("BAML<->OpenAPI unions must have at least one non-null type")
+                }
+
+                TypeSpecWithMeta {
+                    meta: TypeMetadata::default(),
+                    type_spec: TypeSpec::Union { one_of },
+                }
+            }
+            FieldType::Optional(inner) => inner.to_partial_type_spec(ir)?,
+            FieldType::Constrained { base, .. } => match field_type_attributes(self) {
+                Some(checks) => TypeSpecWithMeta {
+                    meta: TypeMetadata::default(),
+                    type_spec: TypeSpec::Inline(TypeDef::Class {
+                        properties: vec![
+                            ("value".to_string(), base.to_partial_type_spec(ir)?),
+                            ("checks".to_string(), type_def_for_checks(checks)),
+                        ]
+                        .into_iter()
+                        .collect(),
+                        required: vec!["value".to_string(), "checks".to_string()],
+                        additional_properties: false,
+                    }),
+                },
+                None => base.to_partial_type_spec(ir)?,
+            },
+            FieldType::Enum(_)
+            | FieldType::Literal(_)
+            | FieldType::Primitive(_)
+            | FieldType::Tuple(_) => self.to_type_spec(ir)?,
+        };
+        // Lists and maps are streamed in as (possibly empty) containers.
+        if !matches!(self, FieldType::List(_) | FieldType::Map(..)) {
+            type_spec.meta.nullable = true;
+        }
+        Ok(type_spec)
+    }
+
     fn to_type_spec(&self, _ir: &'ir IntermediateRepr) -> Result<TypeSpecWithMeta> {
         Ok(match self {
             FieldType::Enum(name) | FieldType::Class(name) => TypeSpecWithMeta {
@@ -2,12 +2,13 @@ use anyhow::Result;
 use baml_types::LiteralValue;
 use itertools::Itertools;
 use std::borrow::Cow;
+use std::collections::HashSet;
 
 use crate::{field_type_attributes, type_check_attributes, TypeCheckAttributes};
 
 use super::python_language_features::ToPython;
 use internal_baml_core::ir::{
-    repr::{Docstring, IntermediateRepr},
+    repr::{Docstring, Field, IntermediateRepr, Node, TypeNamespace},
     ClassWalker, EnumWalker, FieldType, IRHelper,
 };
 
@@ -16,6 +17,8 @@ use internal_baml_core::ir::{
 pub(crate) struct PythonTypes<'ir> {
     enums: Vec<PythonEnum<'ir>>,
     classes: Vec<PythonClass<'ir>>,
+    /// Classes mirroring the BAML namespaces, see [`render_namespaces`].
+    namespaces: String,
 }
 
 #[derive(askama::Template)]
@@ -34,13 +37,26 @@ struct PythonEnum<'ir> {
 
 struct PythonClass<'ir> {
     name: Cow<'ir, str>,
+    /// The classes this class extends.
+    bases: Vec<&'ir str>,
     /// The docstring for the class, including comment delimiters.
     docstring: Option<String>,
     // the name, type and docstring of the field.
     fields: Vec<(Cow<'ir, str>, String, Option<String>)>,
+    /// Fields inherited as is from `bases`, not declared in the class body.
+    inherited_fields: HashSet<&'ir str>,
     dynamic: bool,
 }
 
+impl<'ir> PythonClass<'ir> {
+    /// The fields declared in the class body.
+    fn declared_fields(&self) -> impl Iterator<Item = &(Cow<'ir, str>, String, Option<String>)> {
+        self.fields
+            .iter()
+            .filter(|(name, ..)| !self.inherited_fields.contains(name.as_ref()))
+    }
+}
+
 #[derive(askama::Template)]
 #[template(path = "partial_types.py.j2", escape = "none")]
 pub(crate) struct PythonStreamTypes<'ir> {
@@ -50,6 +66,8 @@ pub(crate) struct PythonStreamTypes<'ir> {
 /// The Python class corresponding to Partial<TypeDefinedInBaml>
 struct PartialPythonClass<'ir> {
     name: &'ir str,
+    /// The partial classes this class extends.
+    bases: Vec<&'ir str>,
     dynamic: bool,
     /// The docstring for the class, including comment delimiters.
     docstring: Option<String>,
@@ -65,7 +83,11 @@ impl<'ir> TryFrom<(&'ir IntermediateRepr, &'_ crate::GeneratorArgs)> for PythonT
     ) -> Result<PythonTypes<'ir>> {
         Ok(PythonTypes {
             enums: ir.walk_enums().map(PythonEnum::from).collect::<Vec<_>>(),
-            classes: ir.walk_classes().map(PythonClass::from).collect::<Vec<_>>(),
+            classes: parents_first(ir)
+                .into_iter()
+                .map(PythonClass::from)
+                .collect::<Vec<_>>(),
+            namespaces: render_namespaces(ir.type_namespaces(), ""),
         })
     }
 }
@@ -104,6 +126,14 @@ impl<'ir> From<ClassWalker<'ir>> for PythonClass<'ir> {
     fn from(c: ClassWalker<'ir>) -> Self {
         PythonClass {
             name: Cow::Borrowed(c.name()),
+            bases: c.parents().map(|p| p.name()).collect(),
+            inherited_fields: c
+                .elem()
+                .static_fields
+                .iter()
+                .filter(|f| c.inherits_field(f))
+                .map(|f| f.elem.name.as_str())
+                .collect(),
             dynamic: c.item.attributes.get("dynamic_type").is_some(),
             fields: c
                 .item
@@ -131,8 +161,8 @@ impl<'ir> TryFrom<(&'ir IntermediateRepr, &'_ crate::GeneratorArgs)> for PythonS
 
     fn try_from((ir, _): (&'ir IntermediateRepr, &'_ crate::GeneratorArgs)) -> Result<Self> {
         Ok(Self {
-            partial_classes: ir
-                .walk_classes()
+            partial_classes: parents_first(ir)
+                .into_iter()
                 .map(PartialPythonClass::from)
                 .collect::<Vec<_>>(),
         })
@@ -143,19 +173,18 @@ impl<'ir> From<ClassWalker<'ir>> for PartialPythonClass<'ir> {
     fn from(c: ClassWalker<'ir>) -> PartialPythonClass<'ir> {
         PartialPythonClass {
             name: c.name(),
+            bases: c.parents().map(|p| p.name()).collect(),
             dynamic: c.item.attributes.get("dynamic_type").is_some(),
             fields: c
                 .item
                 .elem
                 .static_fields
                 .iter()
+                .filter(|f| !c.inherits_field(f))
                 .map(|f| {
                     (
                         f.elem.name.as_str(),
-                        add_default_value(
-                            &f.elem.r#type.elem,
-                            &f.elem.r#type.elem.to_partial_type_ref(c.db, false),
-                        ),
+                        partial_field_type_ref(f, c.db),
                         f.elem.docstring.as_ref().map(render_docstring),
                     )
                 })
@@ -165,6 +194,76 @@ impl<'ir> From<ClassWalker<'ir>> for PartialPythonClass<'ir> {
     }
 }
 
+/// A class per BAML namespace, whose attributes are the types in it, so that
+/// `shared.Resume` is `Shared_Resume`, like in BAML.
+fn render_namespaces(namespaces: &[TypeNamespace], indent: &str) -> String {
+    let mut out = String::new();
+    for namespace in namespaces {
+        out.push_str(&format!("\n{indent}class {}:\n", namespace.name));
+        out.push_str(&format!(
+            "{indent}    \"\"\"The types of the BAML namespace `{}`.\"\"\"\n",
+            namespace.name
+        ));
+        for ty in &namespace.types {
+            out.push_str(&format!("{indent}    {} = {}\n", ty.name, ty.canonical));
+        }
+        out.push_str(&render_namespaces(
+            &namespace.namespaces,
+            &format!("{indent}    "),
+        ));
+    }
+    out
+}
+
+/// All the classes, with every class after the classes it extends, since
+/// Python needs base classes to be defined first.
+fn parents_first(ir: &IntermediateRepr) -> Vec<ClassWalker<'_>> {
+    fn visit<'ir>(
+        class: ClassWalker<'ir>,
+        visited: &mut HashSet<&'ir str>,
+        ordered: &mut Vec<ClassWalker<'ir>>,
+    ) {
+        if !visited.insert(class.name()) {
+            return;
+        }
+        for parent in class.parents() {
+            visit(parent, visited, ordered);
+        }
+        ordered.push(class);
+    }
+
+    let mut visited = HashSet::new();
+    let mut ordered = Vec::new();
+    for class in ir.walk_classes() {
+        visit(class, &mut visited, &mut ordered);
+    }
+    ordered
+}
+
+/// The type of a field in a partial class, taking its `@stream.*`
+/// attributes into account.
+fn partial_field_type_ref(field: &Node<Field>, ir: &IntermediateRepr) -> String {
+    let field_type = &field.elem.r#type.elem;
+    let behavior = field.attributes.streaming_behavior();
+    let mut type_ref = field_type.to_partial_type_ref(ir, false);
+
+    // `@stream.not_null` fields are always present in a partial instance.
+    if behavior.not_null && !field_type.is_optional() {
+        if let Some(inner) = type_ref
+            .strip_prefix("Optional[")
+            .and_then(|t| t.strip_suffix(']'))
+        {
+            type_ref = inner.to_string();
+        }
+    }
+
+    if behavior.state {
+        format!("StreamState[{type_ref}]")
+    } else {
+        add_default_value(field_type, &type_ref)
+    }
+}
+
 pub fn add_default_value(node: &FieldType, type_str: &String) -> String {
     if type_str.starts_with("Optional[") {
         format!("{} = None", type_str)
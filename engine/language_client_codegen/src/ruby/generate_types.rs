@@ -9,7 +9,7 @@ use crate::{field_type_attributes, type_check_attributes, TypeCheckAttributes};
 
 use super::ruby_language_features::ToRuby;
 use internal_baml_core::ir::{
-    repr::{Docstring, IntermediateRepr},
+    repr::{Docstring, Field, IntermediateRepr, Node, TypeNamespace},
     ClassWalker, EnumWalker, FieldType,
 };
 
@@ -18,6 +18,8 @@ use internal_baml_core::ir::{
 pub(crate) struct RubyTypes<'ir> {
     enums: Vec<RubyEnum<'ir>>,
     classes: Vec<RubyStruct<'ir>>,
+    /// Modules mirroring the BAML namespaces, see [`render_namespaces`].
+    namespaces: String,
 }
 
 struct RubyEnum<'ir> {
@@ -62,6 +64,7 @@ impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir crate::GeneratorArgs)> for RubyTy
         Ok(RubyTypes {
             enums: ir.walk_enums().map(|e| e.into()).collect(),
             classes: ir.walk_classes().map(|c| c.into()).collect(),
+            namespaces: render_namespaces(ir.type_namespaces(), "    "),
         })
     }
 }
@@ -138,7 +141,7 @@ impl<'ir> From<ClassWalker<'ir>> for PartialRubyStruct<'ir> {
                 .map(|f| {
                     (
                         f.elem.name.as_str(),
-                        f.elem.r#type.elem.to_partial_type_ref(),
+                        partial_field_type_ref(f),
                         f.elem.docstring.as_ref().map(|d| render_docstring(d, true)),
                     )
                 })
@@ -153,6 +156,30 @@ impl<'ir> From<ClassWalker<'ir>> for PartialRubyStruct<'ir> {
     }
 }
 
+/// The type of a field in a partial struct, taking its `@stream.*`
+/// attributes into account.
+fn partial_field_type_ref(field: &Node<Field>) -> String {
+    let field_type = &field.elem.r#type.elem;
+    let behavior = field.attributes.streaming_behavior();
+    let mut type_ref = field_type.to_partial_type_ref();
+
+    // `@stream.not_null` fields are always present in a partial instance.
+    if behavior.not_null && !field_type.is_optional() {
+        if let Some(inner) = type_ref
+            .strip_prefix("T.nilable(")
+            .and_then(|t| t.strip_suffix(')'))
+        {
+            type_ref = inner.to_string();
+        }
+    }
+
+    if behavior.state {
+        format!("Baml::StreamState[{type_ref}]")
+    } else {
+        type_ref
+    }
+}
+
 pub(super) trait ToTypeReferenceInTypeDefinition {
     fn to_type_ref(&self) -> String;
     fn to_partial_type_ref(&self) -> String;
@@ -238,3 +265,32 @@ fn render_docstring(d: &Docstring, indented: bool) -> String {
         format!("# {lines}")
     }
 }
+
+/// A module per BAML namespace, with constants aliasing the types in it, so
+/// that `Shared::Resume` is `Shared_Resume`. Ruby constants must be
+/// capitalized, so types whose names aren't keep only their canonical name.
+fn render_namespaces(namespaces: &[TypeNamespace], indent: &str) -> String {
+    let mut out = String::new();
+    for namespace in namespaces {
+        let mut chars = namespace.name.chars();
+        let Some(first) = chars.next() else {
+            continue;
+        };
+        out.push_str(&format!(
+            "\n{indent}module {}{}\n",
+            first.to_ascii_uppercase(),
+            chars.as_str()
+        ));
+        for ty in &namespace.types {
+            if ty.name.starts_with(|c: char| c.is_ascii_uppercase()) {
+                out.push_str(&format!("{indent}  {} = {}\n", ty.name, ty.canonical));
+            }
+        }
+        out.push_str(&render_namespaces(
+            &namespace.namespaces,
+            &format!("{indent}  "),
+        ));
+        out.push_str(&format!("{indent}end\n"));
+    }
+    out
+}
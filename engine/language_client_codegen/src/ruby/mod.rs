@@ -28,6 +28,10 @@ struct RubyFunction {
     args: Vec<(String, String)>,
 }
 
+#[derive(askama::Template)]
+#[template(path = "tracing.rb.j2", escape = "none")]
+struct RubyTracing {}
+
 #[derive(askama::Template)]
 #[template(path = "inlined.rb.j2", escape = "none")]
 struct InlinedBaml {
@@ -45,6 +49,7 @@ pub(crate) fn generate(
     collector.add_template::<generate_types::RubyTypes>("types.rb", (ir, generator))?;
     collector.add_template::<generate_types::TypeRegistry>("type-registry.rb", (ir, generator))?;
     collector.add_template::<RubyClient>("client.rb", (ir, generator))?;
+    collector.add_template::<RubyTracing>("tracing.rb", (ir, generator))?;
     collector.add_template::<InlinedBaml>("inlined.rb", (ir, generator))?;
 
     collector.commit(&generator.output_dir())
@@ -84,6 +89,14 @@ impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir crate::GeneratorArgs)> for RubyCl
     }
 }
 
+impl TryFrom<(&'_ IntermediateRepr, &'_ crate::GeneratorArgs)> for RubyTracing {
+    type Error = anyhow::Error;
+
+    fn try_from(_: (&IntermediateRepr, &crate::GeneratorArgs)) -> Result<Self> {
+        Ok(RubyTracing {})
+    }
+}
+
 impl TryFrom<(&'_ IntermediateRepr, &'_ crate::GeneratorArgs)> for InlinedBaml {
     type Error = anyhow::Error;
 
@@ -1,10 +1,11 @@
 use std::borrow::Cow;
+use std::collections::HashSet;
 
 use anyhow::Result;
 use itertools::Itertools;
 
 use internal_baml_core::ir::{
-    repr::{Docstring, IntermediateRepr},
+    repr::{Docstring, Field, IntermediateRepr, Node, TypeNamespace},
     ClassWalker, EnumWalker,
 };
 
@@ -24,6 +25,26 @@ pub(crate) struct TypeBuilder<'ir> {
 pub(crate) struct TypescriptTypes<'ir> {
     enums: Vec<TypescriptEnum<'ir>>,
     classes: Vec<TypescriptClass<'ir>>,
+    /// Namespaces mirroring the BAML namespaces, see [`render_namespaces`].
+    namespaces: String,
+}
+
+#[derive(askama::Template)]
+#[template(path = "partial_types.ts.j2", escape = "none")]
+pub(crate) struct TypescriptStreamTypes<'ir> {
+    enums: Vec<&'ir str>,
+    partial_classes: Vec<PartialTypescriptClass<'ir>>,
+}
+
+/// The Typescript interface corresponding to Partial<TypeDefinedInBaml>
+struct PartialTypescriptClass<'ir> {
+    name: &'ir str,
+    /// The partial interfaces this interface extends.
+    bases: Vec<&'ir str>,
+    dynamic: bool,
+    // the name, whether it may be missing, the partial type, and the docstring
+    fields: Vec<(&'ir str, bool, String, Option<String>)>,
+    docstring: Option<String>,
 }
 
 struct TypescriptEnum<'ir> {
@@ -35,11 +56,26 @@ struct TypescriptEnum<'ir> {
 
 pub struct TypescriptClass<'ir> {
     pub name: Cow<'ir, str>,
+    /// The interfaces this interface extends.
+    pub bases: Vec<&'ir str>,
     pub fields: Vec<(Cow<'ir, str>, bool, String, Option<String>)>,
+    /// Fields inherited as is from `bases`, not declared in the interface body.
+    pub inherited_fields: HashSet<&'ir str>,
     pub dynamic: bool,
     pub docstring: Option<String>,
 }
 
+impl<'ir> TypescriptClass<'ir> {
+    /// The fields declared in the interface body.
+    fn declared_fields(
+        &self,
+    ) -> impl Iterator<Item = &(Cow<'ir, str>, bool, String, Option<String>)> {
+        self.fields
+            .iter()
+            .filter(|(name, ..)| !self.inherited_fields.contains(name.as_ref()))
+    }
+}
+
 impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir GeneratorArgs)> for TypescriptTypes<'ir> {
     type Error = anyhow::Error;
 
@@ -55,6 +91,23 @@ impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir GeneratorArgs)> for TypescriptTyp
                 .walk_classes()
                 .map(|e| Into::<TypescriptClass>::into(&e))
                 .collect::<Vec<_>>(),
+            namespaces: render_namespaces(ir.type_namespaces(), ""),
+        })
+    }
+}
+
+impl<'ir> TryFrom<(&'ir IntermediateRepr, &'ir GeneratorArgs)> for TypescriptStreamTypes<'ir> {
+    type Error = anyhow::Error;
+
+    fn try_from(
+        (ir, _): (&'ir IntermediateRepr, &'ir GeneratorArgs),
+    ) -> Result<TypescriptStreamTypes<'ir>> {
+        Ok(TypescriptStreamTypes {
+            enums: ir.walk_enums().map(|e| e.name()).collect(),
+            partial_classes: ir
+                .walk_classes()
+                .map(|c| Into::<PartialTypescriptClass>::into(&c))
+                .collect::<Vec<_>>(),
         })
     }
 }
@@ -107,6 +160,14 @@ impl<'ir> From<&ClassWalker<'ir>> for TypescriptClass<'ir> {
     fn from(c: &ClassWalker<'ir>) -> TypescriptClass<'ir> {
         TypescriptClass {
             name: Cow::Borrowed(c.name()),
+            bases: extended_interfaces(c),
+            inherited_fields: c
+                .elem()
+                .static_fields
+                .iter()
+                .filter(|f| inherits_field(c, f))
+                .map(|f| f.elem.name.as_str())
+                .collect(),
             dynamic: c.item.attributes.get("dynamic_type").is_some(),
             fields: c
                 .item
@@ -132,6 +193,76 @@ impl<'ir> From<&ClassWalker<'ir>> for TypescriptClass<'ir> {
     }
 }
 
+impl<'ir> From<&ClassWalker<'ir>> for PartialTypescriptClass<'ir> {
+    fn from(c: &ClassWalker<'ir>) -> PartialTypescriptClass<'ir> {
+        PartialTypescriptClass {
+            name: c.name(),
+            bases: extended_interfaces(c),
+            dynamic: c.item.attributes.get("dynamic_type").is_some(),
+            fields: c
+                .item
+                .elem
+                .static_fields
+                .iter()
+                .filter(|f| !inherits_field(c, f))
+                .map(|f| {
+                    let behavior = f.attributes.streaming_behavior();
+                    (
+                        f.elem.name.as_str(),
+                        !(behavior.not_null || behavior.state),
+                        partial_field_type_ref(f, c.db),
+                        f.elem.docstring.as_ref().map(|d| render_docstring(d, true)),
+                    )
+                })
+                .collect(),
+            docstring: c
+                .item
+                .elem
+                .docstring
+                .as_ref()
+                .map(|d| render_docstring(d, false)),
+        }
+    }
+}
+
+/// The parents of a class, if the class can be expressed as an interface
+/// extending them. Interfaces can't change the type of an inherited property,
+/// so classes overriding inherited fields are emitted as flat interfaces.
+fn extended_interfaces<'ir>(c: &ClassWalker<'ir>) -> Vec<&'ir str> {
+    if c.overrides_inherited_fields() {
+        return vec![];
+    }
+    c.parents().map(|p| p.name()).collect()
+}
+
+fn inherits_field(c: &ClassWalker<'_>, field: &Node<Field>) -> bool {
+    !c.overrides_inherited_fields() && c.inherits_field(field)
+}
+
+/// The type of a field in a partial interface, taking its `@stream.*`
+/// attributes into account.
+fn partial_field_type_ref(field: &Node<Field>, ir: &IntermediateRepr) -> String {
+    let field_type = &field.elem.r#type.elem;
+    let behavior = field.attributes.streaming_behavior();
+    let mut type_ref = field_type.to_partial_type_ref(ir);
+
+    // `@stream.not_null` fields are always present in a partial instance.
+    if behavior.not_null && !field_type.is_optional() {
+        if let Some(inner) = type_ref
+            .strip_prefix('(')
+            .and_then(|t| t.strip_suffix(" | null)"))
+        {
+            type_ref = inner.to_string();
+        }
+    }
+
+    if behavior.state {
+        format!("StreamState<{type_ref}>")
+    } else {
+        type_ref
+    }
+}
+
 pub fn type_name_for_checks(checks: &TypeCheckAttributes) -> String {
     checks
         .0
@@ -153,3 +284,33 @@ fn render_docstring(d: &Docstring, indented: bool) -> String {
         format!("/**\n * {lines}\n */")
     }
 }
+
+/// A namespace per BAML namespace, with aliases of the types in it, so that
+/// `shared.Resume` is `Shared_Resume`, like in BAML. Enums are values too.
+fn render_namespaces(namespaces: &[TypeNamespace], indent: &str) -> String {
+    let mut out = String::new();
+    for namespace in namespaces {
+        out.push_str(&format!(
+            "\n{indent}export namespace {} {{\n",
+            namespace.name
+        ));
+        for ty in &namespace.types {
+            if ty.is_enum {
+                out.push_str(&format!(
+                    "{indent}  export const {} = {}\n",
+                    ty.name, ty.canonical
+                ));
+            }
+            out.push_str(&format!(
+                "{indent}  export type {} = {}\n",
+                ty.name, ty.canonical
+            ));
+        }
+        out.push_str(&render_namespaces(
+            &namespace.namespaces,
+            &format!("{indent}  "),
+        ));
+        out.push_str(&format!("{indent}}}\n"));
+    }
+    out
+}
@@ -110,6 +110,9 @@ pub fn check_version(
                     format!("npm install --save-dev @boundaryml/baml@{}", gen_version)
                 }
                 GeneratorOutputType::RubySorbet => format!("gem install baml -v {}", gen_version),
+                GeneratorOutputType::Go => {
+                    format!("build libbaml_cffi from BAML v{}", gen_version)
+                }
             };
             (
                 match generator_type {
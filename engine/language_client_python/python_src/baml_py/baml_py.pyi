@@ -1,4 +1,4 @@
-from typing import Any, Callable, Dict, Optional, Tuple
+from typing import Any, Callable, Dict, List, Optional, Tuple
 
 class FunctionResult:
     """The result of a BAML function call.
@@ -17,6 +17,9 @@ class FunctionResult:
     def __str__(self) -> str: ...
     # Returns True if the function call was successful, False otherwise
     def is_ok(self) -> bool: ...
+    # The responses sampled when the function asks for several `candidates`,
+    # with the selected one last. Empty otherwise.
+    def candidates(self) -> List[FunctionResult]: ...
     def cast_to(self, enum_module: Any, class_module: Any) -> Any: ...
 
     # This is a debug function that returns the internal representation of the response
@@ -122,6 +125,10 @@ class BamlRuntime:
     def set_log_event_callback(
         self, handler: Optional[Callable[[BamlLogEvent], None]]
     ) -> None: ...
+    def register_secret_resolver(
+        self, scheme: str, resolver: Callable[[str], str]
+    ) -> None: ...
+    def refresh_secrets(self) -> None: ...
 
 class LogEventMetadata:
     event_id: str
@@ -101,9 +101,10 @@ impl BamlRuntime {
         files: HashMap<String, String>,
         env_vars: HashMap<String, String>,
     ) -> PyResult<()> {
-        self.inner = CoreBamlRuntime::from_file_content(&root_path, &files, env_vars)
-            .map_err(BamlError::from_anyhow)?
-            .into();
+        let runtime = CoreBamlRuntime::from_file_content(&root_path, &files, env_vars)
+            .map_err(BamlError::from_anyhow)?;
+        runtime.secrets().inherit_resolvers(self.inner.secrets());
+        self.inner = runtime.into();
         Ok(())
     }
 
@@ -284,6 +285,33 @@ impl BamlRuntime {
         self.inner.drain_stats().into()
     }
 
+    /// Resolves environment variables set to `<scheme>:<location>` with
+    /// `resolver(location)`.
+    #[pyo3()]
+    fn register_secret_resolver(&self, scheme: String, resolver: PyObject) -> PyResult<()> {
+        let resolver = Arc::new(resolver);
+        let name = scheme.clone();
+        self.inner
+            .secrets()
+            .register(
+                &scheme,
+                Arc::new(move |location| {
+                    Python::with_gil(|py| {
+                        resolver
+                            .call1(py, (location,))
+                            .and_then(|secret| secret.extract::<String>(py))
+                    })
+                    .map_err(|e| anyhow::anyhow!("Secret resolver `{}` failed: {}", name, e))
+                }),
+            )
+            .map_err(BamlError::from_anyhow)
+    }
+
+    #[pyo3()]
+    fn refresh_secrets(&self) {
+        self.inner.secrets().refresh()
+    }
+
     #[pyo3(signature = (callback = None))]
     fn set_log_event_callback(&self, callback: Option<PyObject>, py: Python<'_>) -> PyResult<()> {
         let baml_runtime = self.inner.clone();
@@ -19,6 +19,16 @@ impl FunctionResult {
         self.inner.result_with_constraints_content().is_ok()
     }
 
+    /// The responses sampled when the function asks for several
+    /// `candidates`, with the selected one last. Empty otherwise.
+    fn candidates(&self) -> Vec<FunctionResult> {
+        self.inner
+            .candidate_results()
+            .into_iter()
+            .map(FunctionResult::from)
+            .collect()
+    }
+
     /// This is a debug function that returns the internal representation of the response
     /// This is not to be relied upon and is subject to change
     fn unstable_internal_repr(&self) -> String {
@@ -31,6 +31,16 @@ impl FunctionResult {
         }
     }
 
+    /// The responses sampled when the function asks for several
+    /// `candidates`, with the selected one last. Empty otherwise.
+    pub fn candidates(&self) -> Vec<FunctionResult> {
+        self.inner
+            .candidate_results()
+            .into_iter()
+            .map(FunctionResult::new)
+            .collect()
+    }
+
     pub fn parsed_using_types(
         ruby: &Ruby,
         rb_self: &FunctionResult,
@@ -61,6 +71,7 @@ impl FunctionResult {
             "parsed_using_types",
             method!(FunctionResult::parsed_using_types, 1),
         )?;
+        cls.define_method("candidates", method!(FunctionResult::candidates, 0))?;
 
         Ok(())
     }
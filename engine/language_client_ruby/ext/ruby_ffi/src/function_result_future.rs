@@ -0,0 +1,83 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use magnus::{class, method, Module, RModule, Ruby};
+use tokio::task::JoinHandle;
+
+use crate::function_result::FunctionResult;
+use crate::types::log_event::LogEventCallback;
+use crate::Error;
+use crate::Result;
+
+/// A function call running on the worker threads of the BAML runtime, so that
+/// several calls can run at the same time.
+#[magnus::wrap(class = "Baml::Ffi::FunctionResultFuture", free_immediately, size)]
+pub struct FunctionResultFuture {
+    function_name: String,
+    handle: RefCell<Option<JoinHandle<anyhow::Result<baml_runtime::FunctionResult>>>>,
+    t: Arc<tokio::runtime::Runtime>,
+    log_events: Arc<LogEventCallback>,
+}
+
+impl FunctionResultFuture {
+    pub(super) fn new(
+        function_name: String,
+        handle: JoinHandle<anyhow::Result<baml_runtime::FunctionResult>>,
+        t: Arc<tokio::runtime::Runtime>,
+        log_events: Arc<LogEventCallback>,
+    ) -> Self {
+        Self {
+            function_name,
+            handle: RefCell::new(Some(handle)),
+            t,
+            log_events,
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.handle
+            .borrow()
+            .as_ref()
+            .map_or(true, |handle| handle.is_finished())
+    }
+
+    /// Blocks until the call finishes. Can only be called once.
+    fn result(ruby: &Ruby, rb_self: &FunctionResultFuture) -> Result<FunctionResult> {
+        let Some(handle) = rb_self.handle.borrow_mut().take() else {
+            return Err(Error::new(
+                ruby.exception_runtime_error(),
+                format!("The result of {} was already read", rb_self.function_name),
+            ));
+        };
+
+        let result = rb_self.t.block_on(handle);
+        rb_self.log_events.deliver_pending(ruby)?;
+
+        match result {
+            Ok(Ok(res)) => Ok(FunctionResult::new(res)),
+            Ok(Err(e)) => Err(Error::new(
+                ruby.exception_runtime_error(),
+                format!(
+                    "{:?}",
+                    e.context(format!("error while calling {}", rb_self.function_name))
+                ),
+            )),
+            Err(e) => Err(Error::new(
+                ruby.exception_runtime_error(),
+                format!("{} was cancelled: {}", rb_self.function_name, e),
+            )),
+        }
+    }
+
+    /// For usage in magnus::init
+    ///
+    /// TODO: use traits and macros to implement this
+    pub fn define_in_ruby(module: &RModule) -> Result<()> {
+        let cls = module.define_class("FunctionResultFuture", class::object())?;
+
+        cls.define_method("done?", method!(FunctionResultFuture::is_done, 0))?;
+        cls.define_method("result", method!(FunctionResultFuture::result, 0))?;
+
+        Ok(())
+    }
+}
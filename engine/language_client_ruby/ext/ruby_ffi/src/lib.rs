@@ -1,16 +1,22 @@
+use baml_runtime::runtime_interface::ExperimentalTracingInterface;
 use baml_runtime::BamlRuntime;
 use baml_types::BamlValue;
-use magnus::{class, function, method, prelude::*, Error, RHash, Ruby};
+use magnus::{block::Proc, class, function, method, prelude::*, Error, RHash, Ruby};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tracing_subscriber::EnvFilter;
 
 use function_result::FunctionResult;
+use function_result_future::FunctionResultFuture;
 use function_result_stream::FunctionResultStream;
+use types::log_event::LogEventCallback;
+use types::proc_holder::ProcHolder;
 use types::runtime_ctx_manager::RuntimeContextManager;
+use types::trace_stats::TraceStats;
 
 mod function_result;
+mod function_result_future;
 mod function_result_stream;
 mod ruby_to_json;
 mod types;
@@ -22,11 +28,14 @@ type Result<T> = std::result::Result<T, magnus::Error>;
 struct BamlRuntimeFfi {
     inner: Arc<BamlRuntime>,
     t: Arc<tokio::runtime::Runtime>,
+    /// The Ruby procs called by the runtime: the log event callback and the
+    /// secret resolvers.
+    procs: Arc<ProcHolder>,
+    log_events: Arc<LogEventCallback>,
 }
 
 impl Drop for BamlRuntimeFfi {
     fn drop(&mut self) {
-        use baml_runtime::runtime_interface::ExperimentalTracingInterface;
         match self.inner.flush() {
             Ok(_) => log::trace!("Flushed BAML log events"),
             Err(e) => log::error!("Error while flushing BAML log events: {:?}", e),
@@ -64,9 +73,12 @@ impl BamlRuntimeFfi {
             }
         };
 
+        let procs = Arc::new(ProcHolder::new(ruby));
         let rt = BamlRuntimeFfi {
             inner: Arc::new(baml_runtime),
             t: Arc::new(Self::make_tokio_runtime(ruby)?),
+            procs: procs.clone(),
+            log_events: Arc::new(LogEventCallback::new(procs)),
         };
 
         Ok(rt)
@@ -88,9 +100,12 @@ impl BamlRuntimeFfi {
             }
         };
 
+        let procs = Arc::new(ProcHolder::new(ruby));
         let rt = BamlRuntimeFfi {
             inner: Arc::new(baml_runtime),
             t: Arc::new(Self::make_tokio_runtime(ruby)?),
+            procs: procs.clone(),
+            log_events: Arc::new(LogEventCallback::new(procs)),
         };
 
         Ok(rt)
@@ -139,10 +154,52 @@ impl BamlRuntimeFfi {
                 ),
             )),
         };
+        rb_self.log_events.deliver_pending(ruby)?;
 
         retval
     }
 
+    /// Starts the call on the worker threads of the runtime and returns
+    /// without waiting for it, so that several calls can run at the same time.
+    pub fn call_function_async(
+        ruby: &Ruby,
+        rb_self: &BamlRuntimeFfi,
+        function_name: String,
+        args: RHash,
+        ctx: &RuntimeContextManager,
+        type_registry: Option<&types::type_builder::TypeBuilder>,
+        client_registry: Option<&types::client_registry::ClientRegistry>,
+    ) -> Result<FunctionResultFuture> {
+        let args = match ruby_to_json::RubyToJson::convert_hash_to_json(args) {
+            Ok(args) => args.into_iter().collect(),
+            Err(e) => {
+                return Err(Error::new(
+                    ruby.exception_syntax_error(),
+                    format!("error while parsing call_function_async args:\n{}", e),
+                ));
+            }
+        };
+
+        let runtime = rb_self.inner.clone();
+        let ctx = ctx.inner.clone();
+        let tb = type_registry.map(|t| t.inner.clone());
+        let cb = client_registry.map(|c| c.inner.borrow().clone());
+        let name = function_name.clone();
+        let handle = rb_self.inner.async_runtime.spawn(async move {
+            let (result, _) = runtime
+                .call_function(name, &args, &ctx, tb.as_ref(), cb.as_ref())
+                .await;
+            result
+        });
+
+        Ok(FunctionResultFuture::new(
+            function_name,
+            handle,
+            rb_self.t.clone(),
+            rb_self.log_events.clone(),
+        ))
+    }
+
     fn stream_function(
         ruby: &Ruby,
         rb_self: &BamlRuntimeFfi,
@@ -183,6 +240,85 @@ impl BamlRuntimeFfi {
 
         retval
     }
+
+    pub fn flush(ruby: &Ruby, rb_self: &BamlRuntimeFfi) -> Result<()> {
+        rb_self.inner.flush().map_err(|e| {
+            Error::new(
+                ruby.exception_runtime_error(),
+                format!("{:?}", e.context("error while flushing BAML log events")),
+            )
+        })?;
+        rb_self.log_events.deliver_pending(ruby)
+    }
+
+    pub fn drain_stats(&self) -> TraceStats {
+        TraceStats {
+            inner: self.inner.drain_stats(),
+        }
+    }
+
+    /// Resolves environment variables set to `<scheme>:<location>` by calling
+    /// `resolver` with the location. libruby panics if called from a thread
+    /// it doesn't know about, so the secrets it resolves can't be used by
+    /// `call_function_async`.
+    pub fn register_secret_resolver(
+        ruby: &Ruby,
+        rb_self: &BamlRuntimeFfi,
+        scheme: String,
+        resolver: Proc,
+    ) -> Result<()> {
+        let key = format!("secret_resolver:{}", scheme);
+        rb_self.procs.set(ruby, &key, Some(resolver))?;
+        let procs = rb_self.procs.clone();
+        let name = scheme.clone();
+
+        rb_self
+            .inner
+            .secrets()
+            .register(
+                &scheme,
+                Arc::new(move |location| {
+                    let Ok(ruby) = Ruby::get() else {
+                        anyhow::bail!(
+                            "Secret resolver `{}` can only be called from the Ruby thread, not by async calls",
+                            name
+                        );
+                    };
+                    let Some(resolver) = procs.get(&ruby, &key) else {
+                        anyhow::bail!("Secret resolver `{}` is not registered", name);
+                    };
+                    resolver
+                        .call::<_, String>((location,))
+                        .map_err(|e| anyhow::anyhow!("Secret resolver `{}` failed: {}", name, e))
+                }),
+            )
+            .map_err(|e| Error::new(ruby.exception_runtime_error(), format!("{:?}", e)))
+    }
+
+    pub fn refresh_secrets(&self) {
+        self.inner.secrets().refresh()
+    }
+
+    /// Calls `callback` with every log event, or stops calling the previous
+    /// callback if `callback` is nil.
+    pub fn set_log_event_callback(
+        ruby: &Ruby,
+        rb_self: &BamlRuntimeFfi,
+        callback: Option<Proc>,
+    ) -> Result<()> {
+        let installed = callback.is_some();
+        rb_self.log_events.set(ruby, callback)?;
+
+        let log_events = rb_self.log_events.clone();
+        rb_self
+            .inner
+            .set_log_event_callback(installed.then(|| {
+                Box::new(move |event: baml_runtime::on_log_event::LogEvent| {
+                    log_events.on_event(event)
+                }) as baml_runtime::on_log_event::LogEventCallbackSync
+            }))
+            .map_err(|e| Error::new(ruby.exception_runtime_error(), format!("{:?}", e)))
+    }
 }
 
 fn invoke_runtime_cli(ruby: &Ruby, argv0: String, argv: Vec<String>) -> Result<()> {
@@ -252,15 +388,36 @@ fn init(ruby: &Ruby) -> Result<()> {
         method!(BamlRuntimeFfi::create_context_manager, 0),
     )?;
     runtime_class.define_method("call_function", method!(BamlRuntimeFfi::call_function, 5))?;
+    runtime_class.define_method(
+        "call_function_async",
+        method!(BamlRuntimeFfi::call_function_async, 5),
+    )?;
     runtime_class.define_method(
         "stream_function",
         method!(BamlRuntimeFfi::stream_function, 5),
     )?;
+    runtime_class.define_method("flush", method!(BamlRuntimeFfi::flush, 0))?;
+    runtime_class.define_method("drain_stats", method!(BamlRuntimeFfi::drain_stats, 0))?;
+    runtime_class.define_method(
+        "set_log_event_callback",
+        method!(BamlRuntimeFfi::set_log_event_callback, 1),
+    )?;
+    runtime_class.define_method(
+        "register_secret_resolver",
+        method!(BamlRuntimeFfi::register_secret_resolver, 2),
+    )?;
+    runtime_class.define_method(
+        "refresh_secrets",
+        method!(BamlRuntimeFfi::refresh_secrets, 0),
+    )?;
 
     FunctionResult::define_in_ruby(&module)?;
+    FunctionResultFuture::define_in_ruby(&module)?;
     FunctionResultStream::define_in_ruby(&module)?;
 
     RuntimeContextManager::define_in_ruby(&module)?;
+    types::span::BamlSpan::define_in_ruby(&module)?;
+    TraceStats::define_in_ruby(&module)?;
 
     types::type_builder::TypeBuilder::define_in_ruby(&module)?;
     types::type_builder::EnumBuilder::define_in_ruby(&module)?;
@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use baml_runtime::on_log_event::LogEvent;
+use magnus::{block::Proc, RHash, Ruby};
+
+use super::proc_holder::ProcHolder;
+use crate::Result;
+
+/// The key of the callback in the runtime's [`ProcHolder`].
+const CALLBACK_KEY: &str = "log_event_callback";
+
+/// How many events logged by other threads are kept until they are
+/// delivered. Older events are dropped beyond that.
+const MAX_PENDING_EVENTS: usize = 1000;
+
+/// Delivers log events to the Ruby callback set with
+/// `BamlRuntime#set_log_event_callback`.
+///
+/// libruby panics if called from a thread it doesn't know about, so events
+/// logged by calls running on tokio worker threads (see
+/// `BamlRuntime#call_function_async`) are queued, and delivered on the Ruby
+/// thread by [`LogEventCallback::deliver_pending`].
+pub(crate) struct LogEventCallback {
+    procs: Arc<ProcHolder>,
+    pending: Mutex<Pending>,
+}
+
+#[derive(Default)]
+struct Pending {
+    events: VecDeque<LogEvent>,
+    dropped: usize,
+}
+
+impl LogEventCallback {
+    pub fn new(procs: Arc<ProcHolder>) -> Self {
+        LogEventCallback {
+            procs,
+            pending: Default::default(),
+        }
+    }
+
+    pub fn set(&self, ruby: &Ruby, callback: Option<Proc>) -> Result<()> {
+        self.procs.set(ruby, CALLBACK_KEY, callback)?;
+        *self.pending.lock().unwrap() = Default::default();
+        Ok(())
+    }
+
+    /// Called by the tracer, on whichever thread submitted the event.
+    pub fn on_event(&self, event: LogEvent) -> anyhow::Result<()> {
+        match Ruby::get() {
+            Ok(ruby) => self.call(&ruby, event),
+            Err(_) => {
+                let mut pending = self.pending.lock().unwrap();
+                if pending.events.len() >= MAX_PENDING_EVENTS {
+                    pending.events.pop_front();
+                    pending.dropped += 1;
+                }
+                pending.events.push_back(event);
+                Ok(())
+            }
+        }
+    }
+
+    /// Calls the callback with the events queued by other threads.
+    pub fn deliver_pending(&self, ruby: &Ruby) -> Result<()> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap());
+        if pending.dropped > 0 {
+            log::warn!(
+                "Dropped {} log events that were not delivered in time; call BamlRuntime#flush more often to receive them all",
+                pending.dropped
+            );
+        }
+        for event in pending.events {
+            self.call(ruby, event).map_err(|e| {
+                magnus::Error::new(ruby.exception_runtime_error(), format!("{:?}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn call(&self, ruby: &Ruby, event: LogEvent) -> anyhow::Result<()> {
+        let Some(callback) = self.procs.get(ruby, CALLBACK_KEY) else {
+            return Ok(());
+        };
+        let event = to_hash(ruby, event)
+            .map_err(|e| anyhow::anyhow!("Failed to convert log event: {}", e))?;
+        callback
+            .call::<_, magnus::Value>((event,))
+            .map(|_| ())
+            .map_err(|e| anyhow::anyhow!("Error calling log_event_callback: {}", e))
+    }
+}
+
+/// The event as a Hash with symbol keys, wrapped in a `Baml::LogEvent` by the
+/// gem.
+fn to_hash(ruby: &Ruby, event: LogEvent) -> Result<RHash> {
+    let metadata = ruby.hash_new();
+    metadata.aset(ruby.to_symbol("event_id"), event.metadata.event_id)?;
+    metadata.aset(ruby.to_symbol("parent_id"), event.metadata.parent_id)?;
+    metadata.aset(
+        ruby.to_symbol("root_event_id"),
+        event.metadata.root_event_id,
+    )?;
+
+    let hash = ruby.hash_new();
+    hash.aset(ruby.to_symbol("metadata"), metadata)?;
+    hash.aset(ruby.to_symbol("prompt"), event.prompt)?;
+    hash.aset(ruby.to_symbol("raw_output"), event.raw_output)?;
+    hash.aset(ruby.to_symbol("parsed_output"), event.parsed_output)?;
+    hash.aset(ruby.to_symbol("start_time"), event.start_time)?;
+    Ok(hash)
+}
@@ -1,5 +1,9 @@
 pub(crate) mod client_registry;
 mod lang_wrapper;
+pub(crate) mod log_event;
 pub(crate) mod media;
+pub(crate) mod proc_holder;
 pub(crate) mod runtime_ctx_manager;
+pub(crate) mod span;
+pub(crate) mod trace_stats;
 pub(crate) mod type_builder;
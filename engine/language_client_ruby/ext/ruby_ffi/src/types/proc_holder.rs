@@ -0,0 +1,39 @@
+use magnus::{block::Proc, value::Opaque, RHash, Ruby};
+
+use crate::Result;
+
+/// Procs referenced from Rust only, e.g. callbacks, which must not be garbage
+/// collected while they may still be called.
+///
+/// Marking each proc would keep every proc ever set alive, so they are kept
+/// in a single Hash, marked once, and replacing a proc lets the previous one
+/// be collected.
+pub(crate) struct ProcHolder {
+    procs: Opaque<RHash>,
+}
+
+impl ProcHolder {
+    pub fn new(ruby: &Ruby) -> Self {
+        let procs = ruby.hash_new();
+        magnus::gc::register_mark_object(procs);
+        ProcHolder {
+            procs: procs.into(),
+        }
+    }
+
+    /// Replaces the proc stored under `key`, or removes it if `proc` is nil.
+    pub fn set(&self, ruby: &Ruby, key: &str, proc: Option<Proc>) -> Result<()> {
+        let procs = ruby.get_inner(self.procs);
+        match proc {
+            Some(proc) => procs.aset(key, proc),
+            None => procs.delete::<_, Option<Proc>>(key).map(|_| ()),
+        }
+    }
+
+    pub fn get(&self, ruby: &Ruby, key: &str) -> Option<Proc> {
+        ruby.get_inner(self.procs)
+            .lookup::<_, Option<Proc>>(key)
+            .ok()
+            .flatten()
+    }
+}
@@ -1,5 +1,6 @@
-use magnus::{class, prelude::*, RModule};
+use magnus::{class, method, prelude::*, Error, RHash, RModule, Ruby};
 
+use crate::ruby_to_json;
 use crate::Result;
 
 #[magnus::wrap(class = "Baml::Ffi::RuntimeContextManager", free_immediately, size)]
@@ -7,11 +8,44 @@ pub struct RuntimeContextManager {
     pub inner: baml_runtime::RuntimeContextManager,
 }
 impl RuntimeContextManager {
+    /// Tags are attached to the current span, and inherited by the spans
+    /// started inside it.
+    pub fn upsert_tags(ruby: &Ruby, rb_self: &Self, tags: RHash) -> Result<bool> {
+        let tags = match ruby_to_json::RubyToJson::convert_hash_to_json(tags) {
+            Ok(tags) => tags,
+            Err(e) => {
+                return Err(Error::new(
+                    ruby.exception_type_error(),
+                    format!("error while parsing tags:\n{}", e),
+                ));
+            }
+        };
+        rb_self.inner.upsert_tags(tags.into_iter().collect());
+        Ok(true)
+    }
+
+    pub fn deep_clone(&self) -> Self {
+        RuntimeContextManager {
+            inner: self.inner.deep_clone(),
+        }
+    }
+
+    pub fn context_depth(&self) -> usize {
+        self.inner.context_depth()
+    }
+
     pub fn define_in_ruby(module: &RModule) -> Result<()> {
-        module.define_class("RuntimeContextManager", class::object())?;
+        let cls = module.define_class("RuntimeContextManager", class::object())?;
 
-        //cls.define_method("upsert_tags", method!(RuntimeContextManager::upsert_tags, 1))?;
-        //cls.define_method("deep_clone", method!(RuntimeContextManager::deep_clone, 0))?;
+        cls.define_method(
+            "upsert_tags",
+            method!(RuntimeContextManager::upsert_tags, 1),
+        )?;
+        cls.define_method("deep_clone", method!(RuntimeContextManager::deep_clone, 0))?;
+        cls.define_method(
+            "context_depth",
+            method!(RuntimeContextManager::context_depth, 0),
+        )?;
 
         Ok(())
     }
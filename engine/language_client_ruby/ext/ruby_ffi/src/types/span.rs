@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use baml_runtime::runtime_interface::ExperimentalTracingInterface;
+use baml_types::{BamlMap, BamlValue};
+use magnus::{
+    class, function, method, prelude::*, r_hash::ForEach, Error, RHash, RModule, Ruby, Value,
+};
+
+use super::runtime_ctx_manager::RuntimeContextManager;
+use crate::ruby_to_json;
+use crate::BamlRuntimeFfi;
+use crate::Result;
+
+#[magnus::wrap(class = "Baml::Ffi::BamlSpan", free_immediately, size)]
+pub(crate) struct BamlSpan {
+    inner: RefCell<Option<Option<baml_runtime::tracing::TracingSpan>>>,
+    rt: Arc<baml_runtime::BamlRuntime>,
+}
+
+impl BamlSpan {
+    /// Starts a span. Arguments that can't be converted, like arbitrary
+    /// objects passed to a traced method, are recorded with their `inspect`.
+    fn new(
+        runtime: &BamlRuntimeFfi,
+        function_name: String,
+        args: RHash,
+        ctx: &RuntimeContextManager,
+    ) -> Result<Self> {
+        let mut params = BamlMap::new();
+        args.foreach(|k: Value, v: Value| {
+            let value = ruby_to_json::RubyToJson::convert(v)
+                .unwrap_or_else(|_| BamlValue::String(v.inspect()));
+            params.insert(k.to_string(), value);
+            Ok(ForEach::Continue)
+        })?;
+
+        let span = runtime
+            .inner
+            .start_span(&function_name, &params, &ctx.inner);
+        log::trace!("Starting span: {:#?} for {:?}\n", span, function_name);
+
+        Ok(Self {
+            inner: RefCell::new(Some(span)),
+            rt: runtime.inner.clone(),
+        })
+    }
+
+    /// Finishes the span with the value returned by the traced block, or the
+    /// exception it raised. Values that can't be converted are recorded with
+    /// their `inspect`.
+    fn finish(
+        ruby: &Ruby,
+        rb_self: &Self,
+        result: Value,
+        ctx: &RuntimeContextManager,
+    ) -> Result<Option<String>> {
+        log::trace!("Finishing span: {:?}", rb_self.inner.borrow());
+        let result = ruby_to_json::RubyToJson::convert(result)
+            .unwrap_or_else(|_| BamlValue::String(result.inspect()));
+
+        let Some(span) = rb_self.inner.borrow_mut().take() else {
+            return Err(Error::new(
+                ruby.exception_runtime_error(),
+                "Span already finished",
+            ));
+        };
+
+        rb_self
+            .rt
+            .finish_span(span, Some(result), &ctx.inner)
+            .map(|id| id.map(|id| id.to_string()))
+            .map_err(|e| {
+                Error::new(
+                    ruby.exception_runtime_error(),
+                    format!("{:?}", e.context("error while finishing span")),
+                )
+            })
+    }
+
+    pub fn define_in_ruby(module: &RModule) -> Result<()> {
+        let cls = module.define_class("BamlSpan", class::object())?;
+
+        cls.define_singleton_method("new", function!(BamlSpan::new, 4))?;
+        cls.define_method("finish", method!(BamlSpan::finish, 2))?;
+
+        Ok(())
+    }
+}
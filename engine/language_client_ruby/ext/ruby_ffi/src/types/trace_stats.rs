@@ -0,0 +1,61 @@
+use magnus::{class, method, Module, RModule};
+
+use crate::Result;
+
+#[magnus::wrap(class = "Baml::Ffi::TraceStats", free_immediately, size)]
+pub(crate) struct TraceStats {
+    pub(crate) inner: baml_runtime::InnerTraceStats,
+}
+
+impl TraceStats {
+    pub fn failed(&self) -> u32 {
+        self.inner.failed
+    }
+
+    pub fn started(&self) -> u32 {
+        self.inner.started
+    }
+
+    pub fn finalized(&self) -> u32 {
+        self.inner.finalized
+    }
+
+    pub fn submitted(&self) -> u32 {
+        self.inner.submitted
+    }
+
+    pub fn sent(&self) -> u32 {
+        self.inner.sent
+    }
+
+    pub fn done(&self) -> u32 {
+        self.inner.done
+    }
+
+    pub fn to_s(&self) -> String {
+        format!(
+            "TraceStats(failed={}, started={}, finalized={}, submitted={}, sent={}, done={})",
+            self.failed(),
+            self.started(),
+            self.finalized(),
+            self.submitted(),
+            self.sent(),
+            self.done()
+        )
+    }
+
+    pub fn define_in_ruby(module: &RModule) -> Result<()> {
+        let cls = module.define_class("TraceStats", class::object())?;
+
+        cls.define_method("failed", method!(TraceStats::failed, 0))?;
+        cls.define_method("started", method!(TraceStats::started, 0))?;
+        cls.define_method("finalized", method!(TraceStats::finalized, 0))?;
+        cls.define_method("submitted", method!(TraceStats::submitted, 0))?;
+        cls.define_method("sent", method!(TraceStats::sent, 0))?;
+        cls.define_method("done", method!(TraceStats::done, 0))?;
+        cls.define_method("to_s", method!(TraceStats::to_s, 0))?;
+        cls.define_method("inspect", method!(TraceStats::to_s, 0))?;
+
+        Ok(())
+    }
+}
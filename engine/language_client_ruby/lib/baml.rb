@@ -5,9 +5,12 @@ rescue LoadError
   require_relative "baml/ruby_ffi"
 end
 # require_relative "baml/ruby_ffi"
+require_relative "ctx_manager"
+require_relative "future"
 require_relative "stream"
 require_relative "struct"
 require_relative "checked"
+require_relative "stream_state"
 
 module Baml
   ClientRegistry = Baml::Ffi::ClientRegistry
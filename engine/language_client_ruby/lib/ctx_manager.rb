@@ -0,0 +1,78 @@
+require "sorbet-runtime"
+
+module Baml
+  LogEventMetadata = Struct.new(:event_id, :parent_id, :root_event_id, keyword_init: true)
+  LogEvent = Struct.new(:metadata, :prompt, :raw_output, :parsed_output, :start_time, keyword_init: true)
+
+  # Tracks the spans and tags of BAML calls. Every thread and fiber gets its
+  # own context, so that concurrent calls are not nested under each other's
+  # spans.
+  class CtxManager
+    extend T::Sig
+
+    sig { params(runtime: Baml::Ffi::BamlRuntime).void }
+    def initialize(runtime)
+      @runtime = runtime
+      @key = :"baml_ctx_#{object_id}"
+      at_exit { @runtime.flush }
+    end
+
+    # The context of the current thread or fiber.
+    sig { returns(Baml::Ffi::RuntimeContextManager) }
+    def get
+      # Thread#[] is fiber-local.
+      Thread.current[@key] ||= @runtime.create_context_manager
+    end
+
+    # Runs the block in a span named `name`, which becomes the parent of the
+    # BAML calls and spans in the block. The span records `args` and the value
+    # returned, or the exception raised, by the block.
+    sig { params(name: T.any(String, Symbol), args: T::Hash[T.untyped, T.untyped], block: T.proc.returns(T.untyped)).returns(T.untyped) }
+    def trace(name, args = {}, &block)
+      ctx = get
+      span = Baml::Ffi::BamlSpan.new(@runtime, name.to_s, args, ctx)
+      begin
+        result = block.call
+      rescue Exception => e
+        span.finish(e, ctx)
+        raise
+      end
+      span.finish(result, ctx)
+      result
+    end
+
+    # Tags the current span. Spans started inside it inherit the tags.
+    sig { params(tags: T.untyped).void }
+    def upsert_tags(**tags)
+      get.upsert_tags(tags)
+    end
+
+    sig { void }
+    def flush
+      @runtime.flush
+    end
+
+    sig { returns(Baml::Ffi::TraceStats) }
+    def drain_stats
+      @runtime.drain_stats
+    end
+
+    # Calls the block with a Baml::LogEvent for every BAML call, or stops if
+    # no block is given. Events of async calls are delivered when the call is
+    # awaited or on flush; up to 1000 of them are kept until then.
+    sig { params(handler: T.nilable(T.proc.params(event: LogEvent).void)).void }
+    def on_log_event(&handler)
+      if handler.nil?
+        @runtime.set_log_event_callback(nil)
+        return
+      end
+
+      @runtime.set_log_event_callback(proc do |event|
+        handler.call(LogEvent.new(
+          **event,
+          metadata: LogEventMetadata.new(**event[:metadata])
+        ))
+      end)
+    end
+  end
+end
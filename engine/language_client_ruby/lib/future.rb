@@ -0,0 +1,38 @@
+require "sorbet-runtime"
+
+module Baml
+  # The result of a call started with `Baml.Client.async`. The call runs in the
+  # background until #value is called.
+  class BamlFuture
+    extend T::Sig
+    extend T::Generic
+
+    ResultType = type_member
+
+    def initialize(ffi_future:)
+      @ffi_future = ffi_future
+      @done = false
+      @value = nil
+    end
+
+    # Whether the call has finished. Does not block.
+    sig { returns(T::Boolean) }
+    def done?
+      @done || @ffi_future.done?
+    end
+
+    # Waits for the call to finish and returns its parsed result, or raises
+    # its error.
+    #
+    # @return [ResultType] the parsed response
+    sig { returns(ResultType) }
+    def value
+      unless @done
+        @value = @ffi_future.result.parsed_using_types(Baml::Types)
+        @done = true
+      end
+
+      @value
+    end
+  end
+end
@@ -27,6 +27,13 @@ export declare class BamlRuntime {
   callFunctionSync(functionName: string, args: { [string]: any }, ctx: RuntimeContextManager, tb?: TypeBuilder | undefined | null, cb?: ClientRegistry | undefined | null): FunctionResult
   streamFunction(functionName: string, args: { [string]: any }, cb: ((err: any, param: FunctionResult) => void) | undefined, ctx: RuntimeContextManager, tb?: TypeBuilder | undefined | null, clientRegistry?: ClientRegistry | undefined | null): FunctionResultStream
   streamFunctionSync(functionName: string, args: { [string]: any }, cb: ((err: any, param: FunctionResult) => void) | undefined, ctx: RuntimeContextManager, tb?: TypeBuilder | undefined | null, clientRegistry?: ClientRegistry | undefined | null): FunctionResultStream
+  /**
+   * Resolves environment variables set to `<scheme>:<location>` with
+   * `resolver(location)`. The resolver runs on the JavaScript thread, so
+   * only async functions can use it: sync functions block that thread.
+   */
+  registerSecretResolver(scheme: string, resolver: (location: string, done: (resolution: SecretResolution) => void) => void): void
+  refreshSecrets(): void
   setLogEventCallback(func?: undefined | ((err: any, param: BamlLogEvent) => void)): void
   flush(): void
   drainStats(): TraceStats
@@ -73,6 +80,11 @@ export declare class FieldType {
 
 export declare class FunctionResult {
   isOk(): boolean
+  /**
+   * The responses sampled when the function asks for several
+   * `candidates`, with the selected one last. Empty otherwise.
+   */
+  candidates(): Array<FunctionResult>
   parsed(): any
 }
 
@@ -123,6 +135,15 @@ export interface BamlLogEvent {
   startTime: string
 }
 
+/**
+ * What a secret resolver returns: the secret, or why it couldn't be
+ * resolved. Resolvers must not throw.
+ */
+export interface SecretResolution {
+  secret?: string
+  error?: string
+}
+
 export declare export declare function invoke_runtime_cli(params: Array<string>): void
 
 export interface LogEventMetadata {
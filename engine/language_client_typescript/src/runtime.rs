@@ -10,15 +10,19 @@ use baml_runtime::on_log_event::LogEvent;
 use baml_runtime::runtime_interface::ExperimentalTracingInterface;
 use baml_runtime::BamlRuntime as CoreRuntime;
 use baml_types::BamlValue;
-use napi::bindgen_prelude::ObjectFinalize;
-use napi::threadsafe_function::{ThreadSafeCallContext, ThreadsafeFunctionCallMode};
+use napi::bindgen_prelude::{FromNapiValue, ObjectFinalize};
+use napi::threadsafe_function::{
+    ErrorStrategy, ThreadSafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
 use napi::JsFunction;
 use napi::JsObject;
-use napi::{Env, JsUndefined};
+use napi::{Env, JsUndefined, JsUnknown, NapiRaw};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
 
 crate::lang_wrapper!(BamlRuntime,
     CoreRuntime,
@@ -46,6 +50,15 @@ pub struct BamlLogEvent {
     pub start_time: String,
 }
 
+/// What a secret resolver returns: the secret, or why it couldn't be
+/// resolved. Resolvers must not throw.
+#[napi(object)]
+#[derive(Debug, Clone)]
+pub struct SecretResolution {
+    pub secret: Option<String>,
+    pub error: Option<String>,
+}
+
 #[napi]
 impl BamlRuntime {
     #[napi(ts_return_type = "BamlRuntime")]
@@ -77,9 +90,10 @@ impl BamlRuntime {
         files: HashMap<String, String>,
         env_vars: HashMap<String, String>,
     ) -> napi::Result<()> {
-        self.inner = CoreRuntime::from_file_content(&root_path, &files, env_vars)
-            .map_err(from_anyhow_error)?
-            .into();
+        let runtime = CoreRuntime::from_file_content(&root_path, &files, env_vars)
+            .map_err(from_anyhow_error)?;
+        runtime.secrets().inherit_resolvers(self.inner.secrets());
+        self.inner = runtime.into();
         Ok(())
     }
 
@@ -251,6 +265,88 @@ impl BamlRuntime {
         Ok(FunctionResultStream::new(stream, cb, tb, client_registry))
     }
 
+    /// Resolves environment variables set to `<scheme>:<location>` with
+    /// `resolver(location, done)`, which calls `done` once it has the secret,
+    /// so it can be async. The resolver runs on the JavaScript thread, so
+    /// only async functions can use it: sync functions block that thread.
+    #[napi]
+    pub fn register_secret_resolver(
+        &self,
+        env: Env,
+        scheme: String,
+        #[napi(
+            ts_arg_type = "(location: string, done: (resolution: SecretResolution) => void) => void"
+        )]
+        resolver: JsFunction,
+    ) -> napi::Result<()> {
+        let mut tsfn: ThreadsafeFunction<(String, Sender<SecretResolution>), ErrorStrategy::Fatal> =
+            resolver.create_threadsafe_function(
+                0,
+                |ctx: ThreadSafeCallContext<(String, Sender<SecretResolution>)>| {
+                    let (location, tx) = ctx.value;
+                    let done = ctx.env.create_function_from_closure("done", move |cb| {
+                        let resolution = cb.get::<JsUnknown>(0)?;
+                        let resolution = unsafe {
+                            SecretResolution::from_napi_value(cb.env.raw(), resolution.raw())?
+                        };
+                        let _ = tx.send(resolution);
+                        cb.env.get_undefined()
+                    })?;
+                    Ok(vec![
+                        ctx.env.create_string(&location)?.into_unknown(),
+                        done.into_unknown(),
+                    ])
+                },
+            )?;
+        // Registering a resolver must not keep the process alive.
+        tsfn.unref(&env)?;
+        let js_thread = std::thread::current().id();
+        let name = scheme.clone();
+
+        self.inner
+            .secrets()
+            .register(
+                &scheme,
+                Arc::new(move |location| {
+                    if std::thread::current().id() == js_thread {
+                        anyhow::bail!(
+                            "Secret resolver `{}` can't be used by sync functions, use the async client",
+                            name
+                        );
+                    }
+                    let (tx, rx) = std::sync::mpsc::channel();
+                    let status = tsfn.call(
+                        (location.to_string(), tx),
+                        ThreadsafeFunctionCallMode::Blocking,
+                    );
+                    if status != napi::Status::Ok {
+                        anyhow::bail!("Failed to call secret resolver `{}`: {:?}", name, status);
+                    }
+                    match rx.recv() {
+                        Ok(SecretResolution {
+                            secret: Some(secret),
+                            ..
+                        }) => Ok(secret),
+                        Ok(SecretResolution { error, .. }) => Err(anyhow::anyhow!(
+                            "Secret resolver `{}` failed: {}",
+                            name,
+                            error.unwrap_or_else(|| "no secret returned".to_string())
+                        )),
+                        Err(_) => Err(anyhow::anyhow!(
+                            "Secret resolver `{}` never called `done`",
+                            name
+                        )),
+                    }
+                }),
+            )
+            .map_err(from_anyhow_error)
+    }
+
+    #[napi]
+    pub fn refresh_secrets(&self) {
+        self.inner.secrets().refresh()
+    }
+
     #[napi]
     pub fn set_log_event_callback(
         &mut self,
@@ -15,6 +15,17 @@ impl FunctionResult {
         self.inner.result_with_constraints_content().is_ok()
     }
 
+    /// The responses sampled when the function asks for several
+    /// `candidates`, with the selected one last. Empty otherwise.
+    #[napi]
+    pub fn candidates(&self) -> Vec<FunctionResult> {
+        self.inner
+            .candidate_results()
+            .into_iter()
+            .map(FunctionResult::from)
+            .collect()
+    }
+
     #[napi]
     pub fn parsed(&self) -> napi::Result<serde_json::Value> {
         let parsed = self
@@ -8,6 +8,7 @@ export {
   invoke_runtime_cli,
   ClientRegistry,
   BamlLogEvent,
+  SecretResolution,
 } from "./native";
 export { BamlStream } from "./stream";
 export { BamlCtxManager } from "./async_context_vars";
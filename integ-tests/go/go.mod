@@ -0,0 +1,3 @@
+module github.com/boundaryml/baml/integ-tests/go
+
+go 1.21
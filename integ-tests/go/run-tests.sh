@@ -0,0 +1,15 @@
+#!/bin/bash
+# Builds libbaml_cffi and baml-cli, generates baml_client and runs the Go
+# smoke tests against them.
+set -e
+
+cd "$(dirname "$0")"
+ENGINE="$(pwd)/../../engine"
+
+(cd "$ENGINE" && cargo build -p baml-cffi -p baml-cli)
+"$ENGINE/target/debug/baml-cli" generate --from baml_src
+
+export CGO_LDFLAGS="-L$ENGINE/target/debug"
+export LD_LIBRARY_PATH="$ENGINE/target/debug${LD_LIBRARY_PATH:+:$LD_LIBRARY_PATH}"
+export DYLD_LIBRARY_PATH="$ENGINE/target/debug${DYLD_LIBRARY_PATH:+:$DYLD_LIBRARY_PATH}"
+go test -count=1 -v ./...
@@ -0,0 +1,154 @@
+// Smoke tests of the generated Go client: they build baml_client with cgo,
+// link it against libbaml_cffi, and call it against a fake OpenAI server.
+// Run them with ./run-tests.sh.
+package smoke_test
+
+import (
+	"context"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"strings"
+	"testing"
+	"time"
+
+	b "github.com/boundaryml/baml/integ-tests/go/baml_client"
+)
+
+const resumeJSON = `{"name": "Grace Hopper", "skills": ["COBOL", "compilers"]}`
+
+// cancelled receives a value whenever the server sees a request to /slow
+// go away before it was answered.
+var cancelled = make(chan struct{}, 1)
+
+func TestMain(m *testing.M) {
+	server := httptest.NewServer(http.HandlerFunc(fakeOpenAI))
+	// Read when baml_client loads the runtime, on the first call.
+	os.Setenv("FAKE_OPENAI_BASE_URL", server.URL)
+	code := m.Run()
+	server.Close()
+	os.Exit(code)
+}
+
+// fakeOpenAI answers chat completions with resumeJSON, streamed in chunks if
+// requested. Requests to /slow are never answered.
+func fakeOpenAI(w http.ResponseWriter, r *http.Request) {
+	if strings.HasPrefix(r.URL.Path, "/slow") {
+		<-r.Context().Done()
+		cancelled <- struct{}{}
+		return
+	}
+
+	var req struct {
+		Stream bool `json:"stream"`
+	}
+	if err := json.NewDecoder(r.Body).Decode(&req); err != nil {
+		http.Error(w, err.Error(), http.StatusBadRequest)
+		return
+	}
+
+	if !req.Stream {
+		w.Header().Set("Content-Type", "application/json")
+		json.NewEncoder(w).Encode(map[string]any{
+			"id":      "chatcmpl-smoke",
+			"object":  "chat.completion",
+			"created": 0,
+			"model":   "gpt-4o",
+			"choices": []map[string]any{{
+				"index":         0,
+				"message":       map[string]any{"role": "assistant", "content": resumeJSON},
+				"finish_reason": "stop",
+			}},
+			"usage": map[string]any{"prompt_tokens": 10, "completion_tokens": 10, "total_tokens": 20},
+		})
+		return
+	}
+
+	w.Header().Set("Content-Type", "text/event-stream")
+	chunk := func(delta map[string]any, finishReason any) {
+		data, _ := json.Marshal(map[string]any{
+			"id":      "chatcmpl-smoke",
+			"object":  "chat.completion.chunk",
+			"created": 0,
+			"model":   "gpt-4o",
+			"choices": []map[string]any{{"index": 0, "delta": delta, "finish_reason": finishReason}},
+		})
+		fmt.Fprintf(w, "data: %s\n\n", data)
+		w.(http.Flusher).Flush()
+	}
+	for i := 0; i < len(resumeJSON); i += 8 {
+		chunk(map[string]any{"content": resumeJSON[i:min(i+8, len(resumeJSON))]}, nil)
+	}
+	chunk(map[string]any{}, "stop")
+	fmt.Fprint(w, "data: [DONE]\n\n")
+}
+
+func TestCall(t *testing.T) {
+	resume, err := b.ExtractResume(context.Background(), "Grace Hopper created COBOL")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if resume.Name != "Grace Hopper" || len(resume.Skills) != 2 {
+		t.Fatalf("unexpected resume: %+v", resume)
+	}
+}
+
+func TestStream(t *testing.T) {
+	partials := 0
+	resume, err := b.StreamExtractResume(context.Background(), "Grace Hopper created COBOL", func(partial *b.PartialResume) {
+		partials++
+	})
+	if err != nil {
+		t.Fatal(err)
+	}
+	if partials == 0 {
+		t.Fatal("expected partial results")
+	}
+	if resume.Name != "Grace Hopper" {
+		t.Fatalf("unexpected resume: %+v", resume)
+	}
+}
+
+func TestInvalidClientIsReported(t *testing.T) {
+	cr := b.NewClientRegistry()
+	err := cr.AddClient("Broken", "not-a-provider", map[string]any{}, "")
+	var bamlErr *b.BamlError
+	if !errors.As(err, &bamlErr) || bamlErr.Kind != "invalid_argument" {
+		t.Fatalf("expected an invalid_argument error, got %v", err)
+	}
+}
+
+func TestCancelAbortsTheRequest(t *testing.T) {
+	cr := b.NewClientRegistry()
+	err := cr.AddClient("Slow", "openai", map[string]any{
+		"model":    "gpt-4o",
+		"api_key":  "test",
+		"base_url": os.Getenv("FAKE_OPENAI_BASE_URL") + "/slow",
+	}, "")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if err := cr.SetPrimary("Slow"); err != nil {
+		t.Fatal(err)
+	}
+
+	ctx, cancel := context.WithTimeout(context.Background(), 200*time.Millisecond)
+	defer cancel()
+	start := time.Now()
+	_, err = b.ExtractResume(ctx, "Grace Hopper created COBOL", b.WithClientRegistry(cr))
+	if !errors.Is(err, context.DeadlineExceeded) {
+		t.Fatalf("expected context.DeadlineExceeded, got %v", err)
+	}
+	if elapsed := time.Since(start); elapsed > 5*time.Second {
+		t.Fatalf("the call returned %s after its context was done", elapsed)
+	}
+
+	select {
+	case <-cancelled:
+	case <-time.After(5 * time.Second):
+		t.Fatal("the request to the LLM was not aborted")
+	}
+}
@@ -44,6 +44,15 @@ export const testStatusAtom = atomFamily(
 )
 export const runningTestsAtom = atom<string[]>([])
 
+/// Tests with a dataset run one case per row, named after the test and the
+/// line of the row in the dataset file.
+const datasetRowName = (testName: string, line: number) => `${testName} [line ${line}]`
+
+const parseDatasetRowName = (name: string): { testName: string; line?: number } => {
+  const match = /^(.*) \[line (\d+)\]$/.exec(name)
+  return match ? { testName: match[1], line: Number(match[2]) } : { testName: name }
+}
+
 // Match the Rust enum
 // engine/baml-schema-wasm/src/runtime_wasm/mod.rs
 enum RustTestStatus {
@@ -133,6 +142,32 @@ export const useRunHooks = () => {
         set(showTestsAtom, true)
         set(testSuiteSummaryAtom, 'unknown')
 
+        const readFile = async (path: string) => {
+          return await vscode.readFile(path)
+        }
+
+        // Expand the tests with a dataset into one case per row.
+        const caseNames: string[] = []
+        for (const name of testNames) {
+          const { testName, line } = parseDatasetRowName(name)
+          if (line !== undefined) {
+            caseNames.push(name)
+            continue
+          }
+          try {
+            const lines = await func.dataset_row_lines(runtime, testName, readFile)
+            if (lines.length === 0) {
+              caseNames.push(testName)
+            } else {
+              lines.forEach((line) => caseNames.push(datasetRowName(testName, line)))
+            }
+          } catch (e) {
+            // Run the test anyway, so that the error shows up in its result.
+            caseNames.push(testName)
+          }
+        }
+        testNames = caseNames
+
         vscode.postMessage({
           command: 'telemetry',
           meta: {
@@ -184,24 +219,21 @@ export const useRunHooks = () => {
                 return Promise.reject(new Error('Code potentially modified while running tests'))
               }
               let now = new Date().getTime()
-              return func
-                .run_test(
-                  runtime,
-                  testName,
-                  (intermediate: WasmFunctionResponse) => {
-                    set(testStatusAtom(testName), {
-                      status: 'running',
-                      response: intermediate,
-                    })
-                  },
-                  async (path: string) => {
-                    return await vscode.readFile(path)
-                  },
-                )
-                .then((res) => {
-                  let elapsed = new Date().getTime() - now
-                  return { res, elapsed }
+              const onPartialResponse = (intermediate: WasmFunctionResponse) => {
+                set(testStatusAtom(testName), {
+                  status: 'running',
+                  response: intermediate,
                 })
+              }
+              const row = parseDatasetRowName(testName)
+              return (
+                row.line === undefined
+                  ? func.run_test(runtime, testName, onPartialResponse, readFile)
+                  : func.run_test_row(runtime, row.testName, row.line, onPartialResponse, readFile)
+              ).then((res) => {
+                let elapsed = new Date().getTime() - now
+                return { res, elapsed }
+              })
             }),
           )
           for (let i = 0; i < promises.length; i++) {
@@ -239,6 +239,10 @@ const PromptPreview: React.FC = () => {
 
   return (
     <div className='flex flex-col gap-4 px-2 w-full h-full'>
+      <div className='text-xs text-muted-foreground'>
+        {promptPreview.token_count_is_exact() ? '' : '~'}
+        {promptPreview.token_count()} tokens ({promptPreview.tokenizer()})
+      </div>
       {promptPreview.as_chat()?.map((chat, idx) => (
         <div key={idx} className='flex flex-col'>
           <div className='flex flex-row gap-2'>{chat.role}</div>